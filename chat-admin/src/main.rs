@@ -0,0 +1,221 @@
+// chat-admin/src/main.rs
+// Alat baris perintah untuk operator chat-server: daftar room dan user, blokir, hapus pesan, slow mode,
+// aturan room, dan pengumuman. Berbicara dengan API admin server (ws://<alamat>/admin) yang hanya aktif jika server
+// dijalankan dengan CHAT_ADMIN_TOKEN; token yang sama dipakai di sini lewat --token atau environment.
+//
+//   CHAT_ADMIN_TOKEN=rahasia cargo run -p chat-admin -- rooms
+//   cargo run -p chat-admin -- --url ws://chat.example.com/admin --token rahasia ban u-bob "Spam"
+//
+// Exit code 1 jika server menolak perintah, 2 untuk argumen yang salah.
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::time::timeout;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{header, HeaderValue};
+use tokio_tungstenite::tungstenite::Message;
+
+use chat_protocol::{AdminCommand, AdminEvent};
+
+const DEFAULT_URL: &str = "ws://127.0.0.1:8080/admin"; // Alamat default chat-server
+const TOKEN_ENV: &str = "CHAT_ADMIN_TOKEN";
+const TIMEOUT: Duration = Duration::from_secs(10);
+const QUEUE_ROWS: usize = 20; // Baris yang ditampilkan `queues`
+
+const USAGE: &str = "pemakaian: chat_admin [--url URL] [--token TOKEN] PERINTAH
+
+Perintah:
+  rooms                             daftar room beserta jumlah anggota dan pesan
+  users [ROOM]                      user online (semua, atau di satu room) dan user yang diblokir
+  ban USER_ID [ALASAN]              blokir user dan putuskan semua koneksinya
+  unban USER_ID                     cabut blokir
+  purge ROOM [--user USER_ID]       hapus riwayat room, atau hanya pesan satu user
+  slow-mode ROOM DETIK              satu pesan per DETIK per user; 0 mematikan
+  rules ROOM [TEKS]                 aturan yang harus disetujui sebelum mengirim pesan; tanpa TEKS dihapus
+  anon ROOM on|off                  room anonim dengan nama samaran; off mengeluarkan anggotanya
+  announce [--room ROOM] TEKS       kirim pengumuman; tanpa --room ke room umum
+  queues                            antrean kirim terdalam dan penghitung klien lambat
+
+ROOM \"\" berarti room umum. Token juga bisa diberikan lewat CHAT_ADMIN_TOKEN.";
+
+struct Args {
+    url: String,
+    token: String,
+    command: AdminCommand,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut url = DEFAULT_URL.to_string();
+    let mut token = std::env::var(TOKEN_ENV).ok();
+    let mut rest = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--url" => url = args.next().ok_or("--url butuh alamat API admin")?,
+            "--token" => token = Some(args.next().ok_or("--token butuh token admin")?),
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ => rest.push(arg),
+        }
+    }
+    let token = token.filter(|token| !token.is_empty()).ok_or_else(|| format!("token admin belum diberikan (--token atau {})", TOKEN_ENV))?;
+    let command = parse_command(rest)?;
+    Ok(Args { url, token, command })
+}
+
+fn parse_command(args: Vec<String>) -> Result<AdminCommand, String> {
+    let mut args = args.into_iter();
+    let name = args.next().ok_or(USAGE)?;
+    let command = match name.as_str() {
+        "rooms" => AdminCommand::ListRooms,
+        "users" => AdminCommand::ListUsers { room: args.next() },
+        "queues" => AdminCommand::QueueStats,
+        "ban" => AdminCommand::Ban { user_id: args.next().ok_or("ban butuh USER_ID")?, reason: args.next() },
+        "unban" => AdminCommand::Unban { user_id: args.next().ok_or("unban butuh USER_ID")? },
+        "purge" => {
+            let room = args.next().ok_or("purge butuh ROOM")?;
+            let user_id = match args.next().as_deref() {
+                Some("--user") => Some(args.next().ok_or("--user butuh USER_ID")?),
+                Some(other) => return Err(format!("argumen tidak dikenal: {}", other)),
+                None => None,
+            };
+            AdminCommand::PurgeMessages { room, user_id }
+        }
+        "slow-mode" => {
+            let room = args.next().ok_or("slow-mode butuh ROOM")?;
+            let interval_secs = args.next().and_then(|secs| secs.parse().ok()).ok_or("slow-mode butuh jumlah detik")?;
+            AdminCommand::SetSlowMode { room, interval_secs }
+        }
+        "rules" => {
+            let room = args.next().ok_or("rules butuh ROOM")?;
+            let text = args.collect::<Vec<_>>().join(" ");
+            let rules = Some(text).filter(|text| !text.trim().is_empty());
+            return Ok(AdminCommand::SetRules { room, rules });
+        }
+        "anon" => {
+            let room = args.next().ok_or("anon butuh ROOM")?;
+            let anonymous = match args.next().as_deref() {
+                Some("on") => true,
+                Some("off") => false,
+                _ => return Err("anon butuh on atau off".to_string()),
+            };
+            AdminCommand::SetAnonymous { room, anonymous }
+        }
+        "announce" => {
+            let mut room = None;
+            let mut words = Vec::new();
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--room" => room = Some(args.next().ok_or("--room butuh nama room")?),
+                    _ => words.push(arg),
+                }
+            }
+            let text = words.join(" ");
+            if text.trim().is_empty() {
+                return Err("announce butuh TEKS".to_string());
+            }
+            return Ok(AdminCommand::Announce { room, text });
+        }
+        other => return Err(format!("perintah tidak dikenal: {}\n\n{}", other, USAGE)),
+    };
+    match args.next() {
+        Some(extra) => Err(format!("argumen tidak dikenal: {}", extra)),
+        None => Ok(command),
+    }
+}
+
+// Satu koneksi, satu perintah, satu balasan
+async fn send(url: &str, token: &str, command: &AdminCommand) -> Result<AdminEvent, String> {
+    let mut request = url.into_client_request().map_err(|e| format!("URL tidak valid: {}", e))?;
+    let bearer = HeaderValue::from_str(&format!("Bearer {}", token)).map_err(|_| "token berisi karakter yang tidak valid".to_string())?;
+    request.headers_mut().insert(header::AUTHORIZATION, bearer);
+    let (mut socket, _) = timeout(TIMEOUT, connect_async(request))
+        .await
+        .map_err(|_| format!("tidak ada jawaban dari {}", url))?
+        .map_err(|e| format!("gagal terhubung ke {}: {}", url, e))?;
+
+    let json = serde_json::to_string(command).map_err(|e| e.to_string())?;
+    socket.send(Message::Text(json)).await.map_err(|e| format!("gagal mengirim perintah: {}", e))?;
+    let reply = loop {
+        match timeout(TIMEOUT, socket.next()).await {
+            Err(_) => return Err("server tidak menjawab".to_string()),
+            Ok(None) => return Err("koneksi ditutup server".to_string()),
+            Ok(Some(Err(e))) => return Err(format!("koneksi error: {}", e)),
+            Ok(Some(Ok(Message::Text(text)))) => break text,
+            Ok(Some(Ok(_))) => continue,
+        }
+    };
+    let _ = socket.close(None).await;
+    serde_json::from_str(&reply).map_err(|e| format!("balasan tidak dikenal: {}", e))
+}
+
+fn room_label(room: &str) -> &str {
+    if room.is_empty() {
+        "(umum)"
+    } else {
+        room
+    }
+}
+
+fn print_event(event: AdminEvent) -> bool {
+    match event {
+        AdminEvent::Rooms { rooms } => {
+            println!("{:<24} {:>7} {:>6} {:>10}  kata sandi", "ROOM", "ANGGOTA", "PESAN", "SLOW MODE");
+            for room in rooms {
+                let slow_mode = if room.slow_mode_secs == 0 { "-".to_string() } else { format!("{} dtk", room.slow_mode_secs) };
+                let password = if room.password_protected { "ya" } else { "-" };
+                println!("{:<24} {:>7} {:>6} {:>10}  {}", room_label(&room.name), room.members, room.messages, slow_mode, password);
+            }
+        }
+        AdminEvent::Users { users, banned } => {
+            if users.is_empty() {
+                println!("Tidak ada user online.");
+            }
+            for user in users {
+                let rooms: Vec<&str> = user.rooms.iter().map(|room| room_label(room)).collect();
+                println!("{:<16} {:<20} {} koneksi  {}", user.user_id, user.name, user.connections, rooms.join(", "));
+            }
+            if !banned.is_empty() {
+                println!("\nDiblokir: {}", banned.join(", "));
+            }
+        }
+        AdminEvent::Queues { queues, max_frames, presence_dropped, presence_coalesced, slow_disconnects } => {
+            println!("{:>8} {:<16} {:>6} {:>10} {:>7}", "KONEKSI", "USER", "FRAME", "BYTE", "PUNCAK");
+            for queue in queues.iter().take(QUEUE_ROWS) {
+                println!("{:>8} {:<16} {:>6} {:>10} {:>7}", queue.connection, queue.user_id.as_deref().unwrap_or("-"), queue.frames, queue.bytes, queue.peak);
+            }
+            if queues.len() > QUEUE_ROWS {
+                println!("... dan {} koneksi lain", queues.len() - QUEUE_ROWS);
+            }
+            println!("\nBatas {} frame per koneksi. Presence dibuang: {}, digabung: {}. Diputus karena lambat: {}.", max_frames, presence_dropped, presence_coalesced, slow_disconnects);
+        }
+        AdminEvent::Done { detail } => println!("{}", detail),
+        AdminEvent::Error { message } => {
+            eprintln!("Ditolak server: {}", message);
+            return false;
+        }
+    }
+    true
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    match send(&args.url, &args.token, &args.command).await {
+        Ok(event) => {
+            if !print_event(event) {
+                std::process::exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
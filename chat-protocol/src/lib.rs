@@ -0,0 +1,1379 @@
+// chat-protocol/src/lib.rs
+// Tipe frame yang dipakai bersama oleh klien web (yew_websockets_chat) dan server (chat-server),
+// supaya kedua sisi tidak bisa berbeda format. Frame protokol memakai field "type" sebagai penanda
+// jenis, sedangkan pesan chat biasa (ChatMessage) tetap dikirim tanpa tag agar kompatibel dengan server lama.
+use serde::{Deserialize, Serialize};
+
+pub const HISTORY_PAGE: usize = 50; // Pesan per halaman riwayat, termasuk yang diputar ulang saat bergabung ke room
+pub const MAX_HISTORY_PAGE: usize = 200; // Batas `limit` HistoryRequest di server
+pub const TYPING_TTL_MS: f64 = 6_000.0; // Indikator mengetik hilang setelah selama ini tanpa Typing baru
+pub const MAX_CUSTOM_PAYLOAD_BYTES: usize = 16 * 1024; // Batas payload Custom; yang lebih besar dibuang server
+pub const NOTES_NAMESPACE: &str = "webchat.notes"; // Namespace Custom untuk notepad bersama room (lihat Notepad)
+pub const MAX_ROOM_NOTES: usize = 100; // Catatan baru di luar batas ini ditolak server; suntingan tetap diterima
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChatMessage {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>, // Id pesan dari server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>, // Id buatan klien pengirim; dikembalikan server apa adanya sebagai tanda pesan diterima
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>, // Id akun yang stabil; `username` hanyalah display name saat pesan dikirim
+    pub username: String,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "epoch_millis")]
+    pub timestamp: Option<f64>, // Epoch millis jam server. Klien mengisinya dulu dari jam server terkoreksi; server selalu menimpanya
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "epoch_millis")]
+    pub edited_at: Option<f64>, // Epoch millis; diisi server jika pesan pernah disunting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachment: Option<Attachment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<f64>, // Epoch millis jam server; diisi server di room dengan pesan sementara
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<Box<RoomEvent>>, // Kartu acara terjadwal dengan tombol RSVP; di-box agar ChatMessage tetap kecil
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub system: bool, // Pesan dari server sendiri, mis. pengingat acara
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room: Option<String>, // Room tujuan; None = room umum, atau room saat ini untuk server lama tanpa multi-room
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<MessageComponent>, // Tombol/menu dari bot; hasilnya dikirim balik sebagai Interaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operator: Option<Box<Operator>>, // Pengirim di balik akun bersama (Capability::Operators); di-box agar ChatMessage tetap kecil
+    // Bisikan: pesan di room ini yang hanya untuk user id ini. Berbeda dengan DirectMessage, bisikan tetap
+    // tampil di antara pesan room. Server hanya meneruskannya ke penerima dan pengirim, dan tidak menyimpannya
+    // di riwayat room, jadi bisikan tidak ikut HistoryReplay atau Resume.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub whisper_to: Option<String>,
+}
+
+// Operator di balik akun bersama (mis. akun tim support atau bot yang dipakai bergantian).
+// Server menurunkan ulang nama dan inisial lewat `from_name`, jadi klien tidak bisa mengirim inisial sembarang.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Operator {
+    pub name: String,
+    pub initials: String, // Ditampilkan di samping nama akun pada pesan dan indikator mengetik
+}
+
+impl Operator {
+    pub const MAX_NAME_CHARS: usize = 40;
+    const MAX_INITIALS: usize = 3;
+
+    // "budi  santoso" -> nama "budi santoso", inisial "BS". None jika nama kosong.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name: String = name.split_whitespace().collect::<Vec<_>>().join(" ").chars().take(Self::MAX_NAME_CHARS).collect();
+        let initials: String = name.split_whitespace().filter_map(|word| word.chars().find(|c| c.is_alphanumeric())).flat_map(char::to_uppercase).take(Self::MAX_INITIALS).collect();
+        (!initials.is_empty()).then_some(Self { name: name.trim_end().to_string(), initials })
+    }
+}
+
+// Perintah dari klien ke server
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum ClientCommand {
+    // Frame paling awal jika klien punya token login (JWT atau token sesi); server membalas AuthResult
+    Authenticate { token: String },
+    // Frame pertama setelah terhubung: fitur protokol opsional yang didukung klien ini. Server yang
+    // mengenal sebagian di antaranya membalas CapabilitiesAccepted; server lama tidak membalas.
+    Hello { capabilities: Vec<Capability> },
+    // User sedang menulis pesan di room ini; dikirim berulang selama masih mengetik (lihat TYPING_TTL_MS).
+    // `operator` hanya dipakai setelah server menerima Capability::Operators.
+    Typing {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        room: Option<String>, // None = room umum
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        operator: Option<Operator>,
+    },
+    // Pesan terbaru yang sudah dirender klien di room ini; server meneruskannya sebagai ReadReceipt jika lebih
+    // baru dari tanda sebelumnya. Tidak dikirim jika Privacy::disable_read_receipts aktif.
+    MarkRead {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        room: Option<String>, // None = room umum
+        message_id: String,
+    },
+    // Data aplikasi embedder (posisi kursor, langkah permainan, …) yang ikut menumpang koneksi chat.
+    // Server hanya meneruskannya ke anggota room lain sebagai ServerEvent::Custom tanpa membaca atau
+    // menyimpan `payload`. Dengan `key`, frame yang belum terkirim diganti frame berikutnya dengan key
+    // yang sama (cukup posisi terakhir); tanpa `key` setiap frame diantar.
+    Custom {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        room: Option<String>, // None = room umum
+        namespace: String,
+        payload: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        key: Option<String>,
+    },
+    JoinRoom {
+        room: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        password: Option<String>, // Dikirim apa adanya; server yang menyimpan dan membandingkan hash-nya
+    },
+    // Membuat room baru (lalu otomatis bergabung). Dengan `ttl_secs`, room diarsipkan setelah tidak aktif selama itu.
+    CreateRoom {
+        room: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        password: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ttl_secs: Option<u64>,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        feedback_box: bool, // Kotak saran anonim; lihat RoomJoined::feedback_box
+    },
+    // Khusus moderator: batasi tiap user satu pesan per `interval_secs`; 0 mematikan slow mode
+    SetSlowMode { room: String, interval_secs: u32 },
+    // Khusus moderator: matikan/nyalakan embed gambar dan GIF di room
+    SetEmbedsDisabled { room: String, disabled: bool },
+    // Khusus moderator: bahasa utama room (kode BCP 47 seperti "en"); None = tidak ditentukan
+    SetRoomLanguage {
+        room: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        language: Option<String>,
+    },
+    // Minta terjemahan satu pesan ke bahasa `target`; server membalas Translated atau TranslationFailed.
+    // `source` adalah bahasa pesan menurut deteksi klien (atau bahasa room); None = server menebak sendiri
+    Translate {
+        message_id: String,
+        target: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        source: Option<String>,
+    },
+    // User menyetujui aturan room; server mencatatnya agar gerbang aturan tidak muncul lagi
+    AcceptRules { room: String },
+    // Dikirim setelah reconnect: minta pesan sejak `since` (timestamp pesan terakhir yang diterima),
+    // dibatasi `limit` pesan terbaru agar tab tidak macet setelah offline lama
+    Resume {
+        #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "epoch_millis")]
+        since: Option<f64>,
+        limit: usize,
+    },
+    // Pesan langsung ke satu user, di luar room mana pun; server membalas kedua pihak dengan ServerEvent::DirectMessage
+    DirectMessage { to: String, text: String },
+    // Mode agen dukungan (opt-in): pesan langsung dari tamu masuk ke antrean bersama, bukan ke agen tertentu.
+    // Server membalas SupportQueue lalu mengirim SupportTicket setiap ada tamu baru atau penugasan berubah.
+    SetSupportAgent { enabled: bool },
+    // Ambil percakapan dari antrean. Jika agen lain lebih dulu, SupportTicket berikutnya menunjukkan agen tersebut.
+    ClaimConversation { guest: String },
+    // Tab klien disembunyikan (false) atau terlihat lagi (true). Selama di latar belakang server boleh berhenti
+    // mengirim Presence dan PresenceDigest; saat kembali klien meminta Roster dan server mengirim digest lengkap.
+    SetForeground { foreground: bool },
+    // Minta daftar lengkap user yang sedang online di room; server membalas PresenceEvent::Roster
+    SyncRoster { room: String },
+    // Status user ini (online/pergi/sibuk), disiarkan ke user lain lewat PresenceEvent::StatusChanged
+    SetStatus { status: UserStatus },
+    // Status kustom user ini; None menghapusnya. Disiarkan lewat PresenceEvent::CustomStatusChanged
+    SetCustomStatus {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        status: Option<CustomStatus>,
+    },
+    // Kembalikan percakapan ke antrean agar bisa diambil agen lain
+    ReleaseConversation { guest: String },
+    // Keluar dari satu room; room lain yang diikuti lewat koneksi ini tetap berjalan
+    LeaveRoom { room: String },
+    // Ambil sisa pesan yang terlewat di antara `after` dan `before` (keduanya timestamp epoch millis, eksklusif)
+    FetchHistory {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        room: Option<String>, // None = room umum
+        #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "epoch_millis")]
+        after: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "epoch_millis")]
+        before: Option<f64>,
+    },
+    // Halaman riwayat room yang lebih lama dari pesan `before_id` (None = halaman terbaru), paling banyak
+    // `limit` pesan; server memangkasnya ke MAX_HISTORY_PAGE. Dibalas HistoryResponse.
+    HistoryRequest {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        room: Option<String>, // None = room umum
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        before_id: Option<String>,
+        limit: usize,
+    },
+    // Upload lampiran bertahap. Server membalas UploadOffset setelah UploadStart, tiap chunk, dan UploadResume.
+    // Chunk dikirim sebagai UploadChunkFrame biner jika server mengizinkannya, selain itu sebagai UploadChunk.
+    UploadStart { upload_id: String, name: String, mime: String, size: u64, chunk_size: u64 },
+    UploadChunk { upload_id: String, offset: u64, data: String }, // `data` dalam base64
+    UploadResume { upload_id: String }, // Tanyakan offset terakhir setelah koneksi pulih
+    UploadCancel { upload_id: String },
+    // Dikirim saat tersambung dan setiap kali pengaturan privasi berubah
+    UpdatePrivacy(Privacy),
+    // Minta arsip semua pesan milik user ini; server membalas ExportReady
+    RequestExport,
+    // Hapus akun. Server menganonimkan pesan lama lalu membalas AccountDeleted.
+    DeleteAccount,
+    // Satu halaman anggota room, urut dari server; `query` mencari di indeks anggota server.
+    // Server membalas MemberPage; `cursor` diambil dari `next_cursor` halaman sebelumnya.
+    FetchMembers {
+        room: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        query: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cursor: Option<String>,
+        limit: usize,
+    },
+    // Cari user di direktori server berdasarkan awal nama/id untuk saran @mention; server membalas
+    // UserSearchResults, atau UserSearchRejected jika terlalu sering
+    SearchUsers { prefix: String, limit: usize },
+    // Dikirim oleh klien bot: daftarkan slash command bot ini ke server (menggantikan daftar sebelumnya)
+    RegisterCommands { commands: Vec<BotCommand> },
+    // Minta slash command bot yang tersedia di room; server membalas Commands
+    ListCommands { room: String },
+    // Jalankan slash command bot; server meneruskannya ke bot pemilik `name`. `args` adalah teks setelah nama perintah.
+    RunCommand { room: String, name: String, args: String },
+    // Jadwalkan acara di room. Server memposting kartu acara sebagai pesan, lalu pesan `system`
+    // pengingat sesaat sebelum `starts_at` (epoch millis).
+    CreateEvent { room: String, title: String, starts_at: f64 },
+    // User menekan tombol atau memilih opsi di pesan bot; server meneruskannya ke bot pengirim pesan
+    Interaction(Interaction),
+    // Jawaban RSVP user ini; server membalas semua anggota room dengan EventUpdated
+    Rsvp { event_id: String, response: Rsvp },
+    // Minta daftar sesi aktif akun ini; server membalas Sessions
+    ListSessions,
+    // Cabut sesi lain; klien di sesi itu menerima SessionRevoked lalu diputus
+    RevokeSession { session_id: String },
+    // Minta jam server; `client_time` (jam lokal, epoch millis) dikembalikan apa adanya di TimeSync
+    TimeSync { client_time: f64 },
+    // Heartbeat saat koneksi lama tidak menerima frame apa pun; server menjawab Pong dengan `nonce` yang sama
+    Ping { nonce: u64 },
+    // Mulai pendaftaran 2FA; server membalas TwoFactorSetup berisi rahasia baru
+    StartTwoFactorSetup,
+    // Kode TOTP pertama dari authenticator untuk menyelesaikan pendaftaran
+    ConfirmTwoFactorSetup { code: String },
+    // Jawaban TwoFactorRequired saat login; isi salah satu dari `code` atau `recovery_code`
+    VerifyTwoFactor {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        code: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        recovery_code: Option<String>,
+    },
+    DisableTwoFactor {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        code: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        recovery_code: Option<String>,
+    },
+    // Minta daftar server STUN/TURN; server membalas IceServers (kredensial TURN biasanya sementara)
+    RequestIceServers,
+    // Khusus admin: ganti daftar STUN/TURN untuk semua klien; server menyiarkan IceServers baru
+    SetIceServers { servers: Vec<IceServer> },
+    // Tag dari klien yang lebih baru dari server ini; diabaikan, bukan dianggap pesan chat rusak
+    #[serde(other)]
+    Unknown,
+}
+
+// Event dari server ke klien
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum ServerEvent {
+    RoomJoined {
+        room: String,
+        #[serde(default)]
+        expires_at: Option<f64>, // Epoch millis, hanya untuk room sementara
+        #[serde(default)]
+        is_moderator: bool,
+        #[serde(default)]
+        slow_mode_secs: u32, // 0 = slow mode tidak aktif
+        #[serde(default)]
+        rules: Option<String>, // Teks sambutan/aturan room
+        #[serde(default)]
+        rules_accepted: bool,  // Sudah pernah disetujui oleh user ini (dicatat server)
+        #[serde(default)]
+        embeds_disabled: bool, // Gambar/GIF ditampilkan sebagai tautan saja
+        #[serde(default)]
+        dm_peer: Option<String>, // Diisi jika room ini adalah pesan langsung: user id lawan bicara
+        #[serde(default)]
+        pseudonym: Option<Pseudonym>, // Diisi jika room anonim: identitas samaran user ini
+        #[serde(default)]
+        message_ttl_secs: Option<u32>, // Pesan sementara: tiap pesan hilang setelah sekian detik
+        #[serde(default)]
+        presence_digest_secs: Option<u32>, // Room besar: presence dikirim sebagai PresenceDigest tiap sekian detik, bukan per user
+        // Kotak saran anonim: pesan hanya diteruskan ke moderator, dan server menghapus id serta nama
+        // pengirim sebelum meneruskannya. Pengirim tetap menerima salinan pesannya sendiri secara utuh.
+        #[serde(default)]
+        feedback_box: bool,
+        #[serde(default)]
+        language: Option<String>, // Bahasa utama room; klien berbahasa lain menawarkan terjemahan
+    },
+    TimeSync { client_time: f64, server_time: f64 },
+    Pong { nonce: u64 },
+    // Balasan Authenticate (atau token di query `?token=` URL WebSocket). Jika berhasil, `user_id` dan `name`
+    // adalah identitas akun dari token dan menggantikan id lokal klien.
+    AuthResult {
+        ok: bool,
+        #[serde(default)]
+        user_id: Option<String>,
+        #[serde(default)]
+        name: Option<String>,
+        #[serde(default)]
+        reason: Option<String>, // Alasan penolakan, mis. token kedaluwarsa
+    },
+    // Server ini mewajibkan login: frame selain Authenticate diabaikan sampai AuthResult berhasil
+    AuthRequired,
+    // Balasan Hello: capability yang ditawarkan klien dan juga didukung server
+    CapabilitiesAccepted { capabilities: Vec<Capability> },
+    // User lain (atau operator lain di akun bersama yang sama) sedang mengetik di room. Hilang sendiri
+    // setelah TYPING_TTL_MS tanpa event baru, atau saat pesannya masuk.
+    Typing {
+        room: String,
+        user_id: String,
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        operator: Option<Operator>,
+    },
+    // User ini sudah membaca room sampai pesan `message_id`. Juga dikirim untuk setiap tanda yang tersimpan
+    // setelah HistoryReplay saat bergabung ke room.
+    ReadReceipt { room: String, user_id: String, message_id: String },
+    // ClientCommand::Custom dari user lain di room, diteruskan apa adanya
+    Custom { room: String, user_id: String, namespace: String, payload: String },
+    // Balasan Hello yang menawarkan MessagePack: format frame berikutnya dari kedua arah. Dikirim sebagai frame teks;
+    // server tetap harus menerima frame JSON yang sudah dikirim klien sebelum event ini tiba.
+    EncodingSelected { encoding: Encoding },
+    // Server mengganti nama samaran user ini di room anonim (mis. setiap sesi baru atau berkala)
+    PseudonymRotated { room: String, pseudonym: Pseudonym },
+    JoinFailed { room: String, reason: JoinFailure },
+    // Pesan langsung masuk, atau salinan pesan langsung yang dikirim user ini (`to` = penerima)
+    DirectMessage { to: String, message: Box<ChatMessage> }, // Di-box agar ServerEvent tetap kecil
+    // Isi antrean dukungan saat mode agen diaktifkan (termasuk yang sudah ditangani agen lain)
+    SupportQueue { tickets: Vec<SupportTicket> },
+    // Percakapan tamu baru masuk antrean, atau agen yang menanganinya berubah
+    SupportTicket { ticket: SupportTicket },
+    // Percakapan selesai atau tamu pergi; hilang dari antrean semua agen
+    SupportTicketClosed { guest: String },
+    // User tidak lagi mengikuti room: balasan LeaveRoom, atau dikeluarkan oleh server
+    RoomLeft { room: String },
+    // Waktu kedaluwarsa room sementara diperpanjang karena ada aktivitas
+    RoomExpiryUpdated { room: String, expires_at: f64 },
+    // Room sementara sudah diarsipkan oleh server
+    RoomExpired { room: String },
+    SlowModeChanged { room: String, interval_secs: u32 },
+    EmbedsChanged { room: String, disabled: bool },
+    RoomLanguageChanged {
+        room: String,
+        #[serde(default)]
+        language: Option<String>,
+    },
+    Translated { message_id: String, target: String, text: String },
+    TranslationFailed {
+        message_id: String,
+        #[serde(default)]
+        reason: Option<String>,
+    },
+    // Arsip ekspor data siap diunduh; tautan hanya berlaku sampai `expires_at` (epoch millis)
+    ExportReady {
+        url: String,
+        #[serde(default)]
+        expires_at: Option<f64>,
+    },
+    Sessions { sessions: Vec<SessionInfo> },
+    // Balasan ListCommands, juga dikirim ulang saat bot di room mendaftarkan perintah baru
+    Commands { room: String, commands: Vec<BotCommand> },
+    // Daftar RSVP acara berubah; kartu acara di pesan lama ikut diperbarui
+    EventUpdated { event: RoomEvent },
+    // Bot mengganti tombol/menu di pesannya, mis. menonaktifkan tombol setelah persetujuan atau menampilkan hasil polling
+    ComponentsUpdated { message_id: String, components: Vec<MessageComponent> },
+    // Balasan SearchUsers; `prefix` dikembalikan apa adanya
+    UserSearchResults { prefix: String, users: Vec<UserMatch> },
+    UserSearchRejected { prefix: String, retry_after_secs: u32 },
+    // Balasan FetchMembers; `next_cursor` kosong berarti halaman terakhir
+    MemberPage {
+        room: String,
+        #[serde(default)]
+        query: Option<String>,
+        members: Vec<MemberInfo>,
+        #[serde(default)]
+        next_cursor: Option<String>,
+        #[serde(default)]
+        total: Option<u64>,
+    },
+    TwoFactorSetup { secret: String, otpauth_url: String, recovery_codes: Vec<String> },
+    // 2FA aktif untuk akun ini; juga dikirim saat tersambung sebagai status
+    TwoFactorEnabled,
+    TwoFactorDisabled,
+    // Login butuh kode 2FA; server menahan event lain sampai TwoFactorAccepted
+    TwoFactorRequired,
+    TwoFactorAccepted,
+    TwoFactorRejected {
+        #[serde(default)]
+        attempts_left: Option<u32>,
+    },
+    // Konfigurasi STUN/TURN untuk fitur WebRTC; kredensial berlaku `ttl_secs` lalu perlu diminta ulang
+    IceServers {
+        servers: Vec<IceServer>,
+        #[serde(default)]
+        ttl_secs: Option<u32>,
+    },
+    // Sesi ini dicabut dari perangkat lain; server akan menutup koneksi
+    SessionRevoked {
+        #[serde(default)]
+        reason: Option<String>,
+    },
+    // Server masuk mode pemeliharaan sampai `until` (epoch millis jam server) dan mungkin menutup koneksi.
+    // Klien menjadi hanya-baca dan tidak mencoba reconnect sebelum waktu itu.
+    Maintenance {
+        until: f64,
+        #[serde(default)]
+        message: Option<String>,
+    },
+    // Akun user ini sudah dihapus (bisa juga dari perangkat lain)
+    AccountDeleted,
+    // Akun user lain dihapus; pesan lamanya kini tampil sebagai pengguna anonim
+    UserDeleted { user_id: String },
+    // Pesan ditolak karena slow mode; klien harus menunggu `retry_after_secs` lagi
+    SlowModeRejected {
+        room: String,
+        retry_after_secs: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        client_id: Option<String>, // Pesan yang ditolak, jika klien memberinya id
+    },
+    // Pesan dengan `client_id` ini tidak disimpan server, mis. dikirim ke room yang tidak diikuti
+    MessageRejected { client_id: String, reason: String },
+    // Balasan Resume: paling banyak `limit` pesan terbaru, plus jumlah total pesan yang terlewat
+    HistoryReplay { messages: Vec<ChatMessage>, total_missed: u64 },
+    // Balasan FetchHistory
+    HistoryPage { messages: Vec<ChatMessage> },
+    // Balasan HistoryRequest, urut dari yang terlama; `room` dan `before_id` disalin dari permintaan.
+    // `has_more` = masih ada pesan yang lebih lama. `before_id` yang sudah tidak ada di riwayat server
+    // (dipangkas retensi) atau room yang tidak diikuti dibalas halaman kosong tanpa `has_more`.
+    HistoryResponse {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        room: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        before_id: Option<String>,
+        messages: Vec<ChatMessage>,
+        has_more: bool,
+    },
+    // Jumlah byte upload yang sudah diterima server; klien melanjutkan dari offset ini.
+    // `binary` = chunk berikutnya boleh dikirim sebagai UploadChunkFrame; server lama tidak mengirimnya.
+    UploadOffset {
+        upload_id: String,
+        offset: u64,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        binary: bool,
+    },
+    // Semua byte sudah diterima; lampiran bisa diakses di `url`
+    UploadComplete { upload_id: String, url: String },
+    // Upload ditolak oleh validasi server (jenis file, ukuran, pemindai antivirus)
+    UploadFailed { upload_id: String, reason: UploadRejection },
+    // Tag dari server yang lebih baru dari klien ini; diabaikan agar fitur baru tidak memutus parsing
+    #[serde(other)]
+    Unknown,
+}
+
+// Jenis sebuah frame, dibaca sebelum isinya. Frame bertag "type" adalah ClientCommand/ServerEvent/
+// PresenceEvent; frame tanpa tag adalah ChatMessage polos, format asli server JS.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct FrameTag {
+    #[serde(rename = "type", default)]
+    pub kind: Option<String>,
+}
+
+// Frame dari server setelah dibedakan lewat FrameTag; loop baca klien cukup mencocokkan variannya
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerFrame {
+    Chat(ChatMessage),
+    Presence(PresenceEvent),
+    Event(ServerEvent), // Termasuk ServerEvent::Unknown untuk tag yang belum dikenal
+}
+
+// Event presence dari server. Formatnya sama dengan ServerEvent (bertag "type"), tetapi dipisah agar
+// klien bisa meneruskannya ke penyimpan presence tanpa melewati penanganan pesan dan event room.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum PresenceEvent {
+    // Status online user; `last_seen` kosong jika user tersebut menyembunyikannya
+    Presence {
+        user_id: String,
+        online: bool,
+        #[serde(default)]
+        last_seen: Option<f64>,
+    },
+    // Ringkasan presence berkala untuk room besar. `full` = `online` adalah daftar lengkap, user lain dianggap offline;
+    // selain itu hanya berisi perubahan sejak digest sebelumnya.
+    PresenceDigest {
+        room: String,
+        #[serde(default)]
+        full: bool,
+        #[serde(default)]
+        online: Vec<String>,
+        #[serde(default)]
+        offline: Vec<OfflineUser>,
+    },
+    // Balasan SyncRoster: semua user yang sedang online di room, menggantikan daftar sebelumnya
+    Roster { room: String, users: Vec<RosterUser> },
+    // Perubahan daftar setelah Roster: user masuk room (atau baru online) dan keluar (atau offline)
+    UserJoined { room: String, user: RosterUser },
+    UserLeft { room: String, user_id: String },
+    StatusChanged { user_id: String, status: UserStatus },
+    CustomStatusChanged {
+        user_id: String,
+        #[serde(default)]
+        status: Option<CustomStatus>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RosterUser {
+    pub user_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub status: UserStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_status: Option<CustomStatus>,
+}
+
+// Status yang ditulis user sendiri, mis. "🗓️ sedang rapat". Server meneruskannya apa adanya;
+// setelah `expires_at` klien berhenti menampilkannya.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CustomStatus {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<String>,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<f64>, // Epoch millis jam server; None = sampai dihapus user
+}
+
+impl CustomStatus {
+    pub const MAX_TEXT_CHARS: usize = 80;
+
+    pub fn is_expired(&self, now_ms: f64) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now_ms)
+    }
+
+    pub fn label(&self) -> String {
+        match &self.emoji {
+            Some(emoji) => format!("{} {}", emoji, self.text),
+            None => self.text.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UserStatus {
+    #[default]
+    Online,
+    Away,
+    Busy, // Tetap online, tetapi tidak ingin diganggu
+}
+
+impl UserStatus {
+    pub const ALL: [UserStatus; 3] = [UserStatus::Online, UserStatus::Away, UserStatus::Busy];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            UserStatus::Online => "Online",
+            UserStatus::Away => "Tidak di tempat",
+            UserStatus::Busy => "Sibuk",
+        }
+    }
+
+    pub fn class(self) -> &'static str {
+        match self {
+            UserStatus::Online => "online",
+            UserStatus::Away => "away",
+            UserStatus::Busy => "busy",
+        }
+    }
+}
+
+// Lampiran yang sudah selesai di-upload, dibawa oleh ChatMessage
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Attachment {
+    pub url: String,
+    pub name: String,
+    pub mime: String,
+    pub size: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poster: Option<String>, // Thumbnail untuk video, ditampilkan sebelum video dimuat
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<GifRating>, // Rating konten dari penyedia GIF (Giphy/Tenor)
+}
+
+impl Attachment {
+    pub fn is_image(&self) -> bool {
+        self.mime.starts_with("image/")
+    }
+
+    pub fn is_video(&self) -> bool {
+        self.mime.starts_with("video/")
+    }
+
+    pub fn is_audio(&self) -> bool {
+        self.mime.starts_with("audio/")
+    }
+
+    pub fn is_gif(&self) -> bool {
+        self.mime == "image/gif"
+    }
+}
+
+// Satu sesi login di tabel sesi server
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SessionInfo {
+    pub session_id: String,
+    pub device: String, // Ringkasan user agent, mis. "Firefox di Linux"
+    #[serde(default)]
+    pub location: Option<String>, // Perkiraan lokasi dari IP, jika server menyediakannya
+    pub last_active: f64,         // Epoch millis
+    #[serde(default)]
+    pub current: bool, // Sesi yang sedang dipakai klien ini
+}
+
+// Satu anggota room di MemberPage, sudah membawa status presence-nya
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MemberInfo {
+    pub user_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub online: bool,
+    #[serde(default)]
+    pub last_seen: Option<f64>,
+    #[serde(default)]
+    pub custom_status: Option<CustomStatus>,
+}
+
+// Slash command milik bot, untuk autocomplete di input pesan
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BotCommand {
+    pub name: String, // Tanpa "/"
+    #[serde(default)]
+    pub description: String,
+    pub bot_id: String,
+    #[serde(default)]
+    pub bot_name: Option<String>,
+    #[serde(default)]
+    pub args: Vec<CommandArg>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CommandArg {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+impl BotCommand {
+    // "/deploy <env> [versi]": argumen wajib dalam <>, opsional dalam []
+    pub fn signature(&self) -> String {
+        let mut signature = format!("/{}", self.name);
+        for arg in &self.args {
+            let (open, close) = if arg.required { ('<', '>') } else { ('[', ']') };
+            signature.push_str(&format!(" {}{}{}", open, arg.name, close));
+        }
+        signature
+    }
+}
+
+// Tombol atau menu pilihan di pesan bot. `custom_id` ditentukan bot dan dikirim kembali apa adanya di Interaction.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MessageComponent {
+    Button {
+        custom_id: String,
+        label: String,
+        #[serde(default)]
+        style: ButtonStyle,
+        #[serde(default)]
+        disabled: bool,
+    },
+    Select {
+        custom_id: String,
+        #[serde(default)]
+        placeholder: Option<String>,
+        options: Vec<SelectOption>,
+        #[serde(default)]
+        disabled: bool,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ButtonStyle {
+    Primary,
+    #[default]
+    Secondary,
+    Success,
+    Danger,
+}
+
+impl ButtonStyle {
+    pub fn class(self) -> &'static str {
+        match self {
+            ButtonStyle::Primary => "primary",
+            ButtonStyle::Secondary => "secondary",
+            ButtonStyle::Success => "success",
+            ButtonStyle::Danger => "danger",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SelectOption {
+    pub value: String,
+    pub label: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Interaction {
+    pub message_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room: Option<String>,
+    pub custom_id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>, // Opsi yang dipilih di menu; kosong untuk tombol
+}
+
+// Acara terjadwal di room, dibawa oleh ChatMessage sebagai kartu
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RoomEvent {
+    pub id: String,
+    pub title: String,
+    pub starts_at: f64, // Epoch millis
+    #[serde(default)]
+    pub going: Vec<String>, // Id user per jawaban RSVP
+    #[serde(default)]
+    pub maybe: Vec<String>,
+    #[serde(default)]
+    pub declined: Vec<String>,
+}
+
+impl RoomEvent {
+    pub fn response_of(&self, user_id: &str) -> Option<Rsvp> {
+        Rsvp::ALL.into_iter().find(|response| self.responders(*response).iter().any(|id| id == user_id))
+    }
+
+    pub fn responders(&self, response: Rsvp) -> &[String] {
+        match response {
+            Rsvp::Going => &self.going,
+            Rsvp::Maybe => &self.maybe,
+            Rsvp::Declined => &self.declined,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Rsvp {
+    Going,
+    Maybe,
+    Declined,
+}
+
+impl Rsvp {
+    pub const ALL: [Rsvp; 3] = [Rsvp::Going, Rsvp::Maybe, Rsvp::Declined];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Rsvp::Going => "Hadir",
+            Rsvp::Maybe => "Mungkin",
+            Rsvp::Declined => "Tidak hadir",
+        }
+    }
+}
+
+// Percakapan tamu di antrean dukungan. Balasan dikirim lewat DirectMessage ke `guest`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SupportTicket {
+    pub guest: String, // User id tamu
+    pub guest_name: String,
+    #[serde(default)]
+    pub preview: Option<String>, // Pesan pertama tamu
+    pub opened_at: f64, // Epoch millis
+    #[serde(default)]
+    pub agent: Option<SupportAgent>, // None = masih menunggu di antrean
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SupportAgent {
+    pub user_id: String,
+    pub name: String,
+}
+
+// Satu hasil SearchUsers
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UserMatch {
+    pub user_id: String,
+    pub name: String,
+}
+
+// Fitur protokol opsional yang diumumkan klien di Hello
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    PresenceDigest, // Klien bisa menggabungkan PresenceDigest; server boleh berhenti mengirim Presence per user
+    MessagePack,    // Klien bisa membaca dan menulis frame biner MessagePack; server memilih lewat EncodingSelected
+    Operators,      // Akun bersama: pesan dan Typing membawa `operator`; server menerimanya hanya setelah CapabilitiesAccepted
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OfflineUser {
+    pub user_id: String,
+    #[serde(default)]
+    pub last_seen: Option<f64>,
+}
+
+// Satu entri RTCIceServer: "stun:host:3478", "turn:host:3478?transport=udp" atau "turns:host:5349"
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IceServer {
+    pub urls: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential: Option<String>,
+}
+
+impl IceServer {
+    pub fn is_turn(&self) -> bool {
+        self.urls.iter().any(|url| url.starts_with("turn:") || url.starts_with("turns:"))
+    }
+}
+
+// Identitas samaran di room anonim. `id` hanya berlaku per sesi dan tidak bisa
+// dihubungkan ke akun asli; pesan dari user lain di room itu juga memakai id seperti ini.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Pseudonym {
+    pub id: String,
+    pub name: String,
+}
+
+// Rating konten GIF, urut dari yang paling aman. Nilainya mengikuti parameter `rating` Giphy.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum GifRating {
+    G,
+    #[default]
+    Pg,
+    #[serde(rename = "pg-13")]
+    Pg13,
+    R,
+}
+
+impl GifRating {
+    pub const ALL: [GifRating; 4] = [GifRating::G, GifRating::Pg, GifRating::Pg13, GifRating::R];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GifRating::G => "G (semua umur)",
+            GifRating::Pg => "PG",
+            GifRating::Pg13 => "PG-13",
+            GifRating::R => "R (dewasa)",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum JoinFailure {
+    WrongPassword,
+    PasswordRequired,
+    RoomNotFound,
+    RoomExists,
+    TooManyAttempts, // Terlalu banyak kata sandi salah dari koneksi ini atau untuk room ini; coba lagi nanti
+    #[serde(other)]
+    Unknown,
+}
+
+// Alasan penolakan upload dari server. Jenis file ditentukan dari magic byte isi file,
+// bukan dari MIME yang dikirim klien.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum UploadRejection {
+    TypeNotAllowed { detected: String },
+    TypeMismatch { declared: String, detected: String },
+    TooLarge { max_size: u64 },
+    TooManyPending, // Batas jumlah atau total ukuran upload yang belum selesai per user tercapai
+    Infected {
+        #[serde(default)]
+        signature: Option<String>,
+    },
+    ScanUnavailable, // Pemindai antivirus sedang tidak bisa dihubungi
+    #[serde(other)]
+    Unknown,
+}
+
+impl UploadRejection {
+    pub fn message(&self) -> String {
+        match self {
+            UploadRejection::TypeNotAllowed { detected } => format!("Jenis file {} tidak diizinkan.", detected),
+            UploadRejection::TypeMismatch { declared, detected } => {
+                format!("Isi file ({}) tidak sesuai dengan jenisnya ({}).", detected, declared)
+            }
+            UploadRejection::TooLarge { max_size } => format!("File terlalu besar; maksimal {}.", format_size(*max_size)),
+            UploadRejection::TooManyPending => "Terlalu banyak upload yang belum selesai; tunggu atau batalkan yang lain dulu.".to_string(),
+            UploadRejection::Infected { signature: Some(signature) } => format!("File terdeteksi berbahaya ({}).", signature),
+            UploadRejection::Infected { signature: None } => "File terdeteksi berbahaya.".to_string(),
+            UploadRejection::ScanUnavailable => "Pemindaian file sedang tidak tersedia.".to_string(),
+            UploadRejection::Unknown => "Upload ditolak server.".to_string(),
+        }
+    }
+
+    // Hanya penolakan sementara yang masuk akal untuk dicoba lagi dengan file yang sama
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, UploadRejection::ScanUnavailable | UploadRejection::Unknown)
+    }
+}
+
+impl JoinFailure {
+    pub fn message(&self) -> &'static str {
+        match self {
+            JoinFailure::WrongPassword => "Kata sandi room salah.",
+            JoinFailure::PasswordRequired => "Room ini membutuhkan kata sandi.",
+            JoinFailure::RoomNotFound => "Room tidak ditemukan.",
+            JoinFailure::RoomExists => "Room dengan nama itu sudah ada.",
+            JoinFailure::TooManyAttempts => "Terlalu banyak percobaan kata sandi. Coba lagi beberapa menit lagi.",
+            JoinFailure::Unknown => "Gagal bergabung ke room.",
+        }
+    }
+}
+
+// Aturan display name. chat-server menolak pesan dengan nama yang melanggarnya (MessageRejected), jadi
+// pesan pertama maupun ganti nama tidak bisa memakai nama cadangan seperti pengirim pengumuman "Admin".
+// Klien memakai aturan yang sama untuk umpan balik sebelum mengirim. Di chat-server diatur lewat [usernames].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct UsernameRules {
+    pub min_len: usize,
+    pub max_len: usize,
+    pub allowed_symbols: String,     // Karakter selain huruf/angka yang diizinkan
+    pub reserved_names: Vec<String>, // Dibandingkan tanpa memperhatikan huruf besar/kecil
+}
+
+impl Default for UsernameRules {
+    fn default() -> Self {
+        Self {
+            min_len: 3,
+            max_len: 20,
+            allowed_symbols: String::from("_-."),
+            reserved_names: vec![String::from("System"), String::from("Admin")],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UsernameError {
+    TooShort(usize),
+    TooLong(usize),
+    InvalidChar(char),
+    Reserved(String),
+}
+
+impl UsernameRules {
+    pub fn validate(&self, name: &str) -> Result<(), UsernameError> {
+        let len = name.chars().count();
+        if len < self.min_len {
+            return Err(UsernameError::TooShort(self.min_len));
+        }
+        if len > self.max_len {
+            return Err(UsernameError::TooLong(self.max_len));
+        }
+        if let Some(c) = name.chars().find(|c| !c.is_alphanumeric() && !self.allowed_symbols.contains(*c)) {
+            return Err(UsernameError::InvalidChar(c));
+        }
+        if let Some(reserved) = self.reserved_names.iter().find(|r| r.eq_ignore_ascii_case(name)) {
+            return Err(UsernameError::Reserved(reserved.clone()));
+        }
+        Ok(())
+    }
+}
+
+impl UsernameError {
+    pub fn message(&self) -> String {
+        match self {
+            UsernameError::TooShort(min) => format!("Username minimal {} karakter.", min),
+            UsernameError::TooLong(max) => format!("Username maksimal {} karakter.", max),
+            UsernameError::InvalidChar(c) => format!("Karakter '{}' tidak diizinkan.", c),
+            UsernameError::Reserved(name) => format!("Nama \"{}\" sudah dicadangkan.", name),
+        }
+    }
+}
+
+// Pengaturan privasi. Selain disimpan lokal, seluruh struct dikirim ke server (UpdatePrivacy)
+// karena server yang menyaring data sebelum diteruskan ke user lain.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct Privacy {
+    pub hide_last_seen: bool,
+    pub disable_read_receipts: bool,   // Berlaku dua arah: tidak mengirim dan tidak menampilkan tanda dibaca
+    pub block_dm_from_strangers: bool, // Pesan langsung hanya dari kontak; ditegakkan server
+    pub hide_typing: bool,             // Jangan kirim indikator sedang mengetik
+}
+
+// API admin untuk operator (lihat chat_admin). Dikirim lewat path /admin dengan header
+// `Authorization: Bearer <token>`, terpisah dari koneksi chat biasa. Setiap perintah dibalas satu AdminEvent.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum AdminCommand {
+    ListRooms,
+    // Tanpa `room`: semua user yang terhubung
+    ListUsers {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        room: Option<String>,
+    },
+    // Putus semua koneksi user ini dan tolak pesannya sampai Unban
+    Ban {
+        user_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+    Unban { user_id: String },
+    // Hapus riwayat room di server; dengan `user_id` hanya pesan dari user itu
+    PurgeMessages {
+        room: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        user_id: Option<String>,
+    },
+    SetSlowMode { room: String, interval_secs: u32 },
+    // Aturan yang harus disetujui user sebelum bisa mengirim pesan di room; None menghapusnya.
+    // Persetujuan yang sudah tercatat direset, sehingga aturan baru ditampilkan lagi saat bergabung.
+    SetRules {
+        room: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rules: Option<String>,
+    },
+    // Room anonim: anggota mendapat Pseudonym per sesi. Mematikannya mengeluarkan anggota dari room (RoomLeft)
+    // agar tidak ada yang masih mengira dirinya anonim.
+    SetAnonymous { room: String, anonymous: bool },
+    // Pesan sistem ke satu room; tanpa `room` ke room umum, yang diikuti semua koneksi
+    Announce {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        room: Option<String>,
+        text: String,
+    },
+    // Kedalaman antrean kirim tiap koneksi dan penghitung backpressure sejak server start
+    QueueStats,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum AdminEvent {
+    Rooms { rooms: Vec<RoomSummary> },
+    Users {
+        users: Vec<AdminUser>,
+        #[serde(default)]
+        banned: Vec<String>,
+    },
+    // Terdalam dulu
+    Queues {
+        queues: Vec<QueueDepth>,
+        max_frames: usize, // Batas sebelum koneksi diputus
+        presence_dropped: u64,
+        presence_coalesced: u64,
+        slow_disconnects: u64,
+    },
+    // Perintah berhasil; `detail` ringkasan untuk operator, mis. "12 pesan dihapus"
+    Done { detail: String },
+    Error { message: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RoomSummary {
+    pub name: String, // "" = room umum
+    pub members: usize, // Koneksi yang sedang mengikuti room
+    pub messages: usize, // Pesan di riwayat server
+    #[serde(default)]
+    pub slow_mode_secs: u32,
+    #[serde(default)]
+    pub password_protected: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct QueueDepth {
+    pub connection: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>, // Kosong sebelum koneksi login atau mengirim pesan
+    pub frames: usize,
+    pub bytes: usize,
+    pub peak: usize, // Kedalaman terbesar sejak koneksi dibuka
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AdminUser {
+    pub user_id: String,
+    pub name: String,
+    pub connections: usize,
+    pub rooms: Vec<String>,
+}
+
+// Format frame di atas WebSocket; JSON (frame teks) default, MessagePack (frame biner) jika dipilih server
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    #[default]
+    Json,
+    MessagePack,
+}
+
+impl Encoding {
+    pub fn label(self) -> &'static str {
+        match self {
+            Encoding::Json => "json/text",
+            Encoding::MessagePack => "msgpack/binary",
+        }
+    }
+}
+
+// Chunk upload sebagai frame biner: byte file dikirim apa adanya tanpa tambahan 33% dari base64.
+// Tata letak: "WCU1" | panjang upload_id (u8) | upload_id (UTF-8) | offset (u64 big-endian) | data.
+// Tidak tertukar dengan frame MessagePack, yang selalu berupa map sehingga tidak pernah diawali 'W'.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UploadChunkFrame {
+    pub upload_id: String,
+    pub offset: u64,
+    pub data: Vec<u8>,
+}
+
+impl UploadChunkFrame {
+    const MAGIC: &'static [u8; 4] = b"WCU1";
+
+    // None jika upload_id lebih dari 255 byte
+    pub fn encode(&self) -> Option<Vec<u8>> {
+        let id_len = u8::try_from(self.upload_id.len()).ok()?;
+        let mut bytes = Vec::with_capacity(Self::MAGIC.len() + 1 + self.upload_id.len() + 8 + self.data.len());
+        bytes.extend_from_slice(Self::MAGIC);
+        bytes.push(id_len);
+        bytes.extend_from_slice(self.upload_id.as_bytes());
+        bytes.extend_from_slice(&self.offset.to_be_bytes());
+        bytes.extend_from_slice(&self.data);
+        Some(bytes)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        let rest = bytes.strip_prefix(Self::MAGIC)?;
+        let (&id_len, rest) = rest.split_first()?;
+        let (id, rest) = rest.split_at_checked(usize::from(id_len))?;
+        let (offset, data) = rest.split_at_checked(8)?;
+        Some(Self {
+            upload_id: std::str::from_utf8(id).ok()?.to_string(),
+            offset: u64::from_be_bytes(offset.try_into().ok()?),
+            data: data.to_vec(),
+        })
+    }
+}
+
+// Satu catatan di notepad bersama room. Payload Custom dengan NOTES_NAMESPACE berisi JSON Vec<NoteEntry>:
+// dari klien berupa suntingan, dari server berupa suntingan user lain atau snapshot semua catatan room
+// (balasan payload kosong `[]`). Catatan yang dihapus tetap disimpan sebagai tombstone agar suntingan
+// lama yang datang terlambat tidak menghidupkannya lagi.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NoteEntry {
+    pub id: String,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deleted: bool,
+    pub created_at: f64, // Epoch millis saat catatan dibuat; urutan tampil
+    pub clock: u64,      // Jam Lamport: satu lebih besar dari clock tertinggi yang sudah dilihat penyunting
+    pub author: String,  // User id penyunting terakhir; diisi ulang server, pemecah seri clock yang sama
+}
+
+impl NoteEntry {
+    // Last-writer-wins: clock lebih besar menang, seri dipecah dengan author agar semua pihak memilih yang sama
+    pub fn supersedes(&self, other: &NoteEntry) -> bool {
+        (self.clock, &self.author) > (other.clock, &other.author)
+    }
+}
+
+// CRDT last-writer-wins map: id catatan -> versi terbaru. Urutan suntingan yang diterima tidak
+// mempengaruhi hasil akhir, sehingga klien dan server cukup saling meneruskan suntingan.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Notepad {
+    entries: Vec<NoteEntry>,
+}
+
+impl Notepad {
+    // true jika `entry` menggantikan versi yang ada atau catatan baru
+    pub fn merge(&mut self, entry: NoteEntry) -> bool {
+        match self.entries.iter_mut().find(|existing| existing.id == entry.id) {
+            Some(existing) if entry.supersedes(existing) => *existing = entry,
+            Some(_) => return false,
+            None => self.entries.push(entry),
+        }
+        true
+    }
+
+    pub fn get(&self, id: &str) -> Option<&NoteEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    // Clock untuk suntingan berikutnya
+    pub fn next_clock(&self) -> u64 {
+        self.entries.iter().map(|entry| entry.clock).max().unwrap_or(0) + 1
+    }
+
+    // Catatan yang belum dihapus, urut dari yang paling lama dibuat
+    pub fn visible(&self) -> Vec<&NoteEntry> {
+        let mut visible: Vec<&NoteEntry> = self.entries.iter().filter(|entry| !entry.deleted).collect();
+        visible.sort_by(|a, b| a.created_at.total_cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
+        visible
+    }
+
+    // Termasuk tombstone; untuk snapshot dan persistence
+    pub fn entries(&self) -> &[NoteEntry] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+// Timestamp sebagai epoch millis. Riwayat lama (file riwayat server, cache lokal klien) dan server lama
+// masih berisi string ISO 8601; string itu dikonversi, string lain dianggap kosong daripada membuat
+// seluruh pesan gagal dibaca.
+fn epoch_millis<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Millis(f64),
+        Text(String),
+    }
+    Ok(match Option::<Raw>::deserialize(deserializer)? {
+        None => None,
+        Some(Raw::Millis(ms)) => Some(ms),
+        Some(Raw::Text(text)) => parse_iso_millis(&text),
+    })
+}
+
+// "2024-05-01T08:30:00.123Z" -> epoch millis. Hanya UTC ("Z", format Date.toISOString()); pecahan detik opsional.
+pub fn parse_iso_millis(text: &str) -> Option<f64> {
+    let (date, time) = text.strip_suffix('Z')?.split_once('T')?;
+    let mut date = date.splitn(3, '-').map(digits);
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    let (hms, fraction) = time.split_once('.').unwrap_or((time, "0"));
+    let mut hms = hms.splitn(3, ':').map(digits);
+    let (hour, minute, second) = (hms.next()??, hms.next()??, hms.next()??);
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) || hour > 23 || minute > 59 || second > 60 || digits(fraction).is_none() {
+        return None;
+    }
+    let millis = (format!("0.{}", fraction).parse::<f64>().ok()? * 1000.0).floor();
+    // Hari sejak 1970-01-01 menurut kalender Gregorian (algoritme days_from_civil)
+    let shifted_year = if month <= 2 { year - 1 } else { year };
+    let era = shifted_year.div_euclid(400);
+    let year_of_era = shifted_year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    Some(((days * 86_400 + hour * 3_600 + minute * 60 + second) * 1_000) as f64 + millis)
+}
+
+// Hanya angka ASCII; `parse` sendiri menerima tanda "+"
+fn digits(part: &str) -> Option<i64> {
+    if part.is_empty() || part.len() > 9 || !part.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    part.parse().ok()
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Ukuran file yang mudah dibaca: 512 B, 12.3 KB, 4.5 MB
+pub fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..=1023 => format!("{} B", bytes),
+        1024..=1_048_575 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_epoch_and_fractional_seconds() {
+        assert_eq!(parse_iso_millis("1970-01-01T00:00:00Z"), Some(0.0));
+        assert_eq!(parse_iso_millis("2024-05-01T08:30:00.123Z"), Some(1_714_552_200_123.0));
+        assert_eq!(parse_iso_millis("2024-05-01T08:30:00.5Z"), Some(1_714_552_200_500.0));
+        assert_eq!(parse_iso_millis("2024-05-01T08:30:00.123456Z"), Some(1_714_552_200_123.0)); // Dibulatkan ke bawah
+    }
+
+    #[test]
+    fn handles_leap_years() {
+        assert_eq!(parse_iso_millis("2024-02-29T00:00:00Z"), Some(1_709_164_800_000.0));
+        assert_eq!(parse_iso_millis("2024-03-01T00:00:00Z"), Some(1_709_251_200_000.0));
+        assert_eq!(parse_iso_millis("2000-02-29T12:00:00Z"), Some(951_825_600_000.0));
+        assert_eq!(parse_iso_millis("2023-02-29T00:00:00Z"), None);
+        assert_eq!(parse_iso_millis("1900-02-29T00:00:00Z"), None); // Kelipatan 100, bukan 400
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        for text in [
+            "",
+            "2024-05-01",
+            "2024-05-01T08:30:00",       // Tanpa zona waktu
+            "2024-05-01T08:30:00+07:00", // Hanya UTC
+            "2024-13-01T00:00:00Z",
+            "2024-04-31T00:00:00Z",
+            "2024-05-01T24:00:00Z",
+            "2024-05-01T08:60:00Z",
+            "2024-05-01T08:30:00.Z",
+            "2024-05-01T08:30:00.12aZ",
+            "2024-+5-01T08:30:00Z",
+            "kemarin",
+        ] {
+            assert_eq!(parse_iso_millis(text), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn timestamp_accepts_millis_and_legacy_strings() {
+        let message: ChatMessage = serde_json::from_str(r#"{"user_id":"u","username":"a","text":"x","timestamp":"1970-01-01T00:00:01Z"}"#).unwrap();
+        assert_eq!(message.timestamp, Some(1_000.0));
+        let message: ChatMessage = serde_json::from_str(r#"{"user_id":"u","username":"a","text":"x","timestamp":"bukan tanggal"}"#).unwrap();
+        assert_eq!(message.timestamp, None);
+    }
+
+    #[test]
+    fn formats_sizes() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(12_595), "12.3 KB");
+        assert_eq!(format_size(4_718_592), "4.5 MB");
+    }
+
+    fn note(id: &str, text: &str, clock: u64, author: &str) -> NoteEntry {
+        NoteEntry { id: id.to_string(), text: text.to_string(), deleted: false, created_at: clock as f64, clock, author: author.to_string() }
+    }
+
+    #[test]
+    fn notepad_converges_in_any_merge_order() {
+        let edits = [note("n1", "a", 1, "ua"), note("n1", "b", 2, "ub"), note("n1", "c", 2, "ua"), note("n2", "x", 1, "ub")];
+        let mut forward = Notepad::default();
+        let mut backward = Notepad::default();
+        for entry in edits.iter().cloned() {
+            forward.merge(entry);
+        }
+        for entry in edits.iter().rev().cloned() {
+            backward.merge(entry);
+        }
+        assert_eq!(forward.get("n1").map(|entry| entry.text.as_str()), Some("b")); // Seri clock: author "ub" menang
+        assert_eq!(backward.get("n1"), forward.get("n1"));
+        assert_eq!(backward.get("n2"), forward.get("n2"));
+        assert_eq!(forward.next_clock(), 3);
+    }
+
+    #[test]
+    fn notepad_keeps_tombstones_over_late_edits() {
+        let mut notepad = Notepad::default();
+        assert!(notepad.merge(note("n1", "a", 1, "ua")));
+        assert!(notepad.merge(NoteEntry { deleted: true, ..note("n1", "", 2, "ua") }));
+        assert!(!notepad.merge(note("n1", "telat", 1, "ub"))); // Suntingan lama tidak menghidupkannya lagi
+        assert!(!notepad.merge(note("n1", "sama", 2, "ua")));
+        assert!(notepad.visible().is_empty());
+        assert_eq!(notepad.entries().len(), 1);
+    }
+
+    #[test]
+    fn notepad_lists_visible_notes_by_creation() {
+        let mut notepad = Notepad::default();
+        notepad.merge(note("n2", "kedua", 2, "ua"));
+        notepad.merge(note("n1", "pertama", 1, "ua"));
+        notepad.merge(NoteEntry { deleted: true, ..note("n3", "", 3, "ua") });
+        let texts: Vec<&str> = notepad.visible().iter().map(|entry| entry.text.as_str()).collect();
+        assert_eq!(texts, ["pertama", "kedua"]);
+    }
+}
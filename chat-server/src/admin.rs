@@ -0,0 +1,69 @@
+// chat-server/src/admin.rs
+// API admin untuk chat_admin: WebSocket di /admin, hanya aktif jika CHAT_ADMIN_TOKEN diset. Token dikirim
+// di header `Authorization: Bearer <token>` saat handshake, jadi koneksi tanpa token yang benar tidak
+// pernah di-upgrade. Setiap frame teks adalah satu AdminCommand dan dijawab tepat satu AdminEvent.
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use chat_protocol::{AdminCommand, AdminEvent};
+use futures_util::{SinkExt, StreamExt};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::{header, StatusCode};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+use crate::hub::Hub;
+
+pub const ADMIN_PATH: &str = "/admin";
+const ADMIN_TOKEN_ENV: &str = "CHAT_ADMIN_TOKEN";
+
+// None jika API admin tidak diaktifkan
+pub fn token_from_env() -> Option<String> {
+    let token = std::env::var(ADMIN_TOKEN_ENV).ok().filter(|token| !token.trim().is_empty())?;
+    log::info!("API admin aktif di {}", ADMIN_PATH);
+    Some(token.trim().to_string())
+}
+
+#[allow(clippy::result_large_err)] // Tipe error ditentukan callback handshake tungstenite
+pub fn check_request(request: &Request, response: Response, expected: Option<&str>) -> Result<Response, ErrorResponse> {
+    let Some(expected) = expected else {
+        return Err(reject(StatusCode::NOT_FOUND, "API admin tidak aktif"));
+    };
+    let given = request.headers().get(header::AUTHORIZATION).and_then(|value| value.to_str().ok()).and_then(|value| value.strip_prefix("Bearer "));
+    if given == Some(expected) {
+        Ok(response)
+    } else {
+        Err(reject(StatusCode::UNAUTHORIZED, "Token admin salah"))
+    }
+}
+
+pub async fn serve<S: AsyncRead + AsyncWrite + Unpin>(socket: WebSocketStream<S>, peer: SocketAddr, hub: Arc<Mutex<Hub>>) {
+    log::info!("Sesi admin dibuka dari {}", peer);
+    let (mut write, mut read) = socket.split();
+    while let Some(frame) = read.next().await {
+        let text = match frame {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) | Err(_) => break,
+            Ok(_) => continue,
+        };
+        let reply = match serde_json::from_str::<AdminCommand>(&text) {
+            Ok(command) => {
+                log::info!("Perintah admin dari {}: {:?}", peer, command);
+                hub.lock().unwrap().admin(command)
+            }
+            Err(e) => AdminEvent::Error { message: format!("Perintah tidak dikenal: {}", e) },
+        };
+        let Ok(json) = serde_json::to_string(&reply) else { continue };
+        if write.send(Message::Text(json)).await.is_err() {
+            break;
+        }
+    }
+    log::info!("Sesi admin dari {} ditutup", peer);
+}
+
+fn reject(status: StatusCode, reason: &str) -> ErrorResponse {
+    let mut error = ErrorResponse::new(Some(reason.to_string()));
+    *error.status_mut() = status;
+    error
+}
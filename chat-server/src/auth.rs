@@ -0,0 +1,52 @@
+// chat-server/src/auth.rs
+// Daftar token yang boleh login, dibaca dari environment CHAT_AUTH_TOKENS:
+//
+//   CHAT_AUTH_TOKENS="rahasia-1:u-alice:Alice,rahasia-2:u-bob"
+//
+// Setiap entri adalah `token:user_id[:nama]`. Tanpa variabel ini server terbuka seperti sebelumnya
+// dan identitas diambil dari pesan klien. Token diperlakukan sebagai string opaque (token sesi);
+// JWT bisa dipakai asalkan dikeluarkan dan didaftarkan di sini oleh backend yang sama.
+use std::collections::HashMap;
+
+const TOKENS_ENV: &str = "CHAT_AUTH_TOKENS";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Account {
+    pub user_id: String,
+    pub name: Option<String>,
+}
+
+pub struct TokenTable {
+    tokens: HashMap<String, Account>,
+}
+
+impl TokenTable {
+    // None jika login tidak diwajibkan
+    pub fn from_env() -> Option<Self> {
+        let raw = std::env::var(TOKENS_ENV).ok()?;
+        let tokens: HashMap<String, Account> = raw.split(',').filter_map(parse_entry).collect();
+        if tokens.is_empty() {
+            log::warn!("{} diset tetapi tidak berisi token yang valid; login dimatikan", TOKENS_ENV);
+            return None;
+        }
+        log::info!("Login diwajibkan: {} token terdaftar", tokens.len());
+        Some(Self { tokens })
+    }
+
+    pub fn check(&self, token: &str) -> Option<&Account> {
+        self.tokens.get(token)
+    }
+
+    #[cfg(test)]
+    pub fn from_entries(raw: &str) -> Self {
+        Self { tokens: raw.split(',').filter_map(parse_entry).collect() }
+    }
+}
+
+fn parse_entry(entry: &str) -> Option<(String, Account)> {
+    let mut parts = entry.trim().splitn(3, ':');
+    let token = parts.next().filter(|token| !token.is_empty())?;
+    let user_id = parts.next().filter(|user_id| !user_id.is_empty())?;
+    let name = parts.next().filter(|name| !name.is_empty()).map(str::to_string);
+    Some((token.to_string(), Account { user_id: user_id.to_string(), name }))
+}
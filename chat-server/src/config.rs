@@ -0,0 +1,335 @@
+// chat-server/src/config.rs
+// Konfigurasi server berlapis: nilai bawaan, lalu file TOML, lalu variabel environment, lalu alamat
+// di argumen pertama. File dipilih dengan `--config PATH` atau CHAT_CONFIG; tanpa keduanya
+// chat-server.toml di direktori kerja dipakai jika ada. Contoh lengkap ada di chat-server.example.toml.
+//
+// Semua kesalahan dilaporkan sekaligus saat start, sehingga server tidak pernah berjalan setengah
+// terkonfigurasi. Rahasia (CHAT_AUTH_TOKENS, CHAT_ADMIN_TOKEN) sengaja tetap hanya dari environment.
+use std::fmt;
+use std::net::ToSocketAddrs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use chat_protocol::UsernameRules;
+use serde::Deserialize;
+
+const DEFAULT_FILE: &str = "chat-server.toml";
+const DEFAULT_BIND: &str = "127.0.0.1:8080"; // Sama dengan URL cadangan klien dan chat_conformance
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub bind: String,
+    pub tls: Option<TlsConfig>,
+    pub persistence: PersistenceConfig,
+    pub retention: RetentionConfig,
+    pub rate_limit: RateLimitConfig,
+    pub send_queue: SendQueueConfig,
+    pub usernames: UsernameRules, // Pesan dengan display name yang melanggarnya ditolak
+    pub allowed_origins: Vec<String>, // Kosong = semua origin diterima
+    pub uploads: UploadConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    pub cert: PathBuf, // Rantai sertifikat PEM
+    pub key: PathBuf,  // Kunci privat PEM (PKCS#8, RSA, atau EC)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    #[default]
+    Memory, // Riwayat hilang saat server berhenti
+    File, // Riwayat ditulis ke file JSON Lines di `path`
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PersistenceConfig {
+    pub backend: Backend,
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetentionConfig {
+    pub history_per_room: usize, // Pesan terbaru per room yang disimpan untuk Resume dan RoomJoined
+    pub max_age_days: Option<u32>, // Pesan yang lebih tua dihapus; None = tanpa batas umur
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self { history_per_room: 500, max_age_days: None }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    pub messages_per_minute: u32, // Per koneksi; 0 = tanpa batas
+    pub burst: u32, // Pesan yang boleh dikirim beruntun sebelum batas per menit berlaku
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self { messages_per_minute: 0, burst: 10 }
+    }
+}
+
+// Batas antrean kirim per koneksi (lihat send_queue.rs)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SendQueueConfig {
+    pub max_frames: usize, // Koneksi diputus jika antreannya melewati ini
+    pub max_bytes: usize,
+    pub presence_after: usize, // Mulai dari kedalaman ini event presence digabung atau dibuang
+}
+
+impl Default for SendQueueConfig {
+    fn default() -> Self {
+        Self { max_frames: 512, max_bytes: 32 * 1024 * 1024, presence_after: 64 }
+    }
+}
+
+// Validasi lampiran (lihat uploads.rs)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UploadConfig {
+    pub allowed_types: Vec<String>, // Tipe MIME yang boleh diumumkan di UploadStart; kosong = upload dimatikan
+    // Program pemindai dan argumennya, mis. clamdscan; isi file dikirim lewat stdin. None = tanpa pemindaian
+    pub scan_command: Option<Vec<String>>,
+}
+
+impl Default for UploadConfig {
+    fn default() -> Self {
+        let allowed_types = [
+            "image/png", "image/jpeg", "image/gif", "image/webp", "video/mp4", "video/webm", "video/ogg", "audio/mp4", "audio/webm", "audio/ogg", "audio/mpeg", "application/pdf",
+        ];
+        Self { allowed_types: allowed_types.map(str::to_string).to_vec(), scan_command: None }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            bind: DEFAULT_BIND.to_string(),
+            tls: None,
+            persistence: PersistenceConfig::default(),
+            retention: RetentionConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+            send_queue: SendQueueConfig::default(),
+            usernames: UsernameRules::default(),
+            allowed_origins: Vec::new(),
+            uploads: UploadConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Args(String),
+    Read { path: PathBuf, source: std::io::Error },
+    Parse { path: PathBuf, message: String },
+    Env { name: &'static str, value: String, expected: &'static str },
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Args(message) => write!(f, "{}", message),
+            ConfigError::Read { path, source } => write!(f, "tidak bisa membaca {}: {}", path.display(), source),
+            ConfigError::Parse { path, message } => write!(f, "{} tidak valid: {}", path.display(), message.trim_end()),
+            ConfigError::Env { name, value, expected } => write!(f, "{}={:?} tidak valid: {}", name, value, expected),
+            ConfigError::Invalid(problems) => {
+                write!(f, "konfigurasi tidak valid:")?;
+                problems.iter().try_for_each(|problem| write!(f, "\n  - {}", problem))
+            }
+        }
+    }
+}
+
+impl Config {
+    pub fn load() -> Result<Self, ConfigError> {
+        let (file, bind) = parse_args(std::env::args().skip(1))?;
+        let file = file.or_else(|| env_var("CHAT_CONFIG").map(PathBuf::from));
+        let mut config = match file {
+            Some(path) => Self::from_file(&path)?,
+            None if Path::new(DEFAULT_FILE).is_file() => Self::from_file(Path::new(DEFAULT_FILE))?,
+            None => Self::default(),
+        };
+        config.apply_env()?;
+        if let Some(bind) = bind {
+            config.bind = bind;
+        }
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let text = std::fs::read_to_string(path).map_err(|source| ConfigError::Read { path: path.to_path_buf(), source })?;
+        let config = toml::from_str(&text).map_err(|e| ConfigError::Parse { path: path.to_path_buf(), message: e.to_string() })?;
+        log::info!("Konfigurasi dibaca dari {}", path.display());
+        Ok(config)
+    }
+
+    fn apply_env(&mut self) -> Result<(), ConfigError> {
+        if let Some(bind) = env_var("CHAT_BIND") {
+            self.bind = bind;
+        }
+        match (env_var("CHAT_TLS_CERT"), env_var("CHAT_TLS_KEY")) {
+            (Some(cert), Some(key)) => self.tls = Some(TlsConfig { cert: cert.into(), key: key.into() }),
+            (None, None) => {}
+            _ => return Err(ConfigError::Invalid(vec!["CHAT_TLS_CERT dan CHAT_TLS_KEY harus diset bersama".to_string()])),
+        }
+        if let Some(backend) = env_parse("CHAT_PERSISTENCE", "memory atau file")? {
+            self.persistence.backend = backend;
+        }
+        if let Some(path) = env_var("CHAT_PERSISTENCE_PATH") {
+            self.persistence.path = Some(path.into());
+        }
+        if let Some(limit) = env_parse("CHAT_HISTORY_PER_ROOM", "bilangan bulat positif")? {
+            self.retention.history_per_room = limit;
+        }
+        if let Some(days) = env_parse::<u32>("CHAT_RETENTION_DAYS", "jumlah hari, 0 = tanpa batas")? {
+            self.retention.max_age_days = (days > 0).then_some(days);
+        }
+        if let Some(per_minute) = env_parse("CHAT_RATE_LIMIT", "pesan per menit, 0 = tanpa batas")? {
+            self.rate_limit.messages_per_minute = per_minute;
+        }
+        if let Some(burst) = env_parse("CHAT_RATE_BURST", "bilangan bulat positif")? {
+            self.rate_limit.burst = burst;
+        }
+        if let Some(frames) = env_parse("CHAT_SEND_QUEUE_FRAMES", "jumlah frame")? {
+            self.send_queue.max_frames = frames;
+        }
+        if let Some(bytes) = env_parse("CHAT_SEND_QUEUE_BYTES", "jumlah byte")? {
+            self.send_queue.max_bytes = bytes;
+        }
+        if let Some(frames) = env_parse("CHAT_SEND_QUEUE_PRESENCE", "jumlah frame")? {
+            self.send_queue.presence_after = frames;
+        }
+        if let Some(origins) = env_var("CHAT_ALLOWED_ORIGINS") {
+            self.allowed_origins = origins.split(',').map(str::trim).filter(|origin| !origin.is_empty()).map(str::to_string).collect();
+        }
+        if let Some(types) = env_var("CHAT_UPLOAD_TYPES") {
+            self.uploads.allowed_types = types.split(',').map(str::trim).filter(|mime| !mime.is_empty()).map(str::to_string).collect();
+        }
+        if let Some(command) = env_var("CHAT_UPLOAD_SCAN") {
+            self.uploads.scan_command = Some(command.split_whitespace().map(str::to_string).collect());
+        }
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        let mut problems = Vec::new();
+        match self.bind.to_socket_addrs().map(|mut addrs| addrs.next()) {
+            Ok(Some(_)) => {}
+            Ok(None) => problems.push(format!("bind {:?} tidak menunjuk ke alamat mana pun", self.bind)),
+            Err(e) => problems.push(format!("bind {:?} bukan alamat host:port ({})", self.bind, e)),
+        }
+        if let Some(tls) = &self.tls {
+            for (field, path) in [("tls.cert", &tls.cert), ("tls.key", &tls.key)] {
+                if !path.is_file() {
+                    problems.push(format!("{} {} tidak ditemukan", field, path.display()));
+                }
+            }
+        }
+        match (self.persistence.backend, &self.persistence.path) {
+            (Backend::File, None) => problems.push("persistence.path wajib diisi untuk backend \"file\"".to_string()),
+            (Backend::File, Some(path)) => {
+                let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+                if !dir.is_dir() {
+                    problems.push(format!("direktori {} untuk persistence.path tidak ada", dir.display()));
+                }
+            }
+            (Backend::Memory, Some(_)) => problems.push("persistence.path hanya dipakai backend \"file\"".to_string()),
+            (Backend::Memory, None) => {}
+        }
+        if self.retention.history_per_room == 0 {
+            problems.push("retention.history_per_room minimal 1".to_string());
+        }
+        if self.retention.max_age_days == Some(0) {
+            problems.push("retention.max_age_days minimal 1; hapus kuncinya untuk tanpa batas umur".to_string());
+        }
+        if self.rate_limit.messages_per_minute > 0 && self.rate_limit.burst == 0 {
+            problems.push("rate_limit.burst minimal 1 jika rate_limit.messages_per_minute diisi".to_string());
+        }
+        let queue = &self.send_queue;
+        if queue.max_frames == 0 || queue.max_bytes == 0 {
+            problems.push("send_queue.max_frames dan send_queue.max_bytes minimal 1".to_string());
+        }
+        if queue.presence_after >= queue.max_frames {
+            problems.push(format!("send_queue.presence_after ({}) harus lebih kecil dari send_queue.max_frames ({})", queue.presence_after, queue.max_frames));
+        }
+        let usernames = &self.usernames;
+        if usernames.min_len == 0 || usernames.min_len > usernames.max_len {
+            problems.push(format!("usernames.min_len ({}) harus antara 1 dan usernames.max_len ({})", usernames.min_len, usernames.max_len));
+        }
+        if self.uploads.scan_command.as_ref().is_some_and(Vec::is_empty) {
+            problems.push("uploads.scan_command tidak boleh kosong; hapus kuncinya untuk mematikan pemindaian".to_string());
+        }
+        for origin in &self.allowed_origins {
+            if let Some(problem) = origin_problem(origin) {
+                problems.push(format!("allowed_origins {:?}: {}", origin, problem));
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(problems))
+        }
+    }
+}
+
+// Origin dibandingkan persis dengan header Origin browser: skema dan host, tanpa path
+fn origin_problem(origin: &str) -> Option<&'static str> {
+    let Some(host) = origin.strip_prefix("https://").or_else(|| origin.strip_prefix("http://")) else {
+        return Some("harus diawali http:// atau https://");
+    };
+    if host.is_empty() {
+        return Some("host kosong");
+    }
+    host.contains('/').then_some("tanpa path atau garis miring di akhir, mis. https://chat.example.com")
+}
+
+// `chat-server [ALAMAT] [--config PATH]`
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<(Option<PathBuf>, Option<String>), ConfigError> {
+    let (mut file, mut bind) = (None, None);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" => file = Some(args.next().ok_or_else(|| ConfigError::Args("--config butuh path file TOML".to_string()))?.into()),
+            "-h" | "--help" => return Err(ConfigError::Args(format!("pemakaian: chat-server [ALAMAT] [--config PATH]  (default {})", DEFAULT_BIND))),
+            _ if arg.starts_with('-') => return Err(ConfigError::Args(format!("argumen tidak dikenal: {}", arg))),
+            _ => bind = Some(arg),
+        }
+    }
+    Ok((file, bind))
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.trim().is_empty())
+}
+
+fn env_parse<T: FromStr>(name: &'static str, expected: &'static str) -> Result<Option<T>, ConfigError> {
+    let Some(value) = env_var(name) else { return Ok(None) };
+    match value.trim().parse() {
+        Ok(parsed) => Ok(Some(parsed)),
+        Err(_) => Err(ConfigError::Env { name, value, expected }),
+    }
+}
+
+// Nama yang sama dengan di file TOML
+impl FromStr for Backend {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, ()> {
+        match value {
+            "memory" => Ok(Backend::Memory),
+            "file" => Ok(Backend::File),
+            _ => Err(()),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+// chat-server/src/history_file.rs
+// Backend persistence "file": riwayat room sebagai JSON Lines, satu ChatMessage per baris. Pesan baru
+// ditambahkan di akhir file; file ditulis ulang saat start (setelah batas retensi diterapkan), setelah
+// purge dari admin, dan saat pesan kedaluwarsa dihapus. Pesan di room berkata sandi tidak pernah
+// ditulis, karena room itu sendiri tidak bertahan melewati restart. Notepad bersama tiap room disimpan
+// terpisah di file `.notes.json` di sebelahnya (mis. history.notes.json), ditulis ulang setiap ada suntingan.
+// Rahasia 2FA akun (two_factor.rs) disimpan dengan cara yang sama di `.2fa.json`; lindungi file itu seperti
+// CHAT_AUTH_TOKENS.
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use chat_protocol::{ChatMessage, Notepad};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::two_factor::TwoFactor;
+
+pub struct HistoryFile {
+    path: PathBuf,
+}
+
+impl HistoryFile {
+    pub fn new(path: &Path) -> Self {
+        Self { path: path.to_path_buf() }
+    }
+
+    // File yang belum ada berarti riwayat kosong; baris rusak dilewati dengan peringatan
+    pub fn load(&self) -> io::Result<Vec<ChatMessage>> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut messages = Vec::new();
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(message) => messages.push(message),
+                Err(e) => log::warn!("{} baris {} dilewati: {}", self.path.display(), index + 1, e),
+            }
+        }
+        Ok(messages)
+    }
+
+    pub fn append(&self, message: &ChatMessage) {
+        let result = OpenOptions::new().create(true).append(true).open(&self.path).and_then(|mut file| {
+            let line = serde_json::to_string(message).map_err(io::Error::other)?;
+            writeln!(file, "{}", line)
+        });
+        if let Err(e) = result {
+            log::error!("Gagal menulis riwayat ke {}: {}", self.path.display(), e);
+        }
+    }
+
+    fn notes_path(&self) -> PathBuf {
+        self.path.with_extension("notes.json")
+    }
+
+    // Room -> notepad; file yang belum ada berarti belum ada catatan
+    pub fn load_notes(&self) -> io::Result<HashMap<String, Notepad>> {
+        load_json(&self.notes_path())
+    }
+
+    pub fn save_notes(&self, notes: &HashMap<&str, &Notepad>) {
+        let path = self.notes_path();
+        if let Err(e) = save_json(&path, notes) {
+            log::error!("Gagal menulis catatan room ke {}: {}", path.display(), e);
+        }
+    }
+
+    fn two_factor_path(&self) -> PathBuf {
+        self.path.with_extension("2fa.json")
+    }
+
+    // User id akun -> 2FA yang aktif
+    pub fn load_two_factor(&self) -> io::Result<HashMap<String, TwoFactor>> {
+        load_json(&self.two_factor_path())
+    }
+
+    pub fn save_two_factor(&self, accounts: &HashMap<String, TwoFactor>) {
+        let path = self.two_factor_path();
+        if let Err(e) = save_json(&path, accounts) {
+            log::error!("Gagal menulis data 2FA ke {}: {}", path.display(), e);
+        }
+    }
+
+    // Tulis ke file sementara lalu ganti, agar crash di tengah jalan tidak memotong riwayat
+    pub fn rewrite<'a>(&self, messages: impl Iterator<Item = &'a ChatMessage>) {
+        let temp = self.path.with_extension("tmp");
+        let result = File::create(&temp)
+            .and_then(|file| {
+                let mut writer = BufWriter::new(file);
+                for message in messages {
+                    let line = serde_json::to_string(message).map_err(io::Error::other)?;
+                    writeln!(writer, "{}", line)?;
+                }
+                writer.flush()
+            })
+            .and_then(|()| fs::rename(&temp, &self.path));
+        if let Err(e) = result {
+            log::error!("Gagal menulis ulang riwayat {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+fn load_json<T: DeserializeOwned + Default>(path: &Path) -> io::Result<T> {
+    match fs::read_to_string(path) {
+        Ok(text) => serde_json::from_str(&text).map_err(io::Error::other),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+        Err(e) => Err(e),
+    }
+}
+
+// Seperti rewrite: file sementara lalu diganti
+fn save_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    let temp = path.with_extension("tmp");
+    serde_json::to_string(value).map_err(io::Error::other).and_then(|text| fs::write(&temp, text)).and_then(|()| fs::rename(&temp, path))
+}
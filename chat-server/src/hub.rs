@@ -0,0 +1,1818 @@
+// chat-server/src/hub.rs
+// Keadaan bersama semua koneksi: room beserta riwayatnya dan antrean kirim tiap koneksi.
+// Frame dibedakan dengan aturan yang sama seperti klien: frame bertag "type" adalah ClientCommand,
+// sisanya dianggap ChatMessage; frame biner hanya berisi chunk upload (uploads.rs).
+// Semua method sinkron agar kunci tidak pernah dipegang melewati await. Argon2 untuk kata sandi room dan
+// pemindaian upload terlalu lambat untuk dijalankan di bawah kunci; handle_frame dan handle_binary
+// mengembalikannya sebagai BlockingTask yang dijalankan session.rs di thread blocking, lalu hasilnya
+// diselesaikan lewat finish_task.
+// Jika login diwajibkan (lihat auth.rs), koneksi yang belum login hanya boleh mengirim Authenticate dan Ping.
+// Akun dengan 2FA (two_factor.rs) baru dianggap login setelah VerifyTwoFactor berhasil.
+// Batas riwayat, retensi, rate limit dan backend persistence diambil dari Config (lihat config.rs).
+// Setiap koneksi punya antrean kirim berbatas (send_queue.rs); event presence dikirim sebagai frame yang
+// boleh digabung atau dibuang jika koneksinya tertinggal.
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use base64::Engine as _;
+use chat_protocol::{
+    AdminCommand, AdminEvent, AdminUser, Capability, ChatMessage, ClientCommand, FrameTag, JoinFailure, NoteEntry, Notepad, PresenceEvent, QueueDepth, RoomSummary, RosterUser, ServerEvent, SessionInfo,
+    Operator, Privacy, Pseudonym, UploadChunkFrame, UploadRejection, UserStatus, UsernameRules, HISTORY_PAGE, MAX_CUSTOM_PAYLOAD_BYTES, MAX_HISTORY_PAGE, MAX_ROOM_NOTES, NOTES_NAMESPACE,
+};
+use serde::Serialize;
+
+use crate::auth::{Account, TokenTable};
+use crate::config::{Backend, Config, RateLimitConfig, RetentionConfig, SendQueueConfig};
+use crate::history_file::HistoryFile;
+use crate::pseudonyms;
+use crate::send_queue::{Frame, QueueMetrics, SendQueue};
+use crate::two_factor::{self, TwoFactor};
+use crate::uploads::{Attachment, Progress, Uploads};
+
+const GENERAL_ROOM: &str = ""; // Room umum; setiap koneksi otomatis ada di sini
+const ANNOUNCER: &str = "Admin"; // Nama pengirim pengumuman dari chat_admin
+const DELETED_NAME: &str = "Pengguna terhapus"; // Pengganti nama di pesan milik akun yang dihapus
+const PSEUDONYM_ROTATION: Duration = Duration::from_secs(60 * 60); // Nama samaran di room anonim diganti setelah ini
+// Kata sandi room yang salah dalam PASSWORD_FAILURE_WINDOW terakhir; setelah batasnya JoinFailed TooManyAttempts
+const PASSWORD_FAILURES_PER_CONNECTION: usize = 5;
+const PASSWORD_FAILURES_PER_ROOM: usize = 20; // Dari semua koneksi; menahan tebakan yang dibagi ke banyak koneksi
+const PASSWORD_FAILURE_WINDOW: Duration = Duration::from_secs(10 * 60);
+// Kode 2FA yang salah per akun dalam jendela yang sama, dari login maupun DisableTwoFactor
+const TWO_FACTOR_FAILURES_PER_ACCOUNT: usize = 5;
+
+pub type Outbox = Arc<SendQueue>;
+
+struct Client {
+    tx: Outbox,
+    rooms: HashSet<String>,
+    current: String, // Room terakhir yang diikuti; tujuan pesan tanpa field room dari klien lama
+    // Identitas dari pesan pertama yang dikirim koneksi ini; sebelum itu koneksi tidak muncul di Roster
+    user: Option<RosterUser>,
+    account: Option<Account>, // Akun dari token; id user di pesan koneksi ini selalu diganti dengan id akun
+    last_sent: HashMap<String, Instant>, // Pesan terakhir per room, untuk slow mode
+    rate: RateBucket,
+    operators: bool, // Klien mengumumkan Capability::Operators; tanpa itu field `operator` dibuang
+    password_failures: Failures,
+    session_id: String, // Acak; id koneksi tidak diberikan ke klien agar sesi lain tidak bisa ditebak
+    device: String, // Ringkasan User-Agent dari handshake
+    last_active: f64, // Epoch millis frame terakhir
+    challenge: Option<Account>, // Token sudah cocok, menunggu VerifyTwoFactor
+    // Dari UpdatePrivacy, per koneksi karena setiap perangkat menyimpan pengaturannya sendiri. Server ini tidak
+    // pernah mengirim last_seen dan belum mendukung DirectMessage, jadi hanya typing dan receipt yang disaring.
+    privacy: Privacy,
+    two_factor_setup: Option<TwoFactor>, // Dari StartTwoFactorSetup, menunggu ConfirmTwoFactorSetup
+}
+
+// Waktu kegagalan dalam PASSWORD_FAILURE_WINDOW terakhir
+#[derive(Default)]
+struct Failures(VecDeque<Instant>);
+
+impl Failures {
+    fn exhausted(&mut self, limit: usize) -> bool {
+        self.remaining(limit) == 0
+    }
+
+    fn remaining(&mut self, limit: usize) -> usize {
+        while self.0.front().is_some_and(|failed| failed.elapsed() >= PASSWORD_FAILURE_WINDOW) {
+            self.0.pop_front();
+        }
+        limit.saturating_sub(self.0.len())
+    }
+
+    fn record(&mut self) {
+        self.0.push_back(Instant::now());
+    }
+}
+
+// Token bucket per koneksi: `burst` pesan sekaligus, lalu terisi `messages_per_minute` per menit
+struct RateBucket {
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl RateBucket {
+    fn new(limit: &RateLimitConfig) -> Self {
+        Self { tokens: f64::from(limit.burst), refilled: Instant::now() }
+    }
+
+    fn try_take(&mut self, limit: &RateLimitConfig) -> bool {
+        if limit.messages_per_minute == 0 {
+            return true;
+        }
+        let now = Instant::now();
+        let refill = now.duration_since(self.refilled).as_secs_f64() * f64::from(limit.messages_per_minute) / 60.0;
+        self.tokens = (self.tokens + refill).min(f64::from(limit.burst));
+        self.refilled = now;
+        if self.tokens < 1.0 {
+            return false;
+        }
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+#[derive(Default)]
+struct Room {
+    history: VecDeque<ChatMessage>, // Terlama dulu
+    password: Option<String>, // Hash argon2 (format PHC, dengan salt acak); kata sandi aslinya tidak disimpan
+    slow_mode_secs: u32, // 0 = tidak aktif; diatur lewat API admin
+    read_marks: HashMap<String, String>, // User id -> id pesan terbaru yang sudah dirender klien user itu
+    notes: Notepad, // Notepad bersama; ikut disimpan backend file kecuali di room berkata sandi
+    ttl_secs: Option<u64>, // Room sementara: dihapus setelah tidak ada pesan selama ini
+    last_active: f64, // Epoch millis pesan terakhir (atau saat dibuat); hanya dipakai room sementara
+    // Pemilik (lihat Hub::owner) yang boleh mengatur room lewat ClientCommand; pembuat room otomatis
+    // termasuk. Room umum dan room yang dimuat dari riwayat tidak punya moderator, hanya API admin.
+    moderators: HashSet<String>,
+    rules: Option<String>, // Diatur lewat API admin; pesan ditolak sampai user menyetujuinya
+    rules_accepted: HashSet<String>, // Pemilik (lihat Hub::owner) yang sudah mengirim AcceptRules
+    password_failures: Failures,
+    // Diatur lewat API admin. Pesan, Typing dan Custom memakai nama samaran koneksi pengirim; presence, tanda
+    // dibaca, bisikan dan suntingan notepad tidak tersedia karena semuanya membawa identitas asli.
+    anonymous: bool,
+    pseudonyms: HashMap<u64, (Pseudonym, Instant)>, // Koneksi -> nama samaran saat ini dan kapan diberikan
+}
+
+impl Room {
+    fn expires_at(&self) -> Option<f64> {
+        self.ttl_secs.map(|ttl| self.last_active + ttl as f64 * 1000.0)
+    }
+
+    // Room berkata sandi dan room sementara tidak ditulis ke backend file
+    fn persisted(&self) -> bool {
+        self.password.is_none() && self.ttl_secs.is_none()
+    }
+}
+
+pub struct Hub {
+    clients: HashMap<u64, Client>,
+    rooms: HashMap<String, Room>,
+    next_client: u64,
+    next_message: u64,
+    auth: Option<TokenTable>, // None = server terbuka tanpa login
+    banned: HashMap<String, Option<String>>, // User id yang diblokir admin beserta alasannya
+    retention: RetentionConfig,
+    rate_limit: RateLimitConfig,
+    history_file: Option<HistoryFile>, // None = backend memory
+    uploads: Uploads,
+    send_queue: SendQueueConfig,
+    usernames: UsernameRules,
+    queue_metrics: Arc<QueueMetrics>,
+    sessions: HashMap<String, HashSet<u64>>, // Identitas (lihat Client::identity) -> koneksinya, untuk ListSessions
+    two_factor: HashMap<String, TwoFactor>, // User id akun -> 2FA yang sudah dikonfirmasi
+    two_factor_failures: HashMap<String, Failures>, // Per user id akun
+}
+
+impl Hub {
+    // Dengan backend file, riwayat dimuat lagi; room dari riwayat dibuat ulang tanpa kata sandi
+    pub fn new(config: &Config, auth: Option<TokenTable>) -> io::Result<Self> {
+        let mut rooms = HashMap::new();
+        rooms.insert(GENERAL_ROOM.to_string(), Room::default());
+        let history_file = match (config.persistence.backend, &config.persistence.path) {
+            (Backend::File, Some(path)) => Some(HistoryFile::new(path)),
+            _ => None,
+        };
+        let mut hub = Self {
+            clients: HashMap::new(),
+            rooms,
+            next_client: 0,
+            next_message: 0,
+            auth,
+            banned: HashMap::new(),
+            retention: config.retention.clone(),
+            rate_limit: config.rate_limit.clone(),
+            history_file,
+            uploads: Uploads::new(&config.uploads),
+            send_queue: config.send_queue.clone(),
+            usernames: config.usernames.clone(),
+            queue_metrics: Arc::default(),
+            sessions: HashMap::new(),
+            two_factor: HashMap::new(),
+            two_factor_failures: HashMap::new(),
+        };
+        let Some(file) = &hub.history_file else { return Ok(hub) };
+        hub.two_factor = file.load_two_factor()?;
+        let messages = file.load()?;
+        let notes = file.load_notes()?;
+        let loaded = messages.len();
+        for message in messages {
+            let number = message.id.as_deref().and_then(|id| id.strip_prefix("msg-")).and_then(|n| n.parse().ok());
+            hub.next_message = hub.next_message.max(number.unwrap_or(0));
+            let room = message.room.clone().unwrap_or_default();
+            let history = &mut hub.rooms.entry(room).or_default().history;
+            history.push_back(message);
+            if history.len() > hub.retention.history_per_room {
+                history.pop_front();
+            }
+        }
+        for (room, notes) in notes {
+            hub.rooms.entry(room).or_default().notes = notes;
+        }
+        hub.prune_expired();
+        hub.persist_all(); // Buang pesan yang melewati batas retensi dari file
+        log::info!("{} pesan dimuat dari riwayat tersimpan", loaded);
+        Ok(hub)
+    }
+
+    // `token` dari query `?token=` saat handshake; klien lain login lewat frame Authenticate.
+    // `device` ditampilkan di daftar sesi. Antrean yang dikembalikan dikosongkan task tulis milik session.
+    pub fn connect(&mut self, token: Option<String>, device: String) -> (u64, Outbox) {
+        self.next_client += 1;
+        let id = self.next_client;
+        let tx = Arc::new(SendQueue::new(self.send_queue.clone(), self.queue_metrics.clone()));
+        let rooms = HashSet::from([GENERAL_ROOM.to_string()]);
+        let rate = RateBucket::new(&self.rate_limit);
+        let session_id = format!("{:016x}", OsRng.next_u64());
+        let client = Client {
+            tx: tx.clone(),
+            rooms,
+            current: GENERAL_ROOM.to_string(),
+            user: None,
+            account: None,
+            last_sent: HashMap::new(),
+            rate,
+            operators: false,
+            password_failures: Failures::default(),
+            session_id,
+            device,
+            last_active: now_millis(),
+            challenge: None,
+            two_factor_setup: None,
+            privacy: Privacy::default(),
+        };
+        self.clients.insert(id, client);
+        match token {
+            Some(token) => self.authenticate(id, &token),
+            None if self.auth.is_some() => self.send(id, &ServerEvent::AuthRequired),
+            None => {}
+        }
+        (id, tx)
+    }
+
+    pub fn disconnect(&mut self, id: u64) {
+        self.uploads.forget(&connection_owner(id)); // Tidak bisa dilanjutkan dari koneksi lain
+        let Some(client) = self.clients.remove(&id) else { return };
+        client.tx.close();
+        self.index_session(id, client.identity(), None);
+        for room in &client.rooms {
+            self.announce_left(&client, room);
+            if let Some(left) = self.rooms.get_mut(room) {
+                left.pseudonyms.remove(&id);
+            }
+        }
+    }
+
+    // Frame bertag "type" adalah ClientCommand, frame tanpa tag adalah ChatMessage. Membuat atau masuk room
+    // berkata sandi dan chunk terakhir upload yang perlu dipindai mengembalikan BlockingTask yang harus
+    // dijalankan pemanggil di luar kunci.
+    pub fn handle_frame(&mut self, id: u64, text: &str) -> Option<BlockingTask> {
+        let tagged = match serde_json::from_str::<FrameTag>(text) {
+            Ok(FrameTag { kind }) => kind.is_some(),
+            Err(e) => {
+                log::warn!("Frame bukan objek JSON dari koneksi {}: {}", id, e);
+                return None;
+            }
+        };
+        if let Some(client) = self.clients.get_mut(&id) {
+            client.last_active = now_millis();
+        }
+        if tagged {
+            match serde_json::from_str::<ClientCommand>(text) {
+                Ok(ClientCommand::Unknown) => log::debug!("Perintah tidak dikenal dari koneksi {} diabaikan: {}", id, text),
+                Ok(command) if !self.is_authorized(id) && !matches!(command, ClientCommand::Authenticate { .. } | ClientCommand::VerifyTwoFactor { .. } | ClientCommand::Ping { .. }) => {
+                    self.send(id, &ServerEvent::AuthRequired);
+                }
+                Ok(ClientCommand::CreateRoom { room, password, ttl_secs, .. }) => return self.create_room(id, room, password, ttl_secs),
+                Ok(ClientCommand::JoinRoom { room, password }) => return self.join_room(id, room, password),
+                Ok(ClientCommand::UploadChunk { upload_id, offset, data }) => match base64::engine::general_purpose::STANDARD.decode(data) {
+                    Ok(bytes) => return self.write_chunk(id, upload_id, offset, &bytes),
+                    Err(e) => log::warn!("Chunk upload {} dari koneksi {} bukan base64: {}", upload_id, id, e),
+                },
+                Ok(command) => self.handle_command(id, command),
+                Err(e) => log::warn!("Perintah rusak dari koneksi {}: {}", id, e),
+            }
+            return None;
+        }
+        match serde_json::from_str::<ChatMessage>(text) {
+            Ok(_) if !self.is_authorized(id) => self.send(id, &ServerEvent::AuthRequired),
+            Ok(message) => self.handle_message(id, message),
+            Err(e) => log::warn!("Pesan rusak dari koneksi {}: {}", id, e),
+        }
+        None
+    }
+
+    // Hasil BlockingTask. Keadaan Hub bisa berubah selama Argon2 berjalan (koneksi putus, room dibuat koneksi
+    // lain atau kedaluwarsa), jadi semuanya diperiksa ulang di sini.
+    pub fn finish_task(&mut self, id: u64, outcome: BlockingOutcome) {
+        if !self.clients.contains_key(&id) {
+            return;
+        }
+        match outcome {
+            BlockingOutcome::Created { room, .. } if self.rooms.contains_key(&room) => self.send(id, &ServerEvent::JoinFailed { room, reason: JoinFailure::RoomExists }),
+            BlockingOutcome::Created { room, hash: None, .. } => self.send(id, &ServerEvent::JoinFailed { room, reason: JoinFailure::Unknown }),
+            BlockingOutcome::Created { room, hash, ttl_secs } => self.insert_room(id, room, hash, ttl_secs),
+            BlockingOutcome::Joined { room, hash, .. } if self.rooms.get(&room).and_then(|target| target.password.as_ref()) != Some(&hash) => {
+                self.send(id, &ServerEvent::JoinFailed { room, reason: JoinFailure::RoomNotFound });
+            }
+            BlockingOutcome::Joined { room, matches: true, .. } => self.join(id, room),
+            BlockingOutcome::Joined { room, matches: false, .. } => {
+                if let Some(client) = self.clients.get_mut(&id) {
+                    client.password_failures.record();
+                }
+                if let Some(target) = self.rooms.get_mut(&room) {
+                    target.password_failures.record();
+                }
+                log::info!("Kata sandi salah untuk {} dari koneksi {}", room_label(&room), id);
+                self.send(id, &ServerEvent::JoinFailed { room, reason: JoinFailure::WrongPassword });
+            }
+            BlockingOutcome::Scanned { upload_id, url: Ok(url) } => self.send(id, &ServerEvent::UploadComplete { upload_id, url }),
+            BlockingOutcome::Scanned { upload_id, url: Err(reason) } => {
+                if let UploadRejection::Infected { signature } = &reason {
+                    log::warn!("Upload {} dari koneksi {} terdeteksi berbahaya: {}", upload_id, id, signature.as_deref().unwrap_or("tanpa nama"));
+                }
+                self.send(id, &ServerEvent::UploadFailed { upload_id, reason });
+            }
+        }
+    }
+
+    // Frame biner dari klien hanya dipakai untuk chunk upload
+    pub fn handle_binary(&mut self, id: u64, bytes: &[u8]) -> Option<BlockingTask> {
+        if !self.is_authorized(id) {
+            self.send(id, &ServerEvent::AuthRequired);
+            return None;
+        }
+        match UploadChunkFrame::decode(bytes) {
+            Some(chunk) => self.write_chunk(id, chunk.upload_id, chunk.offset, &chunk.data),
+            None => {
+                log::warn!("Frame biner dari koneksi {} bukan chunk upload ({} byte)", id, bytes.len());
+                None
+            }
+        }
+    }
+
+    fn handle_command(&mut self, id: u64, command: ClientCommand) {
+        match command {
+            ClientCommand::UploadStart { upload_id, mime, size, .. } => match self.uploads.start(&self.owner(id), upload_id.clone(), mime, size) {
+                Ok(offset) => self.send(id, &ServerEvent::UploadOffset { upload_id, offset, binary: true }),
+                Err(reason) => self.send(id, &ServerEvent::UploadFailed { upload_id, reason }),
+            },
+            ClientCommand::UploadResume { upload_id } => match self.uploads.offset(&self.owner(id), &upload_id) {
+                Some(offset) => self.send(id, &ServerEvent::UploadOffset { upload_id, offset, binary: true }),
+                None => self.send(id, &ServerEvent::UploadFailed { upload_id, reason: UploadRejection::Unknown }),
+            },
+            ClientCommand::UploadCancel { upload_id } => self.uploads.cancel(&self.owner(id), &upload_id),
+            ClientCommand::TimeSync { client_time } => {
+                self.send(id, &ServerEvent::TimeSync { client_time, server_time: now_millis() });
+            }
+            ClientCommand::Ping { nonce } => self.send(id, &ServerEvent::Pong { nonce }),
+            ClientCommand::Authenticate { token } => self.authenticate(id, &token),
+            ClientCommand::VerifyTwoFactor { code, recovery_code } => self.verify_two_factor(id, code, recovery_code),
+            ClientCommand::StartTwoFactorSetup => {
+                let Some(user_id) = self.account_id(id) else {
+                    log::debug!("StartTwoFactorSetup dari koneksi {} tanpa akun diabaikan", id);
+                    return;
+                };
+                if self.two_factor.contains_key(&user_id) {
+                    self.send(id, &ServerEvent::TwoFactorEnabled);
+                    return;
+                }
+                let enrollment = two_factor::enroll(&user_id);
+                let Some(client) = self.clients.get_mut(&id) else { return };
+                client.two_factor_setup = Some(enrollment.pending);
+                self.send(id, &ServerEvent::TwoFactorSetup { secret: enrollment.secret, otpauth_url: enrollment.otpauth_url, recovery_codes: enrollment.recovery_codes });
+            }
+            // Rahasia yang belum dikonfirmasi tetap di koneksi ini, jadi kode salah boleh dicoba lagi
+            ClientCommand::ConfirmTwoFactorSetup { code } => {
+                let Some(user_id) = self.account_id(id) else { return };
+                let Some(client) = self.clients.get_mut(&id) else { return };
+                let Some(pending) = client.two_factor_setup.as_mut() else { return };
+                if !pending.verify(Some(&code), None) {
+                    self.send(id, &ServerEvent::TwoFactorRejected { attempts_left: None });
+                    return;
+                }
+                let Some(confirmed) = client.two_factor_setup.take() else { return };
+                log::info!("2FA diaktifkan untuk akun {}", user_id);
+                self.two_factor.insert(user_id.clone(), confirmed);
+                self.save_two_factor();
+                self.send_to_account(&user_id, &ServerEvent::TwoFactorEnabled);
+            }
+            ClientCommand::DisableTwoFactor { code, recovery_code } => {
+                let Some(user_id) = self.account_id(id) else { return };
+                if !self.two_factor.contains_key(&user_id) {
+                    self.send(id, &ServerEvent::TwoFactorDisabled);
+                    return;
+                }
+                if self.check_two_factor(id, &user_id, code, recovery_code) {
+                    log::info!("2FA dimatikan untuk akun {}", user_id);
+                    self.two_factor.remove(&user_id);
+                    self.save_two_factor();
+                    self.send_to_account(&user_id, &ServerEvent::TwoFactorDisabled);
+                }
+            }
+            ClientCommand::Hello { capabilities } => {
+                // Server ini belum mendukung PresenceDigest dan MessagePack
+                let accepted: Vec<Capability> = capabilities.into_iter().filter(|capability| *capability == Capability::Operators).collect();
+                let Some(client) = self.clients.get_mut(&id) else { return };
+                client.operators = accepted.contains(&Capability::Operators);
+                self.send(id, &ServerEvent::CapabilitiesAccepted { capabilities: accepted });
+            }
+            ClientCommand::Typing { room, operator } => {
+                let Some(client) = self.clients.get(&id) else { return };
+                let room = room.unwrap_or_else(|| GENERAL_ROOM.to_string());
+                let name = client.user.as_ref().map(|user| user.name.clone()).or_else(|| client.account.as_ref().and_then(|account| account.name.clone()));
+                let (Some(mut user_id), Some(mut name)) = (client.identity().map(str::to_string), name) else { return }; // Belum dikenal di Roster
+                if !client.rooms.contains(&room) || client.privacy.hide_typing {
+                    return;
+                }
+                let mut operator = operator.filter(|_| client.operators).and_then(|operator| Operator::from_name(&operator.name));
+                if let Some(anonymous) = self.anonymous_in(id, &room) {
+                    let Some(pseudonym) = anonymous else { return };
+                    (user_id, name, operator) = (pseudonym.id, pseudonym.name, None);
+                }
+                let key = format!("typing:{}:{}:{}", room, user_id, operator.as_ref().map_or("", |operator| operator.name.as_str()));
+                self.broadcast_transient(id, &room, key, &ServerEvent::Typing { room: room.clone(), user_id, name, operator });
+            }
+            ClientCommand::MarkRead { room, message_id } => {
+                let Some(client) = self.clients.get(&id) else { return };
+                let room = room.unwrap_or_else(|| GENERAL_ROOM.to_string());
+                let Some(user_id) = client.identity().map(str::to_string) else { return };
+                let Some(target) = self.rooms.get_mut(&room).filter(|target| client.rooms.contains(&room) && !client.privacy.disable_read_receipts && !target.anonymous) else { return };
+                let position = |message_id: &str| target.history.iter().position(|message| message.id.as_deref() == Some(message_id));
+                let Some(read) = position(&message_id) else { return }; // Id asing atau sudah terpangkas retensi
+                // Tanda dibaca hanya maju; tab lain yang tertinggal tidak menariknya mundur
+                if target.read_marks.get(&user_id).and_then(|previous| position(previous)).is_some_and(|previous| previous >= read) {
+                    return;
+                }
+                target.read_marks.insert(user_id.clone(), message_id.clone());
+                let key = format!("read:{}:{}", room, user_id);
+                self.broadcast_transient(id, &room, key, &ServerEvent::ReadReceipt { room: room.clone(), user_id, message_id });
+            }
+            ClientCommand::RequestExport => self.export(id),
+            ClientCommand::DeleteAccount => self.delete_account(id),
+            // Mematikan tanda dibaca juga menarik tanda yang sudah tercatat, agar anggota yang masuk kemudian tidak melihatnya
+            ClientCommand::UpdatePrivacy(privacy) => {
+                let Some(client) = self.clients.get_mut(&id) else { return };
+                if privacy.disable_read_receipts {
+                    if let Some(user_id) = client.identity() {
+                        for room in self.rooms.values_mut() {
+                            room.read_marks.remove(user_id);
+                        }
+                    }
+                }
+                client.privacy = privacy;
+            }
+            ClientCommand::Custom { room, namespace, payload, key } => {
+                let Some(client) = self.clients.get(&id) else { return };
+                let room = room.unwrap_or_else(|| GENERAL_ROOM.to_string());
+                if !client.rooms.contains(&room) || namespace.is_empty() {
+                    return;
+                }
+                let user_id = client.identity().map(str::to_string);
+                if payload.len() > MAX_CUSTOM_PAYLOAD_BYTES {
+                    log::warn!("Payload Custom {} dari koneksi {} terlalu besar ({} byte)", namespace, id, payload.len());
+                    return;
+                }
+                if namespace == NOTES_NAMESPACE {
+                    return self.edit_notes(id, room, user_id, &payload);
+                }
+                let Some(mut user_id) = user_id else { return }; // Belum dikenal di Roster
+                if let Some(anonymous) = self.anonymous_in(id, &room) {
+                    let Some(pseudonym) = anonymous else { return };
+                    user_id = pseudonym.id;
+                }
+                let event = ServerEvent::Custom { room: room.clone(), user_id: user_id.clone(), namespace: namespace.clone(), payload };
+                match key {
+                    Some(key) => self.broadcast_transient(id, &room, format!("custom:{}:{}:{}:{}", room, user_id, namespace, key), &event),
+                    None => self.broadcast_others(id, &room, &event),
+                }
+            }
+            ClientCommand::Resume { since, limit } => {
+                let Some(client) = self.clients.get(&id) else { return };
+                let mut missed: Vec<ChatMessage> = client
+                    .rooms
+                    .iter()
+                    .filter_map(|room| self.rooms.get(room))
+                    .flat_map(|room| room.history.iter())
+                    .filter(|message| since.is_none() || message.timestamp > since)
+                    .cloned()
+                    .collect();
+                missed.sort_by(|a, b| a.timestamp.unwrap_or_default().total_cmp(&b.timestamp.unwrap_or_default()));
+                let total_missed = missed.len() as u64;
+                let messages = missed.split_off(missed.len().saturating_sub(limit));
+                self.send(id, &ServerEvent::HistoryReplay { messages, total_missed });
+            }
+            ClientCommand::HistoryRequest { room, before_id, limit } => {
+                let name = room.clone().unwrap_or_else(|| GENERAL_ROOM.to_string());
+                let joined = self.clients.get(&id).is_some_and(|client| client.rooms.contains(&name));
+                let (messages, has_more) = match self.rooms.get(&name).filter(|_| joined) {
+                    Some(target) => history_page(&target.history, before_id.as_deref(), limit.min(MAX_HISTORY_PAGE)),
+                    None => (Vec::new(), false),
+                };
+                self.send(id, &ServerEvent::HistoryResponse { room, before_id, messages, has_more });
+            }
+            // Room umum tidak bisa ditinggalkan; room yang tidak diikuti tidak diumumkan dan tidak dibalas
+            ClientCommand::LeaveRoom { room } => {
+                if room == GENERAL_ROOM {
+                    return log::debug!("Koneksi {} mencoba keluar dari room umum", id);
+                }
+                let Some(client) = self.clients.get_mut(&id) else { return };
+                if !client.rooms.remove(&room) {
+                    return;
+                }
+                if client.current == room {
+                    client.current = GENERAL_ROOM.to_string();
+                }
+                if let Some(client) = self.clients.get(&id) {
+                    self.announce_left(client, &room);
+                }
+                if let Some(left) = self.rooms.get_mut(&room) {
+                    left.pseudonyms.remove(&id);
+                }
+                self.send(id, &ServerEvent::RoomLeft { room });
+            }
+            ClientCommand::SetSlowMode { room, interval_secs } => {
+                if !self.is_moderator(id, &room) {
+                    return log::info!("Koneksi {} bukan moderator {}; SetSlowMode diabaikan", id, room_label(&room));
+                }
+                self.set_slow_mode(&room, interval_secs);
+            }
+            ClientCommand::AcceptRules { room } => {
+                let owner = self.owner(id);
+                let joined = self.clients.get(&id).is_some_and(|client| client.rooms.contains(&room));
+                if let Some(target) = self.rooms.get_mut(&room).filter(|target| joined && target.rules.is_some()) {
+                    target.rules_accepted.insert(owner);
+                }
+            }
+            ClientCommand::ListSessions => {
+                let Some(identity) = self.clients.get(&id).and_then(Client::identity) else { return };
+                let mut sessions: Vec<SessionInfo> = self.sessions.get(identity).into_iter().flatten().filter_map(|session| {
+                    let client = self.clients.get(session)?;
+                    Some(SessionInfo { session_id: client.session_id.clone(), device: client.device.clone(), location: None, last_active: client.last_active, current: *session == id })
+                }).collect();
+                sessions.sort_by(|a, b| b.current.cmp(&a.current).then(b.last_active.total_cmp(&a.last_active)));
+                self.send(id, &ServerEvent::Sessions { sessions });
+            }
+            // Hanya sesi dengan identitas yang sama; klien memuat ulang daftar sesudahnya
+            ClientCommand::RevokeSession { session_id } => {
+                let Some(identity) = self.clients.get(&id).and_then(Client::identity) else { return };
+                let target = self.sessions.get(identity).into_iter().flatten().copied().find(|session| self.clients.get(session).is_some_and(|client| client.session_id == session_id));
+                if let Some(target) = target {
+                    log::info!("Koneksi {} mencabut sesi koneksi {}", id, target);
+                    self.kick(target, None);
+                }
+            }
+            ClientCommand::SyncRoster { room } => self.send(id, &self.roster(room)),
+            ClientCommand::SetStatus { status } => {
+                // Koneksi yang belum teridentifikasi belum terlihat siapa pun
+                let Some(user_id) = self.clients.get(&id).and_then(|client| client.user.as_ref()).map(|user| user.user_id.clone()) else { return };
+                // Status berlaku untuk semua tab milik user yang sama
+                for user in self.clients.values_mut().filter_map(|client| client.user.as_mut()).filter(|user| user.user_id == user_id) {
+                    user.status = status;
+                }
+                self.broadcast_presence(None, &PresenceEvent::StatusChanged { user_id, status });
+            }
+            ClientCommand::SetCustomStatus { status } => {
+                let Some(user_id) = self.clients.get(&id).and_then(|client| client.user.as_ref()).map(|user| user.user_id.clone()) else { return };
+                for user in self.clients.values_mut().filter_map(|client| client.user.as_mut()).filter(|user| user.user_id == user_id) {
+                    user.custom_status = status.clone();
+                }
+                self.broadcast_presence(None, &PresenceEvent::CustomStatusChanged { user_id, status });
+            }
+            other => log::debug!("Perintah belum didukung chat-server: {:?}", other),
+        }
+    }
+
+    // Kata sandi di-hash lewat BlockingTask; room tanpa kata sandi langsung dibuat
+    fn create_room(&mut self, id: u64, room: String, password: Option<String>, ttl_secs: Option<u64>) -> Option<BlockingTask> {
+        if self.rooms.contains_key(&room) {
+            self.send(id, &ServerEvent::JoinFailed { room, reason: JoinFailure::RoomExists });
+            return None;
+        }
+        match password {
+            Some(password) => Some(BlockingTask::Create { room, password, ttl_secs }),
+            None => {
+                self.insert_room(id, room, None, ttl_secs);
+                None
+            }
+        }
+    }
+
+    fn insert_room(&mut self, id: u64, room: String, password: Option<String>, ttl_secs: Option<u64>) {
+        let ttl_secs = ttl_secs.filter(|ttl| *ttl > 0);
+        let moderators = HashSet::from([self.owner(id)]);
+        self.rooms.insert(room.clone(), Room { password, ttl_secs, last_active: now_millis(), moderators, ..Room::default() });
+        self.join(id, room);
+    }
+
+    // Batas percobaan diperiksa sebelum Argon2 dijalankan, sehingga tebakan yang ditolak tidak memakan CPU
+    fn join_room(&mut self, id: u64, room: String, password: Option<String>) -> Option<BlockingTask> {
+        let Some(target) = self.rooms.get_mut(&room) else {
+            self.send(id, &ServerEvent::JoinFailed { room, reason: JoinFailure::RoomNotFound });
+            return None;
+        };
+        let Some(hash) = target.password.clone() else {
+            self.join(id, room);
+            return None;
+        };
+        let Some(password) = password else {
+            self.send(id, &ServerEvent::JoinFailed { room, reason: JoinFailure::PasswordRequired });
+            return None;
+        };
+        let room_exhausted = target.password_failures.exhausted(PASSWORD_FAILURES_PER_ROOM);
+        let connection_exhausted = self.clients.get_mut(&id).is_some_and(|client| client.password_failures.exhausted(PASSWORD_FAILURES_PER_CONNECTION));
+        if room_exhausted || connection_exhausted {
+            self.send(id, &ServerEvent::JoinFailed { room, reason: JoinFailure::TooManyAttempts });
+            return None;
+        }
+        Some(BlockingTask::Join { room, password, hash })
+    }
+
+    fn handle_message(&mut self, id: u64, mut message: ChatMessage) {
+        if let Some(account) = self.clients.get(&id).and_then(|client| client.account.as_ref()) {
+            message.user_id = Some(account.user_id.clone()); // Klien yang login tidak bisa mengaku sebagai user lain
+        }
+        if let Some(reason) = message.user_id.as_ref().and_then(|user_id| self.banned.get(user_id)).cloned() {
+            self.kick(id, reason);
+            return;
+        }
+        // Nama diperiksa di setiap pesan, karena pesan pertama dan ganti nama sama-sama lewat sini
+        if let Err(error) = self.usernames.validate(&message.username) {
+            log::info!("Koneksi {} memakai nama {:?} yang tidak diizinkan; pesan ditolak", id, message.username);
+            if let Some(client_id) = message.client_id {
+                self.send(id, &ServerEvent::MessageRejected { client_id, reason: error.message() });
+            }
+            return;
+        }
+        self.identify(id, &message);
+        let Some(client) = self.clients.get(&id) else { return };
+        message.operator = message.operator.filter(|_| client.operators).and_then(|operator| Operator::from_name(&operator.name)).map(Box::new);
+        let room = message.room.clone().unwrap_or_else(|| client.current.clone());
+        if !client.rooms.contains(&room) {
+            log::warn!("Koneksi {} mengirim pesan ke room {:?} yang tidak diikutinya", id, room);
+            if let Some(client_id) = message.client_id {
+                self.send(id, &ServerEvent::MessageRejected { client_id, reason: "Kamu tidak bergabung di room ini.".to_string() });
+            }
+            return;
+        }
+        if self.rooms.get(&room).is_some_and(|target| target.rules.is_some() && !target.rules_accepted.contains(&self.owner(id))) {
+            if let Some(client_id) = message.client_id {
+                self.send(id, &ServerEvent::MessageRejected { client_id, reason: "Setujui aturan room ini sebelum mengirim pesan.".to_string() });
+            }
+            return;
+        }
+        if let Some(anonymous) = self.anonymous_in(id, &room) {
+            // Sebelum pemeriksaan bisikan, yang bisa membocorkan apakah user id tertentu ada di room ini
+            let Some(pseudonym) = anonymous.filter(|_| message.whisper_to.is_none()) else {
+                if let Some(client_id) = message.client_id {
+                    self.send(id, &ServerEvent::MessageRejected { client_id, reason: "Bisikan tidak tersedia di room anonim.".to_string() });
+                }
+                return;
+            };
+            message.user_id = Some(pseudonym.id);
+            message.username = pseudonym.name;
+            message.operator = None;
+        }
+        if let Some(target) = message.whisper_to.as_deref() {
+            let present = self.clients.values().any(|other| other.rooms.contains(&room) && other.identity() == Some(target));
+            if !present {
+                if let Some(client_id) = message.client_id {
+                    self.send(id, &ServerEvent::MessageRejected { client_id, reason: "Penerima bisikan tidak ada di room ini.".to_string() });
+                }
+                return;
+            }
+        }
+        if !self.clients.get_mut(&id).is_some_and(|client| client.rate.try_take(&self.rate_limit)) {
+            log::info!("Koneksi {} melewati rate limit; pesan dibuang", id);
+            if let Some(client_id) = message.client_id {
+                self.send(id, &ServerEvent::MessageRejected { client_id, reason: "Terlalu banyak pesan. Tunggu sebentar lalu coba lagi.".to_string() });
+            }
+            return;
+        }
+        let Some(client) = self.clients.get(&id) else { return };
+        let slow_mode_secs = self.rooms.get(&room).map_or(0, |joined| joined.slow_mode_secs);
+        let elapsed = client.last_sent.get(&room).map(|sent| sent.elapsed().as_secs());
+        if let Some(elapsed) = elapsed.filter(|elapsed| *elapsed < u64::from(slow_mode_secs)) {
+            let retry_after_secs = slow_mode_secs - elapsed as u32;
+            self.send(id, &ServerEvent::SlowModeRejected { room, retry_after_secs, client_id: message.client_id });
+            return;
+        }
+        if let Some(client) = self.clients.get_mut(&id) {
+            client.last_sent.insert(room.clone(), Instant::now());
+        }
+        message.system = false; // Hanya server yang boleh mengirim pesan sistem
+        if message.whisper_to.is_some() {
+            self.whisper(id, room, message);
+        } else {
+            self.publish(room, message);
+        }
+    }
+
+    // Upload yang lengkap langsung dijawab UploadComplete, atau dikembalikan sebagai BlockingTask::Scan
+    fn write_chunk(&mut self, id: u64, upload_id: String, offset: u64, bytes: &[u8]) -> Option<BlockingTask> {
+        let event = match self.uploads.write(&self.owner(id), &upload_id, offset, bytes) {
+            Some(Ok(Progress::Offset(offset))) => ServerEvent::UploadOffset { upload_id, offset, binary: true },
+            Some(Ok(Progress::Complete(attachment))) => match self.uploads.scan_command() {
+                Some(command) => return Some(BlockingTask::Scan { upload_id, attachment, command: command.to_vec() }),
+                None => ServerEvent::UploadComplete { upload_id, url: attachment.into_url() },
+            },
+            Some(Err(reason)) => ServerEvent::UploadFailed { upload_id, reason },
+            None => ServerEvent::UploadFailed { upload_id, reason: UploadRejection::Unknown },
+        };
+        self.send(id, &event);
+        None
+    }
+
+    // Pemilik upload dan room: akun, atau user id dari pesan pertama di server tanpa login, supaya upload bisa
+    // dilanjutkan dan room dimoderasi dari koneksi baru milik user yang sama. Koneksi yang belum dikenal
+    // memakai id koneksinya sampai pesan pertamanya (lihat identify).
+    fn owner(&self, id: u64) -> String {
+        let client = self.clients.get(&id);
+        match (client.and_then(|client| client.account.as_ref()), client.and_then(|client| client.user.as_ref())) {
+            (Some(account), _) => format!("user:{}", account.user_id),
+            (None, Some(user)) => format!("user:{}", user.user_id),
+            (None, None) => connection_owner(id),
+        }
+    }
+
+    // Beri id dan timestamp, simpan di riwayat, lalu siarkan ke room
+    fn publish(&mut self, room: String, mut message: ChatMessage) {
+        self.next_message += 1;
+        message.id = Some(format!("msg-{}", self.next_message));
+        message.timestamp = Some(now_millis()); // Cap waktu dari klien hanya cadangan untuk tampilannya sendiri
+        message.room = Some(room.clone());
+        message.edited_at = None; // `client_id` dibiarkan: pengirim memakainya untuk mencocokkan pesan pending-nya
+
+        let target = self.rooms.entry(room.clone()).or_default();
+        target.history.push_back(message.clone());
+        if target.history.len() > self.retention.history_per_room {
+            target.history.pop_front(); // File baru dipangkas saat ditulis ulang
+        }
+        if let Some(file) = self.history_file.as_ref().filter(|_| target.persisted()) {
+            file.append(&message);
+        }
+        target.last_active = now_millis();
+        let expires_at = target.expires_at();
+        self.broadcast(&room, &message);
+        if let Some(expires_at) = expires_at {
+            self.broadcast(&room, &ServerEvent::RoomExpiryUpdated { room: room.clone(), expires_at });
+        }
+    }
+
+    // Seperti publish, tetapi tidak disimpan dan hanya dikirim ke koneksi penerima dan pengirim di room itu
+    // (termasuk tab lain milik keduanya)
+    fn whisper(&mut self, from: u64, room: String, mut message: ChatMessage) {
+        self.next_message += 1;
+        message.id = Some(format!("msg-{}", self.next_message));
+        message.timestamp = Some(now_millis());
+        message.room = Some(room.clone());
+        message.edited_at = None;
+        let Some(text) = to_json(&message) else { return };
+        let sender = self.clients.get(&from).and_then(Client::identity);
+        let audience = [sender, message.whisper_to.as_deref()];
+        for (id, client) in self.clients.iter().filter(|(_, client)| client.rooms.contains(&room)) {
+            if *id == from || client.identity().is_some_and(|identity| audience.contains(&Some(identity))) {
+                client.tx.push(Frame::Critical(text.clone()));
+            }
+        }
+    }
+
+    fn join(&mut self, id: u64, room: String) {
+        let Some(client) = self.clients.get_mut(&id) else { return };
+        client.rooms.insert(room.clone());
+        client.current = room.clone();
+        // Hanya halaman terbaru; pesan yang lebih lama diminta klien lewat HistoryRequest saat digulir
+        let messages = self.rooms.get(&room).map(|joined| history_page(&joined.history, None, HISTORY_PAGE).0).unwrap_or_default();
+        let slow_mode_secs = self.rooms.get(&room).map_or(0, |joined| joined.slow_mode_secs);
+        let expires_at = self.rooms.get(&room).and_then(Room::expires_at);
+        let is_moderator = self.is_moderator(id, &room);
+        let owner = self.owner(id);
+        let (rules, rules_accepted) = self.rooms.get(&room).map_or((None, false), |joined| (joined.rules.clone(), joined.rules_accepted.contains(&owner)));
+        // Setiap kali masuk (sesi baru) nama samaran baru
+        let pseudonym = self.rooms.get_mut(&room).filter(|joined| joined.anonymous).map(|joined| {
+            let pseudonym = pseudonyms::generate();
+            joined.pseudonyms.insert(id, (pseudonym.clone(), Instant::now()));
+            pseudonym
+        });
+        let Some(client) = self.clients.get(&id) else { return };
+        if let Some(user) = client.user.clone() {
+            self.broadcast_presence(Some(&room), &PresenceEvent::UserJoined { room: room.clone(), user });
+        }
+        let room_name = room.clone();
+        let event = ServerEvent::RoomJoined {
+            room,
+            expires_at,
+            is_moderator,
+            slow_mode_secs,
+            rules,
+            rules_accepted,
+            embeds_disabled: false,
+            dm_peer: None,
+            pseudonym,
+            message_ttl_secs: None,
+            presence_digest_secs: None,
+            feedback_box: false,
+            language: None,
+        };
+        self.send(id, &event);
+        let total_missed = messages.len() as u64;
+        self.send(id, &ServerEvent::HistoryReplay { messages, total_missed });
+        let Some(joined) = self.rooms.get(&room_name).filter(|_| !self.clients.get(&id).is_some_and(|client| client.privacy.disable_read_receipts)) else { return };
+        for (user_id, message_id) in &joined.read_marks {
+            if joined.history.iter().any(|message| message.id.as_ref() == Some(message_id)) {
+                self.send(id, &ServerEvent::ReadReceipt { room: room_name.clone(), user_id: user_id.clone(), message_id: message_id.clone() });
+            }
+        }
+    }
+
+    pub fn admin(&mut self, command: AdminCommand) -> AdminEvent {
+        match command {
+            AdminCommand::ListRooms => {
+                let mut rooms: Vec<RoomSummary> = self
+                    .rooms
+                    .iter()
+                    .map(|(name, room)| RoomSummary {
+                        name: name.clone(),
+                        members: self.clients.values().filter(|client| client.rooms.contains(name)).count(),
+                        messages: room.history.len(),
+                        slow_mode_secs: room.slow_mode_secs,
+                        password_protected: room.password.is_some(),
+                    })
+                    .collect();
+                rooms.sort_by(|a, b| a.name.cmp(&b.name));
+                AdminEvent::Rooms { rooms }
+            }
+            AdminCommand::ListUsers { room } => {
+                let mut users: Vec<AdminUser> = Vec::new();
+                // Koneksi yang belum mengirim pesan belum punya identitas, jadi tidak terdaftar
+                for client in self.clients.values().filter(|client| room.as_ref().is_none_or(|room| client.rooms.contains(room))) {
+                    let Some(user) = &client.user else { continue };
+                    match users.iter_mut().find(|known| known.user_id == user.user_id) {
+                        Some(known) => {
+                            known.connections += 1;
+                            known.rooms.extend(client.rooms.iter().filter(|room| !known.rooms.contains(room)).cloned().collect::<Vec<_>>());
+                        }
+                        None => users.push(AdminUser { user_id: user.user_id.clone(), name: user.name.clone(), connections: 1, rooms: client.rooms.iter().cloned().collect() }),
+                    }
+                }
+                for user in &mut users {
+                    user.rooms.sort();
+                }
+                users.sort_by_key(|user| user.name.to_lowercase());
+                let mut banned: Vec<String> = self.banned.keys().cloned().collect();
+                banned.sort();
+                AdminEvent::Users { users, banned }
+            }
+            AdminCommand::Ban { user_id, reason } => {
+                self.banned.insert(user_id.clone(), reason.clone());
+                let connections: Vec<u64> = self.clients.iter().filter(|(_, client)| client.identity() == Some(user_id.as_str())).map(|(id, _)| *id).collect();
+                for id in &connections {
+                    self.kick(*id, reason.clone());
+                }
+                log::info!("Admin memblokir {} ({} koneksi diputus)", user_id, connections.len());
+                AdminEvent::Done { detail: format!("{} diblokir, {} koneksi diputus", user_id, connections.len()) }
+            }
+            AdminCommand::Unban { user_id } => match self.banned.remove(&user_id) {
+                Some(_) => AdminEvent::Done { detail: format!("{} tidak lagi diblokir", user_id) },
+                None => AdminEvent::Error { message: format!("{} tidak sedang diblokir", user_id) },
+            },
+            AdminCommand::PurgeMessages { room, user_id } => {
+                let Some(existing) = self.rooms.get_mut(&room) else { return room_not_found(&room) };
+                let before = existing.history.len();
+                existing.history.retain(|message| user_id.as_ref().is_some_and(|user_id| message.user_id.as_ref() != Some(user_id)));
+                let purged = before - existing.history.len();
+                self.persist_all();
+                log::info!("Admin menghapus {} pesan dari {}", purged, room_label(&room));
+                AdminEvent::Done { detail: format!("{} pesan dihapus dari {}", purged, room_label(&room)) }
+            }
+            AdminCommand::SetSlowMode { room, interval_secs } => {
+                if !self.set_slow_mode(&room, interval_secs) {
+                    return room_not_found(&room);
+                }
+                let detail = match interval_secs {
+                    0 => format!("Slow mode {} dimatikan", room_label(&room)),
+                    secs => format!("Slow mode {}: satu pesan per {} detik", room_label(&room), secs),
+                };
+                AdminEvent::Done { detail }
+            }
+            AdminCommand::SetRules { room, rules } => {
+                let Some(existing) = self.rooms.get_mut(&room) else { return room_not_found(&room) };
+                let detail = match rules {
+                    Some(_) => format!("Aturan {} diperbarui; semua anggota perlu menyetujuinya lagi", room_label(&room)),
+                    None => format!("Aturan {} dihapus", room_label(&room)),
+                };
+                existing.rules = rules;
+                existing.rules_accepted.clear();
+                AdminEvent::Done { detail }
+            }
+            AdminCommand::SetAnonymous { room, anonymous } => {
+                if room == GENERAL_ROOM {
+                    return AdminEvent::Error { message: "Room umum tidak bisa dijadikan anonim".to_string() };
+                }
+                let Some(existing) = self.rooms.get_mut(&room) else { return room_not_found(&room) };
+                if existing.anonymous == anonymous {
+                    return AdminEvent::Done { detail: format!("{} tidak berubah", room_label(&room)) };
+                }
+                existing.anonymous = anonymous;
+                existing.pseudonyms.clear();
+                existing.read_marks.clear();
+                let members: Vec<u64> = self.clients.iter().filter(|(_, client)| client.rooms.contains(&room)).map(|(id, _)| *id).collect();
+                if anonymous {
+                    // Daftar anggota yang sudah diterima klien memuat identitas asli, jadi dikosongkan
+                    for member in members {
+                        let pseudonym = pseudonyms::generate();
+                        if let Some(existing) = self.rooms.get_mut(&room) {
+                            existing.pseudonyms.insert(member, (pseudonym.clone(), Instant::now()));
+                        }
+                        self.send(member, &ServerEvent::PseudonymRotated { room: room.clone(), pseudonym });
+                        self.send(member, &PresenceEvent::Roster { room: room.clone(), users: Vec::new() });
+                    }
+                    return AdminEvent::Done { detail: format!("{} sekarang anonim; pesan yang sudah ada tetap memakai nama asli", room_label(&room)) };
+                }
+                for member in members {
+                    let Some(client) = self.clients.get_mut(&member) else { continue };
+                    client.rooms.remove(&room);
+                    if client.current == room {
+                        client.current = GENERAL_ROOM.to_string();
+                    }
+                    self.send(member, &ServerEvent::RoomLeft { room: room.clone() });
+                }
+                AdminEvent::Done { detail: format!("{} tidak lagi anonim; anggotanya dikeluarkan dan perlu bergabung lagi", room_label(&room)) }
+            }
+            AdminCommand::QueueStats => {
+                let mut queues: Vec<QueueDepth> = self
+                    .clients
+                    .iter()
+                    .map(|(id, client)| {
+                        let depth = client.tx.depth();
+                        QueueDepth { connection: *id, user_id: client.identity().map(str::to_string), frames: depth.frames, bytes: depth.bytes, peak: depth.peak }
+                    })
+                    .collect();
+                queues.sort_by(|a, b| b.frames.cmp(&a.frames).then(b.peak.cmp(&a.peak)));
+                let metrics = &self.queue_metrics;
+                AdminEvent::Queues {
+                    queues,
+                    max_frames: self.send_queue.max_frames,
+                    presence_dropped: metrics.dropped.load(Ordering::Relaxed),
+                    presence_coalesced: metrics.coalesced.load(Ordering::Relaxed),
+                    slow_disconnects: metrics.overflowed.load(Ordering::Relaxed),
+                }
+            }
+            AdminCommand::Announce { room, text } => {
+                let room = room.unwrap_or_else(|| GENERAL_ROOM.to_string());
+                if !self.rooms.contains_key(&room) {
+                    return room_not_found(&room);
+                }
+                let message = ChatMessage {
+                    id: None,
+                    client_id: None,
+                    user_id: None,
+                    username: ANNOUNCER.to_string(),
+                    text,
+                    timestamp: None,
+                    edited_at: None,
+                    attachment: None,
+                    expires_at: None,
+                    event: None,
+                    system: true,
+                    room: None,
+                    components: Vec::new(),
+                    operator: None,
+                    whisper_to: None,
+                };
+                let detail = format!("Pengumuman dikirim ke {}", room_label(&room));
+                self.publish(room, message);
+                AdminEvent::Done { detail }
+            }
+        }
+    }
+
+    // false jika room tidak ada
+    fn set_slow_mode(&mut self, room: &str, interval_secs: u32) -> bool {
+        let Some(existing) = self.rooms.get_mut(room) else { return false };
+        existing.slow_mode_secs = interval_secs;
+        self.broadcast(room, &ServerEvent::SlowModeChanged { room: room.to_string(), interval_secs });
+        true
+    }
+
+    fn is_moderator(&self, id: u64, room: &str) -> bool {
+        self.rooms.get(room).is_some_and(|existing| existing.moderators.contains(&self.owner(id)))
+    }
+
+    // Hapus room sementara yang tidak aktif melewati TTL-nya dan pesan yang lebih tua dari
+    // retention.max_age_days; dipanggil berkala dari main.rs
+    pub fn prune_expired(&mut self) {
+        self.expire_rooms();
+        self.rotate_pseudonyms();
+        let Some(days) = self.retention.max_age_days else { return };
+        let cutoff = now_millis() - f64::from(days) * 86_400_000.0;
+        let mut pruned = 0;
+        for room in self.rooms.values_mut() {
+            let before = room.history.len();
+            room.history.retain(|message| message.timestamp.is_none_or(|timestamp| timestamp >= cutoff));
+            pruned += before - room.history.len();
+        }
+        if pruned > 0 {
+            log::info!("{} pesan melewati batas retensi {} hari dan dihapus", pruned, days);
+            self.persist_all();
+        }
+    }
+
+    fn rotate_pseudonyms(&mut self) {
+        let mut rotated = Vec::new();
+        for (name, room) in self.rooms.iter_mut().filter(|(_, room)| room.anonymous) {
+            for (id, (pseudonym, assigned)) in room.pseudonyms.iter_mut().filter(|(_, (_, assigned))| assigned.elapsed() >= PSEUDONYM_ROTATION) {
+                (*pseudonym, *assigned) = (pseudonyms::generate(), Instant::now());
+                rotated.push((*id, ServerEvent::PseudonymRotated { room: name.clone(), pseudonym: pseudonym.clone() }));
+            }
+        }
+        for (id, event) in rotated {
+            self.send(id, &event);
+        }
+    }
+
+    // Some jika `room` anonim: nama samaran koneksi ini, atau None jika belum punya (mis. sudah keluar)
+    fn anonymous_in(&self, id: u64, room: &str) -> Option<Option<Pseudonym>> {
+        let target = self.rooms.get(room).filter(|target| target.anonymous)?;
+        Some(target.pseudonyms.get(&id).map(|(pseudonym, _)| pseudonym.clone()))
+    }
+
+    // Anggota menerima RoomExpired lalu dikeluarkan dari room; klien kembali ke room umum
+    fn expire_rooms(&mut self) {
+        let now = now_millis();
+        let expired: Vec<String> = self.rooms.iter().filter(|(_, room)| room.expires_at().is_some_and(|expires_at| expires_at <= now)).map(|(name, _)| name.clone()).collect();
+        for room in expired {
+            self.broadcast(&room, &ServerEvent::RoomExpired { room: room.clone() });
+            for client in self.clients.values_mut() {
+                if client.rooms.remove(&room) && client.current == room {
+                    client.current = GENERAL_ROOM.to_string();
+                }
+                client.last_sent.remove(&room);
+            }
+            self.rooms.remove(&room);
+            log::info!("{} tidak aktif melewati TTL-nya dan dihapus", room_label(&room));
+        }
+    }
+
+    // Roster baru untuk koneksi yang event presence-nya sempat dibuang; dipanggil berkala dari main.rs
+    pub fn resync_presence(&self) {
+        for (id, client) in &self.clients {
+            if !client.tx.take_stale_presence() {
+                continue;
+            }
+            log::debug!("Koneksi {} tertinggal event presence; Roster dikirim ulang", id);
+            for room in &client.rooms {
+                self.send(*id, &self.roster(room.clone()));
+            }
+        }
+    }
+
+    // Room anonim selalu kosong (lihat Room::anonymous)
+    fn roster(&self, room: String) -> PresenceEvent {
+        let mut users: Vec<RosterUser> = Vec::new();
+        let anonymous = self.rooms.get(&room).is_some_and(|target| target.anonymous);
+        for user in self.clients.values().filter(|client| !anonymous && client.rooms.contains(&room)).filter_map(|client| client.user.as_ref()) {
+            if !users.iter().any(|known| known.user_id == user.user_id) {
+                users.push(user.clone()); // User dengan beberapa tab hanya muncul sekali
+            }
+        }
+        PresenceEvent::Roster { room, users }
+    }
+
+    // Tulis ulang file riwayat dari isi memori; room berkata sandi dan room sementara dilewati
+    fn persist_all(&self) {
+        let Some(file) = &self.history_file else { return };
+        let mut messages: Vec<&ChatMessage> = self.rooms.values().filter(|room| room.persisted()).flat_map(|room| room.history.iter()).collect();
+        messages.sort_by(|a, b| a.timestamp.unwrap_or_default().total_cmp(&b.timestamp.unwrap_or_default()));
+        file.rewrite(messages.into_iter());
+    }
+
+    // Kirim alasan lalu lepas koneksi; antrean kirimnya ditutup sehingga session menutup socket setelah alasan terkirim
+    fn kick(&mut self, id: u64, reason: Option<String>) {
+        self.send(id, &ServerEvent::SessionRevoked { reason });
+        self.disconnect(id);
+    }
+
+    fn is_authorized(&self, id: u64) -> bool {
+        self.auth.is_none() || self.clients.get(&id).is_some_and(|client| client.account.is_some())
+    }
+
+    // Server terbuka menerima token apa pun tanpa mengganti identitas klien
+    fn authenticate(&mut self, id: u64, token: &str) {
+        let Some(table) = &self.auth else {
+            self.send(id, &ServerEvent::AuthResult { ok: true, user_id: None, name: None, reason: None });
+            return;
+        };
+        let Some(account) = table.check(token).cloned() else {
+            log::info!("Koneksi {} memakai token yang tidak dikenal", id);
+            self.send(id, &ServerEvent::AuthResult { ok: false, user_id: None, name: None, reason: Some("Token tidak dikenal atau sudah dicabut.".to_string()) });
+            return;
+        };
+        if self.banned.contains_key(&account.user_id) {
+            self.send(id, &ServerEvent::AuthResult { ok: false, user_id: None, name: None, reason: Some("Akun ini diblokir.".to_string()) });
+            return;
+        }
+        if self.two_factor.contains_key(&account.user_id) {
+            let Some(client) = self.clients.get_mut(&id) else { return };
+            client.challenge = Some(account);
+            self.send(id, &ServerEvent::TwoFactorRequired);
+            return;
+        }
+        self.sign_in(id, account);
+    }
+
+    fn sign_in(&mut self, id: u64, account: Account) {
+        let event = ServerEvent::AuthResult { ok: true, user_id: Some(account.user_id.clone()), name: account.name.clone(), reason: None };
+        let Some(client) = self.clients.get_mut(&id) else { return };
+        let before = client.identity().map(str::to_string);
+        client.account = Some(account);
+        let after = client.identity().map(str::to_string);
+        self.index_session(id, before.as_deref(), after.as_deref());
+        self.send(id, &event);
+    }
+
+    // Jawaban TwoFactorRequired; AuthResult baru dikirim setelah TwoFactorAccepted
+    fn verify_two_factor(&mut self, id: u64, code: Option<String>, recovery_code: Option<String>) {
+        let Some(account) = self.clients.get(&id).and_then(|client| client.challenge.clone()) else {
+            log::debug!("VerifyTwoFactor dari koneksi {} tanpa tantangan diabaikan", id);
+            return;
+        };
+        if !self.check_two_factor(id, &account.user_id, code, recovery_code) {
+            return;
+        }
+        if let Some(client) = self.clients.get_mut(&id) {
+            client.challenge = None;
+        }
+        self.send(id, &ServerEvent::TwoFactorAccepted);
+        self.sign_in(id, account);
+    }
+
+    // Kode TOTP atau kode pemulihan untuk akun yang 2FA-nya aktif. Gagal berarti TwoFactorRejected sudah
+    // dikirim; setelah TWO_FACTOR_FAILURES_PER_ACCOUNT kode tidak diperiksa lagi sampai jendelanya lewat.
+    fn check_two_factor(&mut self, id: u64, user_id: &str, code: Option<String>, recovery_code: Option<String>) -> bool {
+        let failures = self.two_factor_failures.entry(user_id.to_string()).or_default();
+        let Some(factor) = self.two_factor.get_mut(user_id).filter(|_| !failures.exhausted(TWO_FACTOR_FAILURES_PER_ACCOUNT)) else {
+            self.send(id, &ServerEvent::TwoFactorRejected { attempts_left: Some(0) });
+            return false;
+        };
+        if factor.verify(code.as_deref(), recovery_code.as_deref()) {
+            self.save_two_factor(); // Langkah terakhir atau kode pemulihan yang terpakai
+            return true;
+        }
+        failures.record();
+        let attempts_left = u32::try_from(failures.remaining(TWO_FACTOR_FAILURES_PER_ACCOUNT)).ok();
+        log::info!("Kode 2FA salah untuk akun {} dari koneksi {}", user_id, id);
+        self.send(id, &ServerEvent::TwoFactorRejected { attempts_left });
+        false
+    }
+
+    fn account_id(&self, id: u64) -> Option<String> {
+        self.clients.get(&id)?.account.as_ref().map(|account| account.user_id.clone())
+    }
+
+    // Semua sesi akun ini, agar status 2FA di pengaturan tab lain ikut berubah
+    fn send_to_account(&self, user_id: &str, event: &ServerEvent) {
+        let sessions: Vec<u64> = self.clients.iter().filter(|(_, client)| client.account.as_ref().is_some_and(|account| account.user_id == user_id)).map(|(id, _)| *id).collect();
+        for id in sessions {
+            self.send(id, event);
+        }
+    }
+
+    fn save_two_factor(&self) {
+        if let Some(file) = &self.history_file {
+            file.save_two_factor(&self.two_factor);
+        }
+    }
+
+    // Pesan pertama (atau pesan dengan nama baru) memperkenalkan koneksi ini ke semua room yang diikutinya
+    fn identify(&mut self, id: u64, message: &ChatMessage) {
+        let Some(user_id) = message.user_id.clone() else { return };
+        let Some(client) = self.clients.get_mut(&id) else { return };
+        let (status, custom_status) = client.user.as_ref().map_or((UserStatus::Online, None), |user| (user.status, user.custom_status.clone()));
+        let user = RosterUser { user_id, name: message.username.clone(), status, custom_status };
+        if client.user.as_ref() == Some(&user) {
+            return;
+        }
+        let anonymous = client.user.is_none() && client.account.is_none();
+        let before = client.identity().map(str::to_string);
+        client.user = Some(user.clone());
+        let after = client.identity().map(str::to_string);
+        self.index_session(id, before.as_deref(), after.as_deref());
+        if anonymous {
+            // Room yang dibuat dan aturan yang disetujui sebelum pesan pertama kini milik identitas user ini
+            let (connection, owner) = (connection_owner(id), self.owner(id));
+            for room in self.rooms.values_mut() {
+                for owners in [&mut room.moderators, &mut room.rules_accepted] {
+                    if owners.remove(&connection) {
+                        owners.insert(owner.clone());
+                    }
+                }
+            }
+        }
+        let Some(client) = self.clients.get(&id) else { return };
+        for room in client.rooms.clone() {
+            self.broadcast_presence(Some(&room), &PresenceEvent::UserJoined { room: room.clone(), user: user.clone() });
+        }
+    }
+
+    // Pindahkan koneksi di tabel sesi saat identitasnya berubah (login, pesan pertama, putus)
+    fn index_session(&mut self, id: u64, before: Option<&str>, after: Option<&str>) {
+        if before == after {
+            return;
+        }
+        if let Some(before) = before {
+            if let Some(sessions) = self.sessions.get_mut(before) {
+                sessions.remove(&id);
+                if sessions.is_empty() {
+                    self.sessions.remove(before);
+                }
+            }
+        }
+        if let Some(after) = after {
+            self.sessions.entry(after.to_string()).or_default().insert(id);
+        }
+    }
+
+    // UserLeft hanya jika tidak ada koneksi lain milik user yang sama di room itu
+    fn announce_left(&self, client: &Client, room: &str) {
+        let Some(user) = &client.user else { return };
+        let still_here = self.clients.values().any(|other| other.rooms.contains(room) && other.user.as_ref().is_some_and(|other| other.user_id == user.user_id));
+        if !still_here {
+            self.broadcast_presence(Some(room), &PresenceEvent::UserLeft { room: room.to_string(), user_id: user.user_id.clone() });
+        }
+    }
+
+    // Pesan diserialisasi sekali lalu dikirim ke setiap koneksi di room itu, termasuk pengirimnya.
+    // Koneksi yang antreannya penuh dibatalkan di sini; session-nya lalu memanggil disconnect.
+    fn broadcast<T: Serialize>(&self, room: &str, frame: &T) {
+        let Some(text) = to_json(frame) else { return };
+        for client in self.clients.values().filter(|client| client.rooms.contains(room)) {
+            client.tx.push(Frame::Critical(text.clone()));
+        }
+    }
+
+    // Suntingan notepad bersama room (payload Vec<NoteEntry>). Versi yang menang diteruskan ke semua koneksi di room;
+    // pengirim suntingan yang kalah menerima versi yang berlaku. Payload kosong `[]` meminta snapshot untuk koneksi ini.
+    // Koneksi yang belum mengirim pesan belum punya identitas; di server tanpa login author dari klien dipakai apa adanya.
+    fn edit_notes(&mut self, id: u64, room: String, user_id: Option<String>, payload: &str) {
+        let entries: Vec<NoteEntry> = match serde_json::from_str(payload) {
+            Ok(entries) => entries,
+            Err(e) => return log::warn!("Suntingan catatan rusak dari koneksi {}: {}", id, e),
+        };
+        let Some(target) = self.rooms.get_mut(&room) else { return };
+        if target.anonymous && !entries.is_empty() {
+            log::debug!("Suntingan catatan di room anonim dari koneksi {} ditolak", id);
+        }
+        let entries = if target.anonymous { Vec::new() } else { entries }; // Dijawab snapshot agar klien kembali ke versi server
+        let notes_event = |user_id: &str, entries: &[NoteEntry]| ServerEvent::Custom {
+            room: room.clone(),
+            user_id: user_id.to_string(),
+            namespace: NOTES_NAMESPACE.to_string(),
+            payload: serde_json::to_string(entries).unwrap_or_default(),
+        };
+        if entries.is_empty() {
+            let snapshot = notes_event("", target.notes.entries());
+            return self.send(id, &snapshot);
+        }
+        let (mut accepted, mut stale) = (Vec::new(), Vec::new());
+        for mut entry in entries {
+            if let Some(user_id) = &user_id {
+                entry.author = user_id.clone(); // Pemecah seri LWW tidak bisa dipalsukan akun lain
+            }
+            match target.notes.get(&entry.id) {
+                None if entry.author.is_empty() || entry.deleted || target.notes.visible().len() >= MAX_ROOM_NOTES => continue,
+                Some(current) if !entry.supersedes(current) => stale.push(current.clone()),
+                _ => {
+                    target.notes.merge(entry.clone());
+                    accepted.push(entry);
+                }
+            }
+        }
+        let persist = target.persisted() && !accepted.is_empty();
+        if !stale.is_empty() {
+            self.send(id, &notes_event("", &stale));
+        }
+        if !accepted.is_empty() {
+            self.broadcast(&room, &notes_event(user_id.as_deref().unwrap_or_default(), &accepted));
+        }
+        if persist {
+            self.persist_notes();
+        }
+    }
+
+    // Arsip JSON semua pesan yang dikirim identitas ini, dikembalikan sebagai data: URL seperti lampiran
+    // karena server ini tidak punya endpoint HTTP. Room berkata sandi hanya disertakan jika koneksi ini
+    // sudah masuk, karena di server tanpa login identitas hanyalah user id yang diakui klien.
+    fn export(&self, id: u64) {
+        let Some(client) = self.clients.get(&id) else { return };
+        let Some(user_id) = client.identity() else {
+            log::debug!("RequestExport dari koneksi {} yang belum dikenal diabaikan", id);
+            return;
+        };
+        let mut messages: Vec<&ChatMessage> = self
+            .rooms
+            .iter()
+            .filter(|(name, room)| room.password.is_none() || client.rooms.contains(*name))
+            .flat_map(|(_, room)| room.history.iter())
+            .filter(|message| message.user_id.as_deref() == Some(user_id))
+            .collect();
+        messages.sort_by(|a, b| a.timestamp.unwrap_or_default().total_cmp(&b.timestamp.unwrap_or_default()));
+        let archive = serde_json::json!({ "user_id": user_id, "exported_at": now_millis(), "messages": messages });
+        let encoded = base64::engine::general_purpose::STANDARD.encode(archive.to_string());
+        log::info!("Ekspor {} pesan untuk {}", messages.len(), user_id);
+        self.send(id, &ServerEvent::ExportReady { url: format!("data:application/json;base64,{}", encoded), expires_at: None });
+    }
+
+    // Hanya untuk akun dari token: di server tanpa login siapa pun bisa memakai user id itu. Pesan lamanya tetap
+    // ada dengan nama DELETED_NAME dan id acak, jejak lain akun itu dibuang, lalu semua sesinya ditutup.
+    // Token di CHAT_AUTH_TOKENS tidak bisa dicabut dari sini; operator perlu menghapusnya sendiri.
+    fn delete_account(&mut self, id: u64) {
+        let Some(user_id) = self.account_id(id) else {
+            log::info!("DeleteAccount dari koneksi {} tanpa akun diabaikan", id);
+            return;
+        };
+        let anonymous = format!("deleted-{:016x}", OsRng.next_u64());
+        let owner = format!("user:{}", user_id);
+        for room in self.rooms.values_mut() {
+            for message in room.history.iter_mut().filter(|message| message.user_id.as_deref() == Some(user_id.as_str())) {
+                message.user_id = Some(anonymous.clone());
+                message.username = DELETED_NAME.to_string();
+                message.operator = None;
+            }
+            room.read_marks.remove(&user_id);
+            room.moderators.remove(&owner);
+            room.rules_accepted.remove(&owner);
+            if room.notes.entries().iter().any(|entry| entry.author == user_id) {
+                let mut notes = Notepad::default();
+                for mut entry in room.notes.entries().iter().cloned() {
+                    if entry.author == user_id {
+                        entry.author = anonymous.clone();
+                    }
+                    notes.merge(entry);
+                }
+                room.notes = notes;
+            }
+        }
+        self.uploads.forget(&owner);
+        self.two_factor_failures.remove(&user_id);
+        if self.two_factor.remove(&user_id).is_some() {
+            self.save_two_factor();
+        }
+        self.persist_all();
+        self.persist_notes();
+        log::info!("Akun {} dihapus atas permintaannya", user_id);
+        let sessions: Vec<u64> = self.clients.iter().filter(|(_, client)| client.account.as_ref().is_some_and(|account| account.user_id == user_id)).map(|(id, _)| *id).collect();
+        for session in sessions {
+            self.send(session, &ServerEvent::AccountDeleted);
+            self.disconnect(session);
+        }
+        let ids: Vec<u64> = self.clients.keys().copied().collect();
+        for other in ids {
+            self.send(other, &ServerEvent::UserDeleted { user_id: user_id.clone() });
+        }
+    }
+
+    fn persist_notes(&self) {
+        let Some(file) = &self.history_file else { return };
+        let notes = self.rooms.iter().filter(|(_, room)| room.persisted() && !room.notes.is_empty()).map(|(name, room)| (name.as_str(), &room.notes)).collect();
+        file.save_notes(&notes);
+    }
+
+    // Seperti broadcast, tetapi tidak dikirim balik ke koneksi pengirim
+    fn broadcast_others<T: Serialize>(&self, from: u64, room: &str, frame: &T) {
+        let Some(text) = to_json(frame) else { return };
+        for (_, client) in self.clients.iter().filter(|(id, client)| **id != from && client.rooms.contains(room)) {
+            client.tx.push(Frame::Critical(text.clone()));
+        }
+    }
+
+    // Presence boleh tertinggal; `room` None = semua koneksi, mis. perubahan status yang terlihat di semua room
+    fn broadcast_presence(&self, room: Option<&str>, event: &PresenceEvent) {
+        if room.is_some_and(|room| self.rooms.get(room).is_some_and(|target| target.anonymous)) {
+            return;
+        }
+        let Some(text) = to_json(event) else { return };
+        for client in self.clients.values().filter(|client| room.is_none_or(|room| client.rooms.contains(room))) {
+            client.tx.push(Frame::presence(event, text.clone()));
+        }
+    }
+
+    // Event room yang hanya berlaku versi terbarunya (mengetik, tanda dibaca). Seperti presence: event dengan
+    // `key` yang sama digabung atau dibuang untuk klien yang lambat. Tidak dikirim balik ke koneksi
+    // pengirim, tetapi tab lain milik akun yang sama (operator lain di akun bersama) tetap menerimanya.
+    fn broadcast_transient(&self, from: u64, room: &str, key: String, event: &ServerEvent) {
+        let Some(text) = to_json(event) else { return };
+        let receipt = matches!(event, ServerEvent::ReadReceipt { .. }); // Tidak untuk koneksi yang mematikan tanda dibaca
+        for (_, client) in self.clients.iter().filter(|(id, client)| **id != from && client.rooms.contains(room) && !(receipt && client.privacy.disable_read_receipts)) {
+            client.tx.push(Frame::Presence { text: text.clone(), key: key.clone() });
+        }
+    }
+
+    fn send<T: Serialize>(&self, id: u64, frame: &T) {
+        if let (Some(client), Some(text)) = (self.clients.get(&id), to_json(frame)) {
+            client.tx.push(Frame::Critical(text));
+        }
+    }
+}
+
+impl Client {
+    // Id akun dari token, atau id dari pesan pertama untuk server tanpa login
+    fn identity(&self) -> Option<&str> {
+        self.account.as_ref().map(|account| account.user_id.as_str()).or_else(|| self.user.as_ref().map(|user| user.user_id.as_str()))
+    }
+}
+
+// Pekerjaan Argon2 untuk CreateRoom/JoinRoom berkata sandi dan pemindaian upload; lihat Hub::handle_frame
+pub enum BlockingTask {
+    Create { room: String, password: String, ttl_secs: Option<u64> },
+    Join { room: String, password: String, hash: String },
+    Scan { upload_id: String, attachment: Attachment, command: Vec<String> },
+}
+
+pub enum BlockingOutcome {
+    Created { room: String, hash: Option<String>, ttl_secs: Option<u64> }, // hash None = gagal membuat hash
+    Joined { room: String, hash: String, matches: bool },
+    Scanned { upload_id: String, url: Result<String, UploadRejection> },
+}
+
+impl BlockingTask {
+    // Memblokir puluhan milidetik (Argon2) sampai SCAN_TIMEOUT (pemindai upload); jalankan di
+    // tokio::task::spawn_blocking tanpa memegang kunci Hub
+    pub fn run(self) -> BlockingOutcome {
+        match self {
+            BlockingTask::Create { room, password, ttl_secs } => BlockingOutcome::Created { room, hash: hash_password(&password), ttl_secs },
+            BlockingTask::Join { room, password, hash } => BlockingOutcome::Joined { matches: password_matches(&hash, &password), room, hash },
+            BlockingTask::Scan { upload_id, attachment, command } => BlockingOutcome::Scanned { upload_id, url: attachment.scan(&command).map(|()| attachment.into_url()) },
+        }
+    }
+}
+
+// Hash argon2id dengan parameter bawaan crate; None jika OS tidak bisa memberi salt acak
+fn hash_password(password: &str) -> Option<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    match Argon2::default().hash_password(password.as_bytes(), &salt) {
+        Ok(hash) => Some(hash.to_string()),
+        Err(e) => {
+            log::error!("Gagal membuat hash kata sandi room: {}", e);
+            None
+        }
+    }
+}
+
+// Perbandingan hash dilakukan argon2 dalam waktu konstan
+fn password_matches(hash: &str, given: &str) -> bool {
+    PasswordHash::new(hash).is_ok_and(|hash| Argon2::default().verify_password(given.as_bytes(), &hash).is_ok())
+}
+
+// Halaman riwayat sebelum pesan `before_id` (None = halaman terbaru) dan apakah masih ada yang lebih lama
+fn history_page(history: &VecDeque<ChatMessage>, before_id: Option<&str>, limit: usize) -> (Vec<ChatMessage>, bool) {
+    let end = match before_id {
+        None => history.len(),
+        Some(before_id) => match history.iter().position(|message| message.id.as_deref() == Some(before_id)) {
+            Some(end) => end,
+            None => return (Vec::new(), false),
+        },
+    };
+    let start = end.saturating_sub(limit);
+    (history.range(start..end).cloned().collect(), start > 0)
+}
+
+fn room_label(room: &str) -> String {
+    if room == GENERAL_ROOM {
+        "room umum".to_string()
+    } else {
+        format!("room {}", room)
+    }
+}
+
+fn room_not_found(room: &str) -> AdminEvent {
+    AdminEvent::Error { message: format!("{} tidak ada", room_label(room)) }
+}
+
+fn to_json<T: Serialize>(frame: &T) -> Option<String> {
+    serde_json::to_string(frame).map_err(|e| log::error!("Gagal serialisasi frame: {}", e)).ok()
+}
+
+fn connection_owner(id: u64) -> String {
+    format!("conn:{}", id)
+}
+
+fn now_millis() -> f64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0.0, |elapsed| elapsed.as_millis() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::FutureExt;
+
+    use super::*;
+    use crate::config::UploadConfig;
+
+    fn hub() -> Hub {
+        Hub::new(&Config::default(), None).unwrap()
+    }
+
+    // Semua event yang sudah antre untuk satu koneksi; frame yang bukan ServerEvent (pesan chat, presence) dilewati
+    fn drain(queue: &Outbox) -> Vec<ServerEvent> {
+        let mut events = Vec::new();
+        while let Some(Some(text)) = queue.pop().now_or_never() {
+            events.extend(serde_json::from_str(&text).ok().filter(|event| *event != ServerEvent::Unknown));
+        }
+        events
+    }
+
+    // Seperti session.rs, tetapi BlockingTask dijalankan langsung
+    fn command(hub: &mut Hub, id: u64, command: &ClientCommand) {
+        if let Some(task) = hub.handle_frame(id, &serde_json::to_string(command).unwrap()) {
+            hub.finish_task(id, task.run());
+        }
+    }
+
+    fn create_room(room: &str, ttl_secs: Option<u64>) -> ClientCommand {
+        ClientCommand::CreateRoom { room: room.to_string(), password: None, ttl_secs, feedback_box: false }
+    }
+
+    #[test]
+    fn inactive_temporary_room_expires() {
+        let mut hub = hub();
+        let (id, queue) = hub.connect(None, String::new());
+        command(&mut hub, id, &create_room("sementara", Some(60)));
+        command(&mut hub, id, &create_room("tetap", None));
+        let expiry: Vec<Option<f64>> = drain(&queue).into_iter().filter_map(|event| match event {
+            ServerEvent::RoomJoined { expires_at, .. } => Some(expires_at),
+            _ => None,
+        }).collect();
+        assert!(matches!(expiry[..], [Some(_), None]));
+
+        hub.prune_expired();
+        assert!(hub.rooms.contains_key("sementara"));
+        hub.rooms.get_mut("sementara").unwrap().last_active -= 61_000.0;
+        hub.prune_expired();
+        assert!(!hub.rooms.contains_key("sementara") && hub.rooms.contains_key("tetap"));
+        assert_eq!(drain(&queue), [ServerEvent::RoomExpired { room: "sementara".to_string() }]);
+        assert!(!hub.clients[&id].rooms.contains("sementara"));
+    }
+
+    fn message(hub: &mut Hub, id: u64, user_id: &str, room: &str) {
+        let frame = serde_json::json!({ "username": user_id, "user_id": user_id, "text": "halo", "room": room });
+        hub.handle_frame(id, &frame.to_string());
+    }
+
+    #[test]
+    fn only_room_creator_can_set_slow_mode() {
+        let mut hub = hub();
+        let (owner, owner_queue) = hub.connect(None, String::new());
+        let (guest, guest_queue) = hub.connect(None, String::new());
+        command(&mut hub, owner, &create_room("kelas", None));
+        message(&mut hub, owner, "guru", "kelas"); // Moderator pindah dari id koneksi ke identitas user
+        command(&mut hub, guest, &ClientCommand::JoinRoom { room: "kelas".to_string(), password: None });
+        let joined = |queue: &Outbox| drain(queue).into_iter().find_map(|event| match event {
+            ServerEvent::RoomJoined { is_moderator, .. } => Some(is_moderator),
+            _ => None,
+        });
+        assert_eq!(joined(&owner_queue), Some(true));
+        assert_eq!(joined(&guest_queue), Some(false));
+
+        let slow = |secs| ClientCommand::SetSlowMode { room: "kelas".to_string(), interval_secs: secs };
+        command(&mut hub, guest, &slow(30));
+        assert_eq!(hub.rooms["kelas"].slow_mode_secs, 0);
+        command(&mut hub, owner, &slow(10));
+        assert_eq!(hub.rooms["kelas"].slow_mode_secs, 10);
+        assert!(drain(&guest_queue).contains(&ServerEvent::SlowModeChanged { room: "kelas".to_string(), interval_secs: 10 }));
+    }
+
+    #[test]
+    fn rules_must_be_accepted_before_sending() {
+        let mut hub = hub();
+        let (id, queue) = hub.connect(None, String::new());
+        command(&mut hub, id, &create_room("klub", None));
+        drain(&queue);
+        hub.admin(AdminCommand::SetRules { room: "klub".to_string(), rules: Some("Sopan.".to_string()) });
+        let join = ClientCommand::JoinRoom { room: "klub".to_string(), password: None };
+        let joined = |queue: &Outbox| drain(queue).into_iter().find_map(|event| match event {
+            ServerEvent::RoomJoined { rules, rules_accepted, .. } => Some((rules, rules_accepted)),
+            _ => None,
+        });
+        command(&mut hub, id, &join);
+        assert_eq!(joined(&queue), Some((Some("Sopan.".to_string()), false)));
+
+        let frame = serde_json::json!({ "username": "ani", "user_id": "ani", "text": "halo", "room": "klub", "client_id": "c1" });
+        hub.handle_frame(id, &frame.to_string());
+        assert!(matches!(&drain(&queue)[..], [ServerEvent::MessageRejected { .. }]));
+        command(&mut hub, id, &ClientCommand::AcceptRules { room: "klub".to_string() });
+        hub.handle_frame(id, &frame.to_string());
+        assert_eq!(hub.rooms["klub"].history.len(), 1);
+        command(&mut hub, id, &join);
+        assert_eq!(joined(&queue), Some((Some("Sopan.".to_string()), true)));
+    }
+
+    #[test]
+    fn password_guesses_are_limited_per_connection() {
+        let mut hub = hub();
+        let (owner, _) = hub.connect(None, String::new());
+        let create = ClientCommand::CreateRoom { room: "rahasia".to_string(), password: Some("benar".to_string()), ttl_secs: None, feedback_box: false };
+        let task = hub.handle_frame(owner, &serde_json::to_string(&create).unwrap());
+        assert!(task.is_some() && !hub.rooms.contains_key("rahasia")); // Hash dibuat di luar Hub
+        hub.finish_task(owner, task.unwrap().run());
+        assert!(hub.rooms["rahasia"].password.is_some());
+
+        let (guest, queue) = hub.connect(None, String::new());
+        let join = |password: &str| ClientCommand::JoinRoom { room: "rahasia".to_string(), password: Some(password.to_string()) };
+        let failure = |queue: &Outbox| drain(queue).into_iter().find_map(|event| match event {
+            ServerEvent::JoinFailed { reason, .. } => Some(reason),
+            _ => None,
+        });
+        for _ in 0..PASSWORD_FAILURES_PER_CONNECTION {
+            command(&mut hub, guest, &join("salah"));
+            assert_eq!(failure(&queue), Some(JoinFailure::WrongPassword));
+        }
+        assert!(hub.handle_frame(guest, &serde_json::to_string(&join("benar")).unwrap()).is_none());
+        assert_eq!(failure(&queue), Some(JoinFailure::TooManyAttempts));
+
+        let (other, other_queue) = hub.connect(None, String::new());
+        command(&mut hub, other, &join("benar"));
+        assert_eq!(failure(&other_queue), None);
+        assert!(hub.clients[&other].rooms.contains("rahasia"));
+    }
+
+    #[test]
+    fn sessions_are_listed_and_revoked_per_identity() {
+        let mut hub = hub();
+        let (laptop, laptop_queue) = hub.connect(None, "Firefox di Linux".to_string());
+        let (phone, phone_queue) = hub.connect(None, "Chrome di Android".to_string());
+        let (stranger, _) = hub.connect(None, String::new());
+        message(&mut hub, laptop, "ani", GENERAL_ROOM);
+        message(&mut hub, phone, "ani", GENERAL_ROOM);
+        message(&mut hub, stranger, "budi", GENERAL_ROOM);
+        drain(&laptop_queue);
+        drain(&phone_queue);
+
+        command(&mut hub, laptop, &ClientCommand::ListSessions);
+        let Some(ServerEvent::Sessions { sessions }) = drain(&laptop_queue).pop() else { panic!("tanpa Sessions") };
+        let devices: Vec<(&str, bool)> = sessions.iter().map(|session| (session.device.as_str(), session.current)).collect();
+        assert_eq!(devices, [("Firefox di Linux", true), ("Chrome di Android", false)]);
+
+        let revoke = ClientCommand::RevokeSession { session_id: sessions[1].session_id.clone() };
+        command(&mut hub, stranger, &revoke); // Sesi milik user lain tidak bisa dicabut
+        assert!(hub.clients.contains_key(&phone));
+        command(&mut hub, laptop, &revoke);
+        assert!(!hub.clients.contains_key(&phone));
+        assert_eq!(drain(&phone_queue), [ServerEvent::SessionRevoked { reason: None }]);
+        assert_eq!(hub.sessions["ani"], HashSet::from([laptop]));
+    }
+
+    #[test]
+    fn deleted_account_is_anonymised_everywhere() {
+        let mut hub = Hub::new(&Config::default(), Some(TokenTable::from_entries("t1:ani:Ani,t2:budi:Budi"))).unwrap();
+        let (ani, ani_queue) = hub.connect(Some("t1".to_string()), String::new());
+        let (budi, budi_queue) = hub.connect(Some("t2".to_string()), String::new());
+        message(&mut hub, ani, "ani", GENERAL_ROOM);
+        message(&mut hub, budi, "budi", GENERAL_ROOM);
+        drain(&ani_queue);
+        drain(&budi_queue);
+
+        command(&mut hub, ani, &ClientCommand::RequestExport);
+        let Some(ServerEvent::ExportReady { url, .. }) = drain(&ani_queue).pop() else { panic!("tanpa ExportReady") };
+        let encoded = url.strip_prefix("data:application/json;base64,").unwrap();
+        let archive: serde_json::Value = serde_json::from_slice(&base64::engine::general_purpose::STANDARD.decode(encoded).unwrap()).unwrap();
+        assert_eq!(archive["messages"].as_array().map(Vec::len), Some(1));
+
+        command(&mut hub, ani, &ClientCommand::DeleteAccount);
+        assert_eq!(drain(&ani_queue), [ServerEvent::AccountDeleted]);
+        assert!(!hub.clients.contains_key(&ani));
+        assert!(drain(&budi_queue).contains(&ServerEvent::UserDeleted { user_id: "ani".to_string() }));
+        let history = &hub.rooms[GENERAL_ROOM].history;
+        assert_eq!(history.iter().map(|message| message.username.as_str()).collect::<Vec<_>>(), [DELETED_NAME, "budi"]);
+        assert!(history.iter().all(|message| message.user_id.as_deref() != Some("ani")));
+    }
+
+    #[test]
+    fn privacy_hides_typing_and_receipts() {
+        let mut hub = hub();
+        let (ani, ani_queue) = hub.connect(None, String::new());
+        let (budi, budi_queue) = hub.connect(None, String::new());
+        message(&mut hub, ani, "ani", GENERAL_ROOM);
+        message(&mut hub, budi, "budi", GENERAL_ROOM);
+        command(&mut hub, ani, &ClientCommand::MarkRead { room: None, message_id: "msg-2".to_string() });
+        drain(&ani_queue);
+        assert!(matches!(drain(&budi_queue)[..], [ServerEvent::ReadReceipt { .. }]));
+
+        let privacy = Privacy { disable_read_receipts: true, hide_typing: true, ..Privacy::default() };
+        command(&mut hub, budi, &ClientCommand::UpdatePrivacy(privacy));
+        let typing = ClientCommand::Typing { room: None, operator: None };
+        command(&mut hub, budi, &typing);
+        command(&mut hub, budi, &ClientCommand::MarkRead { room: None, message_id: "msg-2".to_string() });
+        assert!(drain(&ani_queue).is_empty());
+        assert!(!hub.rooms[GENERAL_ROOM].read_marks.contains_key("budi"));
+
+        // Dua arah: tanda dibaca user lain juga tidak diteruskan, tetapi indikator mengetik tetap
+        command(&mut hub, ani, &typing);
+        message(&mut hub, ani, "ani", GENERAL_ROOM);
+        command(&mut hub, ani, &ClientCommand::MarkRead { room: None, message_id: "msg-3".to_string() });
+        assert!(matches!(drain(&budi_queue)[..], [ServerEvent::Typing { .. }]));
+    }
+
+    #[test]
+    fn anonymous_room_hides_identity_behind_rotating_pseudonym() {
+        let mut hub = hub();
+        let (ani, ani_queue) = hub.connect(None, String::new());
+        let (budi, budi_queue) = hub.connect(None, String::new());
+        command(&mut hub, ani, &create_room("curhat", None));
+        message(&mut hub, ani, "ani", "curhat");
+        command(&mut hub, budi, &ClientCommand::JoinRoom { room: "curhat".to_string(), password: None });
+        drain(&budi_queue);
+        assert!(matches!(hub.admin(AdminCommand::SetAnonymous { room: GENERAL_ROOM.to_string(), anonymous: true }), AdminEvent::Error { .. }));
+        assert!(matches!(hub.admin(AdminCommand::SetAnonymous { room: "curhat".to_string(), anonymous: true }), AdminEvent::Done { .. }));
+        let pseudonym = drain(&ani_queue).into_iter().find_map(|event| match event {
+            ServerEvent::PseudonymRotated { pseudonym, .. } => Some(pseudonym),
+            _ => None,
+        }).unwrap();
+        assert!(pseudonym.id.starts_with("anon-"));
+
+        message(&mut hub, ani, "ani", "curhat");
+        let sent = hub.rooms["curhat"].history.back().unwrap();
+        assert_eq!((sent.user_id.as_deref(), sent.username.as_str()), (Some(pseudonym.id.as_str()), pseudonym.name.as_str()));
+
+        // Masuk ulang memberi nama samaran baru lewat RoomJoined
+        command(&mut hub, budi, &ClientCommand::LeaveRoom { room: "curhat".to_string() });
+        command(&mut hub, budi, &ClientCommand::JoinRoom { room: "curhat".to_string(), password: None });
+        let joined = drain(&budi_queue).into_iter().find_map(|event| match event {
+            ServerEvent::RoomJoined { pseudonym, .. } => pseudonym,
+            _ => None,
+        });
+        assert!(joined.is_some_and(|joined| joined.id != pseudonym.id));
+
+        hub.rooms.get_mut("curhat").unwrap().pseudonyms.get_mut(&ani).unwrap().1 -= PSEUDONYM_ROTATION;
+        hub.prune_expired();
+        assert!(matches!(&drain(&ani_queue)[..], [ServerEvent::PseudonymRotated { pseudonym: rotated, .. }] if rotated.id != pseudonym.id));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn infected_upload_is_rejected_after_scan() {
+        let scan_command = ["sh", "-c", "cat >/dev/null; echo 'stream: Eicar-Test-Signature FOUND'; exit 1"].map(str::to_string).to_vec();
+        let config = Config { uploads: UploadConfig { scan_command: Some(scan_command), ..UploadConfig::default() }, ..Config::default() };
+        let mut hub = Hub::new(&config, None).unwrap();
+        let (id, queue) = hub.connect(None, String::new());
+        let png = b"\x89PNG\r\n\x1a\n";
+        command(&mut hub, id, &ClientCommand::UploadStart { upload_id: "u1".to_string(), name: "a.png".to_string(), mime: "image/png".to_string(), size: png.len() as u64, chunk_size: 64 * 1024 });
+        let data = base64::engine::general_purpose::STANDARD.encode(png);
+        command(&mut hub, id, &ClientCommand::UploadChunk { upload_id: "u1".to_string(), offset: 0, data });
+        let signature = Some("Eicar-Test-Signature".to_string());
+        assert_eq!(drain(&queue).last(), Some(&ServerEvent::UploadFailed { upload_id: "u1".to_string(), reason: UploadRejection::Infected { signature } }));
+    }
+
+    #[test]
+    fn two_factor_holds_login_until_verified() {
+        let mut hub = Hub::new(&Config::default(), Some(TokenTable::from_entries("rahasia:ani:Ani"))).unwrap();
+        let (laptop, laptop_queue) = hub.connect(Some("rahasia".to_string()), String::new());
+        command(&mut hub, laptop, &ClientCommand::StartTwoFactorSetup);
+        let Some(ServerEvent::TwoFactorSetup { recovery_codes, .. }) = drain(&laptop_queue).pop() else { panic!("tanpa TwoFactorSetup") };
+        command(&mut hub, laptop, &ClientCommand::ConfirmTwoFactorSetup { code: "salah".to_string() });
+        assert_eq!(drain(&laptop_queue), [ServerEvent::TwoFactorRejected { attempts_left: None }]);
+        let code = hub.clients[&laptop].two_factor_setup.as_ref().unwrap().current_code();
+        command(&mut hub, laptop, &ClientCommand::ConfirmTwoFactorSetup { code });
+        assert_eq!(drain(&laptop_queue), [ServerEvent::TwoFactorEnabled]);
+
+        let (phone, phone_queue) = hub.connect(Some("rahasia".to_string()), String::new());
+        assert_eq!(drain(&phone_queue), [ServerEvent::TwoFactorRequired]);
+        command(&mut hub, phone, &ClientCommand::ListSessions);
+        assert_eq!(drain(&phone_queue), [ServerEvent::AuthRequired]);
+        let verify = |recovery_code: &str| ClientCommand::VerifyTwoFactor { code: None, recovery_code: Some(recovery_code.to_string()) };
+        command(&mut hub, phone, &verify("bukan-kode"));
+        assert_eq!(drain(&phone_queue), [ServerEvent::TwoFactorRejected { attempts_left: Some(4) }]);
+        command(&mut hub, phone, &verify(&recovery_codes[0]));
+        assert!(matches!(drain(&phone_queue)[..], [ServerEvent::TwoFactorAccepted, ServerEvent::AuthResult { ok: true, .. }]));
+
+        // Kode pemulihan hanya sekali pakai, dan setelah batasnya kode yang benar pun ditolak
+        let (tablet, tablet_queue) = hub.connect(Some("rahasia".to_string()), String::new());
+        for _ in 0..4 {
+            command(&mut hub, tablet, &verify(&recovery_codes[0]));
+        }
+        command(&mut hub, tablet, &verify(&recovery_codes[1]));
+        assert_eq!(drain(&tablet_queue).last(), Some(&ServerEvent::TwoFactorRejected { attempts_left: Some(0) }));
+        assert!(hub.clients[&tablet].account.is_none());
+    }
+
+    #[test]
+    fn leave_room_only_confirms_joined_rooms() {
+        let mut hub = hub();
+        let (id, queue) = hub.connect(None, String::new());
+        command(&mut hub, id, &create_room("lain", None));
+        drain(&queue);
+        let leave = |room: &str| ClientCommand::LeaveRoom { room: room.to_string() };
+        command(&mut hub, id, &leave(GENERAL_ROOM));
+        command(&mut hub, id, &leave("asing"));
+        assert!(drain(&queue).is_empty());
+        assert!(hub.clients[&id].rooms.contains(GENERAL_ROOM));
+
+        command(&mut hub, id, &leave("lain"));
+        command(&mut hub, id, &leave("lain"));
+        assert_eq!(drain(&queue), [ServerEvent::RoomLeft { room: "lain".to_string() }]);
+        assert_eq!(hub.clients[&id].current, GENERAL_ROOM);
+    }
+}
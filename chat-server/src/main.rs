@@ -0,0 +1,119 @@
+// chat-server/src/main.rs
+// Server WebSocket untuk klien Yew. Tipe frame diambil dari crate chat-protocol yang juga dipakai
+// klien, jadi format di kedua sisi tidak bisa berbeda. Server memberi id dan timestamp pada setiap
+// pesan, menyiarkannya ke semua koneksi di room yang sama, dan menyimpan riwayat terbaru di memori.
+//
+//   cargo run -p chat-server -- 127.0.0.1:8080
+//   cargo run -p chat-server -- --config chat-server.toml
+//
+// Alamat, TLS, origin, retensi, rate limit dan persistence diatur lewat file TOML atau environment
+// (lihat config.rs). Klien terhubung ke ws://<alamat>/ws, atau wss:// jika [tls] diisi. Level log
+// diatur lewat RUST_LOG (default info), dan login diwajibkan jika CHAT_AUTH_TOKENS diset (lihat
+// auth.rs). CHAT_ADMIN_TOKEN mengaktifkan API admin untuk chat_admin di /admin (lihat admin.rs).
+mod admin;
+mod auth;
+mod config;
+mod history_file;
+mod hub;
+mod pseudonyms;
+mod send_queue;
+mod session;
+mod tls;
+mod two_factor;
+mod uploads;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::net::TcpListener;
+
+use config::Config;
+use hub::Hub;
+use session::Gate;
+
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60); // Seberapa sering pesan dan room sementara yang kedaluwarsa dihapus
+const RESYNC_INTERVAL: Duration = Duration::from_secs(2); // Seberapa sering klien yang tertinggal presence diberi Roster baru
+
+#[tokio::main]
+async fn main() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            log::error!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    let hub = match Hub::new(&config, auth::TokenTable::from_env()) {
+        Ok(hub) => Arc::new(Mutex::new(hub)),
+        Err(e) => {
+            log::error!("Gagal memuat riwayat tersimpan: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let tls = match config.tls.as_ref().map(tls::acceptor).transpose() {
+        Ok(tls) => tls,
+        Err(e) => {
+            log::error!("TLS tidak bisa disiapkan: {}", e);
+            std::process::exit(2);
+        }
+    };
+    let addr = config.bind.clone();
+    let listener = match TcpListener::bind(&addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::error!("Gagal membuka {}: {}", addr, e);
+            std::process::exit(1);
+        }
+    };
+    log::info!("chat-server mendengarkan di {}://{}/ws", if tls.is_some() { "wss" } else { "ws" }, addr);
+    if !config.allowed_origins.is_empty() {
+        log::info!("Origin yang diizinkan: {}", config.allowed_origins.join(", "));
+    }
+
+    {
+        let hub = hub.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(PRUNE_INTERVAL);
+            loop {
+                ticker.tick().await;
+                hub.lock().unwrap().prune_expired();
+            }
+        });
+    }
+    {
+        let hub = hub.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(RESYNC_INTERVAL);
+            loop {
+                ticker.tick().await;
+                hub.lock().unwrap().resync_presence();
+            }
+        });
+    }
+    let gate = Arc::new(Gate { admin_token: admin::token_from_env(), allowed_origins: config.allowed_origins.clone() });
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                log::warn!("Gagal menerima koneksi: {}", e);
+                continue;
+            }
+        };
+        let (hub, gate) = (hub.clone(), gate.clone());
+        match &tls {
+            Some(acceptor) => {
+                let acceptor = acceptor.clone();
+                tokio::spawn(async move {
+                    match acceptor.accept(stream).await {
+                        Ok(stream) => session::run(stream, peer, hub, gate).await,
+                        Err(e) => log::info!("Handshake TLS dari {} gagal: {}", peer, e),
+                    }
+                });
+            }
+            None => {
+                tokio::spawn(session::run(stream, peer, hub, gate));
+            }
+        }
+    }
+}
@@ -0,0 +1,18 @@
+// chat-server/src/pseudonyms.rs
+// Nama samaran untuk room anonim (lihat Hub::join). Id acak tidak diturunkan dari akun, jadi pesan di room
+// anonim tidak bisa dihubungkan ke pengirimnya, juga tidak antar rotasi. Nama boleh kebetulan sama; id tidak.
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use chat_protocol::Pseudonym;
+
+const ANIMALS: [&str; 16] = [
+    "Kancil", "Elang", "Kucing", "Rusa", "Paus", "Merak", "Badak", "Penyu", "Kakatua", "Harimau", "Lumba-lumba", "Beruang", "Jerapah", "Rubah", "Bangau", "Tupai",
+];
+const COLORS: [&str; 12] = ["Merah", "Biru", "Hijau", "Jingga", "Ungu", "Emas", "Perak", "Kelabu", "Nila", "Cokelat", "Putih", "Hitam"];
+
+pub fn generate() -> Pseudonym {
+    let random = OsRng.next_u64();
+    let animal = ANIMALS[(random % ANIMALS.len() as u64) as usize];
+    let color = COLORS[((random >> 8) % COLORS.len() as u64) as usize];
+    let number = 10 + (random >> 16) % 90;
+    Pseudonym { id: format!("anon-{:016x}", OsRng.next_u64()), name: format!("{} {} {}", animal, color, number) }
+}
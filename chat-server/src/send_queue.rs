@@ -0,0 +1,249 @@
+// chat-server/src/send_queue.rs
+// Antrean kirim satu koneksi dengan batas, pengganti channel tanpa batas. Hub mengisi antrean secara
+// sinkron; task tulis di session.rs mengosongkannya secepat socket mau menerima. Jika klien lambat:
+//
+//   1. di atas `presence_after` frame, event presence digabung per user (versi lama dibuang, yang terbaru
+//      masuk di ujung antrean agar urutannya tetap benar) atau dibuang; koneksi ditandai perlu Roster
+//      baru setelah antreannya surut (Hub::resync_presence)
+//   2. event presence yang melewati `max_frames`/`max_bytes` dibuang, tidak pernah memutus koneksi
+//   3. sebelum frame penting ditolak, event presence yang masih antre dibuang lebih dulu
+//   4. jika tetap melewati `max_frames`/`max_bytes`, antrean dibatalkan dan session memutus koneksinya
+//
+// Pesan chat dan balasan perintah tidak pernah dibuang diam-diam: klien menerimanya utuh atau terputus
+// lalu memulihkannya lewat Resume.
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use chat_protocol::PresenceEvent;
+use tokio::sync::{watch, Notify};
+
+use crate::config::SendQueueConfig;
+
+// Penghitung bersama semua antrean, untuk API admin
+#[derive(Default)]
+pub struct QueueMetrics {
+    pub dropped: AtomicU64,
+    pub coalesced: AtomicU64,
+    pub overflowed: AtomicU64,
+}
+
+pub enum Frame {
+    Critical(String),
+    Presence { text: String, key: String }, // Event dengan `key` yang sama saling menggantikan
+}
+
+impl Frame {
+    pub fn presence(event: &PresenceEvent, text: String) -> Self {
+        let key = match event {
+            PresenceEvent::StatusChanged { user_id, .. } => format!("status:{}", user_id),
+            PresenceEvent::CustomStatusChanged { user_id, .. } => format!("custom:{}", user_id),
+            PresenceEvent::Presence { user_id, .. } => format!("online:{}", user_id),
+            // Masuk lalu keluar room: cukup kirim yang terakhir
+            PresenceEvent::UserJoined { room, user } => format!("member:{}:{}", room, user.user_id),
+            PresenceEvent::UserLeft { room, user_id } => format!("member:{}:{}", room, user_id),
+            PresenceEvent::Roster { .. } | PresenceEvent::PresenceDigest { .. } => return Frame::Critical(text),
+        };
+        Frame::Presence { text, key }
+    }
+}
+
+struct Queued {
+    text: String,
+    key: Option<String>, // None = frame penting
+}
+
+#[derive(Default)]
+struct State {
+    frames: VecDeque<Queued>,
+    bytes: usize,
+    peak: usize,
+    closed: bool, // Tidak menerima frame baru; sisa antrean tetap dikirim
+    presence_stale: bool, // Ada event presence yang dibuang; klien butuh Roster baru
+}
+
+pub struct SendQueue {
+    state: Mutex<State>,
+    ready: Notify, // Satu konsumen (task tulis), jadi notify_one tidak pernah hilang
+    aborted: watch::Sender<bool>,
+    limits: SendQueueConfig,
+    metrics: Arc<QueueMetrics>,
+}
+
+// Ringkasan satu antrean untuk API admin
+pub struct Depth {
+    pub frames: usize,
+    pub bytes: usize,
+    pub peak: usize,
+}
+
+impl SendQueue {
+    pub fn new(limits: SendQueueConfig, metrics: Arc<QueueMetrics>) -> Self {
+        Self { state: Mutex::default(), ready: Notify::new(), aborted: watch::Sender::new(false), limits, metrics }
+    }
+
+    // Frame untuk koneksi yang sudah ditutup atau dibatalkan diabaikan
+    pub fn push(&self, frame: Frame) {
+        if self.is_aborted() {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        if state.closed {
+            return;
+        }
+        let queued = match frame {
+            Frame::Critical(text) => Queued { text, key: None },
+            Frame::Presence { text, key } if state.frames.len() < self.limits.presence_after => Queued { text, key: Some(key) },
+            Frame::Presence { text, key } => {
+                // Versi lama dikeluarkan dari posisinya; event lain yang antre setelahnya tidak boleh terlihat lebih baru
+                let Some(index) = state.frames.iter().position(|queued| queued.key.as_ref() == Some(&key)) else {
+                    state.presence_stale = true;
+                    self.metrics.dropped.fetch_add(1, Ordering::Relaxed);
+                    return;
+                };
+                if let Some(existing) = state.frames.remove(index) {
+                    state.bytes -= existing.text.len();
+                }
+                self.metrics.coalesced.fetch_add(1, Ordering::Relaxed);
+                Queued { text, key: Some(key) }
+            }
+        };
+        if self.over_limit(&state, queued.text.len()) {
+            if queued.key.is_some() {
+                state.presence_stale = true;
+                self.metrics.dropped.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+            self.drop_presence(&mut state);
+            if self.over_limit(&state, queued.text.len()) {
+                state.frames.clear();
+                state.bytes = 0;
+                drop(state);
+                self.metrics.overflowed.fetch_add(1, Ordering::Relaxed);
+                self.aborted.send_replace(true);
+                self.ready.notify_one(); // Bangunkan task tulis agar berhenti
+                return;
+            }
+        }
+        state.bytes += queued.text.len();
+        state.frames.push_back(queued);
+        state.peak = state.peak.max(state.frames.len());
+        drop(state);
+        self.ready.notify_one();
+    }
+
+    // Frame berikutnya untuk task tulis; None setelah antrean ditutup dan kosong, atau dibatalkan
+    pub async fn pop(&self) -> Option<String> {
+        loop {
+            {
+                let mut state = self.state.lock().unwrap();
+                if self.is_aborted() {
+                    return None;
+                }
+                if let Some(queued) = state.frames.pop_front() {
+                    state.bytes -= queued.text.len();
+                    return Some(queued.text);
+                }
+                if state.closed {
+                    return None;
+                }
+            }
+            self.ready.notified().await;
+        }
+    }
+
+    // Hub melepas koneksi ini: kirim yang tersisa lalu tutup socket
+    pub fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.ready.notify_one();
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        *self.aborted.borrow()
+    }
+
+    // Selesai saat koneksi dibatalkan karena terlalu lambat
+    pub async fn aborted(&self) {
+        let mut aborted = self.aborted.subscribe();
+        let _ = aborted.wait_for(|aborted| *aborted).await;
+    }
+
+    // true sekali jika presence pernah dibuang dan antrean sudah cukup surut untuk menerima Roster baru
+    pub fn take_stale_presence(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let stale = state.presence_stale && state.frames.len() < self.limits.presence_after;
+        if stale {
+            state.presence_stale = false;
+        }
+        stale
+    }
+
+    pub fn depth(&self) -> Depth {
+        let state = self.state.lock().unwrap();
+        Depth { frames: state.frames.len(), bytes: state.bytes, peak: state.peak }
+    }
+
+    // Antrean kosong selalu menerima satu frame, sebesar apa pun (mis. riwayat room dengan lampiran)
+    fn over_limit(&self, state: &State, incoming: usize) -> bool {
+        !state.frames.is_empty() && (state.frames.len() >= self.limits.max_frames || state.bytes + incoming > self.limits.max_bytes)
+    }
+
+    fn drop_presence(&self, state: &mut State) {
+        let before = state.frames.len();
+        state.frames.retain(|queued| queued.key.is_none());
+        let dropped = before - state.frames.len();
+        if dropped > 0 {
+            state.bytes = state.frames.iter().map(|queued| queued.text.len()).sum();
+            state.presence_stale = true;
+            self.metrics.dropped.fetch_add(dropped as u64, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::FutureExt;
+
+    use super::*;
+
+    fn queue(max_frames: usize, presence_after: usize) -> SendQueue {
+        SendQueue::new(SendQueueConfig { max_frames, max_bytes: 1024, presence_after }, Arc::default())
+    }
+
+    fn presence(key: &str, text: &str) -> Frame {
+        Frame::Presence { text: text.to_string(), key: key.to_string() }
+    }
+
+    fn drain(queue: &SendQueue) -> Vec<String> {
+        let mut texts = Vec::new();
+        while let Some(Some(text)) = queue.pop().now_or_never() {
+            texts.push(text);
+        }
+        texts
+    }
+
+    #[test]
+    fn coalesced_presence_moves_to_the_end() {
+        let queue = queue(8, 1);
+        queue.push(presence("status:a", "a away"));
+        queue.push(Frame::Critical("pesan".to_string()));
+        queue.push(presence("status:a", "a online"));
+        assert_eq!(drain(&queue), ["pesan", "a online"]);
+        assert_eq!(queue.metrics.coalesced.load(Ordering::Relaxed), 1);
+        assert_eq!(queue.depth().bytes, 0);
+    }
+
+    #[test]
+    fn presence_over_the_limit_is_dropped_without_aborting() {
+        let queue = queue(2, 4); // presence_after di atas max_frames: batas frame yang menahan presence
+        queue.push(Frame::Critical("1".to_string()));
+        queue.push(presence("status:a", "a"));
+        queue.push(presence("status:b", "b"));
+        assert!(!queue.is_aborted());
+        assert_eq!(queue.depth().frames, 2);
+        assert!(queue.take_stale_presence());
+
+        queue.push(Frame::Critical("2".to_string())); // Presence yang antre dibuang dulu
+        assert_eq!(drain(&queue), ["1", "2"]);
+    }
+}
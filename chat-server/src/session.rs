@@ -0,0 +1,178 @@
+// chat-server/src/session.rs
+// Satu koneksi WebSocket: handshake, loop baca yang meneruskan frame ke Hub, dan task tulis yang
+// mengosongkan antrean kirim koneksi ini. Kunci Hub hanya dipegang selama satu frame diproses; Argon2 untuk
+// kata sandi room dijalankan di thread blocking tanpa kunci, dan frame berikutnya baru dibaca setelah selesai.
+// Jika antrean meluap (send_queue.rs) kedua loop berhenti tanpa menunggu socket, lalu koneksi dilepas.
+// Koneksi ke /admin diserahkan ke admin.rs setelah handshake. Stream bisa TCP biasa atau TLS (tls.rs).
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::{header, StatusCode};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::admin::{self, ADMIN_PATH};
+use crate::hub::Hub;
+
+const WS_PATH: &str = "/ws"; // Path default klien (config.rs)
+
+// Aturan handshake yang sama untuk semua koneksi, disiapkan sekali di main.rs
+pub struct Gate {
+    pub admin_token: Option<String>,
+    pub allowed_origins: Vec<String>, // Kosong = semua origin diterima
+}
+
+pub async fn run<S>(stream: S, peer: SocketAddr, hub: Arc<Mutex<Hub>>, gate: Arc<Gate>)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let mut token = None;
+    let mut device = None;
+    let mut is_admin = false;
+    #[allow(clippy::result_large_err)] // Sama seperti check_path
+    let handshake = |request: &Request, response: Response| {
+        if request.uri().path() == ADMIN_PATH {
+            is_admin = true;
+            return admin::check_request(request, response, gate.admin_token.as_deref());
+        }
+        check_origin(request, &gate.allowed_origins)?;
+        token = query_token(request);
+        device = request.headers().get(header::USER_AGENT).and_then(|agent| agent.to_str().ok()).map(device_label);
+        check_path(request, response)
+    };
+    let socket = match tokio_tungstenite::accept_hdr_async(stream, handshake).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            log::warn!("Handshake dari {} gagal: {}", peer, e);
+            return;
+        }
+    };
+    if is_admin {
+        admin::serve(socket, peer, hub).await;
+        return;
+    }
+    let (mut write, mut read) = socket.split();
+    let device = device.unwrap_or_else(|| "Perangkat tidak dikenal".to_string());
+    let (id, queue) = hub.lock().unwrap().connect(token, device);
+    log::info!("Koneksi {} terbuka dari {}", id, peer);
+
+    let outbox = queue.clone();
+    let writer = tokio::spawn(async move {
+        while let Some(text) = outbox.pop().await {
+            tokio::select! {
+                sent = write.send(Message::Text(text)) => {
+                    if sent.is_err() {
+                        return; // Koneksi sudah putus; loop baca akan menyadarinya
+                    }
+                }
+                _ = outbox.aborted() => return, // Socket yang macet tidak ditunggu
+            }
+        }
+        if !outbox.is_aborted() {
+            let _ = write.close().await; // Antrean ditutup Hub (mis. diblokir admin); putuskan juga socketnya
+        }
+    });
+
+    loop {
+        let frame = tokio::select! {
+            frame = read.next() => frame,
+            _ = queue.aborted() => {
+                log::warn!("Koneksi {} terlalu lambat membaca; antrean kirim meluap dan koneksi diputus", id);
+                break;
+            }
+        };
+        let Some(frame) = frame else { break };
+        let task = match frame {
+            Ok(Message::Text(text)) => hub.lock().unwrap().handle_frame(id, &text),
+            Ok(Message::Binary(bytes)) => hub.lock().unwrap().handle_binary(id, &bytes),
+            Ok(Message::Close(_)) => break,
+            Ok(_) => None, // Ping/pong dijawab tungstenite
+            Err(e) => {
+                log::info!("Koneksi {} error: {}", id, e);
+                break;
+            }
+        };
+        // Frame berikutnya dari koneksi ini baru dibaca setelah tugasnya selesai, jadi urutan frame tetap terjaga
+        let Some(task) = task else { continue };
+        match tokio::task::spawn_blocking(move || task.run()).await {
+            Ok(outcome) => hub.lock().unwrap().finish_task(id, outcome),
+            Err(e) => log::error!("Tugas blocking untuk koneksi {} gagal: {}", id, e),
+        }
+    }
+
+    hub.lock().unwrap().disconnect(id);
+    writer.abort();
+    log::info!("Koneksi {} ditutup", id);
+}
+
+// Token dari `?token=...` untuk klien yang tidak bisa mengirim frame Authenticate lebih dulu
+fn query_token(request: &Request) -> Option<String> {
+    let query = request.uri().query()?;
+    let value = query.split('&').find_map(|pair| pair.strip_prefix("token="))?;
+    Some(percent_decode(value)).filter(|token| !token.is_empty())
+}
+
+// Cukup untuk hasil encodeURIComponent; urutan byte yang bukan UTF-8 valid diganti U+FFFD
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|pair| std::str::from_utf8(pair).ok()).and_then(|pair| u8::from_str_radix(pair, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+// Ringkasan untuk daftar sesi, mis. "Firefox di Linux"; klien non-browser memakai User-Agent apa adanya
+fn device_label(agent: &str) -> String {
+    const BROWSERS: [(&str, &str); 5] = [("Edg/", "Edge"), ("OPR/", "Opera"), ("Firefox/", "Firefox"), ("Chrome/", "Chrome"), ("Safari/", "Safari")];
+    const SYSTEMS: [(&str, &str); 6] = [("Android", "Android"), ("iPhone", "iOS"), ("iPad", "iPadOS"), ("Windows", "Windows"), ("Mac OS X", "macOS"), ("Linux", "Linux")];
+    let find = |table: &[(&str, &'static str)]| table.iter().find(|(marker, _)| agent.contains(marker)).map(|(_, name)| *name);
+    match (find(&BROWSERS), find(&SYSTEMS)) {
+        (Some(browser), Some(system)) => format!("{} di {}", browser, system),
+        (Some(name), None) | (None, Some(name)) => name.to_string(),
+        (None, None) => agent.chars().take(60).collect(),
+    }
+}
+
+// Browser selalu mengirim Origin, jadi halaman lain tidak bisa membuka koneksi atas nama user (mirip CSRF).
+// Klien non-browser (chat_conformance, bot) biasanya tanpa Origin dan tetap diterima.
+#[allow(clippy::result_large_err)] // Sama seperti check_path
+fn check_origin(request: &Request, allowed: &[String]) -> Result<(), ErrorResponse> {
+    let Some(origin) = request.headers().get(header::ORIGIN) else { return Ok(()) };
+    let origin = origin.to_str().unwrap_or_default();
+    if allowed.is_empty() || allowed.iter().any(|allowed| allowed.eq_ignore_ascii_case(origin)) {
+        return Ok(());
+    }
+    log::warn!("Handshake dari origin {:?} ditolak", origin);
+    let mut error = ErrorResponse::new(Some(format!("Origin {} tidak diizinkan", origin)));
+    *error.status_mut() = StatusCode::FORBIDDEN;
+    Err(error)
+}
+
+// Hanya path /ws yang di-upgrade, sama seperti server yang diharapkan klien
+#[allow(clippy::result_large_err)] // Tipe error ditentukan callback handshake tungstenite
+fn check_path(request: &Request, response: Response) -> Result<Response, ErrorResponse> {
+    if request.uri().path() == WS_PATH {
+        return Ok(response);
+    }
+    let mut error = ErrorResponse::new(Some(format!("Gunakan path {}", WS_PATH)));
+    *error.status_mut() = StatusCode::NOT_FOUND;
+    Err(error)
+}
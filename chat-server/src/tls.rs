@@ -0,0 +1,40 @@
+// chat-server/src/tls.rs
+// TLS langsung di server (wss://) dengan rustls, agar deployment kecil tidak butuh reverse proxy hanya
+// untuk sertifikat. Sertifikat dan kunci dibaca sekali saat start dari bagian [tls] konfigurasi;
+// mengganti sertifikat berarti me-restart server.
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+
+use crate::config::TlsConfig;
+
+pub fn acceptor(config: &TlsConfig) -> Result<TlsAcceptor, String> {
+    let certs = load_certs(&config.cert)?;
+    let key = load_key(&config.key)?;
+    let server = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| format!("sertifikat {} tidak cocok dengan kunci {}: {}", config.cert.display(), config.key.display(), e))?;
+    Ok(TlsAcceptor::from(Arc::new(server)))
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, String> {
+    let file = File::open(path).map_err(|e| format!("tidak bisa membuka {}: {}", path.display(), e))?;
+    let certs = rustls_pemfile::certs(&mut BufReader::new(file)).collect::<Result<Vec<_>, _>>().map_err(|e| format!("{} bukan PEM yang valid: {}", path.display(), e))?;
+    if certs.is_empty() {
+        return Err(format!("{} tidak berisi sertifikat", path.display()));
+    }
+    Ok(certs)
+}
+
+fn load_key(path: &Path) -> Result<PrivateKeyDer<'static>, String> {
+    let file = File::open(path).map_err(|e| format!("tidak bisa membuka {}: {}", path.display(), e))?;
+    rustls_pemfile::private_key(&mut BufReader::new(file))
+        .map_err(|e| format!("{} bukan PEM yang valid: {}", path.display(), e))?
+        .ok_or_else(|| format!("{} tidak berisi kunci privat", path.display()))
+}
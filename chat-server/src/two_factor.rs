@@ -0,0 +1,172 @@
+// chat-server/src/two_factor.rs
+// Verifikasi dua langkah untuk akun dari CHAT_AUTH_TOKENS: TOTP (RFC 6238, HMAC-SHA1, 6 digit, langkah
+// 30 detik) yang cocok dengan aplikasi authenticator umum, plus kode pemulihan sekali pakai. Rahasia
+// dibuat server saat StartTwoFactorSetup dan baru berlaku setelah kode pertama dikonfirmasi. Kode
+// pemulihan hanya disimpan sebagai hash. Dengan backend file semuanya ikut disimpan (history_file.rs);
+// dengan backend memory 2FA hilang saat server restart.
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+
+const ISSUER: &str = "chat-server"; // Nama yang tampil di aplikasi authenticator
+const STEP_SECS: u64 = 30;
+const DIGITS: u32 = 6;
+const SKEW_STEPS: u64 = 1; // Jam ponsel boleh meleset satu langkah ke depan atau ke belakang
+const SECRET_BYTES: usize = 20; // 160 bit, ukuran yang disarankan RFC 4226 untuk HMAC-SHA1
+const RECOVERY_CODES: usize = 10;
+const BASE32: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TwoFactor {
+    secret: Vec<u8>,
+    recovery: Vec<String>, // Hash SHA-1 (hex) kode pemulihan yang belum dipakai
+    #[serde(default)]
+    last_step: u64, // Langkah TOTP terakhir yang diterima; kode yang sama tidak bisa dipakai ulang
+}
+
+// Rahasia baru yang belum dikonfirmasi, beserta teks yang ditampilkan sekali ke user
+pub struct Enrollment {
+    pub secret: String, // Base32, untuk diketik manual
+    pub otpauth_url: String, // Untuk QR code
+    pub recovery_codes: Vec<String>,
+    pub pending: TwoFactor,
+}
+
+pub fn enroll(user_id: &str) -> Enrollment {
+    let mut secret = vec![0; SECRET_BYTES];
+    OsRng.fill_bytes(&mut secret);
+    let recovery_codes: Vec<String> = (0..RECOVERY_CODES).map(|_| recovery_code()).collect();
+    let encoded = base32(&secret);
+    let label = format!("{}:{}", ISSUER, user_id).replace(' ', "%20");
+    let otpauth_url = format!("otpauth://totp/{}?secret={}&issuer={}&digits={}&period={}", label, encoded, ISSUER, DIGITS, STEP_SECS);
+    let recovery = recovery_codes.iter().map(|code| hash_recovery(code)).collect();
+    Enrollment { secret: encoded, otpauth_url, recovery_codes, pending: TwoFactor { secret, recovery, last_step: 0 } }
+}
+
+impl TwoFactor {
+    // Salah satu dari kode TOTP atau kode pemulihan; kode pemulihan yang cocok langsung dibuang
+    pub fn verify(&mut self, code: Option<&str>, recovery_code: Option<&str>) -> bool {
+        match (code, recovery_code) {
+            (Some(code), _) => self.check_code(code, now_secs()),
+            (None, Some(recovery_code)) => self.use_recovery_code(recovery_code),
+            (None, None) => false,
+        }
+    }
+
+    fn check_code(&mut self, code: &str, now: u64) -> bool {
+        let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+        if code.len() != DIGITS as usize {
+            return false;
+        }
+        let Ok(code) = code.parse::<u32>() else { return false };
+        let current = now / STEP_SECS;
+        let accepted = (current.saturating_sub(SKEW_STEPS)..=current + SKEW_STEPS).find(|step| *step > self.last_step && totp(&self.secret, *step) == code);
+        match accepted {
+            Some(step) => {
+                self.last_step = step;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn use_recovery_code(&mut self, code: &str) -> bool {
+        let hash = hash_recovery(code);
+        let before = self.recovery.len();
+        self.recovery.retain(|known| *known != hash);
+        self.recovery.len() < before
+    }
+
+    #[cfg(test)]
+    pub fn current_code(&self) -> String {
+        format!("{:06}", totp(&self.secret, now_secs() / STEP_SECS))
+    }
+}
+
+fn totp(secret: &[u8], step: u64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC menerima kunci sepanjang apa pun");
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+    let offset = usize::from(digest[digest.len() - 1] & 0x0f);
+    let truncated = u32::from_be_bytes([digest[offset], digest[offset + 1], digest[offset + 2], digest[offset + 3]]) & 0x7fff_ffff;
+    truncated % 10u32.pow(DIGITS)
+}
+
+// Sepuluh karakter base32 (50 bit), dibagi dua agar mudah disalin, mis. "k3vq7-mzx2a"
+fn recovery_code() -> String {
+    let mut bytes = [0; 7];
+    OsRng.fill_bytes(&mut bytes);
+    let code = base32(&bytes).to_lowercase();
+    format!("{}-{}", &code[..5], &code[5..10])
+}
+
+// Kode pemulihan acak dan panjang, jadi hash cepat sudah cukup; tanda hubung dan huruf besar diabaikan
+fn hash_recovery(code: &str) -> String {
+    let normalized: String = code.chars().filter(char::is_ascii_alphanumeric).map(|c| c.to_ascii_lowercase()).collect();
+    Sha1::digest(normalized.as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// RFC 4648 tanpa padding, format rahasia yang diharapkan aplikasi authenticator
+fn base32(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for byte in bytes {
+        buffer = (buffer << 8) | u32::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(char::from(BASE32[((buffer >> bits) & 0x1f) as usize]));
+        }
+    }
+    if bits > 0 {
+        encoded.push(char::from(BASE32[((buffer << (5 - bits)) & 0x1f) as usize]));
+    }
+    encoded
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RFC_SECRET: &[u8] = b"12345678901234567890"; // Kunci uji SHA-1 dari RFC 6238 lampiran B
+
+    #[test]
+    fn totp_matches_rfc_6238_vectors() {
+        assert_eq!(totp(RFC_SECRET, 59 / STEP_SECS), 287_082);
+        assert_eq!(totp(RFC_SECRET, 1_111_111_109 / STEP_SECS), 81_804);
+        assert_eq!(totp(RFC_SECRET, 2_000_000_000 / STEP_SECS), 279_037);
+    }
+
+    #[test]
+    fn codes_cannot_be_replayed() {
+        let mut factor = TwoFactor { secret: RFC_SECRET.to_vec(), recovery: Vec::new(), last_step: 0 };
+        let now = 1_111_111_109;
+        assert!(factor.check_code("081 804", now));
+        assert!(!factor.check_code("081804", now));
+        assert!(!factor.check_code(&format!("{:06}", totp(RFC_SECRET, now / STEP_SECS - 1)), now)); // Langkah lama
+        assert!(factor.check_code(&format!("{:06}", totp(RFC_SECRET, now / STEP_SECS + 1)), now));
+    }
+
+    #[test]
+    fn recovery_codes_work_once() {
+        let mut enrollment = enroll("u-alice");
+        let code = enrollment.recovery_codes[3].clone();
+        assert!(enrollment.pending.verify(None, Some(&code.to_uppercase())));
+        assert!(!enrollment.pending.verify(None, Some(&code)));
+        assert_eq!(enrollment.pending.recovery.len(), RECOVERY_CODES - 1);
+        assert!(enrollment.otpauth_url.starts_with("otpauth://totp/chat-server:u-alice?secret="));
+    }
+
+    #[test]
+    fn base32_follows_rfc_4648() {
+        assert_eq!(base32(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(base32(b""), "");
+    }
+}
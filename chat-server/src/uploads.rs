@@ -0,0 +1,325 @@
+// chat-server/src/uploads.rs
+// Upload lampiran bertahap: UploadStart, lalu chunk (frame biner UploadChunkFrame, atau UploadChunk
+// base64 dari klien lama), sampai semua byte diterima dan server membalas UploadComplete. Upload
+// disimpan per pemilik (akun atau identitas user, lihat Hub::owner), bukan per koneksi, sehingga
+// bisa dilanjutkan setelah reconnect; koneksi lain tidak bisa menulis ke atau melanjutkan upload itu.
+//
+// Server ini tidak punya endpoint HTTP, jadi file yang selesai dikembalikan sebagai data: URL dan ikut
+// tersimpan di riwayat room. Karena itu ukurannya dibatasi MAX_UPLOAD_SIZE, dan jumlah serta total ukuran
+// upload yang belum selesai per pemilik juga dibatasi.
+//
+// Validasi mengikuti UploadConfig: tipe yang diumumkan di UploadStart harus ada di `allowed_types`, dan
+// setelah semua byte diterima isinya harus cocok dengan tipe itu menurut magic byte. Jika `scan_command`
+// diisi, file lalu dipindai (lihat scan) di thread blocking sebelum UploadComplete dikirim.
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use base64::Engine as _;
+use chat_protocol::UploadRejection;
+
+use crate::config::UploadConfig;
+
+pub const MAX_UPLOAD_SIZE: u64 = 8 * 1024 * 1024;
+const MAX_PENDING_PER_OWNER: usize = 4;
+const MAX_PENDING_BYTES_PER_OWNER: u64 = 2 * MAX_UPLOAD_SIZE; // Dihitung dari ukuran yang diumumkan di UploadStart
+const STALE_AFTER: Duration = Duration::from_secs(30 * 60); // Upload yang tidak dilanjutkan selama ini dibuang
+const SCAN_TIMEOUT: Duration = Duration::from_secs(60); // Pemindai yang lebih lama dianggap tidak tersedia
+const UNKNOWN_TYPE: &str = "application/octet-stream"; // Untuk isi yang tidak dikenali atau tipe kosong dari browser
+
+struct PendingUpload {
+    mime: String,
+    size: u64,
+    data: Vec<u8>,
+    touched: Instant,
+}
+
+pub enum Progress {
+    Offset(u64),          // Diterima sampai offset ini; klien mengirim chunk berikutnya dari sini
+    Complete(Attachment), // Semua byte diterima dan tipenya sudah diperiksa
+}
+
+// Upload yang lengkap dan lolos validasi tipe; belum dipindai
+pub struct Attachment {
+    mime: String,
+    data: Vec<u8>,
+}
+
+impl Attachment {
+    pub fn into_url(self) -> String {
+        format!("data:{};base64,{}", self.mime, base64::engine::general_purpose::STANDARD.encode(&self.data))
+    }
+
+    // Jalankan `command` dengan isi file di stdin, dengan konvensi exit code clamdscan/clamscan:
+    // 0 = bersih, 1 = terinfeksi (nama signature dari baris "...: NAMA FOUND" di stdout), selain itu,
+    // gagal dijalankan atau melewati SCAN_TIMEOUT = ScanUnavailable. Memblokir; jalankan di luar kunci Hub.
+    pub fn scan(&self, command: &[String]) -> Result<(), UploadRejection> {
+        let Some((program, args)) = command.split_first() else { return Ok(()) };
+        let mut child = Command::new(program).args(args).stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::null()).spawn().map_err(|e| {
+            log::error!("Pemindai upload {} tidak bisa dijalankan: {}", program, e);
+            UploadRejection::ScanUnavailable
+        })?;
+        // Ditulis dari thread lain agar pemindai yang membaca sambil menulis stdout tidak macet
+        let (mut stdin, data) = (child.stdin.take(), self.data.clone());
+        let writer = std::thread::spawn(move || stdin.as_mut().map(|stdin| stdin.write_all(&data)));
+        let started = Instant::now();
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if started.elapsed() < SCAN_TIMEOUT => std::thread::sleep(Duration::from_millis(20)),
+                Ok(None) => {
+                    log::error!("Pemindai upload {} tidak selesai dalam {} detik", program, SCAN_TIMEOUT.as_secs());
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(UploadRejection::ScanUnavailable);
+                }
+                Err(e) => {
+                    log::error!("Gagal menunggu pemindai upload {}: {}", program, e);
+                    return Err(UploadRejection::ScanUnavailable);
+                }
+            }
+        };
+        let _ = writer.join();
+        let mut output = String::new();
+        if let Some(mut stdout) = child.stdout.take() {
+            let _ = stdout.read_to_string(&mut output);
+        }
+        match status.code() {
+            Some(0) => Ok(()),
+            Some(1) => Err(UploadRejection::Infected { signature: signature(&output) }),
+            code => {
+                log::error!("Pemindai upload {} gagal (exit {:?}): {}", program, code, output.trim());
+                Err(UploadRejection::ScanUnavailable)
+            }
+        }
+    }
+}
+
+pub struct Uploads {
+    pending: HashMap<String, HashMap<String, PendingUpload>>, // Pemilik -> upload_id -> upload
+    allowed_types: Vec<String>,
+    scan_command: Option<Vec<String>>,
+}
+
+impl Uploads {
+    pub fn new(config: &UploadConfig) -> Self {
+        Self { pending: HashMap::new(), allowed_types: config.allowed_types.clone(), scan_command: config.scan_command.clone() }
+    }
+
+    // None = upload tidak dipindai
+    pub fn scan_command(&self) -> Option<&[String]> {
+        self.scan_command.as_deref()
+    }
+
+    // Offset awal; UploadStart ulang untuk id yang sama dari pemilik yang sama dianggap melanjutkan
+    pub fn start(&mut self, owner: &str, upload_id: String, mime: String, size: u64) -> Result<u64, UploadRejection> {
+        self.prune();
+        if size > MAX_UPLOAD_SIZE {
+            return Err(UploadRejection::TooLarge { max_size: MAX_UPLOAD_SIZE });
+        }
+        if !self.allowed_types.contains(&mime) {
+            let detected = if mime.is_empty() { UNKNOWN_TYPE.to_string() } else { mime };
+            return Err(UploadRejection::TypeNotAllowed { detected });
+        }
+        let uploads = self.pending.entry(owner.to_string()).or_default();
+        if let Some(upload) = uploads.get_mut(&upload_id) {
+            upload.touched = Instant::now();
+            return Ok(upload.data.len() as u64);
+        }
+        let reserved: u64 = uploads.values().map(|upload| upload.size).sum();
+        if uploads.len() >= MAX_PENDING_PER_OWNER || reserved + size > MAX_PENDING_BYTES_PER_OWNER {
+            return Err(UploadRejection::TooManyPending);
+        }
+        uploads.insert(upload_id, PendingUpload { mime, size, data: Vec::new(), touched: Instant::now() });
+        Ok(0)
+    }
+
+    pub fn offset(&mut self, owner: &str, upload_id: &str) -> Option<u64> {
+        self.prune();
+        self.pending.get(owner)?.get(upload_id).map(|upload| upload.data.len() as u64)
+    }
+
+    // None jika upload tidak dikenal (mis. server di-restart atau milik pemilik lain). Chunk yang tidak
+    // tepat di offset saat ini diabaikan; balasan offset membuat klien mengirim ulang dari posisi yang benar.
+    pub fn write(&mut self, owner: &str, upload_id: &str, offset: u64, bytes: &[u8]) -> Option<Result<Progress, UploadRejection>> {
+        self.prune();
+        let uploads = self.pending.get_mut(owner)?;
+        let upload = uploads.get_mut(upload_id)?;
+        upload.touched = Instant::now();
+        let received = upload.data.len() as u64;
+        if offset != received {
+            return Some(Ok(Progress::Offset(received)));
+        }
+        if received + bytes.len() as u64 > upload.size {
+            self.remove(owner, upload_id);
+            return Some(Err(UploadRejection::TooLarge { max_size: MAX_UPLOAD_SIZE }));
+        }
+        upload.data.extend_from_slice(bytes);
+        if (upload.data.len() as u64) < upload.size {
+            return Some(Ok(Progress::Offset(upload.data.len() as u64)));
+        }
+        let upload = self.remove(owner, upload_id)?;
+        Some(check_type(&upload.mime, &upload.data).map(|()| Progress::Complete(Attachment { mime: upload.mime, data: upload.data })))
+    }
+
+    pub fn cancel(&mut self, owner: &str, upload_id: &str) {
+        self.remove(owner, upload_id);
+    }
+
+    // Semua upload milik `owner`; dipakai saat koneksi tanpa identitas ditutup
+    pub fn forget(&mut self, owner: &str) {
+        self.pending.remove(owner);
+    }
+
+    fn remove(&mut self, owner: &str, upload_id: &str) -> Option<PendingUpload> {
+        let uploads = self.pending.get_mut(owner)?;
+        let upload = uploads.remove(upload_id);
+        if uploads.is_empty() {
+            self.pending.remove(owner);
+        }
+        upload
+    }
+
+    fn prune(&mut self) {
+        for uploads in self.pending.values_mut() {
+            uploads.retain(|_, upload| upload.touched.elapsed() < STALE_AFTER);
+        }
+        self.pending.retain(|_, uploads| !uploads.is_empty());
+    }
+}
+
+// Isi harus dikenali sebagai tipe yang diumumkan. Tipe tanpa magic byte yang dikenal sniff (mis. text/plain
+// jika ditambahkan ke allowed_types) diterima asalkan isinya tidak terdeteksi sebagai format lain.
+fn check_type(declared: &str, data: &[u8]) -> Result<(), UploadRejection> {
+    let detected = sniff(data);
+    if detected.contains(&declared) || (detected.is_empty() && !has_magic(declared)) {
+        return Ok(());
+    }
+    let detected = detected.first().copied().unwrap_or(UNKNOWN_TYPE);
+    Err(UploadRejection::TypeMismatch { declared: declared.to_string(), detected: detected.to_string() })
+}
+
+fn has_magic(mime: &str) -> bool {
+    matches!(
+        mime,
+        "image/png" | "image/jpeg" | "image/gif" | "image/webp" | "video/mp4" | "audio/mp4" | "video/webm" | "audio/webm" | "video/ogg" | "audio/ogg" | "audio/mpeg" | "application/pdf"
+    )
+}
+
+// "stream: Eicar-Test-Signature FOUND" -> "Eicar-Test-Signature"
+fn signature(output: &str) -> Option<String> {
+    output.lines().find_map(|line| line.trim().strip_suffix(" FOUND")?.rsplit(": ").next()).map(str::to_string)
+}
+
+// Tipe yang cocok dengan magic byte di awal file; daftar tipenya sama dengan has_magic
+fn sniff(data: &[u8]) -> &'static [&'static str] {
+    match data {
+        [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n', ..] => &["image/png"],
+        [0xff, 0xd8, 0xff, ..] => &["image/jpeg"],
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => &["image/gif"],
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => &["image/webp"],
+        [_, _, _, _, b'f', b't', b'y', b'p', ..] => &["video/mp4", "audio/mp4"],
+        [0x1a, 0x45, 0xdf, 0xa3, ..] => &["video/webm", "audio/webm"],
+        [b'O', b'g', b'g', b'S', ..] => &["video/ogg", "audio/ogg"],
+        [b'I', b'D', b'3', ..] => &["audio/mpeg"],
+        [0xff, sync, ..] if sync & 0xe0 == 0xe0 => &["audio/mpeg"], // Frame MPEG audio tanpa tag ID3
+        [b'%', b'P', b'D', b'F', b'-', ..] => &["application/pdf"],
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG: &[u8] = &[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n', 0, 0];
+
+    fn uploads() -> Uploads {
+        Uploads::new(&UploadConfig::default())
+    }
+
+    fn complete(uploads: &mut Uploads, owner: &str, mime: &str, data: &[u8]) -> Result<String, UploadRejection> {
+        uploads.start(owner, "u1".to_string(), mime.to_string(), data.len() as u64)?;
+        match uploads.write(owner, "u1", 0, data) {
+            Some(Ok(Progress::Complete(attachment))) => Ok(attachment.into_url()),
+            Some(Err(reason)) => Err(reason),
+            _ => panic!("upload belum selesai"),
+        }
+    }
+
+    fn mismatch(declared: &str, detected: &str) -> Result<String, UploadRejection> {
+        Err(UploadRejection::TypeMismatch { declared: declared.to_string(), detected: detected.to_string() })
+    }
+
+    #[test]
+    fn other_owner_cannot_write_or_resume() {
+        let mut uploads = uploads();
+        uploads.start("user:a", "u1".to_string(), "image/png".to_string(), 4).unwrap();
+        assert!(uploads.write("user:b", "u1", 0, b"abcd").is_none());
+        assert_eq!(uploads.offset("user:b", "u1"), None);
+        uploads.cancel("user:b", "u1");
+        assert!(matches!(uploads.write("user:a", "u1", 0, b"ab"), Some(Ok(Progress::Offset(2)))));
+        assert_eq!(uploads.start("user:a", "u1".to_string(), "image/png".to_string(), 4), Ok(2)); // Dilanjutkan
+    }
+
+    #[test]
+    fn pending_uploads_are_capped_per_owner() {
+        let mut uploads = uploads();
+        for n in 0..MAX_PENDING_PER_OWNER {
+            uploads.start("user:a", format!("u{}", n), "image/png".to_string(), 1).unwrap();
+        }
+        assert_eq!(uploads.start("user:a", "lagi".to_string(), "image/png".to_string(), 1), Err(UploadRejection::TooManyPending));
+        assert_eq!(uploads.start("user:b", "lagi".to_string(), "image/png".to_string(), 1), Ok(0));
+
+        uploads.start("user:c", "besar1".to_string(), "video/mp4".to_string(), MAX_UPLOAD_SIZE).unwrap();
+        uploads.start("user:c", "besar2".to_string(), "video/mp4".to_string(), MAX_UPLOAD_SIZE).unwrap();
+        assert_eq!(uploads.start("user:c", "besar3".to_string(), "video/mp4".to_string(), 1), Err(UploadRejection::TooManyPending));
+    }
+
+    #[test]
+    fn stale_uploads_are_pruned_on_any_call() {
+        let mut uploads = uploads();
+        uploads.start("user:a", "u1".to_string(), "image/png".to_string(), 4).unwrap();
+        uploads.pending.get_mut("user:a").unwrap().get_mut("u1").unwrap().touched = Instant::now() - STALE_AFTER;
+        assert_eq!(uploads.offset("user:a", "u1"), None);
+        assert!(uploads.pending.is_empty());
+    }
+
+    #[test]
+    fn declared_type_must_be_allowed_and_match_content() {
+        let mut uploads = uploads();
+        assert!(complete(&mut uploads, "user:a", "image/png", PNG).unwrap().starts_with("data:image/png;base64,"));
+        assert_eq!(complete(&mut uploads, "user:a", "image/png", b"<svg onload=alert(1)>"), mismatch("image/png", "application/octet-stream"));
+        assert_eq!(complete(&mut uploads, "user:a", "image/jpeg", PNG), mismatch("image/jpeg", "image/png"));
+        assert_eq!(complete(&mut uploads, "user:a", "text/html", PNG), Err(UploadRejection::TypeNotAllowed { detected: "text/html".to_string() }));
+        assert!(uploads.pending.is_empty());
+
+        // Tipe tanpa magic byte hanya lolos jika isinya tidak menyamar sebagai format lain
+        let mut uploads = Uploads::new(&UploadConfig { allowed_types: vec!["text/plain".to_string()], ..UploadConfig::default() });
+        assert!(complete(&mut uploads, "user:a", "text/plain", b"halo").is_ok());
+        assert_eq!(complete(&mut uploads, "user:a", "text/plain", PNG), mismatch("text/plain", "image/png"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_follows_clamdscan_exit_codes() {
+        let attachment = Attachment { mime: "application/pdf".to_string(), data: b"%PDF-1.7".to_vec() };
+        let shell = |script: &str| vec!["sh".to_string(), "-c".to_string(), script.to_string()];
+        assert_eq!(attachment.scan(&shell("cat >/dev/null")), Ok(()));
+        let infected = shell("cat >/dev/null; echo 'stream: Eicar-Test-Signature FOUND'; exit 1");
+        assert_eq!(attachment.scan(&infected), Err(UploadRejection::Infected { signature: Some("Eicar-Test-Signature".to_string()) }));
+        assert_eq!(attachment.scan(&shell("exit 2")), Err(UploadRejection::ScanUnavailable));
+        assert_eq!(attachment.scan(&["/tidak/ada/clamdscan".to_string()]), Err(UploadRejection::ScanUnavailable));
+    }
+
+    #[test]
+    fn sniff_recognizes_container_formats() {
+        assert_eq!(sniff(b"RIFF\0\0\0\0WEBPVP8 "), ["image/webp"]);
+        assert_eq!(sniff(b"\0\0\0\x18ftypmp42"), ["video/mp4", "audio/mp4"]);
+        assert_eq!(sniff(b"ID3\x04"), ["audio/mpeg"]);
+        assert_eq!(sniff(b"%PDF-1.7"), ["application/pdf"]);
+        assert!(sniff(b"RIFF\0\0\0\0WAVE").is_empty());
+        assert!(sniff(&[]).is_empty());
+    }
+}
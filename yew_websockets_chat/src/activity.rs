@@ -0,0 +1,84 @@
+// src/activity.rs
+use std::cell::Cell;
+use std::rc::Rc;
+
+use gloo_events::EventListener;
+use yew::Callback;
+
+// Event yang dianggap sebagai aktivitas user
+const ACTIVITY_EVENTS: [&str; 5] = ["pointerdown", "pointermove", "keydown", "wheel", "touchstart"];
+
+// Mencatat waktu interaksi terakhir user di halaman. Hanya menyimpan timestamp di Cell,
+// sehingga gerakan mouse tidak memicu pesan/re-render komponen.
+pub struct ActivityMonitor {
+    last_activity: Rc<Cell<f64>>, // Epoch millis
+    _listeners: Vec<EventListener>,
+}
+
+impl ActivityMonitor {
+    pub fn new() -> Self {
+        let last_activity = Rc::new(Cell::new(js_sys::Date::now()));
+        let listeners = match web_sys::window() {
+            Some(window) => ACTIVITY_EVENTS
+                .iter()
+                .map(|event| {
+                    let last_activity = last_activity.clone();
+                    EventListener::new(&window, *event, move |_| last_activity.set(js_sys::Date::now()))
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        Self { last_activity, _listeners: listeners }
+    }
+
+    // Lama user tidak berinteraksi, dalam detik
+    pub fn idle_secs(&self) -> u64 {
+        ((js_sys::Date::now() - self.last_activity.get()) / 1000.0).max(0.0) as u64
+    }
+}
+
+// Tab disembunyikan (pindah tab, jendela diminimalkan) atau terlihat lagi, dari event visibilitychange.
+// Listener berhenti saat nilai ini di-drop.
+pub struct PageVisibility {
+    _listener: Option<EventListener>,
+}
+
+impl PageVisibility {
+    pub fn new(on_change: Callback<bool>) -> Self {
+        let listener = web_sys::window().and_then(|w| w.document()).map(|document| {
+            let target = document.clone();
+            EventListener::new(&document, "visibilitychange", move |_| on_change.emit(target.hidden()))
+        });
+        Self { _listener: listener }
+    }
+}
+
+// Jendela mendapat atau kehilangan fokus (event focus/blur di window); true = fokus.
+// Tab bisa terlihat tetapi tidak fokus, mis. saat user bekerja di jendela lain di sebelahnya.
+pub struct WindowFocus {
+    _listeners: Vec<EventListener>,
+}
+
+impl WindowFocus {
+    pub fn new(on_change: Callback<bool>) -> Self {
+        let listeners = match web_sys::window() {
+            Some(window) => [("focus", true), ("blur", false)]
+                .into_iter()
+                .map(|(event, focused)| {
+                    let on_change = on_change.clone();
+                    EventListener::new(&window, event, move |_| on_change.emit(focused))
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        Self { _listeners: listeners }
+    }
+}
+
+pub fn window_focused() -> bool {
+    web_sys::window().and_then(|w| w.document()).is_none_or(|document| document.has_focus().unwrap_or(true))
+}
+
+pub fn page_hidden() -> bool {
+    web_sys::window().and_then(|w| w.document()).is_some_and(|document| document.hidden())
+}
@@ -0,0 +1,274 @@
+// src/agent.rs
+// Build `worker`: koneksi WebSocket dipegang ChatWorker (gloo-worker) di dalam web worker, bukan oleh
+// komponen. Setiap ChatClient::shared di tab ini (App, ChatStore, panel lain) mendapat bridge hasil fork
+// dari satu bridge induk, jadi semuanya berbagi satu koneksi, koneksi tetap hidup saat komponen di-mount
+// ulang, dan frame dari server diurai di worker; thread utama hanya menerima ClientEvent yang sudah jadi.
+//
+// Worker dijalankan bin `chat_worker` (lihat README). Pesan bridge dikodekan MessagePack, karena enum
+// bertag "type" di protokol tidak bisa dibaca bincode bawaan gloo-worker.
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use futures_channel::oneshot;
+use gloo_net::websocket::Message as WsMessage;
+use gloo_timers::future::TimeoutFuture;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::spawn_local;
+use yew::Callback;
+use gloo_worker::{Codec, HandlerId, Registrable, Spawnable, Worker, WorkerBridge, WorkerScope};
+
+use crate::client::{ChatClient, ClientEvent};
+use crate::handle::{Receipt, SendError};
+use crate::stats::SessionStats;
+
+const WORKER_SCRIPT: &str = "/chat_worker.js"; // Keluaran trunk untuk bin chat_worker
+const IDLE_CLOSE_MS: u32 = 5_000; // Koneksi tanpa pemegang ditutup setelah jeda ini, sehingga mount ulang tidak menyambung ulang
+
+thread_local! {
+    // Bridge induk tidak pernah di-drop: worker hidup selama tab terbuka, ChatClient memakai fork-nya
+    static ROOT: WorkerBridge<ChatWorker> = ChatWorker::spawner().encoding::<MessagePackBridge>().spawn(WORKER_SCRIPT);
+}
+
+// Dipanggil bin chat_worker di dalam web worker
+pub fn register_worker() {
+    wasm_logger::init(wasm_logger::Config::default());
+    ChatWorker::registrar().encoding::<MessagePackBridge>().register();
+}
+
+// Pesan bridge sebagai MessagePack (map bernama, sama seperti MessagePackCodec) di dalam Uint8Array
+pub struct MessagePackBridge;
+
+impl Codec for MessagePackBridge {
+    fn encode<I: Serialize>(input: I) -> JsValue {
+        let bytes = rmp_serde::to_vec_named(&input).expect("pesan worker tidak bisa dikodekan");
+        js_sys::Uint8Array::from(bytes.as_slice()).into()
+    }
+
+    fn decode<O: for<'de> Deserialize<'de>>(input: JsValue) -> O {
+        rmp_serde::from_slice(&js_sys::Uint8Array::new(&input).to_vec()).expect("pesan worker tidak bisa dibaca")
+    }
+}
+
+// WsMessage sendiri tidak bisa diserialisasi
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum AgentFrame {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl From<WsMessage> for AgentFrame {
+    fn from(frame: WsMessage) -> Self {
+        match frame {
+            WsMessage::Text(text) => AgentFrame::Text(text),
+            WsMessage::Bytes(bytes) => AgentFrame::Bytes(bytes),
+        }
+    }
+}
+
+impl From<AgentFrame> for WsMessage {
+    fn from(frame: AgentFrame) -> Self {
+        match frame {
+            AgentFrame::Text(text) => WsMessage::Text(text),
+            AgentFrame::Bytes(bytes) => WsMessage::Bytes(bytes),
+        }
+    }
+}
+
+// Frame masuk yang dihitung worker sejak event sebelumnya, untuk SessionStats di thread utama
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Traffic {
+    pub frames: u64,
+    pub bytes: u64,
+    pub messages: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum AgentRequest {
+    // Koneksi yang sudah terbuka ke URL yang sama dipakai bersama; URL lain membuka koneksi baru untuk semua
+    Connect { url: String },
+    Send { request: u64, frame: AgentFrame }, // Sudah dikodekan ChatClient di thread utama
+    Close, // Bridge ini tidak lagi butuh koneksi
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum AgentOutput {
+    Event { event: ClientEvent, traffic: Traffic }, // Dikirim ke semua bridge
+    Sent { request: u64, result: Result<Receipt, SendError> }, // Hanya ke bridge pengirim
+}
+
+pub enum WorkerMsg {
+    Event(ClientEvent),
+    IdleTimeout(u64),
+}
+
+pub struct ChatWorker {
+    client: ChatClient, // Transport biasa (WebSocket, atau server demo di build `demo-server`)
+    stats: SessionStats,
+    url: Option<String>,
+    handlers: HashSet<HandlerId>, // Bridge yang pernah mengirim permintaan; fork baru tidak diumumkan ke worker
+    holders: HashSet<HandlerId>, // Bridge yang meminta koneksi tetap terbuka
+    forwarded: Traffic, // Total frame masuk yang sudah diteruskan
+    idle: u64, // Naik setiap penutupan dijadwalkan atau dibatalkan; IdleTimeout lama diabaikan
+}
+
+impl Worker for ChatWorker {
+    type Message = WorkerMsg;
+    type Input = AgentRequest;
+    type Output = AgentOutput;
+
+    fn create(scope: &WorkerScope<Self>) -> Self {
+        let stats = SessionStats::new();
+        let client = ChatClient::new(crate::default_transport(), stats.clone());
+        let on_event = scope.callback(WorkerMsg::Event);
+        client.subscribe(Callback::from(move |event| on_event(event)));
+        Self { client, stats, url: None, handlers: HashSet::new(), holders: HashSet::new(), forwarded: Traffic::default(), idle: 0 }
+    }
+
+    fn update(&mut self, scope: &WorkerScope<Self>, msg: Self::Message) {
+        match msg {
+            WorkerMsg::Event(event) => {
+                if matches!(event, ClientEvent::Disconnected(_)) {
+                    self.client.close(); // Connect berikutnya membuka socket baru
+                }
+                let traffic = self.take_traffic();
+                for id in &self.handlers {
+                    scope.respond(*id, AgentOutput::Event { event: event.clone(), traffic });
+                }
+            }
+            WorkerMsg::IdleTimeout(idle) => {
+                if idle == self.idle && self.holders.is_empty() && self.client.is_connected() {
+                    log::info!("Worker: tidak ada komponen yang memakai koneksi, koneksi ditutup");
+                    self.client.close();
+                    self.url = None;
+                }
+            }
+        }
+    }
+
+    fn received(&mut self, scope: &WorkerScope<Self>, msg: Self::Input, id: HandlerId) {
+        self.handlers.insert(id);
+        match msg {
+            AgentRequest::Connect { url } => {
+                self.holders.insert(id);
+                self.idle += 1; // Batalkan penutupan yang sedang dijadwalkan
+                if self.client.is_connected() && self.url.as_deref() == Some(url.as_str()) {
+                    scope.respond(id, AgentOutput::Event { event: ClientEvent::Connected, traffic: Traffic::default() });
+                    return;
+                }
+                match self.client.connect(&url) {
+                    Ok(()) => self.url = Some(url),
+                    Err(error) => {
+                        self.url = None;
+                        scope.respond(id, AgentOutput::Event { event: ClientEvent::Disconnected(error), traffic: Traffic::default() });
+                    }
+                }
+            }
+            AgentRequest::Send { request, frame } => match self.client.send_frame(frame.into()) {
+                Some(sending) => {
+                    let scope = scope.clone();
+                    spawn_local(async move { scope.respond(id, AgentOutput::Sent { request, result: sending.await }) });
+                }
+                None => scope.respond(id, AgentOutput::Sent { request, result: Err(SendError::Closed) }),
+            },
+            AgentRequest::Close => self.release(scope, id),
+        }
+    }
+
+    fn disconnected(&mut self, scope: &WorkerScope<Self>, id: HandlerId) {
+        self.handlers.remove(&id);
+        self.release(scope, id);
+    }
+}
+
+impl ChatWorker {
+    // Pemegang terakhir yang lepas menjadwalkan penutupan setelah IDLE_CLOSE_MS
+    fn release(&mut self, scope: &WorkerScope<Self>, id: HandlerId) {
+        if !self.holders.remove(&id) || !self.holders.is_empty() {
+            return;
+        }
+        self.idle += 1;
+        let (scope, idle) = (scope.clone(), self.idle);
+        spawn_local(async move {
+            TimeoutFuture::new(IDLE_CLOSE_MS).await;
+            scope.send_message(WorkerMsg::IdleTimeout(idle));
+        });
+    }
+
+    fn take_traffic(&mut self) -> Traffic {
+        let snapshot = self.stats.snapshot();
+        let total = Traffic { frames: snapshot.frames_received, bytes: snapshot.bytes_received, messages: snapshot.messages_received };
+        let delta = Traffic { frames: total.frames - self.forwarded.frames, bytes: total.bytes - self.forwarded.bytes, messages: total.messages - self.forwarded.messages };
+        self.forwarded = total;
+        delta
+    }
+}
+
+// Receipt yang ditunggu future kirim, per nomor permintaan
+type PendingSends = Rc<RefCell<HashMap<u64, oneshot::Sender<Result<Receipt, SendError>>>>>;
+
+// Sisi thread utama dari koneksi bersama, dimiliki satu ChatClient. Murah untuk di-clone agar future
+// kirim bisa memegangnya; bridge fork-nya di-drop (dan dilepas di worker) bersama clone terakhir.
+#[derive(Clone)]
+pub struct AgentConnection {
+    inner: Rc<AgentInner>,
+}
+
+struct AgentInner {
+    bridge: WorkerBridge<ChatWorker>,
+    open: Rc<Cell<bool>>, // Sejak connect sampai close atau Disconnected
+    pending: PendingSends,
+    next_request: Cell<u64>,
+}
+
+impl AgentConnection {
+    pub fn new(subscribers: Rc<RefCell<Vec<Callback<ClientEvent>>>>, stats: SessionStats) -> Self {
+        let open = Rc::new(Cell::new(false));
+        let pending = PendingSends::default();
+        let on_output = {
+            let (open, pending) = (open.clone(), pending.clone());
+            move |output: AgentOutput| match output {
+                AgentOutput::Event { event, traffic } => {
+                    stats.record_received_batch(traffic.frames, traffic.bytes, traffic.messages);
+                    if matches!(event, ClientEvent::Disconnected(_)) {
+                        open.set(false);
+                    }
+                    subscribers.borrow().iter().for_each(|subscriber| subscriber.emit(event.clone()));
+                }
+                AgentOutput::Sent { request, result } => {
+                    if let Some(reply) = pending.borrow_mut().remove(&request) {
+                        let _ = reply.send(result);
+                    }
+                }
+            }
+        };
+        let bridge = ROOT.with(|root| root.fork(Some(on_output)));
+        Self { inner: Rc::new(AgentInner { bridge, open, pending, next_request: Cell::new(0) }) }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.inner.open.get()
+    }
+
+    // Connected (atau Disconnected jika gagal) datang dari worker
+    pub fn connect(&self, url: &str) {
+        self.inner.open.set(true);
+        self.inner.bridge.send(AgentRequest::Connect { url: url.to_string() });
+    }
+
+    // Socket baru ditutup worker jika tidak ada ChatClient lain yang memakainya
+    pub fn release(&self) {
+        self.inner.open.set(false);
+        self.inner.bridge.send(AgentRequest::Close);
+    }
+
+    pub async fn send_frame(&self, frame: WsMessage) -> Result<Receipt, SendError> {
+        let request = self.inner.next_request.get() + 1;
+        self.inner.next_request.set(request);
+        let (reply, receipt) = oneshot::channel();
+        self.inner.pending.borrow_mut().insert(request, reply);
+        self.inner.bridge.send(AgentRequest::Send { request, frame: frame.into() });
+        receipt.await.unwrap_or(Err(SendError::Closed))
+    }
+}
@@ -0,0 +1,107 @@
+// src/appearance.rs
+// Tampilan yang diatur dari Rust lewat CSS variable dan atribut di elemen <html>, sehingga
+// style.css cukup memakai var(--chat-...) dan selector [data-...] tanpa perlu tahu pengaturan user.
+use gloo_events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, MediaQueryList};
+use yew::Callback;
+
+use crate::bidi;
+use crate::i18n::Locale;
+use crate::settings::Settings;
+use crate::theme::Theme;
+
+const BUILTIN_STYLESHEET: &str = include_str!("../style.css");
+const STYLESHEET_ID: &str = "webchat-builtin-style";
+const REDUCED_MOTION_QUERY: &str = "(prefers-reduced-motion: reduce)";
+const HIGH_CONTRAST_QUERY: &str = "(prefers-contrast: more), (forced-colors: active)";
+const DARK_QUERY: &str = "(prefers-color-scheme: dark)";
+
+// Style bawaan ikut di dalam wasm dan dipasang sebagai <style> di <head>, sehingga halaman yang
+// menanam widget tidak perlu menyertakan style.css sendiri. Mode headless melepasnya.
+pub fn install_stylesheet(enabled: bool) {
+    let Some(document) = web_sys::window().and_then(|w| w.document()) else { return };
+    let existing = document.get_element_by_id(STYLESHEET_ID);
+    match (existing, enabled) {
+        (Some(style), false) => style.remove(),
+        (None, true) => {
+            let Ok(style) = document.create_element("style") else { return };
+            style.set_id(STYLESHEET_ID);
+            style.set_text_content(Some(BUILTIN_STYLESHEET));
+            if let Some(head) = document.head() {
+                let _ = head.append_child(&style);
+            }
+        }
+        _ => {}
+    }
+}
+
+// Terapkan pengaturan tampilan ke dokumen. Aman dipanggil berulang kali.
+pub fn apply(settings: &Settings) {
+    let Some(root) = root_element() else { return };
+    let reduced = reduced_motion(settings);
+    let style = root.style();
+    let _ = style.set_property("--chat-font-scale", &settings.text_size.scale().to_string());
+    let _ = style.set_property("--chat-motion-duration", if reduced { "0s" } else { "0.3s" });
+    set_flag(&root, "data-reduced-motion", reduced);
+    set_flag(&root, "data-high-contrast", settings.high_contrast.resolve(media_matches(HIGH_CONTRAST_QUERY)));
+    let _ = root.set_attribute("dir", bidi::browser_direction().as_attr()); // Layout dicerminkan untuk locale RTL
+    let _ = root.set_attribute("lang", Locale::resolve(settings.locale).code());
+    let _ = root.set_attribute("data-theme", Theme::resolve(settings.theme).attr()); // Palet di style.css
+}
+
+// Sistem meminta tema gelap; dipakai selama user belum memilih tema sendiri
+pub fn prefers_dark() -> bool {
+    media_matches(DARK_QUERY)
+}
+
+// Animasi dimatikan jika diminta di pengaturan, atau (default) jika sistem memintanya
+pub fn reduced_motion(settings: &Settings) -> bool {
+    settings.reduce_motion.resolve(media_matches(REDUCED_MOTION_QUERY))
+}
+
+// Pantau perubahan preferensi sistem selama aplikasi berjalan (mis. user menyalakan
+// kontras tinggi di OS). Listener berhenti saat nilai kembalian di-drop.
+pub fn watch_system(on_change: Callback<()>) -> Vec<EventListener> {
+    [REDUCED_MOTION_QUERY, HIGH_CONTRAST_QUERY, DARK_QUERY]
+        .iter()
+        .filter_map(|query| media_query(query))
+        .map(|list| {
+            let on_change = on_change.clone();
+            EventListener::new(&list, "change", move |_| on_change.emit(()))
+        })
+        .collect()
+}
+
+// Animasi CSS berhenti selama tab tersembunyi (lihat [data-page-hidden] di style.css)
+pub fn set_page_hidden(hidden: bool) {
+    if let Some(root) = root_element() {
+        set_flag(&root, "data-page-hidden", hidden);
+    }
+}
+
+// Mode ringan: animasi dan transisi dimatikan (lihat [data-lite-mode] di style.css)
+pub fn set_lite_mode(on: bool) {
+    if let Some(root) = root_element() {
+        set_flag(&root, "data-lite-mode", on);
+    }
+}
+
+fn root_element() -> Option<HtmlElement> {
+    web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.document_element())
+        .and_then(|el| el.dyn_into::<HtmlElement>().ok())
+}
+
+fn set_flag(root: &HtmlElement, name: &str, on: bool) {
+    let _ = if on { root.set_attribute(name, "") } else { root.remove_attribute(name) };
+}
+
+fn media_query(query: &str) -> Option<MediaQueryList> {
+    web_sys::window().and_then(|w| w.match_media(query).ok().flatten())
+}
+
+fn media_matches(query: &str) -> bool {
+    media_query(query).is_some_and(|list| list.matches())
+}
@@ -0,0 +1,110 @@
+// src/auth.rs
+// Login dengan token (JWT atau token sesi dari backend embedder). Token dikirim sebagai frame pertama
+// setelah terhubung, atau sebagai query `token` di URL WebSocket untuk server yang memeriksanya saat
+// handshake. Klien tidak membaca isi token; diterima atau tidaknya sepenuhnya keputusan server (AuthResult).
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::i18n::use_locale;
+use crate::storage;
+
+const TOKEN_KEY: &str = "webchat.auth_token"; // Hanya jika user memilih "Ingat di perangkat ini"
+const TOKEN_PARAM: &str = "token";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum AuthState {
+    #[default]
+    Anonymous, // Tanpa token, dan server tidak mewajibkan login
+    Authenticating { from_login: bool }, // Token terkirim, menunggu AuthResult; `from_login` = diketik di layar login
+    Authenticated { user_id: String },
+    LoginRequired { error: Option<String> }, // Diminta server/deployment, atau token ditolak
+}
+
+impl AuthState {
+    // Hanya user yang sudah login (atau di server tanpa login) yang boleh mengirim pesan
+    pub fn can_send(&self) -> bool {
+        matches!(self, AuthState::Anonymous | AuthState::Authenticated { .. })
+    }
+
+    pub fn needs_login(&self) -> bool {
+        matches!(self, AuthState::LoginRequired { .. })
+    }
+}
+
+pub fn load_token() -> Option<String> {
+    storage::get(TOKEN_KEY).filter(|token| !token.is_empty())
+}
+
+pub fn save_token(token: &str) {
+    storage::set(TOKEN_KEY, token);
+}
+
+pub fn forget_token() {
+    storage::remove(TOKEN_KEY);
+}
+
+// URL WebSocket dengan token di query, untuk server yang memeriksa token saat upgrade HTTP
+pub fn url_with_token(ws_url: &str, token: &str) -> String {
+    let separator = if ws_url.contains('?') { '&' } else { '?' };
+    let encoded: String = js_sys::encode_uri_component(token).into();
+    format!("{}{}{}={}", ws_url, separator, TOKEN_PARAM, encoded)
+}
+
+#[derive(Properties, PartialEq)]
+pub struct LoginScreenProps {
+    pub error: Option<String>,
+    pub pending: bool, // Token sedang diperiksa server
+    pub on_login: Callback<(String, bool)>, // Token dan pilihan "ingat di perangkat ini"
+}
+
+// Layar login: menutupi chat sampai server menerima token
+#[function_component(LoginScreen)]
+pub fn login_screen(props: &LoginScreenProps) -> Html {
+    let locale = use_locale();
+    let token = use_state(String::new);
+    let remember = use_state(|| false);
+    let valid = !token.trim().is_empty() && !props.pending;
+
+    let on_input = {
+        let token = token.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            token.set(input.value());
+        })
+    };
+    let on_remember = {
+        let remember = remember.clone();
+        Callback::from(move |_| remember.set(!*remember))
+    };
+    let on_submit = {
+        let (token, remember) = (token.clone(), remember.clone());
+        let on_login = props.on_login.clone();
+        Callback::from(move |e: FocusEvent| {
+            e.prevent_default();
+            let value = token.trim().to_string();
+            if !value.is_empty() {
+                on_login.emit((value, *remember));
+            }
+        })
+    };
+
+    html! {
+        <div class="modal-backdrop">
+            <form class="modal login-screen" onsubmit={on_submit}>
+                <h3>{ locale.t("Masuk") }</h3>
+                <p>{ locale.t("Server ini hanya bisa dipakai setelah login. Tempel token akses dari akun Anda.") }</p>
+                <input type="password" placeholder={locale.t("Token akses")} autocomplete="current-password" value={(*token).clone()} oninput={on_input} />
+                <label class="login-remember">
+                    <input type="checkbox" checked={*remember} onchange={on_remember} />
+                    { locale.t("Ingat di perangkat ini") }
+                </label>
+                if let Some(err) = &props.error {
+                    <p class="field-error">{ err }</p>
+                }
+                <div class="tour-actions">
+                    <button type="submit" disabled={!valid}>{ locale.t(if props.pending { "Memeriksa…" } else { "Masuk" }) }</button>
+                </div>
+            </form>
+        </div>
+    }
+}
@@ -0,0 +1,47 @@
+// src/bidi.rs
+// Deteksi arah teks (kiri-ke-kanan / kanan-ke-kiri) untuk bahasa seperti Arab, Ibrani dan Persia.
+// Arah tiap pesan ditentukan oleh aksara dominannya (script::TextProfile); angka dan tanda baca diabaikan.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Ltr,
+    Rtl,
+}
+
+impl Direction {
+    pub fn as_attr(self) -> &'static str {
+        match self {
+            Direction::Ltr => "ltr",
+            Direction::Rtl => "rtl",
+        }
+    }
+}
+
+// Bahasa yang ditulis dari kanan ke kiri (subtag bahasa BCP 47)
+const RTL_LANGUAGES: [&str; 11] = ["ar", "arc", "ckb", "dv", "fa", "he", "iw", "ps", "sd", "ur", "yi"];
+
+// Arah layout untuk locale browser, mis. "ar-EG" -> Rtl
+pub fn locale_direction(locale: &str) -> Direction {
+    let language = locale.split(['-', '_']).next().unwrap_or("").to_ascii_lowercase();
+    if RTL_LANGUAGES.contains(&language.as_str()) {
+        Direction::Rtl
+    } else {
+        Direction::Ltr
+    }
+}
+
+pub fn is_rtl_char(c: char) -> bool {
+    matches!(c as u32,
+        0x0590..=0x08FF       // Ibrani, Arab, Suriah, Thaana, NKo, Samaria, Mandaik, Arab tambahan
+        | 0xFB1D..=0xFDFF     // Bentuk presentasi Ibrani dan Arab-A
+        | 0xFE70..=0xFEFF     // Bentuk presentasi Arab-B
+        | 0x10800..=0x10FFF   // Aksara RTL kuno
+        | 0x1E800..=0x1EFFF)
+}
+
+// Arah layout dari bahasa browser
+pub fn browser_direction() -> Direction {
+    web_sys::window()
+        .and_then(|w| w.navigator().language())
+        .map_or(Direction::Ltr, |locale| locale_direction(&locale))
+}
@@ -0,0 +1,452 @@
+// src/bin/chat_conformance.rs
+// Uji kesesuaian protokol untuk server alternatif. Terhubung ke URL server, menjalankan skenario
+// berurutan (login, handshake, heartbeat, echo, riwayat, penyuntingan, rate limit, upload) dan mencetak laporan lulus/gagal.
+//
+//   cargo run --features conformance --bin chat_conformance -- ws://127.0.0.1:8080/ws [--token TOKEN]
+//
+// Exit code 1 jika ada pemeriksaan yang gagal, sehingga bisa dipakai di CI server.
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::Engine as _;
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpStream;
+use tokio::time::{timeout, Instant};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+use chat_protocol::{ChatMessage, ClientCommand, FrameTag, PresenceEvent, ServerEvent, UploadChunkFrame};
+
+const DEFAULT_URL: &str = "ws://127.0.0.1:8080/ws";
+const DEFAULT_TIMEOUT_SECS: u64 = 5;
+const SLOW_MODE_SECS: u32 = 10;
+const UPLOAD_SIZE: usize = 100 * 1024; // Dua chunk dengan ukuran chunk klien web
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+// Frame dari server, dibedakan dengan aturan yang sama seperti klien web
+enum Frame {
+    Event(ServerEvent),
+    Presence, // Tidak diperiksa, hanya dikenali agar tidak dihitung sebagai frame asing
+    Chat(ChatMessage),
+    Unknown(String),
+}
+
+enum Outcome {
+    Pass(String),
+    Fail(String),
+    Skip(String),
+}
+
+struct Connection {
+    socket: Socket,
+    wait: Duration,
+    unknown: Vec<String>, // Frame yang bukan event maupun pesan chat; klien web menampilkannya sebagai error protokol
+}
+
+impl Connection {
+    async fn open(url: &str, wait: Duration) -> Result<Self, String> {
+        let (socket, _) = timeout(wait, connect_async(url))
+            .await
+            .map_err(|_| format!("tidak ada respons handshake dalam {:?}", wait))?
+            .map_err(|e| format!("gagal terhubung: {}", e))?;
+        Ok(Self { socket, wait, unknown: Vec::new() })
+    }
+
+    async fn send<T: serde::Serialize>(&mut self, frame: &T) -> Result<(), String> {
+        let text = serde_json::to_string(frame).map_err(|e| e.to_string())?;
+        self.socket.send(Message::Text(text)).await.map_err(|e| format!("gagal mengirim: {}", e))
+    }
+
+    async fn next_frame(&mut self, deadline: Instant) -> Option<Frame> {
+        loop {
+            let message = tokio::time::timeout_at(deadline, self.socket.next()).await.ok()??.ok()?;
+            let Message::Text(text) = message else { continue }; // Ping/pong dan frame biner diabaikan
+            let Ok(FrameTag { kind }) = serde_json::from_str::<FrameTag>(&text) else {
+                return Some(Frame::Unknown(text));
+            };
+            if kind.is_none() {
+                return Some(match serde_json::from_str::<ChatMessage>(&text) {
+                    Ok(msg) => Frame::Chat(msg),
+                    Err(_) => Frame::Unknown(text),
+                });
+            }
+            if serde_json::from_str::<PresenceEvent>(&text).is_ok() {
+                return Some(Frame::Presence);
+            }
+            return Some(match serde_json::from_str::<ServerEvent>(&text) {
+                Ok(ServerEvent::Unknown) | Err(_) => Frame::Unknown(text),
+                Ok(event) => Frame::Event(event),
+            });
+        }
+    }
+
+    // Kirim Authenticate dan tunggu AuthResult; Ok berisi user id akun (jika server mengirimnya)
+    async fn authenticate(&mut self, token: &str) -> Result<Option<String>, String> {
+        self.send(&ClientCommand::Authenticate { token: token.to_string() }).await?;
+        let result = self
+            .wait_for(|frame| match frame {
+                Frame::Event(ServerEvent::AuthResult { ok, user_id, reason, .. }) => Some((ok, user_id, reason)),
+                _ => None,
+            })
+            .await;
+        match result {
+            Some((true, user_id, _)) => Ok(user_id),
+            Some((false, _, reason)) => Err(format!("token ditolak: {}", reason.unwrap_or_default())),
+            None => Err("tidak ada balasan AuthResult".to_string()),
+        }
+    }
+
+    // Tunggu frame pertama yang cocok; frame lain (presence, pesan user lain) dilewati
+    async fn wait_for<T>(&mut self, mut matches: impl FnMut(Frame) -> Option<T>) -> Option<T> {
+        let deadline = Instant::now() + self.wait;
+        while let Some(frame) = self.next_frame(deadline).await {
+            if let Frame::Unknown(text) = frame {
+                self.unknown.push(text);
+                continue;
+            }
+            if let Some(found) = matches(frame) {
+                return Some(found);
+            }
+        }
+        None
+    }
+}
+
+fn chat_message(text: &str) -> ChatMessage {
+    ChatMessage {
+        id: None,
+        client_id: None,
+        user_id: Some("conformance-bot".to_string()),
+        username: "conformance".to_string(),
+        text: text.to_string(),
+        timestamp: None,
+        edited_at: None,
+        attachment: None,
+        expires_at: None,
+        event: None,
+        system: false,
+        components: Vec::new(),
+        room: None,
+        operator: None,
+        whisper_to: None,
+    }
+}
+
+fn now_ms() -> f64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0.0, |d| d.as_secs_f64() * 1000.0)
+}
+
+// Server yang mewajibkan login menerima token dari --token; tanpa token server dianggap terbuka
+async fn check_auth(conn: &mut Connection, token: Option<&str>) -> Outcome {
+    let Some(token) = token else { return Outcome::Skip("tanpa --token".to_string()) };
+    match conn.authenticate(token).await {
+        Ok(Some(user_id)) => Outcome::Pass(format!("login sebagai {}", user_id)),
+        Ok(None) => Outcome::Pass("token diterima tanpa identitas akun".to_string()),
+        Err(e) => Outcome::Fail(e),
+    }
+}
+
+// Server menjawab TimeSync dengan jam server dan mengembalikan `client_time` apa adanya
+async fn check_handshake(conn: &mut Connection) -> Outcome {
+    let client_time = now_ms();
+    if let Err(e) = conn.send(&ClientCommand::TimeSync { client_time }).await {
+        return Outcome::Fail(e);
+    }
+    let reply = conn
+        .wait_for(|frame| match frame {
+            Frame::Event(ServerEvent::TimeSync { client_time: echoed, server_time }) => Some((echoed, server_time)),
+            _ => None,
+        })
+        .await;
+    match reply {
+        Some((echoed, server_time)) if echoed == client_time => {
+            Outcome::Pass(format!("selisih jam server {:+.0} ms", server_time - now_ms()))
+        }
+        Some((echoed, _)) => Outcome::Fail(format!("client_time berubah: dikirim {}, diterima {}", client_time, echoed)),
+        None => Outcome::Fail("tidak ada balasan TimeSync".to_string()),
+    }
+}
+
+// Ping dari klien dijawab Pong dengan nonce yang sama; tanpa itu klien web menganggap koneksi diam sudah mati
+async fn check_heartbeat(conn: &mut Connection) -> Outcome {
+    let nonce = now_ms() as u64;
+    let sent_at = Instant::now();
+    if let Err(e) = conn.send(&ClientCommand::Ping { nonce }).await {
+        return Outcome::Fail(e);
+    }
+    let pong = conn
+        .wait_for(|frame| match frame {
+            Frame::Event(ServerEvent::Pong { nonce: echoed }) => Some(echoed),
+            _ => None,
+        })
+        .await;
+    match pong {
+        Some(echoed) if echoed == nonce => Outcome::Pass(format!("Pong dalam {} ms", sent_at.elapsed().as_millis())),
+        Some(echoed) => Outcome::Fail(format!("nonce berubah: dikirim {}, diterima {}", nonce, echoed)),
+        None => Outcome::Fail("tidak ada balasan Pong".to_string()),
+    }
+}
+
+// Pesan chat disiarkan kembali ke pengirimnya dengan id dan timestamp dari server
+async fn check_echo(conn: &mut Connection, text: &str) -> Outcome {
+    let client_id = format!("conformance-{}", now_ms() as u64);
+    let message = ChatMessage { client_id: Some(client_id.clone()), ..chat_message(text) };
+    if let Err(e) = conn.send(&message).await {
+        return Outcome::Fail(e);
+    }
+    let echoed = conn
+        .wait_for(|frame| match frame {
+            Frame::Chat(msg) if msg.text == text => Some(msg),
+            _ => None,
+        })
+        .await;
+    let Some(msg) = echoed else { return Outcome::Fail("pesan tidak dikirim balik".to_string()) };
+    let mut missing = Vec::new();
+    if msg.id.is_none() {
+        missing.push("id");
+    }
+    if msg.timestamp.is_none() {
+        missing.push("timestamp");
+    }
+    if msg.client_id.as_ref() != Some(&client_id) {
+        missing.push("client_id"); // Klien memakainya untuk menandai pesan pending sebagai terkirim
+    }
+    if missing.is_empty() {
+        Outcome::Pass(format!("id {}", msg.id.unwrap_or_default()))
+    } else {
+        Outcome::Fail(format!("pesan kembali tanpa {}", missing.join(", ")))
+    }
+}
+
+// Koneksi baru yang mengirim Resume menerima pesan dari check_echo di HistoryReplay
+async fn check_history(url: &str, wait: Duration, token: Option<&str>, text: &str) -> Outcome {
+    let mut conn = match Connection::open(url, wait).await {
+        Ok(conn) => conn,
+        Err(e) => return Outcome::Fail(e),
+    };
+    if let Some(token) = token {
+        if let Err(e) = conn.authenticate(token).await {
+            return Outcome::Fail(e);
+        }
+    }
+    if let Err(e) = conn.send(&ClientCommand::Resume { since: None, limit: 50 }).await {
+        return Outcome::Fail(e);
+    }
+    let replay = conn
+        .wait_for(|frame| match frame {
+            Frame::Event(ServerEvent::HistoryReplay { messages, total_missed }) => Some((messages, total_missed)),
+            _ => None,
+        })
+        .await;
+    match replay {
+        Some((messages, total_missed)) if messages.iter().any(|msg| msg.text == text) => {
+            Outcome::Pass(format!("{} pesan diputar ulang, {} terlewat", messages.len(), total_missed))
+        }
+        Some((messages, _)) => Outcome::Fail(format!("pesan uji tidak ada di {} pesan riwayat", messages.len())),
+        None => Outcome::Fail("tidak ada HistoryReplay untuk Resume".to_string()),
+    }
+}
+
+// Protokol belum punya perintah sunting; klien hanya membaca `edited_at` dari server
+fn check_editing() -> Outcome {
+    Outcome::Skip("protokol belum mendefinisikan perintah sunting pesan".to_string())
+}
+
+// Pembuat room menjadi moderator, menyalakan slow mode, lalu pesan kedua harus ditolak
+async fn check_rate_limit(conn: &mut Connection, run_id: &str) -> Outcome {
+    let room = format!("conformance-{}", run_id);
+    if let Err(e) = conn.send(&ClientCommand::CreateRoom { room: room.clone(), password: None, ttl_secs: Some(3600), feedback_box: false }).await {
+        return Outcome::Fail(e);
+    }
+    let joined = conn
+        .wait_for(|frame| match frame {
+            Frame::Event(ServerEvent::RoomJoined { room: joined, is_moderator, .. }) if joined == room => Some(Ok(is_moderator)),
+            Frame::Event(ServerEvent::JoinFailed { room: failed, reason }) if failed == room => Some(Err(reason)),
+            _ => None,
+        })
+        .await;
+    match joined {
+        Some(Ok(true)) => {}
+        Some(Ok(false)) => return Outcome::Skip("pembuat room tidak mendapat hak moderator".to_string()),
+        Some(Err(reason)) => return Outcome::Fail(format!("CreateRoom ditolak: {}", reason.message())),
+        None => return Outcome::Fail("tidak ada RoomJoined untuk CreateRoom".to_string()),
+    }
+    if let Err(e) = conn.send(&ClientCommand::SetSlowMode { room: room.clone(), interval_secs: SLOW_MODE_SECS }).await {
+        return Outcome::Fail(e);
+    }
+    let changed = conn
+        .wait_for(|frame| match frame {
+            Frame::Event(ServerEvent::SlowModeChanged { room: changed, interval_secs }) if changed == room => Some(interval_secs),
+            _ => None,
+        })
+        .await;
+    if changed != Some(SLOW_MODE_SECS) {
+        return Outcome::Fail("SlowModeChanged tidak diterima".to_string());
+    }
+    for i in 0..2 {
+        if let Err(e) = conn.send(&chat_message(&format!("slow mode {} #{}", run_id, i))).await {
+            return Outcome::Fail(e);
+        }
+    }
+    let rejected = conn
+        .wait_for(|frame| match frame {
+            Frame::Event(ServerEvent::SlowModeRejected { room: rejected, retry_after_secs, .. }) if rejected == room => Some(retry_after_secs),
+            _ => None,
+        })
+        .await;
+    match rejected {
+        Some(secs) if secs > 0 && secs <= SLOW_MODE_SECS => Outcome::Pass(format!("pesan kedua ditolak, coba lagi dalam {} detik", secs)),
+        Some(secs) => Outcome::Fail(format!("retry_after_secs {} di luar 1..={}", secs, SLOW_MODE_SECS)),
+        None => Outcome::Fail("pesan kedua tidak ditolak dengan SlowModeRejected".to_string()),
+    }
+}
+
+// Upload bertahap sampai UploadComplete; chunk dikirim biner jika server mengizinkannya di UploadOffset.
+// Isinya diawali header PDF agar lolos server yang mencocokkan tipe dengan magic byte.
+async fn check_upload(conn: &mut Connection, run_id: &str) -> Outcome {
+    let upload_id = format!("conformance-{}", run_id);
+    let mut data = b"%PDF-1.7\n".to_vec();
+    data.extend((data.len()..UPLOAD_SIZE).map(|i| (i % 251) as u8));
+    let start = ClientCommand::UploadStart {
+        upload_id: upload_id.clone(),
+        name: "conformance.pdf".to_string(),
+        mime: "application/pdf".to_string(),
+        size: data.len() as u64,
+        chunk_size: UPLOAD_CHUNK_SIZE as u64,
+    };
+    if let Err(e) = conn.send(&start).await {
+        return Outcome::Fail(e);
+    }
+    let mut chunks_sent = 0;
+    let mut used_binary = false;
+    loop {
+        let reply = conn
+            .wait_for(|frame| match frame {
+                Frame::Event(ServerEvent::UploadOffset { upload_id: id, offset, binary }) if id == upload_id => Some(Ok((offset, binary))),
+                Frame::Event(ServerEvent::UploadComplete { upload_id: id, url }) if id == upload_id => Some(Err(Ok(url))),
+                Frame::Event(ServerEvent::UploadFailed { upload_id: id, reason }) if id == upload_id => Some(Err(Err(reason.message()))),
+                _ => None,
+            })
+            .await;
+        let (offset, binary) = match reply {
+            Some(Ok(progress)) => progress,
+            Some(Err(Ok(url))) if url.is_empty() => return Outcome::Fail("UploadComplete tanpa url".to_string()),
+            Some(Err(Ok(_))) => {
+                let mode = if used_binary { "frame biner" } else { "base64" };
+                return Outcome::Pass(format!("{} byte dalam {} chunk ({})", data.len(), chunks_sent, mode));
+            }
+            Some(Err(Err(reason))) => return Outcome::Fail(format!("upload ditolak: {}", reason)),
+            None if chunks_sent == 0 => return Outcome::Skip("tidak ada UploadOffset; server tanpa dukungan upload".to_string()),
+            None => return Outcome::Fail(format!("tidak ada balasan setelah chunk ke-{}", chunks_sent)),
+        };
+        let Some(rest) = data.get(offset as usize..).filter(|rest| !rest.is_empty()) else {
+            return Outcome::Fail(format!("offset {} tanpa UploadComplete", offset));
+        };
+        let chunk = rest[..rest.len().min(UPLOAD_CHUNK_SIZE)].to_vec();
+        let sent = if binary {
+            used_binary = true;
+            let frame = UploadChunkFrame { upload_id: upload_id.clone(), offset, data: chunk }.encode().unwrap_or_default();
+            conn.socket.send(Message::Binary(frame)).await.map_err(|e| format!("gagal mengirim: {}", e))
+        } else {
+            let data = base64::engine::general_purpose::STANDARD.encode(chunk);
+            conn.send(&ClientCommand::UploadChunk { upload_id: upload_id.clone(), offset, data }).await
+        };
+        if let Err(e) = sent {
+            return Outcome::Fail(e);
+        }
+        chunks_sent += 1;
+    }
+}
+
+// Semua frame selama uji harus bisa dibaca sebagai event protokol atau pesan chat
+async fn check_frames(conn: &mut Connection) -> Outcome {
+    let deadline = Instant::now() + Duration::from_millis(200); // Sisa frame yang masih dalam perjalanan
+    while let Some(frame) = conn.next_frame(deadline).await {
+        if let Frame::Unknown(text) = frame {
+            conn.unknown.push(text);
+        }
+    }
+    match conn.unknown.first() {
+        None => Outcome::Pass("semua frame dikenali".to_string()),
+        Some(first) => Outcome::Fail(format!("{} frame tidak dikenali, mis. {}", conn.unknown.len(), first)),
+    }
+}
+
+fn report(results: &[(&str, Outcome)]) -> bool {
+    println!();
+    let mut failed = 0;
+    for (name, outcome) in results {
+        let (label, detail) = match outcome {
+            Outcome::Pass(detail) => ("LULUS ", detail),
+            Outcome::Fail(detail) => {
+                failed += 1;
+                ("GAGAL ", detail)
+            }
+            Outcome::Skip(detail) => ("LEWATI", detail),
+        };
+        println!("[{}] {:<12} {}", label, name, detail);
+    }
+    println!();
+    println!("{} pemeriksaan, {} gagal", results.len(), failed);
+    failed == 0
+}
+
+fn parse_args() -> Result<(String, Duration, Option<String>), String> {
+    let mut url = DEFAULT_URL.to_string();
+    let mut wait = Duration::from_secs(DEFAULT_TIMEOUT_SECS);
+    let mut token = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--timeout" => {
+                let secs = args.next().and_then(|v| v.parse().ok()).ok_or("--timeout butuh jumlah detik")?;
+                wait = Duration::from_secs(secs);
+            }
+            "--token" => token = Some(args.next().ok_or("--token butuh token login")?),
+            "-h" | "--help" => {
+                return Err(format!("pemakaian: chat_conformance [URL] [--timeout DETIK] [--token TOKEN]  (default {})", DEFAULT_URL));
+            }
+            _ if arg.starts_with("ws://") || arg.starts_with("wss://") => url = arg,
+            _ => return Err(format!("argumen tidak dikenal: {}", arg)),
+        }
+    }
+    Ok((url, wait, token))
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let (url, wait, token) = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    println!("Menguji {} (batas tunggu {:?} per langkah)", url, wait);
+    let run_id = format!("{:x}", now_ms() as u64);
+    let echo_text = format!("conformance echo {}", run_id);
+
+    let mut results = Vec::new();
+    let mut conn = match Connection::open(&url, wait).await {
+        Ok(conn) => conn,
+        Err(e) => {
+            results.push(("handshake", Outcome::Fail(e)));
+            report(&results);
+            std::process::exit(1);
+        }
+    };
+    results.push(("auth", check_auth(&mut conn, token.as_deref()).await));
+    results.push(("handshake", check_handshake(&mut conn).await));
+    results.push(("heartbeat", check_heartbeat(&mut conn).await));
+    results.push(("echo", check_echo(&mut conn, &echo_text).await));
+    results.push(("history", check_history(&url, wait, token.as_deref(), &echo_text).await));
+    results.push(("editing", check_editing()));
+    results.push(("rate-limit", check_rate_limit(&mut conn, &run_id).await));
+    results.push(("upload", check_upload(&mut conn, &run_id).await));
+    results.push(("frames", check_frames(&mut conn).await));
+
+    if !report(&results) {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,6 @@
+// src/bin/chat_worker.rs
+// Entry web worker untuk build `worker`: menjalankan ChatWorker yang memegang koneksi WebSocket bersama
+// untuk semua komponen di tab. Dibangun trunk sebagai worker terpisah (lihat README).
+fn main() {
+    yew_webchat_client::register_worker();
+}
@@ -0,0 +1,53 @@
+// src/canned.rs
+// Balasan cepat buatan user (mis. untuk meja layanan): nama pendek + teks dengan placeholder.
+// Dipilih lewat `/canned <nama>`, palet perintah, atau menu di samping input; hasilnya diisikan
+// ke input pesan agar masih bisa disunting sebelum dikirim.
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{tr, trf};
+
+// Placeholder yang dikenali beserta penjelasannya, untuk panel pengaturan
+pub const PLACEHOLDERS: [(&str, &str); 3] = [
+    ("{nama}", "lawan bicara: user di tab pesan langsung, atau pengirim pesan terakhir di room"),
+    ("{saya}", "username Anda"),
+    ("{room}", "nama room saat ini"),
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CannedResponse {
+    pub name: String,
+    pub text: String,
+}
+
+// Nilai placeholder saat balasan dipilih; None dibiarkan apa adanya di teks
+#[derive(Debug, Clone, Default)]
+pub struct Placeholders {
+    pub peer: Option<String>,
+    pub me: Option<String>,
+    pub room: Option<String>,
+}
+
+pub fn find<'a>(responses: &'a [CannedResponse], name: &str) -> Option<&'a CannedResponse> {
+    responses.iter().find(|response| response.name.eq_ignore_ascii_case(name.trim()))
+}
+
+pub fn expand(text: &str, values: &Placeholders) -> String {
+    let mut expanded = text.to_string();
+    for (placeholder, value) in [("{nama}", &values.peer), ("{saya}", &values.me), ("{room}", &values.room)] {
+        if let Some(value) = value {
+            expanded = expanded.replace(placeholder, value);
+        }
+    }
+    expanded
+}
+
+// Nama baru valid jika tidak kosong, tanpa spasi (dipakai sebagai argumen /canned) dan belum dipakai
+pub fn validate_name(responses: &[CannedResponse], name: &str) -> Result<(), String> {
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return Err(tr("Nama balasan cepat harus satu kata.").to_string());
+    }
+    if find(responses, name).is_some() {
+        return Err(trf("Balasan cepat \"{}\" sudah ada.", &[&name]));
+    }
+    Ok(())
+}
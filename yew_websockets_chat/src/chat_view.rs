@@ -0,0 +1,364 @@
+// src/chat_view.rs
+// Komponen tampilan tanpa state koneksi: semua data masuk lewat props dan semua tindakan keluar lewat
+// callback, sehingga bisa dipakai App, widget ChatRoom, maupun tata letak milik embedder. Markup dan
+// class-nya sama dengan yang tercantum di STYLING.md. Tombol dan menu bot ada di components.rs.
+use web_sys::{File, HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::canned::CannedResponse;
+use crate::i18n::use_locale;
+use crate::media::{self, EmbedPolicy};
+use crate::store::{Delivery, StoredMessage};
+use crate::time;
+
+#[derive(Properties, PartialEq)]
+pub struct MessageItemProps {
+    pub message: StoredMessage,
+    pub sender: AttrValue, // Display name; diambil dari direktori oleh pemanggil
+    pub own: bool,
+    #[prop_or_default]
+    pub repeats: usize, // Pesan identik berturut-turut yang digabung ke entry ini
+    pub policy: EmbedPolicy,
+}
+
+// Satu pesan dalam bentuk ringkas: nama, waktu, status kirim, teks dan lampiran
+#[function_component(MessageItem)]
+pub fn message_item(props: &MessageItemProps) -> Html {
+    let locale = use_locale();
+    let msg = &props.message.message;
+    let delivery_class = match props.message.delivery {
+        Delivery::Sent => None,
+        Delivery::Pending { .. } => Some("pending"),
+        Delivery::Failed(_) => Some("failed"),
+    };
+    html! {
+        <li class={classes!(if props.own { "me" } else { "other" }, msg.system.then_some("system"), delivery_class)} data-user={msg.user_id.clone()} dir="auto">
+            <div class="message-meta">
+                <strong>{ props.sender.clone() }</strong>
+                if let Some(ts) = &msg.timestamp {
+                    <span class="timestamp">{ format!(" - {}", time::format_relative(*ts, js_sys::Date::now())) }</span>
+                }
+                if let Delivery::Failed(reason) = &props.message.delivery {
+                    <span class="delivery-status failed" title={reason.clone()}>{ locale.t("Gagal terkirim") }</span>
+                }
+                if props.repeats > 0 {
+                    <span class="repeat-count">{ format!("×{}", props.repeats + 1) }</span>
+                }
+            </div>
+            if !msg.text.is_empty() {
+                <div>{ &msg.text }</div>
+            }
+            if let Some(attachment) = &msg.attachment {
+                { media::view_attachment(attachment, props.policy) }
+            }
+        </li>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct MessageViewportProps {
+    #[prop_or_default]
+    pub class: Classes, // Tambahan untuk <ul class="messages">, mis. "direct" atau "locked"
+    #[prop_or_default]
+    pub list_ref: NodeRef, // Untuk pemilik yang mengatur scroll sendiri
+    #[prop_or_default]
+    pub on_scroll: Callback<()>,
+    #[prop_or_default]
+    pub header: Html, // Di atas daftar, mis. chip tanggal atau keadaan kosong
+    #[prop_or_default]
+    pub footer: Html, // Di bawah daftar, mis. tombol pesan baru atau indikator mengetik
+    #[prop_or_default]
+    pub children: Children, // Baris <li> pesan; App memakai baris lengkapnya sendiri, MessageList memakai MessageItem
+}
+
+// Bingkai transkrip yang dipakai App maupun MessageList
+#[function_component(MessageViewport)]
+pub fn message_viewport(props: &MessageViewportProps) -> Html {
+    html! {
+        <div class="messages-viewport">
+            { props.header.clone() }
+            <ul class={classes!("messages", props.class.clone())} ref={props.list_ref.clone()} onscroll={props.on_scroll.reform(|_: Event| ())}>
+                { for props.children.iter() }
+            </ul>
+            { props.footer.clone() }
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct UsernameFormProps {
+    pub value: AttrValue, // Isi input; pemilik state yang memvalidasi
+    #[prop_or_default]
+    pub error: Option<AttrValue>, // Hint validasi di bawah input
+    pub on_input: Callback<String>,
+    pub on_submit: Callback<()>, // Enter atau tombol "Set Username"
+}
+
+#[function_component(UsernameForm)]
+pub fn username_form(props: &UsernameFormProps) -> Html {
+    let locale = use_locale();
+    let on_input = props.on_input.reform(|e: InputEvent| e.target_unchecked_into::<HtmlInputElement>().value());
+    let on_submit = props.on_submit.reform(|e: FocusEvent| e.prevent_default());
+    html! {
+        <>
+            <form onsubmit={on_submit}>
+                <input type="text" dir="auto" placeholder={locale.t("Set username...")} value={props.value.clone()} oninput={on_input} />
+                <button type="submit" disabled={props.value.is_empty() || props.error.is_some()}>{ locale.t("Set Username") }</button>
+            </form>
+            if let Some(error) = &props.error {
+                <p class="field-error">{ error.clone() }</p>
+            }
+        </>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ConnectionBannerProps {
+    pub connected: bool,
+    pub on_reconnect: Callback<()>,
+}
+
+// Status koneksi, dengan tombol sambung ulang selama terputus
+#[function_component(ConnectionBanner)]
+pub fn connection_banner(props: &ConnectionBannerProps) -> Html {
+    let locale = use_locale();
+    if props.connected {
+        return html! { <p class="connection-status online">{ locale.t("Terhubung ke server!") }</p> };
+    }
+    html! {
+        <>
+            <p class="connection-status offline">{ locale.t("Tidak terhubung ke server. Mencoba menghubungkan...") }</p>
+            <button onclick={props.on_reconnect.reform(|_| ())}>{ locale.t("Coba Hubungkan Ulang") }</button>
+        </>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ComposerProps {
+    pub value: AttrValue,
+    pub placeholder: AttrValue,
+    #[prop_or_default]
+    pub feedback_box: bool, // Room kotak saran: catatan anonim di atas input
+    #[prop_or_default]
+    pub read_only: bool, // Aturan room belum disetujui, atau server dalam maintenance
+    pub connected: bool,
+    #[prop_or_default]
+    pub cooldown: Option<u32>, // Sisa detik slow mode
+    #[prop_or_default]
+    pub awaiting_echo: bool, // Pesan terakhir belum tampil (SendGuard); tombol Kirim ditahan
+    #[prop_or_default]
+    pub canned: Vec<CannedResponse>,
+    #[prop_or_default]
+    pub completes: bool, // Tab sedang punya saran untuk dilengkapi
+    pub on_input: Callback<String>,
+    pub on_submit: Callback<()>,
+    #[prop_or_default]
+    pub on_complete: Callback<()>,
+    #[prop_or_default]
+    pub on_canned: Callback<String>, // Nama balasan cepat yang dipilih
+    #[prop_or_default]
+    pub on_attach: Option<Callback<File>>, // Kosong = tanpa tombol lampiran
+    #[prop_or_default]
+    pub children: Children, // Saran @mention atau slash command di bawah input
+}
+
+// Input pesan beserta balasan cepat, lampiran dan tombol Kirim
+#[function_component(Composer)]
+pub fn composer(props: &ComposerProps) -> Html {
+    let locale = use_locale();
+    let on_input = props.on_input.reform(|e: InputEvent| e.target_unchecked_into::<HtmlInputElement>().value());
+    let on_submit = props.on_submit.reform(|e: FocusEvent| e.prevent_default());
+    let on_keydown = {
+        let (on_complete, completes) = (props.on_complete.clone(), props.completes);
+        Callback::from(move |e: KeyboardEvent| {
+            if completes && e.key() == "Tab" {
+                e.prevent_default();
+                on_complete.emit(());
+            }
+        })
+    };
+    let on_canned = {
+        let on_canned = props.on_canned.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let name = select.value();
+            select.set_value(""); // Kembali ke "💬" agar balasan yang sama bisa dipilih lagi
+            if !name.is_empty() {
+                on_canned.emit(name);
+            }
+        })
+    };
+    let on_file = props.on_attach.clone().map(|on_attach| {
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let file = input.files().and_then(|files| files.get(0));
+            input.set_value(""); // Supaya file yang sama bisa dipilih lagi
+            if let Some(file) = file {
+                on_attach.emit(file);
+            }
+        })
+    });
+    html! {
+        <>
+            if props.feedback_box {
+                <p class="feedback-composer-note">{ locale.t("📮 Saran anonim: moderator membaca kiriman Anda tanpa nama atau id akun.") }</p>
+            }
+            <form onsubmit={on_submit} style="display: contents;">
+                <input type="text" dir="auto" placeholder={props.placeholder.clone()} value={props.value.clone()} oninput={on_input} onkeydown={on_keydown} disabled={props.read_only} />
+                if !props.canned.is_empty() {
+                    <select class="canned-select" title={locale.t("Balasan cepat")} onchange={on_canned}>
+                        <option value="" selected=true>{ "💬" }</option>
+                        { for props.canned.iter().map(|response| html! {
+                            <option value={response.name.clone()} title={response.text.clone()}>{ &response.name }</option>
+                        }) }
+                    </select>
+                }
+                if let Some(on_file) = on_file {
+                    <label class="attach-button" title={locale.t("Lampirkan file")}>
+                        { "📎" }
+                        <input type="file" onchange={on_file} disabled={props.read_only || !props.connected} />
+                    </label>
+                }
+                // Submit lewat form saja; onclick tambahan membuat satu klik mengirim dua kali
+                <button type="submit" disabled={props.value.is_empty() || props.read_only || props.cooldown.is_some() || props.awaiting_echo}>
+                    {
+                        match props.cooldown {
+                            Some(secs) => locale.f("Kirim ({})", &[&secs]),
+                            None => locale.t("Kirim").to_string(),
+                        }
+                    }
+                </button>
+            </form>
+            { for props.children.iter() }
+        </>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct RoomFormProps {
+    pub room: AttrValue,
+    pub password: AttrValue,
+    pub ttl: Option<u64>, // Untuk room yang akan dibuat; None = permanen
+    pub feedback_box: bool,
+    pub connected: bool,
+    #[prop_or_default]
+    pub error: Option<AttrValue>,
+    pub on_room_input: Callback<String>,
+    pub on_password_input: Callback<String>,
+    pub on_ttl: Callback<Option<u64>>,
+    pub on_feedback_box: Callback<bool>,
+    pub on_join: Callback<()>,
+    pub on_create: Callback<()>,
+}
+
+// Gabung ke room yang ada atau buat room baru
+#[function_component(RoomForm)]
+pub fn room_form(props: &RoomFormProps) -> Html {
+    let locale = use_locale();
+    let on_room_input = props.on_room_input.reform(|e: InputEvent| e.target_unchecked_into::<HtmlInputElement>().value());
+    let on_password_input = props.on_password_input.reform(|e: InputEvent| e.target_unchecked_into::<HtmlInputElement>().value());
+    let on_ttl = props.on_ttl.reform(|e: Event| e.target_unchecked_into::<HtmlSelectElement>().value().parse().ok());
+    let on_feedback_box = props.on_feedback_box.reform(|e: Event| e.target_unchecked_into::<HtmlInputElement>().checked());
+    let on_submit = props.on_join.reform(|e: FocusEvent| e.prevent_default());
+    html! {
+        <>
+            <form onsubmit={on_submit}>
+                <input type="text" dir="auto" placeholder={locale.t("Nama room...")} value={props.room.clone()} oninput={on_room_input} />
+                <input type="password" placeholder={locale.t("Kata sandi (jika ada)")} value={props.password.clone()} oninput={on_password_input} />
+                <button type="submit" disabled={!props.connected}>{ locale.t("Gabung") }</button>
+                <select onchange={on_ttl}>
+                    <option value="" selected={props.ttl.is_none()}>{ locale.t("Room permanen") }</option>
+                    <option value="3600" selected={props.ttl == Some(3600)}>{ locale.t("Sementara (1 jam tidak aktif)") }</option>
+                    <option value="86400" selected={props.ttl == Some(86400)}>{ locale.t("Sementara (24 jam tidak aktif)") }</option>
+                </select>
+                <label title={locale.t("Pesan hanya sampai ke moderator, tanpa nama pengirim")}>
+                    <input type="checkbox" checked={props.feedback_box} onchange={on_feedback_box} />
+                    { " " }{ locale.t("Kotak saran anonim") }
+                </label>
+                <button type="button" onclick={props.on_create.reform(|_| ())} disabled={!props.connected}>{ locale.t("Buat Room") }</button>
+            </form>
+            if let Some(error) = &props.error {
+                <p class="field-error">{ error.clone() }</p>
+            }
+        </>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct VaultLockProps {
+    pub value: AttrValue,
+    pub busy: bool, // Passphrase sedang diperiksa
+    #[prop_or_default]
+    pub error: Option<AttrValue>,
+    pub on_input: Callback<String>,
+    pub on_submit: Callback<()>,
+}
+
+// Layar kunci: menutupi seluruh chat sampai passphrase dimasukkan
+#[function_component(VaultLock)]
+pub fn vault_lock(props: &VaultLockProps) -> Html {
+    let locale = use_locale();
+    let on_input = props.on_input.reform(|e: InputEvent| e.target_unchecked_into::<HtmlInputElement>().value());
+    let on_submit = props.on_submit.reform(|e: FocusEvent| e.prevent_default());
+    html! {
+        <div class="modal-backdrop vault-lock">
+            <form class="modal" onsubmit={on_submit}>
+                <h3>{ locale.t("🔒 Chat terkunci") }</h3>
+                <p>{ locale.t("Masukkan passphrase perangkat untuk membuka riwayat lokal.") }</p>
+                <input type="password" value={props.value.clone()} oninput={on_input} autocomplete="current-password" />
+                <button type="submit" disabled={props.busy || props.value.is_empty()}>
+                    { locale.t(if props.busy { "Membuka…" } else { "Buka" }) }
+                </button>
+                if let Some(error) = &props.error {
+                    <p class="field-error">{ error.clone() }</p>
+                }
+            </form>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct RulesGateProps {
+    pub room: AttrValue,
+    pub rules: AttrValue,
+    pub on_accept: Callback<()>,
+}
+
+// Aturan room yang harus disetujui sebelum bisa mengirim pesan
+#[function_component(RulesGate)]
+pub fn rules_gate(props: &RulesGateProps) -> Html {
+    let locale = use_locale();
+    html! {
+        <div class="modal-backdrop">
+            <div class="modal rules-gate">
+                <h3>{ locale.f("Selamat datang di {}", &[&props.room]) }</h3>
+                <p class="room-rules">{ props.rules.clone() }</p>
+                <button onclick={props.on_accept.reform(|_| ())}>{ locale.t("Saya setuju") }</button>
+            </div>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct SourceModalProps {
+    pub raw: AttrValue, // Payload apa adanya; JSON dirapikan sebelum ditampilkan
+    pub on_close: Callback<()>,
+}
+
+// Payload mentah sebuah pesan (build `dev`)
+#[function_component(SourceModal)]
+pub fn source_modal(props: &SourceModalProps) -> Html {
+    let locale = use_locale();
+    let pretty = serde_json::from_str::<serde_json::Value>(&props.raw)
+        .and_then(|value| serde_json::to_string_pretty(&value))
+        .unwrap_or_else(|_| props.raw.to_string());
+    html! {
+        <div class="modal-backdrop">
+            <div class="modal">
+                <h3>{ locale.t("Sumber pesan") }</h3>
+                <pre class="source-payload">{ pretty }</pre>
+                <button onclick={props.on_close.reform(|_| ())}>{ locale.t("Tutup") }</button>
+            </div>
+        </div>
+    }
+}
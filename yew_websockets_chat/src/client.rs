@@ -0,0 +1,278 @@
+// src/client.rs
+// Koneksi ke server chat tanpa bergantung pada komponen Yew: membuka transport, menjalankan
+// loop baca, dan mengubah frame mentah menjadi ClientEvent bertipe. App hanya berlangganan event
+// dan memanggil `send`; transport lain (server demo di browser, mock) cukup mengimplementasikan ChatTransport.
+// Di build `worker`, ChatClient::shared meneruskan semua itu ke koneksi bersama di ChatWorker (lihat agent.rs).
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::rc::Rc;
+
+use futures_util::stream::LocalBoxStream;
+use futures_util::StreamExt;
+use gloo_net::websocket::{Message as WsMessage, WebSocketError};
+use gloo_timers::future::TimeoutFuture;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen_futures::spawn_local;
+use yew::Callback;
+
+#[cfg(feature = "worker")]
+use crate::agent::AgentConnection;
+use crate::codec::{self, Encoding, JsonCodec, MessageCodec};
+use crate::error::{ChatError, TransportError};
+use crate::handle::{FrameSink, FrameWriter, Receipt, SendError};
+use crate::protocol::{ClientCommand, PresenceEvent, ServerEvent, ServerFrame};
+use crate::stats::SessionStats;
+use crate::ChatMessage;
+
+const HEARTBEAT_INTERVAL_MS: u32 = 15_000; // Ping dikirim jika selama ini tidak ada frame masuk
+const MAX_MISSED_PONGS: u32 = 2; // Koneksi dianggap mati setelah sekian ping berturut-turut tidak dijawab
+
+// Bagian baca koneksi, pasangan dari `FrameSink`
+pub type FrameStream = LocalBoxStream<'static, Result<WsMessage, WebSocketError>>;
+
+// Cara membuka satu koneksi: bagian tulis dan bagian baca frame
+pub trait ChatTransport {
+    fn connect(&self, url: &str) -> Result<(FrameSink, FrameStream), String>;
+}
+
+// Transport default: WebSocket browser
+pub struct WebSocketTransport;
+
+impl ChatTransport for WebSocketTransport {
+    fn connect(&self, url: &str) -> Result<(FrameSink, FrameStream), String> {
+        use futures_util::SinkExt;
+        let ws_conn = gloo_net::websocket::futures::WebSocket::open(url).map_err(|e| format!("{:?}", e))?;
+        let (write, read) = ws_conn.split();
+        Ok((Box::pin(write.sink_map_err(|e| format!("{:?}", e))), read.boxed_local()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ClientEvent {
+    Connected,
+    // Pesan chat biasa; `raw` berisi payload asli (frame biner sebagai JSON) hanya di build `dev`
+    Message { message: Box<ChatMessage>, raw: Option<String>, encoding: Encoding }, // Di-box agar ClientEvent tetap kecil
+    Server(ServerEvent),
+    // Presence dan daftar user online, diteruskan terpisah dari pesan dan event room
+    Presence(PresenceEvent),
+    // Frame yang tidak bisa dibaca; koneksi tetap berjalan
+    Error(ChatError),
+    Disconnected(TransportError),
+}
+
+pub struct ChatClient {
+    transport: Rc<dyn ChatTransport>,
+    handle: Option<FrameWriter>,
+    #[cfg(feature = "worker")]
+    shared: Option<AgentConnection>, // Koneksi dipegang ChatWorker; `transport` dan `handle` tidak dipakai
+    subscribers: Rc<RefCell<Vec<Callback<ClientEvent>>>>,
+    connection: Rc<Cell<u64>>, // Nomor koneksi terbaru; event dari loop baca koneksi lama diabaikan
+    encoding: Cell<Encoding>, // Format frame keluar; kembali ke JSON setiap koneksi baru sampai server memilih lain
+    stats: SessionStats,
+}
+
+impl ChatClient {
+    pub fn new(transport: Rc<dyn ChatTransport>, stats: SessionStats) -> Self {
+        Self {
+            transport,
+            handle: None,
+            #[cfg(feature = "worker")]
+            shared: None,
+            subscribers: Rc::default(),
+            connection: Rc::default(),
+            encoding: Cell::default(),
+            stats,
+        }
+    }
+
+    // Berbagi satu koneksi dengan ChatClient lain di tab ini lewat ChatWorker. Event dan statistik frame
+    // masuk datang dari worker; `close` hanya melepas koneksi milik client ini.
+    #[cfg(feature = "worker")]
+    pub fn shared(stats: SessionStats) -> Self {
+        let mut client = Self::new(Rc::new(WebSocketTransport), stats.clone());
+        client.shared = Some(AgentConnection::new(client.subscribers.clone(), stats));
+        client
+    }
+
+    pub fn subscribe(&self, on_event: Callback<ClientEvent>) {
+        self.subscribers.borrow_mut().push(on_event);
+    }
+
+    pub fn is_connected(&self) -> bool {
+        #[cfg(feature = "worker")]
+        if let Some(shared) = &self.shared {
+            return shared.is_open();
+        }
+        self.handle.is_some()
+    }
+
+    pub fn encoding(&self) -> Encoding {
+        self.encoding.get()
+    }
+
+    // Dipanggil setelah EncodingSelected; frame yang sudah diserialisasi tetap memakai format lama
+    pub fn set_encoding(&self, encoding: Encoding) {
+        self.encoding.set(encoding);
+    }
+
+    // Buka koneksi baru menggantikan yang lama (jika ada). Connected dikirim ke pelanggan setelah
+    // transport terbuka; Disconnected saat loop baca berakhir atau heartbeat tidak dijawab.
+    pub fn connect(&mut self, url: &str) -> Result<(), TransportError> {
+        #[cfg(feature = "worker")]
+        if let Some(shared) = &self.shared {
+            self.encoding.set(Encoding::Json);
+            shared.connect(url); // Gagal terhubung dilaporkan worker sebagai Disconnected
+            return Ok(());
+        }
+        self.close();
+        let (sink, stream) = self.transport.connect(url).map_err(TransportError::ConnectFailed)?;
+        let handle = FrameWriter::spawn(sink);
+        self.handle = Some(handle.clone());
+        self.encoding.set(Encoding::Json);
+        let connection = self.connection.get() + 1;
+        self.connection.set(connection);
+        let emit = {
+            let (subscribers, current) = (self.subscribers.clone(), self.connection.clone());
+            move |event: ClientEvent| {
+                if current.get() == connection {
+                    subscribers.borrow().iter().for_each(|subscriber| subscriber.emit(event.clone()));
+                }
+            }
+        };
+        emit(ClientEvent::Connected);
+        let last_received = Rc::new(Cell::new(js_sys::Date::now()));
+        spawn_local(heartbeat(handle, last_received.clone(), connection, self.connection.clone(), emit.clone()));
+        spawn_local(read_loop(stream, self.stats.clone(), last_received, emit));
+        Ok(())
+    }
+
+    // Serialisasi sekarang, kirim saat future dijalankan. None jika belum tersambung.
+    pub fn send<T: Serialize>(&self, frame: &T) -> Option<impl Future<Output = Result<Receipt, SendError>> + 'static> {
+        let sender = self.sender()?;
+        let frame = self.encoding.get().encode(frame).map_err(SendError::Serialize);
+        Some(async move { sender.send_frame(frame?).await })
+    }
+
+    // Frame biner apa adanya (mis. UploadChunkFrame), di luar format frame yang sedang dipakai
+    pub fn send_bytes(&self, bytes: Vec<u8>) -> Option<impl Future<Output = Result<Receipt, SendError>> + 'static> {
+        self.send_frame(WsMessage::Bytes(bytes))
+    }
+
+    // Frame yang sudah dikodekan, juga dari thread utama ke ChatWorker
+    pub(crate) fn send_frame(&self, frame: WsMessage) -> Option<impl Future<Output = Result<Receipt, SendError>> + 'static> {
+        let sender = self.sender()?;
+        Some(async move { sender.send_frame(frame).await })
+    }
+
+    fn sender(&self) -> Option<Sender> {
+        #[cfg(feature = "worker")]
+        if let Some(shared) = &self.shared {
+            return shared.is_open().then(|| Sender::Shared(shared.clone()));
+        }
+        self.handle.clone().map(Sender::Local)
+    }
+
+    // Frame di antrean tetap ditulis dulu; loop baca lalu melaporkan Disconnected
+    pub fn close(&mut self) {
+        #[cfg(feature = "worker")]
+        if let Some(shared) = &self.shared {
+            shared.release();
+            return;
+        }
+        if let Some(handle) = self.handle.take() {
+            handle.close();
+        }
+    }
+}
+
+// Jalur kirim koneksi saat ini
+enum Sender {
+    Local(FrameWriter),
+    #[cfg(feature = "worker")]
+    Shared(AgentConnection),
+}
+
+impl Sender {
+    async fn send_frame(&self, frame: WsMessage) -> Result<Receipt, SendError> {
+        match self {
+            Sender::Local(handle) => handle.send_frame(frame).await,
+            #[cfg(feature = "worker")]
+            Sender::Shared(shared) => shared.send_frame(frame).await,
+        }
+    }
+}
+
+// Proxy bisa memutus koneksi yang diam tanpa menutup socket di sisi browser. Setiap frame masuk
+// membuktikan koneksi hidup; jika lama tidak ada, Ping dikirim, dan setelah MAX_MISSED_PONGS ping
+// tanpa jawaban koneksi dilaporkan terputus agar App menyambung ulang.
+async fn heartbeat(handle: FrameWriter, last_received: Rc<Cell<f64>>, connection: u64, current: Rc<Cell<u64>>, emit: impl Fn(ClientEvent)) {
+    let mut missed = 0;
+    let mut nonce = 0;
+    loop {
+        TimeoutFuture::new(HEARTBEAT_INTERVAL_MS).await;
+        if current.get() != connection || handle.is_closed() {
+            return; // Koneksi sudah diganti atau ditutup
+        }
+        if js_sys::Date::now() - last_received.get() < f64::from(HEARTBEAT_INTERVAL_MS) {
+            missed = 0;
+            continue;
+        }
+        if missed >= MAX_MISSED_PONGS {
+            log::warn!("Heartbeat: {} ping tidak dijawab, koneksi dianggap mati", missed);
+            emit(ClientEvent::Disconnected(TransportError::HeartbeatTimeout));
+            current.set(connection + 1); // Event dari loop baca socket yang mati ini diabaikan
+            return;
+        }
+        missed += 1;
+        nonce += 1;
+        // Selalu JSON: server membaca frame teks sebagai JSON apa pun format yang sedang dipakai
+        if let Ok(frame) = JsonCodec.encode(&ClientCommand::Ping { nonce }) {
+            let _ = handle.send_frame(frame).await; // Gagal kirim juga berarti tidak akan ada Pong
+        }
+    }
+}
+
+async fn read_loop(mut stream: FrameStream, stats: SessionStats, last_received: Rc<Cell<f64>>, emit: impl Fn(ClientEvent)) {
+    log::info!("Task pembacaan WebSocket telah dimulai.");
+    let mut reason = TransportError::Disconnected; // Stream selesai tanpa error: koneksi ditutup
+    while let Some(frame) = stream.next().await {
+        match frame {
+            Ok(frame) => {
+                last_received.set(js_sys::Date::now());
+                let (encoding, bytes) = (codec::encoding_of(&frame), codec::frame_len(&frame));
+                match codec::decode_server_frame(&frame) {
+                    Ok(ServerFrame::Chat(message)) => {
+                        stats.record_received(bytes, true);
+                        let raw = if cfg!(feature = "dev") { codec::describe(&frame) } else { None };
+                        emit(ClientEvent::Message { message: Box::new(message), raw, encoding });
+                    }
+                    Ok(ServerFrame::Presence(event)) => {
+                        stats.record_received(bytes, false);
+                        emit(ClientEvent::Presence(event));
+                    }
+                    // Cukup dicatat sebagai tanda hidup di atas
+                    Ok(ServerFrame::Event(ServerEvent::Pong { .. })) => stats.record_received(bytes, false),
+                    Ok(ServerFrame::Event(ServerEvent::Unknown)) => {
+                        stats.record_received(bytes, false);
+                        log::debug!("Event server tidak dikenal diabaikan: {:?}", codec::describe(&frame));
+                    }
+                    Ok(ServerFrame::Event(event)) => {
+                        // Pesan langsung dihitung sebagai pesan chat di statistik sesi
+                        stats.record_received(bytes, matches!(event, ServerEvent::DirectMessage { .. }));
+                        emit(ClientEvent::Server(event));
+                    }
+                    Err(detail) => emit(ClientEvent::Error(ChatError::Protocol { detail, payload: codec::describe(&frame) })),
+                }
+            }
+            Err(e) => {
+                reason = match e {
+                    WebSocketError::ConnectionClose(close_event) => TransportError::Closed { code: close_event.code(), reason: close_event.reason() },
+                    WebSocketError::MessageSendError(e) => TransportError::SendFailed(SendError::Socket(format!("{:?}", e))), // Seharusnya tidak terjadi di loop baca
+                    _ => TransportError::Disconnected,
+                };
+                break;
+            }
+        }
+    }
+    emit(ClientEvent::Disconnected(reason));
+}
@@ -0,0 +1,31 @@
+// src/clock.rs
+// Selisih jam browser dengan jam server. Waktu kedaluwarsa dari server (mis. pesan sementara)
+// dibandingkan dengan jam server yang dikoreksi, bukan jam lokal yang bisa meleset beberapa menit.
+
+#[derive(Debug, Default)]
+pub struct ServerClock {
+    offset_ms: f64,         // Jam server dikurangi jam lokal
+    best_rtt: Option<f64>,  // Round-trip tercepat sejauh ini; sampel dengan RTT kecil paling akurat
+}
+
+impl ServerClock {
+    // Catat satu balasan TimeSync. `sent_at` adalah jam lokal saat permintaan dikirim.
+    pub fn observe(&mut self, sent_at: f64, server_time: f64, received_at: f64) {
+        let rtt = (received_at - sent_at).max(0.0);
+        if self.best_rtt.is_some_and(|best| best < rtt) {
+            return;
+        }
+        self.best_rtt = Some(rtt);
+        self.offset_ms = server_time - (sent_at + rtt / 2.0);
+    }
+
+    // Perkiraan jam server saat ini, epoch millis
+    pub fn now(&self) -> f64 {
+        js_sys::Date::now() + self.offset_ms
+    }
+
+    // Waktu server dikonversi ke jam lokal, untuk menjadwalkan timer browser
+    pub fn to_local(&self, server_ms: f64) -> f64 {
+        server_ms - self.offset_ms
+    }
+}
@@ -0,0 +1,101 @@
+// src/codec.rs
+// Format frame di atas WebSocket. JSON (frame teks) tetap default; MessagePack (frame biner) lebih
+// ringkas untuk room besar dan dipakai hanya jika server memilihnya lewat EncodingSelected setelah
+// klien menawarkannya di Hello. Frame masuk dibaca sesuai jenisnya (teks = JSON, biner = MessagePack),
+// jadi frame yang masih dalam perjalanan saat format berganti tetap terbaca.
+use gloo_net::websocket::Message as WsMessage;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::protocol::{FrameTag, ServerFrame};
+
+pub use crate::protocol::Encoding;
+
+pub trait MessageCodec {
+    fn encode<T: Serialize>(&self, frame: &T) -> Result<WsMessage, String>;
+    fn decode<T: DeserializeOwned>(&self, frame: &WsMessage) -> Result<T, String>;
+}
+
+pub struct JsonCodec;
+
+impl MessageCodec for JsonCodec {
+    fn encode<T: Serialize>(&self, frame: &T) -> Result<WsMessage, String> {
+        serde_json::to_string(frame).map(WsMessage::Text).map_err(|e| e.to_string())
+    }
+
+    fn decode<T: DeserializeOwned>(&self, frame: &WsMessage) -> Result<T, String> {
+        match frame {
+            WsMessage::Text(text) => serde_json::from_str(text).map_err(|e| e.to_string()),
+            WsMessage::Bytes(_) => Err("frame biner bukan JSON".to_string()),
+        }
+    }
+}
+
+pub struct MessagePackCodec;
+
+impl MessageCodec for MessagePackCodec {
+    // Struct dikodekan sebagai map bernama (bukan array) agar enum bertag "type" dan field opsional tetap bekerja
+    fn encode<T: Serialize>(&self, frame: &T) -> Result<WsMessage, String> {
+        rmp_serde::to_vec_named(frame).map(WsMessage::Bytes).map_err(|e| e.to_string())
+    }
+
+    fn decode<T: DeserializeOwned>(&self, frame: &WsMessage) -> Result<T, String> {
+        match frame {
+            WsMessage::Bytes(bytes) => rmp_serde::from_slice(bytes).map_err(|e| e.to_string()),
+            WsMessage::Text(_) => Err("frame teks bukan MessagePack".to_string()),
+        }
+    }
+}
+
+// Format sebuah frame masuk ditentukan oleh jenis frame-nya
+pub fn encoding_of(frame: &WsMessage) -> Encoding {
+    match frame {
+        WsMessage::Text(_) => Encoding::Json,
+        WsMessage::Bytes(_) => Encoding::MessagePack,
+    }
+}
+
+impl MessageCodec for Encoding {
+    fn encode<T: Serialize>(&self, frame: &T) -> Result<WsMessage, String> {
+        match self {
+            Encoding::Json => JsonCodec.encode(frame),
+            Encoding::MessagePack => MessagePackCodec.encode(frame),
+        }
+    }
+
+    fn decode<T: DeserializeOwned>(&self, frame: &WsMessage) -> Result<T, String> {
+        match self {
+            Encoding::Json => JsonCodec.decode(frame),
+            Encoding::MessagePack => MessagePackCodec.decode(frame),
+        }
+    }
+}
+
+// Frame masuk dibedakan lewat tag-nya dulu, baru dibaca utuh sebagai tipe yang sesuai. Presence dicoba
+// sebelum ServerEvent karena ServerEvent menampung tag yang tidak dikenalnya sebagai Unknown.
+pub fn decode_server_frame(frame: &WsMessage) -> Result<ServerFrame, String> {
+    let encoding = encoding_of(frame);
+    let FrameTag { kind } = encoding.decode(frame)?;
+    if kind.is_none() {
+        return encoding.decode(frame).map(ServerFrame::Chat);
+    }
+    if let Ok(event) = encoding.decode(frame) {
+        return Ok(ServerFrame::Presence(event));
+    }
+    encoding.decode(frame).map(ServerFrame::Event)
+}
+
+pub fn frame_len(frame: &WsMessage) -> usize {
+    match frame {
+        WsMessage::Text(text) => text.len(),
+        WsMessage::Bytes(bytes) => bytes.len(),
+    }
+}
+
+// Payload yang bisa dibaca manusia untuk "lihat sumber" dan pesan error; frame biner ditampilkan sebagai JSON
+pub fn describe(frame: &WsMessage) -> Option<String> {
+    match frame {
+        WsMessage::Text(text) => Some(text.clone()),
+        WsMessage::Bytes(bytes) => rmp_serde::from_slice::<serde_json::Value>(bytes).ok().map(|value| value.to_string()),
+    }
+}
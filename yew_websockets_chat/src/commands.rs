@@ -0,0 +1,231 @@
+// src/commands.rs
+// Registri perintah: slash command yang bisa diketik di input pesan, dan aksi yang muncul
+// di palet perintah (Ctrl+K). App menerjemahkan keduanya ke Msg yang sudah ada.
+use gloo_events::{EventListener, EventListenerOptions};
+use wasm_bindgen::JsCast;
+use web_sys::KeyboardEvent;
+use yew::Callback;
+
+use crate::canned::CannedResponse;
+use crate::i18n::{tr, trf};
+use crate::protocol::BotCommand;
+
+pub struct SlashCommand {
+    pub name: &'static str,
+    pub usage: &'static str, // Template yang diisikan ke input saat dipilih dari palet
+    pub description: &'static str,
+}
+
+pub const SLASH_COMMANDS: &[SlashCommand] = &[
+    SlashCommand { name: "join", usage: "/join ", description: "Bergabung ke room" },
+    SlashCommand { name: "create", usage: "/create ", description: "Membuat room baru" },
+    SlashCommand { name: "nick", usage: "/nick ", description: "Mengganti username" },
+    SlashCommand { name: "slow", usage: "/slow ", description: "Atur slow mode dalam detik (moderator)" },
+    SlashCommand { name: "event", usage: "/event ", description: "Jadwalkan acara: /event 2024-05-12 19:00 Judul" },
+    SlashCommand { name: "canned", usage: "/canned ", description: "Sisipkan balasan cepat ke input" },
+    SlashCommand { name: "whisper", usage: "/whisper @", description: "Berbisik ke satu user di room ini" },
+];
+
+// Slash command yang sudah di-parse dari input pesan
+#[derive(Debug, Clone, PartialEq)]
+pub enum SlashInvocation {
+    Join(String),
+    Create(String),
+    Nick(String),
+    SlowMode(u32),
+    Event(String), // Argumen mentah; tanggal dan judul diurai oleh schedule::parse_event_args
+    Canned(String), // Nama balasan cepat
+    Whisper { handle: String, text: String }, // Handle tanpa '@'; dikirim sebagai pesan room dengan `whisper_to`
+    Bot { name: String, args: String }, // Perintah bot di room saat ini, diteruskan server ke bot-nya
+    Literal(String), // "//teks" dikirim sebagai pesan biasa "/teks"
+}
+
+// None jika input bukan slash command; Err berisi pesan untuk user.
+// Perintah bawaan didahulukan jika bot mendaftarkan nama yang sama.
+pub fn parse_slash(input: &str, bot_commands: &[BotCommand]) -> Option<Result<SlashInvocation, String>> {
+    let rest = input.strip_prefix('/')?;
+    if rest.starts_with('/') {
+        return Some(Ok(SlashInvocation::Literal(rest.to_string())));
+    }
+    let (name, arg) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    let arg = arg.trim();
+    let required = |usage: &str| if arg.is_empty() { Err(trf("Penggunaan: {}<nilai>", &[&usage])) } else { Ok(arg.to_string()) };
+    Some(match name {
+        "join" => required("/join ").map(SlashInvocation::Join),
+        "create" => required("/create ").map(SlashInvocation::Create),
+        "nick" => required("/nick ").map(SlashInvocation::Nick),
+        "event" => required("/event ").map(SlashInvocation::Event),
+        "canned" => required("/canned ").map(SlashInvocation::Canned),
+        "whisper" => match arg.split_once(char::is_whitespace) {
+            Some((handle, text)) if !text.trim().is_empty() => Ok(SlashInvocation::Whisper { handle: handle.trim_start_matches('@').to_string(), text: text.trim().to_string() }),
+            _ => Err(tr("Penggunaan: /whisper @user <pesan>").to_string()),
+        },
+        "slow" => arg.parse().map(SlashInvocation::SlowMode).map_err(|_| tr("Penggunaan: /slow <detik>").to_string()),
+        _ if bot_commands.iter().any(|command| command.name == name) => {
+            Ok(SlashInvocation::Bot { name: name.to_string(), args: arg.to_string() })
+        }
+        _ => Err(trf("Perintah /{} tidak dikenal. Awali dengan // untuk mengirim teks yang diawali /.", &[&name])),
+    })
+}
+
+// Aksi yang bisa dipilih di palet perintah
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaletteAction {
+    SwitchRoom(String),
+    OpenDirect(String), // Tab pesan langsung dengan user id ini
+    InsertCommand(String), // Isi input pesan dengan template slash command
+    InsertCanned(String), // Isi input pesan dengan balasan cepat bernama ini
+    OpenSettings,
+    StartTour,
+    LockChat,
+    ListSessions,
+    RequestExport,
+    Reconnect,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CommandGroup {
+    Room,
+    Direct,
+    Canned,
+    Settings,
+    Slash,
+}
+
+impl CommandGroup {
+    pub fn label(self) -> &'static str {
+        match self {
+            CommandGroup::Room => "Room",
+            CommandGroup::Direct => "Pesan langsung",
+            CommandGroup::Canned => "Balasan cepat",
+            CommandGroup::Settings => "Pengaturan",
+            CommandGroup::Slash => "Perintah",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteItem {
+    pub label: String,
+    pub hint: Option<String>,
+    pub group: CommandGroup,
+    pub action: PaletteAction,
+}
+
+impl PaletteItem {
+    pub fn new(group: CommandGroup, label: impl Into<String>, action: PaletteAction) -> Self {
+        Self { label: label.into(), hint: None, group, action }
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+// Entri palet untuk semua slash command bawaan dan milik bot di room saat ini
+pub fn slash_items(bot_commands: &[BotCommand]) -> impl Iterator<Item = PaletteItem> + '_ {
+    let builtin = SLASH_COMMANDS.iter().map(|command| {
+        PaletteItem::new(CommandGroup::Slash, format!("/{}", command.name), PaletteAction::InsertCommand(command.usage.to_string()))
+            .with_hint(tr(command.description))
+    });
+    let bots = bot_commands.iter().map(|command| {
+        PaletteItem::new(CommandGroup::Slash, command.signature(), PaletteAction::InsertCommand(format!("/{} ", command.name)))
+            .with_hint(bot_hint(command))
+    });
+    builtin.chain(bots)
+}
+
+// Entri palet untuk balasan cepat user
+pub fn canned_items(responses: &[CannedResponse]) -> impl Iterator<Item = PaletteItem> + '_ {
+    responses.iter().map(|response| {
+        PaletteItem::new(CommandGroup::Canned, response.name.clone(), PaletteAction::InsertCanned(response.name.clone())).with_hint(response.text.clone())
+    })
+}
+
+// Satu baris saran di bawah input saat mengetik "/"
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandHint {
+    pub name: String,
+    pub signature: String, // Nama plus placeholder argumen
+    pub description: String,
+    pub arg_help: Vec<String>, // "env: lingkungan tujuan", ditampilkan setelah nama perintah lengkap diketik
+}
+
+// Saran untuk input yang diawali "/": semua perintah berawalan nama yang sedang diketik, atau,
+// setelah spasi pertama, hanya perintah itu beserta penjelasan argumennya. Untuk /canned,
+// "argumennya" adalah daftar balasan cepat user.
+pub fn hints(input: &str, bot_commands: &[BotCommand], canned: &[CannedResponse]) -> Vec<CommandHint> {
+    let Some(rest) = input.strip_prefix('/').filter(|rest| !rest.starts_with('/')) else { return Vec::new() };
+    let (typed, exact) = match rest.split_once(char::is_whitespace) {
+        Some((name, _)) => (name, true),
+        None => (rest, false),
+    };
+    let builtin = SLASH_COMMANDS.iter().map(|command| CommandHint {
+        name: command.name.to_string(),
+        signature: format!("/{}", command.name),
+        description: tr(command.description).to_string(),
+        arg_help: match command.name {
+            "canned" => canned.iter().map(|response| format!("{}: {}", response.name, response.text)).collect(),
+            _ => Vec::new(),
+        },
+    });
+    let bots = bot_commands.iter().map(|command| CommandHint {
+        name: command.name.clone(),
+        signature: command.signature(),
+        description: bot_hint(command),
+        arg_help: command
+            .args
+            .iter()
+            .filter_map(|arg| arg.description.as_ref().map(|description| format!("{}: {}", arg.name, description)))
+            .collect(),
+    });
+    builtin
+        .chain(bots)
+        .filter(|hint| if exact { hint.name == typed } else { hint.name.starts_with(typed) })
+        .collect()
+}
+
+fn bot_hint(command: &BotCommand) -> String {
+    let bot = command.bot_name.as_deref().unwrap_or(&command.bot_id);
+    if command.description.is_empty() {
+        trf("Perintah bot {}", &[&bot])
+    } else {
+        format!("{} ({})", command.description, bot)
+    }
+}
+
+// Pencocokan fuzzy sederhana: semua huruf query harus muncul berurutan di teks. Huruf yang
+// bersambung dan yang berada di awal kata diberi skor lebih tinggi. None jika tidak cocok.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut previous: Option<usize> = None;
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = pos + text[pos..].iter().position(|&c| c == q)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 3;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 2;
+        }
+        previous = Some(found);
+        pos = found + 1;
+    }
+    Some(score - text.len() as i32 / 10) // Label pendek sedikit diutamakan
+}
+
+// Ctrl+K / Cmd+K di mana pun di halaman
+pub fn listen_shortcut(on_open: Callback<()>) -> Option<EventListener> {
+    let window = web_sys::window()?;
+    let options = EventListenerOptions::enable_prevent_default();
+    Some(EventListener::new_with_options(&window, "keydown", options, move |event| {
+        let Some(event) = event.dyn_ref::<KeyboardEvent>() else { return };
+        if (event.ctrl_key() || event.meta_key()) && event.key().eq_ignore_ascii_case("k") {
+            event.prevent_default(); // Jangan buka pencarian bawaan browser
+            on_open.emit(());
+        }
+    }))
+}
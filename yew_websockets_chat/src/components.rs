@@ -0,0 +1,64 @@
+// src/components.rs
+// Tombol dan menu pilihan di pesan bot. Klik dikirim ke server sebagai Interaction terstruktur
+// (id pesan + `custom_id` milik bot), jadi alur persetujuan atau polling cepat tidak perlu
+// mengurai teks bebas. Selama bot belum membalas, komponen pesan itu dinonaktifkan agar tidak terkirim dua kali.
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+use crate::i18n::tr;
+use crate::protocol::{Interaction, MessageComponent};
+
+pub const INTERACTION_TIMEOUT_MS: u32 = 10_000; // Komponen aktif lagi jika bot tidak membalas selama ini
+
+pub fn view_components(message_id: &str, room: Option<&str>, components: &[MessageComponent], pending: bool, on_interact: Callback<Interaction>) -> Html {
+    if components.is_empty() {
+        return html! {};
+    }
+    let interaction = {
+        let (message_id, room) = (message_id.to_string(), room.map(str::to_string));
+        move |custom_id: &str, values: Vec<String>| Interaction {
+            message_id: message_id.clone(),
+            room: room.clone(),
+            custom_id: custom_id.to_string(),
+            values,
+        }
+    };
+    html! {
+        <div class={classes!("message-components", pending.then_some("pending"))}>
+            { for components.iter().map(|component| match component {
+                MessageComponent::Button { custom_id, label, style, disabled } => {
+                    let onclick = {
+                        let interaction = interaction(custom_id, Vec::new());
+                        on_interact.reform(move |_| interaction.clone())
+                    };
+                    html! {
+                        <button class={classes!("component-button", style.class())} {onclick} disabled={*disabled || pending}>{ label }</button>
+                    }
+                }
+                MessageComponent::Select { custom_id, placeholder, options, disabled } => {
+                    let onchange = {
+                        let (interaction, custom_id, on_interact) = (interaction.clone(), custom_id.clone(), on_interact.clone());
+                        Callback::from(move |e: Event| {
+                            let select: HtmlSelectElement = e.target_unchecked_into();
+                            let value = select.value();
+                            if !value.is_empty() {
+                                on_interact.emit(interaction(&custom_id, vec![value]));
+                            }
+                        })
+                    };
+                    html! {
+                        <select class="component-select" {onchange} disabled={*disabled || pending}>
+                            <option value="" selected=true>{ placeholder.as_deref().unwrap_or(tr("Pilih…")) }</option>
+                            { for options.iter().map(|option| html! {
+                                <option value={option.value.clone()}>{ &option.label }</option>
+                            }) }
+                        </select>
+                    }
+                }
+            }) }
+            if pending {
+                <span class="component-pending">{ tr("Menunggu bot…") }</span>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,54 @@
+// src/config.rs
+// Konfigurasi koneksi yang ditentukan deployment/server, bukan oleh user (lihat Settings untuk
+// preferensi user). Nilai default cukup untuk pengembangan lokal.
+use crate::protocol::IceServer;
+
+const FALLBACK_WS_URL: &str = "ws://127.0.0.1:8080/ws"; // Jika tidak ada window (mis. di luar browser)
+const WS_PATH: &str = "/ws";
+const DEFAULT_STUN: &str = "stun:stun.l.google.com:19302";
+const ICE_REFRESH_MARGIN_SECS: u32 = 60; // Minta kredensial TURN baru sebelum yang lama kedaluwarsa
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatConfig {
+    pub ws_url: String, // Dari AppProps, atau diturunkan dari alamat halaman
+    pub ice_servers: Vec<IceServer>, // Dipakai fitur WebRTC (panggilan, transfer file) untuk menembus NAT
+    pub ice_servers_from_server: bool, // false = masih memakai default karena server belum mengirim IceServers
+}
+
+impl Default for ChatConfig {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl ChatConfig {
+    pub fn new(ws_url: Option<String>) -> Self {
+        Self {
+            ws_url: ws_url.unwrap_or_else(default_ws_url),
+            ice_servers: vec![IceServer { urls: vec![DEFAULT_STUN.to_string()], username: None, credential: None }],
+            ice_servers_from_server: false,
+        }
+    }
+
+    // Terapkan IceServers dari server. Mengembalikan jeda (millis) sebelum daftar perlu diminta ulang.
+    pub fn apply_ice_servers(&mut self, servers: Vec<IceServer>, ttl_secs: Option<u32>) -> Option<u32> {
+        if !servers.is_empty() {
+            self.ice_servers = servers;
+            self.ice_servers_from_server = true;
+        }
+        ttl_secs.map(|ttl| ttl.saturating_sub(ICE_REFRESH_MARGIN_SECS).max(ttl / 2) * 1000)
+    }
+}
+
+// Server WebSocket di host yang sama dengan halaman: "wss://" jika halaman dibuka lewat HTTPS,
+// sehingga build yang sama jalan di `trunk serve` maupun di belakang TLS di produksi
+pub fn default_ws_url() -> String {
+    let Some(location) = web_sys::window().map(|w| w.location()) else { return FALLBACK_WS_URL.to_string() };
+    match (location.protocol(), location.host()) {
+        (Ok(protocol), Ok(host)) if !host.is_empty() => {
+            let scheme = if protocol == "https:" { "wss:" } else { "ws:" };
+            format!("{}//{}{}", scheme, host, WS_PATH)
+        }
+        _ => FALLBACK_WS_URL.to_string(), // Mis. halaman dibuka dari file://
+    }
+}
@@ -0,0 +1,78 @@
+// src/date_header.rs
+// Mencari entry paling atas yang terlihat di daftar pesan dengan IntersectionObserver, untuk
+// chip tanggal yang mengambang saat riwayat digulir. Tiap `<li>` entry diberi atribut
+// `data-entry` berisi index-nya di MessageStore.
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeSet;
+use std::ops::Range;
+use std::rc::Rc;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, IntersectionObserver, IntersectionObserverEntry, IntersectionObserverInit};
+use yew::Callback;
+
+pub struct TopEntryObserver {
+    observer: IntersectionObserver,
+    visible: Rc<RefCell<BTreeSet<usize>>>,
+    observed: Range<usize>, // Index entry yang dirender saat elemen terakhir kali didaftarkan
+    _on_change: Closure<dyn FnMut(js_sys::Array)>,
+}
+
+impl TopEntryObserver {
+    // `root` adalah elemen yang di-scroll; `on_top` dipanggil hanya saat entry teratas berubah
+    pub fn new(root: &Element, on_top: Callback<Option<usize>>) -> Option<Self> {
+        let visible = Rc::new(RefCell::new(BTreeSet::new()));
+        let last_top = Rc::new(Cell::new(None));
+        let on_change = {
+            let visible = visible.clone();
+            Closure::wrap(Box::new(move |entries: js_sys::Array| {
+                let mut visible = visible.borrow_mut();
+                for entry in entries.iter().filter_map(|e| e.dyn_into::<IntersectionObserverEntry>().ok()) {
+                    let Some(index) = entry_index(&entry.target()) else { continue };
+                    if entry.is_intersecting() {
+                        visible.insert(index);
+                    } else {
+                        visible.remove(&index);
+                    }
+                }
+                let top = visible.first().copied();
+                if last_top.replace(top) != top {
+                    on_top.emit(top);
+                }
+            }) as Box<dyn FnMut(js_sys::Array)>)
+        };
+        let options = IntersectionObserverInit::new();
+        options.set_root(Some(root));
+        let observer = IntersectionObserver::new_with_options(on_change.as_ref().unchecked_ref(), &options).ok()?;
+        Some(Self { observer, visible, observed: 0..0, _on_change: on_change })
+    }
+
+    // Daftarkan ulang elemen entry jika entry yang dirender berubah (pesan baru, riwayat dimuat, pesan kedaluwarsa,
+    // jendela render diperlebar). Browser langsung melaporkan status semua elemen yang baru didaftarkan,
+    // sehingga set terlihat dibangun ulang.
+    pub fn sync(&mut self, list: &Element, rendered: Range<usize>) {
+        if rendered == self.observed {
+            return;
+        }
+        self.observed = rendered;
+        self.observer.disconnect();
+        self.visible.borrow_mut().clear();
+        let Ok(nodes) = list.query_selector_all("li[data-entry]") else { return };
+        for node in (0..nodes.length()).filter_map(|i| nodes.item(i)) {
+            if let Ok(el) = node.dyn_into::<Element>() {
+                self.observer.observe(&el);
+            }
+        }
+    }
+}
+
+impl Drop for TopEntryObserver {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
+
+fn entry_index(el: &Element) -> Option<usize> {
+    el.get_attribute("data-entry")?.parse().ok()
+}
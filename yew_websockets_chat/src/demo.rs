@@ -0,0 +1,699 @@
+// src/demo.rs
+// Server tiruan di dalam browser untuk build dengan fitur `demo-server`: `trunk serve --features demo-server`
+// sudah memberi chat yang berfungsi tanpa backend. Frame berjalan lewat channel di memori dengan
+// format JSON yang sama seperti WebSocket, sehingga seluruh jalur klien (FrameWriter, parser, reducer) tetap dipakai.
+// Skenario (banjir pesan, gagal autentikasi, restart server, dst.) bisa dipicu dari menu dev untuk
+// memunculkan keadaan UI yang sulit didapat dari server sungguhan.
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use futures_channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures_util::{stream, SinkExt, StreamExt};
+use gloo_net::websocket::{events::CloseEvent, Message as WsMessage, WebSocketError};
+use gloo_timers::future::TimeoutFuture;
+use wasm_bindgen_futures::spawn_local;
+
+use crate::client::{ChatTransport, FrameStream};
+use crate::handle::FrameSink;
+use crate::mentions;
+use crate::protocol::{BotCommand, ButtonStyle, Capability, ClientCommand, CommandArg, CustomStatus, FrameTag, JoinFailure, MessageComponent, SelectOption, MemberInfo, PresenceEvent, RoomEvent, RosterUser, Rsvp, ServerEvent, SessionInfo, SupportAgent, SupportTicket, UserMatch, UsernameRules};
+use crate::ChatMessage;
+
+const BOT_ID: &str = "demo-bot";
+const BOT_NAME: &str = "Demo Bot";
+const BOT_REPLY_DELAY_MS: u32 = 700;
+const HISTORY_LIMIT: usize = 500; // Room demo hanya menyimpan pesan terbaru
+const GENERAL_ROOM: &str = ""; // Room umum sebelum user bergabung ke room lain
+const BURST_SIZE: usize = 1_000;
+const BURST_USERS: [&str; 5] = ["Ayu", "Bima", "Citra", "Dodi", "Eka"];
+const RATE_LIMIT_SECS: u32 = 30;
+const REMINDER_LEAD_MS: f64 = 5.0 * 60_000.0; // Pengingat acara 5 menit sebelum mulai
+const DEMO_USER_ID: &str = "demo-you"; // Sampai klien mengirim pesan yang membawa id akunnya
+const MAINTENANCE_MS: f64 = 30_000.0;
+
+type Outbox = UnboundedSender<Result<WsMessage, WebSocketError>>;
+
+thread_local! {
+    // Jalur kontrol ke server demo yang sedang berjalan; diganti tiap kali klien terhubung ulang
+    static CONTROL: RefCell<Option<UnboundedSender<Scenario>>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scenario {
+    MessageBurst,  // 1.000 pesan dari beberapa user sekaligus
+    AuthFailure,   // Sesi dicabut lalu koneksi ditutup dengan kode 4001
+    RateLimited,   // Pesan berikutnya ditolak slow mode
+    ServerRestart, // Koneksi ditutup (1012); reconnect mendapat server baru tanpa riwayat
+    GarbledFrames, // JSON rusak, event tak dikenal, tipe field salah, frame biner
+    Maintenance,   // Pemeliharaan 30 detik lalu koneksi ditutup (1001); klien menyambung lagi sendiri
+}
+
+impl Scenario {
+    pub const ALL: [Scenario; 6] = [Scenario::MessageBurst, Scenario::AuthFailure, Scenario::RateLimited, Scenario::ServerRestart, Scenario::GarbledFrames, Scenario::Maintenance];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Scenario::MessageBurst => "Banjir 1.000 pesan",
+            Scenario::AuthFailure => "Gagal autentikasi",
+            Scenario::RateLimited => "Kena rate limit",
+            Scenario::ServerRestart => "Server restart",
+            Scenario::GarbledFrames => "Frame rusak",
+            Scenario::Maintenance => "Pemeliharaan server",
+        }
+    }
+}
+
+// Jalankan skenario di server demo yang sedang terhubung
+pub fn run_scenario(scenario: Scenario) {
+    CONTROL.with(|control| match control.borrow().as_ref() {
+        Some(tx) if tx.unbounded_send(scenario).is_ok() => {}
+        _ => log::warn!("Server demo tidak sedang berjalan; skenario {:?} diabaikan", scenario),
+    });
+}
+
+enum Input {
+    Frame(WsMessage),
+    Scenario(Scenario),
+}
+
+// Transport `demo-server`: tiap connect menjalankan server tiruan baru
+pub struct DemoTransport;
+
+impl ChatTransport for DemoTransport {
+    fn connect(&self, url: &str) -> Result<(FrameSink, FrameStream), String> {
+        Ok(connect(url))
+    }
+}
+
+fn connect(url: &str) -> (FrameSink, FrameStream) {
+    log::info!("Fitur demo-server aktif: {} tidak dihubungi, memakai server tiruan di browser", url);
+    let (client_tx, client_rx) = mpsc::unbounded::<WsMessage>();
+    let (server_tx, server_rx) = mpsc::unbounded();
+    let (control_tx, control_rx) = mpsc::unbounded();
+    CONTROL.with(|control| *control.borrow_mut() = Some(control_tx));
+    spawn_local(DemoServer::new(server_tx).run(client_rx, control_rx));
+    let sink: FrameSink = Box::pin(client_tx.sink_map_err(|e| e.to_string()));
+    (sink, server_rx.boxed_local())
+}
+
+#[derive(Default)]
+struct DemoRoom {
+    history: Vec<ChatMessage>,
+    slow_mode_secs: u32,
+    embeds_disabled: bool,
+    password: Option<String>,
+    feedback_box: bool,
+    language: Option<String>,
+}
+
+struct DemoServer {
+    tx: Outbox,
+    rooms: HashMap<String, DemoRoom>,
+    current: String, // Room yang terakhir diikuti; tujuan skenario dan pesan tanpa field room
+    next_id: u64,
+    last_sent: Option<f64>, // Untuk slow mode; demo hanya punya satu user nyata
+    user_id: String, // Id akun satu-satunya user nyata, untuk daftar RSVP
+    events: HashMap<String, RoomEvent>,
+    support: Vec<SupportTicket>, // Antrean tamu tiruan, dibuat saat mode agen pertama kali diaktifkan
+}
+
+impl DemoServer {
+    fn new(tx: Outbox) -> Self {
+        let mut rooms = HashMap::new();
+        rooms.insert(GENERAL_ROOM.to_string(), DemoRoom::default());
+        Self { tx, rooms, current: GENERAL_ROOM.to_string(), next_id: 0, last_sent: None, user_id: DEMO_USER_ID.to_string(), events: HashMap::new(), support: Vec::new() }
+    }
+
+    async fn run(mut self, rx: UnboundedReceiver<WsMessage>, control: UnboundedReceiver<Scenario>) {
+        emit_json(&self.tx, &PresenceEvent::Presence { user_id: BOT_ID.to_string(), online: true, last_seen: None });
+        let welcome = self.bot_message("Selamat datang di mode demo! Semua pesan hanya ada di tab ini. Coba /create, /join atau Ctrl+K.");
+        self.store(&welcome);
+        emit_json(&self.tx, &welcome);
+        let mut inputs = stream::select(rx.map(Input::Frame), control.map(Input::Scenario));
+        while let Some(input) = inputs.next().await {
+            let frame = match input {
+                Input::Frame(frame) => frame,
+                Input::Scenario(scenario) => {
+                    if self.play(scenario) {
+                        continue;
+                    }
+                    break; // Skenario menutup koneksi
+                }
+            };
+            let WsMessage::Text(text) = frame else { continue };
+            // Dibedakan dengan aturan yang sama seperti chat-server: bertag "type" = perintah
+            let parsed = match serde_json::from_str::<FrameTag>(&text) {
+                Ok(FrameTag { kind: Some(_) }) => serde_json::from_str::<ClientCommand>(&text).map(|command| self.handle_command(command)),
+                Ok(FrameTag { kind: None }) => serde_json::from_str::<ChatMessage>(&text).map(|message| self.handle_message(message)),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = parsed {
+                log::warn!("Server demo menerima frame tidak dikenal ({}): {}", e, text);
+            }
+        }
+    }
+
+    fn handle_command(&mut self, command: ClientCommand) {
+        match command {
+            ClientCommand::TimeSync { client_time } => {
+                self.emit(&ServerEvent::TimeSync { client_time, server_time: js_sys::Date::now() });
+            }
+            ClientCommand::Ping { nonce } => self.emit(&ServerEvent::Pong { nonce }),
+            // Server demo menerima token apa pun yang tidak kosong; identitas lokal klien tetap dipakai
+            ClientCommand::Authenticate { token } => {
+                let reason = token.trim().is_empty().then(|| "Token kosong.".to_string());
+                self.emit(&ServerEvent::AuthResult { ok: reason.is_none(), user_id: None, name: None, reason });
+            }
+            ClientCommand::JoinRoom { room, password } => match self.rooms.get(&room) {
+                None => self.emit(&ServerEvent::JoinFailed { room, reason: JoinFailure::RoomNotFound }),
+                Some(existing) if existing.password.is_some() && existing.password != password => {
+                    let reason = if password.is_none() { JoinFailure::PasswordRequired } else { JoinFailure::WrongPassword };
+                    self.emit(&ServerEvent::JoinFailed { room, reason });
+                }
+                Some(_) => self.join(room, false),
+            },
+            ClientCommand::CreateRoom { room, password, feedback_box, .. } => {
+                if self.rooms.contains_key(&room) {
+                    self.emit(&ServerEvent::JoinFailed { room, reason: JoinFailure::RoomExists });
+                    return;
+                }
+                self.rooms.insert(room.clone(), DemoRoom { password, feedback_box, ..DemoRoom::default() });
+                self.join(room, true);
+            }
+            ClientCommand::SetSlowMode { room, interval_secs } => {
+                if let Some(target) = self.rooms.get_mut(&room) {
+                    target.slow_mode_secs = interval_secs;
+                    self.emit(&ServerEvent::SlowModeChanged { room, interval_secs });
+                }
+            }
+            ClientCommand::SetEmbedsDisabled { room, disabled } => {
+                if let Some(target) = self.rooms.get_mut(&room) {
+                    target.embeds_disabled = disabled;
+                    self.emit(&ServerEvent::EmbedsChanged { room, disabled });
+                }
+            }
+            ClientCommand::SetRoomLanguage { room, language } => {
+                if let Some(target) = self.rooms.get_mut(&room) {
+                    target.language = language.clone();
+                    self.emit(&ServerEvent::RoomLanguageChanged { room, language });
+                }
+            }
+            ClientCommand::Translate { message_id, target, .. } => {
+                // Demo tidak punya mesin terjemahan: teks asli diberi tanda bahasa tujuan
+                let original = self.rooms.values().flat_map(|room| room.history.iter()).find(|message| message.id.as_ref() == Some(&message_id));
+                match original.map(|message| format!("[{}] {}", target, message.text)) {
+                    Some(text) => self.emit(&ServerEvent::Translated { message_id, target, text }),
+                    None => self.emit(&ServerEvent::TranslationFailed { message_id, reason: Some("Pesan tidak ditemukan".to_string()) }),
+                }
+            }
+            ClientCommand::Resume { limit, .. } => {
+                let history = &self.room().history;
+                let messages = history[history.len().saturating_sub(limit)..].to_vec();
+                let total_missed = history.len() as u64;
+                self.emit(&ServerEvent::HistoryReplay { messages, total_missed });
+            }
+            ClientCommand::HistoryRequest { room, before_id, limit } => {
+                let history = self.rooms.get(room.as_deref().unwrap_or(GENERAL_ROOM)).map(|joined| joined.history.as_slice()).unwrap_or_default();
+                let end = match &before_id {
+                    Some(before_id) => history.iter().position(|message| message.id.as_ref() == Some(before_id)).unwrap_or(0),
+                    None => history.len(),
+                };
+                let start = end.saturating_sub(limit);
+                let messages = history[start..end].to_vec();
+                self.emit(&ServerEvent::HistoryResponse { room, before_id, messages, has_more: start > 0 });
+            }
+            ClientCommand::FetchHistory { room, .. } => {
+                let room = room.unwrap_or_else(|| GENERAL_ROOM.to_string());
+                let messages = self.rooms.get(&room).map(|room| room.history.clone()).unwrap_or_default();
+                self.emit(&ServerEvent::HistoryPage { messages });
+            }
+            ClientCommand::FetchMembers { room, query, .. } => {
+                // Anggota demo muat dalam satu halaman
+                let needle = query.as_ref().map(|q| q.to_lowercase());
+                let members = demo_members()
+                    .filter(|member| needle.as_ref().is_none_or(|needle| member.name.to_lowercase().contains(needle.as_str())))
+                    .collect::<Vec<_>>();
+                let total = Some(members.len() as u64);
+                self.emit(&ServerEvent::MemberPage { room, query, members, next_cursor: None, total });
+            }
+            ClientCommand::SearchUsers { prefix, limit } => {
+                let users = demo_members()
+                    .filter(|member| mentions::matches_prefix(&member.user_id, &member.name, &prefix))
+                    .map(|member| UserMatch { user_id: member.user_id, name: member.name })
+                    .take(limit)
+                    .collect();
+                self.emit(&ServerEvent::UserSearchResults { prefix, users });
+            }
+            ClientCommand::CreateEvent { title, starts_at, .. } => {
+                let event = RoomEvent {
+                    id: format!("event-{}", self.events.len() + 1),
+                    title,
+                    starts_at,
+                    going: vec![self.user_id.clone()],
+                    maybe: Vec::new(),
+                    declined: Vec::new(),
+                };
+                self.events.insert(event.id.clone(), event.clone());
+                let mut card = self.system_message("Acara baru dijadwalkan");
+                card.event = Some(Box::new(event.clone()));
+                self.store(&card);
+                emit_json(&self.tx, &card);
+
+                let mut reminder = self.system_message(&format!("⏰ \"{}\" dimulai sebentar lagi", event.title));
+                reminder.event = Some(Box::new(event));
+                let delay_ms = (starts_at - REMINDER_LEAD_MS - js_sys::Date::now()).max(0.0) as u32;
+                let tx = self.tx.clone();
+                spawn_local(async move {
+                    TimeoutFuture::new(delay_ms).await;
+                    emit_json(&tx, &reminder);
+                });
+            }
+            ClientCommand::Rsvp { event_id, response } => {
+                let Some(event) = self.events.get_mut(&event_id) else { return };
+                for list in [&mut event.going, &mut event.maybe, &mut event.declined] {
+                    list.retain(|id| *id != self.user_id);
+                }
+                match response {
+                    Rsvp::Going => event.going.push(self.user_id.clone()),
+                    Rsvp::Maybe => event.maybe.push(self.user_id.clone()),
+                    Rsvp::Declined => event.declined.push(self.user_id.clone()),
+                }
+                let event = event.clone();
+                // Kartu di riwayat ikut diperbarui agar replay setelah join ulang tidak basi
+                for message in self.rooms.values_mut().flat_map(|room| room.history.iter_mut()) {
+                    if let Some(card) = message.event.as_deref_mut().filter(|card| card.id == event.id) {
+                        *card = event.clone();
+                    }
+                }
+                self.emit(&ServerEvent::EventUpdated { event });
+            }
+            ClientCommand::DirectMessage { to, text } => {
+                let mut message = ChatMessage { user_id: Some(self.user_id.clone()), username: "Anda".to_string(), room: None, ..self.bot_message(&text) };
+                self.emit(&ServerEvent::DirectMessage { to: to.clone(), message: Box::new(message.clone()) });
+                if to != BOT_ID {
+                    return; // User tiruan lain tidak membalas
+                }
+                message = ChatMessage { room: None, ..self.bot_message(&format!("(DM) {}", bot_reply(&text))) };
+                let reply = ServerEvent::DirectMessage { to: self.user_id.clone(), message: Box::new(message) };
+                let tx = self.tx.clone();
+                spawn_local(async move {
+                    TimeoutFuture::new(BOT_REPLY_DELAY_MS).await;
+                    emit_json(&tx, &reply);
+                });
+            }
+            ClientCommand::SyncRoster { room } => {
+                // Satu-satunya user lain di server demo adalah bot
+                let custom_status = Some(CustomStatus { emoji: Some("🤖".to_string()), text: "Siap membalas".to_string(), expires_at: None });
+                let users = vec![RosterUser { user_id: BOT_ID.to_string(), name: BOT_NAME.to_string(), status: Default::default(), custom_status }];
+                emit_json(&self.tx, &PresenceEvent::Roster { room, users });
+            }
+            ClientCommand::SetStatus { status } => {
+                emit_json(&self.tx, &PresenceEvent::StatusChanged { user_id: self.user_id.clone(), status });
+            }
+            ClientCommand::SetCustomStatus { status } => {
+                emit_json(&self.tx, &PresenceEvent::CustomStatusChanged { user_id: self.user_id.clone(), status });
+            }
+            ClientCommand::SetSupportAgent { enabled: true } => {
+                if self.support.is_empty() {
+                    self.support = demo_tickets();
+                }
+                self.emit(&ServerEvent::SupportQueue { tickets: self.support.clone() });
+            }
+            ClientCommand::ClaimConversation { guest } => {
+                let agent = SupportAgent { user_id: self.user_id.clone(), name: "Anda".to_string() };
+                let Some(ticket) = self.support.iter_mut().find(|ticket| ticket.guest == guest) else { return };
+                if ticket.agent.is_some() {
+                    let ticket = ticket.clone();
+                    self.emit(&ServerEvent::SupportTicket { ticket }); // Sudah diambil agen lain
+                    return;
+                }
+                ticket.agent = Some(agent);
+                let (ticket, preview) = (ticket.clone(), ticket.preview.clone().unwrap_or_default());
+                let message = ChatMessage { user_id: Some(guest.clone()), username: ticket.guest_name.clone(), room: None, ..self.bot_message(&preview) };
+                self.emit(&ServerEvent::SupportTicket { ticket });
+                self.emit(&ServerEvent::DirectMessage { to: self.user_id.clone(), message: Box::new(message) });
+            }
+            ClientCommand::ReleaseConversation { guest } => {
+                let Some(ticket) = self.support.iter_mut().find(|ticket| ticket.guest == guest) else { return };
+                ticket.agent = None;
+                let ticket = ticket.clone();
+                self.emit(&ServerEvent::SupportTicket { ticket });
+            }
+            ClientCommand::LeaveRoom { room } => {
+                // Room tetap ada; demo hanya punya satu user nyata jadi cukup dikonfirmasi
+                self.emit(&ServerEvent::RoomLeft { room });
+            }
+            ClientCommand::ListCommands { room } => {
+                self.emit(&ServerEvent::Commands { room, commands: bot_commands() });
+            }
+            ClientCommand::RunCommand { room, name, args } => {
+                let text = match name.as_str() {
+                    "roll" => {
+                        let sides = args.trim().parse::<u32>().ok().filter(|&sides| sides > 1).unwrap_or(6);
+                        let roll = (js_sys::Math::random() * sides as f64) as u32 + 1;
+                        format!("🎲 Dadu {} sisi: {}", sides, roll)
+                    }
+                    "quote" => "\"Kode yang baik adalah dokumentasinya sendiri.\"".to_string(),
+                    "approval" if !args.trim().is_empty() => {
+                        let request = ChatMessage {
+                            room: Some(room),
+                            components: approval_components(false),
+                            ..self.bot_message(&format!("Permintaan persetujuan: {}", args.trim()))
+                        };
+                        self.store(&request);
+                        emit_json(&self.tx, &request);
+                        return;
+                    }
+                    "approval" => "Penggunaan: /approval <judul>".to_string(),
+                    _ => format!("Perintah /{} tidak dikenal bot demo.", name),
+                };
+                let reply = ChatMessage { room: Some(room), ..self.bot_message(&text) };
+                self.store(&reply);
+                emit_json(&self.tx, &reply);
+            }
+            ClientCommand::Interaction(interaction) => {
+                let mut history = self.rooms.values_mut().flat_map(|room| room.history.iter_mut());
+                let Some(request) = history.find(|message| message.id.as_ref() == Some(&interaction.message_id)) else { return };
+                let outcome = match (interaction.custom_id.as_str(), interaction.values.first()) {
+                    ("approve", _) => "✅ disetujui",
+                    ("reject", _) => "❌ ditolak",
+                    ("priority", Some(priority)) => {
+                        let text = format!("Prioritas \"{}\" diubah menjadi {}", request.text, priority);
+                        let room = request.room.clone();
+                        let reply = ChatMessage { room, ..self.bot_message(&text) };
+                        self.store(&reply);
+                        emit_json(&self.tx, &reply);
+                        return;
+                    }
+                    _ => return,
+                };
+                // Setelah diputuskan tombol dinonaktifkan untuk semua orang
+                request.components = approval_components(true);
+                let (message_id, room, text) = (interaction.message_id, request.room.clone(), format!("{}: {}", request.text, outcome));
+                self.emit(&ServerEvent::ComponentsUpdated { message_id, components: approval_components(true) });
+                let reply = ChatMessage { room, ..self.bot_message(&text) };
+                self.store(&reply);
+                emit_json(&self.tx, &reply);
+            }
+            ClientCommand::ListSessions => {
+                let session = SessionInfo {
+                    session_id: "demo".to_string(),
+                    device: "Browser ini (demo)".to_string(),
+                    location: None,
+                    last_active: js_sys::Date::now(),
+                    current: true,
+                };
+                self.emit(&ServerEvent::Sessions { sessions: vec![session] });
+            }
+            ClientCommand::Hello { capabilities } => {
+                // Frame demo selalu JSON; operator akun bersama ikut ditampilkan di pesan yang dipantulkan
+                let capabilities = capabilities.into_iter().filter(|capability| *capability == Capability::Operators).collect();
+                self.emit(&ServerEvent::CapabilitiesAccepted { capabilities });
+            }
+            ClientCommand::Typing { .. } | ClientCommand::MarkRead { .. } | ClientCommand::Custom { .. } => {} // Tidak ada user lain yang perlu diberi tahu
+            other => log::info!("Server demo mengabaikan perintah {:?}", other),
+        }
+    }
+
+    fn handle_message(&mut self, mut message: ChatMessage) {
+        let now = js_sys::Date::now();
+        // Aturan bawaan chat-server
+        if let Err(error) = UsernameRules::default().validate(&message.username) {
+            if let Some(client_id) = message.client_id {
+                self.emit(&ServerEvent::MessageRejected { client_id, reason: error.message() });
+            }
+            return;
+        }
+        // Klien multi-room menandai room tujuan; semua pesan keluar dari server ini selalu bertanda room
+        let room = message.room.clone().unwrap_or_else(|| self.current.clone());
+        let slow_mode_secs = self.rooms.get(&room).map_or(0, |target| target.slow_mode_secs);
+        if let Some(last) = self.last_sent.filter(|_| slow_mode_secs > 0) {
+            let elapsed_secs = ((now - last) / 1000.0) as u32;
+            if elapsed_secs < slow_mode_secs {
+                let client_id = message.client_id.clone();
+                self.emit(&ServerEvent::SlowModeRejected { room, retry_after_secs: slow_mode_secs - elapsed_secs, client_id });
+                return;
+            }
+        }
+        self.last_sent = Some(now);
+        if let Some(user_id) = &message.user_id {
+            self.user_id = user_id.clone();
+        }
+        message.id = Some(self.next_message_id());
+        message.timestamp = Some(js_sys::Date::now());
+        message.room = Some(room.clone());
+        if let Some(target) = message.whisper_to.clone() {
+            // Satu-satunya user lain di server demo adalah bot; bisikan tidak masuk riwayat
+            if target != BOT_ID {
+                if let Some(client_id) = message.client_id {
+                    self.emit(&ServerEvent::MessageRejected { client_id, reason: "Penerima bisikan tidak ada di room ini.".to_string() });
+                }
+                return;
+            }
+            emit_json(&self.tx, &message);
+            let reply = ChatMessage { room: Some(room), whisper_to: Some(self.user_id.clone()), ..self.bot_message(&format!("(bisik) {}", bot_reply(&message.text))) };
+            let tx = self.tx.clone();
+            spawn_local(async move {
+                TimeoutFuture::new(BOT_REPLY_DELAY_MS).await;
+                emit_json(&tx, &reply);
+            });
+            return;
+        }
+        if self.rooms.get(&room).is_some_and(|target| target.feedback_box) {
+            // Riwayat (yang dibaca moderator) hanya menyimpan salinan tanpa identitas; pengirim menerima salinan utuh
+            self.store(&ChatMessage { user_id: None, username: "Anonim".to_string(), ..message.clone() });
+            emit_json(&self.tx, &message);
+            return; // Bot tidak membalas kiriman kotak saran
+        }
+        self.store(&message);
+        emit_json(&self.tx, &message);
+
+        // Balasan bot dikirim sedikit terlambat agar terasa seperti user lain
+        let reply = ChatMessage { room: Some(room), ..self.bot_message(&bot_reply(&message.text)) };
+        self.store(&reply);
+        let tx = self.tx.clone();
+        spawn_local(async move {
+            TimeoutFuture::new(BOT_REPLY_DELAY_MS).await;
+            emit_json(&tx, &reply);
+        });
+    }
+
+    // Mengembalikan false jika skenario menutup koneksi
+    fn play(&mut self, scenario: Scenario) -> bool {
+        log::info!("Server demo menjalankan skenario {:?}", scenario);
+        match scenario {
+            Scenario::MessageBurst => {
+                for i in 0..BURST_SIZE {
+                    let user = BURST_USERS[i % BURST_USERS.len()];
+                    let message = ChatMessage {
+                        id: Some(self.next_message_id()),
+                        client_id: None,
+                        user_id: Some(format!("demo-{}", user.to_lowercase())),
+                        username: user.to_string(),
+                        text: format!("Pesan banjir #{}", i + 1),
+                        timestamp: Some(js_sys::Date::now()),
+                        edited_at: None,
+                        attachment: None,
+                        expires_at: None,
+                        event: None,
+                        system: false,
+                        components: Vec::new(),
+                        room: Some(self.current.clone()),
+                        operator: None,
+                        whisper_to: None,
+                    };
+                    self.store(&message);
+                    emit_json(&self.tx, &message);
+                }
+                true
+            }
+            Scenario::AuthFailure => {
+                self.emit(&ServerEvent::SessionRevoked { reason: Some("Skenario demo: token sesi kedaluwarsa".to_string()) });
+                self.close(4001, "Unauthorized");
+                false
+            }
+            Scenario::RateLimited => {
+                self.last_sent = Some(js_sys::Date::now());
+                self.emit(&ServerEvent::SlowModeRejected { room: self.current.clone(), retry_after_secs: RATE_LIMIT_SECS, client_id: None });
+                true
+            }
+            Scenario::ServerRestart => {
+                self.close(1012, "Service Restart");
+                false
+            }
+            Scenario::Maintenance => {
+                let message = Some("Skenario demo: pembaruan database, chat kembali sebentar lagi.".to_string());
+                self.emit(&ServerEvent::Maintenance { until: js_sys::Date::now() + MAINTENANCE_MS, message });
+                self.close(1001, "Going Away");
+                false
+            }
+            Scenario::GarbledFrames => {
+                let garbled = [
+                    r#"{"username": "rusak", "text": "#,                  // JSON terpotong
+                    r#"{"type": "EventTidakDikenal", "data": 1}"#,           // Tag event yang tidak ada
+                    r#"{"username": 42, "text": true}"#,                     // Tipe field salah
+                    "bukan json sama sekali",
+                ];
+                for text in garbled {
+                    let _ = self.tx.unbounded_send(Ok(WsMessage::Text(text.to_string())));
+                }
+                let _ = self.tx.unbounded_send(Ok(WsMessage::Bytes(vec![0xde, 0xad, 0xbe, 0xef])));
+                true
+            }
+        }
+    }
+
+    fn close(&self, code: u16, reason: &str) {
+        let event = CloseEvent { code, reason: reason.to_string(), was_clean: true };
+        let _ = self.tx.unbounded_send(Err(WebSocketError::ConnectionClose(event)));
+    }
+
+    fn join(&mut self, room: String, is_moderator: bool) {
+        self.current = room.clone();
+        self.last_sent = None;
+        let joined = self.room();
+        let event = ServerEvent::RoomJoined {
+            room,
+            expires_at: None,
+            is_moderator,
+            slow_mode_secs: joined.slow_mode_secs,
+            rules: None,
+            rules_accepted: false,
+            embeds_disabled: joined.embeds_disabled,
+            dm_peer: None,
+            pseudonym: None,
+            message_ttl_secs: None,
+            presence_digest_secs: None,
+            feedback_box: joined.feedback_box,
+            language: joined.language.clone(),
+        };
+        let messages = joined.history.clone();
+        let total_missed = messages.len() as u64;
+        self.emit(&event);
+        self.emit(&ServerEvent::HistoryReplay { messages, total_missed });
+    }
+
+    fn bot_message(&mut self, text: &str) -> ChatMessage {
+        ChatMessage {
+            id: Some(self.next_message_id()),
+            client_id: None,
+            user_id: Some(BOT_ID.to_string()),
+            username: BOT_NAME.to_string(),
+            text: text.to_string(),
+            timestamp: Some(js_sys::Date::now()),
+            edited_at: None,
+            attachment: None,
+            expires_at: None,
+            event: None,
+            system: false,
+            components: Vec::new(),
+            room: Some(self.current.clone()),
+            operator: None,
+            whisper_to: None,
+        }
+    }
+
+    fn system_message(&mut self, text: &str) -> ChatMessage {
+        ChatMessage { user_id: None, username: "Server".to_string(), system: true, ..self.bot_message(text) }
+    }
+
+    fn store(&mut self, message: &ChatMessage) {
+        let room = message.room.clone().unwrap_or_else(|| self.current.clone());
+        let history = &mut self.rooms.entry(room).or_default().history;
+        history.push(message.clone());
+        if history.len() > HISTORY_LIMIT {
+            history.remove(0);
+        }
+    }
+
+    fn next_message_id(&mut self) -> String {
+        self.next_id += 1;
+        format!("demo-{}", self.next_id)
+    }
+
+    fn room(&self) -> &DemoRoom {
+        &self.rooms[&self.current]
+    }
+
+    fn emit(&self, event: &ServerEvent) {
+        emit_json(&self.tx, event);
+    }
+}
+
+fn emit_json<T: serde::Serialize>(tx: &Outbox, frame: &T) {
+    match serde_json::to_string(frame) {
+        Ok(text) => {
+            let _ = tx.unbounded_send(Ok(WsMessage::Text(text))); // Klien sudah menutup koneksi: abaikan
+        }
+        Err(e) => log::error!("Server demo gagal serialisasi frame: {}", e),
+    }
+}
+
+fn bot_reply(text: &str) -> String {
+    let words = text.split_whitespace().count();
+    match text.trim() {
+        t if t.ends_with('?') => "Pertanyaan bagus! Di mode demo saya hanya bisa membalas seadanya.".to_string(),
+        t if t.to_lowercase().starts_with("halo") || t.to_lowercase().starts_with("hai") => "Halo juga! 👋".to_string(),
+        _ => format!("Pesan Anda ({} kata) sudah diterima server demo.", words),
+    }
+}
+
+// Perintah yang didaftarkan bot demo di setiap room
+fn bot_commands() -> Vec<BotCommand> {
+    let command = |name: &str, description: &str, args: Vec<CommandArg>| BotCommand {
+        name: name.to_string(),
+        description: description.to_string(),
+        bot_id: BOT_ID.to_string(),
+        bot_name: Some(BOT_NAME.to_string()),
+        args,
+    };
+    vec![
+        command("roll", "Lempar dadu", vec![CommandArg { name: "sisi".to_string(), description: Some("jumlah sisi dadu, bawaan 6".to_string()), required: false }]),
+        command("quote", "Kutipan acak", Vec::new()),
+        command("approval", "Minta persetujuan dengan tombol", vec![CommandArg { name: "judul".to_string(), description: Some("hal yang perlu disetujui".to_string()), required: true }]),
+    ]
+}
+
+// Tombol dan menu untuk pesan /approval; `decided` menonaktifkan semuanya
+fn approval_components(decided: bool) -> Vec<MessageComponent> {
+    let button = |custom_id: &str, label: &str, style| MessageComponent::Button { custom_id: custom_id.to_string(), label: label.to_string(), style, disabled: decided };
+    let priority = |value: &str| SelectOption { value: value.to_string(), label: value.to_string() };
+    vec![
+        button("approve", "Setujui", ButtonStyle::Success),
+        button("reject", "Tolak", ButtonStyle::Danger),
+        MessageComponent::Select {
+            custom_id: "priority".to_string(),
+            placeholder: Some("Prioritas…".to_string()),
+            options: vec![priority("rendah"), priority("sedang"), priority("tinggi")],
+            disabled: decided,
+        },
+    ]
+}
+
+// Bot dan user tiruan dari skenario banjir pesan
+// Satu tamu menunggu dan satu yang sudah ditangani agen tiruan lain
+fn demo_tickets() -> Vec<SupportTicket> {
+    let now = js_sys::Date::now();
+    vec![
+        SupportTicket {
+            guest: "tamu-1".to_string(),
+            guest_name: "Tamu 1".to_string(),
+            preview: Some("Halo, pesanan saya belum sampai.".to_string()),
+            opened_at: now - 4.0 * 60_000.0,
+            agent: None,
+        },
+        SupportTicket {
+            guest: "tamu-2".to_string(),
+            guest_name: "Tamu 2".to_string(),
+            preview: Some("Bagaimana cara mengganti kata sandi?".to_string()),
+            opened_at: now - 9.0 * 60_000.0,
+            agent: Some(SupportAgent { user_id: "agen-rina".to_string(), name: "Rina".to_string() }),
+        },
+    ]
+}
+
+fn demo_members() -> impl Iterator<Item = MemberInfo> {
+    std::iter::once((BOT_ID.to_string(), BOT_NAME, true))
+        .chain(BURST_USERS.iter().map(|name| (format!("demo-{}", name.to_lowercase()), *name, false)))
+        .map(|(user_id, name, online)| MemberInfo { user_id, name: name.to_string(), online, last_seen: None, custom_status: None })
+}
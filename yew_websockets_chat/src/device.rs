@@ -0,0 +1,44 @@
+// src/device.rs
+// Deteksi perangkat lemah untuk mode ringan: jumlah core CPU, memori (navigator.deviceMemory, hanya
+// di browser Chromium) dan baterai yang hampir habis (navigator.getBattery). API yang tidak tersedia
+// dianggap tidak membatasi, jadi browser lain tetap memakai tampilan penuh kecuali user memilih sendiri.
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+const LOW_END_CORES: u32 = 2;
+const LOW_END_MEMORY_GB: f64 = 2.0;
+const LOW_BATTERY_LEVEL: f64 = 0.2; // Baterai di bawah 20% dan tidak sedang diisi
+
+pub fn is_low_end_hardware() -> bool {
+    let Some(navigator) = web_sys::window().map(|w| w.navigator()) else { return false };
+    let cores = navigator.hardware_concurrency() as u32; // 0 jika browser menyembunyikannya
+    let memory_gb = js_sys::Reflect::get(&navigator, &JsValue::from_str("deviceMemory")).ok().and_then(|value| value.as_f64());
+    (cores > 0 && cores <= LOW_END_CORES) || memory_gb.is_some_and(|gb| gb <= LOW_END_MEMORY_GB)
+}
+
+// Ringkasan perangkat untuk petunjuk di pengaturan, mis. "2 core, 1 GB"
+pub fn describe_hardware() -> String {
+    let Some(navigator) = web_sys::window().map(|w| w.navigator()) else { return String::new() };
+    let mut parts = Vec::new();
+    let cores = navigator.hardware_concurrency() as u32;
+    if cores > 0 {
+        parts.push(format!("{} core", cores));
+    }
+    if let Some(gb) = js_sys::Reflect::get(&navigator, &JsValue::from_str("deviceMemory")).ok().and_then(|value| value.as_f64()) {
+        parts.push(format!("{} GB", gb));
+    }
+    parts.join(", ")
+}
+
+// true jika baterai lemah dan tidak sedang diisi; false jika Battery Status API tidak ada
+pub async fn battery_low() -> bool {
+    let Some(navigator) = web_sys::window().map(|w| w.navigator()) else { return false };
+    let Ok(get_battery) = js_sys::Reflect::get(&navigator, &JsValue::from_str("getBattery")) else { return false };
+    let Some(get_battery) = get_battery.dyn_ref::<js_sys::Function>() else { return false };
+    let Ok(promise) = get_battery.call0(&navigator) else { return false };
+    let Ok(battery) = JsFuture::from(js_sys::Promise::from(promise)).await else { return false };
+    let read = |name: &str| js_sys::Reflect::get(&battery, &JsValue::from_str(name)).ok();
+    let charging = read("charging").and_then(|value| value.as_bool()).unwrap_or(true);
+    let level = read("level").and_then(|value| value.as_f64()).unwrap_or(1.0);
+    !charging && level <= LOW_BATTERY_LEVEL
+}
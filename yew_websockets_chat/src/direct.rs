@@ -0,0 +1,66 @@
+// src/direct.rs
+// Pesan langsung antar user, di luar room. Tiap lawan bicara punya percakapan sendiri yang
+// disimpan terpisah dari pesan room, dengan penghitung belum dibaca selama tab DM-nya tidak dibuka.
+use crate::store::MessageStore;
+use crate::ChatMessage;
+
+#[derive(Debug, Clone, Default)]
+pub struct Conversation {
+    pub peer: String, // User id lawan bicara
+    pub messages: MessageStore,
+    pub unread: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DirectInbox {
+    conversations: Vec<Conversation>, // Aktivitas terbaru dulu
+    open: Option<String>, // Tab DM yang sedang ditampilkan menggantikan daftar pesan room
+}
+
+impl DirectInbox {
+    pub fn conversations(&self) -> &[Conversation] {
+        &self.conversations
+    }
+
+    pub fn open(&self) -> Option<&Conversation> {
+        let peer = self.open.as_ref()?;
+        self.conversations.iter().find(|conversation| conversation.peer == *peer)
+    }
+
+    pub fn open_peer(&self) -> Option<&str> {
+        self.open.as_deref()
+    }
+
+    // Tampilkan percakapan dengan `peer` (dibuat kosong jika belum ada), atau kembali ke room dengan None
+    pub fn set_open(&mut self, peer: Option<String>) -> bool {
+        if peer == self.open {
+            return false;
+        }
+        if let Some(peer) = &peer {
+            match self.conversations.iter_mut().find(|conversation| conversation.peer == *peer) {
+                Some(conversation) => conversation.unread = 0,
+                None => self.conversations.insert(0, Conversation { peer: peer.clone(), ..Conversation::default() }),
+            }
+        }
+        self.open = peer;
+        true
+    }
+
+    // Simpan pesan ke percakapan dengan `peer` dan pindahkan percakapan itu ke atas.
+    // Pesan sendiri (`own`) dan pesan di tab yang sedang dibuka tidak menambah hitungan belum dibaca.
+    pub fn push(&mut self, peer: &str, message: ChatMessage, own: bool) {
+        let index = match self.conversations.iter().position(|conversation| conversation.peer == peer) {
+            Some(index) => index,
+            None => {
+                self.conversations.push(Conversation { peer: peer.to_string(), ..Conversation::default() });
+                self.conversations.len() - 1
+            }
+        };
+        let mut conversation = self.conversations.remove(index);
+        conversation.messages.push(message, None, false);
+        if !own && self.open.as_deref() != Some(peer) {
+            conversation.unread += 1;
+        }
+        self.conversations.insert(0, conversation);
+    }
+}
@@ -0,0 +1,72 @@
+// src/directory.rs
+use std::collections::HashMap;
+
+use crate::i18n::tr;
+use crate::{mentions, storage, ChatMessage};
+
+const USER_ID_KEY: &str = "webchat.user_id";
+pub const DELETED_USER_NAME: &str = "Pengguna terhapus"; // Disimpan apa adanya; diterjemahkan saat ditampilkan
+
+// Direktori user: memetakan id akun yang stabil ke display name terakhir yang diketahui.
+// Pesan menyimpan id, sehingga saat seseorang ganti nama semua pesan lamanya ikut berubah di tampilan.
+#[derive(Debug, Clone, Default)]
+pub struct UserDirectory {
+    names: HashMap<String, String>,
+}
+
+impl UserDirectory {
+    // Catat display name pengirim pesan. Mengembalikan true jika nama berubah (perlu re-render).
+    pub fn observe(&mut self, msg: &ChatMessage) -> bool {
+        match &msg.user_id {
+            Some(id) => self.set(id, &msg.username),
+            None => false,
+        }
+    }
+
+    pub fn set(&mut self, user_id: &str, display_name: &str) -> bool {
+        if self.names.get(user_id).map(String::as_str) == Some(display_name) {
+            return false;
+        }
+        self.names.insert(user_id.to_string(), display_name.to_string());
+        true
+    }
+
+    pub fn name_of(&self, user_id: &str) -> Option<&str> {
+        self.names.get(user_id).map(|name| shown(name))
+    }
+
+    // User yang dikenal klien ini dengan id atau nama berawalan `prefix`, untuk saran @mention
+    pub fn matching<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+        self.names
+            .iter()
+            .filter(move |(id, name)| *name != DELETED_USER_NAME && mentions::matches_prefix(id, name, prefix))
+            .map(|(id, name)| (id.as_str(), name.as_str()))
+    }
+
+    // Nama yang ditampilkan untuk sebuah pesan; server lama tanpa id tetap memakai username di pesan
+    pub fn display_name<'a>(&'a self, msg: &'a ChatMessage) -> &'a str {
+        msg.user_id
+            .as_ref()
+            .and_then(|id| self.names.get(id))
+            .map(|name| shown(name))
+            .unwrap_or(&msg.username)
+    }
+}
+
+fn shown(name: &str) -> &str {
+    if name == DELETED_USER_NAME {
+        tr(DELETED_USER_NAME)
+    } else {
+        name
+    }
+}
+
+// Id akun lokal, dibuat sekali lalu disimpan di localStorage agar tetap sama setelah reload
+pub fn load_or_create_user_id() -> String {
+    if let Some(id) = storage::get(USER_ID_KEY) {
+        return id;
+    }
+    let id = format!("u-{:08x}", (js_sys::Math::random() * f64::from(u32::MAX)) as u32);
+    storage::set(USER_ID_KEY, &id); // Gagal menyimpan tidak fatal, id hanya berlaku untuk sesi ini
+    id
+}
@@ -0,0 +1,506 @@
+// src/e2e.rs
+// Enkripsi end-to-end opsional per room (build `crypto`). Anggota yang menyalakannya punya pasangan kunci
+// X25519 untuk room itu dan satu kunci pengirim acak. Kunci publik diumumkan lewat saluran Custom
+// (E2E_NAMESPACE), dan kunci pengirim dikirim ke tiap anggota lain dalam keadaan tersegel
+// XChaCha20-Poly1305 dengan kunci bersama X25519 (diturunkan lewat HKDF-SHA256). Teks pesan disegel dengan
+// kunci pengirim sebelum dikirim dan dibuka di callback langganan ChatClient, jadi server hanya pernah
+// melihat ciphertext. Sidik jari kunci publik ditampilkan di E2ePanel untuk dicocokkan di luar aplikasi.
+// Kunci publik pertama seorang anggota langsung dipakai; jika kunci itu kemudian berubah, kunci baru ditahan
+// (PeerKey::changed_fingerprint) dan kunci pengirim tidak dikirim ke sana sampai user memverifikasinya.
+//
+// Yang tetap terbuka: lampiran, nama, waktu dan metadata lain pesan, juga DirectMessage. Kunci pengirim
+// tidak dirotasi (tanpa forward secrecy) dan tidak ditandatangani, sehingga anggota yang memegangnya bisa
+// membuat pesan dengan kunci itu. Kunci disimpan di localStorage agar riwayat tetap terbaca setelah reload;
+// saat enkripsi cache aktif isinya disegel dengan kunci vault, dan keyring kosong selama chat terkunci.
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::client::ClientEvent;
+use crate::i18n::tr;
+use crate::protocol::{ChatMessage, ServerEvent};
+use crate::storage;
+use crate::vault::{self, EncryptedSlot, VaultKey};
+
+pub const E2E_NAMESPACE: &str = "webchat.e2e"; // Namespace Custom untuk pertukaran kunci
+const STORAGE_KEY: &str = "webchat.e2e";
+const SEALED_PREFIX: &str = "e2e1:"; // Teks tersegel: "e2e1:<id kunci>:<base64 nonce + ciphertext>"
+const MAX_RECEIVED_KEYS: usize = 200; // Kunci pengirim anggota lain per room; yang terlama dibuang
+const HKDF_INFO: &str = "webchat e2e v1 "; // Diikuti nama room, jadi kunci bersama berbeda tiap room
+const NONCE_LEN: usize = 24;
+
+// Payload Custom di E2E_NAMESPACE
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Envelope {
+    // Kunci publik X25519 pengirim untuk room ini. `missing` = anggota yang kunci pengirimnya belum
+    // dimiliki pengirim; mereka membalas dengan SenderKey.
+    Announce {
+        public_key: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        missing: Vec<String>,
+    },
+    // Kunci pengirim milik pengirim untuk anggota `to`; `public_key` milik pengirim, untuk membukanya
+    SenderKey { to: String, public_key: String, sealed: String },
+}
+
+// Anggota lain yang kunci publiknya dikenal di room
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeerKey {
+    pub user_id: String,
+    pub fingerprint: String,
+    pub has_sender_key: bool, // Pesan terenkripsinya bisa dibuka
+    pub changed_fingerprint: Option<String>, // Kunci publik baru yang belum diverifikasi user
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct RoomKeys {
+    enabled: bool,
+    own: Option<OwnKeys>, // Dibuat saat enkripsi pertama kali dinyalakan; dipakai lagi jika dinyalakan ulang
+    peers: HashMap<String, [u8; 32]>, // User id -> kunci publik X25519 yang dipercaya
+    #[serde(default)]
+    changed: HashMap<String, [u8; 32]>, // User id -> kunci publik baru yang menunggu verifikasi
+    received: Vec<ReceivedKey>, // Kunci pengirim lama tetap disimpan untuk membuka riwayat
+}
+
+#[derive(Serialize, Deserialize)]
+struct OwnKeys {
+    secret: [u8; 32],
+    sender_key: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize)]
+struct ReceivedKey {
+    id: String,
+    owner: String, // Pesan dengan kunci ini yang mengaku dari user lain tidak dibuka
+    key: [u8; 32],
+}
+
+// Murah untuk di-clone; App dan callback langganannya memegang keyring yang sama
+#[derive(Clone, Default)]
+pub struct Keyring {
+    rooms: Rc<RefCell<HashMap<String, RoomKeys>>>,
+    vault_key: Rc<RefCell<Option<VaultKey>>>,
+    slot: EncryptedSlot,
+}
+
+impl Keyring {
+    // Saat enkripsi cache aktif keyring dimulai kosong sampai `restore` dan `unlock`
+    pub fn load() -> Self {
+        let rooms = if vault::is_enabled() { HashMap::new() } else { storage::get_json(STORAGE_KEY).unwrap_or_default() };
+        Self { rooms: Rc::new(RefCell::new(rooms)), ..Self::default() }
+    }
+
+    // Baca dan dekripsi keyring tersimpan dengan kunci vault; dipanggil sebelum `unlock`
+    pub async fn restore(&self, key: &VaultKey) -> Result<(), String> {
+        let rooms = vault::load_json(key, STORAGE_KEY).await?.unwrap_or_default();
+        *self.rooms.borrow_mut() = rooms;
+        Ok(())
+    }
+
+    // Pasang kunci vault (setelah setup atau unlock) dan simpan ulang keyring dalam bentuk terenkripsi
+    pub fn unlock(&self, key: VaultKey) {
+        *self.vault_key.borrow_mut() = Some(key);
+        self.save();
+    }
+
+    // Buang kunci vault dan rahasia X25519 dari memori
+    pub fn lock(&self) {
+        *self.vault_key.borrow_mut() = None;
+        self.rooms.borrow_mut().clear();
+        self.slot.cancel();
+    }
+
+    // Enkripsi cache dimatikan: simpan ulang sebagai JSON biasa
+    pub fn disable_encryption(&self) {
+        *self.vault_key.borrow_mut() = None;
+        self.save();
+    }
+
+    // Terkunci: rahasia tidak ada di memori, jadi pesan di room terenkripsi tidak boleh dikirim
+    pub fn is_locked(&self) -> bool {
+        self.vault_key.borrow().is_none() && vault::is_enabled()
+    }
+
+    fn save(&self) {
+        match &*self.vault_key.borrow() {
+            Some(key) => match serde_json::to_vec(&*self.rooms.borrow()) {
+                Ok(plaintext) => self.slot.store(key, STORAGE_KEY, plaintext),
+                Err(e) => log::error!("Gagal menyimpan kunci enkripsi: {}", e),
+            },
+            None if vault::is_enabled() => {} // Terkunci: jangan sentuh data terenkripsi yang tersimpan
+            None => {
+                self.slot.cancel();
+                storage::set_json(STORAGE_KEY, &*self.rooms.borrow());
+            }
+        }
+    }
+
+    pub fn is_enabled(&self, room: &str) -> bool {
+        self.rooms.borrow().get(room).is_some_and(|keys| keys.enabled)
+    }
+
+    // false jika browser tidak bisa memberi angka acak untuk kunci baru
+    pub fn set_enabled(&self, room: &str, enabled: bool) -> bool {
+        if self.is_locked() {
+            return false;
+        }
+        {
+            let mut rooms = self.rooms.borrow_mut();
+            let keys = rooms.entry(room.to_string()).or_default();
+            if enabled && keys.own.is_none() {
+                let (Some(secret), Some(sender_key)) = (random(), random()) else { return false };
+                keys.own = Some(OwnKeys { secret, sender_key });
+            }
+            keys.enabled = enabled;
+        }
+        self.save();
+        true
+    }
+
+    // Dikirim setelah menyalakan enkripsi, terhubung, atau masuk room: kunci publik sendiri beserta
+    // kunci pengirim untuk setiap anggota yang sudah dikenal
+    pub fn handshake(&self, room: &str) -> Vec<Envelope> {
+        let rooms = self.rooms.borrow();
+        let Some(keys) = rooms.get(room).filter(|keys| keys.enabled) else { return Vec::new() };
+        let Some(own) = &keys.own else { return Vec::new() };
+        std::iter::once(keys.announce(own, None))
+            .chain(keys.trusted_peers().filter_map(|(user_id, public_key)| own.seal_for(room, user_id, public_key)))
+            .collect()
+    }
+
+    // Frame dari anggota lain di room. Mengembalikan balasan: kunci sendiri untuk anggota yang baru dikenal
+    // atau yang memintanya lewat `missing`.
+    pub fn receive(&self, room: &str, from: &str, me: &str, envelope: Envelope) -> Vec<Envelope> {
+        if self.is_locked() {
+            return Vec::new(); // Dibalas lagi lewat handshake setelah dibuka
+        }
+        let replies = self.rooms.borrow_mut().entry(room.to_string()).or_default().receive(room, from, me, envelope);
+        self.save();
+        replies
+    }
+
+    // User sudah mencocokkan sidik jari kunci baru anggota: kunci itu dipercaya dan kunci pengirim
+    // dipertukarkan ulang. Mengembalikan frame yang perlu dikirim.
+    pub fn verify(&self, room: &str, user_id: &str) -> Vec<Envelope> {
+        let envelopes = match self.rooms.borrow_mut().get_mut(room) {
+            Some(keys) => keys.verify(room, user_id),
+            None => return Vec::new(),
+        };
+        self.save();
+        envelopes
+    }
+
+    // Salinan pesan dengan teks tersegel; None jika enkripsi tidak aktif di room pesan atau teksnya kosong
+    pub fn seal(&self, message: &ChatMessage) -> Option<ChatMessage> {
+        let room = room_of(message);
+        let rooms = self.rooms.borrow();
+        let own = rooms.get(room).filter(|keys| keys.enabled)?.own.as_ref()?;
+        if message.text.is_empty() {
+            return None;
+        }
+        let sealed = seal_bytes(&own.sender_key, room.as_bytes(), message.text.as_bytes())?;
+        Some(ChatMessage { text: format!("{}{}:{}", SEALED_PREFIX, key_id(&own.sender_key), sealed), ..message.clone() })
+    }
+
+    // Buka teks tersegel di event masuk; dipanggil sebelum event sampai ke App
+    pub fn open_event(&self, event: ClientEvent) -> ClientEvent {
+        match event {
+            ClientEvent::Message { mut message, raw, encoding } => {
+                self.open(&mut message);
+                ClientEvent::Message { message, raw, encoding }
+            }
+            ClientEvent::Server(ServerEvent::HistoryReplay { mut messages, total_missed }) => {
+                messages.iter_mut().for_each(|message| self.open(message));
+                ClientEvent::Server(ServerEvent::HistoryReplay { messages, total_missed })
+            }
+            ClientEvent::Server(ServerEvent::HistoryPage { mut messages }) => {
+                messages.iter_mut().for_each(|message| self.open(message));
+                ClientEvent::Server(ServerEvent::HistoryPage { messages })
+            }
+            ClientEvent::Server(ServerEvent::HistoryResponse { room, before_id, mut messages, has_more }) => {
+                messages.iter_mut().for_each(|message| self.open(message));
+                ClientEvent::Server(ServerEvent::HistoryResponse { room, before_id, messages, has_more })
+            }
+            event => event,
+        }
+    }
+
+    // Pesan yang kuncinya belum diterima diganti teks pengganti; ciphertext tidak ditampilkan
+    fn open(&self, message: &mut ChatMessage) {
+        let Some(sealed) = message.text.strip_prefix(SEALED_PREFIX) else { return };
+        let opened = self.open_text(room_of(message), message.user_id.as_deref(), sealed);
+        message.text = opened.unwrap_or_else(|| tr("🔒 Pesan terenkripsi yang belum bisa dibuka").to_string());
+    }
+
+    fn open_text(&self, room: &str, sender: Option<&str>, sealed: &str) -> Option<String> {
+        let (id, body) = sealed.split_once(':')?;
+        let rooms = self.rooms.borrow();
+        let keys = rooms.get(room)?;
+        let key = match &keys.own {
+            Some(own) if key_id(&own.sender_key) == id => &own.sender_key, // Pesan sendiri, juga dari tab lain
+            _ => &keys.received.iter().rev().find(|received| received.id == id && Some(received.owner.as_str()) == sender)?.key,
+        };
+        String::from_utf8(open_bytes(key, room.as_bytes(), body)?).ok()
+    }
+
+    // Sidik jari kunci publik sendiri; None jika enkripsi belum pernah dinyalakan di room ini
+    pub fn own_fingerprint(&self, room: &str) -> Option<String> {
+        let rooms = self.rooms.borrow();
+        let own = rooms.get(room)?.own.as_ref()?;
+        Some(fingerprint(PublicKey::from(&StaticSecret::from(own.secret)).as_bytes()))
+    }
+
+    pub fn peers(&self, room: &str) -> Vec<PeerKey> {
+        let rooms = self.rooms.borrow();
+        let Some(keys) = rooms.get(room) else { return Vec::new() };
+        let mut peers: Vec<PeerKey> = keys
+            .peers
+            .iter()
+            .map(|(user_id, public_key)| PeerKey {
+                user_id: user_id.clone(),
+                fingerprint: fingerprint(public_key),
+                has_sender_key: keys.received.iter().any(|received| &received.owner == user_id),
+                changed_fingerprint: keys.changed.get(user_id).map(fingerprint),
+            })
+            .collect();
+        peers.sort_by(|a, b| a.user_id.cmp(&b.user_id));
+        peers
+    }
+}
+
+impl RoomKeys {
+    // `ask` diminta kunci pengirimnya lagi walaupun kunci lamanya sudah ada (setelah verifikasi ulang)
+    fn announce(&self, own: &OwnKeys, ask: Option<&str>) -> Envelope {
+        let missing = self
+            .trusted_peers()
+            .map(|(user_id, _)| user_id)
+            .filter(|user_id| ask == Some(user_id.as_str()) || !self.received.iter().any(|received| &received.owner == *user_id))
+            .cloned()
+            .collect();
+        Envelope::Announce { public_key: BASE64.encode(PublicKey::from(&StaticSecret::from(own.secret)).as_bytes()), missing }
+    }
+
+    // Anggota yang kuncinya tidak sedang menunggu verifikasi
+    fn trusted_peers(&self) -> impl Iterator<Item = (&String, &[u8; 32])> {
+        self.peers.iter().filter(|(user_id, _)| !self.changed.contains_key(*user_id))
+    }
+
+    fn receive(&mut self, room: &str, from: &str, me: &str, envelope: Envelope) -> Vec<Envelope> {
+        let (public_key, sealed, asked) = match envelope {
+            Envelope::Announce { public_key, missing } => (public_key, None, missing.iter().any(|user_id| user_id == me)),
+            Envelope::SenderKey { to, .. } if to != me => return Vec::new(),
+            Envelope::SenderKey { public_key, sealed, .. } => (public_key, Some(sealed), false),
+        };
+        let Some(public_key) = decode_key(&public_key) else { return Vec::new() };
+        let known = match self.peers.get(from) {
+            Some(trusted) if *trusted != public_key => {
+                // Bisa perangkat baru, bisa juga penyusup: kunci pengirim tidak dikirim ke atau diterima dari kunci ini
+                if self.changed.insert(from.to_string(), public_key) != Some(public_key) {
+                    log::warn!("Kunci publik {} di room {} berubah; menunggu verifikasi sidik jari", from, room);
+                }
+                return Vec::new();
+            }
+            Some(_) => {
+                self.changed.remove(from); // Kembali ke kunci yang dipercaya
+                true
+            }
+            None => {
+                self.peers.insert(from.to_string(), public_key);
+                false
+            }
+        };
+        if let (Some(sealed), Some(own)) = (sealed, &self.own) {
+            match own.open_sender_key(room, me, &public_key, &sealed) {
+                Some(key) => self.remember(from, key),
+                None => log::warn!("Kunci pengirim dari {} di room {} tidak bisa dibuka", from, room),
+            }
+        }
+        match &self.own {
+            Some(own) if self.enabled && (!known || asked) => {
+                let announce = (!known).then(|| self.announce(own, None));
+                announce.into_iter().chain(own.seal_for(room, from, &public_key)).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn verify(&mut self, room: &str, user_id: &str) -> Vec<Envelope> {
+        let Some(public_key) = self.changed.remove(user_id) else { return Vec::new() };
+        self.peers.insert(user_id.to_string(), public_key);
+        match &self.own {
+            Some(own) if self.enabled => std::iter::once(self.announce(own, Some(user_id))).chain(own.seal_for(room, user_id, &public_key)).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn remember(&mut self, owner: &str, key: [u8; 32]) {
+        let id = key_id(&key);
+        if self.received.iter().any(|received| received.id == id && received.owner == owner) {
+            return;
+        }
+        self.received.push(ReceivedKey { id, owner: owner.to_string(), key });
+        if self.received.len() > MAX_RECEIVED_KEYS {
+            self.received.remove(0);
+        }
+    }
+}
+
+impl OwnKeys {
+    fn seal_for(&self, room: &str, to: &str, public_key: &[u8; 32]) -> Option<Envelope> {
+        let shared = shared_key(&self.secret, public_key, room);
+        let sealed = seal_bytes(&shared, to.as_bytes(), &self.sender_key)?;
+        let own_public = PublicKey::from(&StaticSecret::from(self.secret));
+        Some(Envelope::SenderKey { to: to.to_string(), public_key: BASE64.encode(own_public.as_bytes()), sealed })
+    }
+
+    fn open_sender_key(&self, room: &str, me: &str, public_key: &[u8; 32], sealed: &str) -> Option<[u8; 32]> {
+        let shared = shared_key(&self.secret, public_key, room);
+        open_bytes(&shared, me.as_bytes(), sealed)?.try_into().ok()
+    }
+}
+
+// Kunci bersama X25519, diturunkan per room
+fn shared_key(secret: &[u8; 32], public_key: &[u8; 32], room: &str) -> [u8; 32] {
+    let shared = StaticSecret::from(*secret).diffie_hellman(&PublicKey::from(*public_key));
+    let mut key = [0; 32];
+    let info = format!("{}{}", HKDF_INFO, room);
+    Hkdf::<Sha256>::new(None, shared.as_bytes()).expand(info.as_bytes(), &mut key).expect("panjang kunci HKDF valid");
+    key
+}
+
+// base64(nonce acak || ciphertext)
+fn seal_bytes(key: &[u8; 32], aad: &[u8], plaintext: &[u8]) -> Option<String> {
+    let nonce: [u8; NONCE_LEN] = random()?;
+    let ciphertext = XChaCha20Poly1305::new(Key::from_slice(key)).encrypt(XNonce::from_slice(&nonce), Payload { msg: plaintext, aad }).ok()?;
+    Some(BASE64.encode([nonce.as_slice(), &ciphertext].concat()))
+}
+
+fn open_bytes(key: &[u8; 32], aad: &[u8], sealed: &str) -> Option<Vec<u8>> {
+    let bytes = BASE64.decode(sealed).ok()?;
+    if bytes.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+    XChaCha20Poly1305::new(Key::from_slice(key)).decrypt(XNonce::from_slice(nonce), Payload { msg: ciphertext, aad }).ok()
+}
+
+fn random<const N: usize>() -> Option<[u8; N]> {
+    let mut bytes = [0; N];
+    match getrandom::getrandom(&mut bytes) {
+        Ok(()) => Some(bytes),
+        Err(e) => {
+            log::error!("Angka acak untuk enkripsi tidak tersedia: {}", e);
+            None
+        }
+    }
+}
+
+fn decode_key(encoded: &str) -> Option<[u8; 32]> {
+    BASE64.decode(encoded).ok()?.try_into().ok()
+}
+
+// 8 byte pertama SHA-256 kunci pengirim, cukup untuk memilih kunci tanpa membukanya
+fn key_id(key: &[u8; 32]) -> String {
+    Sha256::digest(key)[..8].iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// "1a2b 3c4d …": 16 byte pertama SHA-256 kunci publik dalam kelompok empat digit hex
+fn fingerprint(public_key: &[u8; 32]) -> String {
+    let digest = Sha256::digest(public_key);
+    digest[..16].chunks(2).map(|pair| format!("{:02x}{:02x}", pair[0], pair[1])).collect::<Vec<_>>().join(" ")
+}
+
+// Room umum dikirim tanpa nama room
+fn room_of(message: &ChatMessage) -> &str {
+    message.room.as_deref().unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROOM: &str = "rahasia";
+
+    fn member() -> RoomKeys {
+        RoomKeys { enabled: true, own: Some(OwnKeys { secret: random().unwrap(), sender_key: random().unwrap() }), ..RoomKeys::default() }
+    }
+
+    fn announce(keys: &RoomKeys) -> Envelope {
+        keys.announce(keys.own.as_ref().unwrap(), None)
+    }
+
+    // Alice dan Bob saling bertukar kunci sampai tidak ada balasan lagi
+    fn exchange(alice: &mut RoomKeys, bob: &mut RoomKeys) {
+        let mut to_bob = vec![announce(alice)];
+        while !to_bob.is_empty() {
+            let to_alice: Vec<Envelope> = to_bob.drain(..).flat_map(|envelope| bob.receive(ROOM, "alice", "bob", envelope)).collect();
+            to_bob = to_alice.into_iter().flat_map(|envelope| alice.receive(ROOM, "bob", "alice", envelope)).collect();
+        }
+    }
+
+    fn sender_key_for(replies: &[Envelope], user_id: &str) -> bool {
+        replies.iter().any(|envelope| matches!(envelope, Envelope::SenderKey { to, .. } if to == user_id))
+    }
+
+    #[test]
+    fn exchanged_keys_open_sealed_text() {
+        let (mut alice, mut bob) = (member(), member());
+        exchange(&mut alice, &mut bob);
+        assert!(bob.received.iter().any(|received| received.owner == "alice"));
+        assert!(alice.received.iter().any(|received| received.owner == "bob"));
+
+        let sealed = seal_bytes(&alice.own.as_ref().unwrap().sender_key, ROOM.as_bytes(), b"halo").unwrap();
+        let text = format!("{}:{}", key_id(&alice.own.as_ref().unwrap().sender_key), sealed);
+        let keyring = Keyring { rooms: Rc::new(RefCell::new(HashMap::from([(ROOM.to_string(), bob)]))), ..Keyring::default() };
+        assert_eq!(keyring.open_text(ROOM, Some("alice"), &text).as_deref(), Some("halo"));
+        assert_eq!(keyring.open_text(ROOM, Some("mallory"), &text), None); // Kunci milik Alice, bukan pengirim lain
+    }
+
+    #[test]
+    fn changed_key_is_held_until_verified() {
+        let (mut alice, mut bob) = (member(), member());
+        exchange(&mut alice, &mut bob);
+        let trusted = bob.peers["alice"];
+
+        let mut mallory = member();
+        let replies = bob.receive(ROOM, "alice", "bob", announce(&mallory));
+        assert!(replies.is_empty(), "kunci pengirim tidak boleh dikirim ke kunci baru");
+        assert_eq!(bob.peers["alice"], trusted);
+        assert!(bob.changed.contains_key("alice"));
+        assert!(bob.trusted_peers().next().is_none()); // Handshake tidak menyegel kunci pengirim untuknya
+
+        // Kunci pengirim yang disegel dengan kunci baru juga diabaikan
+        let received = bob.received.len();
+        let from_mallory = mallory.receive(ROOM, "bob", "alice", announce(&bob));
+        let sealed = from_mallory.into_iter().find(|envelope| matches!(envelope, Envelope::SenderKey { .. })).unwrap();
+        assert!(bob.receive(ROOM, "alice", "bob", sealed).is_empty());
+        assert_eq!(bob.received.len(), received);
+
+        let envelopes = bob.verify(ROOM, "alice");
+        assert!(!bob.changed.contains_key("alice"));
+        assert_ne!(bob.peers["alice"], trusted);
+        assert!(sender_key_for(&envelopes, "alice"));
+        assert!(matches!(&envelopes[0], Envelope::Announce { missing, .. } if missing == &["alice".to_string()]));
+    }
+
+    #[test]
+    fn original_key_clears_pending_change() {
+        let (mut alice, mut bob) = (member(), member());
+        exchange(&mut alice, &mut bob);
+        bob.receive(ROOM, "alice", "bob", announce(&member()));
+        assert!(bob.changed.contains_key("alice"));
+        bob.receive(ROOM, "alice", "bob", announce(&alice));
+        assert!(bob.changed.is_empty());
+        assert!(bob.verify(ROOM, "alice").is_empty()); // Tidak ada yang perlu diverifikasi
+    }
+}
@@ -0,0 +1,77 @@
+// src/e2e_panel.rs
+// Panel enkripsi end-to-end room yang sedang dibuka (build `crypto`): sakelar per room, sidik jari kunci
+// sendiri, dan sidik jari anggota lain untuk dicocokkan di luar aplikasi. Anggota yang kunci publiknya
+// berubah ditandai sampai user memverifikasi sidik jari barunya.
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::i18n::use_locale;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeerRow {
+    pub user_id: String,
+    pub name: String,
+    pub fingerprint: String,
+    pub has_sender_key: bool,
+    pub changed_fingerprint: Option<String>, // Kunci baru yang belum diverifikasi
+}
+
+#[derive(Properties, PartialEq)]
+pub struct E2eProps {
+    pub enabled: bool,
+    pub fingerprint: Option<String>, // None sebelum enkripsi pernah dinyalakan di room ini
+    pub peers: Vec<PeerRow>,
+    pub disabled: bool, // Offline, atau room anonim tempat pertukaran kunci akan membuka user id
+    pub on_toggle: Callback<bool>,
+    pub on_verify: Callback<String>, // User id yang kunci barunya dipercaya
+}
+
+#[function_component(E2ePanel)]
+pub fn e2e_panel(props: &E2eProps) -> Html {
+    let locale = use_locale();
+    let onchange = {
+        let on_toggle = props.on_toggle.clone();
+        Callback::from(move |e: Event| on_toggle.emit(e.target_unchecked_into::<HtmlInputElement>().checked()))
+    };
+
+    html! {
+        <details class={classes!("e2e-panel", props.enabled.then_some("enabled"))}>
+            <summary>{ if props.enabled { locale.t("🔒 Enkripsi end-to-end aktif") } else { locale.t("Enkripsi end-to-end") } }</summary>
+            <label>
+                <input type="checkbox" checked={props.enabled} {onchange} disabled={props.disabled} />
+                { locale.t("Enkripsi teks pesan di room ini") }
+            </label>
+            if let Some(fingerprint) = &props.fingerprint {
+                <p>{ locale.t("Sidik jari kunci Anda:") }{ " " }<code class="e2e-fingerprint">{ fingerprint }</code></p>
+            }
+            if props.peers.is_empty() {
+                <p class="e2e-empty">{ locale.t("Belum ada anggota lain yang mengaktifkan enkripsi di room ini.") }</p>
+            } else {
+                <p class="e2e-hint">{ locale.t("Cocokkan sidik jari berikut dengan pemiliknya lewat jalur lain, mis. bertemu langsung.") }</p>
+                <ul class="e2e-peers">
+                    { for props.peers.iter().map(|peer| {
+                        let onclick = {
+                            let (on_verify, user_id) = (props.on_verify.clone(), peer.user_id.clone());
+                            Callback::from(move |_| on_verify.emit(user_id.clone()))
+                        };
+                        html! {
+                            <li class={classes!("e2e-peer", peer.changed_fingerprint.is_some().then_some("changed"))} key={peer.user_id.clone()}>
+                                <span>{ &peer.name }</span>
+                                <code class="e2e-fingerprint">{ &peer.fingerprint }</code>
+                                if let Some(changed) = &peer.changed_fingerprint {
+                                    <p class="e2e-warning">
+                                        { locale.t("⚠️ Kunci anggota ini berubah. Pesan tidak dienkripsi untuk kunci baru sampai Anda mencocokkan sidik jarinya:") }
+                                        { " " }<code class="e2e-fingerprint">{ changed }</code>
+                                    </p>
+                                    <button class="e2e-verify" {onclick}>{ locale.t("Sidik jari cocok, percayai kunci baru") }</button>
+                                } else {
+                                    <span class="e2e-status">{ if peer.has_sender_key { locale.t("kunci diterima") } else { locale.t("menunggu kunci") } }</span>
+                                }
+                            </li>
+                        }
+                    }) }
+                </ul>
+            }
+        </details>
+    }
+}
@@ -0,0 +1,109 @@
+// src/error.rs
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::handle::SendError;
+use crate::i18n::{tr, trf};
+
+// Error yang ditampilkan di banner status. Tiap varian membawa data terstruktur sehingga
+// UI bisa menawarkan tindakan pemulihan yang sesuai, bukan sekadar teks debug.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ChatError {
+    Transport(TransportError),
+    // Frame dari server tidak bisa dibaca; `payload` disimpan untuk log, tidak ditampilkan
+    Protocol { detail: String, payload: Option<String> },
+    Auth(AuthError),
+    RateLimited { retry_after_secs: u32 },
+    Validation(String), // Input user ditolak sebelum dikirim
+    Internal(String),   // Bug/kegagalan di klien sendiri (serialisasi, API browser)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TransportError {
+    ConnectFailed(String),
+    Closed { code: u16, reason: String },
+    Disconnected,
+    NotConnected, // Aksi butuh koneksi tetapi socket belum/tidak tersambung
+    SendFailed(SendError),
+    HeartbeatTimeout, // Ping berturut-turut tidak dijawab; socket mungkin sudah mati di balik proxy
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AuthError {
+    SessionRevoked { reason: Option<String> },
+    LoginRequired, // Aksi butuh login; layar login sudah tampil
+}
+
+// Tindakan yang ditawarkan di samping pesan error
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Recovery {
+    Reconnect,
+    Reload,
+    Wait(u32), // Detik
+    Dismiss,
+}
+
+impl ChatError {
+    pub fn not_connected() -> Self {
+        ChatError::Transport(TransportError::NotConnected)
+    }
+
+    pub fn recovery(&self) -> Recovery {
+        match self {
+            ChatError::Transport(_) => Recovery::Reconnect,
+            ChatError::Auth(AuthError::LoginRequired) => Recovery::Dismiss,
+            ChatError::Auth(_) => Recovery::Reload,
+            ChatError::RateLimited { retry_after_secs } => Recovery::Wait(*retry_after_secs),
+            ChatError::Protocol { .. } | ChatError::Validation(_) | ChatError::Internal(_) => Recovery::Dismiss,
+        }
+    }
+}
+
+impl From<SendError> for ChatError {
+    fn from(error: SendError) -> Self {
+        match error {
+            SendError::Serialize(detail) => ChatError::Internal(format!("serialisasi pesan: {}", detail)),
+            error => ChatError::Transport(TransportError::SendFailed(error)),
+        }
+    }
+}
+
+// Teks banner dalam bahasa antarmuka (lihat i18n)
+impl fmt::Display for ChatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            ChatError::Transport(TransportError::ConnectFailed(_)) => tr("Gagal terhubung ke server.").to_string(),
+            ChatError::Transport(TransportError::Closed { code, reason }) if reason.is_empty() => {
+                trf("Koneksi ditutup oleh server (kode {}).", &[code])
+            }
+            ChatError::Transport(TransportError::Closed { code, reason }) => {
+                trf("Koneksi ditutup oleh server (kode {}): {}", &[code, reason])
+            }
+            ChatError::Transport(TransportError::Disconnected) => tr("Koneksi ke server terputus.").to_string(),
+            ChatError::Transport(TransportError::NotConnected) => tr("Tidak terhubung ke server WebSocket.").to_string(),
+            ChatError::Transport(TransportError::HeartbeatTimeout) => {
+                tr("Server tidak merespons lagi; menyambung ulang…").to_string()
+            }
+            ChatError::Transport(TransportError::SendFailed(SendError::QueueFull)) => {
+                tr("Antrean kirim penuh; koneksi terlalu lambat.").to_string()
+            }
+            ChatError::Transport(TransportError::SendFailed(SendError::Timeout)) => {
+                tr("Server tidak merespons saat mengirim, juga setelah dicoba ulang.").to_string()
+            }
+            ChatError::Transport(TransportError::SendFailed(_)) => tr("Gagal mengirim ke server.").to_string(),
+            ChatError::Protocol { detail, .. } => trf("Server mengirim data yang tidak dikenali ({}).", &[detail]),
+            ChatError::Auth(AuthError::SessionRevoked { reason: Some(reason) }) => {
+                trf("Sesi ini dikeluarkan dari perangkat lain: {}", &[reason])
+            }
+            ChatError::Auth(AuthError::SessionRevoked { reason: None }) => tr("Sesi ini dikeluarkan dari perangkat lain.").to_string(),
+            ChatError::Auth(AuthError::LoginRequired) => tr("Masuk dulu untuk mengirim pesan.").to_string(),
+            ChatError::RateLimited { retry_after_secs } => {
+                trf("Terlalu banyak pesan. Coba lagi dalam {} detik.", &[retry_after_secs])
+            }
+            ChatError::Validation(message) => message.clone(),
+            ChatError::Internal(detail) => trf("Terjadi kesalahan di aplikasi: {}", &[detail]),
+        };
+        f.write_str(&text)
+    }
+}
@@ -0,0 +1,237 @@
+// src/exif.rs
+// Penghapusan metadata (EXIF/GPS, XMP, IPTC, komentar teks) dari file gambar langsung pada
+// level byte, tanpa decode ulang, sehingga kualitas gambar asli tetap utuh. Orientasi EXIF JPEG
+// dipertahankan agar foto dari kamera ponsel tidak tampil miring.
+
+// Kembalikan salinan gambar tanpa metadata, atau None jika format tidak didukung/tidak valid
+// (pemanggil lalu mengirim file aslinya)
+pub fn strip_metadata(mime: &str, bytes: &[u8]) -> Option<Vec<u8>> {
+    match mime {
+        "image/jpeg" => strip_jpeg(bytes),
+        "image/png" => strip_png(bytes),
+        "image/webp" => strip_webp(bytes),
+        _ => None,
+    }
+}
+
+pub fn is_supported(mime: &str) -> bool {
+    matches!(mime, "image/jpeg" | "image/png" | "image/webp")
+}
+
+// JPEG: buang segmen APP1 (EXIF/XMP), APP13 (IPTC/Photoshop) dan COM; sisanya disalin apa adanya.
+// EXIF asli diganti segmen APP1 minimal yang hanya berisi tag Orientation, jika tag itu ada.
+fn strip_jpeg(bytes: &[u8]) -> Option<Vec<u8>> {
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut out = Vec::with_capacity(bytes.len());
+    out.extend_from_slice(&bytes[..2]);
+    let mut pos = 2;
+    let mut oriented = false;
+    while pos + 4 <= bytes.len() {
+        if bytes[pos] != 0xFF {
+            return None;
+        }
+        let marker = bytes[pos + 1];
+        if marker == 0xDA {
+            // Start of Scan: data gambar terkompresi sampai akhir file, salin semuanya
+            out.extend_from_slice(&bytes[pos..]);
+            return Some(out);
+        }
+        let len = usize::from(u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]));
+        let end = pos + 2 + len;
+        if len < 2 || end > bytes.len() {
+            return None;
+        }
+        match marker {
+            0xE1 if !oriented => {
+                if let Some(orientation) = exif_orientation(&bytes[pos + 4..end]) {
+                    out.extend_from_slice(&orientation_segment(orientation));
+                    oriented = true;
+                }
+            }
+            0xE1 | 0xED | 0xFE => {}
+            _ => out.extend_from_slice(&bytes[pos..end]),
+        }
+        pos = end;
+    }
+    None
+}
+
+// Nilai tag Orientation (0x0112) di IFD0 sebuah payload APP1 EXIF; None jika tidak ada atau 1 (normal)
+fn exif_orientation(app1: &[u8]) -> Option<u16> {
+    let tiff = app1.strip_prefix(b"Exif\0\0")?;
+    let big_endian = match tiff.get(..2)? {
+        b"MM" => true,
+        b"II" => false,
+        _ => return None,
+    };
+    let read = |at: usize, width: usize| -> Option<u32> {
+        let bytes = tiff.get(at..at.checked_add(width)?)?;
+        Some(bytes.iter().enumerate().fold(0, |value, (i, byte)| {
+            let shift = if big_endian { 8 * (width - 1 - i) } else { 8 * i };
+            value | (u32::from(*byte) << shift)
+        }))
+    };
+    let ifd = usize::try_from(read(4, 4)?).ok()?;
+    let count = read(ifd, 2)? as usize;
+    (0..count)
+        .find_map(|i| {
+            let entry = ifd.checked_add(2 + i * 12)?;
+            (read(entry, 2)? == 0x0112).then(|| read(entry.checked_add(8)?, 2)).flatten()
+        })
+        .and_then(|orientation| u16::try_from(orientation).ok())
+        .filter(|orientation| (2..=8).contains(orientation))
+}
+
+// APP1 berisi TIFF big-endian dengan satu IFD dan satu entri: Orientation (SHORT, 1 nilai)
+fn orientation_segment(orientation: u16) -> Vec<u8> {
+    let mut segment = vec![0xFF, 0xE1, 0x00, 0x22];
+    segment.extend_from_slice(b"Exif\0\0");
+    segment.extend_from_slice(&[b'M', b'M', 0x00, 0x2A, 0x00, 0x00, 0x00, 0x08]);
+    segment.extend_from_slice(&[0x00, 0x01, 0x01, 0x12, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01]);
+    segment.extend_from_slice(&orientation.to_be_bytes());
+    segment.extend_from_slice(&[0x00; 6]); // Sisa field nilai, lalu offset IFD berikutnya (tidak ada)
+    segment
+}
+
+// PNG: buang chunk eXIf dan chunk teks (tEXt, zTXt, iTXt) yang bisa berisi lokasi/komentar
+fn strip_png(bytes: &[u8]) -> Option<Vec<u8>> {
+    const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+    if !bytes.starts_with(&SIGNATURE) {
+        return None;
+    }
+    let mut out = Vec::with_capacity(bytes.len());
+    out.extend_from_slice(&SIGNATURE);
+    let mut pos = SIGNATURE.len();
+    while pos + 12 <= bytes.len() {
+        let len = u32::from_be_bytes(bytes[pos..pos + 4].try_into().ok()?) as usize;
+        let end = pos.checked_add(12)?.checked_add(len)?; // panjang + tipe + data + CRC
+        if end > bytes.len() {
+            return None;
+        }
+        let kind = &bytes[pos + 4..pos + 8];
+        if !matches!(kind, b"eXIf" | b"tEXt" | b"zTXt" | b"iTXt") {
+            out.extend_from_slice(&bytes[pos..end]);
+        }
+        if kind == b"IEND" {
+            return Some(out);
+        }
+        pos = end;
+    }
+    None
+}
+
+// WebP: buang chunk EXIF dan "XMP ", matikan flag-nya di VP8X, lalu perbarui ukuran RIFF
+fn strip_webp(bytes: &[u8]) -> Option<Vec<u8>> {
+    if bytes.len() < 12 || &bytes[..4] != b"RIFF" || &bytes[8..12] != b"WEBP" {
+        return None;
+    }
+    let mut out = Vec::with_capacity(bytes.len());
+    out.extend_from_slice(&bytes[..12]);
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let kind = &bytes[pos..pos + 4];
+        let len = u32::from_le_bytes(bytes[pos + 4..pos + 8].try_into().ok()?) as usize;
+        let end = pos.checked_add(8)?.checked_add(len)?.checked_add(len & 1)?; // chunk RIFF di-pad ke jumlah byte genap
+        if end > bytes.len() {
+            return None;
+        }
+        match kind {
+            b"EXIF" | b"XMP " => {}
+            b"VP8X" if len >= 1 => {
+                let start = out.len();
+                out.extend_from_slice(&bytes[pos..end]);
+                out[start + 8] &= !(0x08 | 0x04); // flag EXIF dan XMP
+            }
+            _ => out.extend_from_slice(&bytes[pos..end]),
+        }
+        pos = end;
+    }
+    let riff_size = u32::try_from(out.len() - 8).ok()?;
+    out[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+    const SCAN: [u8; 8] = [0xFF, 0xDA, 0x00, 0x02, 0x12, 0x34, 0xFF, 0xD9];
+
+    fn png_chunk(kind: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+        chunk.extend_from_slice(kind);
+        chunk.extend_from_slice(data);
+        chunk.extend_from_slice(&[0; 4]); // CRC tidak diperiksa
+        chunk
+    }
+
+    fn jpeg_segment(marker: u8, data: &[u8]) -> Vec<u8> {
+        let mut segment = vec![0xFF, marker];
+        segment.extend_from_slice(&(data.len() as u16 + 2).to_be_bytes());
+        segment.extend_from_slice(data);
+        segment
+    }
+
+    // APP1 little-endian berisi GPSInfo palsu dan Orientation
+    fn exif_app1(orientation: u16) -> Vec<u8> {
+        let mut data = b"Exif\0\0II\x2A\0\x08\0\0\0".to_vec();
+        data.extend_from_slice(&2u16.to_le_bytes());
+        data.extend_from_slice(&[0x25, 0x88, 0x04, 0x00, 0x01, 0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00]);
+        data.extend_from_slice(&[0x12, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00]);
+        data.extend_from_slice(&orientation.to_le_bytes());
+        data.extend_from_slice(&[0; 6]);
+        jpeg_segment(0xE1, &data)
+    }
+
+    #[test]
+    fn jpeg_keeps_only_orientation() {
+        let jfif = jpeg_segment(0xE0, b"JFIF\0");
+        let input = [&[0xFF, 0xD8][..], &jfif, &exif_app1(6), &jpeg_segment(0xFE, b"komentar"), &SCAN].concat();
+        let expected = [&[0xFF, 0xD8][..], &jfif, &orientation_segment(6), &SCAN].concat();
+        assert_eq!(strip_jpeg(&input), Some(expected));
+
+        // Segmen pengganti bisa dibaca lagi sebagai EXIF yang valid
+        let segment = orientation_segment(6);
+        assert_eq!(usize::from(u16::from_be_bytes([segment[2], segment[3]])), segment.len() - 2);
+        assert_eq!(exif_orientation(&segment[4..]), Some(6));
+    }
+
+    #[test]
+    fn jpeg_without_rotation_drops_exif_entirely() {
+        let input = [&[0xFF, 0xD8][..], &exif_app1(1), &SCAN].concat();
+        assert_eq!(strip_jpeg(&input), Some([&[0xFF, 0xD8][..], &SCAN].concat()));
+        assert_eq!(exif_orientation(b"Exif\0\0II\x2A\0\xFF\xFF\xFF\xFF"), None); // Offset IFD di luar data
+    }
+
+    #[test]
+    fn png_drops_text_and_exif_chunks() {
+        let ihdr = png_chunk(b"IHDR", &[0; 13]);
+        let iend = png_chunk(b"IEND", &[]);
+        let input = [&PNG_SIGNATURE[..], &ihdr, &png_chunk(b"tEXt", b"GPS\0-6.2,106.8"), &png_chunk(b"eXIf", b"MM"), &iend].concat();
+        assert_eq!(strip_png(&input), Some([&PNG_SIGNATURE[..], &ihdr, &iend].concat()));
+    }
+
+    #[test]
+    fn oversized_chunk_lengths_are_rejected() {
+        let png = [&PNG_SIGNATURE[..], &u32::MAX.to_be_bytes(), b"tEXt", &[0; 8]].concat();
+        assert_eq!(strip_png(&png), None);
+        let webp = [&b"RIFF\0\0\0\0WEBP"[..], b"EXIF", &u32::MAX.to_le_bytes(), &[0; 8]].concat();
+        assert_eq!(strip_webp(&webp), None);
+    }
+
+    #[test]
+    fn webp_clears_flags_and_fixes_riff_size() {
+        let vp8x = [&b"VP8X"[..], &10u32.to_le_bytes(), &[0x0C], &[0; 9]].concat();
+        let image = [&b"VP8L"[..], &3u32.to_le_bytes(), &[1, 2, 3, 0]].concat(); // Di-pad ke genap
+        let exif = [&b"EXIF"[..], &2u32.to_le_bytes(), b"II"].concat();
+        let input = [&b"RIFF\0\0\0\0WEBP"[..], &vp8x, &exif, &image].concat();
+        let out = strip_webp(&input).unwrap();
+        assert_eq!(out.len(), 12 + vp8x.len() + image.len());
+        assert_eq!(u32::from_le_bytes(out[4..8].try_into().unwrap()) as usize, out.len() - 8);
+        assert_eq!(out[20], 0x00); // Flag EXIF dan XMP dimatikan
+        assert!(out.ends_with(&image));
+    }
+}
@@ -0,0 +1,145 @@
+// src/gallery.rs
+// Panel galeri: semua lampiran yang pernah dibagikan di room saat ini, diambil dari
+// pesan yang sudah ada di MessageStore (termasuk riwayat yang di-replay server).
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+use crate::i18n::use_locale;
+use crate::media::EmbedPolicy;
+use crate::protocol::Attachment;
+use crate::uploads::format_size;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GalleryItem {
+    pub entry_index: usize, // Index entry di MessageStore, untuk lompat ke pesannya
+    pub attachment: Attachment,
+    pub sender: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum KindFilter {
+    All,
+    Images,
+    Videos,
+    Audio,
+    Files,
+}
+
+impl KindFilter {
+    const ALL: [(KindFilter, &'static str); 5] = [
+        (KindFilter::All, "Semua"),
+        (KindFilter::Images, "Gambar"),
+        (KindFilter::Videos, "Video"),
+        (KindFilter::Audio, "Suara"),
+        (KindFilter::Files, "File"),
+    ];
+
+    fn matches(self, attachment: &Attachment) -> bool {
+        match self {
+            KindFilter::All => true,
+            KindFilter::Images => attachment.is_image(),
+            KindFilter::Videos => attachment.is_video(),
+            KindFilter::Audio => attachment.is_audio(),
+            KindFilter::Files => !attachment.is_image() && !attachment.is_video() && !attachment.is_audio(),
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct GalleryProps {
+    pub items: Vec<GalleryItem>,
+    pub on_jump: Callback<usize>, // Menerima entry_index pesan yang dipilih
+    pub policy: EmbedPolicy,
+}
+
+#[function_component(AttachmentGallery)]
+pub fn attachment_gallery(props: &GalleryProps) -> Html {
+    let locale = use_locale();
+    let kind = use_state(|| KindFilter::All);
+    let sender = use_state(|| None::<String>);
+
+    let mut senders: Vec<&str> = props.items.iter().map(|item| item.sender.as_str()).collect();
+    senders.sort_unstable();
+    senders.dedup();
+
+    let on_kind_change = {
+        let kind = kind.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let index: usize = select.value().parse().unwrap_or(0);
+            kind.set(KindFilter::ALL.get(index).map_or(KindFilter::All, |(filter, _)| *filter));
+        })
+    };
+    let on_sender_change = {
+        let sender = sender.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let value = select.value();
+            sender.set((!value.is_empty()).then_some(value));
+        })
+    };
+
+    let visible: Vec<&GalleryItem> = props
+        .items
+        .iter()
+        .rev() // Lampiran terbaru di depan
+        .filter(|item| kind.matches(&item.attachment))
+        .filter(|item| sender.as_deref().is_none_or(|name| item.sender == name))
+        .collect();
+
+    html! {
+        <details class="gallery-panel">
+            <summary>{ locale.f("Media & file ({})", &[&props.items.len()]) }</summary>
+            <div class="gallery-filters">
+                <select onchange={on_kind_change}>
+                    { for KindFilter::ALL.iter().enumerate().map(|(i, (filter, label))| html! {
+                        <option value={i.to_string()} selected={*kind == *filter}>{ locale.t(label) }</option>
+                    }) }
+                </select>
+                <select onchange={on_sender_change}>
+                    <option value="" selected={sender.is_none()}>{ locale.t("Semua pengirim") }</option>
+                    { for senders.iter().map(|name| html! {
+                        <option value={name.to_string()} selected={sender.as_deref() == Some(*name)}>{ *name }</option>
+                    }) }
+                </select>
+            </div>
+            if visible.is_empty() {
+                <p class="gallery-empty">{ locale.t("Belum ada lampiran.") }</p>
+            } else {
+                <ul class="gallery-grid">
+                    { for visible.into_iter().map(|item| view_item(item, props.policy, &props.on_jump)) }
+                </ul>
+            }
+        </details>
+    }
+}
+
+fn view_item(item: &GalleryItem, policy: EmbedPolicy, on_jump: &Callback<usize>) -> Html {
+    let attachment = &item.attachment;
+    let index = item.entry_index;
+    let on_click = on_jump.reform(move |_| index);
+    let thumbnail = if attachment.is_image() && policy.blocked_reason(attachment).is_none() {
+        html! { <img src={attachment.url.clone()} alt={attachment.name.clone()} loading="lazy" /> }
+    } else if let Some(poster) = attachment.poster.as_ref().filter(|_| attachment.is_video()) {
+        html! { <img src={poster.clone()} alt={attachment.name.clone()} /> }
+    } else {
+        let icon = if attachment.is_image() {
+            "🖼"
+        } else if attachment.is_video() {
+            "🎬"
+        } else if attachment.is_audio() {
+            "🎤"
+        } else {
+            "📎"
+        };
+        html! { <span class="gallery-icon">{ icon }</span> }
+    };
+    html! {
+        <li class="gallery-item">
+            <button onclick={on_click} title={format!("{} · {} · {}", attachment.name, item.sender, format_size(attachment.size))}>
+                { thumbnail }
+                <span class="gallery-name">{ &attachment.name }</span>
+            </button>
+        </li>
+    }
+}
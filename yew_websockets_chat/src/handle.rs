@@ -0,0 +1,136 @@
+// src/handle.rs
+// Jalur kirim ke server. Semua frame masuk ke satu antrean terbatas yang dikosongkan oleh satu
+// task penulis: urutan frame terjaga, dan pengirim menunggu (backpressure) saat antrean penuh
+// alih-alih menumpuk task spawn_local tanpa batas.
+use std::future::Future;
+use std::pin::Pin;
+
+use futures_channel::{mpsc, oneshot};
+use futures_util::future::{self, Either};
+use futures_util::{Sink, SinkExt, StreamExt};
+use gloo_net::websocket::Message as WsMessage;
+use gloo_timers::future::TimeoutFuture;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen_futures::spawn_local;
+
+use crate::codec;
+
+const QUEUE_CAPACITY: usize = 32;
+const ENQUEUE_TIMEOUT_MS: u32 = 5_000; // Batas menunggu tempat kosong di antrean
+const WRITE_TIMEOUT_MS: u32 = 10_000;  // Batas satu percobaan menulis ke socket
+const RETRY_DELAY_MS: u32 = 500;
+
+// Bagian tulis koneksi: WebSocket asli atau transport lain (mis. server demo di dalam browser)
+pub type FrameSink = Pin<Box<dyn Sink<WsMessage, Error = String>>>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SendError {
+    Closed,    // Koneksi sudah ditutup atau task penulis berhenti
+    QueueFull, // Antrean tetap penuh sampai batas waktu
+    Timeout,   // Socket tidak menerima frame dalam batas waktu, juga setelah dicoba ulang
+    Serialize(String),
+    Socket(String),
+}
+
+impl SendError {
+    // Kegagalan yang layak dicoba sekali lagi tanpa membuka koneksi baru
+    pub fn is_transient(&self) -> bool {
+        matches!(self, SendError::Timeout)
+    }
+}
+
+// Bukti frame sudah ditulis ke socket (belum tentu sudah diproses server)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Receipt {
+    pub attempts: u32,
+    pub queued_ms: f64, // Lama frame menunggu di antrean sebelum ditulis
+    pub bytes: usize,
+}
+
+struct Outgoing {
+    frame: WsMessage, // Teks (JSON) atau biner (MessagePack), sudah dikodekan oleh ChatClient
+    enqueued_at: f64,
+    reply: oneshot::Sender<Result<Receipt, SendError>>,
+}
+
+// Handle pengirim yang murah untuk di-clone; task penulis berhenti dan menutup socket
+// setelah `close()` dipanggil atau semua handle di-drop.
+#[derive(Clone)]
+pub struct FrameWriter {
+    tx: mpsc::Sender<Outgoing>,
+}
+
+impl FrameWriter {
+    pub fn spawn(sink: FrameSink) -> Self {
+        let (tx, rx) = mpsc::channel(QUEUE_CAPACITY);
+        spawn_local(write_loop(sink, rx));
+        Self { tx }
+    }
+
+    pub async fn send_frame(&self, frame: WsMessage) -> Result<Receipt, SendError> {
+        let (reply, receipt) = oneshot::channel();
+        let item = Outgoing { frame, enqueued_at: js_sys::Date::now(), reply };
+        let mut tx = self.tx.clone();
+        match with_timeout(tx.send(item), ENQUEUE_TIMEOUT_MS).await {
+            None => return Err(SendError::QueueFull),
+            Some(Err(_)) => return Err(SendError::Closed),
+            Some(Ok(())) => {}
+        }
+        receipt.await.unwrap_or(Err(SendError::Closed))
+    }
+
+    // Frame yang sudah di antrean tetap ditulis dulu, baru socket ditutup
+    pub fn close(&self) {
+        self.tx.clone().close_channel();
+    }
+
+    // Setelah `close()` atau setelah task penulis berhenti
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+}
+
+async fn write_loop(mut sink: FrameSink, mut rx: mpsc::Receiver<Outgoing>) {
+    while let Some(item) = rx.next().await {
+        let queued_ms = js_sys::Date::now() - item.enqueued_at;
+        let bytes = codec::frame_len(&item.frame);
+        let result = write_with_retry(&mut sink, item.frame).await.map(|attempts| Receipt { attempts, queued_ms, bytes });
+        let broken = matches!(result, Err(SendError::Socket(_)));
+        let _ = item.reply.send(result);
+        if broken {
+            break; // Socket rusak: sisa antrean digagalkan di bawah, pengirim menyimpannya ke outbox
+        }
+    }
+    rx.close();
+    while let Ok(item) = rx.try_recv() {
+        let _ = item.reply.send(Err(SendError::Closed));
+    }
+    let _ = sink.close().await;
+}
+
+async fn write_with_retry(sink: &mut FrameSink, frame: WsMessage) -> Result<u32, SendError> {
+    match write_once(sink, frame.clone()).await {
+        Err(e) if e.is_transient() => {
+            TimeoutFuture::new(RETRY_DELAY_MS).await;
+            write_once(sink, frame).await.map(|()| 2)
+        }
+        result => result.map(|()| 1),
+    }
+}
+
+async fn write_once(sink: &mut FrameSink, frame: WsMessage) -> Result<(), SendError> {
+    match with_timeout(sink.send(frame), WRITE_TIMEOUT_MS).await {
+        None => Err(SendError::Timeout),
+        Some(Err(e)) => Err(SendError::Socket(e)),
+        Some(Ok(())) => Ok(()),
+    }
+}
+
+// None jika `fut` belum selesai setelah `ms` milidetik
+async fn with_timeout<F: Future>(fut: F, ms: u32) -> Option<F::Output> {
+    let fut = std::pin::pin!(fut);
+    match future::select(fut, TimeoutFuture::new(ms)).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(_) => None,
+    }
+}
@@ -0,0 +1,112 @@
+// src/history.rs
+// Riwayat pesan room dan username terakhir di localStorage, supaya refresh halaman tidak
+// mengosongkan chat. Yang disimpan hanya N pesan terbaru (lintas room); pesan sementara dan pesan
+// langsung tidak ikut. Saat enkripsi cache aktif riwayat dienkripsi dengan kunci vault seperti outbox,
+// dan baru dimuat setelah chat dibuka kuncinya.
+use crate::vault::{self, EncryptedSlot, VaultKey};
+use crate::{storage, ChatMessage};
+
+const HISTORY_KEY: &str = "webchat.history";
+const USERNAME_KEY: &str = "webchat.username";
+
+pub const DEFAULT_LIMIT: usize = 200;
+pub const LIMIT_CHOICES: [usize; 5] = [0, 50, 200, 500, 1000]; // 0 = jangan simpan riwayat
+
+#[derive(Debug, Default)]
+pub struct LocalHistory {
+    messages: Vec<ChatMessage>, // Terlama dulu
+    key: Option<VaultKey>,
+    slot: EncryptedSlot,
+}
+
+impl LocalHistory {
+    // Saat enkripsi aktif riwayat dimulai kosong sampai `unlock`
+    pub fn load(limit: usize) -> Self {
+        if limit == 0 || vault::is_enabled() {
+            return Self::default();
+        }
+        let mut messages: Vec<ChatMessage> = storage::get_json(HISTORY_KEY).unwrap_or_default();
+        let excess = messages.len().saturating_sub(limit);
+        messages.drain(..excess);
+        Self { messages, ..Self::default() }
+    }
+
+    // Baca dan dekripsi riwayat tersimpan dengan kunci vault
+    pub async fn load_encrypted(key: &VaultKey) -> Result<Vec<ChatMessage>, String> {
+        Ok(vault::load_json(key, HISTORY_KEY).await?.unwrap_or_default())
+    }
+
+    // Pasang kunci vault (setelah setup atau unlock): riwayat tersimpan ditaruh sebelum pesan yang
+    // diterima selama terkunci, lalu semuanya disimpan ulang dalam bentuk terenkripsi
+    pub fn unlock(&mut self, key: VaultKey, restored: Vec<ChatMessage>, limit: usize) {
+        self.key = Some(key);
+        let current = std::mem::take(&mut self.messages);
+        self.messages = restored.into_iter().filter(|msg| !current.iter().any(|newer| newer.id.is_some() && newer.id == msg.id)).collect();
+        self.messages.extend(current);
+        self.truncate(limit);
+    }
+
+    // Buang kunci dan pesan yang sudah didekripsi dari memori
+    pub fn lock(&mut self) {
+        self.key = None;
+        self.messages.clear();
+    }
+
+    // Enkripsi dimatikan: simpan ulang sebagai JSON biasa
+    pub fn disable_encryption(&mut self) {
+        self.key = None;
+        self.persist();
+    }
+
+    pub fn messages(&self) -> &[ChatMessage] {
+        &self.messages
+    }
+
+    pub fn record(&mut self, messages: &[ChatMessage], limit: usize) {
+        let kept = messages.iter().filter(|msg| msg.expires_at.is_none());
+        self.messages.extend(kept.cloned());
+        self.truncate(limit);
+    }
+
+    // Batas baru dari pengaturan; 0 menghapus riwayat yang tersimpan
+    pub fn truncate(&mut self, limit: usize) {
+        let excess = self.messages.len().saturating_sub(limit);
+        if excess > 0 {
+            self.messages.drain(..excess);
+        }
+        self.persist();
+    }
+
+    pub fn clear(&mut self) {
+        self.messages.clear();
+        self.slot.cancel();
+        storage::remove(HISTORY_KEY);
+    }
+
+    fn persist(&self) {
+        if self.key.is_none() && vault::is_enabled() {
+            return; // Terkunci: jangan sentuh data terenkripsi yang tersimpan
+        }
+        self.slot.cancel();
+        if self.messages.is_empty() {
+            storage::remove(HISTORY_KEY);
+            return;
+        }
+        match &self.key {
+            Some(key) => {
+                if let Ok(plaintext) = serde_json::to_vec(&self.messages) {
+                    self.slot.store(key, HISTORY_KEY, plaintext);
+                }
+            }
+            None => storage::set_json(HISTORY_KEY, &self.messages),
+        }
+    }
+}
+
+pub fn load_username() -> Option<String> {
+    storage::get(USERNAME_KEY).filter(|name| !name.is_empty())
+}
+
+pub fn save_username(name: &str) {
+    storage::set(USERNAME_KEY, name);
+}
@@ -0,0 +1,675 @@
+// src/i18n.rs
+// Bahasa antarmuka. Teks sumber ditulis dalam bahasa Indonesia langsung di kode dan sekaligus menjadi
+// kunci terjemahan (gaya gettext), jadi kode tetap terbaca dan teks yang belum diterjemahkan tampil
+// dalam bahasa Indonesia. `{}` di teks diisi berurutan oleh `Locale::f`; terjemahan untuk `Locale::n`
+// boleh berisi bentuk tunggal dan jamak yang dipisah `|`.
+//
+// Locale aktif disimpan di thread_local untuk kode di luar komponen (Display ChatError, pesan sistem
+// lokal), dan App menyediakannya lewat ContextProvider<Locale> sehingga function component yang
+// memakai `use_locale` ikut dirender ulang saat bahasa diganti.
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Locale {
+    #[default]
+    Id,
+    En,
+}
+
+pub const LOCALES: [Locale; 2] = [Locale::Id, Locale::En];
+
+thread_local! {
+    static CURRENT: Cell<Locale> = Cell::new(Locale::browser());
+    static BUNDLES: RefCell<HashMap<Locale, HashMap<&'static str, &'static str>>> = RefCell::default();
+}
+
+impl Locale {
+    // Kode BCP 47 untuk atribut `lang` dan format tanggal
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::Id => "id",
+            Locale::En => "en",
+        }
+    }
+
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::Id => "id-ID",
+            Locale::En => "en-US",
+        }
+    }
+
+    // Nama bahasa dalam bahasanya sendiri, untuk pilihan di pengaturan
+    pub fn name(self) -> &'static str {
+        match self {
+            Locale::Id => "Bahasa Indonesia",
+            Locale::En => "English",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Locale> {
+        LOCALES.into_iter().find(|locale| locale.code() == code)
+    }
+
+    // Bahasa browser: Indonesia/Melayu tetap Indonesia, selain itu Inggris
+    pub fn browser() -> Locale {
+        let language = web_sys::window().and_then(|w| w.navigator().language()).map(|tag| crate::translate::primary_subtag(&tag));
+        match language.as_deref() {
+            Some("id" | "ms") | None => Locale::Id,
+            Some(_) => Locale::En,
+        }
+    }
+
+    // Pilihan di pengaturan; None = ikuti browser
+    pub fn resolve(chosen: Option<Locale>) -> Locale {
+        chosen.unwrap_or_else(Locale::browser)
+    }
+
+    fn bundle(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::Id => &[],
+            Locale::En => EN,
+        }
+    }
+
+    fn lookup(self, source: &'static str) -> Option<&'static str> {
+        if self == Locale::Id {
+            return None;
+        }
+        BUNDLES.with(|bundles| {
+            let mut bundles = bundles.borrow_mut();
+            let bundle = bundles.entry(self).or_insert_with(|| self.bundle().iter().copied().collect());
+            bundle.get(source).copied()
+        })
+    }
+
+    pub fn t(self, source: &'static str) -> &'static str {
+        self.lookup(source).unwrap_or(source)
+    }
+
+    // t() lalu isi `{}` berurutan dengan `args`
+    pub fn f(self, source: &'static str, args: &[&dyn Display]) -> String {
+        fill(self.t(source), args)
+    }
+
+    // Teks dengan jumlah; `{}` pertama diisi `count`, sisanya dari `args`
+    pub fn n(self, source: &'static str, count: usize, args: &[&dyn Display]) -> String {
+        let text = self.t(source);
+        let text = match text.split_once('|') {
+            Some((one, _)) if count == 1 => one,
+            Some((_, many)) => many,
+            None => text,
+        };
+        let mut all: Vec<&dyn Display> = vec![&count];
+        all.extend_from_slice(args);
+        fill(text, &all)
+    }
+}
+
+fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut parts = template.split("{}");
+    out.push_str(parts.next().unwrap_or_default());
+    for part in parts {
+        if let Some(arg) = args.next() {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+pub fn current() -> Locale {
+    CURRENT.with(Cell::get)
+}
+
+// Dipanggil App saat pengaturan bahasa berubah
+pub fn set(locale: Locale) {
+    CURRENT.with(|current| current.set(locale));
+}
+
+// Untuk kode di luar komponen; komponen memakai Locale dari use_locale
+pub fn tr(source: &'static str) -> &'static str {
+    current().t(source)
+}
+
+pub fn trf(source: &'static str, args: &[&dyn Display]) -> String {
+    current().f(source, args)
+}
+
+pub fn trn(source: &'static str, count: usize, args: &[&dyn Display]) -> String {
+    current().n(source, count, args)
+}
+
+#[hook]
+pub fn use_locale() -> Locale {
+    use_context::<Locale>().unwrap_or_else(current)
+}
+
+// Bahasa Inggris. Urutkan menurut file tempat teksnya dipakai.
+const EN: &[(&str, &str)] = &[
+    // auth.rs
+    ("Masuk", "Sign in"),
+    ("Server ini hanya bisa dipakai setelah login. Tempel token akses dari akun Anda.", "This server requires signing in. Paste an access token from your account."),
+    ("Token akses", "Access token"),
+    ("Ingat di perangkat ini", "Remember on this device"),
+    ("Memeriksa…", "Checking…"),
+    // gallery.rs
+    ("Semua", "All"),
+    ("Gambar", "Images"),
+    ("Video", "Videos"),
+    ("Suara", "Audio"),
+    ("File", "Files"),
+    ("Media & file ({})", "Media & files ({})"),
+    ("Semua pengirim", "All senders"),
+    ("Belum ada lampiran.", "No attachments yet."),
+    // ice_panel.rs
+    ("Jaringan panggilan (STUN/TURN)", "Call network (STUN/TURN)"),
+    ("dengan kredensial", "with credentials"),
+    ("Server belum mengirim konfigurasi; memakai STUN publik bawaan tanpa TURN.", "The server has not sent a configuration yet; using the built-in public STUN without TURN."),
+    ("Uji konektivitas", "Test connectivity"),
+    ("Mengumpulkan kandidat ICE…", "Gathering ICE candidates…"),
+    ("Kandidat: {} lokal, {} STUN, {} relay", "Candidates: {} local, {} STUN, {} relay"),
+    ("(batas waktu habis sebelum selesai)", "(timed out before finishing)"),
+    ("Browser menolak membuat koneksi WebRTC: {}", "The browser refused to create a WebRTC connection: {}"),
+    ("TURN berfungsi: panggilan dan transfer file bisa lewat relay bila koneksi langsung gagal.", "TURN works: calls and file transfers can go through the relay when a direct connection fails."),
+    ("STUN berfungsi, tetapi server TURN tidak menjawab. Koneksi di balik NAT ketat bisa gagal.", "STUN works, but the TURN server is not responding. Connections behind strict NAT may fail."),
+    ("STUN berfungsi. Tanpa TURN, koneksi di balik NAT ketat bisa gagal.", "STUN works. Without TURN, connections behind strict NAT may fail."),
+    ("Server STUN/TURN tidak terjangkau; hanya koneksi di jaringan lokal yang mungkin berhasil.", "The STUN/TURN servers are unreachable; only connections on the local network may succeed."),
+    // media.rs
+    ("embed gambar dimatikan di room ini", "image embeds are turned off in this room"),
+    ("GIF tanpa rating", "unrated GIF"),
+    ("rating {}", "rated {}"),
+    ("🖼 {} (disembunyikan: {})", "🖼 {} (hidden: {})"),
+    // uploads.rs
+    ("Format video {} tidak didukung (gunakan MP4, WebM atau Ogg).", "Video format {} is not supported (use MP4, WebM or Ogg)."),
+    ("Video terlalu besar; maksimal {}.", "Video is too large; the maximum is {}."),
+    ("Jenis file {} tidak diizinkan.", "File type {} is not allowed."),
+    ("Isi file ({}) tidak sesuai dengan jenisnya ({}).", "The file contents ({}) do not match its type ({})."),
+    ("File terlalu besar; maksimal {}.", "File is too large; the maximum is {}."),
+    ("File terdeteksi berbahaya ({}).", "File was flagged as malicious ({})."),
+    ("File terdeteksi berbahaya.", "File was flagged as malicious."),
+    ("Pemindaian file sedang tidak tersedia.", "File scanning is unavailable right now."),
+    ("Upload ditolak server.", "The server rejected the upload."),
+    ("Terlalu banyak upload yang belum selesai; tunggu atau batalkan yang lain dulu.", "Too many unfinished uploads; wait for them or cancel one first."),
+    // label enum protokol
+    ("Tidak di tempat", "Away"),
+    ("Sibuk", "Busy"),
+    ("Hadir", "Going"),
+    ("Mungkin", "Maybe"),
+    ("Tidak hadir", "Not going"),
+    ("G (semua umur)", "G (all ages)"),
+    ("R (dewasa)", "R (adults)"),
+    ("Kata sandi room salah.", "Wrong room password."),
+    ("Room ini membutuhkan kata sandi.", "This room requires a password."),
+    ("Room tidak ditemukan.", "Room not found."),
+    ("Room dengan nama itu sudah ada.", "A room with that name already exists."),
+    ("Terlalu banyak percobaan kata sandi. Coba lagi beberapa menit lagi.", "Too many password attempts. Try again in a few minutes."),
+    ("Gagal bergabung ke room.", "Failed to join the room."),
+    // members_panel.rs
+    ("Anggota ({})", "Members ({})"),
+    ("Anggota", "Members"),
+    ("Cari anggota...", "Search members..."),
+    ("Memuat...", "Loading..."),
+    ("Belum ada anggota", "No members yet"),
+    ("Tidak ada anggota yang cocok", "No matching members"),
+    ("Muat lebih banyak", "Load more"),
+    // online_panel.rs
+    ("Online ({})", "Online ({})"),
+    ("Online", "Online"),
+    ("Status saya:", "My status:"),
+    ("Tidak ada yang online", "Nobody is online"),
+    ("{} (Anda)", "{} (you)"),
+    ("Sampai {}", "Until {}"),
+    // palette.rs
+    ("Palet perintah", "Command palette"),
+    ("Cari room, pengaturan atau perintah...", "Search rooms, settings or commands..."),
+    ("Tidak ada yang cocok", "No matches"),
+    // commands.rs
+    ("Bergabung ke room", "Join a room"),
+    ("Membuat room baru", "Create a new room"),
+    ("Mengganti username", "Change your username"),
+    ("Atur slow mode dalam detik (moderator)", "Set slow mode in seconds (moderators)"),
+    ("Jadwalkan acara: /event 2024-05-12 19:00 Judul", "Schedule an event: /event 2024-05-12 19:00 Title"),
+    ("Sisipkan balasan cepat ke input", "Insert a canned response into the input"),
+    ("Penggunaan: {}<nilai>", "Usage: {}<value>"),
+    ("Penggunaan: /slow <detik>", "Usage: /slow <seconds>"),
+    ("Perintah /{} tidak dikenal. Awali dengan // untuk mengirim teks yang diawali /.", "Unknown command /{}. Start with // to send text that begins with /."),
+    ("Room", "Room"),
+    ("Pesan langsung", "Direct messages"),
+    ("Balasan cepat", "Canned responses"),
+    ("Pengaturan", "Settings"),
+    ("Perintah", "Commands"),
+    ("Perintah bot {}", "Bot command {}"),
+    ("Berbisik ke satu user di room ini", "Whisper to one user in this room"),
+    ("Penggunaan: /whisper @user <pesan>", "Usage: /whisper @user <message>"),
+    // privacy_panel.rs
+    ("Privasi", "Privacy"),
+    ("Sembunyikan status \"terakhir dilihat\" saya", "Hide my \"last seen\" status"),
+    ("Matikan tanda dibaca", "Turn off read receipts"),
+    ("(Anda juga tidak akan melihat tanda dibaca dari orang lain)", "(you will not see read receipts from others either)"),
+    ("Jangan tampilkan saat saya sedang mengetik", "Don't show when I'm typing"),
+    ("Tolak pesan langsung dari selain kontak", "Refuse direct messages from non-contacts"),
+    ("Data akun", "Account data"),
+    ("Menyiapkan arsip…", "Preparing archive…"),
+    ("Ekspor semua pesan saya", "Export all my messages"),
+    ("Unduh arsip", "Download archive"),
+    ("Hapus akun", "Delete account"),
+    // rooms_panel.rs
+    ("Aktifkan notifikasi", "Enable notifications"),
+    ("Gabung lagi", "Rejoin"),
+    ("Notifikasi dibisukan sampai {}", "Notifications muted until {}"),
+    ("Bunyikan lagi", "Unmute"),
+    ("Bisukan notifikasi", "Mute notifications"),
+    ("Bisukan…", "Mute…"),
+    ("Keluar dari room", "Leave room"),
+    // notify.rs
+    ("Bisukan 1 jam", "Mute for 1 hour"),
+    ("Bisukan 8 jam", "Mute for 8 hours"),
+    ("Bisukan sampai besok", "Mute until tomorrow"),
+    // stats.rs
+    ("{}d", "{}s"),
+    ("{}m {}d", "{}m {}s"),
+    ("{}j {}m {}d", "{}h {}m {}s"),
+    // sessions_panel.rs
+    ("Perangkat", "Devices"),
+    ("Muat ulang", "Refresh"),
+    ("Tampilkan perangkat yang login", "Show signed-in devices"),
+    ("perangkat ini", "this device"),
+    ("aktif {}", "active {}"),
+    ("Keluarkan", "Sign out"),
+    // stats_panel.rs
+    ("terputus", "disconnected"),
+    ("Laporan disalin.", "Report copied."),
+    ("Gagal menyalin ke clipboard.", "Could not copy to the clipboard."),
+    ("Diagnostik koneksi", "Connection diagnostics"),
+    ("Uptime", "Uptime"),
+    ("Durasi sesi", "Session length"),
+    ("Reconnect", "Reconnects"),
+    ("Pesan dikirim / diterima", "Messages sent / received"),
+    ("Data dikirim / diterima", "Data sent / received"),
+    ("Latensi rata-rata", "Average latency"),
+    ("Latensi terakhir", "Last latency"),
+    ("Perbarui", "Refresh"),
+    ("Salin laporan", "Copy report"),
+    // status.rs
+    ("Jangan hapus", "Don't clear"),
+    ("{} jam", "{} hour|{} hours"),
+    ("{} menit", "{} minute|{} minutes"),
+    ("Hari ini", "Today"),
+    ("Sedang rapat", "In a meeting"),
+    ("Dalam perjalanan", "Commuting"),
+    ("Makan siang", "Out for lunch"),
+    ("Fokus, balas nanti", "Focusing, will reply later"),
+    ("Sedang sakit", "Out sick"),
+    ("Teks status tidak boleh kosong.", "Status text cannot be empty."),
+    ("Teks status maksimal {} karakter.", "Status text can be at most {} characters."),
+    // status_panel.rs
+    ("Ubah status", "Change status"),
+    ("Atur status…", "Set a status…"),
+    ("Emoji status", "Status emoji"),
+    ("Apa yang sedang Anda lakukan?", "What are you up to?"),
+    ("Hapus setelah:", "Clear after:"),
+    ("Simpan", "Save"),
+    ("Batal", "Cancel"),
+    ("Hapus", "Remove"),
+    // support_panel.rs
+    ("Antrean dukungan ({})", "Support queue ({})"),
+    ("Tidak ada tamu yang menunggu", "No guests waiting"),
+    ("Mengambil…", "Claiming…"),
+    ("Ambil", "Claim"),
+    ("Buka", "Open"),
+    ("Lepas", "Release"),
+    ("Ditangani {}", "Handled by {}"),
+    // tour.rs
+    ("Lewati", "Skip"),
+    ("Kembali", "Back"),
+    ("Selesai", "Done"),
+    ("Lanjut", "Next"),
+    // twofactor.rs
+    ("Kode QR 2FA", "2FA QR code"),
+    ("Masukkan salah satu kode pemulihan Anda.", "Enter one of your recovery codes."),
+    ("Masukkan kode 6 digit dari aplikasi authenticator.", "Enter the 6-digit code from your authenticator app."),
+    ("Pakai kode authenticator", "Use an authenticator code"),
+    ("Pakai kode pemulihan", "Use a recovery code"),
+    ("Aktifkan verifikasi dua langkah", "Turn on two-step verification"),
+    ("1. Pindai kode QR ini dengan aplikasi authenticator (Google Authenticator, Aegis, 1Password, dll.).", "1. Scan this QR code with an authenticator app (Google Authenticator, Aegis, 1Password, etc.)."),
+    ("Atau masukkan kunci ini secara manual:", "Or enter this key manually:"),
+    ("2. Simpan kode pemulihan berikut di tempat aman. Tiap kode hanya bisa dipakai sekali jika ponsel Anda hilang.", "2. Store these recovery codes somewhere safe. Each code can be used once if you lose your phone."),
+    ("3. Masukkan kode 6 digit dari aplikasi untuk menyelesaikan.", "3. Enter the 6-digit code from the app to finish."),
+    ("Aktifkan", "Turn on"),
+    ("Verifikasi", "Verify"),
+    // voice.rs
+    ("Kecepatan putar", "Playback speed"),
+    // widgets.rs
+    ("Gagal terkirim", "Failed to send"),
+    ("Ketik pesan...", "Type a message..."),
+    ("Kirim", "Send"),
+    // validation.rs
+    ("Username minimal {} karakter.", "Usernames need at least {} characters."),
+    ("Username maksimal {} karakter.", "Usernames can have at most {} characters."),
+    ("Karakter '{}' tidak diizinkan.", "The character '{}' is not allowed."),
+    ("Nama \"{}\" sudah dicadangkan.", "The name \"{}\" is reserved."),
+    // vault.rs
+    ("Enkripsi belum diaktifkan", "Encryption is not enabled"),
+    ("Data verifikasi hilang", "Verification data is missing"),
+    ("Passphrase salah.", "Wrong passphrase."),
+    ("Data terenkripsi rusak", "Encrypted data is corrupted"),
+    // directory.rs
+    ("Pengguna terhapus", "Deleted user"),
+    // presence.rs
+    ("terakhir dilihat {}", "last seen {}"),
+    // schedule.rs
+    ("Penggunaan: /event <YYYY-MM-DD> <HH:MM> <judul>", "Usage: /event <YYYY-MM-DD> <HH:MM> <title>"),
+    ("Waktu acara sudah lewat.", "The event time has already passed."),
+    // room.rs
+    ("{} detik", "{} second|{} seconds"),
+    ("{} hari", "{} day|{} days"),
+    // canned.rs
+    ("lawan bicara: user di tab pesan langsung, atau pengirim pesan terakhir di room", "the other person: the user in the direct message tab, or the sender of the last message in the room"),
+    ("username Anda", "your username"),
+    ("nama room saat ini", "the current room name"),
+    ("Nama balasan cepat harus satu kata.", "Canned response names must be a single word."),
+    ("Balasan cepat \"{}\" sudah ada.", "A canned response named \"{}\" already exists."),
+    // maintenance.rs
+    ("Server sedang dalam pemeliharaan", "The server is under maintenance"),
+    ("Pesan lama tetap bisa dibaca. Menyambung lagi otomatis dalam {}.", "Older messages can still be read. Reconnecting automatically in {}."),
+    // components.rs
+    ("Pilih…", "Choose…"),
+    ("Menunggu bot…", "Waiting for the bot…"),
+    // typing.rs
+    ("{} sedang mengetik…", "{} is typing…"),
+    ("{} dan {} sedang mengetik…", "{} and {} are typing…"),
+    ("{} orang sedang mengetik…", "{} people are typing…"),
+    ("{} sedang menulis pesan panjang…", "{} is writing a long message…"),
+    // time.rs
+    ("baru saja", "just now"),
+    ("{} menit lalu", "{} minute ago|{} minutes ago"),
+    ("{} jam lalu", "{} hour ago|{} hours ago"),
+    ("{} hari lalu", "{} day ago|{} days ago"),
+    // error.rs
+    ("Gagal terhubung ke server.", "Could not connect to the server."),
+    ("Koneksi ditutup oleh server (kode {}).", "The server closed the connection (code {})."),
+    ("Koneksi ditutup oleh server (kode {}): {}", "The server closed the connection (code {}): {}"),
+    ("Koneksi ke server terputus.", "Lost the connection to the server."),
+    ("Tidak terhubung ke server WebSocket.", "Not connected to the WebSocket server."),
+    ("Server tidak merespons lagi; menyambung ulang…", "The server stopped responding; reconnecting…"),
+    ("Antrean kirim penuh; koneksi terlalu lambat.", "The send queue is full; the connection is too slow."),
+    ("Server tidak merespons saat mengirim, juga setelah dicoba ulang.", "The server did not respond while sending, even after retrying."),
+    ("Gagal mengirim ke server.", "Could not send to the server."),
+    ("Server mengirim data yang tidak dikenali ({}).", "The server sent data that could not be recognized ({})."),
+    ("Sesi ini dikeluarkan dari perangkat lain: {}", "This session was signed out from another device: {}"),
+    ("Sesi ini dikeluarkan dari perangkat lain.", "This session was signed out from another device."),
+    ("Masuk dulu untuk mengirim pesan.", "Sign in first to send messages."),
+    ("Terlalu banyak pesan. Coba lagi dalam {} detik.", "Too many messages. Try again in {} seconds."),
+    ("Terjadi kesalahan di aplikasi: {}", "Something went wrong in the app: {}"),
+    // state.rs
+    ("Server tidak mengonfirmasi pesan ini", "The server did not confirm this message"),
+    // settings_panel.rs
+    ("Ikuti sistem", "Follow system"),
+    ("Aktif", "On"),
+    ("Mati", "Off"),
+    ("Teks balasan cepat tidak boleh kosong.", "Canned response text cannot be empty."),
+    ("Kualitas: {}%", "Quality: {}%"),
+    ("Tampilan", "Appearance"),
+    ("Bahasa antarmuka:", "Interface language:"),
+    ("Ikuti browser ({})", "Follow browser ({})"),
+    ("Kecil", "Small"),
+    ("Normal", "Normal"),
+    ("Besar", "Large"),
+    ("Kustom", "Custom"),
+    ("Skala: {}%", "Scale: {}%"),
+    ("Mode ringan mematikan animasi, merender lebih sedikit pesan sekaligus dan mengumpulkan pesan masuk sebelum ditampilkan.", "Lite mode turns off animations, renders fewer messages at once and batches incoming messages before showing them."),
+    ("Dengan \"Ikuti sistem\", mode ini aktif otomatis di perangkat dengan CPU/memori terbatas atau saat baterai lemah", "With \"Follow system\", it turns on automatically on devices with limited CPU/memory or when the battery is low"),
+    ("perangkat ini: {}", "this device: {}"),
+    ("Saat ini aktif.", "Currently on."),
+    ("mis. Sari Dewi", "e.g. Sari Dewi"),
+    ("Jangan simpan", "Don't keep"),
+    ("{} pesan", "{} message|{} messages"),
+    ("Nama (satu kata)", "Name (one word)"),
+    ("Teks balasan", "Response text"),
+    ("Upload gambar", "Image uploads"),
+    ("Kompres gambar sebelum dikirim (matikan untuk mengirim file asli)", "Compress images before sending (turn off to send the original file)"),
+    ("Hapus metadata (lokasi GPS, info kamera) dari gambar", "Remove metadata (GPS location, camera info) from images"),
+    ("Terjemahan", "Translation"),
+    ("Pesan di room berbahasa lain bisa diterjemahkan ke bahasa ini.", "Messages in rooms in other languages can be translated into this language."),
+    ("Akun bersama", "Shared account"),
+    ("Jika akun ini dipakai bergantian, inisial nama ini ditampilkan di pesan Anda dan saat Anda sedang mengetik. Hanya dikirim ke server yang mendukungnya.", "If this account is shared, the initials of this name are shown on your messages and while you are typing. Only sent to servers that support it."),
+    ("GIF", "GIF"),
+    ("Riwayat lokal", "Local history"),
+    ("Hapus riwayat", "Clear history"),
+    ("Riwayat tidak disimpan selama enkripsi cache lokal aktif.", "History is not kept while local cache encryption is on."),
+    ("Layanan pelanggan", "Customer support"),
+    ("Mode agen dukungan", "Support agent mode"),
+    ("(pesan langsung dari tamu masuk ke antrean bersama yang bisa Anda ambil)", "(direct messages from guests go into a shared queue you can claim from)"),
+    ("Ukuran teks:", "Text size:"),
+    ("Kontras tinggi:", "High contrast:"),
+    ("Kurangi animasi:", "Reduce motion:"),
+    ("Mode ringan:", "Lite mode:"),
+    ("Bahasa saya:", "My language:"),
+    ("Nama operator:", "Operator name:"),
+    ("Rating konten maksimal:", "Maximum content rating:"),
+    ("Simpan pesan terakhir:", "Keep recent messages:"),
+    ("Placeholder:", "Placeholders:"),
+    ("Tambah", "Add"),
+    ("Tema:", "Theme:"),
+    ("Terang", "Light"),
+    ("Gelap", "Dark"),
+    // lib.rs
+    ("Percakapan ini sedang ditangani {}.", "This conversation is being handled by {}."),
+    ("Gagal membaca file: {}", "Could not read the file: {}"),
+    ("Hapus akun? Pesan lama Anda akan dianonimkan dan tindakan ini tidak bisa dibatalkan.", "Delete your account? Your old messages will be anonymized and this cannot be undone."),
+    ("Token ditolak server.", "The server rejected the token."),
+    ("Balasan cepat \"{}\" tidak ditemukan.", "Canned response \"{}\" was not found."),
+    ("Bot tidak menanggapi. Coba lagi nanti.", "The bot did not respond. Try again later."),
+    ("Hanya moderator yang bisa mengatur slow mode.", "Only moderators can set slow mode."),
+    ("Bergabunglah ke room dulu untuk menjadwalkan acara.", "Join a room first to schedule an event."),
+    ("Room \"{}\" telah kedaluwarsa dan diarsipkan.", "Room \"{}\" has expired and was archived."),
+    ("YewChat Interaktif V2!", "Interactive YewChat V2!"),
+    ("Tampilkan tur pengenalan", "Show the introduction tour"),
+    ("Username saat ini: {}", "Current username: {}"),
+    ("Tidak terhubung ke server. Mencoba menghubungkan...", "Not connected to the server. Trying to connect..."),
+    ("Coba Hubungkan Ulang", "Reconnect"),
+    ("Terhubung ke server!", "Connected to the server!"),
+    ("Set username...", "Set username..."),
+    ("Set Username", "Set Username"),
+    ("Nama room...", "Room name..."),
+    ("Kata sandi (jika ada)", "Password (if any)"),
+    ("Gabung", "Join"),
+    ("Room permanen", "Permanent room"),
+    ("Sementara (1 jam tidak aktif)", "Temporary (1 hour inactive)"),
+    ("Sementara (24 jam tidak aktif)", "Temporary (24 hours inactive)"),
+    ("Pesan hanya sampai ke moderator, tanpa nama pengirim", "Messages only reach moderators, without the sender's name"),
+    ("Kotak saran anonim", "Anonymous feedback box"),
+    ("Buat Room", "Create Room"),
+    ("📮 Saran anonim: moderator membaca kiriman Anda tanpa nama atau id akun.", "📮 Anonymous feedback: moderators read your submission without your name or account id."),
+    ("Pesan langsung untuk {}...", "Direct message to {}..."),
+    ("Tulis saran anonim...", "Write anonymous feedback..."),
+    ("Lampirkan file", "Attach file"),
+    ("Kirim ({})", "Send ({})"),
+    ("Pesan dari {}", "Message from {}"),
+    ("{} di {}", "{} in {}"),
+    ("{} di room umum", "{} in the general room"),
+    ("Buka pengaturan", "Open settings"),
+    ("Ekspor data saya", "Export my data"),
+    ("Mulai tur pengenalan", "Start the introduction tour"),
+    ("Pesan langsung dari {}", "Direct message from {}"),
+    ("Terjemahan tidak tersedia", "Translation unavailable"),
+    ("Kode salah. Terlalu banyak percobaan, coba lagi nanti.", "Wrong code. Too many attempts, try again later."),
+    ("Kode salah. Sisa percobaan: {}.", "Wrong code. Attempts left: {}."),
+    ("Kode salah.", "Wrong code."),
+    ("Hubungkan ulang", "Reconnect"),
+    ("Masuk lagi", "Sign in again"),
+    ("Tutup", "Close"),
+    ("Room: (umum)", "Room: (general)"),
+    ("Coba lagi", "Retry"),
+    ("🔐 Cache lokal terenkripsi", "🔐 Local cache encrypted"),
+    ("Kunci chat", "Lock chat"),
+    ("Kunci otomatis: ", "Auto-lock: "),
+    ("Matikan enkripsi", "Turn off encryption"),
+    ("Enkripsi cache lokal", "Encrypt local cache"),
+    ("🛡 Verifikasi dua langkah aktif", "🛡 Two-step verification on"),
+    ("Matikan", "Turn off"),
+    ("🔒 Chat terkunci", "🔒 Chat locked"),
+    ("Masukkan passphrase perangkat untuk membuka riwayat lokal.", "Enter the device passphrase to unlock the local history."),
+    ("· metadata dihapus", "· metadata removed"),
+    ("Bisa mengirim lagi dalam {} detik", "You can send again in {} seconds"),
+    ("Error: {}", "Error: {}"),
+    ("Pesan langsung dengan {}", "Direct messages with {}"),
+    ("Room: {}", "Room: {}"),
+    ("Room sementara akan diarsipkan jika tidak ada aktivitas", "Temporary rooms are archived when there is no activity"),
+    ("⏳ berakhir dalam {}", "⏳ ends in {}"),
+    ("Identitas asli disembunyikan; nama samaran diganti secara berkala oleh server", "Real identities are hidden; the server rotates pseudonyms periodically"),
+    ("🎭 Room anonim · Anda tampil sebagai {}", "🎭 Anonymous room · you appear as {}"),
+    ("Server menghapus nama dan id pengirim sebelum pesan diteruskan", "The server removes the sender's name and id before forwarding messages"),
+    ("📮 Kotak saran · kiriman tampil tanpa nama pengirim", "📮 Feedback box · submissions appear without the sender's name"),
+    ("📮 Kotak saran anonim · hanya moderator yang membaca", "📮 Anonymous feedback box · only moderators read it"),
+    ("Terjemahkan otomatis ke {}", "Automatically translate to {}"),
+    ("⏱ pesan hilang setelah {}", "⏱ messages disappear after {}"),
+    ("Room besar: status online dikirim server secara berkala", "Large room: the server sends online status periodically"),
+    ("👥 status online diperbarui tiap {} detik", "👥 online status updated every {} seconds"),
+    ("🐢 slow mode: 1 pesan / {} detik", "🐢 slow mode: 1 message / {} seconds"),
+    ("Room umum", "General room"),
+    ("chunk {} / {}", "chunk {} / {}"),
+    ("terputus, menunggu koneksi…", "disconnected, waiting for connection…"),
+    ("gagal: {}", "failed: {}"),
+    ("ditolak: {}", "rejected: {}"),
+    ("dikompres dari {}", "compressed from {}"),
+    ("Lokasi GPS dan data kamera tidak ikut terkirim", "GPS location and camera data are not sent"),
+    ("setelah {} menit tidak aktif", "after {} minutes of inactivity"),
+    ("Passphrase perangkat", "Device passphrase"),
+    ("Matikan verifikasi dua langkah", "Turn off two-step verification"),
+    ("Verifikasi dua langkah", "Two-step verification"),
+    ("Membuka…", "Unlocking…"),
+    ("menunggu koneksi…", "waiting for connection…"),
+    ("Buang", "Discard"),
+    ("Saya setuju", "I agree"),
+    ("Slow mode: ", "Slow mode: "),
+    ("Matikan embed gambar/GIF", "Disable image/GIF embeds"),
+    ("Bahasa room: ", "Room language: "),
+    ("Tidak ditentukan", "Not set"),
+    ("Belum terkirim ({})", "Not sent yet ({})"),
+    ("muat pesan yang terlewat ({})", "load missed messages ({})"),
+    ("Selamat datang di {}", "Welcome to {}"),
+    ("Pilih username", "Choose a username"),
+    ("Atur nama yang akan dilihat orang lain. Kamu bisa menggantinya kapan saja.", "Set the name others will see. You can change it any time."),
+    ("Pindah room", "Switch rooms"),
+    ("Gabung ke room lain atau buat room baru, termasuk room berkata sandi dan room sementara.", "Join another room or create a new one, including password-protected and temporary rooms."),
+    ("Atur preferensi seperti kompresi gambar sebelum dikirim.", "Set preferences such as compressing images before sending."),
+    ("Kirim pesan", "Send a message"),
+    ("Ketik pesan lalu tekan Enter atau tombol Kirim.", "Type a message, then press Enter or the Send button."),
+    ("Lihat asli", "Show original"),
+    ("Terjemahkan", "Translate"),
+    ("Nama", "Name"),
+    ("Status", "Status"),
+    ("Sedang", "Currently"),
+    ("Dikirim", "Sent"),
+    ("Disunting", "Edited"),
+    ("Id pesan", "Message id"),
+    ("lihat sumber", "view source"),
+    ("⚠ tidak berurutan", "⚠ out of order"),
+    ("Tampilkan tanda terima pesan (debug server)", "Show message receipts (server debug)"),
+    ("Skenario server demo", "Demo server scenarios"),
+    ("Sumber pesan", "Message source"),
+    ("◀ Mundur", "◀ Back"),
+    ("Maju ▶", "Forward ▶"),
+    ("Pengirim anonim", "Anonymous sender"),
+    ("Dilihat oleh {}", "Seen by {}"),
+    ("✓ Dilihat {}", "✓ Seen {}"),
+    ("Sembunyikan pesan duplikat", "Hide duplicate messages"),
+    ("Tampilkan pesan duplikat", "Show duplicate messages"),
+    ("{} pesan baru ↓", "{} new message ↓|{} new messages ↓"),
+    ("@ {} mention", "@ {} mention|@ {} mentions"),
+    ("Mention sebelumnya (Alt+↑)", "Previous mention (Alt+↑)"),
+    ("Mention berikutnya (Alt+↓)", "Next mention (Alt+↓)"),
+    ("{} · klik untuk pesan langsung", "{} · click to send a direct message"),
+    ("Dikirim oleh {}", "Sent by {}"),
+    ("Pesan ini akan hilang otomatis", "This message will disappear automatically"),
+    ("🌐 Diterjemahkan dari {} · ", "🌐 Translated from {} · "),
+    (" (sampai {})", " (until {})"),
+    ("Menunggu server", "Waiting for the server"),
+    ("Terkirim ke server", "Delivered to the server"),
+    ("Diterima", "Received"),
+    ("riwayat (tidak diterima live)", "history (not received live)"),
+    ("tanpa id", "no id"),
+    ("Waktu tiba dikurangi timestamp server, menurut jam lokal / jam server terkoreksi", "Arrival time minus the server timestamp, by the local clock / corrected server clock"),
+    ("Δ {} (terkoreksi {})", "Δ {} (corrected {})"),
+    ("Δ - (timestamp tidak terbaca)", "Δ - (unreadable timestamp)"),
+    ("Time-travel ({} aksi)", "Time-travel ({} actions)"),
+    (" · meninjau #{}", " · reviewing #{}"),
+    ("· menerjemahkan…", "· translating…"),
+    ("· gagal menerjemahkan ", "· translation failed "),
+    ("· mengirim…", "· sending…"),
+    ("· gagal terkirim", "· not sent"),
+    ("Bisikan tidak tersedia di room anonim dan kotak saran.", "Whispers are not available in anonymous rooms and feedback boxes."),
+    ("Tidak bisa berbisik ke diri sendiri.", "You cannot whisper to yourself."),
+    ("@{} tidak sedang online di room ini.", "@{} is not online in this room."),
+    ("🤫 bisikan untuk {}", "🤫 whisper to {}"),
+    ("🤫 bisikan untuk Anda", "🤫 whisper to you"),
+    ("browser tidak menyediakan angka acak untuk kunci enkripsi", "the browser provides no random numbers for encryption keys"),
+    ("Anda menangani percakapan ini", "You are handling this conversation"),
+    ("Percakapan ini masih menunggu di antrean dukungan", "This conversation is still waiting in the support queue"),
+    ("Memuat pesan sebelumnya…", "Loading earlier messages…"),
+    ("Awal riwayat room ini", "Beginning of this room's history"),
+    // theme.rs
+    ("Beralih ke mode gelap", "Switch to dark mode"),
+    ("Beralih ke mode terang", "Switch to light mode"),
+    // notes.rs
+    ("Catatan room ({})", "Room notes ({})"),
+    ("Belum ada catatan. Catatan terlihat dan bisa disunting semua anggota room.", "No notes yet. Notes are visible to and editable by every member of the room."),
+    ("Catatan", "Note"),
+    ("Terakhir disunting {}", "Last edited by {}"),
+    ("Tulis catatan baru…", "Write a new note…"),
+    ("Tambah catatan", "Add note"),
+    // e2e_panel.rs
+    ("🔒 Enkripsi end-to-end aktif", "🔒 End-to-end encryption on"),
+    ("Enkripsi end-to-end", "End-to-end encryption"),
+    ("Enkripsi teks pesan di room ini", "Encrypt message text in this room"),
+    ("Sidik jari kunci Anda:", "Your key fingerprint:"),
+    ("Belum ada anggota lain yang mengaktifkan enkripsi di room ini.", "No other member has turned on encryption in this room yet."),
+    ("Cocokkan sidik jari berikut dengan pemiliknya lewat jalur lain, mis. bertemu langsung.", "Compare these fingerprints with their owners through another channel, e.g. in person."),
+    ("kunci diterima", "key received"),
+    ("menunggu kunci", "waiting for key"),
+    ("⚠️ Kunci anggota ini berubah. Pesan tidak dienkripsi untuk kunci baru sampai Anda mencocokkan sidik jarinya:", "⚠️ This member's key changed. Messages are not encrypted to the new key until you compare its fingerprint:"),
+    ("Sidik jari cocok, percayai kunci baru", "Fingerprint matches, trust the new key"),
+    // e2e.rs
+    ("🔒 Pesan terenkripsi yang belum bisa dibuka", "🔒 Encrypted message that can't be opened yet"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn english_bundle_has_unique_keys_and_matching_placeholders() {
+        let mut seen = std::collections::HashSet::new();
+        for (source, english) in EN {
+            assert!(seen.insert(source), "terjemahan ganda: {}", source);
+            let holes = |text: &str| text.split('|').map(|form| form.matches("{}").count()).max().unwrap_or(0);
+            assert_eq!(holes(source), holes(english), "jumlah {{}} berbeda: {}", source);
+        }
+    }
+
+    #[test]
+    fn untranslated_text_falls_back_to_source() {
+        assert_eq!(Locale::En.t("Anda menangani percakapan ini"), "You are handling this conversation");
+        assert_eq!(Locale::Id.t("Anda menangani percakapan ini"), "Anda menangani percakapan ini");
+        assert_eq!(Locale::En.t("teks tanpa terjemahan"), "teks tanpa terjemahan");
+    }
+
+    #[test]
+    fn fill_and_plural_forms() {
+        assert_eq!(fill("{} dari {}", &[&1, &"dua"]), "1 dari dua");
+        assert_eq!(fill("{} dan {}", &[&1]), "1 dan ");
+        assert_eq!(Locale::Id.n("{} jam", 3, &[]), "3 jam");
+        assert_eq!(Locale::En.n("{} jam", 1, &[]), "1 hour");
+        assert_eq!(Locale::En.n("{} jam", 3, &[]), "3 hours");
+    }
+}
@@ -0,0 +1,109 @@
+// src/ice.rs
+// Pemeriksaan konektivitas WebRTC sebelum panggilan/transfer file: buat RTCPeerConnection dengan
+// server STUN/TURN dari konfigurasi, kumpulkan kandidat ICE, lalu lihat jenis kandidat yang muncul.
+// "srflx" berarti STUN menjawab (alamat publik diketahui); "relay" berarti TURN bisa dipakai.
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gloo_events::EventListener;
+use gloo_timers::future::TimeoutFuture;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{RtcConfiguration, RtcIceGatheringState, RtcIceServer, RtcPeerConnection, RtcPeerConnectionIceEvent, RtcSessionDescriptionInit};
+
+use crate::protocol::IceServer;
+
+const GATHER_TIMEOUT_MS: u32 = 8_000;
+const POLL_INTERVAL_MS: u32 = 200;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IceCheckReport {
+    pub host: usize,  // Alamat lokal
+    pub srflx: usize, // Alamat publik dari STUN
+    pub relay: usize, // Alamat relay dari TURN
+    pub timed_out: bool, // Pengumpulan kandidat belum selesai saat batas waktu
+}
+
+impl IceCheckReport {
+    // Ringkasan untuk user; `turn_configured` membedakan "TURN gagal" dari "TURN tidak dipasang"
+    pub fn verdict(&self, turn_configured: bool) -> &'static str {
+        match (self.relay > 0, self.srflx > 0) {
+            (true, _) => "TURN berfungsi: panggilan dan transfer file bisa lewat relay bila koneksi langsung gagal.",
+            (false, true) if turn_configured => "STUN berfungsi, tetapi server TURN tidak menjawab. Koneksi di balik NAT ketat bisa gagal.",
+            (false, true) => "STUN berfungsi. Tanpa TURN, koneksi di balik NAT ketat bisa gagal.",
+            (false, false) => "Server STUN/TURN tidak terjangkau; hanya koneksi di jaringan lokal yang mungkin berhasil.",
+        }
+    }
+}
+
+fn rtc_configuration(servers: &[IceServer]) -> RtcConfiguration {
+    let list = js_sys::Array::new();
+    for server in servers {
+        let entry = RtcIceServer::new();
+        let urls: js_sys::Array = server.urls.iter().map(|url| JsValue::from_str(url)).collect();
+        entry.set_urls(&urls);
+        if let Some(username) = &server.username {
+            entry.set_username(username);
+        }
+        if let Some(credential) = &server.credential {
+            entry.set_credential(credential);
+        }
+        list.push(&entry);
+    }
+    let config = RtcConfiguration::new();
+    config.set_ice_servers(&list);
+    config
+}
+
+pub async fn check(servers: &[IceServer]) -> Result<IceCheckReport, String> {
+    let pc = RtcPeerConnection::new_with_configuration(&rtc_configuration(servers)).map_err(|e| format!("{:?}", e))?;
+    let candidates = Rc::new(RefCell::new(Vec::new()));
+    let _listener = {
+        let candidates = candidates.clone();
+        EventListener::new(&pc, "icecandidate", move |event| {
+            let candidate = event.dyn_ref::<RtcPeerConnectionIceEvent>().and_then(RtcPeerConnectionIceEvent::candidate);
+            if let Some(candidate) = candidate {
+                candidates.borrow_mut().push(candidate.candidate());
+            }
+        })
+    };
+    let _channel = pc.create_data_channel("ice-check"); // Tanpa media/data channel tidak ada kandidat yang dikumpulkan
+    let result = gather(&pc).await;
+    pc.close();
+    let timed_out = result?;
+
+    let mut report = IceCheckReport { timed_out, ..IceCheckReport::default() };
+    for candidate in candidates.borrow().iter() {
+        match candidate_type(candidate) {
+            Some("host") => report.host += 1,
+            Some("srflx") | Some("prflx") => report.srflx += 1,
+            Some("relay") => report.relay += 1,
+            _ => {}
+        }
+    }
+    Ok(report)
+}
+
+// Mengembalikan true jika batas waktu habis sebelum pengumpulan kandidat selesai
+async fn gather(pc: &RtcPeerConnection) -> Result<bool, String> {
+    let offer = JsFuture::from(pc.create_offer()).await.map_err(|e| format!("{:?}", e))?;
+    JsFuture::from(pc.set_local_description(offer.unchecked_ref::<RtcSessionDescriptionInit>()))
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+    let mut waited = 0;
+    while pc.ice_gathering_state() != RtcIceGatheringState::Complete {
+        if waited >= GATHER_TIMEOUT_MS {
+            return Ok(true);
+        }
+        TimeoutFuture::new(POLL_INTERVAL_MS).await;
+        waited += POLL_INTERVAL_MS;
+    }
+    Ok(false)
+}
+
+// "candidate:1 1 udp 2122260223 192.168.1.2 54321 typ host ..." -> "host"
+fn candidate_type(candidate: &str) -> Option<&str> {
+    let mut parts = candidate.split_whitespace();
+    parts.find(|part| *part == "typ")?;
+    parts.next()
+}
@@ -0,0 +1,77 @@
+// src/ice_panel.rs
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+use crate::i18n::use_locale;
+use crate::ice::{self, IceCheckReport};
+use crate::protocol::IceServer;
+
+#[derive(Properties, PartialEq)]
+pub struct IcePanelProps {
+    pub servers: Vec<IceServer>,
+    pub from_server: bool, // false = default bawaan klien karena server belum mengirim konfigurasi
+}
+
+#[derive(Clone, PartialEq)]
+enum CheckState {
+    Idle,
+    Running,
+    Done(Result<IceCheckReport, String>),
+}
+
+// Daftar STUN/TURN yang dipakai dan uji konektivitas sebelum memakai fitur WebRTC
+#[function_component(IcePanel)]
+pub fn ice_panel(props: &IcePanelProps) -> Html {
+    let locale = use_locale();
+    let state = use_state(|| CheckState::Idle);
+    let turn_configured = props.servers.iter().any(IceServer::is_turn);
+
+    let on_check = {
+        let state = state.clone();
+        let servers = props.servers.clone();
+        Callback::from(move |_| {
+            state.set(CheckState::Running);
+            let state = state.clone();
+            let servers = servers.clone();
+            spawn_local(async move {
+                state.set(CheckState::Done(ice::check(&servers).await));
+            });
+        })
+    };
+
+    html! {
+        <details class="settings-panel ice-panel">
+            <summary>{ locale.t("Jaringan panggilan (STUN/TURN)") }</summary>
+            <ul class="ice-servers">
+                { for props.servers.iter().map(|server| html! {
+                    <li>
+                        <code>{ server.urls.join(", ") }</code>
+                        if server.username.is_some() {
+                            <span class="settings-hint">{ " · " }{ locale.t("dengan kredensial") }</span>
+                        }
+                    </li>
+                }) }
+            </ul>
+            if !props.from_server {
+                <p class="settings-hint">{ locale.t("Server belum mengirim konfigurasi; memakai STUN publik bawaan tanpa TURN.") }</p>
+            }
+            <button onclick={on_check} disabled={*state == CheckState::Running}>{ locale.t("Uji konektivitas") }</button>
+            { match &*state {
+                CheckState::Idle => html! {},
+                CheckState::Running => html! { <p class="settings-hint">{ locale.t("Mengumpulkan kandidat ICE…") }</p> },
+                CheckState::Done(Ok(report)) => html! {
+                    <>
+                        <p>{ locale.t(report.verdict(turn_configured)) }</p>
+                        <p class="settings-hint">
+                            { locale.f("Kandidat: {} lokal, {} STUN, {} relay", &[&report.host, &report.srflx, &report.relay]) }
+                            if report.timed_out {
+                                { " " }{ locale.t("(batas waktu habis sebelum selesai)") }
+                            }
+                        </p>
+                    </>
+                },
+                CheckState::Done(Err(e)) => html! { <p class="field-error">{ locale.f("Browser menolak membuat koneksi WebRTC: {}", &[e]) }</p> },
+            } }
+        </details>
+    }
+}
@@ -0,0 +1,125 @@
+// src/imaging.rs
+// Kompresi gambar di browser: gambar di-decode ke canvas, diperkecil, lalu di-encode ulang
+// sebagai WebP (atau JPEG jika browser tidak mendukung WebP) sebelum di-upload.
+use gloo_file::Blob;
+use js_sys::Promise;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, HtmlVideoElement, ImageBitmap, Url};
+
+const POSTER_MAX_DIMENSION: f64 = 320.0;
+
+// Hasil kompresi yang siap di-upload
+pub struct CompressedImage {
+    pub blob: Blob,
+    pub mime: String,
+    pub name: String,
+}
+
+// Format yang layak dikompres ulang; GIF dilewati agar animasinya tidak hilang
+pub fn is_compressible(mime: &str) -> bool {
+    matches!(mime, "image/jpeg" | "image/png" | "image/webp" | "image/bmp")
+}
+
+pub async fn compress_image(file: &web_sys::File, max_dimension: u32, quality: f64) -> Result<CompressedImage, String> {
+    let window = web_sys::window().ok_or("window tidak tersedia")?;
+    let bitmap: ImageBitmap = JsFuture::from(window.create_image_bitmap_with_blob(file).map_err(js_err)?)
+        .await
+        .map_err(js_err)?
+        .unchecked_into();
+
+    let (width, height) = (bitmap.width(), bitmap.height());
+    let scale = (f64::from(max_dimension) / f64::from(width.max(height))).min(1.0);
+    let (target_w, target_h) = ((f64::from(width) * scale).round() as u32, (f64::from(height) * scale).round() as u32);
+
+    let document = window.document().ok_or("document tidak tersedia")?;
+    let canvas: HtmlCanvasElement = document.create_element("canvas").map_err(js_err)?.unchecked_into();
+    canvas.set_width(target_w);
+    canvas.set_height(target_h);
+    let context: CanvasRenderingContext2d = canvas
+        .get_context("2d")
+        .map_err(js_err)?
+        .ok_or("canvas 2d tidak tersedia")?
+        .unchecked_into();
+    context
+        .draw_image_with_image_bitmap_and_dw_and_dh(&bitmap, 0.0, 0.0, f64::from(target_w), f64::from(target_h))
+        .map_err(js_err)?;
+    bitmap.close();
+
+    // Browser tanpa encoder WebP diam-diam menghasilkan PNG, jadi jatuh ke JPEG
+    let mut blob = canvas_to_blob(&canvas, "image/webp", quality).await?;
+    if blob.type_() != "image/webp" {
+        blob = canvas_to_blob(&canvas, "image/jpeg", quality).await?;
+    }
+    let mime = blob.type_();
+    let extension = if mime == "image/webp" { "webp" } else { "jpg" };
+    let file_name = file.name();
+    let stem = file_name.rsplit_once('.').map_or(file_name.as_str(), |(stem, _)| stem);
+    Ok(CompressedImage { name: format!("{}.{}", stem, extension), mime, blob: Blob::from(blob) })
+}
+
+// Ambil frame awal video sebagai thumbnail JPEG kecil (data URL) untuk poster pemutar
+pub async fn video_poster(file: &web_sys::File) -> Result<String, String> {
+    let document = web_sys::window().and_then(|w| w.document()).ok_or("document tidak tersedia")?;
+    let video: HtmlVideoElement = document.create_element("video").map_err(js_err)?.unchecked_into();
+    let url = Url::create_object_url_with_blob(file).map_err(js_err)?;
+    video.set_muted(true);
+    video.set_preload("auto");
+    video.set_src(&url);
+
+    let result = async {
+        wait_for(&video, |video, resolve, reject| {
+            video.set_onloadeddata(Some(resolve));
+            video.set_onerror(Some(reject));
+        })
+        .await?;
+        // Frame tepat di 0 detik sering hitam, ambil sedikit setelahnya
+        video.set_current_time((video.duration() / 2.0).min(0.5));
+        wait_for(&video, |video, resolve, _| video.set_onseeked(Some(resolve))).await?;
+
+        let (width, height) = (f64::from(video.video_width()), f64::from(video.video_height()));
+        if width == 0.0 || height == 0.0 {
+            return Err("video tidak punya track gambar".to_string());
+        }
+        let scale = (POSTER_MAX_DIMENSION / width.max(height)).min(1.0);
+        let canvas: HtmlCanvasElement = document.create_element("canvas").map_err(js_err)?.unchecked_into();
+        canvas.set_width((width * scale).round() as u32);
+        canvas.set_height((height * scale).round() as u32);
+        let context: CanvasRenderingContext2d = canvas
+            .get_context("2d")
+            .map_err(js_err)?
+            .ok_or("canvas 2d tidak tersedia")?
+            .unchecked_into();
+        context
+            .draw_image_with_html_video_element_and_dw_and_dh(&video, 0.0, 0.0, width * scale, height * scale)
+            .map_err(js_err)?;
+        canvas.to_data_url_with_type_and_encoder_options("image/jpeg", &JsValue::from_f64(0.7)).map_err(js_err)
+    }
+    .await;
+
+    let _ = Url::revoke_object_url(&url);
+    result
+}
+
+// Tunggu satu event elemen media; `attach` memasang fungsi resolve/reject sebagai handler
+async fn wait_for(
+    video: &HtmlVideoElement,
+    attach: impl Fn(&HtmlVideoElement, &js_sys::Function, &js_sys::Function),
+) -> Result<(), String> {
+    let promise = Promise::new(&mut |resolve, reject| attach(video, &resolve, &reject));
+    JsFuture::from(promise).await.map(|_| ()).map_err(|_| "gagal memuat video".to_string())
+}
+
+async fn canvas_to_blob(canvas: &HtmlCanvasElement, mime: &str, quality: f64) -> Result<web_sys::Blob, String> {
+    let mut result = Ok(());
+    let promise = Promise::new(&mut |resolve, _reject| {
+        result = canvas.to_blob_with_type_and_encoder_options(&resolve, mime, &JsValue::from_f64(quality));
+    });
+    result.map_err(js_err)?;
+    let value = JsFuture::from(promise).await.map_err(js_err)?;
+    value.dyn_into::<web_sys::Blob>().map_err(|_| "gagal meng-encode gambar".to_string())
+}
+
+fn js_err(value: JsValue) -> String {
+    value.as_string().unwrap_or_else(|| format!("{:?}", value))
+}
@@ -1,309 +1,4064 @@
 // src/lib.rs
-use serde::{Deserialize, Serialize};
+pub use chat_protocol::ChatMessage;
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-pub struct ChatMessage {
-    pub username: String,
-    pub text: String,
-    pub timestamp: Option<String>, // Server mungkin menambahkan ini
-}
+use std::rc::Rc;
 
 use yew::prelude::*;
-use gloo_net::websocket::{futures::WebSocket, Message as WsMessage, WebSocketError};
 use wasm_bindgen_futures::spawn_local;
-use futures_util::{StreamExt, SinkExt, stream::SplitSink, stream::SplitStream};
-use web_sys::HtmlInputElement; // Untuk mendapatkan nilai dari input field
+use base64::Engine as _;
+use web_sys::{HtmlInputElement, HtmlSelectElement, ScrollBehavior}; // Untuk mendapatkan nilai dari input field
+use gloo_timers::callback::{Interval, Timeout};
+
+mod activity;
+#[cfg(feature = "worker")]
+mod agent;
+mod appearance;
+mod auth;
+mod bidi;
+mod canned;
+mod chat_view;
+mod client;
+mod codec;
+mod clock;
+mod commands;
+mod components;
+mod config;
+mod date_header;
+mod device;
+mod direct;
+#[cfg(feature = "demo-server")]
+mod demo;
+mod directory;
+#[cfg(feature = "crypto")]
+mod e2e;
+#[cfg(feature = "crypto")]
+mod e2e_panel;
+mod error;
+mod exif;
+mod gallery;
+mod handle;
+mod history;
+mod i18n;
+mod ice;
+mod ice_panel;
+mod imaging;
+mod maintenance;
+mod media;
+mod members;
+mod members_panel;
+mod mentions;
+mod notes;
+mod notify;
+mod online_panel;
+mod outbox;
+mod palette;
+#[cfg(feature = "perf")]
+mod perf;
+mod plugins;
+mod presence;
+mod privacy_panel;
+pub mod protocol;
+#[cfg(feature = "dev")]
+mod receipts;
+mod read_marks;
+mod room;
+mod rooms_panel;
+mod schedule;
+mod script;
+mod send_guard;
+mod settings;
+mod sessions_panel;
+mod settings_panel;
+mod state;
+mod stats;
+mod stats_panel;
+mod status;
+mod status_panel;
+mod storage;
+mod support;
+mod support_panel;
+mod store;
+mod theme;
+mod time;
+#[cfg(feature = "dev")]
+mod timetravel;
+mod tooltip;
+mod twofactor;
+mod tour;
+mod translate;
+mod typing;
+mod unread;
+mod uploads;
+mod virtual_list;
+mod validation;
+mod vault;
+mod voice;
+mod widgets;
+use activity::{ActivityMonitor, PageVisibility, WindowFocus};
+use auth::{AuthState, LoginScreen};
+pub use canned::CannedResponse;
+pub use chat_view::{Composer, ConnectionBanner, MessageItem, MessageViewport, RoomForm, RulesGate, SourceModal, UsernameForm, VaultLock};
+#[cfg(feature = "worker")]
+pub use agent::register_worker;
+pub use client::{ChatClient, ChatTransport, ClientEvent, FrameStream, WebSocketTransport};
+use clock::ServerClock;
+use codec::Encoding;
+use commands::{CommandGroup, CommandHint, PaletteAction, PaletteItem, SlashInvocation};
+use config::ChatConfig;
+use date_header::TopEntryObserver;
+#[cfg(feature = "crypto")]
+use e2e_panel::{E2ePanel, PeerRow};
+use error::{AuthError, Recovery};
+pub use error::{ChatError, TransportError};
+use gallery::{AttachmentGallery, GalleryItem};
+use history::LocalHistory;
+use i18n::{tr, trf, trn};
+pub use i18n::{use_locale, Locale};
+use maintenance::Maintenance;
+use notes::{NoteItem, NotesPanel};
+pub use handle::{FrameSink, Receipt, SendError};
+pub use plugins::{CustomEvent, PluginRegistry};
+use plugins::CustomSend;
+use ice_panel::IcePanel;
+use members::MemberList;
+use members_panel::{MemberRow, MembersPanel};
+use mentions::{MentionSearch, MentionStep};
+use notify::{SnoozeDuration, Snoozes};
+use online_panel::{OnlinePanel, OnlineRow};
+use outbox::{Outbox, QueuedMessage};
+use palette::CommandPalette;
+use privacy_panel::PrivacyPanel;
+use room::{RecentRoom, RoomState};
+use rooms_panel::{RoomRow, RoomsPanel};
+use settings::Settings;
+use sessions_panel::SessionsPanel;
+use settings_panel::SettingsPanel;
+use stats::SessionStats;
+use stats_panel::StatsPanel;
+use support::SupportQueue;
+use support_panel::SupportPanel;
+use status::StatusDraft;
+use script::{Script, TextProfile};
+use send_guard::SendGuard;
+pub use protocol::{Attachment, BotCommand, ButtonStyle, Capability, ClientCommand, CommandArg, CustomStatus, Interaction, MessageComponent, PresenceEvent, SelectOption, JoinFailure, Pseudonym, RoomEvent, Rsvp, ServerEvent, SessionInfo, UserMatch, UserStatus};
+use protocol::{NoteEntry, Operator, UploadChunkFrame, HISTORY_PAGE, NOTES_NAMESPACE};
+pub use state::{reduce, Action, ChatState};
+pub use store::{Delivery, MessageEntry, MessageStore, StoredMessage};
+pub use theme::{use_theme, Theme, ThemeContext};
+use theme::{ThemeProvider, ThemeToggle};
+use tooltip::Tooltip;
+#[cfg(feature = "dev")]
+use timetravel::TimeTravel;
+#[cfg(feature = "dev")]
+use receipts::ReceiptLog;
+use twofactor::{Enrollment, TwoFactorCode, TwoFactorPrompt, TwoFactorSetup, TwoFactorStep};
+use tour::{Tour, TourStep};
+use translate::{Translation, Translations};
+use typing::TypingIndicators;
+use unread::{ScrollFollow, UnreadTitle};
+use uploads::{PreparedFile, UploadManager, UploadStatus};
+use virtual_list::VirtualList;
+pub use protocol::{UsernameError, UsernameRules};
+pub use widgets::{use_chat_store, use_chat_websocket, ChatHandle, ChatProvider, ChatRoom, ChatSocketConfig, ChatStore, MessageInput, MessageList, PresenceList};
 
-const WEBSOCKET_URL: &str = "ws://127.0.0.1:8080/ws"; // Ganti dengan URL server JS Anda
+const DEFAULT_BACKFILL_LIMIT: usize = 200; // Batas pesan yang otomatis dimuat ulang setelah reconnect
+const LATENCY_PROBE_INTERVAL_MS: u32 = 30_000; // TimeSync berkala untuk statistik latensi
+const IDLE_CHECK_INTERVAL_MS: u32 = 15_000;
+const TIMESTAMP_REFRESH_MS: u32 = 60_000; // Re-render berkala agar "5 menit lalu" tetap benar
+const IDLE_PAUSE_SECS: u64 = 300; // Tanpa interaksi selama ini, pekerjaan berkala yang hanya untuk tampilan dihentikan
+const LOAD_OLDER_PX: i32 = 300; // Riwayat lama dimuat saat daftar digulir sedekat ini ke atas
+const OVERSCAN_PX: f64 = 1200.0; // Entry di luar viewport yang tetap dirender, agar scroll cepat tidak menampakkan area kosong
+const LITE_OVERSCAN_PX: f64 = 300.0; // Overscan di mode ringan
+const LITE_RENDER_BATCH_MS: u32 = 500; // Di mode ringan pesan masuk dirender paling sering sekali per jeda ini
+
+// Bagian tulis WebSocket yang bisa dipakai bersama oleh beberapa task pengirim
 
 pub enum Msg {
     Connect, // Pesan untuk memulai koneksi WebSocket
-    SetWsWrite(Option<SplitSink<WebSocket, WsMessage>>), // Menyimpan bagian tulis dari WebSocket
-    SetWsRead(Option<SplitStream<WebSocket>>), // Menyimpan bagian baca (disimpan untuk referensi, tapi task akan membacanya)
-    WsReadTaskStarted, // Konfirmasi task pembacaan WS telah dimulai
-    ConnectionFailed,
-    MessageReceived(ChatMessage),
+    Client(ClientEvent), // Event koneksi, pesan chat dan frame protokol dari ChatClient
+    ToggleRepeats(usize), // Buka/tutup rangkaian pesan duplikat pada index tertentu
+    JumpToMessage(usize), // Gulir ke entry tertentu (dari galeri lampiran) dan sorot sebentar
+    JumpToMention(MentionStep), // Ke mention sebelumnya/berikutnya dari entry yang sedang disorot
+    TopEntryChanged(Option<usize>), // Entry teratas yang terlihat saat daftar pesan digulir
+    #[cfg(feature = "dev")]
+    ShowSource(String), // Tampilkan payload mentah sebuah pesan di modal
+    #[cfg(feature = "dev")]
+    CloseSource,
+    #[cfg(feature = "dev")]
+    TimeTravelBack,
+    #[cfg(feature = "dev")]
+    TimeTravelForward,
+    #[cfg(feature = "dev")]
+    TimeTravelJump(usize),
+    #[cfg(feature = "dev")]
+    TimeTravelLive,
+    #[cfg(feature = "dev")]
+    ToggleReceipts, // Tampilkan/sembunyikan catatan urutan dan waktu tiba di tiap pesan
+    #[cfg(feature = "demo-server")]
+    RunScenario(demo::Scenario), // Picu skenario di server demo (banjir pesan, restart, dst.)
     UpdateInput(String),
     SendMessage,
     SetUsername(String),
     UpdateUsernameInput(String),
-    Error(String), // Untuk menampilkan error umum
+    UpdateRoomInput(String),
+    UpdateRoomPasswordInput(String),
+    UpdateRoomTtl(Option<u64>), // TTL untuk room sementara yang akan dibuat; None = room biasa
+    UpdateRoomFeedbackBox(bool), // Room yang akan dibuat adalah kotak saran anonim
+    JoinRoom,
+    CreateRoom,
+    SwitchRoom(String), // Tampilkan room lain yang sudah diikuti; tidak ada perintah ke server
+    OpenDirect(String), // Buka tab pesan langsung dengan user id ini
+    CloseDirect, // Kembali ke daftar pesan room
+    SetStatus(UserStatus),       // Status yang terlihat user lain di daftar online
+    SetCustomStatus(Option<StatusDraft>), // None = hapus status kustom
+    ClaimConversation(String),   // Ambil percakapan tamu dari antrean dukungan
+    ReleaseConversation(String), // Kembalikan percakapan tamu ke antrean
+    InsertCanned(String), // Isi input dengan balasan cepat bernama ini, placeholder sudah diganti
+    LeaveRoom(String),
+    AcceptRules, // Setujui aturan room saat ini agar bisa mulai mengirim pesan
+    AttachFile(web_sys::File), // File dipilih dari input lampiran
+    StartUpload(PreparedFile), // File siap di-upload (setelah kompresi jika ada)
+    UploadChunkRead { upload_id: String, offset: u64, data: Vec<u8> },
+    UploadChunkFailed { upload_id: String, error: String },
+    CancelUpload(String),
+    RetryUpload(String),
+    SetDropActive(bool), // File sedang diseret di atas jendela chat
+    UpdateSettings(Settings),
+    UpdatePrivacy(settings::Privacy),
+    RequestExport,
+    DeleteAccount, // Minta konfirmasi lalu kirim DeleteAccount ke server
+    ClearHistory,  // Hapus riwayat pesan yang tersimpan lokal dan yang sedang ditampilkan
+    UpdateVaultInput(String),
+    SetupVault,  // Aktifkan enkripsi cache lokal dengan passphrase di input
+    UnlockVault, // Buka kunci chat dengan passphrase di input
+    VaultReady(vault::VaultKey, Vec<QueuedMessage>, Vec<ChatMessage>), // Kunci siap; berisi outbox dan riwayat lokal yang sudah didekripsi
+    VaultFailed(String),
+    LockVault,    // Buang kunci dari memori
+    DisableVault, // Matikan enkripsi dan simpan ulang data sebagai teks biasa
+    CheckIdle,    // Periksa berkala apakah chat perlu dikunci otomatis
+    VisibilityChanged(bool), // true = tab disembunyikan
+    WindowFocusChanged(bool), // true = jendela fokus lagi
+    MessagesScrolled, // Event scroll ul.messages
+    ScrollToNewest, // Tombol "N pesan baru"
+    BatteryChecked(bool), // true = baterai lemah dan tidak diisi
+    FlushRender, // Jeda batch render mode ringan selesai
+    MessagesMeasured, // Tinggi entry yang dirender berubah; jendela render dihitung ulang
+    LoadOlderHistory, // Daftar pesan room digulir ke dekat atas
+    RefreshTimestamps,
+    ProbeLatency,
+    RefreshStats,
+    RefreshIceServers, // Kredensial TURN hampir kedaluwarsa
+    ListSessions,
+    RevokeSession(String),
+    StartTwoFactorSetup,
+    ConfirmTwoFactorSetup(String),
+    StartDisableTwoFactor,
+    SubmitTwoFactor(TwoFactorCode), // Kode untuk login (Challenge) atau untuk mematikan 2FA
+    CancelTwoFactor,
+    Login(String, bool), // Token dari layar login dan pilihan "ingat di perangkat ini"
+    Authenticated { user_id: Option<String>, name: Option<String> },
+    AuthFailed(Option<String>),
+    SystemAppearanceChanged, // Preferensi kontras/animasi/tema di sistem berubah
+    SetTheme(Option<Theme>), // Dari ThemeToggle; disimpan di pengaturan
+    SendCustom(CustomSend),  // Dari PluginRegistry::send
+    SaveNote(Option<String>, String), // Catatan room disunting; None = catatan baru
+    DeleteNote(String),
+    #[cfg(feature = "crypto")]
+    SetE2e(bool), // Sakelar enkripsi end-to-end room saat ini
+    #[cfg(feature = "crypto")]
+    VerifyE2ePeer(String), // Percayai kunci publik baru anggota ini di room saat ini
+    SendQueued(usize),    // Kirim satu pesan dari baki "belum terkirim"
+    DiscardQueued(usize), // Buang satu pesan dari baki "belum terkirim"
+    FetchMissed, // Muat sisa pesan yang terlewat selama offline
+    StartTour,
+    OpenPalette,
+    ClosePalette,
+    RunPaletteAction(PaletteAction),
+    FinishTour, // Tur selesai/dilewati; ditandai di localStorage agar tidak muncul lagi
+    SetSlowMode(u32), // Moderator mengubah slow mode room saat ini (0 = mati)
+    SetEmbedsDisabled(bool), // Moderator mematikan/menyalakan embed gambar dan GIF
+    SetRoomLanguage(Option<String>), // Moderator menentukan bahasa utama room
+    Translate { message_id: String, source: String }, // Terjemahkan pesan ini ke bahasa user
+    ShowOriginal(String),            // Tampilkan lagi teks asli pesan yang sudah diterjemahkan
+    ToggleAutoTranslate(bool),       // Terjemahan otomatis untuk room saat ini
+    ExpireMessages, // Timer pesan sementara yang paling cepat kedaluwarsa sudah jatuh tempo
+    Tick, // Detak tiap detik untuk hitung mundur room sementara dan cooldown slow mode
+    MaintenanceEnded, // Waktu `until` dari ServerEvent::Maintenance tercapai
+    TypingExpired, // Indikator mengetik yang paling cepat habis sudah jatuh tempo
+    Error(ChatError), // Untuk menampilkan error umum
+    DismissError,
+    Rsvp(String, Rsvp), // Jawab undangan acara (id acara, jawaban)
+    Interact(Interaction), // Tombol/menu di pesan bot dipakai
+    InteractionTimedOut(String), // Bot tidak membalas interaksi pada pesan ini
+    SnoozeRoom(String, Option<SnoozeDuration>), // None = aktifkan lagi notifikasi room
+    EnableNotifications, // Minta izin notifikasi browser (harus dari klik user)
+    SearchMembers(String), // Isi kotak pencarian anggota berubah
+    SearchMentions(String), // Tanyakan prefix "@..." ke pencarian user server (setelah debounce)
+    CompleteMention(String), // Ganti "@..." yang sedang diketik dengan handle ini
+    CompleteCommand(String), // Ganti "/..." yang sedang diketik dengan nama perintah ini
+    LoadMoreMembers, // Sidebar anggota digulir ke bawah, atau pencarian selesai di-debounce
+    ChatSendFailed(String, SendError), // Id klien pesan; sudah dicoba ulang sekali oleh FrameWriter
+    RetryMessage(String),  // Kirim ulang pesan gagal dengan id klien ini
+    DiscardUnsent(String), // Buang pesan gagal dari daftar
+}
+
+
+#[derive(Properties, PartialEq, Clone, Default)]
+pub struct AppProps {
+    // URL server WebSocket, mis. "wss://chat.example.com/ws". Kosong = host halaman ini dengan path /ws.
+    #[prop_or_default]
+    pub ws_url: Option<String>,
+    // Tanpa style bawaan: hanya class dan atribut data yang stabil (lihat STYLING.md) untuk di-style embedder
+    #[prop_or_default]
+    pub headless: bool,
+    // Token login (JWT atau token sesi) dari halaman induk. Tanpa ini dipakai token yang diingat, atau layar login.
+    #[prop_or_default]
+    pub auth_token: Option<String>,
+    // Tampilkan layar login sebelum chat bisa dipakai, tanpa menunggu server mengirim AuthRequired
+    #[prop_or_default]
+    pub require_auth: bool,
+    // Kirim token sebagai query `?token=` di URL WebSocket, bukan sebagai frame Authenticate
+    #[prop_or_default]
+    pub token_in_url: bool,
+    // Slot untuk halaman induk: branding di atas (menggantikan judul bawaan), teks hukum atau kontrol
+    // tambahan di bawah input, dan isi daftar pesan yang masih kosong
+    #[prop_or_default]
+    pub header: Option<Html>,
+    #[prop_or_default]
+    pub footer: Option<Html>,
+    #[prop_or_default]
+    pub empty_state: Option<Html>,
+    // Handler ServerEvent::Custom per namespace, sekaligus jalur kirim payload embedder (lihat plugins)
+    #[prop_or_default]
+    pub plugins: PluginRegistry,
+    // Aturan display name; samakan dengan [usernames] di chat-server, yang menolak pesan dengan nama lain
+    #[prop_or_default]
+    pub username_rules: UsernameRules,
 }
 
+// Nama komponen untuk embedder; `App` tetap dipakai oleh run_app
+pub type ChatWidget = App;
+
 pub struct App {
-    username: String,
+    user_id: String,  // Id akun lokal, tidak berubah saat ganti nama
+    username: String, // Display name saat ini
     username_input: String,
-    ws_write: Option<SplitSink<WebSocket, WsMessage>>,
-    messages: Vec<ChatMessage>,
+    username_error: Option<UsernameError>, // Hint validasi yang ditampilkan di bawah form username
+    client: ChatClient,
+    #[cfg(feature = "crypto")]
+    e2e: e2e::Keyring, // Juga dipegang callback langganan client untuk membuka pesan masuk
+    #[cfg(feature = "crypto")]
+    e2e_announced: std::collections::HashSet<String>, // Room yang kunci publiknya sudah diumumkan ulang setelah pesan terenkripsi pertama di koneksi ini
+    chat: ChatState, // Pesan, direktori user, presence dan room; hanya diubah lewat dispatch
+    #[cfg(feature = "dev")]
+    time_travel: TimeTravel,
+    #[cfg(feature = "dev")]
+    receipts: ReceiptLog,
+    #[cfg(feature = "dev")]
+    show_receipts: bool,
+    outbox: Outbox, // Pesan yang ditulis saat offline, disimpan di localStorage
+    history: LocalHistory, // Pesan room terbaru yang dimuat lagi setelah refresh
+    uploads: UploadManager,
+    drop_active: bool,
+    settings: Settings,
+    _appearance_watchers: Vec<gloo_events::EventListener>, // Listener media query sistem
+    export_pending: bool,
+    export_url: Option<String>, // Tautan arsip ekspor dari server
+    sessions: Option<Vec<SessionInfo>>, // Daftar perangkat yang login, dimuat saat diminta
+    two_factor_enabled: bool,
+    two_factor_step: Option<TwoFactorStep>,
+    two_factor_error: Option<String>,
+    auth: AuthState,
+    auth_token: Option<String>, // Token sesi ini; disimpan di localStorage hanya jika user memilih diingat
+    remember_token: bool,       // Simpan token setelah server menerimanya
+    vault_locked: bool,          // Enkripsi aktif tetapi kunci belum ada di memori
+    vault_unlocked: bool,        // Kunci vault sedang ada di memori
+    vault_input: String,
+    vault_busy: bool,            // Penurunan kunci (PBKDF2) sedang berjalan
+    vault_error: Option<String>,
+    activity: ActivityMonitor,
+    _idle_check: Interval,
+    _visibility: PageVisibility,
+    page_hidden: bool,
+    _window_focus: WindowFocus,
+    window_focused: bool,
+    scroll_follow: ScrollFollow,
+    unread_title: UnreadTitle, // Hitungan di judul tab selama tab tersembunyi atau tidak fokus
+    timestamp_refresh: Option<Interval>, // Mati selama tab tersembunyi atau user idle
+    lite_mode: bool, // Mode ringan aktif (pengaturan, atau otomatis di perangkat/baterai lemah)
+    low_battery: bool,
+    render_batch: Option<Timeout>, // Render pesan masuk yang sedang ditahan di mode ringan
+    virtual_list: VirtualList, // Hanya entry di sekitar viewport daftar pesan room yang dirender
+    pending_jump: Option<usize>, // Entry yang di-scroll setelah render berikutnya, mis. setelah jendela render digeser ke sana
+    older_request: Option<String>, // Room yang halaman riwayat lamanya sedang diminta
+    stats: SessionStats,
+    config: ChatConfig,
+    ice_refresh: Option<Timeout>, // Permintaan ulang IceServers sebelum kredensial TURN kedaluwarsa
+    _latency_probe: Interval,
     current_input: String,
-    error: Option<String>,
+    error: Option<ChatError>,
     is_connected: bool,
+    room_input: String,
+    room_password_input: String,
+    room_ttl: Option<u64>,
+    room_feedback_box: bool,
+    send_cooldown_until: Option<f64>, // Epoch millis; selama slow mode tombol kirim dinonaktifkan sampai waktu ini
+    ticker: Option<Interval>, // Hanya aktif saat ada hitung mundur yang perlu ditampilkan
+    maintenance: Option<Maintenance>, // Server sedang pemeliharaan: hanya-baca dan reconnect ditahan
+    clock: ServerClock,
+    expiry_timer: Option<(f64, Timeout)>, // Waktu kedaluwarsa (jam server) yang sedang ditunggu
+    typing: TypingIndicators,
+    typing_timer: Option<Timeout>,
+    operators_accepted: bool, // Server membalas Hello dengan Capability::Operators; baru setelah itu `operator` dikirim
+    read_sent: std::collections::HashMap<String, String>, // Id pesan terakhir yang dilaporkan lewat MarkRead, per room
+    send_guard: SendGuard,
+    backfill_limit: usize,
+    highlighted_entry: Option<usize>, // Entry yang dituju dari galeri atau navigasi mention
+    top_entry: Option<usize>, // Untuk chip tanggal; None jika tidak ada entry yang terlihat
+    top_entry_observer: Option<TopEntryObserver>, // Dibuat setelah render pertama, saat daftar pesan sudah ada di DOM
+    messages_ref: NodeRef,
+    show_tour: bool,
+    show_palette: bool,
+    recent_rooms: Vec<RecentRoom>, // Room yang pernah diikuti di sesi ini, terbaru dulu; untuk palet perintah dan daftar room
+    snoozes: Snoozes,
+    members: MemberList, // Anggota room saat ini yang sudah dimuat, per halaman
+    member_search: Option<Timeout>, // Debounce pencarian anggota
+    mention_search: MentionSearch,
+    mention_search_timer: Option<Timeout>,
+    mention_query: Option<String>, // Prefix "@..." terakhir yang dirender sarannya
+    bot_commands: Vec<BotCommand>, // Slash command bot di room saat ini, dari ServerEvent::Commands
+    pending_interactions: std::collections::HashMap<String, Timeout>, // Id pesan bot yang interaksinya belum dibalas
+    support: SupportQueue, // Antrean tamu saat mode agen dukungan aktif
+    my_status: UserStatus,
+    translations: Translations,
+    command_hints: Vec<CommandHint>, // Saran untuk "/..." yang sedang diketik
+    _palette_shortcut: Option<gloo_events::EventListener>, // Ctrl+K
+    _mention_shortcut: Option<gloo_events::EventListener>, // Alt+↑ / Alt+↓
+    username_ref: NodeRef, // Target sorotan tur pengenalan
+    room_ref: NodeRef,
+    input_ref: NodeRef,
+    settings_ref: NodeRef,
+    room_error: Option<String>, // Alasan gagal join, ditampilkan di form room
+    #[cfg(feature = "dev")]
+    source_view: Option<String>, // Payload yang sedang ditampilkan di modal "lihat sumber"
 }
 
 impl Component for App {
     type Message = Msg;
-    type Properties = ();
+    type Properties = AppProps;
 
     fn create(ctx: &Context<Self>) -> Self {
         ctx.link().send_message(Msg::Connect); // Memulai koneksi saat komponen dibuat
+        let user_id = directory::load_or_create_user_id();
+        let username = history::load_username().unwrap_or_else(|| String::from("Anonim")); // Default username
+        let mut chat = ChatState { me: user_id.clone(), ..ChatState::default() };
+        chat.directory.set(&user_id, &username);
+        let settings = Settings::load();
+        // Tampilkan lagi pesan dari sesi sebelumnya; `last_seen` ikut terisi sehingga Resume hanya meminta yang lebih baru
+        let history = LocalHistory::load(settings.history_limit);
+        let messages = history.messages().to_vec();
+        state::reduce(&mut chat, Action::HistoryReplayed { messages, total_missed: 0, now_ms: js_sys::Date::now() });
+        appearance::install_stylesheet(!ctx.props().headless);
+        ctx.props().plugins.attach(ctx.link().callback(Msg::SendCustom));
+        i18n::set(Locale::resolve(settings.locale));
+        appearance::apply(&settings);
+        appearance::set_page_hidden(activity::page_hidden());
+        let lite_mode = settings.lite_mode.resolve(device::is_low_end_hardware());
+        appearance::set_lite_mode(lite_mode);
+        let link = ctx.link().clone();
+        spawn_local(async move { link.send_message(Msg::BatteryChecked(device::battery_low().await)) });
+        let stats = SessionStats::new();
+        let client = default_client(stats.clone());
+        #[cfg(not(feature = "crypto"))]
+        client.subscribe(ctx.link().callback(Msg::Client));
+        #[cfg(feature = "crypto")]
+        let e2e = e2e::Keyring::load();
+        #[cfg(feature = "crypto")]
+        client.subscribe({
+            let e2e = e2e.clone();
+            ctx.link().callback(move |event| Msg::Client(e2e.open_event(event)))
+        });
+        let auth_token = ctx.props().auth_token.clone().or_else(auth::load_token);
         Self {
-            username: String::from("Anonim"), // Default username
+            user_id,
+            username,
             username_input: String::new(),
-            ws_write: None,
-            messages: Vec::new(),
+            username_error: None,
+            client,
+            #[cfg(feature = "crypto")]
+            e2e,
+            #[cfg(feature = "crypto")]
+            e2e_announced: std::collections::HashSet::new(),
+            chat,
+            #[cfg(feature = "dev")]
+            time_travel: TimeTravel::default(),
+            #[cfg(feature = "dev")]
+            receipts: ReceiptLog::default(),
+            #[cfg(feature = "dev")]
+            show_receipts: false,
+            outbox: Outbox::load(),
+            history,
+            uploads: UploadManager::default(),
+            drop_active: false,
+            settings,
+            _appearance_watchers: appearance::watch_system(ctx.link().callback(|_| Msg::SystemAppearanceChanged)),
+            export_pending: false,
+            export_url: None,
+            sessions: None,
+            two_factor_enabled: false,
+            two_factor_step: None,
+            two_factor_error: None,
+            auth: if ctx.props().require_auth && auth_token.is_none() { AuthState::LoginRequired { error: None } } else { AuthState::Anonymous },
+            auth_token,
+            remember_token: false,
+            vault_locked: vault::is_enabled(),
+            vault_unlocked: false,
+            vault_input: String::new(),
+            vault_busy: false,
+            vault_error: None,
+            activity: ActivityMonitor::new(),
+            _idle_check: {
+                let link = ctx.link().clone();
+                Interval::new(IDLE_CHECK_INTERVAL_MS, move || link.send_message(Msg::CheckIdle))
+            },
+            _visibility: PageVisibility::new(ctx.link().callback(Msg::VisibilityChanged)),
+            page_hidden: activity::page_hidden(),
+            _window_focus: WindowFocus::new(ctx.link().callback(Msg::WindowFocusChanged)),
+            window_focused: activity::window_focused(),
+            scroll_follow: ScrollFollow::default(),
+            unread_title: UnreadTitle::default(),
+            timestamp_refresh: (!activity::page_hidden()).then(|| {
+                let link = ctx.link().clone();
+                Interval::new(TIMESTAMP_REFRESH_MS, move || link.send_message(Msg::RefreshTimestamps))
+            }),
+            lite_mode,
+            low_battery: false,
+            render_batch: None,
+            virtual_list: VirtualList::new(if lite_mode { LITE_OVERSCAN_PX } else { OVERSCAN_PX }),
+            pending_jump: None,
+            older_request: None,
+            stats,
+            config: ChatConfig::new(ctx.props().ws_url.clone()),
+            ice_refresh: None,
+            _latency_probe: {
+                let link = ctx.link().clone();
+                Interval::new(LATENCY_PROBE_INTERVAL_MS, move || link.send_message(Msg::ProbeLatency))
+            },
             current_input: String::new(),
             error: None,
             is_connected: false,
+            room_input: String::new(),
+            room_password_input: String::new(),
+            room_ttl: None,
+            room_feedback_box: false,
+            send_cooldown_until: None,
+            ticker: None,
+            maintenance: None,
+            clock: ServerClock::default(),
+            expiry_timer: None,
+            typing: TypingIndicators::default(),
+            typing_timer: None,
+            operators_accepted: false,
+            read_sent: std::collections::HashMap::new(),
+            send_guard: SendGuard::default(),
+            backfill_limit: DEFAULT_BACKFILL_LIMIT,
+            highlighted_entry: None,
+            top_entry: None,
+            top_entry_observer: None,
+            messages_ref: NodeRef::default(),
+            show_tour: !tour::tour_completed(),
+            show_palette: false,
+            recent_rooms: Vec::new(),
+            snoozes: Snoozes::load(js_sys::Date::now()),
+            members: MemberList::default(),
+            member_search: None,
+            mention_search: MentionSearch::default(),
+            mention_search_timer: None,
+            mention_query: None,
+            bot_commands: Vec::new(),
+            pending_interactions: std::collections::HashMap::new(),
+            support: SupportQueue::default(),
+            my_status: UserStatus::default(),
+            translations: Translations::default(),
+            command_hints: Vec::new(),
+            _palette_shortcut: commands::listen_shortcut(ctx.link().callback(|_| Msg::OpenPalette)),
+            _mention_shortcut: mentions::listen_shortcut(ctx.link().callback(Msg::JumpToMention)),
+            username_ref: NodeRef::default(),
+            room_ref: NodeRef::default(),
+            input_ref: NodeRef::default(),
+            settings_ref: NodeRef::default(),
+            room_error: None,
+            #[cfg(feature = "dev")]
+            source_view: None,
         }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             Msg::Connect => {
-                let link = ctx.link().clone();
-                spawn_local(async move {
-                    match WebSocket::open(WEBSOCKET_URL) {
-                        Ok(ws_conn) => {
-                            link.send_message(Msg::SetWsWrite(Some(ws_conn.split().0))); // Kirim bagian tulis
-                            link.send_message(Msg::SetWsRead(Some(ws_conn.split().1))); // Kirim bagian baca
-                        }
-                        Err(e) => {
-                            link.send_message(Msg::Error(format!("Gagal terhubung ke WebSocket: {:?}", e)));
-                            link.send_message(Msg::ConnectionFailed);
-                        }
-                    }
-                });
+                if let Some(maintenance) = &self.maintenance {
+                    log::info!("Reconnect ditahan sampai pemeliharaan server selesai ({} detik lagi)", maintenance.remaining_secs());
+                    return false;
+                }
+                let url = match &self.auth_token {
+                    Some(token) if ctx.props().token_in_url => auth::url_with_token(&self.config.ws_url, token),
+                    _ => self.config.ws_url.clone(),
+                };
+                if let Err(error) = self.client.connect(&url) {
+                    return Component::update(self, ctx, Msg::Client(ClientEvent::Disconnected(error)));
+                }
                 false // Tidak perlu re-render UI segera
             }
-            Msg::SetWsWrite(ws_write_half) => {
-                self.ws_write = ws_write_half;
-                self.is_connected = self.ws_write.is_some();
+            Msg::Client(ClientEvent::Connected) => {
+                self.is_connected = true;
                 self.error = None; // Hapus error jika koneksi berhasil
+                self.stats.record_connected();
+                match self.auth_token.clone() {
+                    // Sesi baru dimulai setelah AuthResult; token di URL sudah diperiksa server saat handshake
+                    Some(token) => {
+                        if !ctx.props().token_in_url {
+                            self.send_command(ctx, &ClientCommand::Authenticate { token });
+                        }
+                        let from_login = matches!(self.auth, AuthState::Authenticating { from_login: true });
+                        self.auth = AuthState::Authenticating { from_login };
+                    }
+                    None if self.auth.needs_login() => {} // Menunggu token dari layar login
+                    None => self.start_session(ctx),
+                }
                 true // Re-render untuk update status koneksi
             }
-            Msg::SetWsRead(Some(ws_read_half)) => {
-                // Mulai task baru untuk membaca pesan dari WebSocket
-                let link = ctx.link().clone();
-                spawn_local(async move {
-                    let mut read_stream = ws_read_half;
-                    link.send_message(Msg::WsReadTaskStarted); // Konfirmasi task dimulai
-                    while let Some(msg_result) = read_stream.next().await {
-                        match msg_result {
-                            Ok(WsMessage::Text(text_data)) => {
-                                match serde_json::from_str::<ChatMessage>(&text_data) {
-                                    Ok(chat_msg) => {
-                                        link.send_message(Msg::MessageReceived(chat_msg));
-                                    }
-                                    Err(e) => {
-                                        link.send_message(Msg::Error(format!("Gagal parse pesan server: {}. Data: {}",e, text_data)));
-                                    }
-                                }
-                            }
-                            Ok(WsMessage::Bytes(_)) => {
-                                link.send_message(Msg::Error("Menerima pesan biner, tidak didukung.".to_string()));
-                            }
-                            Err(e) => {
-                                let err_msg = match e {
-                                    WebSocketError::ConnectionError => "Koneksi WebSocket error.".to_string(),
-                                    WebSocketError::ConnectionClose(close_event) => format!("Koneksi WebSocket ditutup: code={}, reason='{}'", close_event.code(), close_event.reason()),
-                                    WebSocketError::MessageSendError(_) => "Error mengirim pesan WebSocket.".to_string(), // Seharusnya tidak terjadi di read loop
-                                    _ => "Error WebSocket tidak diketahui.".to_string(),
-                                };
-                                link.send_message(Msg::Error(err_msg));
-                                link.send_message(Msg::ConnectionFailed); // Set status koneksi gagal
-                                break; // Keluar dari loop pembacaan
-                            }
+            Msg::Client(ClientEvent::Disconnected(error)) => {
+                log::error!("Koneksi terputus: {:?}", error);
+                if error == TransportError::HeartbeatTimeout {
+                    // Socket mati tanpa pernah ditutup; langsung buka koneksi baru
+                    ctx.link().send_message(Msg::Connect);
+                }
+                if self.maintenance.is_none() {
+                    self.error = Some(ChatError::Transport(error)); // Penutupan saat pemeliharaan sudah dijelaskan banner
+                }
+                self.is_connected = false;
+                self.stats.record_disconnected();
+                self.client.close(); // Reset write stream
+                self.uploads.pause_all();
+                self.members.cancel_request();
+                self.older_request = None;
+                self.typing.clear();
+                self.typing_timer = None;
+                self.read_sent.clear(); // Server mungkin dimulai ulang tanpa tanda dibaca yang lama
+                self.pending_interactions.clear(); // Balasan bot tidak akan datang lewat koneksi ini
+                #[cfg(feature = "crypto")]
+                self.e2e_announced.clear();
+                true // Re-render untuk update status koneksi
+            }
+            Msg::Client(ClientEvent::Error(error)) => Component::update(self, ctx, Msg::Error(error)),
+            Msg::Client(ClientEvent::Message { message, raw, encoding }) => {
+                self.ingest(ctx, *message, raw, encoding);
+                self.render_when_visible(ctx, true) // Re-render UI untuk menampilkan pesan baru
+            }
+            Msg::Client(ClientEvent::Server(event)) => self.handle_server_event(ctx, event),
+            Msg::Client(ClientEvent::Presence(event)) => {
+                // Pengirim yang terputus saat mengetik tidak perlu menunggu TTL
+                let stopped_typing = match &event {
+                    PresenceEvent::UserLeft { room, user_id } => self.typing.left(Some(room), user_id),
+                    PresenceEvent::Presence { user_id, online: false, .. } => self.typing.left(None, user_id),
+                    PresenceEvent::PresenceDigest { offline, .. } => offline.iter().fold(false, |stopped, user| self.typing.left(None, &user.user_id) | stopped),
+                    _ => false,
+                };
+                if stopped_typing {
+                    self.schedule_typing_expiry(ctx);
+                }
+                let changed = self.dispatch(event.into()) || stopped_typing;
+                self.render_when_visible(ctx, changed)
+            }
+            Msg::FetchMissed => {
+                let Some(gap) = &self.chat.missed_gap else { return false };
+                let room = Some(self.chat.current_name().to_string()).filter(|room| !room.is_empty());
+                let command = ClientCommand::FetchHistory { room, after: gap.after, before: gap.before };
+                self.send_command(ctx, &command);
+                false
+            }
+            Msg::ToggleRepeats(index) => self.dispatch(Action::RepeatsToggled(index)),
+            Msg::JumpToMessage(index) => {
+                self.scroll_follow.detach();
+                let target = web_sys::window()
+                    .and_then(|w| w.document())
+                    .and_then(|d| d.get_element_by_id(&entry_anchor(index)));
+                match target {
+                    Some(el) => {
+                        let options = web_sys::ScrollIntoViewOptions::new();
+                        options.set_behavior(if self.reduced_motion() { ScrollBehavior::Auto } else { ScrollBehavior::Smooth });
+                        el.scroll_into_view_with_scroll_into_view_options(&options);
+                    }
+                    None if index < self.shown().messages.len() => {
+                        // Entry di luar jendela render: geser jendelanya dulu, scroll setelah dirender
+                        if let Some(list) = self.messages_ref.cast::<web_sys::Element>() {
+                            self.virtual_list.scroll_to(&list, self.shown().messages.entries(), index);
                         }
+                        self.pending_jump = Some(index);
                     }
-                    // Jika loop berakhir, berarti koneksi tertutup dari sisi server atau ada error
-                    link.send_message(Msg::Error("Koneksi WebSocket terputus.".to_string()));
-                    link.send_message(Msg::ConnectionFailed);
-                });
-                false // Tidak perlu re-render UI segera karena task berjalan di background
+                    None => {}
+                }
+                self.highlighted_entry = Some(index);
+                true
+            }
+            Msg::MessagesMeasured => true,
+            Msg::LoadOlderHistory => {
+                if self.older_request.is_some() || self.chat.history_complete {
+                    return false;
+                }
+                // Entry pertama yang punya id; pesan lokal tanpa id tidak dikenal server
+                let Some(before_id) = self.chat.messages.entries().iter().find_map(|entry| entry.first.message.id.clone()) else { return false };
+                let room = self.chat.current_name().to_string();
+                let command = ClientCommand::HistoryRequest { room: Some(room.clone()).filter(|room| !room.is_empty()), before_id: Some(before_id), limit: HISTORY_PAGE };
+                if !self.send_command(ctx, &command) {
+                    return false;
+                }
+                self.older_request = Some(room);
+                true // Tampilkan penanda "memuat"
+            }
+            Msg::TopEntryChanged(top) => {
+                self.top_entry = top;
+                true
+            }
+            Msg::JumpToMention(step) => {
+                let target = mentions::step(self.shown().messages.mentions(), self.highlighted_entry, step);
+                match target {
+                    Some(index) => Component::update(self, ctx, Msg::JumpToMessage(index)),
+                    None => false,
+                }
             }
-            Msg::SetWsRead(None) => { /* Seharusnya tidak terjadi jika SetWsWrite berhasil */ false }
-            Msg::WsReadTaskStarted => {
-                log::info!("Task pembacaan WebSocket telah dimulai.");
+            #[cfg(feature = "dev")]
+            Msg::ShowSource(raw) => {
+                self.source_view = Some(raw);
+                true
+            }
+            #[cfg(feature = "dev")]
+            Msg::TimeTravelBack => self.time_travel.step_back(),
+            #[cfg(feature = "dev")]
+            Msg::TimeTravelForward => self.time_travel.step_forward(),
+            #[cfg(feature = "dev")]
+            Msg::TimeTravelJump(index) => self.time_travel.jump(index),
+            #[cfg(feature = "dev")]
+            Msg::TimeTravelLive => self.time_travel.go_live(),
+            #[cfg(feature = "demo-server")]
+            Msg::RunScenario(scenario) => {
+                demo::run_scenario(scenario);
                 false
             }
-            Msg::ConnectionFailed => {
-                self.is_connected = false;
-                self.ws_write = None; // Reset write stream
-                true // Re-render untuk update status koneksi
+            #[cfg(feature = "dev")]
+            Msg::ToggleReceipts => {
+                self.show_receipts = !self.show_receipts;
+                true
             }
-            Msg::MessageReceived(msg) => {
-                self.messages.push(msg);
-                true // Re-render UI untuk menampilkan pesan baru
+            #[cfg(feature = "dev")]
+            Msg::CloseSource => {
+                self.source_view = None;
+                true
             }
             Msg::UpdateInput(input) => {
                 self.current_input = input;
-                false // Tidak perlu re-render untuk setiap ketikan
+                self.announce_typing(ctx);
+                let hints_changed = self.refresh_command_hints();
+                let query = self.active_mention_query().map(|(_, prefix)| prefix.to_string());
+                if query == self.mention_query {
+                    return hints_changed; // Tidak perlu re-render untuk setiap ketikan
+                }
+                self.mention_search_timer = query.as_ref().filter(|prefix| self.mention_search.needs_fetch(prefix)).map(|prefix| {
+                    let (link, prefix) = (ctx.link().clone(), prefix.clone());
+                    Timeout::new(mentions::SEARCH_DEBOUNCE_MS, move || link.send_message(Msg::SearchMentions(prefix)))
+                });
+                self.mention_query = query;
+                true // Perbarui daftar saran @mention
+            }
+            Msg::SearchMentions(prefix) => {
+                self.mention_search_timer = None;
+                if self.mention_query.as_ref() != Some(&prefix) || !self.mention_search.needs_fetch(&prefix) {
+                    return false; // User sudah lanjut mengetik, atau hasilnya sudah ada
+                }
+                let command = self.mention_search.request(&prefix);
+                if !self.send_command(ctx, &command) {
+                    self.mention_search.cancel();
+                }
+                false
+            }
+            Msg::CompleteMention(handle) => {
+                let Some((at, _)) = self.active_mention_query() else { return false };
+                self.current_input = mentions::complete(&self.current_input, at, &handle);
+                self.mention_query = None;
+                self.mention_search_timer = None;
+                true
+            }
+            Msg::CompleteCommand(name) => {
+                self.current_input = format!("/{} ", name);
+                self.refresh_command_hints();
+                true
             }
             Msg::SendMessage => {
-                if let Some(ws_write) = &mut self.ws_write {
-                    if !self.current_input.is_empty() {
-                        let msg_to_send = ChatMessage {
-                            username: self.username.clone(),
-                            text: self.current_input.clone(),
-                            timestamp: None, // Server mungkin yang akan mengisi ini
-                        };
-                        match serde_json::to_string(&msg_to_send) {
-                            Ok(json_msg) => {
-                                let current_input_for_log = self.current_input.clone(); // Clone sebelum di-clear
-                                let link = ctx.link().clone(); // Clone link untuk task
-                                let ws_write_clone = ws_write; // Ini tricky, cara aman adalah tidak menyimpan ws_write di self secara mutlak atau pakai Rc<RefCell<>>
-                                                              // Untuk contoh ini, kita spawn task baru dan berharap ws_write masih valid
-                                                              // Dalam aplikasi riil, penanganan state koneksi WS perlu lebih robust
-                                // Untuk gloo-net, send adalah async, jadi perlu spawn_local
-                                let future = ws_write_clone.send(WsMessage::Text(json_msg));
-                                spawn_local(async move {
-                                    if let Err(e) = future.await {
-                                         link.send_message(Msg::Error(format!("Gagal mengirim pesan: {:?}", e)));
-                                    } else {
-                                         log::info!("Pesan terkirim: {}", current_input_for_log);
-                                    }
-                                });
-                            }
-                            Err(e) => {
-                                self.error = Some(format!("Gagal serialisasi pesan: {}", e));
-                            }
+                if self.current_input.is_empty() || self.maintenance.is_some() {
+                    return false;
+                }
+                if !self.send_guard.allows(&self.current_input, js_sys::Date::now()) {
+                    return false; // Submit ganda untuk pesan yang baru saja dikirim
+                }
+                if self.auth.needs_login() {
+                    self.error = Some(ChatError::Auth(AuthError::LoginRequired));
+                    return true;
+                }
+                // Di tab DM teks dikirim apa adanya ke lawan bicara, tanpa slash command dan antrean offline
+                if let Some(peer) = self.chat.direct.open_peer() {
+                    // Tamu yang sudah ditangani agen lain tidak dibalas dari sini, agar tamu tidak menerima dua jawaban
+                    if let Some(agent) = self.support.agent_of(peer).filter(|agent| agent.user_id != self.user_id) {
+                        self.error = Some(ChatError::Validation(trf("Percakapan ini sedang ditangani {}.", &[&agent.name])));
+                        return true;
+                    }
+                    let command = ClientCommand::DirectMessage { to: peer.to_string(), text: self.current_input.clone() };
+                    if !self.send_command(ctx, &command) {
+                        self.error = Some(ChatError::not_connected());
+                        return true;
+                    }
+                    self.send_guard.sent(std::mem::take(&mut self.current_input), js_sys::Date::now());
+                    return true;
+                }
+                let (text, whisper_to) = match commands::parse_slash(&self.current_input, &self.bot_commands) {
+                    None => (self.current_input.clone(), None),
+                    Some(Ok(SlashInvocation::Literal(text))) => (text, None),
+                    Some(Ok(SlashInvocation::Whisper { handle, text })) => match self.whisper_target(&handle) {
+                        Ok(user_id) => (text, Some(user_id)),
+                        Err(e) => {
+                            self.error = Some(ChatError::Validation(e));
+                            return true;
                         }
+                    },
+                    Some(Ok(invocation)) => {
                         self.current_input.clear();
+                        self.command_hints.clear();
+                        return self.run_slash(ctx, invocation);
+                    }
+                    Some(Err(e)) => {
+                        self.error = Some(ChatError::Validation(e));
+                        return true;
+                    }
+                };
+                let msg_to_send = ChatMessage {
+                    id: None,
+                    client_id: Some(outbox::new_client_id()), // Juga dipakai saat dikirim dari outbox
+                    user_id: Some(self.user_id.clone()),
+                    username: self.username.clone(),
+                    text,
+                    timestamp: Some(self.clock.now()), // Cadangan untuk tampilan pending; server menimpanya
+                    edited_at: None,
+                    attachment: None,
+                    expires_at: None,
+                    event: None,
+                    system: false,
+                    components: Vec::new(),
+                    room: self.chat.current_room.as_ref().map(|room| room.name.clone()),
+                    operator: self.current_operator().map(Box::new),
+                    whisper_to,
+                };
+                self.typing.sent_message();
+                // Saat login ulang setelah reconnect pesan menunggu di antrean sampai sesi siap
+                if self.client.is_connected() && self.auth.can_send() {
+                    if self.send_cooldown_remaining().is_some() {
+                        return false; // Masih dalam cooldown slow mode
+                    }
+                    if self.send_chat(ctx, &msg_to_send) {
+                        self.start_slow_mode_cooldown(ctx, None);
                     }
                 } else {
-                    self.error = Some("Tidak terhubung ke server WebSocket.".to_string());
+                    // Offline: simpan ke antrean, dikirim otomatis begitu koneksi pulih
+                    self.outbox.push(msg_to_send);
                 }
+                self.scroll_follow.stick(); // Pesan sendiri selalu terlihat, walau user sedang menggulir ke atas
+                self.send_guard.sent(std::mem::take(&mut self.current_input), js_sys::Date::now());
                 true // Re-render untuk membersihkan input atau menampilkan error
             }
+            Msg::AttachFile(file) => {
+                if let Err(e) = uploads::check_limits(&file) {
+                    self.error = Some(ChatError::Validation(e));
+                    return true;
+                }
+                // Kompresi dan penghapusan metadata berjalan async sebelum upload dimulai
+                let settings = self.settings.clone();
+                let link = ctx.link().clone();
+                spawn_local(async move {
+                    link.send_message(Msg::StartUpload(uploads::prepare(file, &settings).await));
+                });
+                false
+            }
+            Msg::StartUpload(file) => {
+                let command = self.uploads.add(file);
+                if !self.send_command(ctx, &command) {
+                    // Tanpa koneksi server tidak bisa memberi offset awal, jadi upload dibatalkan
+                    if let ClientCommand::UploadStart { upload_id, .. } = &command {
+                        self.uploads.remove(upload_id);
+                    }
+                    self.error = Some(ChatError::not_connected());
+                }
+                true
+            }
+            Msg::UploadChunkRead { upload_id, offset, data } => {
+                let Some(upload) = self.uploads.get_mut(&upload_id) else { return false };
+                if upload.status != UploadStatus::Uploading || upload.acked != offset {
+                    return false; // Chunk basi, mis. koneksi sempat putus saat file dibaca
+                }
+                let binary = upload.binary;
+                let chunk = UploadChunkFrame { upload_id, offset, data };
+                match chunk.encode().filter(|_| binary) {
+                    Some(bytes) => self.send_bytes(ctx, bytes),
+                    None => {
+                        let data = base64::engine::general_purpose::STANDARD.encode(&chunk.data);
+                        self.send_command(ctx, &ClientCommand::UploadChunk { upload_id: chunk.upload_id, offset, data });
+                    }
+                }
+                false
+            }
+            Msg::UploadChunkFailed { upload_id, error } => {
+                if let Some(upload) = self.uploads.get_mut(&upload_id) {
+                    upload.status = UploadStatus::Failed(trf("Gagal membaca file: {}", &[&error]));
+                    upload.in_flight = false;
+                }
+                true
+            }
+            Msg::CancelUpload(upload_id) => {
+                if self.uploads.remove(&upload_id).is_some() {
+                    self.send_command(ctx, &ClientCommand::UploadCancel { upload_id });
+                }
+                true
+            }
+            Msg::RetryUpload(upload_id) => {
+                if let Some(command) = self.uploads.retry(&upload_id) {
+                    self.send_command(ctx, &command);
+                }
+                true
+            }
+            Msg::SetDropActive(active) => {
+                let changed = self.drop_active != active; // dragover terpicu terus selama file diseret
+                self.drop_active = active;
+                changed
+            }
+            Msg::UpdateSettings(settings) => {
+                settings.save();
+                i18n::set(Locale::resolve(settings.locale));
+                appearance::apply(&settings);
+                if settings.history_limit != self.settings.history_limit {
+                    self.history.truncate(settings.history_limit);
+                }
+                if settings.privacy != self.settings.privacy {
+                    self.send_command(ctx, &ClientCommand::UpdatePrivacy(settings.privacy.clone()));
+                }
+                if settings.support_agent != self.settings.support_agent {
+                    self.send_command(ctx, &ClientCommand::SetSupportAgent { enabled: settings.support_agent });
+                    self.support.clear(); // Diisi lagi oleh SupportQueue dari server
+                }
+                self.settings = settings;
+                self.update_lite_mode();
+                true
+            }
+            Msg::UpdatePrivacy(privacy) => {
+                let settings = Settings { privacy, ..self.settings.clone() };
+                Component::update(self, ctx, Msg::UpdateSettings(settings))
+            }
+            Msg::RequestExport => {
+                self.export_pending = self.send_command(ctx, &ClientCommand::RequestExport);
+                if !self.export_pending {
+                    self.error = Some(ChatError::not_connected());
+                }
+                true
+            }
+            Msg::DeleteAccount => {
+                let confirmed = web_sys::window()
+                    .and_then(|w| w.confirm_with_message(tr("Hapus akun? Pesan lama Anda akan dianonimkan dan tindakan ini tidak bisa dibatalkan.")).ok())
+                    .unwrap_or(false);
+                if confirmed && !self.send_command(ctx, &ClientCommand::DeleteAccount) {
+                    self.error = Some(ChatError::not_connected());
+                    return true;
+                }
+                false
+            }
+            Msg::ClearHistory => {
+                self.history.clear();
+                self.highlighted_entry = None;
+                self.dispatch(Action::HistoryCleared)
+            }
+            Msg::UpdateVaultInput(value) => {
+                self.vault_input = value;
+                self.vault_error = None;
+                true
+            }
+            Msg::SetupVault | Msg::UnlockVault if self.vault_input.is_empty() => false,
+            Msg::SetupVault => {
+                let passphrase = std::mem::take(&mut self.vault_input);
+                let link = ctx.link().clone();
+                spawn_local(async move {
+                    match vault::setup(&passphrase).await {
+                        Ok(key) => link.send_message(Msg::VaultReady(key, Vec::new(), Vec::new())),
+                        Err(e) => link.send_message(Msg::VaultFailed(e)),
+                    }
+                });
+                self.vault_busy = true;
+                true
+            }
+            Msg::UnlockVault => {
+                let passphrase = std::mem::take(&mut self.vault_input);
+                let link = ctx.link().clone();
+                #[cfg(feature = "crypto")]
+                let e2e = self.e2e.clone();
+                spawn_local(async move {
+                    let result = match vault::unlock(&passphrase).await {
+                        Ok(key) => match Outbox::load_encrypted(&key).await {
+                            Ok(items) => LocalHistory::load_encrypted(&key).await.map(|messages| (key, items, messages)),
+                            Err(e) => Err(e),
+                        },
+                        Err(e) => Err(e),
+                    };
+                    #[cfg(feature = "crypto")]
+                    let result = match result {
+                        Ok(ready) => e2e.restore(&ready.0).await.map(|()| ready),
+                        Err(e) => Err(e),
+                    };
+                    match result {
+                        Ok((key, items, messages)) => link.send_message(Msg::VaultReady(key, items, messages)),
+                        Err(e) => link.send_message(Msg::VaultFailed(e)),
+                    }
+                });
+                self.vault_busy = true;
+                true
+            }
+            Msg::VaultReady(key, restored, messages) => {
+                self.outbox.unlock(key.clone(), restored);
+                #[cfg(feature = "crypto")]
+                self.e2e.unlock(key.clone());
+                self.history.unlock(key, messages.clone(), self.settings.history_limit);
+                self.dispatch(Action::LocalHistoryRestored { messages, now_ms: self.clock.now() });
+                self.vault_locked = false;
+                self.vault_unlocked = true;
+                self.vault_busy = false;
+                self.vault_error = None;
+                #[cfg(feature = "crypto")]
+                self.announce_e2e(ctx, self.chat.current_name()); // Frame kunci diabaikan selama terkunci
+                true
+            }
+            Msg::VaultFailed(error) => {
+                self.vault_busy = false;
+                self.vault_error = Some(error);
+                true
+            }
+            Msg::LockVault => {
+                self.outbox.lock();
+                self.history.lock();
+                #[cfg(feature = "crypto")]
+                self.e2e.lock();
+                self.vault_locked = true;
+                self.vault_unlocked = false;
+                true
+            }
+            Msg::DisableVault => {
+                vault::disable();
+                self.outbox.disable_encryption();
+                self.history.disable_encryption();
+                #[cfg(feature = "crypto")]
+                self.e2e.disable_encryption();
+                self.vault_unlocked = false;
+                true
+            }
+            Msg::SearchMembers(query) => {
+                // Hasil pencarian menggantikan daftar; permintaan dikirim setelah user berhenti mengetik
+                self.members = MemberList::new(self.members.room().to_string(), query);
+                let link = ctx.link().clone();
+                self.member_search = Some(Timeout::new(members::SEARCH_DEBOUNCE_MS, move || link.send_message(Msg::LoadMoreMembers)));
+                true
+            }
+            Msg::LoadMoreMembers => {
+                self.member_search = None;
+                let Some(command) = self.members.next_request() else { return false };
+                if !self.send_command(ctx, &command) {
+                    self.members.cancel_request();
+                    return false;
+                }
+                true // Tampilkan "Memuat..."
+            }
+            Msg::ListSessions => {
+                if !self.send_command(ctx, &ClientCommand::ListSessions) {
+                    self.error = Some(ChatError::not_connected());
+                    return true;
+                }
+                false
+            }
+            Msg::RevokeSession(session_id) => {
+                self.send_command(ctx, &ClientCommand::RevokeSession { session_id: session_id.clone() });
+                // Hapus dari daftar langsung; daftar lengkap dimuat ulang sesudahnya
+                if let Some(sessions) = &mut self.sessions {
+                    sessions.retain(|session| session.session_id != session_id);
+                }
+                self.send_command(ctx, &ClientCommand::ListSessions);
+                true
+            }
+            Msg::StartTwoFactorSetup => {
+                self.two_factor_error = None;
+                if !self.send_command(ctx, &ClientCommand::StartTwoFactorSetup) {
+                    self.error = Some(ChatError::not_connected());
+                    return true;
+                }
+                false // Modal muncul setelah server mengirim TwoFactorSetup
+            }
+            Msg::ConfirmTwoFactorSetup(code) => {
+                self.send_command(ctx, &ClientCommand::ConfirmTwoFactorSetup { code });
+                false
+            }
+            Msg::StartDisableTwoFactor => {
+                self.two_factor_error = None;
+                self.two_factor_step = Some(TwoFactorStep::Disabling);
+                true
+            }
+            Msg::SubmitTwoFactor(submitted) => {
+                let (code, recovery_code) = submitted.into_fields();
+                let command = match self.two_factor_step {
+                    Some(TwoFactorStep::Challenge) => ClientCommand::VerifyTwoFactor { code, recovery_code },
+                    Some(TwoFactorStep::Disabling) => ClientCommand::DisableTwoFactor { code, recovery_code },
+                    _ => return false,
+                };
+                self.send_command(ctx, &command);
+                false
+            }
+            Msg::Login(token, remember) => {
+                self.auth_token = Some(token.clone());
+                self.remember_token = remember;
+                self.auth = AuthState::Authenticating { from_login: true };
+                if ctx.props().token_in_url {
+                    return Component::update(self, ctx, Msg::Connect); // Token baru berarti URL baru
+                }
+                self.send_command(ctx, &ClientCommand::Authenticate { token }); // Offline: dikirim saat Connected
+                true
+            }
+            Msg::Authenticated { user_id, name } => {
+                if let Some(token) = self.auth_token.as_deref().filter(|_| self.remember_token) {
+                    auth::save_token(token);
+                }
+                if let Some(name) = &name {
+                    self.username = name.clone();
+                }
+                if let Some(user_id) = user_id {
+                    self.user_id = user_id.clone();
+                    self.dispatch(Action::SignedIn { user_id, name });
+                }
+                self.auth = AuthState::Authenticated { user_id: self.user_id.clone() };
+                self.start_session(ctx);
+                true
+            }
+            Msg::AuthFailed(reason) => {
+                // Token yang ditolak tidak dicoba lagi saat reconnect
+                if ctx.props().auth_token.is_none() {
+                    auth::forget_token();
+                }
+                self.auth_token = None;
+                self.remember_token = false;
+                self.auth = AuthState::LoginRequired { error: Some(reason.unwrap_or_else(|| tr("Token ditolak server.").to_string())) };
+                true
+            }
+            Msg::CancelTwoFactor => {
+                // Tantangan login tidak bisa dibatalkan
+                if self.two_factor_step == Some(TwoFactorStep::Challenge) {
+                    return false;
+                }
+                self.two_factor_step = None;
+                self.two_factor_error = None;
+                true
+            }
+            Msg::CheckIdle => {
+                self.update_timestamp_refresh(ctx);
+                let timed_out = self.dispatch(Action::DeliveriesTimedOut { now_ms: self.clock.now() });
+                let limit_secs = u64::from(self.settings.auto_lock_minutes) * 60;
+                if !self.vault_unlocked || limit_secs == 0 || self.activity.idle_secs() < limit_secs {
+                    return timed_out;
+                }
+                // Koneksi WebSocket tetap hidup; pesan baru tetap diterima di balik layar kunci
+                Component::update(self, ctx, Msg::LockVault)
+            }
+            Msg::WindowFocusChanged(focused) => {
+                self.window_focused = focused;
+                if focused && !self.page_hidden {
+                    self.unread_title.clear();
+                }
+                false
+            }
+            Msg::MessagesScrolled => {
+                let Some(list) = self.messages_ref.cast::<web_sys::Element>() else { return false };
+                let button_changed = self.scroll_follow.on_scroll(&list, self.shown().messages.len());
+                let following = self.scroll_follow.following();
+                let window_changed = self.virtual_list.on_scroll(&list, self.shown().messages.entries(), following);
+                if list.scroll_top() <= LOAD_OLDER_PX {
+                    ctx.link().send_message(Msg::LoadOlderHistory);
+                }
+                button_changed || window_changed
+            }
+            Msg::ScrollToNewest => {
+                self.scroll_follow.stick();
+                true // Render berikutnya menggulir ke entry terbaru
+            }
+            Msg::VisibilityChanged(hidden) => {
+                self.page_hidden = hidden;
+                if !hidden && self.window_focused {
+                    self.unread_title.clear();
+                }
+                appearance::set_page_hidden(hidden);
+                self.update_ticker(ctx);
+                self.update_timestamp_refresh(ctx);
+                if self.is_connected {
+                    self.send_command(ctx, &ClientCommand::SetForeground { foreground: !hidden });
+                    if !hidden {
+                        // Presence yang terlewat selama di latar belakang diambil lagi, jam disinkronkan ulang
+                        self.send_command(ctx, &ClientCommand::SyncRoster { room: self.chat.current_name().to_string() });
+                        self.send_command(ctx, &ClientCommand::TimeSync { client_time: js_sys::Date::now() });
+                    }
+                }
+                if !hidden {
+                    // Baterai bisa berubah selama tab di latar belakang
+                    let link = ctx.link().clone();
+                    spawn_local(async move { link.send_message(Msg::BatteryChecked(device::battery_low().await)) });
+                }
+                !hidden // Waktu relatif dan perubahan yang ditunda langsung dirender saat tab terlihat lagi
+            }
+            Msg::RefreshTimestamps => !self.is_idle(),
+            Msg::BatteryChecked(low) => {
+                self.low_battery = low;
+                self.update_lite_mode()
+            }
+            Msg::FlushRender => {
+                self.render_batch = None;
+                !self.page_hidden
+            }
+            Msg::ProbeLatency => {
+                if self.is_connected && !self.page_hidden {
+                    self.send_command(ctx, &ClientCommand::TimeSync { client_time: js_sys::Date::now() });
+                }
+                false
+            }
+            Msg::RefreshStats => true,
+            Msg::RefreshIceServers => {
+                self.ice_refresh = None;
+                self.send_command(ctx, &ClientCommand::RequestIceServers);
+                false
+            }
+            Msg::SystemAppearanceChanged => {
+                appearance::apply(&self.settings);
+                self.settings.theme.is_none() // Ikon ThemeToggle mengikuti tema sistem; selain itu hanya atribut/variable di <html> yang berubah
+            }
+            Msg::SendCustom(CustomSend { namespace, payload, key }) => {
+                // Selalu ke room yang sedang dibuka; saat offline dibuang, data seperti ini cepat basi
+                let room = Some(self.chat.current_name().to_string()).filter(|room| !room.is_empty());
+                self.send_command(ctx, &ClientCommand::Custom { room, namespace, payload, key });
+                false
+            }
+            Msg::SaveNote(id, text) => self.edit_note(ctx, id, text, false),
+            Msg::DeleteNote(id) => self.edit_note(ctx, Some(id), String::new(), true),
+            #[cfg(feature = "crypto")]
+            Msg::SetE2e(enabled) => {
+                let room = self.chat.current_name().to_string();
+                if !self.e2e.set_enabled(&room, enabled) {
+                    self.error = Some(ChatError::Internal(tr("browser tidak menyediakan angka acak untuk kunci enkripsi").to_string()));
+                    return true;
+                }
+                self.announce_e2e(ctx, &room);
+                true
+            }
+            #[cfg(feature = "crypto")]
+            Msg::VerifyE2ePeer(user_id) => {
+                let room = self.chat.current_name().to_string();
+                for envelope in self.e2e.verify(&room, &user_id) {
+                    self.send_e2e(ctx, &room, &envelope);
+                }
+                true
+            }
+            Msg::SetTheme(theme) => {
+                let settings = Settings { theme, ..self.settings.clone() };
+                Component::update(self, ctx, Msg::UpdateSettings(settings))
+            }
+            Msg::SendQueued(index) => {
+                if !self.client.is_connected() {
+                    self.error = Some(ChatError::not_connected());
+                    return true;
+                }
+                if let Some(item) = self.outbox.remove(index) {
+                    self.send_chat(ctx, &item.message);
+                }
+                true
+            }
+            Msg::DiscardQueued(index) => self.outbox.remove(index).is_some(),
             Msg::UpdateUsernameInput(input) => {
+                let error = if input.is_empty() { None } else { ctx.props().username_rules.validate(&input).err() };
+                let changed = error != self.username_error;
                 self.username_input = input;
-                false
+                self.username_error = error;
+                changed // Re-render hanya jika hint validasi berubah
             }
             Msg::SetUsername => {
-                if !self.username_input.is_empty() {
-                    self.username = self.username_input.clone();
-                    self.username_input.clear();
+                match ctx.props().username_rules.validate(&self.username_input) {
+                    Ok(()) => {
+                        self.username = self.username_input.clone();
+                        history::save_username(&self.username);
+                        self.dispatch(Action::UserRenamed { user_id: self.user_id.clone(), name: self.username.clone() });
+                        self.username_input.clear();
+                        self.username_error = None;
+                    }
+                    Err(e) => self.username_error = Some(e),
                 }
                 true // Re-render untuk update tampilan username
             }
-            Msg::Error(err_msg) => {
-                self.error = Some(err_msg);
-                log::error!("Error: {:?}", self.error);
-                true // Re-render untuk menampilkan error
+            Msg::UpdateRoomInput(input) => {
+                self.room_input = input;
+                false
             }
-        }
-    }
-
-    fn view(&self, ctx: &Context<Self>) -> Html {
-        let link = ctx.link();
-
-        let on_input_change = link.callback(|e: InputEvent| {
-            let input: HtmlInputElement = e.target_unchecked_into();
-            Msg::UpdateInput(input.value())
-        });
-
-        let on_username_input_change = link.callback(|e: InputEvent| {
-            let input: HtmlInputElement = e.target_unchecked_into();
-            Msg::UpdateUsernameInput(input.value())
-        });
-
-        let on_send_click = link.callback(|_| Msg::SendMessage);
-        let on_set_username_click = link.callback(|_| Msg::SetUsername);
-
-        let on_submit = link.batch_callback(|e: FocusEvent| { // Menggunakan FocusEvent untuk onsubmit form
-            e.prevent_default(); // Mencegah reload halaman default
-            Some(Msg::SendMessage)
-        });
-         let on_username_submit = link.batch_callback(|e: FocusEvent| {
-            e.prevent_default();
-            Some(Msg::SetUsername)
-        });
-
-
-        html! {
-            <div class="chat-container">
-                <header style="text-align:center; margin-bottom:20px; background-color:#333; color:white; padding:10px; border-radius: 5px;">
-                    <h1 style="margin:0;">{ "YewChat Interaktif V2!" }</h1>
-                </header>
-                <h2>{ "Yew WebChat" }</h2>
-                <div>
-                    <p>{ format!("Username saat ini: {}", self.username) }</p>
-                    if !self.is_connected {
-                         <p style="color: red;">{ "Tidak terhubung ke server. Mencoba menghubungkan..." }</p>
-                         <button onclick={link.callback(|_| Msg::Connect)}>{ "Coba Hubungkan Ulang" }</button>
-                    } else {
-                         <p style="color: green;">{ "Terhubung ke server!" }</p>
+            Msg::UpdateRoomPasswordInput(input) => {
+                self.room_password_input = input;
+                false
+            }
+            Msg::UpdateRoomTtl(ttl) => {
+                self.room_ttl = ttl;
+                false
+            }
+            Msg::UpdateRoomFeedbackBox(enabled) => {
+                self.room_feedback_box = enabled;
+                false
+            }
+            Msg::JoinRoom | Msg::CreateRoom => {
+                let room = self.room_input.trim().to_string();
+                if room.is_empty() {
+                    return false;
+                }
+                let password = Some(std::mem::take(&mut self.room_password_input)).filter(|p| !p.is_empty());
+                let command = if matches!(msg, Msg::CreateRoom) {
+                    ClientCommand::CreateRoom { room, password, ttl_secs: self.room_ttl, feedback_box: self.room_feedback_box }
+                } else {
+                    ClientCommand::JoinRoom { room, password }
+                };
+                self.room_error = None;
+                if !self.send_command(ctx, &command) {
+                    self.error = Some(ChatError::not_connected());
+                }
+                true // Re-render untuk mengosongkan field kata sandi
+            }
+            Msg::OpenDirect(peer) => {
+                if peer == self.user_id {
+                    return false;
+                }
+                focus_text_input(&self.input_ref);
+                self.dispatch(Action::DirectOpened(Some(peer)))
+            }
+            Msg::CloseDirect => {
+                self.scroll_follow.stick(); // Daftar pesan room dipasang ulang dari atas
+                self.dispatch(Action::DirectOpened(None))
+            }
+            Msg::SetStatus(status) => {
+                if !self.send_command(ctx, &ClientCommand::SetStatus { status }) {
+                    self.error = Some(ChatError::not_connected());
+                    return true;
+                }
+                self.my_status = status;
+                let user_id = self.user_id.clone();
+                self.dispatch(Action::StatusChanged { user_id, status });
+                true
+            }
+            Msg::SetCustomStatus(draft) => {
+                let status = draft.map(|draft| draft.into_status(self.clock.now()));
+                if !self.send_command(ctx, &ClientCommand::SetCustomStatus { status: status.clone() }) {
+                    self.error = Some(ChatError::not_connected());
+                    return true;
+                }
+                let user_id = self.user_id.clone();
+                self.dispatch(Action::CustomStatusChanged { user_id, status: status.clone() });
+                let settings = Settings { custom_status: status, ..self.settings.clone() };
+                Component::update(self, ctx, Msg::UpdateSettings(settings))
+            }
+            Msg::ClaimConversation(guest) => {
+                if !self.send_command(ctx, &ClientCommand::ClaimConversation { guest: guest.clone() }) {
+                    self.error = Some(ChatError::not_connected());
+                    return true;
+                }
+                self.support.start_claim(guest);
+                true
+            }
+            Msg::ReleaseConversation(guest) => {
+                if !self.send_command(ctx, &ClientCommand::ReleaseConversation { guest: guest.clone() }) {
+                    self.error = Some(ChatError::not_connected());
+                    return true;
+                }
+                if self.chat.direct.open_peer() == Some(guest.as_str()) {
+                    self.dispatch(Action::DirectOpened(None));
+                }
+                true
+            }
+            Msg::InsertCanned(name) => {
+                let Some(response) = canned::find(&self.settings.canned_responses, &name) else {
+                    self.error = Some(ChatError::Validation(trf("Balasan cepat \"{}\" tidak ditemukan.", &[&name.trim()])));
+                    return true;
+                };
+                self.current_input = canned::expand(&response.text, &self.canned_placeholders());
+                self.refresh_command_hints();
+                focus_text_input(&self.input_ref);
+                true
+            }
+            Msg::SwitchRoom(room) => {
+                if !self.dispatch(Action::RoomSwitched(room)) {
+                    return false;
+                }
+                self.enter_room(ctx);
+                true
+            }
+            Msg::LeaveRoom(room) => {
+                // Langsung keluar di klien; RoomLeft dari server nanti tidak mengubah apa-apa lagi
+                let was_current = self.chat.current_name() == room;
+                if !self.dispatch(Action::RoomLeft { room: room.clone() }) {
+                    return false;
+                }
+                self.send_command(ctx, &ClientCommand::LeaveRoom { room });
+                if was_current {
+                    self.enter_room(ctx);
+                }
+                true
+            }
+            Msg::AcceptRules => {
+                let Some(room) = self.chat.current_room.as_ref().filter(|room| room.rules_pending()) else {
+                    return false;
+                };
+                let command = ClientCommand::AcceptRules { room: room.name.clone() };
+                self.dispatch(Action::RulesAccepted);
+                self.send_command(ctx, &command);
+                true
+            }
+            Msg::OpenPalette => {
+                let opened = !self.show_palette;
+                self.show_palette = true;
+                opened
+            }
+            Msg::ClosePalette => {
+                let closed = self.show_palette;
+                self.show_palette = false;
+                closed
+            }
+            Msg::RunPaletteAction(action) => {
+                self.show_palette = false;
+                // Selalu re-render agar palet tertutup, apa pun hasil aksinya
+                let _ = match action {
+                    PaletteAction::SwitchRoom(room) if self.chat.is_joined(&room) => Component::update(self, ctx, Msg::SwitchRoom(room)),
+                    PaletteAction::SwitchRoom(room) => {
+                        self.room_input = room;
+                        Component::update(self, ctx, Msg::JoinRoom)
                     }
-                    {
-                        if let Some(err) = &self.error {
-                            html! { <p style="color: red;">{ format!("Error: {}", err) }</p> }
-                        } else {
-                            html! {}
-                        }
+                    PaletteAction::InsertCommand(usage) => {
+                        self.current_input = usage;
+                        self.refresh_command_hints();
+                        focus_text_input(&self.input_ref);
+                        true
                     }
-                </div>
-                <div class="username-area">
-                    <form onsubmit={on_username_submit}> // Tambahkan form untuk submit username dengan Enter
-                        <input
-                            type="text"
-                            placeholder="Set username..."
-                            value={self.username_input.clone()}
-                            oninput={on_username_input_change}
-                        />
-                        <button onclick={on_set_username_click} disabled={self.username_input.is_empty()}>{ "Set Username" }</button>
-                    </form>
-                </div>
-
-                <ul class="messages">
-                    { for self.messages.iter().map(|msg| self.view_message(msg)) }
-                </ul>
-
-                <div class="input-area">
-                     <form onsubmit={on_submit} style="display: contents;"> // Tambahkan form untuk submit pesan dengan Enter
-                        <input
-                            type="text"
-                            placeholder="Ketik pesan..."
-                            value={self.current_input.clone()}
-                            oninput={on_input_change}
-                            disabled={!self.is_connected}
-                        />
-                        <button onclick={on_send_click} disabled={self.current_input.is_empty() || !self.is_connected}>
-                            { "Kirim" }
-                        </button>
-                    </form>
-                </div>
-            </div>
-        }
-    }
-}
-
-// Metode helper untuk merender satu pesan
-impl App {
-    fn view_message(&self, msg: &ChatMessage) -> Html {
-        let is_me = msg.username == self.username;
-        let class_name = if is_me { "me" } else { "other" };
-        html! {
-            <li class={class_name}>
-                <div class="message-meta">
-                    <strong>{ &msg.username }</strong>
-                    {
-                        if let Some(ts) = &msg.timestamp {
-                            html!{ <span class="timestamp">{ format!(" - {}", ts) }</span> }
-                        } else {
-                            html!{}
+                    PaletteAction::OpenSettings => {
+                        if let Some(settings) = self.settings_ref.cast::<web_sys::Element>() {
+                            settings.scroll_into_view();
                         }
+                        true
                     }
-                </div>
-                <div>{ &msg.text }</div>
-            </li>
-        }
-    }
+                    PaletteAction::StartTour => Component::update(self, ctx, Msg::StartTour),
+                    PaletteAction::LockChat => Component::update(self, ctx, Msg::LockVault),
+                    PaletteAction::ListSessions => Component::update(self, ctx, Msg::ListSessions),
+                    PaletteAction::OpenDirect(peer) => Component::update(self, ctx, Msg::OpenDirect(peer)),
+                    PaletteAction::InsertCanned(name) => Component::update(self, ctx, Msg::InsertCanned(name)),
+                    PaletteAction::RequestExport => Component::update(self, ctx, Msg::RequestExport),
+                    PaletteAction::Reconnect => Component::update(self, ctx, Msg::Connect),
+                };
+                true
+            }
+            Msg::StartTour => {
+                self.show_tour = true;
+                true
+            }
+            Msg::FinishTour => {
+                tour::mark_tour_completed();
+                self.show_tour = false;
+                true
+            }
+            Msg::SetSlowMode(interval_secs) => {
+                if let Some(room) = self.chat.current_room.as_ref().filter(|room| room.is_moderator) {
+                    let command = ClientCommand::SetSlowMode { room: room.name.clone(), interval_secs };
+                    self.send_command(ctx, &command);
+                }
+                false // Tampilan berubah setelah server mengirim SlowModeChanged
+            }
+            Msg::SetEmbedsDisabled(disabled) => {
+                if let Some(room) = self.chat.current_room.as_ref().filter(|room| room.is_moderator) {
+                    let command = ClientCommand::SetEmbedsDisabled { room: room.name.clone(), disabled };
+                    self.send_command(ctx, &command);
+                }
+                false
+            }
+            Msg::SetRoomLanguage(language) => {
+                if let Some(room) = self.chat.current_room.as_ref().filter(|room| room.is_moderator) {
+                    let command = ClientCommand::SetRoomLanguage { room: room.name.clone(), language };
+                    self.send_command(ctx, &command);
+                }
+                false // Tampilan berubah setelah server mengirim RoomLanguageChanged
+            }
+            Msg::Translate { message_id, source } => {
+                let target = self.settings.language();
+                self.request_translation(ctx, message_id, source, target);
+                true
+            }
+            Msg::ShowOriginal(message_id) => {
+                self.translations.show_original(message_id);
+                true
+            }
+            Msg::ToggleAutoTranslate(enabled) => {
+                let room = self.chat.current_name().to_string();
+                let mut settings = self.settings.clone();
+                settings.auto_translate_rooms.retain(|name| *name != room);
+                if enabled {
+                    settings.auto_translate_rooms.push(room);
+                }
+                Component::update(self, ctx, Msg::UpdateSettings(settings));
+                if enabled {
+                    // Pesan terakhir yang sudah tampil ikut diterjemahkan, bukan hanya yang masuk berikutnya
+                    let backlog: Vec<(String, String)> = self.chat.messages.entries().iter().rev()
+                        .map(|entry| &entry.first)
+                        .filter_map(|stored| Some((stored.message.id.clone()?, self.translation_target(&stored.message, &stored.profile)?)))
+                        .take(translate::AUTO_TRANSLATE_BACKLOG)
+                        .collect();
+                    let target = self.settings.language();
+                    for (message_id, source) in backlog {
+                        self.request_translation(ctx, message_id, source, target.clone());
+                    }
+                }
+                true
+            }
+            Msg::TypingExpired => {
+                self.typing_timer = None;
+                self.typing.expire(js_sys::Date::now());
+                self.schedule_typing_expiry(ctx);
+                true // Entry hilang atau mulai tampil sebagai pesan panjang
+            }
+            Msg::ExpireMessages => {
+                self.expiry_timer = None;
+                let removed = self.dispatch(Action::MessagesExpired { now_ms: self.clock.now() });
+                if removed {
+                    self.highlighted_entry = None; // Index entry bergeser
+                }
+                self.schedule_expiry(ctx);
+                self.update_ticker(ctx);
+                removed
+            }
+            Msg::Tick => {
+                if self.send_cooldown_remaining().is_none() {
+                    self.send_cooldown_until = None;
+                }
+                self.update_ticker(ctx);
+                true
+            }
+            Msg::MaintenanceEnded => {
+                log::info!("Pemeliharaan server selesai; menyambung lagi");
+                self.maintenance = None;
+                self.update_ticker(ctx);
+                if !self.client.is_connected() {
+                    ctx.link().send_message(Msg::Connect);
+                }
+                true
+            }
+            Msg::Error(error) => {
+                log::error!("Error: {:?}", error); // Detail lengkap (termasuk payload) hanya di log
+                self.error = Some(error);
+                true // Re-render untuk menampilkan error
+            }
+            Msg::ChatSendFailed(client_id, error) => {
+                // Pesan tetap di daftar dengan tanda gagal dan tombol kirim ulang
+                let reason = ChatError::from(error).to_string();
+                self.dispatch(Action::DeliveryFailed { client_id, reason })
+            }
+            Msg::RetryMessage(client_id) => {
+                let Some(message) = self.chat.unsent(&client_id).map(|stored| stored.message.clone()) else { return false };
+                if !self.send_chat(ctx, &message) {
+                    self.error = Some(ChatError::not_connected());
+                }
+                true
+            }
+            Msg::DiscardUnsent(client_id) => self.dispatch(Action::UnsentDiscarded { client_id }),
+            Msg::DismissError => {
+                self.error.take().is_some()
+            }
+            Msg::Rsvp(event_id, response) => {
+                if !self.send_command(ctx, &ClientCommand::Rsvp { event_id, response }) {
+                    self.error = Some(ChatError::not_connected());
+                    return true;
+                }
+                false // Kartu diperbarui saat server mengirim EventUpdated
+            }
+            Msg::Interact(interaction) => {
+                let message_id = interaction.message_id.clone();
+                if self.pending_interactions.contains_key(&message_id) {
+                    return false;
+                }
+                if !self.send_command(ctx, &ClientCommand::Interaction(interaction)) {
+                    self.error = Some(ChatError::not_connected());
+                    return true;
+                }
+                let timeout = {
+                    let (link, message_id) = (ctx.link().clone(), message_id.clone());
+                    Timeout::new(components::INTERACTION_TIMEOUT_MS, move || link.send_message(Msg::InteractionTimedOut(message_id)))
+                };
+                self.pending_interactions.insert(message_id, timeout);
+                true
+            }
+            Msg::InteractionTimedOut(message_id) => {
+                if self.pending_interactions.remove(&message_id).is_none() {
+                    return false;
+                }
+                self.error = Some(ChatError::Validation(tr("Bot tidak menanggapi. Coba lagi nanti.").to_string()));
+                true
+            }
+            Msg::SnoozeRoom(room, duration) => {
+                match duration {
+                    Some(duration) => self.snoozes.snooze(&room, duration.until(js_sys::Date::now())),
+                    None => self.snoozes.unsnooze(&room),
+                }
+                true
+            }
+            Msg::EnableNotifications => {
+                notify::request_permission();
+                false
+            }
+        }
+    }
+
+    // Halaman induk mengganti server: tutup koneksi lama lalu sambung ke URL baru
+    fn changed(&mut self, ctx: &Context<Self>, old_props: &Self::Properties) -> bool {
+        if ctx.props().plugins != old_props.plugins {
+            ctx.props().plugins.attach(ctx.link().callback(Msg::SendCustom));
+        }
+        if ctx.props().ws_url == old_props.ws_url && ctx.props().auth_token == old_props.auth_token {
+            return false;
+        }
+        if ctx.props().auth_token != old_props.auth_token {
+            self.auth_token = ctx.props().auth_token.clone().or_else(auth::load_token);
+        }
+        self.config.ws_url = ctx.props().ws_url.clone().unwrap_or_else(config::default_ws_url);
+        Component::update(self, ctx, Msg::Connect) // Koneksi lama ditutup dan event-nya diabaikan
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+        #[cfg(feature = "perf")]
+        perf::end("App");
+        self.send_guard.echoed();
+        let Some(list) = self.messages_ref.cast::<web_sys::Element>() else { return };
+        if self.top_entry_observer.is_none() {
+            self.top_entry_observer = TopEntryObserver::new(&list, ctx.link().callback(Msg::TopEntryChanged));
+        }
+        let rendered = self.rendered_entries();
+        if let Some(observer) = self.top_entry_observer.as_mut() {
+            observer.sync(&list, rendered.clone());
+        }
+        self.virtual_list.restore_position(&list);
+        self.scroll_follow.follow(&list, self.shown().messages.len());
+        // Isi daftar belum memenuhi viewport, jadi tidak akan ada event scroll yang memuat riwayat lama
+        if list.scroll_height() <= list.client_height() && self.older_request.is_none() && !self.chat.history_complete && !self.chat.messages.is_empty() {
+            ctx.link().send_message(Msg::LoadOlderHistory);
+        }
+        // Tinggi asli bisa berbeda dari perkiraan; render ulang jika jendelanya jadi bergeser
+        if self.virtual_list.measure(&list, self.shown().messages.entries()) && self.rendered_entries() != rendered {
+            ctx.link().send_message(Msg::MessagesMeasured);
+        }
+        if let Some(index) = self.pending_jump.take() {
+            ctx.link().send_message(Msg::JumpToMessage(index));
+        }
+        self.mark_read(ctx);
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        #[cfg(feature = "perf")]
+        perf::begin("App", self.shown().messages.len());
+        let link = ctx.link();
+
+        // Tab memilih saran @mention pertama, atau melengkapi nama slash command yang sedang diketik
+        let mention_suggestions = self.mention_suggestions();
+        let first_mention = mention_suggestions.first().map(|user| mentions::handle_for(user).to_string());
+        let first_command = self.command_hints.first().filter(|_| !self.current_input.contains(char::is_whitespace)).map(|hint| hint.name.clone());
+        let completes = first_mention.is_some() || first_command.is_some();
+        let on_complete = link.batch_callback(move |()| match (first_mention.clone(), first_command.clone()) {
+            (Some(handle), _) => Some(Msg::CompleteMention(handle)),
+            (None, Some(name)) => Some(Msg::CompleteCommand(name)),
+            (None, None) => None,
+        });
+
+        let cooldown = self.send_cooldown_remaining();
+        let direct_peer = self.shown().direct.open_peer();
+        let rules_pending = direct_peer.is_none() && self.shown().current_room.as_ref().is_some_and(RoomState::rules_pending);
+        let read_only = rules_pending || self.maintenance.is_some();
+        let feedback_box = direct_peer.is_none() && self.shown().current_room.as_ref().is_some_and(|room| room.feedback_box);
+        let accepts_files = self.is_connected && !read_only;
+        let on_drag_over = link.batch_callback(|e: DragEvent| {
+            if !uploads::drags_files(&e) {
+                return None;
+            }
+            e.prevent_default(); // Tanpa ini browser tidak mengizinkan drop
+            Some(Msg::SetDropActive(true))
+        });
+        let on_drag_leave = link.batch_callback(|e: DragEvent| uploads::leaves_drop_area(&e).then_some(Msg::SetDropActive(false)));
+        let on_drop = link.batch_callback(move |e: DragEvent| {
+            if !uploads::drags_files(&e) {
+                return Vec::new();
+            }
+            e.prevent_default(); // Jangan biarkan browser membuka file di tab ini
+            let files = if accepts_files { uploads::dropped_files(&e) } else { Vec::new() };
+            std::iter::once(Msg::SetDropActive(false)).chain(files.into_iter().map(Msg::AttachFile)).collect()
+        });
+
+        // Function component di bawah App membaca bahasa dan tema lewat context ini (lihat i18n dan theme)
+        html! {
+            <ContextProvider<Locale> context={i18n::current()}>
+            <ThemeProvider theme={Theme::resolve(self.settings.theme)} on_change={link.callback(Msg::SetTheme)}>
+            <div class="chat-container" data-connected={self.is_connected.to_string()} data-room={self.chat.current_name().to_string()}
+                data-drop-active={self.drop_active.then_some("true")} data-maintenance={self.maintenance.is_some().then_some("true")} ondragover={on_drag_over} ondragleave={on_drag_leave} ondrop={on_drop}>
+                <header class="app-header">
+                    if let Some(header) = &ctx.props().header {
+                        { header.clone() }
+                    } else {
+                        <h1>{ tr("YewChat Interaktif V2!") }</h1>
+                    }
+                </header>
+                <h2>
+                    { "Yew WebChat" }
+                    <button class="tour-replay" title={tr("Tampilkan tur pengenalan")} onclick={link.callback(|_| Msg::StartTour)}>{ "?" }</button>
+                    <ThemeToggle />
+                </h2>
+                <div>
+                    <p>{ trf("Username saat ini: {}", &[&self.username]) } <span class="user-handle">{ format!("@{}", self.user_id) }</span></p>
+                    if let Some(maintenance) = &self.maintenance {
+                        { maintenance::view_banner(maintenance) }
+                    } else {
+                        <ConnectionBanner connected={self.is_connected} on_reconnect={link.callback(|_| Msg::Connect)} />
+                    }
+                    { self.view_error(ctx) }
+                </div>
+                <div class="username-area" ref={self.username_ref.clone()}>
+                    <UsernameForm
+                        value={self.username_input.clone()}
+                        error={self.username_error.as_ref().map(|err| AttrValue::from(validation::error_message(err)))}
+                        on_input={link.callback(Msg::UpdateUsernameInput)}
+                        on_submit={link.callback(|_| Msg::SetUsername)}
+                    />
+                </div>
+
+                <div class="settings-area" ref={self.settings_ref.clone()}>
+                    <SettingsPanel settings={self.settings.clone()} lite_mode={self.lite_mode} on_change={link.callback(Msg::UpdateSettings)} on_clear_history={link.callback(|_| Msg::ClearHistory)} />
+                    <PrivacyPanel
+                        privacy={self.settings.privacy.clone()}
+                        on_change={link.callback(Msg::UpdatePrivacy)}
+                        export_pending={self.export_pending}
+                        export_url={self.export_url.clone()}
+                        on_export={link.callback(|_| Msg::RequestExport)}
+                        on_delete_account={link.callback(|_| Msg::DeleteAccount)}
+                    />
+                    <SessionsPanel
+                        sessions={self.sessions.clone()}
+                        on_refresh={link.callback(|_| Msg::ListSessions)}
+                        on_revoke={link.callback(Msg::RevokeSession)}
+                    />
+                    <StatsPanel stats={self.stats.snapshot()} on_refresh={link.callback(|_| Msg::RefreshStats)} />
+                    <IcePanel servers={self.config.ice_servers.clone()} from_server={self.config.ice_servers_from_server} />
+                    { self.view_vault_controls(ctx) }
+                    { self.view_two_factor_controls(ctx) }
+                </div>
+
+                <div class="room-area" ref={self.room_ref.clone()}>
+                    { self.view_room_header(ctx) }
+                    { self.view_room_list(ctx) }
+                    { self.view_slow_mode_control(ctx) }
+                    <RoomForm
+                        room={self.room_input.clone()}
+                        password={self.room_password_input.clone()}
+                        ttl={self.room_ttl}
+                        feedback_box={self.room_feedback_box}
+                        connected={self.is_connected}
+                        error={self.room_error.clone().map(AttrValue::from)}
+                        on_room_input={link.callback(Msg::UpdateRoomInput)}
+                        on_password_input={link.callback(Msg::UpdateRoomPasswordInput)}
+                        on_ttl={link.callback(Msg::UpdateRoomTtl)}
+                        on_feedback_box={link.callback(Msg::UpdateRoomFeedbackBox)}
+                        on_join={link.callback(|_| Msg::JoinRoom)}
+                        on_create={link.callback(|_| Msg::CreateRoom)}
+                    />
+                </div>
+
+                { self.view_online(ctx) }
+                { self.view_members(ctx) }
+                { self.view_support_queue(ctx) }
+
+                <AttachmentGallery items={self.gallery_items()} on_jump={link.callback(Msg::JumpToMessage)} policy={self.embed_policy()} />
+                <NotesPanel items={self.note_items()} disabled={!self.is_connected || self.chat.current_room.as_ref().is_some_and(RoomState::hides_identity)}
+                    on_save={link.callback(|(id, text)| Msg::SaveNote(id, text))} on_delete={link.callback(Msg::DeleteNote)} />
+                { self.view_e2e(ctx) }
+
+                { self.view_mention_nav(ctx) }
+
+                { self.view_direct_tabs(ctx) }
+
+                if let Some(conversation) = self.shown().direct.open() {
+                    { self.view_support_banner(&conversation.peer) }
+                    <MessageViewport class={classes!("direct", self.vault_locked.then_some("locked"))} header={self.view_empty_state(ctx, conversation.messages.is_empty())}>
+                        { for conversation.messages.entries().iter().flat_map(|entry| std::iter::once(&entry.first).chain(&entry.repeats)).map(|stored| {
+                            self.view_message(ctx, stored, None, html! {})
+                        }) }
+                    </MessageViewport>
+                } else {
+                    <MessageViewport
+                        class={classes!(self.vault_locked.then_some("locked"))}
+                        list_ref={self.messages_ref.clone()}
+                        on_scroll={link.callback(|_| Msg::MessagesScrolled)}
+                        header={html! { <>{ self.view_date_chip() }{ self.view_empty_state(ctx, self.shown().messages.is_empty()) }</> }}
+                        footer={html! {
+                            <>
+                                { self.view_new_below(ctx) }
+                                if let Some(label) = self.typing.label(i18n::current(), self.shown().current_name(), js_sys::Date::now()) {
+                                    <p class="typing-indicator" aria-live="polite">{ label }</p>
+                                }
+                            </>
+                        }}
+                    >
+                        { self.view_message_window(ctx) }
+                    </MessageViewport>
+                }
+
+                { self.view_outbox(ctx) }
+                { self.view_uploads(ctx) }
+
+                <div class={classes!("input-area", feedback_box.then_some("feedback"))} ref={self.input_ref.clone()}>
+                    <Composer
+                        value={self.current_input.clone()}
+                        placeholder={match direct_peer {
+                            Some(peer) => trf("Pesan langsung untuk {}...", &[&self.shown().directory.name_of(peer).unwrap_or(peer)]),
+                            None if feedback_box => tr("Tulis saran anonim...").to_string(),
+                            None => tr("Ketik pesan...").to_string(),
+                        }}
+                        {feedback_box}
+                        {read_only}
+                        connected={self.is_connected}
+                        {cooldown}
+                        awaiting_echo={self.send_guard.awaiting_echo()}
+                        canned={self.settings.canned_responses.clone()}
+                        {completes}
+                        on_input={link.callback(Msg::UpdateInput)}
+                        on_submit={link.callback(|_| Msg::SendMessage)}
+                        {on_complete}
+                        on_canned={link.callback(Msg::InsertCanned)}
+                        on_attach={link.callback(Msg::AttachFile)}
+                    >
+                        { self.view_mention_suggestions(ctx, &mention_suggestions) }
+                        if mention_suggestions.is_empty() {
+                            { self.view_command_hints(ctx) }
+                        }
+                    </Composer>
+                </div>
+                if let Some(footer) = &ctx.props().footer {
+                    <footer class="app-footer">{ footer.clone() }</footer>
+                }
+                { self.view_tour(ctx) }
+                if self.show_palette {
+                    <CommandPalette
+                        items={self.palette_items()}
+                        on_select={link.callback(Msg::RunPaletteAction)}
+                        on_close={link.callback(|_| Msg::ClosePalette)}
+                    />
+                }
+                { self.view_rules_gate(ctx) }
+                { self.view_source_modal(ctx) }
+                { self.view_time_travel(ctx) }
+                { self.view_receipts_toggle(ctx) }
+                { self.view_demo_scenarios(ctx) }
+                { self.view_vault_lock(ctx) }
+                { self.view_two_factor_step(ctx) }
+                { self.view_login(ctx) }
+            </div>
+            </ThemeProvider>
+            </ContextProvider<Locale>>
+        }
+    }
+}
+
+// Metode helper untuk pengiriman frame dan penanganan event server
+impl App {
+    fn embed_policy(&self) -> media::EmbedPolicy {
+        media::EmbedPolicy {
+            embeds_disabled: self.shown().current_room.as_ref().is_some_and(|room| room.embeds_disabled),
+            max_gif_rating: self.settings.max_gif_rating,
+        }
+    }
+
+    // Semua lampiran di room saat ini, urut sesuai daftar pesan
+    fn view_members(&self, ctx: &Context<Self>) -> Html {
+        if self.chat.current_room.is_none() {
+            return html! {};
+        }
+        let now = js_sys::Date::now();
+        let chat = self.shown();
+        let rows = self.members
+            .members()
+            .iter()
+            .map(|(user_id, name)| {
+                let presence = chat.presence.get(user_id);
+                MemberRow {
+                    user_id: user_id.clone(),
+                    name: chat.directory.name_of(user_id).unwrap_or(name).to_string(), // Nama terbaru jika sudah ganti nama
+                    online: presence.is_some_and(|p| p.online),
+                    status: presence::describe(presence, chat.presence.status_of(user_id), now),
+                    custom_status: chat.presence.custom_of(user_id, self.clock.now()).map(|custom| custom.label()),
+                }
+            })
+            .collect::<Vec<_>>();
+        let link = ctx.link();
+        html! {
+            <MembersPanel
+                members={rows}
+                query={self.members.query().to_string()}
+                total={self.members.total()}
+                loading={self.members.is_loading()}
+                complete={self.members.is_complete()}
+                on_search={link.callback(Msg::SearchMembers)}
+                on_load_more={link.callback(|_| Msg::LoadMoreMembers)}
+            />
+        }
+    }
+
+    // Daftar online room yang ditampilkan; kotak saran tidak menampilkannya karena pengirimnya anonim
+    fn view_online(&self, ctx: &Context<Self>) -> Html {
+        let chat = self.shown();
+        if chat.current_room.as_ref().is_some_and(|room| room.feedback_box) {
+            return html! {};
+        }
+        let now = self.clock.now();
+        let users = chat.presence.roster(chat.current_name()).map(|roster| {
+            let mut rows: Vec<OnlineRow> = roster
+                .users
+                .iter()
+                .map(|user| OnlineRow {
+                    user_id: user.user_id.clone(),
+                    name: chat.directory.name_of(&user.user_id).unwrap_or(&user.name).to_string(), // Nama terbaru jika sudah ganti nama
+                    status: user.status,
+                    custom_status: chat.presence.custom_of(&user.user_id, now).cloned(),
+                    me: user.user_id == self.user_id,
+                })
+                .collect();
+            rows.sort_by_key(|row| (!row.me, row.name.to_lowercase()));
+            rows
+        });
+        let link = ctx.link();
+        html! {
+            <OnlinePanel
+                users={users}
+                my_status={self.my_status}
+                my_custom_status={self.my_custom_status()}
+                connected={self.is_connected}
+                on_status_change={link.callback(Msg::SetStatus)}
+                on_custom_status={link.callback(Msg::SetCustomStatus)}
+                on_open={link.callback(Msg::OpenDirect)}
+            />
+        }
+    }
+
+    // Status kustom user ini selama belum kedaluwarsa
+    fn my_custom_status(&self) -> Option<CustomStatus> {
+        self.settings.custom_status.clone().filter(|status| !status.is_expired(self.clock.now()))
+    }
+
+    fn view_support_queue(&self, ctx: &Context<Self>) -> Html {
+        if !self.settings.support_agent {
+            return html! {};
+        }
+        let link = ctx.link();
+        html! {
+            <SupportPanel
+                tickets={self.support.tickets().to_vec()}
+                me={self.user_id.clone()}
+                claiming={self.support.claiming().map(str::to_string)}
+                connected={self.is_connected}
+                now_ms={js_sys::Date::now()}
+                on_claim={link.callback(Msg::ClaimConversation)}
+                on_release={link.callback(Msg::ReleaseConversation)}
+                on_open={link.callback(Msg::OpenDirect)}
+            />
+        }
+    }
+
+    // Agen yang menangani percakapan tamu di tab DM yang terbuka
+    fn view_support_banner(&self, peer: &str) -> Html {
+        let Some(ticket) = self.support.ticket(peer) else { return html! {} };
+        match &ticket.agent {
+            Some(agent) if agent.user_id == self.user_id => html! {
+                <div class="support-banner mine">{ tr("Anda menangani percakapan ini") }</div>
+            },
+            Some(agent) => html! {
+                <div class="support-banner taken">{ format!("Ditangani oleh {} — balasan dari Anda dinonaktifkan", agent.name) }</div>
+            },
+            None => html! {
+                <div class="support-banner">{ tr("Percakapan ini masih menunggu di antrean dukungan") }</div>
+            },
+        }
+    }
+
+    fn gallery_items(&self) -> Vec<GalleryItem> {
+        self.shown().messages
+            .entries()
+            .iter()
+            .enumerate()
+            .flat_map(|(index, entry)| {
+                std::iter::once(&entry.first).chain(&entry.repeats).filter_map(move |stored| {
+                    let attachment = stored.message.attachment.clone()?;
+                    Some(GalleryItem {
+                        entry_index: index,
+                        attachment,
+                        sender: self.shown().directory.display_name(&stored.message).to_string(),
+                    })
+                })
+            })
+            .collect()
+    }
+
+    // Kirim lewat ChatClient di background. Pesan langsung tampil sebagai pending sampai dikembalikan
+    // server; yang gagal terkirim ditandai gagal di daftar. Mengembalikan false jika belum tersambung sama sekali.
+    // Perintah awal sesi: langsung setelah terhubung, atau setelah AuthResult jika memakai token
+    fn start_session(&mut self, ctx: &Context<Self>) {
+        if !self.chat.messages.is_empty() {
+            // Reconnect: minta pesan yang terlewat, dibatasi agar tab tidak macet
+            let command = ClientCommand::Resume { since: self.chat.last_seen, limit: self.backfill_limit };
+            self.send_command(ctx, &command);
+        }
+        self.operators_accepted = false;
+        self.send_command(ctx, &ClientCommand::Hello { capabilities: vec![Capability::PresenceDigest, Capability::MessagePack, Capability::Operators] });
+        self.send_command(ctx, &ClientCommand::TimeSync { client_time: js_sys::Date::now() });
+        self.send_command(ctx, &ClientCommand::RequestIceServers);
+        self.send_command(ctx, &ClientCommand::UpdatePrivacy(self.settings.privacy.clone()));
+        self.request_notes(ctx); // Suntingan anggota lain selama terputus
+        #[cfg(feature = "crypto")]
+        self.announce_e2e(ctx, self.chat.current_name());
+        if self.settings.support_agent {
+            self.send_command(ctx, &ClientCommand::SetSupportAgent { enabled: true });
+        }
+        if self.my_status != UserStatus::Online {
+            self.send_command(ctx, &ClientCommand::SetStatus { status: self.my_status });
+        }
+        if let Some(status) = self.my_custom_status() {
+            self.send_command(ctx, &ClientCommand::SetCustomStatus { status: Some(status) });
+        }
+        self.send_command(ctx, &ClientCommand::SyncRoster { room: self.chat.current_name().to_string() });
+        // Pesan yang ditulis saat offline di sesi ini langsung dikirim; sisa sesi lalu menunggu keputusan user
+        for item in self.outbox.take_pending() {
+            self.send_chat(ctx, &item.message);
+        }
+        // Upload yang terputus dilanjutkan dari offset terakhir yang dikonfirmasi server
+        for command in self.uploads.resume_commands() {
+            self.send_command(ctx, &command);
+        }
+    }
+
+    fn send_chat(&mut self, ctx: &Context<Self>, message: &ChatMessage) -> bool {
+        let mut message = message.clone();
+        let client_id = message.client_id.get_or_insert_with(outbox::new_client_id).clone();
+        // Hanya frame ke server yang tersegel; daftar pesan dan outbox menyimpan teks aslinya. Selama chat
+        // terkunci keyring kosong, jadi pesan ditahan agar tidak terkirim tanpa enkripsi.
+        #[cfg(feature = "crypto")]
+        if self.e2e.is_locked() {
+            return false;
+        }
+        #[cfg(feature = "crypto")]
+        let sealed = self.e2e.seal(&message);
+        #[cfg(feature = "crypto")]
+        let wire = sealed.as_ref().unwrap_or(&message);
+        #[cfg(not(feature = "crypto"))]
+        let wire = &message;
+        let Some(sending) = self.client.send(wire) else { return false };
+        // Server tanpa login baru mengenal user dari pesan pertamanya dan membuang Custom sebelum itu,
+        // jadi kunci diumumkan lagi setelah pesan terenkripsi pertama di koneksi ini
+        #[cfg(feature = "crypto")]
+        if sealed.is_some() {
+            let room = message.room.clone().unwrap_or_default();
+            if self.e2e_announced.insert(room.clone()) {
+                self.announce_e2e(ctx, &room);
+            }
+        }
+        let now_ms = self.clock.now();
+        if self.chat.unsent(&client_id).is_some() {
+            self.dispatch(Action::DeliveryRetried { client_id: client_id.clone(), now_ms });
+        } else {
+            self.dispatch(Action::MessageSending { message, now_ms });
+        }
+        let (link, stats) = (ctx.link().clone(), self.stats.clone());
+        spawn_local(async move {
+            match sending.await {
+                Ok(receipt) => stats.record_sent(receipt.bytes, true),
+                Err(error) => link.send_message(Msg::ChatSendFailed(client_id, error)),
+            }
+        });
+        true
+    }
+
+    fn send_command(&self, ctx: &Context<Self>, command: &ClientCommand) -> bool {
+        let Some(sending) = self.client.send(command) else { return false };
+        let (link, command, stats) = (ctx.link().clone(), command.clone(), self.stats.clone());
+        spawn_local(async move {
+            match sending.await {
+                Ok(receipt) => stats.record_sent(receipt.bytes, false),
+                Err(error) => {
+                    log::error!("Gagal mengirim perintah {:?}: {:?}", command, error);
+                    link.send_message(Msg::Error(error.into()));
+                }
+            }
+        });
+        true
+    }
+
+    fn send_bytes(&self, ctx: &Context<Self>, bytes: Vec<u8>) {
+        let Some(sending) = self.client.send_bytes(bytes) else { return };
+        let (link, stats) = (ctx.link().clone(), self.stats.clone());
+        spawn_local(async move {
+            match sending.await {
+                Ok(receipt) => stats.record_sent(receipt.bytes, false),
+                Err(error) => {
+                    log::error!("Gagal mengirim frame biner: {:?}", error);
+                    link.send_message(Msg::Error(error.into()));
+                }
+            }
+        });
+    }
+
+    // Nyalakan detak per detik hanya jika ada hitung mundur (room sementara, cooldown slow mode, pesan sementara, pemeliharaan)
+    fn update_ticker(&mut self, ctx: &Context<Self>) {
+        let room_expires = self.chat.current_room.as_ref().is_some_and(|room| room.expires_at.is_some());
+        let messages_expire = self.chat.messages.next_expiry().is_some();
+        if self.page_hidden || (!room_expires && !messages_expire && self.send_cooldown_until.is_none() && self.maintenance.is_none()) {
+            self.ticker = None; // Hitung mundur tidak perlu diperbarui selama tidak terlihat
+        } else if self.ticker.is_none() {
+            let link = ctx.link().clone();
+            self.ticker = Some(Interval::new(1_000, move || link.send_message(Msg::Tick)));
+        }
+    }
+
+    // Tab tersembunyi atau user lama tidak berinteraksi
+    fn is_idle(&self) -> bool {
+        self.page_hidden || self.activity.idle_secs() >= IDLE_PAUSE_SECS
+    }
+
+    fn update_timestamp_refresh(&mut self, ctx: &Context<Self>) {
+        if self.is_idle() {
+            self.timestamp_refresh = None;
+        } else if self.timestamp_refresh.is_none() {
+            let link = ctx.link().clone();
+            self.timestamp_refresh = Some(Interval::new(TIMESTAMP_REFRESH_MS, move || link.send_message(Msg::RefreshTimestamps)));
+        }
+    }
+
+    // Selama tab tersembunyi perubahan tetap masuk ke state, tetapi baru dirender saat tab terlihat lagi
+    // (VisibilityChanged selalu me-render ulang). Di mode ringan perubahan dikumpulkan dan dirender sekaligus.
+    fn render_when_visible(&mut self, ctx: &Context<Self>, changed: bool) -> bool {
+        if !changed || self.page_hidden {
+            return false;
+        }
+        if !self.lite_mode {
+            return true;
+        }
+        if self.render_batch.is_none() {
+            let link = ctx.link().clone();
+            self.render_batch = Some(Timeout::new(LITE_RENDER_BATCH_MS, move || link.send_message(Msg::FlushRender)));
+        }
+        false
+    }
+
+    // Hitung ulang mode ringan setelah pengaturan atau status baterai berubah
+    fn update_lite_mode(&mut self) -> bool {
+        let lite = self.settings.lite_mode.resolve(self.low_battery || device::is_low_end_hardware());
+        appearance::set_lite_mode(lite);
+        if lite == self.lite_mode {
+            return false;
+        }
+        self.lite_mode = lite;
+        self.virtual_list.set_overscan(if lite { LITE_OVERSCAN_PX } else { OVERSCAN_PX });
+        self.render_batch = None;
+        true
+    }
+
+    fn reduced_motion(&self) -> bool {
+        self.lite_mode || appearance::reduced_motion(&self.settings)
+    }
+
+    // Pasang timer tepat pada kedaluwarsa pesan berikutnya; tick per detik hanya untuk tampilan
+    fn schedule_expiry(&mut self, ctx: &Context<Self>) {
+        let Some(next) = self.chat.messages.next_expiry() else {
+            self.expiry_timer = None;
+            return;
+        };
+        if self.expiry_timer.as_ref().is_some_and(|(at, _)| *at <= next) {
+            return;
+        }
+        let delay = (self.clock.to_local(next) - js_sys::Date::now()).max(0.0).ceil() as u32;
+        let link = ctx.link().clone();
+        self.expiry_timer = Some((next, Timeout::new(delay, move || link.send_message(Msg::ExpireMessages))));
+    }
+
+    fn schedule_typing_expiry(&mut self, ctx: &Context<Self>) {
+        let Some(next) = self.typing.next_change(js_sys::Date::now()) else {
+            self.typing_timer = None;
+            return;
+        };
+        let delay = (next - js_sys::Date::now()).max(0.0).ceil() as u32;
+        let link = ctx.link().clone();
+        self.typing_timer = Some(Timeout::new(delay, move || link.send_message(Msg::TypingExpired)));
+    }
+
+    // Laporkan pesan terbaru yang sudah dirender di room ini, hanya jika lebih baru dari yang terakhir dilaporkan.
+    // Tidak untuk tab tersembunyi, chat terkunci, room anonim/kotak saran, atau jika tanda dibaca dimatikan.
+    fn mark_read(&mut self, ctx: &Context<Self>) {
+        let hidden_room = self.chat.current_room.as_ref().is_some_and(|room| room.is_anonymous() || room.feedback_box);
+        if self.page_hidden || self.vault_locked || self.settings.privacy.disable_read_receipts || hidden_room || self.chat.direct.open_peer().is_some() || !self.client.is_connected() {
+            return;
+        }
+        let messages = &self.shown().messages;
+        let Some(message_id) = messages.entries()[self.rendered_entries()]
+            .iter()
+            .rev()
+            .flat_map(|entry| entry.repeats.iter().rev().chain(std::iter::once(&entry.first)))
+            .find_map(|stored| stored.message.id.clone())
+        else {
+            return;
+        };
+        let room = self.chat.current_name().to_string();
+        let previous = self.read_sent.get(&room).and_then(|previous| messages.position(previous));
+        if previous.is_some_and(|previous| Some(previous) >= messages.position(&message_id)) {
+            return;
+        }
+        let command = ClientCommand::MarkRead { room: Some(room.clone()).filter(|room| !room.is_empty()), message_id: message_id.clone() };
+        if self.send_command(ctx, &command) {
+            self.read_sent.insert(room, message_id);
+        }
+    }
+
+    // Operator akun bersama dari pengaturan, hanya jika server sudah menerima Capability::Operators
+    fn current_operator(&self) -> Option<Operator> {
+        Some(&self.settings.operator_name).filter(|_| self.operators_accepted).and_then(|name| Operator::from_name(name))
+    }
+
+    // Umumkan bahwa user sedang mengetik, paling sering sekali per typing::SEND_INTERVAL_MS. Tidak untuk
+    // slash command, pesan langsung, room anonim/kotak saran, atau jika user menyembunyikan status mengetik.
+    fn announce_typing(&mut self, ctx: &Context<Self>) {
+        let hidden_room = self.chat.current_room.as_ref().is_some_and(|room| room.is_anonymous() || room.feedback_box);
+        if self.current_input.is_empty() || self.current_input.starts_with('/') || self.settings.privacy.hide_typing || hidden_room || self.chat.direct.open_peer().is_some() || !self.client.is_connected() {
+            return;
+        }
+        let room = self.chat.current_name().to_string();
+        if self.typing.should_send(&room, js_sys::Date::now()) {
+            let command = ClientCommand::Typing { room: Some(room).filter(|room| !room.is_empty()), operator: self.current_operator() };
+            self.send_command(ctx, &command);
+        }
+    }
+
+    // Sisa detik cooldown slow mode, None jika sudah boleh mengirim lagi
+    fn send_cooldown_remaining(&self) -> Option<u32> {
+        let until = self.send_cooldown_until?;
+        let remaining = ((until - js_sys::Date::now()) / 1000.0).ceil();
+        (remaining > 0.0).then_some(remaining as u32)
+    }
+
+    // Mulai cooldown setelah mengirim pesan; `secs` diisi saat server menolak pesan karena slow mode
+    fn start_slow_mode_cooldown(&mut self, ctx: &Context<Self>, secs: Option<u32>) {
+        let secs = secs.unwrap_or_else(|| self.chat.current_room.as_ref().map_or(0, |room| room.slow_mode_secs));
+        if secs > 0 {
+            self.send_cooldown_until = Some(js_sys::Date::now() + f64::from(secs) * 1000.0);
+            self.update_ticker(ctx);
+        }
+    }
+
+    // Baca chunk berikutnya dari file di background, lalu kirim lewat Msg::UploadChunkRead
+    fn read_next_chunk(&mut self, ctx: &Context<Self>, upload_id: &str) {
+        let Some(upload) = self.uploads.get_mut(upload_id) else { return };
+        if upload.is_finished() || upload.status != UploadStatus::Uploading {
+            return;
+        }
+        upload.in_flight = true;
+        let (blob, offset, upload_id) = (upload.blob.clone(), upload.acked, upload.id.clone());
+        let link = ctx.link().clone();
+        spawn_local(async move {
+            match uploads::read_chunk(blob, offset).await {
+                Ok(data) => link.send_message(Msg::UploadChunkRead { upload_id, offset, data }),
+                Err(error) => link.send_message(Msg::UploadChunkFailed { upload_id, error }),
+            }
+        });
+    }
+
+    fn ingest(&mut self, ctx: &Context<Self>, msg: ChatMessage, raw: Option<String>, encoding: Encoding) {
+        let expires = msg.expires_at.is_some();
+        if !self.chat.is_own(&msg) {
+            self.notify(&msg);
+        }
+        #[cfg(feature = "dev")]
+        self.receipts.record(msg.id.as_deref(), raw.as_deref(), encoding.label(), js_sys::Date::now(), self.clock.now());
+        #[cfg(not(feature = "dev"))]
+        let _ = encoding; // Hanya dicatat di tanda terima build `dev`
+        self.history.record(std::slice::from_ref(&msg), self.settings.history_limit);
+        let room = msg.room.as_deref().unwrap_or(self.chat.current_name());
+        let auto_translate = msg.id.clone()
+            .filter(|_| self.settings.auto_translate_rooms.iter().any(|name| name == room))
+            .and_then(|message_id| Some((message_id, self.translation_target(&msg, &TextProfile::detect(&msg.text))?)));
+        if let Some((message_id, source)) = auto_translate {
+            self.request_translation(ctx, message_id, source, self.settings.language());
+        }
+        if let Some(user_id) = &msg.user_id {
+            let operator = msg.operator.as_deref();
+            if self.typing.stopped(msg.room.as_deref().unwrap_or(""), user_id, operator) {
+                self.schedule_typing_expiry(ctx);
+            }
+        }
+        self.dispatch(Action::MessageReceived { message: msg, raw, now_ms: self.clock.now() });
+        if expires {
+            self.schedule_expiry(ctx);
+            self.update_ticker(ctx);
+        }
+    }
+
+    // Bahasa sumber pesan ini jika berbeda dari bahasa user, artinya pesan perlu ditawari terjemahan.
+    // Terjemahan hanya ditawarkan di room yang bahasanya ditentukan; bahasa hasil deteksi lebih
+    // diutamakan daripada bahasa room, jadi pesan yang memang ditulis dalam bahasa user dilewati.
+    fn translation_target(&self, msg: &ChatMessage, profile: &TextProfile) -> Option<String> {
+        if msg.id.is_none() || msg.text.is_empty() || msg.system || msg.user_id.as_deref() == Some(self.user_id.as_str()) {
+            return None;
+        }
+        let room = self.chat.joined_room(msg.room.as_deref().unwrap_or(self.chat.current_name()))?;
+        let room_language = room.language.as_deref()?;
+        let source = profile.language.unwrap_or(room_language);
+        (source != self.settings.language()).then(|| source.to_string())
+    }
+
+    fn request_translation(&mut self, ctx: &Context<Self>, message_id: String, source: String, target: String) {
+        if !self.translations.request(&message_id, &target) {
+            return;
+        }
+        if !self.send_command(ctx, &ClientCommand::Translate { message_id: message_id.clone(), target, source: Some(source) }) {
+            self.translations.cancel(&message_id);
+        }
+    }
+
+    // Room yang ditampilkan berganti (bergabung, berpindah, atau keluar): muat ulang data milik room itu
+    fn enter_room(&mut self, ctx: &Context<Self>) {
+        let room = self.chat.current_name().to_string();
+        // Sidebar mulai dari halaman pertama; halaman berikutnya dimuat saat digulir
+        self.members = MemberList::new(room.clone(), String::new());
+        self.member_search = None;
+        // Perintah bot berlaku per room
+        self.bot_commands.clear();
+        self.refresh_command_hints();
+        self.highlighted_entry = None; // Index entry milik daftar pesan room sebelumnya
+        self.scroll_follow.stick();
+        self.dispatch(Action::DirectOpened(None)); // User memilih room, jadi daftar pesan room yang ditampilkan
+        self.send_cooldown_until = None;
+        self.schedule_expiry(ctx);
+        self.send_command(ctx, &ClientCommand::SyncRoster { room: room.clone() });
+        self.request_notes(ctx);
+        #[cfg(feature = "crypto")]
+        self.announce_e2e(ctx, &room);
+        if !room.is_empty() {
+            ctx.link().send_message(Msg::LoadMoreMembers);
+            self.send_command(ctx, &ClientCommand::ListCommands { room });
+        }
+        self.update_ticker(ctx);
+    }
+
+    // Payload kosong: server membalas snapshot notepad room yang ditampilkan
+    fn request_notes(&self, ctx: &Context<Self>) {
+        let room = Some(self.chat.current_name().to_string()).filter(|room| !room.is_empty());
+        self.send_command(ctx, &ClientCommand::Custom { room, namespace: NOTES_NAMESPACE.to_string(), payload: "[]".to_string(), key: None });
+    }
+
+    // Suntingan langsung digabung ke notepad lokal; server meneruskannya ke anggota lain, atau
+    // mengembalikan versi yang berlaku jika suntingan lain menang
+    fn edit_note(&mut self, ctx: &Context<Self>, id: Option<String>, text: String, deleted: bool) -> bool {
+        let notes = &self.chat.notes;
+        let existing = id.as_deref().and_then(|id| notes.get(id));
+        if !deleted && existing.is_some_and(|note| note.text == text) {
+            return false;
+        }
+        let entry = NoteEntry {
+            id: id.unwrap_or_else(notes::new_note_id),
+            text,
+            deleted,
+            created_at: existing.map_or_else(|| self.clock.now(), |note| note.created_at),
+            clock: notes.next_clock(),
+            author: self.user_id.clone(),
+        };
+        let room = self.chat.current_name().to_string();
+        let payload = serde_json::to_string(&[&entry]).unwrap_or_default();
+        let command = ClientCommand::Custom { room: Some(room.clone()).filter(|room| !room.is_empty()), namespace: NOTES_NAMESPACE.to_string(), payload, key: None };
+        if !self.send_command(ctx, &command) {
+            self.error = Some(ChatError::not_connected());
+            return true;
+        }
+        self.dispatch(Action::NotesMerged { room, entries: vec![entry] })
+    }
+
+    // Kunci publik (dan kunci pengirim untuk anggota yang sudah dikenal) ke `room`, jika enkripsi aktif di sana
+    #[cfg(feature = "crypto")]
+    fn announce_e2e(&self, ctx: &Context<Self>, room: &str) {
+        for envelope in self.e2e.handshake(room) {
+            self.send_e2e(ctx, room, &envelope);
+        }
+    }
+
+    // Key transient: frame yang belum terkirim diganti versi terbarunya
+    #[cfg(feature = "crypto")]
+    fn send_e2e(&self, ctx: &Context<Self>, room: &str, envelope: &e2e::Envelope) {
+        let key = match envelope {
+            e2e::Envelope::Announce { .. } => "announce".to_string(),
+            e2e::Envelope::SenderKey { to, .. } => format!("key:{}", to),
+        };
+        let payload = serde_json::to_string(envelope).unwrap_or_default();
+        let command = ClientCommand::Custom { room: Some(room.to_string()).filter(|room| !room.is_empty()), namespace: e2e::E2E_NAMESPACE.to_string(), payload, key: Some(key) };
+        self.send_command(ctx, &command);
+    }
+
+    #[cfg(feature = "crypto")]
+    fn view_e2e(&self, ctx: &Context<Self>) -> Html {
+        let room = self.chat.current_name();
+        let peers: Vec<PeerRow> = self
+            .e2e
+            .peers(room)
+            .into_iter()
+            .map(|peer| PeerRow {
+                name: self.chat.directory.name_of(&peer.user_id).unwrap_or(&peer.user_id).to_string(),
+                user_id: peer.user_id,
+                fingerprint: peer.fingerprint,
+                has_sender_key: peer.has_sender_key,
+                changed_fingerprint: peer.changed_fingerprint,
+            })
+            .collect();
+        // Di room anonim pertukaran kunci akan membuka user id pengirim
+        let disabled = !self.is_connected || self.chat.current_room.as_ref().is_some_and(RoomState::hides_identity);
+        html! {
+            <E2ePanel enabled={self.e2e.is_enabled(room)} fingerprint={self.e2e.own_fingerprint(room)} {peers} {disabled} on_toggle={ctx.link().callback(Msg::SetE2e)} on_verify={ctx.link().callback(Msg::VerifyE2ePeer)} />
+        }
+    }
+
+    #[cfg(not(feature = "crypto"))]
+    fn view_e2e(&self, _ctx: &Context<Self>) -> Html {
+        html! {}
+    }
+
+    fn note_items(&self) -> Vec<NoteItem> {
+        let chat = self.shown();
+        chat.notes
+            .visible()
+            .into_iter()
+            .map(|note| NoteItem {
+                id: note.id.clone(),
+                text: note.text.clone(),
+                edited_by: chat.directory.name_of(&note.author).unwrap_or(&note.author).to_string(),
+            })
+            .collect()
+    }
+
+    // Notifikasi browser untuk pesan orang lain; room yang dibisukan dilewati oleh `notify`
+    fn notify(&mut self, msg: &ChatMessage) {
+        let sender = self.chat.directory.display_name(msg);
+        let joined = match &msg.room {
+            Some(room) => self.chat.joined_room(room),
+            None => self.chat.current_room.as_ref(),
+        };
+        let (room, title) = match joined {
+            Some(room) if room.dm_peer.is_some() => (room.name.as_str(), trf("Pesan dari {}", &[&sender])),
+            Some(room) => (room.name.as_str(), trf("{} di {}", &[&sender, &room.name])),
+            None => ("", trf("{} di room umum", &[&sender])),
+        };
+        let room = room.to_string();
+        notify::notify_message(&self.snoozes, &room, &title, &msg.text, js_sys::Date::now());
+        self.count_unread(&room);
+    }
+
+    // Hitungan di judul tab hanya selama user tidak sedang melihat tab ini; room yang dibisukan tidak dihitung
+    fn count_unread(&mut self, room: &str) {
+        if (self.page_hidden || !self.window_focused) && self.snoozes.snoozed_until(room, js_sys::Date::now()).is_none() {
+            self.unread_title.bump();
+        }
+    }
+
+    fn run_slash(&mut self, ctx: &Context<Self>, invocation: SlashInvocation) -> bool {
+        match invocation {
+            SlashInvocation::Join(room) => {
+                self.room_input = room;
+                Component::update(self, ctx, Msg::JoinRoom)
+            }
+            SlashInvocation::Create(room) => {
+                self.room_input = room;
+                Component::update(self, ctx, Msg::CreateRoom)
+            }
+            SlashInvocation::Nick(name) => {
+                self.username_input = name;
+                Component::update(self, ctx, Msg::SetUsername)
+            }
+            SlashInvocation::SlowMode(secs) => {
+                if !self.chat.current_room.as_ref().is_some_and(|room| room.is_moderator) {
+                    self.error = Some(ChatError::Validation(tr("Hanya moderator yang bisa mengatur slow mode.").to_string()));
+                    return true;
+                }
+                Component::update(self, ctx, Msg::SetSlowMode(secs))
+            }
+            SlashInvocation::Canned(name) => Component::update(self, ctx, Msg::InsertCanned(name)),
+            SlashInvocation::Event(arg) => {
+                let Some(room) = self.chat.current_room.as_ref().map(|room| room.name.clone()) else {
+                    self.error = Some(ChatError::Validation(tr("Bergabunglah ke room dulu untuk menjadwalkan acara.").to_string()));
+                    return true;
+                };
+                match schedule::parse_event_args(&arg, js_sys::Date::now()) {
+                    Ok((starts_at, title)) => {
+                        if !self.send_command(ctx, &ClientCommand::CreateEvent { room, title, starts_at }) {
+                            self.error = Some(ChatError::not_connected());
+                        }
+                    }
+                    Err(e) => self.error = Some(ChatError::Validation(e)),
+                }
+                true
+            }
+            SlashInvocation::Bot { name, args } => {
+                let Some(room) = self.chat.current_room.as_ref().map(|room| room.name.clone()) else { return false };
+                if !self.send_command(ctx, &ClientCommand::RunCommand { room, name, args }) {
+                    self.error = Some(ChatError::not_connected());
+                }
+                true
+            }
+            SlashInvocation::Literal(_) | SlashInvocation::Whisper { .. } => false, // Ditangani SendMessage
+        }
+    }
+
+    // Penerima `/whisper @handle`: user online di room saat ini, dicocokkan seperti @mention. Room besar tanpa
+    // roster lengkap memakai direktori lokal; server tetap memeriksa penerimanya ada di room.
+    fn whisper_target(&self, handle: &str) -> Result<String, String> {
+        if self.chat.current_room.as_ref().is_some_and(RoomState::hides_identity) {
+            return Err(tr("Bisikan tidak tersedia di room anonim dan kotak saran.").to_string());
+        }
+        let users: Vec<UserMatch> = match self.chat.presence.roster(self.chat.current_name()) {
+            Some(roster) => roster
+                .users
+                .iter()
+                .map(|user| UserMatch { user_id: user.user_id.clone(), name: self.chat.directory.name_of(&user.user_id).unwrap_or(&user.name).to_string() })
+                .collect(),
+            None => self.chat.directory.matching(handle).map(|(user_id, name)| UserMatch { user_id: user_id.to_string(), name: name.to_string() }).collect(),
+        };
+        match mentions::resolve_handle(users, handle) {
+            Some(user) if user.user_id == self.user_id => Err(tr("Tidak bisa berbisik ke diri sendiri.").to_string()),
+            Some(user) => Ok(user.user_id),
+            None => Err(trf("@{} tidak sedang online di room ini.", &[&handle])),
+        }
+    }
+
+    // Isi palet perintah: room terakhir, pesan langsung, aksi pengaturan dan slash command
+    fn palette_items(&self) -> Vec<PaletteItem> {
+        let current = self.chat.current_room.as_ref().map(|room| room.name.as_str());
+        let rooms = self.recent_rooms.iter().filter(|recent| Some(recent.name.as_str()) != current).map(|recent| {
+            let action = PaletteAction::SwitchRoom(recent.name.clone());
+            match &recent.dm_peer {
+                Some(peer) => {
+                    let name = self.chat.directory.name_of(peer).unwrap_or(peer);
+                    PaletteItem::new(CommandGroup::Direct, name, action).with_hint(recent.name.clone())
+                }
+                None => PaletteItem::new(CommandGroup::Room, recent.name.clone(), action),
+            }
+        });
+        let direct = self.chat.direct.conversations().iter().map(|conversation| {
+            let name = self.chat.directory.name_of(&conversation.peer).unwrap_or(&conversation.peer);
+            PaletteItem::new(CommandGroup::Direct, name, PaletteAction::OpenDirect(conversation.peer.clone())).with_hint(tr("Pesan langsung"))
+        });
+        let mut settings = vec![
+            PaletteItem::new(CommandGroup::Settings, tr("Buka pengaturan"), PaletteAction::OpenSettings),
+            PaletteItem::new(CommandGroup::Settings, tr("Tampilkan perangkat yang login"), PaletteAction::ListSessions),
+            PaletteItem::new(CommandGroup::Settings, tr("Ekspor data saya"), PaletteAction::RequestExport),
+            PaletteItem::new(CommandGroup::Settings, tr("Mulai tur pengenalan"), PaletteAction::StartTour),
+        ];
+        if self.vault_unlocked {
+            settings.push(PaletteItem::new(CommandGroup::Settings, tr("Kunci chat"), PaletteAction::LockChat));
+        }
+        if !self.is_connected {
+            settings.push(PaletteItem::new(CommandGroup::Settings, tr("Hubungkan ulang"), PaletteAction::Reconnect));
+        }
+        rooms
+            .chain(direct)
+            .chain(commands::canned_items(&self.settings.canned_responses))
+            .chain(settings)
+            .chain(commands::slash_items(&self.bot_commands))
+            .collect()
+    }
+
+    // Satu-satunya jalur perubahan ChatState. Pada build `dev` tiap aksi dicatat untuk time-travel.
+    fn dispatch(&mut self, action: Action) -> bool {
+        #[cfg(feature = "dev")]
+        let (name, detail) = (action.name(), format!("{:?}", action));
+        let changed = state::reduce(&mut self.chat, action);
+        #[cfg(feature = "dev")]
+        self.time_travel.record(name, detail, &self.chat);
+        changed
+    }
+
+    // State yang dirender: snapshot time-travel jika sedang ditinjau, selain itu state terbaru
+    #[cfg(feature = "dev")]
+    fn shown(&self) -> &ChatState {
+        self.time_travel.snapshot().unwrap_or(&self.chat)
+    }
+
+    #[cfg(not(feature = "dev"))]
+    fn shown(&self) -> &ChatState {
+        &self.chat
+    }
+
+    fn handle_server_event(&mut self, ctx: &Context<Self>, event: ServerEvent) -> bool {
+        match event {
+            ServerEvent::HistoryReplay { messages, total_missed } => {
+                self.history.record(&messages, self.settings.history_limit);
+                self.dispatch(Action::HistoryReplayed { messages, total_missed, now_ms: self.clock.now() });
+                self.schedule_expiry(ctx);
+                self.update_ticker(ctx);
+            }
+            ServerEvent::UploadOffset { upload_id, offset, binary } => {
+                let Some(upload) = self.uploads.get_mut(&upload_id) else { return false };
+                upload.acked = offset.min(upload.size);
+                upload.binary = binary;
+                upload.in_flight = false;
+                self.read_next_chunk(ctx, &upload_id);
+            }
+            ServerEvent::UploadComplete { upload_id, url } => {
+                let Some(upload) = self.uploads.remove(&upload_id) else { return false };
+                let message = ChatMessage {
+                    id: None,
+                    client_id: Some(outbox::new_client_id()),
+                    user_id: Some(self.user_id.clone()),
+                    username: self.username.clone(),
+                    text: String::new(),
+                    timestamp: Some(self.clock.now()),
+                    edited_at: None,
+                    attachment: Some(Attachment {
+                        url,
+                        name: upload.name,
+                        mime: upload.mime,
+                        size: upload.size,
+                        poster: upload.poster,
+                        rating: None,
+                    }),
+                    expires_at: None,
+                    event: None,
+                    system: false,
+                    components: Vec::new(),
+                    room: self.chat.current_room.as_ref().map(|room| room.name.clone()),
+                    operator: self.current_operator().map(Box::new),
+                    whisper_to: None,
+                };
+                if !self.send_chat(ctx, &message) {
+                    self.outbox.push(message);
+                }
+            }
+            ServerEvent::UploadFailed { upload_id, reason } => {
+                let Some(upload) = self.uploads.get_mut(&upload_id) else { return false };
+                upload.status = UploadStatus::Rejected(reason);
+                upload.in_flight = false;
+            }
+            ServerEvent::HistoryResponse { room, messages, has_more, .. } => {
+                let room = room.unwrap_or_default();
+                if self.older_request.as_ref() == Some(&room) {
+                    self.older_request = None;
+                }
+                let shown = self.chat.current_name() == room && self.chat.direct.open_peer().is_none();
+                let list = self.messages_ref.cast::<web_sys::Element>().filter(|_| shown && !self.scroll_follow.following());
+                if let Some(list) = &list {
+                    self.virtual_list.hold_position(list);
+                }
+                let before = self.chat.messages.len();
+                self.dispatch(Action::OlderHistoryLoaded { room, messages, has_more, now_ms: self.clock.now() });
+                let inserted = if shown { self.chat.messages.len() - before } else { 0 };
+                self.virtual_list.prepended(self.shown().messages.entries(), inserted);
+                self.scroll_follow.prepended(inserted);
+                self.highlighted_entry = self.highlighted_entry.map(|index| index + inserted);
+                self.schedule_expiry(ctx);
+                self.update_ticker(ctx);
+            }
+            ServerEvent::HistoryPage { messages } => {
+                if !self.dispatch(Action::HistoryPageLoaded { messages, now_ms: self.clock.now() }) {
+                    return false;
+                }
+                self.schedule_expiry(ctx);
+                self.update_ticker(ctx);
+            }
+            ServerEvent::Pong { .. } => return false, // Ditangani heartbeat ChatClient
+            ServerEvent::Unknown => return false, // Sudah disaring loop baca ChatClient
+            ServerEvent::ReadReceipt { room, user_id, message_id } => {
+                return self.dispatch(Action::ReadReceiptReceived { room, user_id, message_id });
+            }
+            ServerEvent::Custom { room, namespace, payload, .. } if namespace == NOTES_NAMESPACE => {
+                return match serde_json::from_str::<Vec<NoteEntry>>(&payload) {
+                    Ok(entries) => self.dispatch(Action::NotesMerged { room, entries }),
+                    Err(e) => {
+                        log::warn!("Catatan room rusak dari server: {}", e);
+                        false
+                    }
+                };
+            }
+            #[cfg(feature = "crypto")]
+            ServerEvent::Custom { room, user_id, namespace, payload } if namespace == e2e::E2E_NAMESPACE => {
+                match serde_json::from_str::<e2e::Envelope>(&payload) {
+                    Ok(envelope) => {
+                        for reply in self.e2e.receive(&room, &user_id, &self.user_id, envelope) {
+                            self.send_e2e(ctx, &room, &reply);
+                        }
+                    }
+                    Err(e) => log::warn!("Frame kunci enkripsi dari {} rusak: {}", user_id, e),
+                }
+                return room == self.chat.current_name(); // Daftar sidik jari di panel
+            }
+            ServerEvent::Custom { room, user_id, namespace, payload } => {
+                let event = CustomEvent { room, user_id, namespace: namespace.clone(), payload };
+                if !ctx.props().plugins.dispatch(event) {
+                    log::debug!("Tidak ada handler untuk payload Custom {}", namespace);
+                }
+                return false;
+            }
+            ServerEvent::CapabilitiesAccepted { capabilities } => {
+                self.operators_accepted = capabilities.contains(&Capability::Operators);
+                return false;
+            }
+            ServerEvent::Typing { room, user_id, name, operator } => {
+                // Tab lain milik user ini sendiri; operator lain di akun bersama tetap ditampilkan
+                let mine = self.current_operator();
+                if user_id == self.user_id && operator.as_ref().map(|o| &o.name) == mine.as_ref().map(|o| &o.name) {
+                    return false;
+                }
+                self.typing.record(room, user_id, name, operator, js_sys::Date::now());
+                self.schedule_typing_expiry(ctx);
+            }
+            ServerEvent::EncodingSelected { encoding } => {
+                log::info!("Server memilih format frame {}", encoding.label());
+                self.client.set_encoding(encoding);
+                return false;
+            }
+            ServerEvent::TimeSync { client_time, server_time } => {
+                let received_at = js_sys::Date::now();
+                self.stats.record_latency(received_at - client_time);
+                self.clock.observe(client_time, server_time, received_at);
+                // Jadwal ulang dengan selisih jam yang baru
+                self.expiry_timer = None;
+                self.schedule_expiry(ctx);
+                return false;
+            }
+            ServerEvent::RoomJoined { room, expires_at, is_moderator, slow_mode_secs, rules, rules_accepted, embeds_disabled, dm_peer, pseudonym, message_ttl_secs, presence_digest_secs, feedback_box, language } => {
+                let dm_peer_for_recent = dm_peer.clone();
+                self.dispatch(Action::RoomJoined(RoomState {
+                    is_moderator,
+                    slow_mode_secs,
+                    rules,
+                    rules_accepted,
+                    embeds_disabled,
+                    dm_peer,
+                    pseudonym,
+                    message_ttl_secs,
+                    presence_digest_secs,
+                    feedback_box,
+                    language,
+                    ..RoomState::new(room.clone(), expires_at)
+                }));
+                self.enter_room(ctx);
+                self.recent_rooms.retain(|recent| recent.name != room);
+                self.recent_rooms.insert(0, RecentRoom { name: room, dm_peer: dm_peer_for_recent });
+                self.recent_rooms.truncate(room::MAX_RECENT_ROOMS);
+                self.room_input.clear();
+                self.room_error = None;
+            }
+            ServerEvent::DirectMessage { to, message } => {
+                if message.user_id.as_deref() != Some(self.user_id.as_str()) {
+                    let sender = self.chat.directory.display_name(&message);
+                    let tag = format!("@{}", message.user_id.as_deref().unwrap_or_default());
+                    notify::notify_message(&self.snoozes, &tag, &trf("Pesan langsung dari {}", &[&sender]), &message.text, js_sys::Date::now());
+                    self.count_unread(&tag);
+                }
+                return self.dispatch(Action::DirectMessageReceived { to, message: *message });
+            }
+            ServerEvent::SupportQueue { tickets } => {
+                self.support.replace(tickets);
+            }
+            ServerEvent::SupportTicket { ticket } => {
+                let guest = ticket.guest.clone();
+                if self.support.upsert(ticket, &self.user_id) {
+                    return Component::update(self, ctx, Msg::OpenDirect(guest)); // Klaim berhasil: langsung ke percakapannya
+                }
+            }
+            ServerEvent::SupportTicketClosed { guest } => {
+                self.support.remove(&guest);
+            }
+            ServerEvent::RoomLeft { room } => {
+                let was_current = self.chat.current_name() == room;
+                if !self.dispatch(Action::RoomLeft { room }) {
+                    return false;
+                }
+                if was_current {
+                    self.enter_room(ctx);
+                }
+            }
+            ServerEvent::RoomExpiryUpdated { room, expires_at } => {
+                if !self.dispatch(Action::RoomExpiryUpdated { room, expires_at }) {
+                    return false;
+                }
+                self.update_ticker(ctx);
+            }
+            ServerEvent::PseudonymRotated { room, pseudonym } => {
+                return self.dispatch(Action::PseudonymRotated { room, pseudonym });
+            }
+            ServerEvent::RoomExpired { room } => {
+                let was_current = self.chat.current_name() == room;
+                if !self.dispatch(Action::RoomExpired { room: room.clone() }) {
+                    return false;
+                }
+                self.room_error = Some(trf("Room \"{}\" telah kedaluwarsa dan diarsipkan.", &[&room]));
+                if was_current {
+                    self.enter_room(ctx);
+                }
+                self.update_ticker(ctx);
+            }
+            ServerEvent::SlowModeChanged { room, interval_secs } => {
+                if !self.dispatch(Action::SlowModeChanged { room, interval_secs }) {
+                    return false;
+                }
+                if interval_secs == 0 {
+                    self.send_cooldown_until = None;
+                }
+            }
+            ServerEvent::EmbedsChanged { room, disabled } => {
+                return self.dispatch(Action::EmbedsChanged { room, disabled });
+            }
+            ServerEvent::RoomLanguageChanged { room, language } => {
+                return self.dispatch(Action::RoomLanguageChanged { room, language });
+            }
+            ServerEvent::Translated { message_id, target, text } => {
+                self.translations.resolve(message_id, target, text);
+            }
+            ServerEvent::TranslationFailed { message_id, reason } => {
+                self.translations.fail(message_id, reason.unwrap_or_else(|| tr("Terjemahan tidak tersedia").to_string()));
+            }
+            ServerEvent::ExportReady { url, expires_at } => {
+                log::info!("Arsip ekspor siap, berlaku sampai {:?}", expires_at);
+                self.export_pending = false;
+                self.export_url = Some(url);
+            }
+            ServerEvent::Sessions { sessions } => {
+                self.sessions = Some(sessions);
+            }
+            ServerEvent::EventUpdated { event } => {
+                return self.dispatch(Action::EventUpdated(event));
+            }
+            ServerEvent::ComponentsUpdated { message_id, components } => {
+                let answered = self.pending_interactions.remove(&message_id).is_some();
+                return self.dispatch(Action::ComponentsUpdated { message_id, components }) || answered;
+            }
+            ServerEvent::Commands { room, commands } => {
+                if self.chat.current_room.as_ref().is_none_or(|current| current.name != room) {
+                    return false;
+                }
+                self.bot_commands = commands;
+                self.refresh_command_hints();
+            }
+            ServerEvent::UserSearchResults { prefix, users } => {
+                self.mention_search.store(prefix, users);
+            }
+            ServerEvent::UserSearchRejected { prefix, retry_after_secs } => {
+                self.mention_search.cancel();
+                // Coba lagi setelah jeda dari server, hanya jika user masih mengetik prefix yang sama
+                let link = ctx.link().clone();
+                self.mention_search_timer = Some(Timeout::new(retry_after_secs.saturating_mul(1000), move || link.send_message(Msg::SearchMentions(prefix))));
+                return false;
+            }
+            ServerEvent::MemberPage { room, query, members, next_cursor, total } => {
+                if !self.members.append(&room, query.as_deref(), &members, next_cursor, total) {
+                    return false;
+                }
+                self.dispatch(Action::MembersLoaded { room, members });
+            }
+            ServerEvent::IceServers { servers, ttl_secs } => {
+                self.ice_refresh = self.config.apply_ice_servers(servers, ttl_secs).map(|delay_ms| {
+                    let link = ctx.link().clone();
+                    Timeout::new(delay_ms, move || link.send_message(Msg::RefreshIceServers))
+                });
+            }
+            ServerEvent::TwoFactorSetup { secret, otpauth_url, recovery_codes } => {
+                self.two_factor_error = None;
+                self.two_factor_step = Some(TwoFactorStep::Enrolling(Enrollment { secret, otpauth_url, recovery_codes }));
+            }
+            ServerEvent::TwoFactorEnabled => {
+                self.two_factor_enabled = true;
+                if matches!(self.two_factor_step, Some(TwoFactorStep::Enrolling(_))) {
+                    self.two_factor_step = None;
+                }
+            }
+            ServerEvent::TwoFactorDisabled => {
+                self.two_factor_enabled = false;
+                self.two_factor_step = None;
+            }
+            ServerEvent::TwoFactorRequired => {
+                self.two_factor_error = None;
+                self.two_factor_step = Some(TwoFactorStep::Challenge);
+            }
+            ServerEvent::TwoFactorAccepted => {
+                self.two_factor_enabled = true;
+                self.two_factor_step = None;
+                self.two_factor_error = None;
+            }
+            ServerEvent::TwoFactorRejected { attempts_left } => {
+                self.two_factor_error = Some(match attempts_left {
+                    Some(0) => tr("Kode salah. Terlalu banyak percobaan, coba lagi nanti.").to_string(),
+                    Some(n) => trf("Kode salah. Sisa percobaan: {}.", &[&n]),
+                    None => tr("Kode salah.").to_string(),
+                });
+            }
+            ServerEvent::AuthResult { ok: true, user_id, name, .. } => {
+                ctx.link().send_message(Msg::Authenticated { user_id, name });
+                return false;
+            }
+            ServerEvent::AuthResult { ok: false, reason, .. } => {
+                ctx.link().send_message(Msg::AuthFailed(reason));
+                return false;
+            }
+            ServerEvent::AuthRequired => {
+                // Jawaban untuk token yang sedang diperiksa masih akan datang
+                if !matches!(self.auth, AuthState::Authenticating { .. }) {
+                    self.auth = AuthState::LoginRequired { error: None };
+                }
+            }
+            ServerEvent::SessionRevoked { reason } => {
+                // Putus paksa: tutup koneksi dari sisi klien juga, tanpa menunggu server
+                self.client.close();
+                self.is_connected = false;
+                self.uploads.pause_all();
+                self.sessions = None;
+                self.error = Some(ChatError::Auth(AuthError::SessionRevoked { reason }));
+            }
+            ServerEvent::Maintenance { until, message } => {
+                let until = self.clock.to_local(until);
+                log::info!("Server masuk mode pemeliharaan sampai {}", time::format_absolute(until));
+                let link = ctx.link().clone();
+                self.maintenance = Some(Maintenance::start(until, message, move || link.send_message(Msg::MaintenanceEnded)));
+                self.error = None;
+                self.uploads.pause_all();
+                self.update_ticker(ctx);
+            }
+            ServerEvent::AccountDeleted => {
+                // Semua data lokal ikut dihapus; muat ulang agar mulai lagi sebagai user baru
+                storage::clear_app_data();
+                if let Some(window) = web_sys::window() {
+                    let _ = window.location().reload();
+                }
+                return false;
+            }
+            ServerEvent::UserDeleted { user_id } => {
+                self.dispatch(Action::UserDeleted { user_id });
+            }
+            ServerEvent::SlowModeRejected { room, retry_after_secs, client_id } => {
+                log::warn!("Pesan ke room {} ditolak karena slow mode", room);
+                let error = ChatError::RateLimited { retry_after_secs };
+                if let Some(client_id) = client_id {
+                    self.dispatch(Action::DeliveryFailed { client_id, reason: error.to_string() });
+                }
+                self.error = Some(error);
+                self.start_slow_mode_cooldown(ctx, Some(retry_after_secs));
+            }
+            ServerEvent::MessageRejected { client_id, reason } => {
+                log::warn!("Pesan {} ditolak server: {}", client_id, reason);
+                return self.dispatch(Action::DeliveryFailed { client_id, reason });
+            }
+            ServerEvent::JoinFailed { room, reason } => {
+                log::warn!("Gagal bergabung ke room {}: {:?}", room, reason);
+                self.room_error = Some(tr(reason.message()).to_string());
+            }
+        }
+        true
+    }
+}
+
+// Metode helper untuk merender satu pesan
+impl App {
+    // "Dilihat N" pada pesan sendiri; nama pembacanya di tooltip
+    fn view_seen_by(&self, anchor: Option<usize>) -> Html {
+        let Some(index) = anchor.filter(|_| !self.settings.privacy.disable_read_receipts) else { return html! {} };
+        let readers = self.shown().read_marks.seen_by(&self.shown().messages, index, &self.user_id);
+        if readers.is_empty() {
+            return html! {};
+        }
+        let names: Vec<&str> = readers.iter().map(|user_id| self.shown().directory.name_of(user_id).unwrap_or(user_id)).collect();
+        html! {
+            <span class="seen-by" title={trf("Dilihat oleh {}", &[&names.join(", ")])}>{ trf("✓ Dilihat {}", &[&readers.len()]) }</span>
+        }
+    }
+
+    // Banner error dengan tindakan pemulihan sesuai jenis error
+    fn view_error(&self, ctx: &Context<Self>) -> Html {
+        let Some(error) = &self.error else { return html! {} };
+        let link = ctx.link();
+        let action = match error.recovery() {
+            // Tombol "Coba Hubungkan Ulang" di atas sudah tampil selama tidak terhubung
+            Recovery::Reconnect if !self.is_connected => html! {},
+            Recovery::Reconnect => html! { <button onclick={link.callback(|_| Msg::Connect)}>{ tr("Hubungkan ulang") }</button> },
+            Recovery::Reload => html! {
+                <button onclick={Callback::from(|_| { let _ = web_sys::window().map(|w| w.location().reload()); })}>{ tr("Masuk lagi") }</button>
+            },
+            Recovery::Wait(_) => match self.send_cooldown_remaining() {
+                Some(secs) => html! { <span class="error-wait">{ trf("Bisa mengirim lagi dalam {} detik", &[&secs]) }</span> },
+                None => html! { <button onclick={link.callback(|_| Msg::DismissError)}>{ tr("Tutup") }</button> },
+            },
+            Recovery::Dismiss => html! { <button onclick={link.callback(|_| Msg::DismissError)}>{ tr("Tutup") }</button> },
+        };
+        html! {
+            <p class="error-banner" role="alert">
+                <span class="error-text">{ trf("Error: {}", &[&error]) }</span>
+                { action }
+            </p>
+        }
+    }
+
+    fn view_room_header(&self, ctx: &Context<Self>) -> Html {
+        let Some(room) = &self.shown().current_room else {
+            return html! { <p>{ tr("Room: (umum)") }</p> };
+        };
+        if let Some(peer) = &room.dm_peer {
+            let name = self.shown().directory.name_of(peer).unwrap_or(peer);
+            let presence = self.shown().presence.get(peer);
+            let online = presence.is_some_and(|p| p.online);
+            return html! {
+                <p class="dm-header">
+                    { trf("Pesan langsung dengan {}", &[&name]) }
+                    if let Some(status) = presence::describe(presence, self.shown().presence.status_of(peer), js_sys::Date::now()) {
+                        <span class={classes!("presence-status", online.then_some("online"))}>{ status }</span>
+                    }
+                </p>
+            };
+        }
+        html! {
+            <p>
+                { trf("Room: {}", &[&room.name]) }
+                if let Some(secs) = room.remaining_secs(js_sys::Date::now()) {
+                    <span class="room-countdown" title={tr("Room sementara akan diarsipkan jika tidak ada aktivitas")}>
+                        { trf("⏳ berakhir dalam {}", &[&room::format_countdown(secs)]) }
+                    </span>
+                }
+                if let Some(pseudonym) = &room.pseudonym {
+                    <span class="anonymous-badge" title={tr("Identitas asli disembunyikan; nama samaran diganti secara berkala oleh server")}>
+                        { trf("🎭 Room anonim · Anda tampil sebagai {}", &[&pseudonym.name]) }
+                    </span>
+                }
+                if room.feedback_box {
+                    <span class="feedback-badge" title={tr("Server menghapus nama dan id pengirim sebelum pesan diteruskan")}>
+                        { tr(if room.is_moderator { "📮 Kotak saran · kiriman tampil tanpa nama pengirim" } else { "📮 Kotak saran anonim · hanya moderator yang membaca" }) }
+                    </span>
+                }
+                if let Some(language) = &room.language {
+                    <span class="room-language-badge">{ format!("🌐 {}", translate::language_name(language)) }</span>
+                    if *language != self.settings.language() {
+                        <label class="auto-translate-toggle">
+                            <input
+                                type="checkbox"
+                                checked={self.settings.auto_translate_rooms.contains(&room.name)}
+                                onchange={ctx.link().callback(|e: Event| Msg::ToggleAutoTranslate(e.target_unchecked_into::<HtmlInputElement>().checked()))}
+                            />
+                            { " " }{ trf("Terjemahkan otomatis ke {}", &[&translate::language_name(&self.settings.language())]) }
+                        </label>
+                    }
+                }
+                if let Some(ttl) = room.message_ttl_secs {
+                    <span class="message-ttl-badge">{ trf("⏱ pesan hilang setelah {}", &[&room::format_ttl(ttl)]) }</span>
+                }
+                if let Some(secs) = room.presence_digest_secs {
+                    <span class="presence-digest-badge" title={tr("Room besar: status online dikirim server secara berkala")}>
+                        { trf("👥 status online diperbarui tiap {} detik", &[&secs]) }
+                    </span>
+                }
+                if room.slow_mode_secs > 0 {
+                    <span class="slow-mode-badge">{ trf("🐢 slow mode: 1 pesan / {} detik", &[&room.slow_mode_secs]) }</span>
+                }
+            </p>
+        }
+    }
+
+    // Room yang sedang diikuti dan yang pernah diikuti di sesi ini, dengan pilihan bisukan notifikasi per room
+    fn view_room_list(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let now = js_sys::Date::now();
+        let current = self.chat.current_name();
+        let row = |name: &str, label: String| {
+            let buffer = self.chat.background.iter().find(|buffer| buffer.name() == name);
+            RoomRow {
+                name: name.to_string(),
+                label,
+                joined: self.chat.is_joined(name),
+                current: current == name,
+                unread: buffer.map_or(0, |buffer| buffer.unread),
+                mentioned: buffer.is_some_and(|buffer| buffer.mentioned),
+                snoozed_until: self.snoozes.snoozed_until(name, now),
+            }
+        };
+        // Room umum hanya dicantumkan jika sempat dipakai sebelum bergabung ke room lain
+        let general = self.chat.is_joined("") && !self.chat.background.is_empty();
+        let rows = general
+            .then(|| row("", tr("Room umum").to_string()))
+            .into_iter()
+            .chain(self.recent_rooms.iter().map(|recent| {
+                let label = match &recent.dm_peer {
+                    Some(peer) => format!("@{}", self.chat.directory.name_of(peer).unwrap_or(peer)),
+                    None => recent.name.clone(),
+                };
+                row(&recent.name, label)
+            }))
+            .collect::<Vec<_>>();
+        html! {
+            <RoomsPanel
+                rooms={rows}
+                show_enable_notifications={notify::permission() == web_sys::NotificationPermission::Default}
+                on_open={link.callback(|room| Msg::RunPaletteAction(PaletteAction::SwitchRoom(room)))}
+                on_leave={link.callback(Msg::LeaveRoom)}
+                on_snooze={link.callback(|(room, duration)| Msg::SnoozeRoom(room, duration))}
+                on_enable_notifications={link.callback(|_| Msg::EnableNotifications)}
+            />
+        }
+    }
+
+    // Tab "Room" dan satu tab per percakapan pesan langsung, dengan jumlah belum dibaca
+    fn view_direct_tabs(&self, ctx: &Context<Self>) -> Html {
+        let direct = &self.shown().direct;
+        if direct.conversations().is_empty() {
+            return html! {};
+        }
+        let link = ctx.link();
+        html! {
+            <div class="direct-tabs" role="tablist">
+                <button role="tab" class={classes!("direct-tab", direct.open_peer().is_none().then_some("active"))} onclick={link.callback(|_| Msg::CloseDirect)}>
+                    { tr("Room") }
+                </button>
+                { for direct.conversations().iter().map(|conversation| {
+                    let peer = conversation.peer.clone();
+                    let active = direct.open_peer() == Some(peer.as_str());
+                    html! {
+                        <button role="tab" class={classes!("direct-tab", active.then_some("active"))} onclick={link.callback(move |_| Msg::OpenDirect(peer.clone()))}>
+                            { format!("@{}", self.shown().directory.name_of(&conversation.peer).unwrap_or(&conversation.peer)) }
+                            if conversation.unread > 0 {
+                                <span class="unread-badge">{ conversation.unread }</span>
+                            }
+                        </button>
+                    }
+                }) }
+            </div>
+        }
+    }
+
+    // Progres upload per chunk, dengan tombol batal/coba lagi
+    fn view_uploads(&self, ctx: &Context<Self>) -> Html {
+        if self.uploads.uploads().is_empty() {
+            return html! {};
+        }
+        html! {
+            <ul class="upload-list">
+                { for self.uploads.uploads().iter().map(|upload| {
+                    let id = upload.id.clone();
+                    let on_cancel = ctx.link().callback(move |_| Msg::CancelUpload(id.clone()));
+                    let id = upload.id.clone();
+                    let on_retry = ctx.link().callback(move |_| Msg::RetryUpload(id.clone()));
+                    let status = match &upload.status {
+                        UploadStatus::Uploading => trf("chunk {} / {}", &[&upload.acked_chunks(), &upload.total_chunks()]),
+                        UploadStatus::Paused => tr("terputus, menunggu koneksi…").to_string(),
+                        UploadStatus::Failed(reason) => trf("gagal: {}", &[reason]),
+                        UploadStatus::Rejected(reason) => trf("ditolak: {}", &[&uploads::rejection_message(reason)]),
+                    };
+                    html! {
+                        <li class={classes!("upload-item", upload.is_failed().then_some("failed"))}>
+                            <span class="upload-name">
+                                { format!("{} ({})", upload.name, uploads::format_size(upload.size)) }
+                                if let Some(original) = upload.original_size {
+                                    <span class="upload-note">{ " " }{ trf("dikompres dari {}", &[&uploads::format_size(original)]) }</span>
+                                }
+                                if upload.metadata_stripped {
+                                    <span class="upload-note" title={tr("Lokasi GPS dan data kamera tidak ikut terkirim")}>{ " " }{ tr("· metadata dihapus") }</span>
+                                }
+                            </span>
+                            <progress max={upload.size.to_string()} value={upload.acked.to_string()}></progress>
+                            <span class="upload-status">{ status }</span>
+                            if upload.can_retry() {
+                                <button onclick={on_retry} disabled={!self.is_connected}>{ tr("Coba lagi") }</button>
+                            }
+                            <button onclick={on_cancel}>{ tr("Batal") }</button>
+                        </li>
+                    }
+                }) }
+            </ul>
+        }
+    }
+
+    // Pengaturan enkripsi cache lokal
+    fn view_vault_controls(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        if self.vault_unlocked {
+            let settings = self.settings.clone();
+            let on_auto_lock_change = link.callback(move |e: Event| {
+                let select: HtmlSelectElement = e.target_unchecked_into();
+                Msg::UpdateSettings(Settings { auto_lock_minutes: select.value().parse().unwrap_or(0), ..settings.clone() })
+            });
+            return html! {
+                <div class="vault-controls">
+                    <span>{ tr("🔐 Cache lokal terenkripsi") }</span>
+                    <button onclick={link.callback(|_| Msg::LockVault)}>{ tr("Kunci chat") }</button>
+                    <label>
+                        { tr("Kunci otomatis: ") }
+                        <select onchange={on_auto_lock_change}>
+                            { for [0u32, 1, 5, 15, 30].iter().map(|&minutes| html! {
+                                <option value={minutes.to_string()} selected={self.settings.auto_lock_minutes == minutes}>
+                                    { if minutes == 0 { tr("Mati").to_string() } else { trf("setelah {} menit tidak aktif", &[&minutes]) } }
+                                </option>
+                            }) }
+                        </select>
+                    </label>
+                    <button onclick={link.callback(|_| Msg::DisableVault)}>{ tr("Matikan enkripsi") }</button>
+                </div>
+            };
+        }
+        let on_input = link.callback(|e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            Msg::UpdateVaultInput(input.value())
+        });
+        let on_submit = link.batch_callback(|e: FocusEvent| {
+            e.prevent_default();
+            Some(Msg::SetupVault)
+        });
+        html! {
+            <form class="vault-controls" onsubmit={on_submit}>
+                <input type="password" placeholder={tr("Passphrase perangkat")} value={self.vault_input.clone()} oninput={on_input} autocomplete="new-password" />
+                <button type="submit" disabled={self.vault_busy || self.vault_input.is_empty()}>{ tr("Enkripsi cache lokal") }</button>
+                if let Some(err) = &self.vault_error {
+                    <p class="field-error">{ err }</p>
+                }
+            </form>
+        }
+    }
+
+    fn view_two_factor_controls(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        html! {
+            <div class="vault-controls">
+                if self.two_factor_enabled {
+                    <span>{ tr("🛡 Verifikasi dua langkah aktif") }</span>
+                    <button onclick={link.callback(|_| Msg::StartDisableTwoFactor)}>{ tr("Matikan") }</button>
+                } else {
+                    <button onclick={link.callback(|_| Msg::StartTwoFactorSetup)} disabled={!self.is_connected}>
+                        { tr("Aktifkan verifikasi dua langkah") }
+                    </button>
+                }
+            </div>
+        }
+    }
+
+    fn view_login(&self, ctx: &Context<Self>) -> Html {
+        let (error, pending) = match &self.auth {
+            AuthState::LoginRequired { error } => (error.clone(), false),
+            AuthState::Authenticating { from_login: true } => (None, true), // Layar tetap tampil selama token diperiksa
+            _ => return html! {},
+        };
+        html! {
+            <LoginScreen {error} {pending} on_login={ctx.link().callback(|(token, remember)| Msg::Login(token, remember))} />
+        }
+    }
+
+    fn view_two_factor_step(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let on_cancel = link.callback(|_| Msg::CancelTwoFactor);
+        match &self.two_factor_step {
+            None => html! {},
+            Some(TwoFactorStep::Enrolling(enrollment)) => html! {
+                <TwoFactorSetup
+                    enrollment={enrollment.clone()}
+                    error={self.two_factor_error.clone()}
+                    on_confirm={link.callback(Msg::ConfirmTwoFactorSetup)}
+                    {on_cancel}
+                />
+            },
+            Some(TwoFactorStep::Disabling) => html! {
+                <TwoFactorPrompt
+                    title={tr("Matikan verifikasi dua langkah")}
+                    error={self.two_factor_error.clone()}
+                    allow_recovery=true
+                    on_submit={link.callback(Msg::SubmitTwoFactor)}
+                    on_cancel={Some(on_cancel)}
+                />
+            },
+            Some(TwoFactorStep::Challenge) => html! {
+                <TwoFactorPrompt
+                    title={tr("Verifikasi dua langkah")}
+                    error={self.two_factor_error.clone()}
+                    allow_recovery=true
+                    on_submit={link.callback(Msg::SubmitTwoFactor)}
+                />
+            },
+        }
+    }
+
+    // Layar kunci: menutupi seluruh chat sampai passphrase dimasukkan
+    fn view_vault_lock(&self, ctx: &Context<Self>) -> Html {
+        if !self.vault_locked {
+            return html! {};
+        }
+        let link = ctx.link();
+        html! {
+            <VaultLock
+                value={self.vault_input.clone()}
+                busy={self.vault_busy}
+                error={self.vault_error.clone().map(AttrValue::from)}
+                on_input={link.callback(Msg::UpdateVaultInput)}
+                on_submit={link.callback(|_| Msg::UnlockVault)}
+            />
+        }
+    }
+
+    // Baki "belum terkirim": pesan yang ditulis saat offline
+    fn view_outbox(&self, ctx: &Context<Self>) -> Html {
+        if self.outbox.is_empty() {
+            return html! {};
+        }
+        html! {
+            <div class="outbox-tray">
+                <p class="outbox-title">{ trf("Belum terkirim ({})", &[&self.outbox.items().len()]) }</p>
+                <ul>
+                    { for self.outbox.items().iter().enumerate().map(|(i, item)| html! {
+                        <li class={classes!("outbox-item", item.restored.then_some("restored"))}>
+                            <span class="outbox-text">{ &item.message.text }</span>
+                            if item.restored {
+                                <button onclick={ctx.link().callback(move |_| Msg::SendQueued(i))} disabled={!self.is_connected}>{ tr("Kirim") }</button>
+                            } else {
+                                <span class="outbox-status">{ tr("menunggu koneksi…") }</span>
+                            }
+                            <button onclick={ctx.link().callback(move |_| Msg::DiscardQueued(i))}>{ tr("Buang") }</button>
+                        </li>
+                    }) }
+                </ul>
+            </div>
+        }
+    }
+
+    // Tombol pemuat pesan yang terlewat, ditampilkan di posisi celah riwayat
+    fn view_missed_gap(&self, ctx: &Context<Self>, index: usize) -> Html {
+        match &self.shown().missed_gap {
+            Some(gap) if gap.index == index => html! {
+                <li class="missed-gap">
+                    <button onclick={ctx.link().callback(|_| Msg::FetchMissed)}>
+                        { trf("muat pesan yang terlewat ({})", &[&format_count(gap.count)]) }
+                    </button>
+                </li>
+            },
+            _ => html! {},
+        }
+    }
+
+    fn view_tour(&self, ctx: &Context<Self>) -> Html {
+        if !self.show_tour {
+            return html! {};
+        }
+        let steps = vec![
+            TourStep {
+                target: self.username_ref.clone(),
+                title: "Pilih username",
+                body: "Atur nama yang akan dilihat orang lain. Kamu bisa menggantinya kapan saja.",
+            },
+            TourStep {
+                target: self.room_ref.clone(),
+                title: "Pindah room",
+                body: "Gabung ke room lain atau buat room baru, termasuk room berkata sandi dan room sementara.",
+            },
+            TourStep {
+                target: self.settings_ref.clone(),
+                title: "Pengaturan",
+                body: "Atur preferensi seperti kompresi gambar sebelum dikirim.",
+            },
+            TourStep {
+                target: self.input_ref.clone(),
+                title: "Kirim pesan",
+                body: "Ketik pesan lalu tekan Enter atau tombol Kirim.",
+            },
+        ];
+        html! { <Tour {steps} on_finish={ctx.link().callback(|_| Msg::FinishTour)} /> }
+    }
+
+    // Modal sambutan/aturan yang harus disetujui sebelum user bisa mengirim pesan di room ini
+    fn view_rules_gate(&self, ctx: &Context<Self>) -> Html {
+        let Some(room) = self.shown().current_room.as_ref().filter(|room| room.rules_pending()) else {
+            return html! {};
+        };
+        html! {
+            <RulesGate room={room.name.clone()} rules={room.rules.clone().unwrap_or_default()} on_accept={ctx.link().callback(|_| Msg::AcceptRules)} />
+        }
+    }
+
+    // Pengaturan slow mode, hanya ditampilkan untuk moderator room
+    fn view_slow_mode_control(&self, ctx: &Context<Self>) -> Html {
+        let Some(room) = self.shown().current_room.as_ref().filter(|room| room.is_moderator) else {
+            return html! {};
+        };
+        let on_change = ctx.link().callback(|e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            Msg::SetSlowMode(select.value().parse().unwrap_or(0))
+        });
+        let on_embeds_change = ctx.link().callback(|e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            Msg::SetEmbedsDisabled(input.checked())
+        });
+        let on_language_change = ctx.link().callback(|e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            Msg::SetRoomLanguage(Some(select.value()).filter(|code| !code.is_empty()))
+        });
+        html! {
+            <>
+            <label class="slow-mode-control">
+                { tr("Slow mode: ") }
+                <select onchange={on_change}>
+                    { for [0u32, 5, 10, 30, 60].iter().map(|&secs| html! {
+                        <option value={secs.to_string()} selected={room.slow_mode_secs == secs}>
+                            { if secs == 0 { tr("Mati").to_string() } else { trn("{} detik", secs as usize, &[]) } }
+                        </option>
+                    }) }
+                </select>
+            </label>
+            <label class="slow-mode-control">
+                <input type="checkbox" checked={room.embeds_disabled} onchange={on_embeds_change} />
+                { " " }{ tr("Matikan embed gambar/GIF") }
+            </label>
+            <label class="slow-mode-control">
+                { tr("Bahasa room: ") }
+                <select onchange={on_language_change}>
+                    <option value="" selected={room.language.is_none()}>{ tr("Tidak ditentukan") }</option>
+                    { for translate::LANGUAGES.iter().map(|(code, name)| html! {
+                        <option value={*code} selected={room.language.as_deref() == Some(*code)}>{ *name }</option>
+                    }) }
+                </select>
+            </label>
+            </>
+        }
+    }
+
+    // Merender satu entry; rangkaian pesan duplikat ditampilkan sebagai satu baris dengan penghitung
+    // Entry yang dirender beserta tinggi spacer di sekitarnya: hanya yang ada di sekitar viewport
+    fn message_window(&self) -> virtual_list::Window {
+        self.virtual_list.window(self.shown().messages.entries(), self.scroll_follow.following())
+    }
+
+    fn rendered_entries(&self) -> std::ops::Range<usize> {
+        self.message_window().range
+    }
+
+    fn view_message_window(&self, ctx: &Context<Self>) -> Html {
+        let window = self.message_window();
+        let entries = &self.shown().messages.entries()[window.range.clone()];
+        // Hanya membangun VNode entry yang terlihat; diff dan patch DOM-nya terhitung di render App
+        #[cfg(feature = "perf")]
+        perf::begin("MessageList", entries.len());
+        let loading_older = self.older_request.as_deref() == Some(self.shown().current_name());
+        let at_start = self.shown().history_complete && window.range.start == 0 && !entries.is_empty();
+        let list = html! {
+            <>
+                if loading_older {
+                    <li class="older-history loading">{ tr("Memuat pesan sebelumnya…") }</li>
+                } else if at_start {
+                    <li class="older-history complete">{ tr("Awal riwayat room ini") }</li>
+                }
+                <li class="virtual-spacer before" aria-hidden="true" style={format!("height: {}px", window.before)} />
+                { for entries.iter().zip(window.range).map(|(entry, i)| html! {
+                    <>
+                        { self.view_missed_gap(ctx, i) }
+                        { self.view_day_separator(i) }
+                        { self.view_entry(ctx, i, entry) }
+                    </>
+                }) }
+                <li class="virtual-spacer after" aria-hidden="true" style={format!("height: {}px", window.after)} />
+            </>
+        };
+        #[cfg(feature = "perf")]
+        perf::end("MessageList");
+        list
+    }
+
+    // Tombol ke entry terbaru selama user menggulir ke atas dan ada entry baru di bawahnya
+    fn view_new_below(&self, ctx: &Context<Self>) -> Html {
+        let count = self.scroll_follow.new_below(self.shown().messages.len());
+        if count == 0 {
+            return html! {};
+        }
+        html! {
+            <button class="new-messages-button" onclick={ctx.link().callback(|_| Msg::ScrollToNewest)}>
+                { trn("{} pesan baru ↓", count, &[]) }
+            </button>
+        }
+    }
+
+    fn view_entry(&self, ctx: &Context<Self>, index: usize, entry: &MessageEntry) -> Html {
+        let anchor = Some(index);
+        if entry.count() == 1 {
+            return self.view_message(ctx, &entry.first, anchor, html! {});
+        }
+        let on_toggle = ctx.link().callback(move |_| Msg::ToggleRepeats(index));
+        let counter = html! {
+            <button class="repeat-count" onclick={on_toggle} title={ tr(if entry.expanded { "Sembunyikan pesan duplikat" } else { "Tampilkan pesan duplikat" }) }>
+                { format!("×{}", entry.count()) }
+            </button>
+        };
+        html! {
+            <>
+                { self.view_message(ctx, &entry.first, anchor, counter) }
+                if entry.expanded {
+                    { for entry.repeats.iter().map(|msg| self.view_message(ctx, msg, None, html! {})) }
+                }
+            </>
+        }
+    }
+
+    // Tanggal pesan teratas yang terlihat, mengambang di atas daftar pesan
+    fn view_empty_state(&self, ctx: &Context<Self>, empty: bool) -> Html {
+        match &ctx.props().empty_state {
+            Some(content) if empty => html! { <div class="empty-state">{ content.clone() }</div> },
+            _ => html! {},
+        }
+    }
+
+    // Pemisah hari sebelum entry pertama tiap tanggal. Entry tanpa timestamp (pesan lokal) dilewati
+    // saat mencari tanggal entry sebelumnya.
+    fn view_day_separator(&self, index: usize) -> Html {
+        let entries = self.shown().messages.entries();
+        let Some(at) = entries.get(index).and_then(|entry| entry.first.message.timestamp) else { return html! {} };
+        let previous = entries[..index].iter().rev().find_map(|entry| entry.first.message.timestamp);
+        if previous.is_some_and(|previous| time::same_day(previous, at)) {
+            return html! {};
+        }
+        html! {
+            <li class="day-separator" role="separator"><span>{ time::format_date(at) }</span></li>
+        }
+    }
+
+    fn view_date_chip(&self) -> Html {
+        let label = self
+            .top_entry
+            .and_then(|i| self.shown().messages.entries().get(i))
+            .and_then(|entry| entry.first.message.timestamp)
+            .map(time::format_date);
+        match label {
+            Some(label) => html! { <div class="date-chip" aria-hidden="true">{ label }</div> },
+            None => html! {},
+        }
+    }
+
+    // Tombol ↑/↓ untuk berpindah antar pesan yang menyebut user di room ini
+    // "@..." yang sedang diketik. Di room anonim dan kotak saran tidak ada saran, agar akun asli tidak terungkap.
+    fn active_mention_query(&self) -> Option<(usize, &str)> {
+        if self.chat.current_room.as_ref().is_some_and(RoomState::hides_identity) {
+            return None;
+        }
+        mentions::active_query(&self.current_input)
+    }
+
+    // User yang dikenal klien lebih dulu, lalu hasil pencarian server (termasuk yang offline)
+    fn mention_suggestions(&self) -> Vec<UserMatch> {
+        let Some((_, prefix)) = self.active_mention_query() else { return Vec::new() };
+        let local = self.chat.directory.matching(prefix).map(|(user_id, name)| UserMatch { user_id: user_id.to_string(), name: name.to_string() });
+        let remote = self.mention_search.cached(prefix).unwrap_or_default();
+        let mut seen = std::collections::HashSet::new();
+        local
+            .chain(remote)
+            .filter(|user| user.user_id != self.user_id && seen.insert(user.user_id.clone()))
+            .take(mentions::SUGGESTION_LIMIT)
+            .collect()
+    }
+
+    fn view_mention_suggestions(&self, ctx: &Context<Self>, suggestions: &[UserMatch]) -> Html {
+        if suggestions.is_empty() {
+            return html! {};
+        }
+        html! {
+            <ul class="mention-suggestions">
+                { for suggestions.iter().map(|user| {
+                    let handle = mentions::handle_for(user).to_string();
+                    // mousedown + preventDefault supaya input pesan tidak kehilangan fokus
+                    let on_pick = ctx.link().callback(move |e: MouseEvent| {
+                        e.prevent_default();
+                        Msg::CompleteMention(handle.clone())
+                    });
+                    html! {
+                        <li onmousedown={on_pick}>
+                            <span dir="auto">{ &user.name }</span>
+                            <span class="user-handle">{ format!(" @{}", mentions::handle_for(user)) }</span>
+                        </li>
+                    }
+                }) }
+            </ul>
+        }
+    }
+
+    // Hitung ulang saran slash command; true jika berubah dan perlu render ulang
+    // Nilai {nama}, {saya} dan {room} untuk balasan cepat
+    fn canned_placeholders(&self) -> canned::Placeholders {
+        let peer = match self.chat.direct.open_peer() {
+            Some(peer) => Some(self.chat.directory.name_of(peer).unwrap_or(peer).to_string()),
+            None => self
+                .chat
+                .messages
+                .entries()
+                .iter()
+                .rev()
+                .map(|entry| &entry.first.message)
+                .find(|msg| !msg.system && !self.chat.is_own(msg))
+                .map(|msg| self.chat.directory.display_name(msg).to_string()),
+        };
+        canned::Placeholders {
+            peer,
+            me: Some(self.username.clone()).filter(|name| !name.is_empty()),
+            room: self.chat.current_room.as_ref().map(|room| room.name.clone()),
+        }
+    }
+
+    fn refresh_command_hints(&mut self) -> bool {
+        let hints = commands::hints(&self.current_input, &self.bot_commands, &self.settings.canned_responses);
+        if hints == self.command_hints {
+            return false;
+        }
+        self.command_hints = hints;
+        true
+    }
+
+    fn view_command_hints(&self, ctx: &Context<Self>) -> Html {
+        if self.command_hints.is_empty() {
+            return html! {};
+        }
+        html! {
+            <ul class="command-hints">
+                { for self.command_hints.iter().map(|hint| {
+                    let name = hint.name.clone();
+                    // mousedown + preventDefault supaya input pesan tidak kehilangan fokus
+                    let on_pick = ctx.link().callback(move |e: MouseEvent| {
+                        e.prevent_default();
+                        Msg::CompleteCommand(name.clone())
+                    });
+                    html! {
+                        <li onmousedown={on_pick}>
+                            <span class="command-signature">{ &hint.signature }</span>
+                            <span class="command-description">{ &hint.description }</span>
+                            { for hint.arg_help.iter().map(|help| html! { <div class="command-arg">{ help }</div> }) }
+                        </li>
+                    }
+                }) }
+            </ul>
+        }
+    }
+
+    fn view_mention_nav(&self, ctx: &Context<Self>) -> Html {
+        let mentions = self.shown().messages.mentions();
+        if mentions.is_empty() {
+            return html! {};
+        }
+        let link = ctx.link();
+        let position = self.highlighted_entry.and_then(|entry| mentions.iter().position(|&i| i == entry));
+        html! {
+            <div class="mention-nav">
+                <span class="mention-count">
+                    { match position {
+                        Some(pos) => format!("@ {}/{}", pos + 1, mentions.len()),
+                        None => trn("@ {} mention", mentions.len(), &[]),
+                    } }
+                </span>
+                <button title={tr("Mention sebelumnya (Alt+↑)")} onclick={link.callback(|_| Msg::JumpToMention(MentionStep::Previous))}>{ "↑" }</button>
+                <button title={tr("Mention berikutnya (Alt+↓)")} onclick={link.callback(|_| Msg::JumpToMention(MentionStep::Next))}>{ "↓" }</button>
+            </div>
+        }
+    }
+
+    // `anchor` diisi untuk pesan pertama sebuah entry, agar bisa dituju dari galeri
+    fn view_message(&self, ctx: &Context<Self>, stored: &StoredMessage, anchor: Option<usize>, extra: Html) -> Html {
+        let msg = &stored.message;
+        // Pesan langsung selalu memakai akun asli, walaupun room yang diikuti anonim
+        let in_room = self.shown().direct.open_peer().is_none();
+        let anonymous_room = self.shown().current_room.as_ref().filter(|room| room.is_anonymous() && in_room);
+        let feedback_box = in_room && self.shown().current_room.as_ref().is_some_and(|room| room.feedback_box);
+        let is_me = match (&msg.user_id, anonymous_room) {
+            (Some(id), Some(room)) => room.is_own_pseudonym(id),
+            (Some(id), None) => *id == self.user_id,
+            (None, _) if feedback_box => false, // Kiriman yang identitasnya sudah dihapus server
+            (None, _) => msg.username == self.username, // Pesan dari klien/server lama tanpa id
+        };
+        let class_name = if is_me { "me" } else { "other" };
+        let highlighted = anchor.is_some() && anchor == self.highlighted_entry;
+        let mention = anchor.and_then(|i| self.shown().messages.entries().get(i)).is_some_and(|entry| entry.mentions_me);
+        let delivery_class = match stored.delivery {
+            Delivery::Sent => None,
+            Delivery::Pending { .. } => Some("pending"),
+            Delivery::Failed(_) => Some("failed"),
+        };
+        html! {
+            <li class={classes!(class_name, msg.system.then_some("system"), msg.whisper_to.is_some().then_some("whisper"), highlighted.then_some("highlighted"), mention.then_some("mention"), delivery_class)} data-user={msg.user_id.clone()} id={anchor.map(entry_anchor)} data-entry={anchor.map(|i| i.to_string())} dir={stored.profile.direction.map(bidi::Direction::as_attr)} lang={stored.profile.language} data-script={stored.profile.script.map(Script::as_attr)}>
+                <div class="message-meta">
+                    // Di room anonim id samaran tidak ditampilkan dan nama tidak dicocokkan ke direktori akun
+                    if anonymous_room.is_some() {
+                        <strong class="pseudonym">{ &msg.username }</strong>
+                    } else if feedback_box && !is_me && !msg.system {
+                        <strong class="pseudonym">{ tr("Pengirim anonim") }</strong>
+                    } else {
+                        if let Some(sender) = msg.user_id.clone().filter(|_| !is_me && !msg.system) {
+                            <Tooltip content={self.view_profile_card(&sender)}>
+                                <strong class="sender-link" title={trf("{} · klik untuk pesan langsung", &[&sender])} onclick={ctx.link().callback(move |_| Msg::OpenDirect(sender.clone()))}>
+                                    { self.shown().directory.display_name(msg) }
+                                </strong>
+                            </Tooltip>
+                        } else {
+                            <strong title={msg.user_id.clone()}>{ self.shown().directory.display_name(msg) }</strong>
+                        }
+                    }
+                    if let Some(operator) = msg.operator.as_deref().filter(|_| anonymous_room.is_none()) {
+                        <span class="operator-badge" title={trf("Dikirim oleh {}", &[&operator.name])}>{ &operator.initials }</span>
+                    }
+                    if let Some(target) = msg.whisper_to.as_deref() {
+                        <span class="whisper-label">
+                            { if is_me { trf("🤫 bisikan untuk {}", &[&self.shown().directory.name_of(target).unwrap_or(target)]) } else { tr("🤫 bisikan untuk Anda").to_string() } }
+                        </span>
+                    }
+                    if let Some(ts) = &msg.timestamp {
+                        <span class="timestamp">
+                            { " - " }
+                            <Tooltip content={self.view_message_details(msg, is_me)}>{ time::format_relative(*ts, self.clock.now()) }</Tooltip>
+                        </span>
+                    }
+                    if let Some(expires_at) = msg.expires_at {
+                        <span class="message-ttl" title={tr("Pesan ini akan hilang otomatis")}>
+                            { format!("⏱ {}", room::format_countdown(((expires_at - self.clock.now()) / 1000.0).max(0.0).ceil() as u64)) }
+                        </span>
+                    }
+                    { self.view_delivery(ctx, stored) }
+                    if is_me && anonymous_room.is_none() && !feedback_box {
+                        { self.view_seen_by(anchor) }
+                    }
+                </div>
+                if !msg.text.is_empty() {
+                    { self.view_message_text(ctx, stored) }
+                }
+                if let Some(attachment) = &msg.attachment {
+                    { media::view_attachment(attachment, self.embed_policy()) }
+                }
+                if let Some(event) = &msg.event {
+                    { schedule::view_event_card(event, &self.user_id, self.clock.now(), ctx.link().callback(|(id, response)| Msg::Rsvp(id, response))) }
+                }
+                if let Some(id) = msg.id.as_deref() {
+                    { components::view_components(id, msg.room.as_deref(), &msg.components, self.pending_interactions.contains_key(id), ctx.link().callback(Msg::Interact)) }
+                }
+                { extra }
+                { self.view_receipt(msg) }
+                { self.view_source_action(ctx, stored) }
+            </li>
+        }
+    }
+
+    // Teks pesan, atau terjemahannya jika sudah diminta; pesan di room berbahasa lain mendapat tautan "Terjemahkan"
+    fn view_message_text(&self, ctx: &Context<Self>, stored: &StoredMessage) -> Html {
+        let msg = &stored.message;
+        let (Some(id), Some(source)) = (msg.id.clone(), self.translation_target(msg, &stored.profile)) else {
+            return html! { <div>{ &msg.text }</div> };
+        };
+        let translation = self.translations.get(&id).cloned();
+        let on_translate = {
+            let (message_id, source) = (id.clone(), source.clone());
+            ctx.link().callback(move |_| Msg::Translate { message_id: message_id.clone(), source: source.clone() })
+        };
+        match translation {
+            Some(Translation::Done { text, .. }) if !self.translations.showing_original(&id) => html! {
+                <div class="translated">
+                    <div dir="auto">{ text }</div>
+                    <span class="translation-note">
+                        { trf("🌐 Diterjemahkan dari {} · ", &[&translate::language_name(&source)]) }
+                        <button class="link-button" onclick={ctx.link().callback(move |_| Msg::ShowOriginal(id.clone()))}>{ tr("Lihat asli") }</button>
+                    </span>
+                </div>
+            },
+            Some(Translation::Pending) => html! {
+                <div>
+                    { &msg.text }
+                    <span class="translation-note">{ " " }{ tr("· menerjemahkan…") }</span>
+                </div>
+            },
+            Some(Translation::Failed(reason)) => html! {
+                <div>
+                    { &msg.text }
+                    <span class="translation-note" title={reason}>
+                        { " " }{ tr("· gagal menerjemahkan ") }
+                        <button class="link-button" onclick={on_translate}>{ tr("Coba lagi") }</button>
+                    </span>
+                </div>
+            },
+            _ => html! {
+                <div>
+                    { &msg.text }
+                    <button class="link-button translate-button" onclick={on_translate}>{ tr("Terjemahkan") }</button>
+                </div>
+            },
+        }
+    }
+
+    // Isi tooltip waktu pesan: waktu lengkap, waktu sunting, status pengiriman dan id pesan
+    // Kartu profil saat nama pengirim di-hover: nama terbaru, status online dan status kustom
+    fn view_profile_card(&self, user_id: &str) -> Html {
+        let chat = self.shown();
+        let presence = presence::describe(chat.presence.get(user_id), chat.presence.status_of(user_id), js_sys::Date::now());
+        let custom = chat.presence.custom_of(user_id, self.clock.now());
+        html! {
+            <dl class="message-details profile-card">
+                <dt>{ tr("Nama") }</dt>
+                <dd dir="auto">{ chat.directory.name_of(user_id).unwrap_or(user_id) }</dd>
+                if let Some(presence) = presence {
+                    <dt>{ tr("Status") }</dt>
+                    <dd>{ presence }</dd>
+                }
+                if let Some(custom) = custom {
+                    <dt>{ tr("Sedang") }</dt>
+                    <dd dir="auto">
+                        { custom.label() }
+                        if let Some(expires_at) = custom.expires_at {
+                            <span class="custom-status-expiry">{ trf(" (sampai {})", &[&time::format_absolute(expires_at)]) }</span>
+                        }
+                    </dd>
+                }
+            </dl>
+        }
+    }
+
+    // Tanda "mengirim…" atau "gagal" untuk pesan user ini yang belum dikembalikan server
+    fn view_delivery(&self, ctx: &Context<Self>, stored: &StoredMessage) -> Html {
+        let Some(client_id) = stored.message.client_id.clone() else { return html! {} };
+        match &stored.delivery {
+            Delivery::Sent => html! {},
+            Delivery::Pending { .. } => html! { <span class="delivery-status pending">{ " " }{ tr("· mengirim…") }</span> },
+            Delivery::Failed(reason) => {
+                let discard = client_id.clone();
+                html! {
+                    <span class="delivery-status failed">
+                        <span title={reason.clone()}>{ " " }{ tr("· gagal terkirim") }</span>
+                        <button class="link-button" onclick={ctx.link().callback(move |_| Msg::RetryMessage(client_id.clone()))}>{ tr("Coba lagi") }</button>
+                        <button class="link-button" onclick={ctx.link().callback(move |_| Msg::DiscardUnsent(discard.clone()))}>{ tr("Hapus") }</button>
+                    </span>
+                }
+            }
+        }
+    }
+
+    fn view_message_details(&self, msg: &ChatMessage, is_me: bool) -> Html {
+        html! {
+            <dl class="message-details">
+                if let Some(ts) = msg.timestamp {
+                    <dt>{ tr("Dikirim") }</dt>
+                    <dd>{ time::format_absolute(ts) }</dd>
+                }
+                if let Some(edited) = msg.edited_at {
+                    <dt>{ tr("Disunting") }</dt>
+                    <dd>{ time::format_absolute(edited) }</dd>
+                }
+                <dt>{ tr("Status") }</dt>
+                // Pesan pending/gagal hanya punya cap waktu dari klien; statusnya juga tampil di baris pesan
+                <dd>{ tr(if msg.id.is_none() { "Menunggu server" } else if is_me { "Terkirim ke server" } else { "Diterima" }) }</dd>
+                if let Some(id) = &msg.id {
+                    <dt>{ tr("Id pesan") }</dt>
+                    <dd><code>{ id }</code></dd>
+                }
+            </dl>
+        }
+    }
+
+    #[cfg(feature = "dev")]
+    fn view_source_action(&self, ctx: &Context<Self>, stored: &StoredMessage) -> Html {
+        match &stored.raw {
+            Some(raw) => {
+                let raw = raw.clone();
+                let on_click = ctx.link().callback(move |_| Msg::ShowSource(raw.clone()));
+                html! { <button class="view-source" onclick={on_click}>{ tr("lihat sumber") }</button> }
+            }
+            None => html! {},
+        }
+    }
+
+    #[cfg(not(feature = "dev"))]
+    fn view_source_action(&self, _ctx: &Context<Self>, _stored: &StoredMessage) -> Html {
+        html! {}
+    }
+
+    // Baris debug di bawah pesan: urutan tiba, `seq` server, id, codec dan selisih waktu tiba
+    #[cfg(feature = "dev")]
+    fn view_receipt(&self, msg: &ChatMessage) -> Html {
+        if !self.show_receipts {
+            return html! {};
+        }
+        let Some(receipt) = msg.id.as_deref().and_then(|id| self.receipts.get(id)) else {
+            let note = tr(if msg.id.is_some() { "riwayat (tidak diterima live)" } else { "tanpa id" });
+            return html! { <div class="receipt-debug">{ note }</div> };
+        };
+        let deltas = msg.timestamp.map(|ts| receipt.deltas(ts));
+        html! {
+            <div class={classes!("receipt-debug", receipt.out_of_order.then_some("out-of-order"))}>
+                <span>{ format!("#{}", receipt.arrival_seq) }</span>
+                <span>{ format!("seq {}", receipt.server_seq.map_or_else(|| "-".to_string(), |seq| seq.to_string())) }</span>
+                if receipt.out_of_order {
+                    <span>{ tr("⚠ tidak berurutan") }</span>
+                }
+                <span>{ format!("id {}", msg.id.as_deref().unwrap_or_default()) }</span>
+                <span>{ receipt.codec }</span>
+                <span title={tr("Waktu tiba dikurangi timestamp server, menurut jam lokal / jam server terkoreksi")}>
+                    { match deltas {
+                        Some((local, corrected)) => trf("Δ {} (terkoreksi {})", &[&receipts::format_delta(local), &receipts::format_delta(corrected)]),
+                        None => tr("Δ - (timestamp tidak terbaca)").to_string(),
+                    } }
+                </span>
+            </div>
+        }
+    }
+
+    #[cfg(not(feature = "dev"))]
+    fn view_receipt(&self, _msg: &ChatMessage) -> Html {
+        html! {}
+    }
+
+    #[cfg(feature = "dev")]
+    fn view_receipts_toggle(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <label class="dev-toggle">
+                <input type="checkbox" checked={self.show_receipts} onchange={ctx.link().callback(|_| Msg::ToggleReceipts)} />
+                { " " }{ tr("Tampilkan tanda terima pesan (debug server)") }
+            </label>
+        }
+    }
+
+    #[cfg(not(feature = "dev"))]
+    fn view_receipts_toggle(&self, _ctx: &Context<Self>) -> Html {
+        html! {}
+    }
+
+    // Menu dev untuk memicu skenario server demo
+    #[cfg(feature = "demo-server")]
+    fn view_demo_scenarios(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <details class="demo-scenarios">
+                <summary>{ tr("Skenario server demo") }</summary>
+                { for demo::Scenario::ALL.into_iter().map(|scenario| html! {
+                    <button onclick={ctx.link().callback(move |_| Msg::RunScenario(scenario))}>{ scenario.label() }</button>
+                }) }
+            </details>
+        }
+    }
+
+    #[cfg(not(feature = "demo-server"))]
+    fn view_demo_scenarios(&self, _ctx: &Context<Self>) -> Html {
+        html! {}
+    }
+
+    // Modal berisi payload protokol persis seperti yang diterima, di-pretty-print jika JSON valid
+    #[cfg(feature = "dev")]
+    fn view_source_modal(&self, ctx: &Context<Self>) -> Html {
+        let Some(raw) = &self.source_view else { return html! {} };
+        html! {
+            <SourceModal raw={raw.clone()} on_close={ctx.link().callback(|_| Msg::CloseSource)} />
+        }
+    }
+
+    #[cfg(not(feature = "dev"))]
+    fn view_source_modal(&self, _ctx: &Context<Self>) -> Html {
+        html! {}
+    }
+
+    // Panel time-travel: daftar aksi reducer terbaru, klik untuk meninjau state sesudah aksi itu
+    #[cfg(feature = "dev")]
+    fn view_time_travel(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let tt = &self.time_travel;
+        let count = tt.records().count();
+        let selected = tt.cursor().unwrap_or(count.saturating_sub(1));
+        html! {
+            <details class={classes!("time-travel", (!tt.is_live()).then_some("traveling"))}>
+                <summary>
+                    { trf("Time-travel ({} aksi)", &[&count]) }
+                    if !tt.is_live() {
+                        <strong>{ trf(" · meninjau #{}", &[&(selected + 1)]) }</strong>
+                    }
+                </summary>
+                <div class="time-travel-controls">
+                    <button onclick={link.callback(|_| Msg::TimeTravelBack)} disabled={selected == 0}>{ tr("◀ Mundur") }</button>
+                    <button onclick={link.callback(|_| Msg::TimeTravelForward)} disabled={tt.is_live()}>{ tr("Maju ▶") }</button>
+                    <button onclick={link.callback(|_| Msg::TimeTravelLive)} disabled={tt.is_live()}>{ "Live" }</button>
+                </div>
+                <ol class="time-travel-log">
+                    { for tt.records().enumerate().map(|(i, record)| html! {
+                        <li
+                            class={classes!((i == selected).then_some("selected"))}
+                            title={record.detail.clone()}
+                            onclick={link.callback(move |_| Msg::TimeTravelJump(i))}
+                        >
+                            <span class="timestamp">{ time::format_absolute(record.at) }</span>
+                            { format!(" {}", record.action) }
+                        </li>
+                    }) }
+                </ol>
+            </details>
+        }
+    }
+
+    #[cfg(not(feature = "dev"))]
+    fn view_time_travel(&self, _ctx: &Context<Self>) -> Html {
+        html! {}
+    }
 }
 
+// Fokuskan input teks di dalam `container` dan taruh kursor di akhir
+fn focus_text_input(container: &NodeRef) {
+    let input = container
+        .cast::<web_sys::Element>()
+        .and_then(|el| el.query_selector("input[type=text]").ok().flatten())
+        .and_then(|el| wasm_bindgen::JsCast::dyn_into::<HtmlInputElement>(el).ok());
+    if let Some(input) = input {
+        let _ = input.focus();
+    }
+}
+
+#[cfg(not(feature = "demo-server"))]
+fn default_transport() -> Rc<dyn ChatTransport> {
+    Rc::new(WebSocketTransport)
+}
+
+// Build `demo-server`: tidak ada koneksi jaringan, server tiruan berjalan di tab ini
+#[cfg(feature = "demo-server")]
+fn default_transport() -> Rc<dyn ChatTransport> {
+    Rc::new(demo::DemoTransport)
+}
+
+// Koneksi App dan ChatStore: milik sendiri di tab ini, atau koneksi bersama di ChatWorker pada build `worker`
+#[cfg(not(feature = "worker"))]
+fn default_client(stats: SessionStats) -> ChatClient {
+    ChatClient::new(default_transport(), stats)
+}
+
+#[cfg(feature = "worker")]
+fn default_client(stats: SessionStats) -> ChatClient {
+    ChatClient::shared(stats)
+}
+
+// Id elemen DOM untuk entry pesan, dipakai untuk lompat dari galeri
+fn entry_anchor(index: usize) -> String {
+    format!("entry-{}", index)
+}
+
+// Format jumlah ringkas: 950, 1.2k, 3.4jt
+fn format_count(n: u64) -> String {
+    match n {
+        0..=999 => n.to_string(),
+        1_000..=999_999 => format!("{:.1}k", n as f64 / 1_000.0),
+        _ => format!("{:.1}jt", n as f64 / 1_000_000.0),
+    }
+}
 
 // Fungsi utama untuk menjalankan aplikasi Yew
 #[wasm_bindgen(start)]
 pub fn run_app() {
+    // Wasm bin chat_worker ikut membawa fungsi start ini, padahal di web worker tidak ada DOM untuk App
+    #[cfg(feature = "worker")]
+    if web_sys::window().is_none() {
+        return;
+    }
     // Inisialisasi logger (opsional, tapi berguna untuk debug)
     // Anda mungkin perlu menambahkan dependensi `wasm-logger` dan `log`
     wasm_logger::init(wasm_logger::Config::default());
@@ -0,0 +1,43 @@
+// src/maintenance.rs
+// Mode pemeliharaan server (ServerEvent::Maintenance). Selama aktif chat hanya bisa dibaca: composer
+// dimatikan dan reconnect, otomatis maupun lewat tombol, ditahan sampai `until`. Setelah itu App
+// menyambung lagi sendiri. Server biasanya menutup koneksi tak lama setelah mengirim event ini.
+use gloo_timers::callback::Timeout;
+use yew::prelude::*;
+
+use crate::i18n::{tr, trf};
+use crate::{room, time};
+
+const MAX_DELAY_MS: f64 = i32::MAX as f64; // Batas setTimeout browser (~24 hari)
+
+pub struct Maintenance {
+    pub until: f64, // Epoch millis jam lokal
+    pub message: Option<String>,
+    _resume: Timeout, // Ikut dibatalkan jika event Maintenance baru menggantikannya
+}
+
+impl Maintenance {
+    // `on_end` dipanggil sekali saat `until` tercapai
+    pub fn start(until: f64, message: Option<String>, on_end: impl FnOnce() + 'static) -> Self {
+        let delay = (until - js_sys::Date::now()).clamp(0.0, MAX_DELAY_MS) as u32;
+        Self { until, message, _resume: Timeout::new(delay, on_end) }
+    }
+
+    pub fn remaining_secs(&self) -> u64 {
+        ((self.until - js_sys::Date::now()) / 1000.0).max(0.0).ceil() as u64
+    }
+}
+
+pub fn view_banner(maintenance: &Maintenance) -> Html {
+    html! {
+        <div class="maintenance-banner" role="status">
+            <strong>{ tr("Server sedang dalam pemeliharaan") }</strong>
+            if let Some(message) = &maintenance.message {
+                <p class="maintenance-message" dir="auto">{ message }</p>
+            }
+            <p class="maintenance-until" title={time::format_absolute(maintenance.until)}>
+                { trf("Pesan lama tetap bisa dibaca. Menyambung lagi otomatis dalam {}.", &[&room::format_countdown(maintenance.remaining_secs())]) }
+            </p>
+        </div>
+    }
+}
@@ -0,0 +1,100 @@
+// src/media.rs
+// Tampilan lampiran di daftar pesan
+use yew::prelude::*;
+
+use crate::i18n::{tr, trf};
+use crate::protocol::{Attachment, GifRating};
+use crate::uploads::format_size;
+use crate::voice::VoiceMessage;
+
+// Aturan embed yang berlaku: gabungan flag room dan rating GIF pilihan user
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EmbedPolicy {
+    pub embeds_disabled: bool,
+    pub max_gif_rating: GifRating,
+}
+
+impl EmbedPolicy {
+    // Alasan sebuah gambar tidak di-embed, atau None jika boleh ditampilkan
+    pub fn blocked_reason(&self, attachment: &Attachment) -> Option<String> {
+        if !attachment.is_image() {
+            return None;
+        }
+        if self.embeds_disabled {
+            return Some(tr("embed gambar dimatikan di room ini").to_string());
+        }
+        match attachment.rating {
+            // GIF tanpa rating dianggap belum tentu aman, kecuali user mengizinkan semua rating
+            None if attachment.is_gif() && self.max_gif_rating < GifRating::R => Some(tr("GIF tanpa rating").to_string()),
+            Some(rating) if rating > self.max_gif_rating => Some(trf("rating {}", &[&rating.label()])),
+            _ => None,
+        }
+    }
+}
+
+pub fn view_attachment(attachment: &Attachment, policy: EmbedPolicy) -> Html {
+    if let Some(reason) = policy.blocked_reason(attachment) {
+        html! {
+            <a class="attachment attachment-file attachment-blocked" href={attachment.url.clone()} target="_blank" rel="noopener">
+                { trf("🖼 {} (disembunyikan: {})", &[&attachment.name, &reason]) }
+            </a>
+        }
+    } else if attachment.is_video() {
+        html! { <VideoAttachment attachment={attachment.clone()} /> }
+    } else if attachment.is_audio() {
+        html! { <VoiceMessage attachment={attachment.clone()} /> }
+    } else if attachment.is_image() {
+        html! {
+            <a class="attachment attachment-image" href={attachment.url.clone()} target="_blank">
+                <img src={attachment.url.clone()} alt={attachment.name.clone()} loading="lazy" />
+            </a>
+        }
+    } else {
+        html! {
+            <a class="attachment attachment-file" href={attachment.url.clone()} download={attachment.name.clone()}>
+                { format!("📎 {} ({})", attachment.name, format_size(attachment.size)) }
+            </a>
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct VideoAttachmentProps {
+    pub attachment: Attachment,
+}
+
+// Pemutar video inline. Sebelum diklik hanya poster yang ditampilkan, sehingga data video
+// baru diunduh saat user benar-benar ingin memutarnya.
+#[function_component(VideoAttachment)]
+pub fn video_attachment(props: &VideoAttachmentProps) -> Html {
+    let activated = use_state(|| false);
+    let attachment = &props.attachment;
+
+    if *activated {
+        return html! {
+            <video
+                class="attachment attachment-video"
+                src={attachment.url.clone()}
+                poster={attachment.poster.clone()}
+                controls=true
+                autoplay=true
+                preload="none"
+            />
+        };
+    }
+    let on_play = {
+        let activated = activated.clone();
+        Callback::from(move |_| activated.set(true))
+    };
+    html! {
+        <button class="attachment attachment-video-poster" onclick={on_play} title={attachment.name.clone()}>
+            if let Some(poster) = &attachment.poster {
+                <img src={poster.clone()} alt={attachment.name.clone()} />
+            } else {
+                <span class="video-placeholder">{ &attachment.name }</span>
+            }
+            <span class="video-play">{ "▶" }</span>
+            <span class="video-size">{ format_size(attachment.size) }</span>
+        </button>
+    }
+}
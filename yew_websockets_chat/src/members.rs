@@ -0,0 +1,90 @@
+// src/members.rs
+// Daftar anggota room yang dimuat per halaman. Room besar bisa punya ribuan anggota, jadi klien
+// hanya meminta halaman berikutnya saat sidebar digulir mendekati bawah. Pencarian memakai indeks
+// anggota di server; hasilnya menggantikan daftar, bukan disaring dari halaman yang sudah dimuat.
+use crate::protocol::{ClientCommand, MemberInfo};
+
+pub const PAGE_SIZE: usize = 50;
+pub const SEARCH_DEBOUNCE_MS: u32 = 300; // Tunggu user berhenti mengetik sebelum bertanya ke server
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemberList {
+    room: String,
+    query: String, // Isi kotak pencarian apa adanya; kosong = seluruh anggota
+    members: Vec<(String, String)>, // (user_id, nama di halaman), urutan dari server; presence ada di ChatState
+    next_cursor: Option<String>,
+    total: Option<u64>,
+    loading: bool,
+    complete: bool, // Server tidak punya halaman lagi
+}
+
+impl MemberList {
+    pub fn new(room: String, query: String) -> Self {
+        Self { room, query, ..Self::default() }
+    }
+
+    pub fn room(&self) -> &str {
+        &self.room
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn members(&self) -> &[(String, String)] {
+        &self.members
+    }
+
+    pub fn total(&self) -> Option<u64> {
+        self.total
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.loading
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    // Perintah untuk halaman berikutnya, atau None jika masih menunggu balasan / sudah habis
+    pub fn next_request(&mut self) -> Option<ClientCommand> {
+        if self.room.is_empty() || self.loading || self.complete {
+            return None;
+        }
+        self.loading = true;
+        Some(ClientCommand::FetchMembers {
+            room: self.room.clone(),
+            query: self.search_term().map(str::to_string),
+            cursor: self.next_cursor.clone(),
+            limit: PAGE_SIZE,
+        })
+    }
+
+    // Koneksi putus sebelum balasan datang; halaman yang sama boleh diminta lagi
+    pub fn cancel_request(&mut self) {
+        self.loading = false;
+    }
+
+    fn search_term(&self) -> Option<&str> {
+        Some(self.query.trim()).filter(|term| !term.is_empty())
+    }
+
+    // Tambahkan satu halaman. Balasan untuk room atau pencarian lama diabaikan (false).
+    pub fn append(&mut self, room: &str, query: Option<&str>, members: &[MemberInfo], next_cursor: Option<String>, total: Option<u64>) -> bool {
+        if room != self.room || query.map(str::trim).filter(|term| !term.is_empty()) != self.search_term() {
+            return false;
+        }
+        for member in members {
+            // Anggota bisa bergeser antar halaman saat ada yang join
+            if !self.members.iter().any(|(user_id, _)| *user_id == member.user_id) {
+                self.members.push((member.user_id.clone(), member.name.clone()));
+            }
+        }
+        self.complete = next_cursor.is_none();
+        self.next_cursor = next_cursor;
+        self.total = total.or(self.total);
+        self.loading = false;
+        true
+    }
+}
@@ -0,0 +1,85 @@
+// src/members_panel.rs
+// Sidebar anggota room. Halaman berikutnya diminta saat daftar digulir mendekati bawah;
+// kotak pencarian diteruskan ke App yang menanyakan indeks anggota di server.
+use web_sys::{Element, HtmlInputElement};
+use yew::prelude::*;
+
+use crate::i18n::use_locale;
+
+const LOAD_MORE_THRESHOLD_PX: i32 = 120; // Jarak dari bawah daftar sebelum halaman berikutnya diminta
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemberRow {
+    pub user_id: String,
+    pub name: String,
+    pub online: bool,
+    pub status: Option<String>, // Dari presence::describe
+    pub custom_status: Option<String>, // Emoji dan teks status kustom yang belum kedaluwarsa
+}
+
+#[derive(Properties, PartialEq)]
+pub struct MembersPanelProps {
+    pub members: Vec<MemberRow>,
+    pub query: String,
+    pub total: Option<u64>, // Jumlah anggota (atau hasil pencarian) menurut server, jika dikirim
+    pub loading: bool,
+    pub complete: bool,
+    pub on_search: Callback<String>,
+    pub on_load_more: Callback<()>,
+}
+
+#[function_component(MembersPanel)]
+pub fn members_panel(props: &MembersPanelProps) -> Html {
+    let locale = use_locale();
+    let on_search = props.on_search.reform(|e: InputEvent| e.target_unchecked_into::<HtmlInputElement>().value());
+    let on_scroll = {
+        let on_load_more = props.on_load_more.clone();
+        let wants_more = !props.loading && !props.complete;
+        Callback::from(move |e: Event| {
+            let list: Element = e.target_unchecked_into();
+            if wants_more && list.scroll_top() + list.client_height() >= list.scroll_height() - LOAD_MORE_THRESHOLD_PX {
+                on_load_more.emit(());
+            }
+        })
+    };
+    let heading = match props.total {
+        Some(total) => locale.f("Anggota ({})", &[&total]),
+        None => locale.t("Anggota").to_string(),
+    };
+    html! {
+        <aside class="members-sidebar">
+            <h3>{ heading }</h3>
+            <input
+                type="search"
+                dir="auto"
+                placeholder={locale.t("Cari anggota...")}
+                value={props.query.clone()}
+                oninput={on_search}
+            />
+            <ul class="member-list" onscroll={on_scroll}>
+                { for props.members.iter().map(|member| html! {
+                    <li class={classes!("member-item", member.online.then_some("online"))} key={member.user_id.clone()}>
+                        <span class="member-name" dir="auto" title={member.user_id.clone()}>{ &member.name }</span>
+                        if let Some(status) = &member.status {
+                            <span class="member-status">{ status }</span>
+                        }
+                        if let Some(custom) = &member.custom_status {
+                            <span class="member-status custom-status-text" dir="auto">{ custom }</span>
+                        }
+                    </li>
+                }) }
+                if props.loading {
+                    <li class="member-loading">{ locale.t("Memuat...") }</li>
+                } else if props.complete && props.members.is_empty() {
+                    <li class="member-loading">
+                        { locale.t(if props.query.is_empty() { "Belum ada anggota" } else { "Tidak ada anggota yang cocok" }) }
+                    </li>
+                } else if !props.complete {
+                    <li>
+                        <button class="member-more" onclick={props.on_load_more.reform(|_| ())}>{ locale.t("Muat lebih banyak") }</button>
+                    </li>
+                }
+            </ul>
+        </aside>
+    }
+}
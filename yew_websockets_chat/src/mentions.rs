@@ -0,0 +1,152 @@
+// src/mentions.rs
+// Deteksi pesan yang menyebut user saat ini, navigasi antar mention di room, dan saran "@nama"
+// saat mengetik. Deteksi dilakukan sekali saat pesan masuk ke store (lihat `state::reduce`), bukan saat render.
+// Saran digabung dari direktori lokal dan pencarian user di server, sehingga anggota yang
+// sedang offline atau belum pernah menulis di room ini tetap bisa disebut.
+use std::collections::HashMap;
+
+use gloo_events::{EventListener, EventListenerOptions};
+use wasm_bindgen::JsCast;
+use web_sys::KeyboardEvent;
+use yew::Callback;
+
+use crate::protocol::{ClientCommand, UserMatch};
+
+pub const SUGGESTION_LIMIT: usize = 8;
+pub const SEARCH_DEBOUNCE_MS: u32 = 250; // Server membatasi laju pencarian; jangan bertanya tiap ketikan
+const MIN_SERVER_PREFIX: usize = 2; // Prefix satu huruf terlalu luas untuk deployment besar
+
+// Teks menyebut salah satu `handles` dalam bentuk "@handle" (tanpa memperhatikan huruf besar/kecil).
+// Handle harus diakhiri batas kata, sehingga "@budi" tidak cocok dengan "@budiman".
+pub fn is_mention(text: &str, handles: &[&str]) -> bool {
+    let text = text.to_lowercase();
+    handles.iter().filter(|handle| !handle.is_empty()).any(|handle| {
+        let needle = format!("@{}", handle.to_lowercase());
+        text.match_indices(&needle).any(|(at, _)| {
+            let before_ok = text[..at].chars().next_back().is_none_or(|c| !c.is_alphanumeric());
+            let after_ok = text[at + needle.len()..].chars().next().is_none_or(|c| !c.is_alphanumeric() && c != '_');
+            before_ok && after_ok
+        })
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MentionStep {
+    Previous,
+    Next,
+}
+
+// Entry mention berikutnya/sebelumnya dari posisi `current`, berputar di ujung daftar.
+// Tanpa posisi, "sebelumnya" mulai dari mention terbaru dan "berikutnya" dari yang terlama.
+pub fn step(mentions: &[usize], current: Option<usize>, step: MentionStep) -> Option<usize> {
+    let found = match (step, current) {
+        (MentionStep::Next, Some(current)) => mentions.iter().find(|&&i| i > current),
+        (MentionStep::Previous, Some(current)) => mentions.iter().rev().find(|&&i| i < current),
+        (_, None) => None,
+    };
+    let wrapped = match step {
+        MentionStep::Next => mentions.first(),
+        MentionStep::Previous => mentions.last(),
+    };
+    found.or(wrapped).copied()
+}
+
+// Alt+↑ / Alt+↓ di mana pun di halaman
+pub fn listen_shortcut(on_step: Callback<MentionStep>) -> Option<EventListener> {
+    let window = web_sys::window()?;
+    let options = EventListenerOptions::enable_prevent_default();
+    Some(EventListener::new_with_options(&window, "keydown", options, move |event| {
+        let Some(event) = event.dyn_ref::<KeyboardEvent>() else { return };
+        if !event.alt_key() || event.ctrl_key() || event.meta_key() {
+            return;
+        }
+        let step = match event.key().as_str() {
+            "ArrowUp" => MentionStep::Previous,
+            "ArrowDown" => MentionStep::Next,
+            _ => return,
+        };
+        event.prevent_default(); // Jangan gulir halaman
+        on_step.emit(step);
+    }))
+}
+
+// "@pre" yang sedang diketik di akhir input: (posisi byte '@', prefix tanpa '@')
+pub fn active_query(input: &str) -> Option<(usize, &str)> {
+    let at = input.rfind('@')?;
+    let prefix = &input[at + 1..];
+    let starts_word = input[..at].chars().next_back().is_none_or(char::is_whitespace);
+    (starts_word && !prefix.contains(char::is_whitespace)).then_some((at, prefix))
+}
+
+// Ganti "@pre" di posisi `at` dengan handle lengkap
+pub fn complete(input: &str, at: usize, handle: &str) -> String {
+    format!("{}@{} ", &input[..at], handle)
+}
+
+// Teks setelah '@': display name jika satu kata (is_mention mencocokkannya), selain itu id akun
+pub fn handle_for(user: &UserMatch) -> &str {
+    if user.name.is_empty() || user.name.contains(char::is_whitespace) {
+        &user.user_id
+    } else {
+        &user.name
+    }
+}
+
+// User yang ditulis sebagai "@handle" (id, atau display name satu kata), tanpa memperhatikan huruf besar/kecil
+pub fn resolve_handle(users: impl IntoIterator<Item = UserMatch>, handle: &str) -> Option<UserMatch> {
+    let handle = handle.to_lowercase();
+    users.into_iter().find(|user| user.user_id.to_lowercase() == handle || handle_for(user).to_lowercase() == handle)
+}
+
+// Prefix cocok dengan id, awal nama, atau awal salah satu kata di nama
+pub fn matches_prefix(user_id: &str, name: &str, prefix: &str) -> bool {
+    let prefix = prefix.to_lowercase();
+    user_id.to_lowercase().starts_with(&prefix) || name.to_lowercase().split_whitespace().any(|word| word.starts_with(&prefix))
+}
+
+// Hasil pencarian user di server per prefix, supaya prefix yang sama atau lebih panjang tidak ditanyakan lagi
+#[derive(Debug, Default)]
+pub struct MentionSearch {
+    results: HashMap<String, Vec<UserMatch>>, // Kunci: prefix huruf kecil
+    pending: Option<String>,
+}
+
+impl MentionSearch {
+    // Hasil server untuk prefix ini. Hasil prefix yang lebih pendek yang tidak terpotong limit
+    // sudah memuat semua kecocokan, jadi cukup disaring di klien.
+    pub fn cached(&self, prefix: &str) -> Option<Vec<UserMatch>> {
+        let prefix = prefix.to_lowercase();
+        if let Some(users) = self.results.get(&prefix) {
+            return Some(users.clone());
+        }
+        self.results
+            .iter()
+            .filter(|(known, users)| prefix.starts_with(known.as_str()) && users.len() < SUGGESTION_LIMIT)
+            .max_by_key(|(known, _)| known.len())
+            .map(|(_, users)| users.iter().filter(|user| matches_prefix(&user.user_id, &user.name, &prefix)).cloned().collect())
+    }
+
+    pub fn needs_fetch(&self, prefix: &str) -> bool {
+        prefix.chars().count() >= MIN_SERVER_PREFIX
+            && self.pending.as_deref() != Some(prefix.to_lowercase().as_str())
+            && self.cached(prefix).is_none()
+    }
+
+    pub fn request(&mut self, prefix: &str) -> ClientCommand {
+        let prefix = prefix.to_lowercase();
+        self.pending = Some(prefix.clone());
+        ClientCommand::SearchUsers { prefix, limit: SUGGESTION_LIMIT }
+    }
+
+    pub fn store(&mut self, prefix: String, users: Vec<UserMatch>) {
+        if self.pending.as_ref() == Some(&prefix) {
+            self.pending = None;
+        }
+        self.results.insert(prefix, users);
+    }
+
+    // Server menolak karena rate limit; prefix ini boleh dicoba lagi nanti
+    pub fn cancel(&mut self) {
+        self.pending = None;
+    }
+}
@@ -0,0 +1,82 @@
+// src/notes.rs
+// Notepad bersama per room. Catatan disinkronkan lewat saluran Custom dengan NOTES_NAMESPACE dan
+// digabung sebagai CRDT last-writer-wins (Notepad di chat-protocol), jadi suntingan dari beberapa
+// anggota sekaligus berakhir sama di semua klien. Server menyimpan notepad tiap room dan mengirim
+// snapshot saat diminta (setelah terhubung dan setiap bergabung ke room).
+use web_sys::HtmlTextAreaElement;
+use yew::prelude::*;
+
+use crate::i18n::use_locale;
+
+// Id catatan baru; unik tanpa koordinasi karena memuat angka acak dan jam klien
+pub fn new_note_id() -> String {
+    format!("n-{:08x}-{}", (js_sys::Math::random() * f64::from(u32::MAX)) as u32, js_sys::Date::now() as u64)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteItem {
+    pub id: String,
+    pub text: String,
+    pub edited_by: String, // Display name penyunting terakhir
+}
+
+#[derive(Properties, PartialEq)]
+pub struct NotesProps {
+    pub items: Vec<NoteItem>,
+    pub disabled: bool, // Offline, atau room anonim tempat nama penyunting akan terbuka
+    pub on_save: Callback<(Option<String>, String)>, // Id catatan (None = catatan baru) dan teksnya
+    pub on_delete: Callback<String>,
+}
+
+// Suntingan dikirim saat textarea kehilangan fokus (event change), bukan per ketukan
+#[function_component(NotesPanel)]
+pub fn notes_panel(props: &NotesProps) -> Html {
+    let locale = use_locale();
+    let draft = use_state(String::new);
+
+    let on_draft_input = {
+        let draft = draft.clone();
+        Callback::from(move |e: InputEvent| draft.set(e.target_unchecked_into::<HtmlTextAreaElement>().value()))
+    };
+    let on_add = {
+        let (draft, on_save) = (draft.clone(), props.on_save.clone());
+        Callback::from(move |_| {
+            if !draft.trim().is_empty() {
+                on_save.emit((None, draft.trim().to_string()));
+                draft.set(String::new());
+            }
+        })
+    };
+
+    html! {
+        <details class="notes-panel">
+            <summary>{ locale.f("Catatan room ({})", &[&props.items.len()]) }</summary>
+            if props.items.is_empty() {
+                <p class="notes-empty">{ locale.t("Belum ada catatan. Catatan terlihat dan bisa disunting semua anggota room.") }</p>
+            }
+            <ul class="notes-list">
+                { for props.items.iter().map(|item| {
+                    let on_change = {
+                        let (id, on_save) = (item.id.clone(), props.on_save.clone());
+                        Callback::from(move |e: Event| on_save.emit((Some(id.clone()), e.target_unchecked_into::<HtmlTextAreaElement>().value())))
+                    };
+                    let on_delete = {
+                        let (id, on_delete) = (item.id.clone(), props.on_delete.clone());
+                        Callback::from(move |_| on_delete.emit(id.clone()))
+                    };
+                    html! {
+                        <li class="note-item" key={item.id.clone()}>
+                            <textarea value={item.text.clone()} onchange={on_change} disabled={props.disabled} aria-label={locale.t("Catatan")} />
+                            <span class="note-meta">{ locale.f("Terakhir disunting {}", &[&item.edited_by]) }</span>
+                            <button type="button" class="link-button" onclick={on_delete} disabled={props.disabled}>{ locale.t("Hapus") }</button>
+                        </li>
+                    }
+                }) }
+            </ul>
+            <div class="notes-new">
+                <textarea value={(*draft).clone()} oninput={on_draft_input} disabled={props.disabled} placeholder={locale.t("Tulis catatan baru…")} />
+                <button type="button" onclick={on_add} disabled={props.disabled || draft.trim().is_empty()}>{ locale.t("Tambah catatan") }</button>
+            </div>
+        </details>
+    }
+}
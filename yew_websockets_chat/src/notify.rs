@@ -0,0 +1,133 @@
+// src/notify.rs
+// Notifikasi browser untuk pesan baru saat tab tidak terlihat, plus "bisukan" per room/DM.
+// Waktu berakhirnya bisukan disimpan di localStorage supaya tetap berlaku setelah reload.
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+use web_sys::{Notification, NotificationOptions, NotificationPermission};
+
+use crate::storage;
+
+const SNOOZES_KEY: &str = "webchat.snoozes";
+const TOMORROW_HOUR: u32 = 8; // "Sampai besok" berakhir pukul 08.00 waktu lokal
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SnoozeDuration {
+    OneHour,
+    EightHours,
+    UntilTomorrow,
+}
+
+impl SnoozeDuration {
+    pub const ALL: [SnoozeDuration; 3] = [SnoozeDuration::OneHour, SnoozeDuration::EightHours, SnoozeDuration::UntilTomorrow];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SnoozeDuration::OneHour => "Bisukan 1 jam",
+            SnoozeDuration::EightHours => "Bisukan 8 jam",
+            SnoozeDuration::UntilTomorrow => "Bisukan sampai besok",
+        }
+    }
+
+    // Nilai <option> di pilihan bisukan
+    pub fn key(self) -> &'static str {
+        match self {
+            SnoozeDuration::OneHour => "1h",
+            SnoozeDuration::EightHours => "8h",
+            SnoozeDuration::UntilTomorrow => "tomorrow",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|duration| duration.key() == key)
+    }
+
+    // Epoch millis saat bisukan berakhir jika dimulai pada `now_ms`
+    pub fn until(self, now_ms: f64) -> f64 {
+        match self {
+            SnoozeDuration::OneHour => now_ms + 3_600_000.0,
+            SnoozeDuration::EightHours => now_ms + 8.0 * 3_600_000.0,
+            SnoozeDuration::UntilTomorrow => {
+                let date = js_sys::Date::new(&JsValue::from_f64(now_ms));
+                date.set_date(date.get_date() + 1); // Date menormalkan akhir bulan sendiri
+                date.set_hours(TOMORROW_HOUR);
+                date.set_minutes(0);
+                date.set_seconds(0);
+                date.set_milliseconds(0);
+                date.get_time()
+            }
+        }
+    }
+}
+
+// Room yang dibisukan beserta waktu berakhirnya (epoch millis)
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Snoozes {
+    rooms: HashMap<String, f64>,
+}
+
+impl Snoozes {
+    // Muat dari localStorage dan buang yang sudah berakhir
+    pub fn load(now_ms: f64) -> Self {
+        let mut snoozes: Self = storage::get_json(SNOOZES_KEY).unwrap_or_default();
+        if snoozes.prune(now_ms) {
+            snoozes.save();
+        }
+        snoozes
+    }
+
+    fn save(&self) {
+        storage::set_json(SNOOZES_KEY, self);
+    }
+
+    fn prune(&mut self, now_ms: f64) -> bool {
+        let before = self.rooms.len();
+        self.rooms.retain(|_, until| *until > now_ms);
+        self.rooms.len() != before
+    }
+
+    pub fn snooze(&mut self, room: &str, until: f64) {
+        self.rooms.insert(room.to_string(), until);
+        self.save();
+    }
+
+    pub fn unsnooze(&mut self, room: &str) {
+        if self.rooms.remove(room).is_some() {
+            self.save();
+        }
+    }
+
+    // Waktu berakhir jika room ini masih dibisukan pada `now_ms`
+    pub fn snoozed_until(&self, room: &str, now_ms: f64) -> Option<f64> {
+        self.rooms.get(room).copied().filter(|until| *until > now_ms)
+    }
+}
+
+pub fn permission() -> NotificationPermission {
+    Notification::permission()
+}
+
+// Harus dipanggil dari handler klik; browser menolak permintaan izin tanpa interaksi user
+pub fn request_permission() {
+    if let Err(e) = Notification::request_permission() {
+        log::warn!("Gagal meminta izin notifikasi: {:?}", e);
+    }
+}
+
+fn page_hidden() -> bool {
+    web_sys::window().and_then(|w| w.document()).is_some_and(|d| d.hidden())
+}
+
+// Tampilkan notifikasi jika tab tidak terlihat, izin sudah diberikan, dan room tidak dibisukan
+pub fn notify_message(snoozes: &Snoozes, room: &str, title: &str, body: &str, now_ms: f64) {
+    if !page_hidden() || permission() != NotificationPermission::Granted || snoozes.snoozed_until(room, now_ms).is_some() {
+        return;
+    }
+    let options = NotificationOptions::new();
+    options.set_body(body);
+    options.set_tag(room); // Satu notifikasi per room, yang baru menggantikan yang lama
+    if let Err(e) = Notification::new_with_options(title, &options) {
+        log::warn!("Gagal menampilkan notifikasi: {:?}", e);
+    }
+}
@@ -0,0 +1,85 @@
+// src/online_panel.rs
+// Sidebar user yang sedang online di room yang ditampilkan, dari daftar Roster server beserta
+// statusnya. Status user ini sendiri bisa diganti dari panel yang sama.
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+use crate::i18n::{tr, trf, use_locale};
+use crate::protocol::{CustomStatus, UserStatus};
+use crate::status::StatusDraft;
+use crate::status_panel::StatusEditor;
+use crate::time;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OnlineRow {
+    pub user_id: String,
+    pub name: String,
+    pub status: UserStatus,
+    pub custom_status: Option<CustomStatus>, // Sudah disaring dari status yang kedaluwarsa
+    pub me: bool,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct OnlinePanelProps {
+    pub users: Option<Vec<OnlineRow>>, // None = daftar belum diterima dari server
+    pub my_status: UserStatus,
+    pub my_custom_status: Option<CustomStatus>,
+    pub connected: bool,
+    pub on_status_change: Callback<UserStatus>,
+    pub on_custom_status: Callback<Option<StatusDraft>>,
+    pub on_open: Callback<String>, // Buka pesan langsung dengan user ini
+}
+
+#[function_component(OnlinePanel)]
+pub fn online_panel(props: &OnlinePanelProps) -> Html {
+    let locale = use_locale();
+    let on_status_change = props.on_status_change.reform(|e: Event| {
+        let select: HtmlSelectElement = e.target_unchecked_into();
+        UserStatus::ALL.get(select.selected_index().max(0) as usize).copied().unwrap_or_default()
+    });
+    let heading = match &props.users {
+        Some(users) => locale.f("Online ({})", &[&users.len()]),
+        None => locale.t("Online").to_string(),
+    };
+    html! {
+        <aside class="online-sidebar">
+            <h3>{ heading }</h3>
+            <label class="my-status">
+                { locale.t("Status saya:") }{ " " }
+                <select onchange={on_status_change} disabled={!props.connected}>
+                    { for UserStatus::ALL.iter().map(|status| html! {
+                        <option selected={*status == props.my_status}>{ locale.t(status.label()) }</option>
+                    }) }
+                </select>
+            </label>
+            <StatusEditor current={props.my_custom_status.clone()} connected={props.connected} on_change={props.on_custom_status.clone()} />
+            <ul class="online-list">
+                { for props.users.iter().flatten().map(|user| view_user(props, user)) }
+                { match &props.users {
+                    None => html! { <li class="member-loading">{ locale.t("Memuat...") }</li> },
+                    Some(users) if users.is_empty() => html! { <li class="member-loading">{ locale.t("Tidak ada yang online") }</li> },
+                    Some(_) => html! {},
+                } }
+            </ul>
+        </aside>
+    }
+}
+
+fn view_user(props: &OnlinePanelProps, user: &OnlineRow) -> Html {
+    let user_id = user.user_id.clone();
+    html! {
+        <li class={classes!("online-user", user.status.class())} key={user.user_id.clone()} title={tr(user.status.label())}>
+            <span class="status-dot"></span>
+            if user.me {
+                <span class="member-name" dir="auto">{ trf("{} (Anda)", &[&user.name]) }</span>
+            } else {
+                <button class="link-button member-name" dir="auto" onclick={props.on_open.reform(move |_| user_id.clone())}>{ &user.name }</button>
+            }
+            if let Some(custom) = &user.custom_status {
+                <span class="member-status custom-status-text" dir="auto" title={custom.expires_at.map(|at| trf("Sampai {}", &[&time::format_absolute(at)]))}>
+                    { custom.label() }
+                </span>
+            }
+        </li>
+    }
+}
@@ -0,0 +1,123 @@
+// src/outbox.rs
+use serde::{Deserialize, Serialize};
+
+use crate::vault::{self, EncryptedSlot, VaultKey};
+use crate::{storage, ChatMessage};
+
+const OUTBOX_KEY: &str = "webchat.outbox";
+
+// Id pesan buatan klien; server mengembalikannya di pesan yang sudah disimpan sebagai tanda terima
+pub fn new_client_id() -> String {
+    format!("c-{:08x}-{}", (js_sys::Math::random() * f64::from(u32::MAX)) as u32, js_sys::Date::now() as u64)
+}
+
+// Pesan yang ditulis saat offline dan belum terkirim
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct QueuedMessage {
+    pub message: ChatMessage,
+    pub queued_at: f64, // Epoch millis
+    #[serde(skip)]
+    pub restored: bool, // Berasal dari sesi sebelumnya; tidak dikirim otomatis, user yang memutuskan
+}
+
+// Antrean kirim yang disimpan di localStorage, supaya pesan yang ditulis saat offline
+// tidak hilang walaupun tab ditutup sebelum koneksi pulih. Jika enkripsi cache aktif,
+// isinya dienkripsi dengan kunci vault dan baru bisa dibaca setelah chat dibuka kuncinya.
+#[derive(Debug, Default)]
+pub struct Outbox {
+    items: Vec<QueuedMessage>,
+    key: Option<VaultKey>,
+    slot: EncryptedSlot,
+}
+
+impl Outbox {
+    // Muat antrean tersimpan. Saat enkripsi aktif antrean dimulai kosong sampai `unlock`.
+    pub fn load() -> Self {
+        if vault::is_enabled() {
+            return Self::default();
+        }
+        let mut items: Vec<QueuedMessage> = storage::get_json(OUTBOX_KEY).unwrap_or_default();
+        for item in &mut items {
+            item.restored = true;
+        }
+        Self { items, ..Self::default() }
+    }
+
+    // Baca dan dekripsi antrean tersimpan dengan kunci vault
+    pub async fn load_encrypted(key: &VaultKey) -> Result<Vec<QueuedMessage>, String> {
+        let mut items: Vec<QueuedMessage> = vault::load_json(key, OUTBOX_KEY).await?.unwrap_or_default();
+        for item in &mut items {
+            item.restored = true;
+        }
+        Ok(items)
+    }
+
+    // Pasang kunci vault (setelah setup atau unlock) lalu simpan ulang dalam bentuk terenkripsi
+    pub fn unlock(&mut self, key: VaultKey, restored: Vec<QueuedMessage>) {
+        self.key = Some(key);
+        let current = std::mem::take(&mut self.items);
+        self.items = restored.into_iter().chain(current).collect();
+        self.persist();
+    }
+
+    // Buang kunci dan isi antrean yang sudah didekripsi dari memori
+    pub fn lock(&mut self) {
+        self.key = None;
+        self.items.clear();
+    }
+
+    // Enkripsi dimatikan: simpan ulang sebagai JSON biasa
+    pub fn disable_encryption(&mut self) {
+        self.key = None;
+        self.persist();
+    }
+
+    pub fn push(&mut self, message: ChatMessage) {
+        self.items.push(QueuedMessage { message, queued_at: js_sys::Date::now(), restored: false });
+        self.persist();
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<QueuedMessage> {
+        if index >= self.items.len() {
+            return None;
+        }
+        let item = self.items.remove(index);
+        self.persist();
+        Some(item)
+    }
+
+    // Ambil semua pesan dari sesi ini untuk dikirim otomatis setelah reconnect
+    pub fn take_pending(&mut self) -> Vec<QueuedMessage> {
+        let (pending, restored) = std::mem::take(&mut self.items).into_iter().partition(|item| !item.restored);
+        self.items = restored;
+        self.persist();
+        pending
+    }
+
+    pub fn items(&self) -> &[QueuedMessage] {
+        &self.items
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    fn persist(&self) {
+        if self.key.is_none() && vault::is_enabled() {
+            return; // Terkunci: jangan sentuh data terenkripsi yang tersimpan
+        }
+        self.slot.cancel();
+        if self.items.is_empty() {
+            storage::remove(OUTBOX_KEY);
+            return;
+        }
+        match &self.key {
+            Some(key) => {
+                if let Ok(plaintext) = serde_json::to_vec(&self.items) {
+                    self.slot.store(key, OUTBOX_KEY, plaintext);
+                }
+            }
+            None => storage::set_json(OUTBOX_KEY, &self.items),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+// src/palette.rs
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::commands::{fuzzy_score, PaletteAction, PaletteItem};
+use crate::i18n::use_locale;
+
+#[derive(Properties, PartialEq)]
+pub struct CommandPaletteProps {
+    pub items: Vec<PaletteItem>,
+    pub on_select: Callback<PaletteAction>,
+    pub on_close: Callback<()>,
+}
+
+// Palet perintah: ketik untuk memfilter, panah atas/bawah untuk memilih, Enter menjalankan, Esc menutup
+#[function_component(CommandPalette)]
+pub fn command_palette(props: &CommandPaletteProps) -> Html {
+    let locale = use_locale();
+    let query = use_state(String::new);
+    let selected = use_state(|| 0usize);
+
+    let matches: Vec<&PaletteItem> = if query.trim().is_empty() {
+        props.items.iter().collect()
+    } else {
+        let mut scored: Vec<(i32, &PaletteItem)> = props
+            .items
+            .iter()
+            .filter_map(|item| fuzzy_score(&query, &item.label).map(|score| (score, item)))
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score)); // Stabil: urutan grup tetap untuk skor yang sama
+        scored.into_iter().map(|(_, item)| item).collect()
+    };
+    let current = (*selected).min(matches.len().saturating_sub(1));
+
+    let on_input = {
+        let query = query.clone();
+        let selected = selected.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            query.set(input.value());
+            selected.set(0);
+        })
+    };
+    let on_keydown = {
+        let selected = selected.clone();
+        let on_select = props.on_select.clone();
+        let on_close = props.on_close.clone();
+        let actions: Vec<PaletteAction> = matches.iter().map(|item| item.action.clone()).collect();
+        Callback::from(move |e: KeyboardEvent| {
+            let count = actions.len();
+            match e.key().as_str() {
+                "ArrowDown" if count > 0 => selected.set((current + 1) % count),
+                "ArrowUp" if count > 0 => selected.set((current + count - 1) % count),
+                "Enter" => {
+                    if let Some(action) = actions.get(current) {
+                        on_select.emit(action.clone());
+                    }
+                }
+                "Escape" => on_close.emit(()),
+                _ => return,
+            }
+            e.prevent_default();
+        })
+    };
+
+    html! {
+        <div class="modal-backdrop" onclick={props.on_close.reform(|_| ())}>
+            <div class="modal command-palette" role="dialog" aria-label={locale.t("Palet perintah")} onclick={Callback::from(|e: MouseEvent| e.stop_propagation())}>
+                <input
+                    type="text"
+                    placeholder={locale.t("Cari room, pengaturan atau perintah...")}
+                    autofocus=true
+                    value={(*query).clone()}
+                    oninput={on_input}
+                    onkeydown={on_keydown}
+                />
+                <ul class="palette-results" role="listbox">
+                    if matches.is_empty() {
+                        <li class="palette-empty">{ locale.t("Tidak ada yang cocok") }</li>
+                    }
+                    { for matches.iter().enumerate().map(|(i, item)| {
+                        let action = item.action.clone();
+                        let onclick = props.on_select.reform(move |_| action.clone());
+                        html! {
+                            <li class={classes!("palette-item", (i == current).then_some("selected"))} role="option" aria-selected={(i == current).to_string()} {onclick}>
+                                <span class="palette-group">{ locale.t(item.group.label()) }</span>
+                                <span class="palette-label">{ &item.label }</span>
+                                if let Some(hint) = &item.hint {
+                                    <span class="palette-hint">{ hint }</span>
+                                }
+                            </li>
+                        }
+                    }) }
+                </ul>
+            </div>
+        </div>
+    }
+}
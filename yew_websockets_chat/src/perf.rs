@@ -0,0 +1,43 @@
+// src/perf.rs
+// Pengukuran render untuk build `perf` (`trunk serve --features perf`). Setiap render komponen yang
+// diukur ditandai `performance.mark`/`measure` sehingga terlihat di tab Performance DevTools, dan render
+// yang lebih lama dari satu frame dicatat ke console beserta jumlah pesannya. Untuk komponen struct,
+// `begin` dipanggil di awal `view` dan `end` di `rendered`, jadi waktu diff dan patch DOM ikut terhitung.
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use web_sys::Performance;
+
+const SLOW_RENDER_MS: f64 = 16.0; // Satu frame pada 60 Hz
+
+thread_local! {
+    static STARTED: RefCell<HashMap<&'static str, (f64, usize)>> = RefCell::default();
+}
+
+fn performance() -> Option<Performance> {
+    web_sys::window()?.performance()
+}
+
+pub fn begin(component: &'static str, messages: usize) {
+    let Some(performance) = performance() else { return };
+    let _ = performance.mark(&format!("{}:start", component));
+    STARTED.with(|started| started.borrow_mut().insert(component, (performance.now(), messages)));
+}
+
+// Render tanpa `begin` yang cocok (mis. `rendered` setelah `view` dilewati) diabaikan
+pub fn end(component: &'static str) {
+    let Some(performance) = performance() else { return };
+    let Some((start, messages)) = STARTED.with(|started| started.borrow_mut().remove(component)) else { return };
+    let (start_mark, end_mark) = (format!("{}:start", component), format!("{}:end", component));
+    let _ = performance.mark(&end_mark);
+    let name = format!("render {}", component);
+    let _ = performance.measure_with_start_mark_and_end_mark(&name, &start_mark, &end_mark);
+    performance.clear_marks_with_mark_name(&start_mark);
+    performance.clear_marks_with_mark_name(&end_mark);
+    let elapsed = performance.now() - start;
+    if elapsed > SLOW_RENDER_MS {
+        log::warn!("Render {} lambat: {:.1} ms ({} pesan)", component, elapsed, messages);
+    } else {
+        performance.clear_measures_with_measure_name(&name); // Buffer timeline hanya menyimpan render yang lambat
+    }
+}
@@ -0,0 +1,154 @@
+// src/plugins.rs
+// Saluran samping untuk embedder: data realtime milik aplikasi induk (posisi kursor, langkah permainan)
+// ikut menumpang koneksi chat lewat ClientCommand::Custom / ServerEvent::Custom. Embedder membuat satu
+// PluginRegistry, mendaftarkan handler per namespace, lalu memberikannya ke App lewat prop `plugins`:
+//
+//     let plugins = PluginRegistry::default();
+//     plugins.register("cursor", Callback::from(|event: CustomEvent| { /* event.payload */ }));
+//     html! { <App plugins={plugins.clone()} /> }
+//     plugins.send("cursor", "{\"x\":10,\"y\":20}", Some("pos".to_string()));
+//
+// Payload berupa teks apa adanya (biasanya JSON buatan embedder); chat tidak membaca isinya.
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use yew::Callback;
+
+use crate::protocol::{MAX_CUSTOM_PAYLOAD_BYTES, NOTES_NAMESPACE};
+
+// ServerEvent::Custom dari user lain, diteruskan ke handler namespace-nya
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomEvent {
+    pub room: String, // Kosong = room umum
+    pub user_id: String,
+    pub namespace: String,
+    pub payload: String,
+}
+
+// Dari PluginRegistry::send ke App, yang mengirimnya ke room yang sedang dibuka
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomSend {
+    pub namespace: String,
+    pub payload: String,
+    pub key: Option<String>, // Lihat ClientCommand::Custom: frame dengan key sama saling menggantikan
+}
+
+#[derive(Default)]
+struct Registry {
+    handlers: HashMap<String, Callback<CustomEvent>>,
+    outgoing: Option<Callback<CustomSend>>, // Diisi App saat dibuat
+}
+
+// Murah untuk di-clone; semua clone berbagi handler yang sama. Dua registry dianggap sama hanya
+// jika keduanya clone dari registry yang sama, sehingga mendaftarkan handler tidak me-render App.
+#[derive(Clone, Default)]
+pub struct PluginRegistry {
+    inner: Rc<RefCell<Registry>>,
+}
+
+impl PartialEq for PluginRegistry {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl PluginRegistry {
+    // Handler lama untuk namespace yang sama diganti
+    pub fn register(&self, namespace: impl Into<String>, handler: Callback<CustomEvent>) {
+        self.inner.borrow_mut().handlers.insert(namespace.into(), handler);
+    }
+
+    pub fn unregister(&self, namespace: &str) {
+        self.inner.borrow_mut().handlers.remove(namespace);
+    }
+
+    // Kirim ke anggota lain room yang sedang dibuka. false jika registry belum dipasang ke App, namespace
+    // kosong atau milik notepad bawaan, atau payload melebihi MAX_CUSTOM_PAYLOAD_BYTES; saat offline frame
+    // dibuang App tanpa antre.
+    pub fn send(&self, namespace: &str, payload: impl Into<String>, key: Option<String>) -> bool {
+        let payload = payload.into();
+        if namespace.is_empty() || namespace == NOTES_NAMESPACE || payload.len() > MAX_CUSTOM_PAYLOAD_BYTES {
+            return false;
+        }
+        let Some(outgoing) = self.inner.borrow().outgoing.clone() else { return false };
+        outgoing.emit(CustomSend { namespace: namespace.to_string(), payload, key });
+        true
+    }
+
+    pub(crate) fn attach(&self, outgoing: Callback<CustomSend>) {
+        self.inner.borrow_mut().outgoing = Some(outgoing);
+    }
+
+    // false jika tidak ada handler untuk namespace event ini
+    pub(crate) fn dispatch(&self, event: CustomEvent) -> bool {
+        let handler = self.inner.borrow().handlers.get(&event.namespace).cloned(); // Handler boleh mendaftar ulang
+        match handler {
+            Some(handler) => {
+                handler.emit(event);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(namespace: &str) -> CustomEvent {
+        CustomEvent { room: String::new(), user_id: "u1".to_string(), namespace: namespace.to_string(), payload: "{}".to_string() }
+    }
+
+    #[test]
+    fn send_checks_namespace_size_and_attachment() {
+        let plugins = PluginRegistry::default();
+        assert!(!plugins.send("cursor", "{}", None)); // Belum dipasang ke App
+        let sent = Rc::new(RefCell::new(Vec::new()));
+        plugins.attach({
+            let sent = sent.clone();
+            Callback::from(move |send: CustomSend| sent.borrow_mut().push(send))
+        });
+        assert!(plugins.send("cursor", "{}", Some("pos".to_string())));
+        assert!(!plugins.send("", "{}", None));
+        assert!(!plugins.send(NOTES_NAMESPACE, "[]", None));
+        assert!(!plugins.send("cursor", "x".repeat(MAX_CUSTOM_PAYLOAD_BYTES + 1), None));
+        assert!(plugins.clone().send("game", "x".repeat(MAX_CUSTOM_PAYLOAD_BYTES), None)); // Clone berbagi App yang sama
+        let namespaces: Vec<String> = sent.borrow().iter().map(|send| send.namespace.clone()).collect();
+        assert_eq!(namespaces, ["cursor", "game"]);
+        assert_eq!(sent.borrow()[0].key.as_deref(), Some("pos"));
+    }
+
+    #[test]
+    fn dispatch_routes_by_namespace() {
+        let plugins = PluginRegistry::default();
+        let received = Rc::new(RefCell::new(Vec::new()));
+        plugins.register("cursor", {
+            let received = received.clone();
+            Callback::from(move |event: CustomEvent| received.borrow_mut().push(event.namespace))
+        });
+        assert!(plugins.dispatch(event("cursor")));
+        assert!(!plugins.dispatch(event("game")));
+        plugins.unregister("cursor");
+        assert!(!plugins.dispatch(event("cursor")));
+        assert_eq!(*received.borrow(), ["cursor"]);
+    }
+
+    #[test]
+    fn handler_may_unregister_itself_while_dispatched() {
+        let plugins = PluginRegistry::default();
+        let calls = Rc::new(RefCell::new(0));
+        let handler = {
+            let (plugins, calls) = (plugins.clone(), calls.clone());
+            Callback::from(move |_: CustomEvent| {
+                *calls.borrow_mut() += 1;
+                plugins.unregister("once");
+            })
+        };
+        plugins.register("once", handler);
+        assert!(plugins.dispatch(event("once")));
+        assert!(!plugins.dispatch(event("once")));
+        assert_eq!(*calls.borrow(), 1);
+    }
+}
@@ -0,0 +1,152 @@
+// src/presence.rs
+use std::collections::{HashMap, HashSet};
+
+use crate::i18n::{tr, trf};
+use crate::protocol::{CustomStatus, RosterUser, UserStatus};
+use crate::time;
+
+// Status online satu user, dari event Presence server
+#[derive(Debug, Clone, PartialEq)]
+pub struct Presence {
+    pub online: bool,
+    pub last_seen: Option<f64>, // Epoch millis; None jika user menyembunyikannya
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PresenceStore {
+    users: HashMap<String, Presence>,
+    statuses: HashMap<String, UserStatus>, // Hanya user yang statusnya bukan Online
+    custom: HashMap<String, CustomStatus>, // Status kustom, termasuk yang sudah kedaluwarsa (disaring saat dibaca)
+    roster: Option<Roster>, // Daftar online room yang ditampilkan, setelah disinkronkan lewat SyncRoster
+}
+
+// User yang sedang online di satu room, urut sesuai kedatangan
+#[derive(Debug, Clone, PartialEq)]
+pub struct Roster {
+    pub room: String,
+    pub users: Vec<RosterUser>,
+}
+
+impl PresenceStore {
+    // Mengembalikan true jika status berubah (perlu re-render)
+    pub fn update(&mut self, user_id: String, presence: Presence) -> bool {
+        if self.users.get(&user_id) == Some(&presence) {
+            return false;
+        }
+        self.users.insert(user_id, presence);
+        true
+    }
+
+    // Gabungkan satu PresenceDigest sekaligus, sehingga ribuan perubahan hanya memicu satu re-render.
+    // Mengembalikan true jika ada status yang berubah.
+    pub fn merge_digest(&mut self, full: bool, online: Vec<String>, offline: Vec<(String, Option<f64>)>) -> bool {
+        let mut changed = false;
+        if full {
+            let online: HashSet<&str> = online.iter().map(String::as_str).collect();
+            for (user_id, presence) in self.users.iter_mut() {
+                if presence.online && !online.contains(user_id.as_str()) {
+                    presence.online = false; // last_seen lama dipertahankan
+                    changed = true;
+                }
+            }
+        }
+        for user_id in online {
+            changed |= self.update(user_id, Presence { online: true, last_seen: None });
+        }
+        for (user_id, last_seen) in offline {
+            changed |= self.update(user_id, Presence { online: false, last_seen });
+        }
+        changed
+    }
+
+    pub fn remove(&mut self, user_id: &str) {
+        self.users.remove(user_id);
+        self.statuses.remove(user_id);
+        self.custom.remove(user_id);
+        if let Some(roster) = &mut self.roster {
+            roster.users.retain(|user| user.user_id != user_id);
+        }
+    }
+
+    // Daftar lengkap dari server menggantikan daftar room sebelumnya
+    pub fn sync_roster(&mut self, room: String, users: Vec<RosterUser>) -> bool {
+        for user in &users {
+            self.update(user.user_id.clone(), Presence { online: true, last_seen: None });
+            self.set_status(user.user_id.clone(), user.status);
+            self.set_custom(user.user_id.clone(), user.custom_status.clone());
+        }
+        let roster = Some(Roster { room, users });
+        if self.roster == roster {
+            return false;
+        }
+        self.roster = roster;
+        true
+    }
+
+    // Perubahan untuk room lain (mis. masih dalam perjalanan setelah pindah room) diabaikan
+    pub fn user_joined(&mut self, room: &str, user: RosterUser) -> bool {
+        let mut changed = self.update(user.user_id.clone(), Presence { online: true, last_seen: None });
+        changed |= self.set_status(user.user_id.clone(), user.status);
+        changed |= self.set_custom(user.user_id.clone(), user.custom_status.clone());
+        let Some(roster) = self.roster.as_mut().filter(|roster| roster.room == room) else { return changed };
+        match roster.users.iter_mut().find(|existing| existing.user_id == user.user_id) {
+            Some(existing) if *existing == user => {}
+            Some(existing) => *existing = user,
+            None => roster.users.push(user),
+        }
+        true
+    }
+
+    // Keluar dari room belum tentu offline; status online tetap mengikuti event Presence
+    pub fn user_left(&mut self, room: &str, user_id: &str) -> bool {
+        let Some(roster) = self.roster.as_mut().filter(|roster| roster.room == room) else { return false };
+        let before = roster.users.len();
+        roster.users.retain(|user| user.user_id != user_id);
+        roster.users.len() != before
+    }
+
+    pub fn set_status(&mut self, user_id: String, status: UserStatus) -> bool {
+        if let Some(user) = self.roster.as_mut().and_then(|roster| roster.users.iter_mut().find(|user| user.user_id == user_id)) {
+            user.status = status;
+        }
+        let previous = match status {
+            UserStatus::Online => self.statuses.remove(&user_id),
+            _ => self.statuses.insert(user_id, status),
+        };
+        previous.unwrap_or_default() != status
+    }
+
+    pub fn set_custom(&mut self, user_id: String, status: Option<CustomStatus>) -> bool {
+        let previous = match status {
+            Some(status) => self.custom.insert(user_id.clone(), status),
+            None => self.custom.remove(&user_id),
+        };
+        previous.as_ref() != self.custom.get(&user_id)
+    }
+
+    pub fn custom_of(&self, user_id: &str, now_ms: f64) -> Option<&CustomStatus> {
+        self.custom.get(user_id).filter(|status| !status.is_expired(now_ms))
+    }
+
+    pub fn status_of(&self, user_id: &str) -> UserStatus {
+        self.statuses.get(user_id).copied().unwrap_or_default()
+    }
+
+    // None sampai Roster untuk room yang ditampilkan diterima
+    pub fn roster(&self, room: &str) -> Option<&Roster> {
+        self.roster.as_ref().filter(|roster| roster.room == room)
+    }
+
+    pub fn get(&self, user_id: &str) -> Option<&Presence> {
+        self.users.get(user_id)
+    }
+}
+
+// "online", "sibuk", "terakhir dilihat 10 menit lalu", atau None jika tidak ada yang bisa ditampilkan
+pub fn describe(presence: Option<&Presence>, status: UserStatus, now_ms: f64) -> Option<String> {
+    let presence = presence?;
+    if presence.online {
+        return Some(tr(status.label()).to_lowercase());
+    }
+    presence.last_seen.map(|ms| trf("terakhir dilihat {}", &[&time::format_relative(ms, now_ms)]))
+}
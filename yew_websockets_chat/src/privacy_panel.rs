@@ -0,0 +1,68 @@
+// src/privacy_panel.rs
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::i18n::use_locale;
+use crate::settings::Privacy;
+
+#[derive(Properties, PartialEq)]
+pub struct PrivacyPanelProps {
+    pub privacy: Privacy,
+    pub on_change: Callback<Privacy>,
+    pub export_pending: bool,       // Menunggu server menyiapkan arsip
+    pub export_url: Option<String>, // Tautan unduh arsip, jika sudah siap
+    pub on_export: Callback<()>,
+    pub on_delete_account: Callback<()>, // Konfirmasi ditangani induk
+}
+
+// Panel privasi. Sama seperti SettingsPanel, tiap perubahan langsung dikirim ke induk.
+#[function_component(PrivacyPanel)]
+pub fn privacy_panel(props: &PrivacyPanelProps) -> Html {
+    let locale = use_locale();
+    let toggle = |apply: fn(&mut Privacy, bool)| {
+        let privacy = props.privacy.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut next = privacy.clone();
+            apply(&mut next, input.checked());
+            on_change.emit(next);
+        })
+    };
+    let privacy = &props.privacy;
+
+    html! {
+        <details class="settings-panel privacy-panel">
+            <summary>{ locale.t("Privasi") }</summary>
+            <fieldset>
+                <label>
+                    <input type="checkbox" checked={privacy.hide_last_seen} onchange={toggle(|p, on| p.hide_last_seen = on)} />
+                    { " " }{ locale.t("Sembunyikan status \"terakhir dilihat\" saya") }
+                </label>
+                <label>
+                    <input type="checkbox" checked={privacy.disable_read_receipts} onchange={toggle(|p, on| p.disable_read_receipts = on)} />
+                    { " " }{ locale.t("Matikan tanda dibaca") }
+                    <span class="settings-hint">{ " " }{ locale.t("(Anda juga tidak akan melihat tanda dibaca dari orang lain)") }</span>
+                </label>
+                <label>
+                    <input type="checkbox" checked={privacy.hide_typing} onchange={toggle(|p, on| p.hide_typing = on)} />
+                    { " " }{ locale.t("Jangan tampilkan saat saya sedang mengetik") }
+                </label>
+                <label>
+                    <input type="checkbox" checked={privacy.block_dm_from_strangers} onchange={toggle(|p, on| p.block_dm_from_strangers = on)} />
+                    { " " }{ locale.t("Tolak pesan langsung dari selain kontak") }
+                </label>
+            </fieldset>
+            <fieldset>
+                <legend>{ locale.t("Data akun") }</legend>
+                <button onclick={props.on_export.reform(|_| ())} disabled={props.export_pending}>
+                    { locale.t(if props.export_pending { "Menyiapkan arsip…" } else { "Ekspor semua pesan saya" }) }
+                </button>
+                if let Some(url) = &props.export_url {
+                    <a class="export-link" href={url.clone()} download="">{ locale.t("Unduh arsip") }</a>
+                }
+                <button class="danger" onclick={props.on_delete_account.reform(|_| ())}>{ locale.t("Hapus akun") }</button>
+            </fieldset>
+        </details>
+    }
+}
@@ -0,0 +1,3 @@
+// src/protocol.rs
+// Frame protokol dan ChatMessage didefinisikan di crate chat-protocol, yang juga dipakai chat-server.
+pub use chat_protocol::*;
@@ -0,0 +1,28 @@
+// src/read_marks.rs
+// Tanda dibaca satu room: pesan terbaru yang sudah dirender klien tiap user, dari ServerEvent::ReadReceipt.
+// Yang disimpan id pesan, bukan index, karena index bergeser saat riwayat lama disisipkan di atas.
+// Pesan yang idnya belum dimuat (lebih lama dari riwayat yang ada) dianggap belum terbaca.
+use std::collections::HashMap;
+
+use crate::store::MessageStore;
+
+#[derive(Debug, Clone, Default)]
+pub struct ReadMarks {
+    marks: HashMap<String, String>, // User id -> id pesan
+}
+
+impl ReadMarks {
+    // Server hanya meneruskan tanda yang maju, jadi yang terakhir diterima selalu menggantikan yang lama
+    pub fn record(&mut self, user_id: String, message_id: String) -> bool {
+        self.marks.insert(user_id, message_id.clone()).as_ref() != Some(&message_id)
+    }
+
+    // User selain `me` yang sudah membaca sampai entry `index` atau lebih jauh
+    pub fn seen_by<'a>(&'a self, messages: &MessageStore, index: usize, me: &str) -> Vec<&'a str> {
+        self.marks
+            .iter()
+            .filter(|(user_id, message_id)| *user_id != me && messages.position(message_id).is_some_and(|read| read >= index))
+            .map(|(user_id, _)| user_id.as_str())
+            .collect()
+    }
+}
@@ -0,0 +1,60 @@
+// src/receipts.rs
+// Mode debug "tanda terima" untuk build dengan fitur `dev`: tiap pesan live diberi catatan urutan
+// kedatangan, nomor urut dari server (field `seq` jika ada), codec frame dan selisih waktu tiba
+// dengan timestamp server. Membantu pengembang server memeriksa urutan pesan dan jam server.
+use std::collections::HashMap;
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrivalReceipt {
+    pub arrival_seq: u64,        // Urutan kedatangan di klien ini, mulai dari 1
+    pub server_seq: Option<u64>, // Field `seq` pada payload, jika server mengirimnya
+    pub out_of_order: bool,      // `seq` lebih kecil dari yang sudah pernah diterima
+    pub codec: &'static str, // Encoding::label frame pesan
+    pub arrived_local: f64,  // Jam browser saat frame diproses
+    pub arrived_server: f64, // Jam server yang dikoreksi ServerClock pada saat yang sama
+}
+
+impl ArrivalReceipt {
+    // (selisih menurut jam lokal, selisih menurut jam server terkoreksi) dalam millis
+    pub fn deltas(&self, sent: f64) -> (f64, f64) {
+        (self.arrived_local - sent, self.arrived_server - sent)
+    }
+}
+
+// Catatan per id pesan; pesan tanpa id dan pesan dari riwayat tidak punya catatan
+#[derive(Default)]
+pub struct ReceiptLog {
+    receipts: HashMap<String, ArrivalReceipt>,
+    next_seq: u64,
+    max_server_seq: Option<u64>,
+}
+
+impl ReceiptLog {
+    pub fn record(&mut self, id: Option<&str>, raw: Option<&str>, codec: &'static str, arrived_local: f64, arrived_server: f64) {
+        self.next_seq += 1;
+        let server_seq = raw
+            .and_then(|raw| serde_json::from_str::<serde_json::Value>(raw).ok())
+            .and_then(|value| value.get("seq").and_then(serde_json::Value::as_u64));
+        let out_of_order = server_seq.zip(self.max_server_seq).is_some_and(|(seq, max)| seq < max);
+        self.max_server_seq = self.max_server_seq.max(server_seq);
+        let Some(id) = id else { return };
+        let receipt = ArrivalReceipt { arrival_seq: self.next_seq, server_seq, out_of_order, codec, arrived_local, arrived_server };
+        self.receipts.insert(id.to_string(), receipt);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ArrivalReceipt> {
+        self.receipts.get(id)
+    }
+}
+
+// "+120 ms", "-3,4 s"
+pub fn format_delta(ms: f64) -> String {
+    let sign = if ms < 0.0 { "-" } else { "+" };
+    let abs = ms.abs();
+    if abs < 1000.0 {
+        format!("{}{} ms", sign, abs.round())
+    } else {
+        format!("{}{:.1} s", sign, abs / 1000.0).replace('.', ",")
+    }
+}
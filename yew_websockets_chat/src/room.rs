@@ -0,0 +1,87 @@
+// src/room.rs
+use crate::i18n::trn;
+use crate::protocol::Pseudonym;
+
+pub const MAX_RECENT_ROOMS: usize = 10;
+
+// Room yang pernah diikuti di sesi ini, untuk palet perintah
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecentRoom {
+    pub name: String,
+    pub dm_peer: Option<String>,
+}
+
+// Informasi room yang sedang diikuti
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoomState {
+    pub name: String,
+    pub expires_at: Option<f64>, // Epoch millis; hanya ada untuk room sementara, diperpanjang server saat ada aktivitas
+    pub is_moderator: bool,
+    pub slow_mode_secs: u32, // 0 = slow mode tidak aktif
+    pub rules: Option<String>,
+    pub rules_accepted: bool,
+    pub embeds_disabled: bool, // Moderator mematikan embed gambar/GIF
+    pub dm_peer: Option<String>, // User id lawan bicara jika room ini pesan langsung
+    pub pseudonym: Option<Pseudonym>, // Some = room anonim; nama samaran user ini saat ini
+    pub past_pseudonym_ids: Vec<String>, // Id samaran lama, agar pesan sendiri sebelum rotasi tetap dikenali
+    pub message_ttl_secs: Option<u32>, // Umur pesan di room dengan pesan sementara
+    pub presence_digest_secs: Option<u32>, // Room besar: status online hanya diperbarui tiap sekian detik
+    pub feedback_box: bool, // Kotak saran: pesan orang lain sampai ke moderator tanpa identitas pengirim
+    pub language: Option<String>, // Bahasa utama room, jika ditentukan moderator
+}
+
+impl RoomState {
+    pub fn new(name: String, expires_at: Option<f64>) -> Self {
+        Self { name, expires_at, is_moderator: false, slow_mode_secs: 0, rules: None, rules_accepted: false, embeds_disabled: false, dm_peer: None, pseudonym: None, past_pseudonym_ids: Vec::new(), message_ttl_secs: None, presence_digest_secs: None, feedback_box: false, language: None }
+    }
+
+    // Selama aturan room belum disetujui, input pesan tetap dinonaktifkan
+    pub fn rules_pending(&self) -> bool {
+        self.rules.is_some() && !self.rules_accepted
+    }
+
+    pub fn is_anonymous(&self) -> bool {
+        self.pseudonym.is_some()
+    }
+
+    // Nama dan id akun tidak ditampilkan (room anonim atau kotak saran), jadi saran @mention juga dimatikan
+    pub fn hides_identity(&self) -> bool {
+        self.is_anonymous() || self.feedback_box
+    }
+
+    // Di room anonim pesan sendiri dikenali dari id samaran, bukan id akun
+    pub fn is_own_pseudonym(&self, user_id: &str) -> bool {
+        self.pseudonym.as_ref().is_some_and(|p| p.id == user_id) || self.past_pseudonym_ids.iter().any(|id| id == user_id)
+    }
+
+    pub fn rotate_pseudonym(&mut self, pseudonym: Pseudonym) {
+        if let Some(old) = self.pseudonym.replace(pseudonym) {
+            self.past_pseudonym_ids.push(old.id);
+        }
+    }
+
+    // Sisa waktu sebelum room diarsipkan, dalam detik
+    pub fn remaining_secs(&self, now_ms: f64) -> Option<u64> {
+        self.expires_at.map(|at| ((at - now_ms) / 1000.0).max(0.0) as u64)
+    }
+}
+
+// Lama umur pesan untuk badge room: "30 detik", "5 menit", "1 jam", "7 hari"
+pub fn format_ttl(secs: u32) -> String {
+    match secs {
+        0..=59 => trn("{} detik", secs as usize, &[]),
+        60..=3599 => trn("{} menit", secs as usize / 60, &[]),
+        3600..=86_399 => trn("{} jam", secs as usize / 3600, &[]),
+        _ => trn("{} hari", secs as usize / 86_400, &[]),
+    }
+}
+
+// Format hitung mundur "j:mm:dd" atau "m:dd"
+pub fn format_countdown(secs: u64) -> String {
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
+}
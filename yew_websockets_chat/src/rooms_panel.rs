@@ -0,0 +1,97 @@
+// src/rooms_panel.rs
+// Daftar room di sidebar: room yang sedang diikuti lewat koneksi ini (dengan jumlah pesan belum
+// dibaca dan tombol keluar) dan room yang pernah diikuti sebelumnya, plus pilihan bisukan notifikasi.
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+use crate::i18n::{tr, trf, use_locale};
+use crate::notify::SnoozeDuration;
+use crate::time;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoomRow {
+    pub name: String, // Nama room di server; "" = room umum
+    pub label: String, // Yang ditampilkan: nama room, atau "@nama" untuk pesan langsung
+    pub joined: bool, // Masih diikuti; false = hanya riwayat, klik untuk bergabung lagi
+    pub current: bool,
+    pub unread: usize,
+    pub mentioned: bool,
+    pub snoozed_until: Option<f64>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct RoomsPanelProps {
+    pub rooms: Vec<RoomRow>,
+    pub show_enable_notifications: bool,
+    pub on_open: Callback<String>,
+    pub on_leave: Callback<String>,
+    pub on_snooze: Callback<(String, Option<SnoozeDuration>)>, // None = aktifkan lagi notifikasi room
+    pub on_enable_notifications: Callback<()>,
+}
+
+#[function_component(RoomsPanel)]
+pub fn rooms_panel(props: &RoomsPanelProps) -> Html {
+    let locale = use_locale();
+    html! {
+        <div class="room-list">
+            if props.show_enable_notifications {
+                <button class="link-button" onclick={props.on_enable_notifications.reform(|_| ())}>{ "🔔 " }{ locale.t("Aktifkan notifikasi") }</button>
+            }
+            <ul>
+                { for props.rooms.iter().map(|row| view_row(props, row)) }
+            </ul>
+        </div>
+    }
+}
+
+fn view_row(props: &RoomsPanelProps, row: &RoomRow) -> Html {
+    let on_open = {
+        let room = row.name.clone();
+        props.on_open.reform(move |_| room.clone())
+    };
+    let on_leave = {
+        let room = row.name.clone();
+        props.on_leave.reform(move |_| room.clone())
+    };
+    let on_snooze = {
+        let (room, on_snooze) = (row.name.clone(), props.on_snooze.clone());
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(duration) = SnoozeDuration::from_key(&select.value()) {
+                on_snooze.emit((room.clone(), Some(duration)));
+            }
+        })
+    };
+    let on_unsnooze = {
+        let room = row.name.clone();
+        props.on_snooze.reform(move |_| (room.clone(), None))
+    };
+    let classes = classes!(
+        "room-list-item",
+        row.current.then_some("current"),
+        (!row.joined).then_some("left"),
+        row.mentioned.then_some("mentioned"),
+    );
+    html! {
+        <li class={classes} key={row.name.clone()}>
+            <button class="link-button" onclick={on_open} title={(!row.joined).then(|| tr("Gabung lagi"))}>{ &row.label }</button>
+            if row.unread > 0 {
+                <span class="unread-badge">{ if row.unread > 99 { "99+".to_string() } else { row.unread.to_string() } }</span>
+            }
+            if let Some(until) = row.snoozed_until {
+                <span class="muted-bell" title={trf("Notifikasi dibisukan sampai {}", &[&time::format_absolute(until)])}>{ "🔕" }</span>
+                <button class="link-button" onclick={on_unsnooze}>{ tr("Bunyikan lagi") }</button>
+            } else {
+                <select class="snooze-select" onchange={on_snooze} title={tr("Bisukan notifikasi")}>
+                    <option value="" selected=true>{ tr("Bisukan…") }</option>
+                    { for SnoozeDuration::ALL.into_iter().map(|duration| html! {
+                        <option value={duration.key()}>{ tr(duration.label()) }</option>
+                    }) }
+                </select>
+            }
+            if row.joined {
+                <button class="link-button room-leave" onclick={on_leave} title={tr("Keluar dari room")}>{ "✕" }</button>
+            }
+        </li>
+    }
+}
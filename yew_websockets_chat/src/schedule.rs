@@ -0,0 +1,56 @@
+// src/schedule.rs
+// Acara terjadwal di room: `/event 2024-05-12 19:00 Judul` membuat acara, server memposting
+// kartunya sebagai pesan dan mengirim pengingat sesaat sebelum mulai. Kartu menampilkan
+// jumlah jawaban RSVP dan tombol untuk menjawab.
+use wasm_bindgen::JsValue;
+use yew::prelude::*;
+
+use crate::i18n::tr;
+use crate::protocol::{RoomEvent, Rsvp};
+use crate::time;
+
+pub const USAGE: &str = "Penggunaan: /event <YYYY-MM-DD> <HH:MM> <judul>";
+
+// Argumen /event menjadi (waktu mulai dalam epoch millis menurut zona waktu lokal, judul)
+pub fn parse_event_args(arg: &str, now_ms: f64) -> Result<(f64, String), String> {
+    let mut parts = arg.splitn(3, char::is_whitespace);
+    let (Some(date), Some(clock), Some(title)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(tr(USAGE).to_string());
+    };
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(tr(USAGE).to_string());
+    }
+    // Format "YYYY-MM-DDTHH:MM" tanpa zona waktu dibaca sebagai waktu lokal oleh Date
+    let starts_at = js_sys::Date::new(&JsValue::from_str(&format!("{}T{}", date, clock))).get_time();
+    if starts_at.is_nan() {
+        return Err(tr(USAGE).to_string());
+    }
+    if starts_at <= now_ms {
+        return Err(tr("Waktu acara sudah lewat.").to_string());
+    }
+    Ok((starts_at, title.to_string()))
+}
+
+// Kartu acara di dalam pesan; `on_rsvp` menerima (id acara, jawaban)
+pub fn view_event_card(event: &RoomEvent, me: &str, now_ms: f64, on_rsvp: Callback<(String, Rsvp)>) -> Html {
+    let mine = event.response_of(me);
+    let started = event.starts_at <= now_ms;
+    html! {
+        <div class="event-card">
+            <div class="event-title" dir="auto">{ format!("📅 {}", event.title) }</div>
+            <div class="event-time">{ time::format_absolute(event.starts_at) }</div>
+            <div class="event-rsvp">
+                { for Rsvp::ALL.into_iter().map(|response| {
+                    let id = event.id.clone();
+                    let onclick = on_rsvp.reform(move |_| (id.clone(), response));
+                    html! {
+                        <button class={classes!((mine == Some(response)).then_some("selected"))} {onclick} disabled={started}>
+                            { format!("{} ({})", tr(response.label()), event.responders(response).len()) }
+                        </button>
+                    }
+                }) }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,132 @@
+// src/script.rs
+// Aksara dan bahasa dominan sebuah pesan, dihitung sekali saat pesan disimpan (StoredMessage::profile).
+// Dipakai untuk arah teks, aturan pemenggalan baris (CJK, Hangul) dan hyphenation lewat `lang`, serta
+// bahasa sumber terjemahan. Aksara yang hanya dipakai satu bahasa langsung menentukan bahasanya; teks
+// Latin ditebak dari kata-kata umum dan dibiarkan None jika tidak meyakinkan.
+use crate::bidi::{self, Direction};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    Arabic,
+    Hebrew,
+    Devanagari,
+    Thai,
+    Hangul,
+    Kana,
+    Han,
+}
+
+impl Script {
+    pub fn as_attr(self) -> &'static str {
+        match self {
+            Script::Latin => "latin",
+            Script::Cyrillic => "cyrillic",
+            Script::Greek => "greek",
+            Script::Arabic => "arabic",
+            Script::Hebrew => "hebrew",
+            Script::Devanagari => "devanagari",
+            Script::Thai => "thai",
+            Script::Hangul => "hangul",
+            Script::Kana => "kana",
+            Script::Han => "han",
+        }
+    }
+
+    fn of(c: char) -> Option<Self> {
+        let script = match c as u32 {
+            0x0041..=0x024F | 0x1E00..=0x1EFF => Script::Latin,
+            0x0370..=0x03FF | 0x1F00..=0x1FFF => Script::Greek,
+            0x0400..=0x052F => Script::Cyrillic,
+            0x0590..=0x05FF | 0xFB1D..=0xFB4F => Script::Hebrew,
+            0x0600..=0x06FF | 0x0750..=0x077F | 0x08A0..=0x08FF | 0xFB50..=0xFDFF | 0xFE70..=0xFEFF => Script::Arabic,
+            0x0900..=0x097F => Script::Devanagari,
+            0x0E00..=0x0E7F => Script::Thai,
+            0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => Script::Hangul,
+            0x3040..=0x30FF | 0xFF66..=0xFF9F => Script::Kana,
+            0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF => Script::Han,
+            _ => return None,
+        };
+        c.is_alphabetic().then_some(script)
+    }
+
+    // Bahasa yang hampir pasti untuk aksara ini; None untuk aksara yang dipakai banyak bahasa
+    fn language(self) -> Option<&'static str> {
+        match self {
+            Script::Greek => Some("el"),
+            Script::Hebrew => Some("he"),
+            Script::Thai => Some("th"),
+            Script::Hangul => Some("ko"),
+            Script::Kana => Some("ja"),
+            Script::Han => Some("zh"),
+            Script::Latin | Script::Cyrillic | Script::Arabic | Script::Devanagari => None,
+        }
+    }
+}
+
+// Kata umum per bahasa Latin di translate::LANGUAGES. Kata yang sama-sama lazim di bahasa Indonesia
+// dan Melayu hanya dicatat di "id"; "ms" berisi kata yang khas Melayu.
+const STOPWORDS: [(&str, &[&str]); 5] = [
+    ("id", &["yang", "dan", "tidak", "ini", "itu", "dengan", "untuk", "saya", "kamu", "ada", "sudah", "akan", "bisa", "juga", "dari", "apa", "belum", "sedang", "nggak", "gak", "kalau", "karena", "tapi"]),
+    ("ms", &["tak", "awak", "kerana", "sahaja", "mahu", "boleh", "hendak", "ialah", "bagaimana", "betul"]),
+    ("jv", &["ora", "opo", "iki", "kuwi", "karo", "wis", "arep", "ning", "lan", "sing", "piye", "durung", "ngono"]),
+    ("en", &["the", "and", "is", "are", "you", "to", "of", "it", "that", "this", "what", "have", "not", "with", "for", "was", "my", "we"]),
+    ("es", &["el", "la", "los", "las", "que", "es", "y", "por", "para", "con", "una", "pero", "muy", "como", "gracias", "hola", "esta"]),
+];
+const MIN_STOPWORDS: usize = 2; // Kata umum yang harus cocok sebelum bahasa Latin ditebak
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TextProfile {
+    pub direction: Option<Direction>, // None jika tidak ada huruf (mis. hanya emoji/angka)
+    pub script: Option<Script>,
+    pub language: Option<&'static str>, // Subtag BCP 47, hanya jika tebakannya meyakinkan
+}
+
+impl TextProfile {
+    pub fn detect(text: &str) -> Self {
+        let mut counts: Vec<(Script, usize)> = Vec::new();
+        let (mut rtl, mut ltr) = (0, 0);
+        for c in text.chars().filter(|c| c.is_alphabetic()) {
+            if bidi::is_rtl_char(c) {
+                rtl += 1;
+            } else {
+                ltr += 1;
+            }
+            if let Some(script) = Script::of(c) {
+                match counts.iter_mut().find(|(known, _)| *known == script) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((script, 1)),
+                }
+            }
+        }
+        let direction = match (rtl, ltr) {
+            (0, 0) => None,
+            _ if rtl > ltr => Some(Direction::Rtl),
+            _ => Some(Direction::Ltr),
+        };
+        // Teks Jepang mencampur kanji dengan kana; adanya kana sudah cukup untuk menandainya
+        let has_kana = counts.iter().any(|(script, _)| *script == Script::Kana);
+        let script = counts.iter().max_by_key(|(_, count)| *count).map(|(script, _)| *script)
+            .map(|script| if script == Script::Han && has_kana { Script::Kana } else { script });
+        let language = match script {
+            Some(Script::Latin) => latin_language(text),
+            other => other.and_then(Script::language),
+        };
+        Self { direction, script, language }
+    }
+}
+
+fn latin_language(text: &str) -> Option<&'static str> {
+    let words: Vec<String> = text.split(|c: char| !c.is_alphabetic()).filter(|w| !w.is_empty()).map(str::to_lowercase).collect();
+    let mut scores: Vec<(&'static str, usize)> = STOPWORDS
+        .iter()
+        .map(|(language, stopwords)| (*language, words.iter().filter(|word| stopwords.contains(&word.as_str())).count()))
+        .collect();
+    scores.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    match scores.as_slice() {
+        [(language, best), (_, second), ..] if *best >= MIN_STOPWORDS && best > second => Some(language),
+        _ => None,
+    }
+}
@@ -0,0 +1,75 @@
+// src/send_guard.rs
+// Pengaman kirim ganda. Enter di input dan klik tombol Kirim bisa menghasilkan dua Msg::SendMessage
+// sebelum render berikutnya membersihkan input, sehingga pesan yang sama terkirim dua kali. Setelah
+// satu pesan dikirim, submit berikutnya ditolak sampai echo optimistisnya (entry pending atau baki
+// outbox) sudah dirender, dan submit dengan teks yang sama ditolak selama DEBOUNCE_MS.
+pub const DEBOUNCE_MS: f64 = 500.0;
+
+#[derive(Default)]
+pub struct SendGuard {
+    awaiting_echo: bool,
+    last: Option<(String, f64)>, // Teks yang terakhir dikirim dan waktunya (jam lokal)
+}
+
+impl SendGuard {
+    pub fn allows(&self, text: &str, now: f64) -> bool {
+        !self.awaiting_echo && !self.last.as_ref().is_some_and(|(last, at)| last == text && now - at < DEBOUNCE_MS)
+    }
+
+    pub fn sent(&mut self, text: String, now: f64) {
+        self.awaiting_echo = true;
+        self.last = Some((text, now));
+    }
+
+    // Dipanggil dari App::rendered: echo pesan terakhir sudah ada di DOM
+    pub fn echoed(&mut self) {
+        self.awaiting_echo = false;
+    }
+
+    pub fn awaiting_echo(&self) -> bool {
+        self.awaiting_echo
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enter_then_click_in_same_window_sends_once() {
+        let mut guard = SendGuard::default();
+        assert!(guard.allows("halo", 0.0));
+        guard.sent("halo".to_string(), 0.0);
+        // Klik tombol Kirim sebelum render membersihkan input
+        assert!(!guard.allows("halo", 10.0));
+        assert!(!guard.allows("halo", DEBOUNCE_MS - 1.0));
+    }
+
+    #[test]
+    fn echo_unblocks_other_text_but_not_the_same_text() {
+        let mut guard = SendGuard::default();
+        guard.sent("halo".to_string(), 0.0);
+        assert!(!guard.allows("pesan lain", 100.0));
+        guard.echoed();
+        assert!(!guard.awaiting_echo());
+        assert!(guard.allows("pesan lain", 100.0));
+        assert!(!guard.allows("halo", 100.0)); // Masih di dalam DEBOUNCE_MS
+    }
+
+    #[test]
+    fn same_text_allowed_again_after_window() {
+        let mut guard = SendGuard::default();
+        guard.sent("halo".to_string(), 0.0);
+        guard.echoed();
+        assert!(guard.allows("halo", DEBOUNCE_MS));
+        guard.sent("halo".to_string(), DEBOUNCE_MS);
+        assert!(!guard.allows("halo", DEBOUNCE_MS + 1.0));
+    }
+
+    #[test]
+    fn window_alone_does_not_skip_missing_echo() {
+        let mut guard = SendGuard::default();
+        guard.sent("halo".to_string(), 0.0);
+        assert!(!guard.allows("halo", DEBOUNCE_MS * 4.0)); // Echo belum dirender
+    }
+}
@@ -0,0 +1,51 @@
+// src/sessions_panel.rs
+use yew::prelude::*;
+
+use crate::i18n::use_locale;
+use crate::protocol::SessionInfo;
+use crate::time;
+
+#[derive(Properties, PartialEq)]
+pub struct SessionsPanelProps {
+    pub sessions: Option<Vec<SessionInfo>>, // None sampai daftar pertama kali dimuat
+    pub on_refresh: Callback<()>,
+    pub on_revoke: Callback<String>, // Menerima session_id
+}
+
+// Daftar perangkat/sesi yang sedang login ke akun ini, dari tabel sesi server
+#[function_component(SessionsPanel)]
+pub fn sessions_panel(props: &SessionsPanelProps) -> Html {
+    let locale = use_locale();
+    let now = js_sys::Date::now();
+    html! {
+        <details class="settings-panel sessions-panel">
+            <summary>{ locale.t("Perangkat") }</summary>
+            <button onclick={props.on_refresh.reform(|_| ())}>
+                { locale.t(if props.sessions.is_some() { "Muat ulang" } else { "Tampilkan perangkat yang login" }) }
+            </button>
+            if let Some(sessions) = &props.sessions {
+                <ul class="session-list">
+                    { for sessions.iter().map(|session| {
+                        let id = session.session_id.clone();
+                        let on_revoke = props.on_revoke.reform(move |_| id.clone());
+                        html! {
+                            <li class={classes!("session-item", session.current.then_some("current"))}>
+                                <span class="session-device">{ &session.device }</span>
+                                if let Some(location) = &session.location {
+                                    <span class="session-meta">{ format!(" · {}", location) }</span>
+                                }
+                                <span class="session-meta">
+                                    { " · " }
+                                    { if session.current { locale.t("perangkat ini").to_string() } else { locale.f("aktif {}", &[&time::format_relative(session.last_active, now)]) } }
+                                </span>
+                                if !session.current {
+                                    <button class="danger" onclick={on_revoke}>{ locale.t("Keluarkan") }</button>
+                                }
+                            </li>
+                        }
+                    }) }
+                </ul>
+            }
+        </details>
+    }
+}
@@ -0,0 +1,127 @@
+// src/settings.rs
+use serde::{Deserialize, Serialize};
+
+use crate::canned::CannedResponse;
+use crate::history;
+use crate::i18n::Locale;
+use crate::protocol::{CustomStatus, GifRating};
+use crate::storage;
+use crate::theme::Theme;
+use crate::translate;
+
+const SETTINGS_KEY: &str = "webchat.settings";
+
+// Preferensi user yang disimpan di localStorage. Field baru wajib punya nilai default
+// (lewat `#[serde(default)]` di struct) agar pengaturan lama tetap bisa dibaca.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub compress_images: bool, // Kompres gambar di browser sebelum upload; false = kirim file asli
+    pub image_quality: f64,    // Kualitas encoder WebP/JPEG, 0.0 - 1.0
+    pub max_image_dimension: u32,
+    pub strip_image_metadata: bool, // Buang EXIF/GPS dari gambar sebelum di-upload
+    pub max_gif_rating: GifRating,  // GIF dengan rating di atas ini disembunyikan
+    pub text_size: TextSize,
+    pub high_contrast: SystemPreference,
+    pub reduce_motion: SystemPreference,
+    pub privacy: Privacy,
+    pub auto_lock_minutes: u32, // Kunci chat setelah sekian menit tanpa aktivitas; 0 = mati, butuh enkripsi cache
+    pub canned_responses: Vec<CannedResponse>,
+    pub history_limit: usize, // Jumlah pesan room yang disimpan untuk dimuat lagi setelah refresh; 0 = tidak disimpan
+    pub support_agent: bool, // Mode agen dukungan: terima antrean pesan langsung dari tamu
+    pub language: Option<String>, // Bahasa user untuk terjemahan; None = bahasa browser
+    pub auto_translate_rooms: Vec<String>, // Room yang pesannya diterjemahkan otomatis
+    pub custom_status: Option<CustomStatus>, // Dikirim ulang ke server setiap terhubung, selama belum kedaluwarsa
+    pub lite_mode: SystemPreference, // Mode ringan; "ikuti sistem" = otomatis di perangkat atau baterai lemah
+    pub operator_name: String, // Nama orang yang memakai akun bersama di perangkat ini; kosong = tidak dikirim
+    pub locale: Option<Locale>, // Bahasa antarmuka; None = bahasa browser
+    pub theme: Option<Theme>,   // Tema terang/gelap; None = ikuti prefers-color-scheme
+}
+
+pub use chat_protocol::Privacy; // Juga dikirim ke server lewat UpdatePrivacy
+
+// Pengaturan yang secara default mengikuti preferensi sistem (media query browser)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemPreference {
+    #[default]
+    System,
+    On,
+    Off,
+}
+
+impl SystemPreference {
+    pub fn resolve(self, system: bool) -> bool {
+        match self {
+            SystemPreference::System => system,
+            SystemPreference::On => true,
+            SystemPreference::Off => false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TextSize {
+    Small,
+    #[default]
+    Normal,
+    Large,
+    Custom(f64), // Skala bebas, dibatasi 0.75 - 2.0
+}
+
+impl TextSize {
+    pub const MIN_SCALE: f64 = 0.75;
+    pub const MAX_SCALE: f64 = 2.0;
+
+    pub fn scale(&self) -> f64 {
+        match self {
+            TextSize::Small => 0.875,
+            TextSize::Normal => 1.0,
+            TextSize::Large => 1.25,
+            TextSize::Custom(scale) => scale.clamp(Self::MIN_SCALE, Self::MAX_SCALE),
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            compress_images: true,
+            image_quality: 0.8,
+            max_image_dimension: 1920,
+            strip_image_metadata: true,
+            max_gif_rating: GifRating::default(),
+            text_size: TextSize::default(),
+            high_contrast: SystemPreference::default(),
+            reduce_motion: SystemPreference::default(),
+            privacy: Privacy::default(),
+            auto_lock_minutes: 0,
+            canned_responses: Vec::new(),
+            history_limit: history::DEFAULT_LIMIT,
+            support_agent: false,
+            language: None,
+            auto_translate_rooms: Vec::new(),
+            custom_status: None,
+            lite_mode: SystemPreference::default(),
+            operator_name: String::new(),
+            locale: None,
+            theme: None,
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        storage::get_json(SETTINGS_KEY).unwrap_or_default()
+    }
+
+    // Bahasa tujuan terjemahan
+    pub fn language(&self) -> String {
+        self.language.clone().unwrap_or_else(translate::browser_language)
+    }
+
+    pub fn save(&self) {
+        storage::set_json(SETTINGS_KEY, self);
+    }
+}
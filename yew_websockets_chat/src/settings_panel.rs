@@ -0,0 +1,374 @@
+// src/settings_panel.rs
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+use crate::canned::{self, CannedResponse};
+use crate::device;
+use crate::history;
+use crate::i18n::{use_locale, Locale, LOCALES};
+use crate::protocol::{GifRating, Operator};
+use crate::settings::{Settings, SystemPreference, TextSize};
+use crate::theme::Theme;
+use crate::translate;
+
+#[derive(Properties, PartialEq)]
+pub struct SettingsPanelProps {
+    pub settings: Settings,
+    pub lite_mode: bool, // Mode ringan sedang aktif, termasuk yang aktif otomatis
+    pub on_change: Callback<Settings>, // Menerima salinan pengaturan yang sudah diubah
+    pub on_clear_history: Callback<()>,
+}
+
+// Panel pengaturan yang bisa dilipat. Tiap perubahan langsung dikirim ke induk untuk disimpan.
+#[function_component(SettingsPanel)]
+pub fn settings_panel(props: &SettingsPanelProps) -> Html {
+    let locale = use_locale();
+    // Buat callback yang mengubah satu bagian pengaturan dari event input
+    let update = |apply: fn(&mut Settings, &HtmlInputElement)| {
+        let settings = props.settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut next = settings.clone();
+            apply(&mut next, &input);
+            on_change.emit(next);
+        })
+    };
+    let settings = &props.settings;
+    let on_text_size_change = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let text_size = match select.value().as_str() {
+                "small" => TextSize::Small,
+                "large" => TextSize::Large,
+                "custom" => TextSize::Custom(settings.text_size.scale()), // Mulai dari ukuran saat ini
+                _ => TextSize::Normal,
+            };
+            on_change.emit(Settings { text_size, ..settings.clone() });
+        })
+    };
+    let text_size_value = match settings.text_size {
+        TextSize::Small => "small",
+        TextSize::Normal => "normal",
+        TextSize::Large => "large",
+        TextSize::Custom(_) => "custom",
+    };
+    // Select tiga pilihan (ikuti sistem / aktif / mati) untuk satu SystemPreference
+    let preference_select = |current: SystemPreference, apply: fn(&mut Settings, SystemPreference)| {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        let onchange = Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let preference = match select.value().as_str() {
+                "on" => SystemPreference::On,
+                "off" => SystemPreference::Off,
+                _ => SystemPreference::System,
+            };
+            let mut next = settings.clone();
+            apply(&mut next, preference);
+            on_change.emit(next);
+        });
+        html! {
+            <select {onchange}>
+                <option value="system" selected={current == SystemPreference::System}>{ locale.t("Ikuti sistem") }</option>
+                <option value="on" selected={current == SystemPreference::On}>{ locale.t("Aktif") }</option>
+                <option value="off" selected={current == SystemPreference::Off}>{ locale.t("Mati") }</option>
+            </select>
+        }
+    };
+    let on_rating_change = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let rating = select.value().parse().ok().and_then(|i: usize| GifRating::ALL.get(i).copied());
+            on_change.emit(Settings { max_gif_rating: rating.unwrap_or_default(), ..settings.clone() });
+        })
+    };
+    let on_history_limit_change = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let history_limit = select.value().parse().unwrap_or(history::DEFAULT_LIMIT);
+            on_change.emit(Settings { history_limit, ..settings.clone() });
+        })
+    };
+
+    let on_locale_change = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            on_change.emit(Settings { locale: Locale::from_code(&select.value()), ..settings.clone() });
+        })
+    };
+    let on_theme_change = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let theme = match select.value().as_str() {
+                "light" => Some(Theme::Light),
+                "dark" => Some(Theme::Dark),
+                _ => None,
+            };
+            on_change.emit(Settings { theme, ..settings.clone() });
+        })
+    };
+    let on_language_change = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let language = Some(select.value()).filter(|code| !code.is_empty());
+            on_change.emit(Settings { language, ..settings.clone() });
+        })
+    };
+
+    // Draf balasan cepat baru (nama, teks) dan pesan validasinya
+    let canned_draft = use_state(|| (String::new(), String::new()));
+    let canned_error = use_state(|| None::<String>);
+    let on_canned_name = {
+        let draft = canned_draft.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            draft.set((input.value(), draft.1.clone()));
+        })
+    };
+    let on_canned_text = {
+        let draft = canned_draft.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            draft.set((draft.0.clone(), input.value()));
+        })
+    };
+    let on_canned_add = {
+        let (settings, on_change) = (settings.clone(), props.on_change.clone());
+        let (draft, error) = (canned_draft.clone(), canned_error.clone());
+        Callback::from(move |e: FocusEvent| {
+            e.prevent_default();
+            let (name, text) = (draft.0.trim().to_string(), draft.1.trim().to_string());
+            if let Err(e) = canned::validate_name(&settings.canned_responses, &name) {
+                error.set(Some(e));
+                return;
+            }
+            if text.is_empty() {
+                error.set(Some(locale.t("Teks balasan cepat tidak boleh kosong.").to_string()));
+                return;
+            }
+            let mut next = settings.clone();
+            next.canned_responses.push(CannedResponse { name, text });
+            on_change.emit(next);
+            draft.set((String::new(), String::new()));
+            error.set(None);
+        })
+    };
+
+    html! {
+        <details class="settings-panel">
+            <summary>{ locale.t("Pengaturan") }</summary>
+            <fieldset>
+                <legend>{ locale.t("Upload gambar") }</legend>
+                <label>
+                    <input
+                        type="checkbox"
+                        checked={settings.compress_images}
+                        onchange={update(|s, input| s.compress_images = input.checked())}
+                    />
+                    { " " }{ locale.t("Kompres gambar sebelum dikirim (matikan untuk mengirim file asli)") }
+                </label>
+                <label>
+                    { locale.f("Kualitas: {}%", &[&(settings.image_quality * 100.0).round()]) }
+                    <input
+                        type="range"
+                        min="30"
+                        max="100"
+                        step="5"
+                        value={((settings.image_quality * 100.0).round() as u32).to_string()}
+                        disabled={!settings.compress_images}
+                        onchange={update(|s, input| s.image_quality = input.value_as_number() / 100.0)}
+                    />
+                </label>
+                <label>
+                    <input
+                        type="checkbox"
+                        checked={settings.strip_image_metadata}
+                        onchange={update(|s, input| s.strip_image_metadata = input.checked())}
+                    />
+                    { " " }{ locale.t("Hapus metadata (lokasi GPS, info kamera) dari gambar") }
+                </label>
+            </fieldset>
+            <fieldset>
+                <legend>{ locale.t("Tampilan") }</legend>
+                <label>
+                    { locale.t("Bahasa antarmuka:") }{ " " }
+                    <select onchange={on_locale_change}>
+                        <option value="" selected={settings.locale.is_none()}>{ locale.f("Ikuti browser ({})", &[&Locale::browser().name()]) }</option>
+                        { for LOCALES.iter().map(|option| html! {
+                            <option value={option.code()} selected={settings.locale == Some(*option)}>{ option.name() }</option>
+                        }) }
+                    </select>
+                </label>
+                <label>
+                    { locale.t("Tema:") }{ " " }
+                    <select onchange={on_theme_change}>
+                        <option value="system" selected={settings.theme.is_none()}>{ locale.t("Ikuti sistem") }</option>
+                        <option value="light" selected={settings.theme == Some(Theme::Light)}>{ locale.t("Terang") }</option>
+                        <option value="dark" selected={settings.theme == Some(Theme::Dark)}>{ locale.t("Gelap") }</option>
+                    </select>
+                </label>
+                <label>
+                    { locale.t("Ukuran teks:") }{ " " }
+                    <select onchange={on_text_size_change}>
+                        { for [("small", "Kecil"), ("normal", "Normal"), ("large", "Besar"), ("custom", "Kustom")].iter().map(|(value, label)| html! {
+                            <option value={*value} selected={text_size_value == *value}>{ locale.t(label) }</option>
+                        }) }
+                    </select>
+                </label>
+                if let TextSize::Custom(_) = settings.text_size {
+                    <label>
+                        { locale.f("Skala: {}%", &[&(settings.text_size.scale() * 100.0).round()]) }
+                        <input
+                            type="range"
+                            min={(TextSize::MIN_SCALE * 100.0).to_string()}
+                            max={(TextSize::MAX_SCALE * 100.0).to_string()}
+                            step="5"
+                            value={((settings.text_size.scale() * 100.0).round() as u32).to_string()}
+                            onchange={update(|s, input| s.text_size = TextSize::Custom(input.value_as_number() / 100.0))}
+                        />
+                    </label>
+                }
+                <label>
+                    { locale.t("Kontras tinggi:") }{ " " }
+                    { preference_select(settings.high_contrast, |s, p| s.high_contrast = p) }
+                </label>
+                <label>
+                    { locale.t("Kurangi animasi:") }{ " " }
+                    { preference_select(settings.reduce_motion, |s, p| s.reduce_motion = p) }
+                </label>
+                <label>
+                    { locale.t("Mode ringan:") }{ " " }
+                    { preference_select(settings.lite_mode, |s, p| s.lite_mode = p) }
+                </label>
+                <p class="settings-hint">
+                    { locale.t("Mode ringan mematikan animasi, merender lebih sedikit pesan sekaligus dan mengumpulkan pesan masuk sebelum ditampilkan.") }{ " " }
+                    { locale.t("Dengan \"Ikuti sistem\", mode ini aktif otomatis di perangkat dengan CPU/memori terbatas atau saat baterai lemah") }
+                    { match device::describe_hardware().as_str() {
+                        "" => String::new(),
+                        hardware => format!(" ({})", locale.f("perangkat ini: {}", &[&hardware])),
+                    } }
+                    { "." }
+                    if props.lite_mode {
+                        { " " }{ locale.t("Saat ini aktif.") }
+                    }
+                </p>
+            </fieldset>
+            <fieldset>
+                <legend>{ locale.t("Terjemahan") }</legend>
+                <label>
+                    { locale.t("Bahasa saya:") }{ " " }
+                    <select onchange={on_language_change}>
+                        <option value="" selected={settings.language.is_none()}>
+                            { locale.f("Ikuti browser ({})", &[&translate::language_name(&translate::browser_language())]) }
+                        </option>
+                        { for translate::LANGUAGES.iter().map(|(code, name)| html! {
+                            <option value={*code} selected={settings.language.as_deref() == Some(*code)}>{ *name }</option>
+                        }) }
+                    </select>
+                </label>
+                <p class="settings-hint">{ locale.t("Pesan di room berbahasa lain bisa diterjemahkan ke bahasa ini.") }</p>
+            </fieldset>
+            <fieldset>
+                <legend>{ locale.t("Akun bersama") }</legend>
+                <label>
+                    { locale.t("Nama operator:") }{ " " }
+                    <input
+                        type="text"
+                        maxlength={Operator::MAX_NAME_CHARS.to_string()}
+                        placeholder={locale.t("mis. Sari Dewi")}
+                        value={settings.operator_name.clone()}
+                        onchange={update(|s, input| s.operator_name = input.value().trim().to_string())}
+                    />
+                </label>
+                <p class="settings-hint">{ locale.t("Jika akun ini dipakai bergantian, inisial nama ini ditampilkan di pesan Anda dan saat Anda sedang mengetik. Hanya dikirim ke server yang mendukungnya.") }</p>
+            </fieldset>
+            <fieldset>
+                <legend>{ locale.t("GIF") }</legend>
+                <label>
+                    { locale.t("Rating konten maksimal:") }{ " " }
+                    <select onchange={on_rating_change}>
+                        { for GifRating::ALL.iter().enumerate().map(|(i, rating)| html! {
+                            <option value={i.to_string()} selected={settings.max_gif_rating == *rating}>{ locale.t(rating.label()) }</option>
+                        }) }
+                    </select>
+                </label>
+            </fieldset>
+            <fieldset>
+                <legend>{ locale.t("Riwayat lokal") }</legend>
+                <label>
+                    { locale.t("Simpan pesan terakhir:") }{ " " }
+                    <select onchange={on_history_limit_change}>
+                        { for history::LIMIT_CHOICES.iter().map(|&limit| html! {
+                            <option value={limit.to_string()} selected={settings.history_limit == limit}>
+                                { if limit == 0 { locale.t("Jangan simpan").to_string() } else { locale.n("{} pesan", limit, &[]) } }
+                            </option>
+                        }) }
+                    </select>
+                </label>
+                <button type="button" class="danger" onclick={props.on_clear_history.reform(|_| ())}>{ locale.t("Hapus riwayat") }</button>
+                <p class="settings-hint">{ locale.t("Riwayat tidak disimpan selama enkripsi cache lokal aktif.") }</p>
+            </fieldset>
+            <fieldset>
+                <legend>{ locale.t("Layanan pelanggan") }</legend>
+                <label>
+                    <input
+                        type="checkbox"
+                        checked={settings.support_agent}
+                        onchange={update(|s, input| s.support_agent = input.checked())}
+                    />
+                    { " " }{ locale.t("Mode agen dukungan") }
+                    <span class="settings-hint">{ " " }{ locale.t("(pesan langsung dari tamu masuk ke antrean bersama yang bisa Anda ambil)") }</span>
+                </label>
+            </fieldset>
+            <fieldset class="canned-settings">
+                <legend>{ locale.t("Balasan cepat") }</legend>
+                <ul>
+                    { for settings.canned_responses.iter().enumerate().map(|(i, response)| {
+                        let on_remove = {
+                            let (settings, on_change) = (settings.clone(), props.on_change.clone());
+                            Callback::from(move |_| {
+                                let mut next = settings.clone();
+                                next.canned_responses.remove(i);
+                                on_change.emit(next);
+                            })
+                        };
+                        html! {
+                            <li>
+                                <strong>{ &response.name }</strong>
+                                <span class="canned-text" dir="auto">{ &response.text }</span>
+                                <button type="button" class="link-button" onclick={on_remove}>{ locale.t("Hapus") }</button>
+                            </li>
+                        }
+                    }) }
+                </ul>
+                <form onsubmit={on_canned_add}>
+                    <input type="text" placeholder={locale.t("Nama (satu kata)")} value={canned_draft.0.clone()} oninput={on_canned_name} />
+                    <textarea dir="auto" placeholder={locale.t("Teks balasan")} value={canned_draft.1.clone()} oninput={on_canned_text} />
+                    <button type="submit">{ locale.t("Tambah") }</button>
+                </form>
+                if let Some(error) = &*canned_error {
+                    <p class="field-error">{ error }</p>
+                }
+                <p class="settings-hint">
+                    { locale.t("Placeholder:") }{ " " }
+                    { for canned::PLACEHOLDERS.iter().map(|(placeholder, description)| html! {
+                        <span title={locale.t(description)}><code>{ *placeholder }</code>{ " " }</span>
+                    }) }
+                </p>
+            </fieldset>
+        </details>
+    }
+}
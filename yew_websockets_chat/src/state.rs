@@ -0,0 +1,739 @@
+// src/state.rs
+// Data percakapan yang ditampilkan (pesan, direktori user, presence, room) dan satu-satunya
+// cara mengubahnya: `reduce(state, action)`. Reducer tidak menyentuh jaringan, timer atau DOM;
+// efek samping tetap di App setelah dispatch, sehingga tiap perubahan bisa dicatat dan diputar ulang.
+// Beberapa room bisa diikuti sekaligus lewat satu koneksi: room yang ditampilkan ada di `current_room`
+// dan `messages`, room lain menunggu di `background` dan bertukar tempat saat user berpindah room.
+use crate::direct::DirectInbox;
+use crate::directory::{self, UserDirectory};
+use crate::i18n::tr;
+use crate::mentions;
+use crate::presence::{Presence, PresenceStore};
+use crate::protocol::{CustomStatus, PresenceEvent, RosterUser, UserStatus};
+use crate::protocol::{MemberInfo, MessageComponent, NoteEntry, Notepad, Pseudonym, RoomEvent};
+use crate::read_marks::ReadMarks;
+use crate::room::RoomState;
+use crate::store::{self, Delivery, MessageStore, StoredMessage};
+use crate::ChatMessage;
+
+#[derive(Debug, Clone, Default)]
+pub struct ChatState {
+    pub me: String, // Id akun user saat ini, untuk mendeteksi mention
+    pub messages: MessageStore,
+    pub directory: UserDirectory,
+    pub presence: PresenceStore,
+    pub current_room: Option<RoomState>,
+    pub background: Vec<RoomBuffer>, // Room lain yang diikuti, urut dari yang terakhir ditampilkan paling akhir
+    pub direct: DirectInbox, // Pesan langsung, terpisah dari pesan room
+    pub last_seen: Option<f64>, // Timestamp pesan terakhir yang diterima, dipakai sebagai titik resume
+    pub missed_gap: Option<MissedGap>,
+    pub history_complete: bool, // Server tidak punya pesan room ini yang lebih lama dari entry pertama
+    pub read_marks: ReadMarks,
+    pub notes: Notepad, // Notepad bersama room yang ditampilkan
+}
+
+impl ChatState {
+    // Pesan dikirim oleh user ini; di room anonim dicocokkan lewat id samaran
+    pub fn is_own(&self, msg: &ChatMessage) -> bool {
+        match (&msg.user_id, self.current_room.as_ref().filter(|room| room.is_anonymous())) {
+            (Some(id), Some(room)) => room.is_own_pseudonym(id),
+            (Some(id), None) => *id == self.me,
+            // Kiriman di kotak saran tanpa identitas; bukan milik siapa pun walaupun namanya sama
+            (None, _) if self.current_room.as_ref().is_some_and(|room| room.feedback_box) => false,
+            (None, _) => self.directory.name_of(&self.me) == Some(msg.username.as_str()),
+        }
+    }
+
+    // Nama room yang ditampilkan; "" = room umum
+    pub fn current_name(&self) -> &str {
+        self.current_room.as_ref().map_or("", |room| room.name.as_str())
+    }
+
+    // Room yang sedang ditampilkan atau yang menunggu di latar belakang
+    pub fn joined_room(&self, name: &str) -> Option<&RoomState> {
+        match &self.current_room {
+            Some(room) if room.name == name => Some(room),
+            _ => self.background.iter().find_map(|buffer| buffer.room.as_ref().filter(|room| room.name == name)),
+        }
+    }
+
+    pub fn is_joined(&self, name: &str) -> bool {
+        self.current_name() == name || self.background.iter().any(|buffer| buffer.name() == name)
+    }
+
+    // Pesan user ini yang masih pending atau gagal, di room mana pun
+    pub fn unsent(&self, client_id: &str) -> Option<&StoredMessage> {
+        self.messages.unsent(client_id).or_else(|| self.background.iter().find_map(|buffer| buffer.messages.unsent(client_id)))
+    }
+
+    fn stores_mut(&mut self) -> impl Iterator<Item = &mut MessageStore> {
+        std::iter::once(&mut self.messages).chain(self.background.iter_mut().map(|buffer| &mut buffer.messages))
+    }
+}
+
+// Pesan dan status room yang sedang tidak ditampilkan
+#[derive(Debug, Clone, Default)]
+pub struct RoomBuffer {
+    pub room: Option<RoomState>, // None = room umum
+    pub messages: MessageStore,
+    pub missed_gap: Option<MissedGap>,
+    pub history_complete: bool,
+    pub read_marks: ReadMarks,
+    pub notes: Notepad,
+    pub unread: usize, // Pesan yang masuk sejak room ini terakhir ditampilkan
+    pub mentioned: bool,
+}
+
+impl RoomBuffer {
+    pub fn name(&self) -> &str {
+        self.room.as_ref().map_or("", |room| room.name.as_str())
+    }
+}
+
+// Celah riwayat setelah backfill yang dibatasi: pesan di antara `after` dan `before` belum dimuat
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissedGap {
+    pub index: usize, // Posisi entry di MessageStore tempat pesan yang terlewat akan disisipkan
+    pub count: u64,
+    pub after: Option<f64>, // Timestamp pesan di kedua sisi celah
+    pub before: Option<f64>,
+}
+
+// `now_ms` selalu jam server yang sudah dikoreksi, diisi oleh pemanggil agar reducer tetap murni
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    MessageReceived { message: ChatMessage, raw: Option<String>, now_ms: f64 },
+    MessageSending { message: ChatMessage, now_ms: f64 }, // Pesan user ini ditampilkan sebelum dikembalikan server
+    DeliveryFailed { client_id: String, reason: String },
+    DeliveryRetried { client_id: String, now_ms: f64 },
+    DeliveriesTimedOut { now_ms: f64 },
+    UnsentDiscarded { client_id: String },
+    HistoryReplayed { messages: Vec<ChatMessage>, total_missed: u64, now_ms: f64 },
+    HistoryPageLoaded { messages: Vec<ChatMessage>, now_ms: f64 },
+    OlderHistoryLoaded { room: String, messages: Vec<ChatMessage>, has_more: bool, now_ms: f64 }, // Halaman HistoryResponse; "" = room umum
+    LocalHistoryRestored { messages: Vec<ChatMessage>, now_ms: f64 }, // Riwayat terenkripsi dibuka setelah vault di-unlock
+    MessagesExpired { now_ms: f64 },
+    HistoryCleared, // Riwayat lokal dihapus user; semua room dikosongkan
+    RepeatsToggled(usize),
+    UserRenamed { user_id: String, name: String },
+    SignedIn { user_id: String, name: Option<String> }, // Identitas akun dari AuthResult menggantikan id lokal
+    UserDeleted { user_id: String },
+    PresenceChanged { user_id: String, presence: Presence },
+    PresenceDigest { room: String, full: bool, online: Vec<String>, offline: Vec<(String, Option<f64>)> },
+    RosterSynced { room: String, users: Vec<RosterUser> },
+    UserJoined { room: String, user: RosterUser },
+    UserLeft { room: String, user_id: String },
+    StatusChanged { user_id: String, status: UserStatus },
+    CustomStatusChanged { user_id: String, status: Option<CustomStatus> },
+    MembersLoaded { room: String, members: Vec<MemberInfo> },
+    EventUpdated(RoomEvent),
+    ComponentsUpdated { message_id: String, components: Vec<MessageComponent> },
+    DirectMessageReceived { to: String, message: ChatMessage },
+    DirectOpened(Option<String>), // None = kembali ke daftar pesan room
+    RoomJoined(RoomState),
+    RoomSwitched(String),
+    RoomLeft { room: String },
+    ReadReceiptReceived { room: String, user_id: String, message_id: String },
+    NotesMerged { room: String, entries: Vec<NoteEntry> }, // Suntingan notepad dari server, atau suntingan sendiri sebelum dikirim
+    RoomExpiryUpdated { room: String, expires_at: f64 },
+    RoomExpired { room: String },
+    SlowModeChanged { room: String, interval_secs: u32 },
+    EmbedsChanged { room: String, disabled: bool },
+    RoomLanguageChanged { room: String, language: Option<String> },
+    PseudonymRotated { room: String, pseudonym: Pseudonym },
+    RulesAccepted,
+}
+
+// Setiap event presence dari server langsung menjadi satu aksi
+impl From<PresenceEvent> for Action {
+    fn from(event: PresenceEvent) -> Self {
+        match event {
+            PresenceEvent::Presence { user_id, online, last_seen } => Action::PresenceChanged { user_id, presence: Presence { online, last_seen } },
+            PresenceEvent::PresenceDigest { room, full, online, offline } => {
+                let offline = offline.into_iter().map(|user| (user.user_id, user.last_seen)).collect();
+                Action::PresenceDigest { room, full, online, offline }
+            }
+            PresenceEvent::Roster { room, users } => Action::RosterSynced { room, users },
+            PresenceEvent::UserJoined { room, user } => Action::UserJoined { room, user },
+            PresenceEvent::UserLeft { room, user_id } => Action::UserLeft { room, user_id },
+            PresenceEvent::StatusChanged { user_id, status } => Action::StatusChanged { user_id, status },
+            PresenceEvent::CustomStatusChanged { user_id, status } => Action::CustomStatusChanged { user_id, status },
+        }
+    }
+}
+
+#[cfg(feature = "dev")]
+impl Action {
+    // Nama singkat untuk log time-travel
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MessageReceived { .. } => "MessageReceived",
+            Action::MessageSending { .. } => "MessageSending",
+            Action::DeliveryFailed { .. } => "DeliveryFailed",
+            Action::DeliveryRetried { .. } => "DeliveryRetried",
+            Action::DeliveriesTimedOut { .. } => "DeliveriesTimedOut",
+            Action::UnsentDiscarded { .. } => "UnsentDiscarded",
+            Action::HistoryReplayed { .. } => "HistoryReplayed",
+            Action::HistoryPageLoaded { .. } => "HistoryPageLoaded",
+            Action::OlderHistoryLoaded { .. } => "OlderHistoryLoaded",
+            Action::LocalHistoryRestored { .. } => "LocalHistoryRestored",
+            Action::MessagesExpired { .. } => "MessagesExpired",
+            Action::HistoryCleared => "HistoryCleared",
+            Action::RepeatsToggled(_) => "RepeatsToggled",
+            Action::UserRenamed { .. } => "UserRenamed",
+            Action::SignedIn { .. } => "SignedIn",
+            Action::UserDeleted { .. } => "UserDeleted",
+            Action::PresenceChanged { .. } => "PresenceChanged",
+            Action::PresenceDigest { .. } => "PresenceDigest",
+            Action::RosterSynced { .. } => "RosterSynced",
+            Action::UserJoined { .. } => "UserJoined",
+            Action::UserLeft { .. } => "UserLeft",
+            Action::StatusChanged { .. } => "StatusChanged",
+            Action::CustomStatusChanged { .. } => "CustomStatusChanged",
+            Action::MembersLoaded { .. } => "MembersLoaded",
+            Action::EventUpdated(_) => "EventUpdated",
+            Action::ComponentsUpdated { .. } => "ComponentsUpdated",
+            Action::DirectMessageReceived { .. } => "DirectMessageReceived",
+            Action::DirectOpened(_) => "DirectOpened",
+            Action::RoomJoined(_) => "RoomJoined",
+            Action::RoomSwitched(_) => "RoomSwitched",
+            Action::RoomLeft { .. } => "RoomLeft",
+            Action::ReadReceiptReceived { .. } => "ReadReceiptReceived",
+            Action::NotesMerged { .. } => "NotesMerged",
+            Action::RoomExpiryUpdated { .. } => "RoomExpiryUpdated",
+            Action::RoomExpired { .. } => "RoomExpired",
+            Action::SlowModeChanged { .. } => "SlowModeChanged",
+            Action::EmbedsChanged { .. } => "EmbedsChanged",
+            Action::RoomLanguageChanged { .. } => "RoomLanguageChanged",
+            Action::PseudonymRotated { .. } => "PseudonymRotated",
+            Action::RulesAccepted => "RulesAccepted",
+        }
+    }
+}
+
+// Terapkan satu aksi. Mengembalikan true jika ada yang berubah (perlu re-render).
+pub fn reduce(state: &mut ChatState, action: Action) -> bool {
+    match action {
+        Action::MessageReceived { message, raw, now_ms } => ingest_routed(state, message, raw, now_ms),
+        Action::MessageSending { message, now_ms } => match target(state, message.room.as_deref()) {
+            Some(Target::Current) => {
+                state.messages.push_pending(message, now_ms);
+                true
+            }
+            Some(Target::Background(index)) => {
+                state.background[index].messages.push_pending(message, now_ms);
+                true
+            }
+            None => false,
+        },
+        Action::DeliveryFailed { client_id, reason } => {
+            state.stores_mut().any(|messages| messages.set_delivery(&client_id, Delivery::Failed(reason.clone())))
+        }
+        Action::DeliveryRetried { client_id, now_ms } => {
+            state.stores_mut().any(|messages| messages.set_delivery(&client_id, Delivery::Pending { since: now_ms }))
+        }
+        Action::DeliveriesTimedOut { now_ms } => {
+            let cutoff = now_ms - store::ACK_TIMEOUT_MS;
+            let mut changed = false;
+            for messages in state.stores_mut() {
+                changed |= messages.fail_stale(cutoff, tr("Server tidak mengonfirmasi pesan ini"));
+            }
+            changed
+        }
+        Action::UnsentDiscarded { client_id } => state.stores_mut().any(|messages| messages.remove_unsent(&client_id)),
+        Action::HistoryReplayed { messages, total_missed, now_ms } => {
+            // Setelah reconnect server bisa mengirim pesan dari semua room yang diikuti
+            let current = state.current_name().to_string();
+            let (messages, others): (Vec<_>, Vec<_>) =
+                messages.into_iter().partition(|msg| msg.room.as_deref().is_none_or(|room| room == current));
+            for msg in others {
+                ingest_routed(state, msg, None, now_ms);
+            }
+            let index = state.messages.len();
+            let after = state.last_seen;
+            let before = messages.first().and_then(|msg| msg.timestamp);
+            let replayed = messages.len() as u64;
+            for msg in messages {
+                ingest(state, msg, None, now_ms);
+            }
+            state.missed_gap = (total_missed > replayed).then(|| MissedGap { index, count: total_missed - replayed, after, before });
+            true
+        }
+        Action::HistoryPageLoaded { messages, now_ms } => {
+            // Celah bisa milik room yang sudah ditinggal ke latar belakang sebelum halaman ini tiba
+            match target(state, messages.first().and_then(|msg| msg.room.as_deref())) {
+                Some(Target::Current) => fill_gap(state, messages, now_ms),
+                Some(Target::Background(index)) => with_background(state, index, |state| fill_gap(state, messages, now_ms)),
+                None => false,
+            }
+        }
+        Action::OlderHistoryLoaded { room, messages, has_more, now_ms } => match target(state, Some(&room)) {
+            Some(Target::Current) => prepend_older(state, messages, has_more, now_ms),
+            Some(Target::Background(index)) => with_background(state, index, |state| prepend_older(state, messages, has_more, now_ms)),
+            None => false,
+        },
+        // Pesan baru mungkin sudah masuk selama terkunci; riwayat lama yang belum ada disisipkan sebelumnya per room
+        Action::LocalHistoryRestored { messages, now_ms } => {
+            let mut rooms: Vec<(Option<String>, Vec<ChatMessage>)> = Vec::new();
+            for msg in messages {
+                match rooms.iter_mut().find(|(room, _)| *room == msg.room) {
+                    Some((_, list)) => list.push(msg),
+                    None => rooms.push((msg.room.clone(), vec![msg])),
+                }
+            }
+            let mut changed = false;
+            for (room, messages) in rooms {
+                changed |= match target(state, room.as_deref()) {
+                    Some(Target::Current) => restore_older(state, messages, now_ms),
+                    Some(Target::Background(index)) => with_background(state, index, |state| restore_older(state, messages, now_ms)),
+                    None => false,
+                };
+            }
+            changed
+        }
+        Action::MessagesExpired { now_ms } => {
+            let mut changed = state.messages.remove_expired(now_ms);
+            for buffer in &mut state.background {
+                changed |= buffer.messages.remove_expired(now_ms);
+            }
+            changed
+        }
+        Action::HistoryCleared => {
+            state.messages = MessageStore::default();
+            state.missed_gap = None;
+            state.history_complete = false;
+            for buffer in &mut state.background {
+                buffer.messages = MessageStore::default();
+                buffer.missed_gap = None;
+                buffer.history_complete = false;
+                buffer.unread = 0;
+                buffer.mentioned = false;
+            }
+            true
+        }
+        Action::RepeatsToggled(index) => state.messages.toggle_expanded(index),
+        Action::EventUpdated(event) => {
+            let mut changed = state.messages.update_event(&event);
+            for buffer in &mut state.background {
+                changed |= buffer.messages.update_event(&event);
+            }
+            changed
+        }
+        Action::ComponentsUpdated { message_id, components } => {
+            state.messages.update_components(&message_id, &components)
+                || state.background.iter_mut().any(|buffer| buffer.messages.update_components(&message_id, &components))
+        }
+        Action::UserRenamed { user_id, name } => state.directory.set(&user_id, &name),
+        Action::SignedIn { user_id, name } => {
+            if let Some(name) = name {
+                state.directory.set(&user_id, &name);
+            }
+            state.me = user_id;
+            true
+        }
+        Action::UserDeleted { user_id } => {
+            state.presence.remove(&user_id);
+            state.directory.set(&user_id, directory::DELETED_USER_NAME);
+            true
+        }
+        Action::PresenceChanged { user_id, presence } => state.presence.update(user_id, presence),
+        Action::PresenceDigest { room, full, online, offline } => {
+            if state.current_room.as_ref().is_none_or(|current| current.name != room) {
+                return false; // Digest room lama yang masih dalam perjalanan
+            }
+            state.presence.merge_digest(full, online, offline)
+        }
+        Action::RosterSynced { room, users } => {
+            if state.current_name() != room {
+                return false;
+            }
+            if state.current_room.as_ref().is_none_or(|current| !current.is_anonymous()) {
+                for user in &users {
+                    state.directory.set(&user.user_id, &user.name);
+                }
+            }
+            state.presence.sync_roster(room, users)
+        }
+        Action::UserJoined { room, user } => {
+            if state.joined_room(&room).is_none_or(|joined| !joined.is_anonymous()) {
+                state.directory.set(&user.user_id, &user.name);
+            }
+            state.presence.user_joined(&room, user)
+        }
+        Action::UserLeft { room, user_id } => state.presence.user_left(&room, &user_id),
+        Action::StatusChanged { user_id, status } => state.presence.set_status(user_id, status),
+        Action::CustomStatusChanged { user_id, status } => state.presence.set_custom(user_id, status),
+        Action::MembersLoaded { room, members } => {
+            let Some(current) = state.current_room.as_ref().filter(|current| current.name == room) else { return false };
+            let record_names = !current.is_anonymous(); // Sama seperti ingest: nama akun tidak dicatat di room anonim
+            let mut changed = false;
+            for member in members {
+                if record_names {
+                    changed |= state.directory.set(&member.user_id, &member.name);
+                }
+                changed |= state.presence.set_custom(member.user_id.clone(), member.custom_status);
+                changed |= state.presence.update(member.user_id, Presence { online: member.online, last_seen: member.last_seen });
+            }
+            changed
+        }
+        Action::DirectMessageReceived { to, message } => {
+            let own = message.user_id.as_deref() == Some(state.me.as_str());
+            let peer = match (&message.user_id, own) {
+                (_, true) => to,
+                (Some(sender), false) => sender.clone(),
+                (None, false) => return false, // Pesan langsung selalu membawa id pengirim
+            };
+            state.directory.observe(&message);
+            state.direct.push(&peer, message, own);
+            true
+        }
+        Action::DirectOpened(peer) => state.direct.set_open(peer),
+        Action::RoomJoined(room) => {
+            if state.current_name() == room.name {
+                state.current_room = Some(room); // Bergabung ulang: pengaturan room diperbarui, pesan tetap
+                return true;
+            }
+            // Pesan room yang diikuti ulang akan diputar ulang server dari awal
+            state.background.retain(|buffer| buffer.name() != room.name);
+            // Room umum yang belum pernah dipakai tidak perlu disimpan
+            if state.current_room.is_some() || !state.messages.is_empty() {
+                let previous = RoomBuffer {
+                    room: state.current_room.take(),
+                    messages: std::mem::take(&mut state.messages),
+                    missed_gap: state.missed_gap.take(),
+                    history_complete: state.history_complete,
+                    read_marks: std::mem::take(&mut state.read_marks),
+                    notes: std::mem::take(&mut state.notes),
+                    ..RoomBuffer::default()
+                };
+                state.background.push(previous);
+            }
+            state.current_room = Some(room);
+            state.messages = MessageStore::default();
+            state.missed_gap = None;
+            state.history_complete = false;
+            state.read_marks = ReadMarks::default();
+            state.notes = Notepad::default(); // Snapshot diminta App setelah bergabung
+            true
+        }
+        Action::RoomSwitched(room) => {
+            let Some(Target::Background(index)) = target(state, Some(&room)) else { return false };
+            swap_current(state, index);
+            let mut previous = state.background.remove(index);
+            previous.unread = 0;
+            previous.mentioned = false;
+            state.background.push(previous);
+            true
+        }
+        Action::RoomLeft { room } => leave(state, &room),
+        Action::ReadReceiptReceived { room, user_id, message_id } => match target(state, Some(&room)) {
+            Some(Target::Current) => state.read_marks.record(user_id, message_id),
+            Some(Target::Background(index)) => {
+                state.background[index].read_marks.record(user_id, message_id);
+                false // Baru terlihat saat room itu ditampilkan
+            }
+            None => false,
+        },
+        Action::NotesMerged { room, entries } => match target(state, Some(&room)) {
+            Some(Target::Current) => merge_notes(&mut state.notes, entries),
+            Some(Target::Background(index)) => {
+                merge_notes(&mut state.background[index].notes, entries);
+                false
+            }
+            None => false,
+        },
+        Action::RoomExpiryUpdated { room, expires_at } => update_room(state, &room, |current| current.expires_at = Some(expires_at)),
+        Action::RoomExpired { room } => leave(state, &room),
+        Action::SlowModeChanged { room, interval_secs } => update_room(state, &room, |current| current.slow_mode_secs = interval_secs),
+        Action::EmbedsChanged { room, disabled } => update_room(state, &room, |current| current.embeds_disabled = disabled),
+        Action::RoomLanguageChanged { room, language } => update_room(state, &room, |current| current.language = language),
+        Action::PseudonymRotated { room, pseudonym } => update_room(state, &room, |current| current.rotate_pseudonym(pseudonym)),
+        Action::RulesAccepted => match state.current_room.as_mut().filter(|room| room.rules_pending()) {
+            Some(room) => {
+                room.rules_accepted = true;
+                true
+            }
+            None => false,
+        },
+    }
+}
+
+enum Target {
+    Current,
+    Background(usize), // Index di ChatState::background
+}
+
+// Room tujuan pesan atau event; None jika room itu tidak (lagi) diikuti
+fn target(state: &ChatState, room: Option<&str>) -> Option<Target> {
+    match room {
+        None => Some(Target::Current), // Server lama tanpa multi-room
+        Some(room) if room == state.current_name() => Some(Target::Current),
+        Some(room) => state.background.iter().position(|buffer| buffer.name() == room).map(Target::Background),
+    }
+}
+
+// Tukar room yang ditampilkan dengan room latar belakang di `index`; memanggilnya dua kali mengembalikan keadaan semula
+fn swap_current(state: &mut ChatState, index: usize) {
+    let buffer = &mut state.background[index];
+    std::mem::swap(&mut state.current_room, &mut buffer.room);
+    std::mem::swap(&mut state.messages, &mut buffer.messages);
+    std::mem::swap(&mut state.missed_gap, &mut buffer.missed_gap);
+    std::mem::swap(&mut state.history_complete, &mut buffer.history_complete);
+    std::mem::swap(&mut state.read_marks, &mut buffer.read_marks);
+    std::mem::swap(&mut state.notes, &mut buffer.notes);
+}
+
+// true jika ada catatan yang berubah
+fn merge_notes(notes: &mut Notepad, entries: Vec<NoteEntry>) -> bool {
+    let mut changed = false;
+    for entry in entries {
+        changed |= notes.merge(entry); // Semua entry digabung, bukan berhenti di perubahan pertama
+    }
+    changed
+}
+
+// Jalankan `apply` seolah room latar belakang sedang ditampilkan, agar aturan room (anonim, mention) tetap berlaku
+fn with_background<R>(state: &mut ChatState, index: usize, apply: impl FnOnce(&mut ChatState) -> R) -> R {
+    swap_current(state, index);
+    let result = apply(state);
+    swap_current(state, index);
+    result
+}
+
+fn ingest_routed(state: &mut ChatState, msg: ChatMessage, raw: Option<String>, now_ms: f64) -> bool {
+    match target(state, msg.room.as_deref()) {
+        Some(Target::Current) => ingest(state, msg, raw, now_ms),
+        Some(Target::Background(index)) => {
+            let mentions = state.background[index].messages.mentions().len();
+            if !with_background(state, index, |state| ingest(state, msg, raw, now_ms)) {
+                return false;
+            }
+            let buffer = &mut state.background[index];
+            buffer.unread += 1;
+            buffer.mentioned |= buffer.messages.mentions().len() > mentions;
+            true
+        }
+        None => false, // Pesan yang masih dalam perjalanan dari room yang sudah ditinggalkan
+    }
+}
+
+// Keluar dari room; jika room itu yang ditampilkan, room latar belakang terakhir menggantikannya
+fn leave(state: &mut ChatState, room: &str) -> bool {
+    match target(state, Some(room)) {
+        Some(Target::Background(index)) => {
+            state.background.remove(index);
+            true
+        }
+        Some(Target::Current) => {
+            let next = state.background.pop().unwrap_or_default();
+            state.current_room = next.room;
+            state.messages = next.messages;
+            state.missed_gap = next.missed_gap;
+            state.history_complete = next.history_complete;
+            state.read_marks = next.read_marks;
+            true
+        }
+        None => false,
+    }
+}
+
+fn fill_gap(state: &mut ChatState, messages: Vec<ChatMessage>, now_ms: f64) -> bool {
+    let Some(gap) = state.missed_gap.take() else { return false };
+    for msg in &messages {
+        state.directory.observe(msg);
+    }
+    let messages = messages
+        .into_iter()
+        .map(|msg| {
+            let mention = mentions_me(state, &msg);
+            (msg, mention)
+        })
+        .collect();
+    state.messages.insert_at(gap.index, messages);
+    state.messages.remove_expired(now_ms);
+    true
+}
+
+// Sisipkan halaman riwayat lama di awal daftar. Celah tanpa `after` (pesan sebelum backfill pertama)
+// adalah riwayat yang sama, jadi digantikan halaman ini; celah lain ikut bergeser.
+fn prepend_older(state: &mut ChatState, messages: Vec<ChatMessage>, has_more: bool, now_ms: f64) -> bool {
+    state.history_complete = !has_more;
+    let anonymous = state.current_room.as_ref().is_some_and(RoomState::is_anonymous);
+    let messages: Vec<(ChatMessage, bool)> = messages
+        .into_iter()
+        .filter(|msg| msg.expires_at.is_none_or(|at| at > now_ms))
+        .map(|msg| {
+            if !anonymous {
+                state.directory.observe(&msg);
+            }
+            let mention = mentions_me(state, &msg);
+            (msg, mention)
+        })
+        .collect();
+    let before = state.messages.len();
+    state.messages.insert_at(0, messages);
+    let inserted = state.messages.len() - before;
+    match &mut state.missed_gap {
+        Some(gap) if gap.after.is_none() => state.missed_gap = None,
+        Some(gap) => gap.index += inserted,
+        None => {}
+    }
+    true
+}
+
+fn restore_older(state: &mut ChatState, messages: Vec<ChatMessage>, now_ms: f64) -> bool {
+    let messages: Vec<ChatMessage> = messages.into_iter().filter(|msg| msg.id.as_deref().is_none_or(|id| state.messages.position(id).is_none())).collect();
+    if messages.is_empty() {
+        return false;
+    }
+    let has_more = !state.history_complete;
+    prepend_older(state, messages, has_more, now_ms)
+}
+
+fn ingest(state: &mut ChatState, msg: ChatMessage, raw: Option<String>, now_ms: f64) -> bool {
+    // Pesan sementara yang sudah kedaluwarsa (mis. dari riwayat resume) tidak ditampilkan lagi
+    if msg.expires_at.is_some_and(|at| at <= now_ms) {
+        return false;
+    }
+    // Id samaran di room anonim tidak dicatat ke direktori akun
+    if !state.current_room.as_ref().is_some_and(RoomState::is_anonymous) {
+        state.directory.observe(&msg); // Nama baru pengirim juga berlaku untuk pesan-pesan lamanya
+    }
+    if msg.timestamp.is_some() {
+        state.last_seen = msg.timestamp;
+    }
+    // Pesan user ini yang dikembalikan server menggantikan versi pending-nya
+    let Some((msg, raw)) = state.messages.acknowledge(msg, raw) else { return true };
+    let mention = mentions_me(state, &msg);
+    state.messages.push(msg, raw, mention);
+    true
+}
+
+// Pesan orang lain yang menyebut "@nama", "@id" atau, di room anonim, "@nama-samaran" user ini.
+// Bisikan untuk user ini juga dihitung, agar ikut navigasi mention dan notifikasi.
+fn mentions_me(state: &ChatState, msg: &ChatMessage) -> bool {
+    if state.is_own(msg) {
+        return false;
+    }
+    if msg.whisper_to.as_deref() == Some(state.me.as_str()) {
+        return true;
+    }
+    let room = state.current_room.as_ref();
+    let mut handles = vec![state.me.as_str()];
+    handles.extend(state.directory.name_of(&state.me));
+    handles.extend(room.and_then(|room| room.pseudonym.as_ref()).map(|p| p.name.as_str()));
+    mentions::is_mention(&msg.text, &handles)
+}
+
+// Ubah room yang diikuti (ditampilkan atau di latar belakang) hanya jika event memang untuk room itu
+fn update_room(state: &mut ChatState, room: &str, apply: impl FnOnce(&mut RoomState)) -> bool {
+    let target = match state.current_room.as_mut() {
+        Some(current) if current.name == room => Some(current),
+        _ => state.background.iter_mut().find_map(|buffer| buffer.room.as_mut().filter(|joined| joined.name == room)),
+    };
+    match target {
+        Some(joined) => {
+            apply(joined);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: Option<&str>, client_id: Option<&str>, user: &str, text: &str, room: Option<&str>) -> ChatMessage {
+        serde_json::from_value(serde_json::json!({
+            "id": id, "client_id": client_id, "user_id": user, "username": user, "text": text, "room": room,
+        }))
+        .unwrap()
+    }
+
+    fn received(state: &mut ChatState, msg: ChatMessage) -> bool {
+        reduce(state, Action::MessageReceived { message: msg, raw: None, now_ms: 0.0 })
+    }
+
+    fn joined(state: &mut ChatState, room: &str) {
+        reduce(state, Action::RoomJoined(RoomState::new(room.to_string(), None)));
+    }
+
+    fn texts(messages: &MessageStore) -> Vec<&str> {
+        messages.entries().iter().map(|entry| entry.first.message.text.as_str()).collect()
+    }
+
+    #[test]
+    fn received_messages_append_and_group_repeats() {
+        let mut state = ChatState { me: "saya".to_string(), ..ChatState::default() };
+        assert!(received(&mut state, message(Some("m1"), None, "budi", "halo", None)));
+        assert!(received(&mut state, message(Some("m2"), None, "budi", "halo", None)));
+        assert!(received(&mut state, message(Some("m3"), None, "ani", "hai", None)));
+        assert_eq!(texts(&state.messages), ["halo", "hai"]);
+        assert_eq!(state.messages.entries()[0].count(), 2); // Pesan identik berturut-turut digabung
+        assert_eq!(state.messages.position("m2"), Some(0));
+    }
+
+    #[test]
+    fn server_echo_replaces_pending_message() {
+        let mut state = ChatState { me: "saya".to_string(), ..ChatState::default() };
+        reduce(&mut state, Action::MessageSending { message: message(None, Some("c1"), "saya", "halo", None), now_ms: 0.0 });
+        assert!(state.unsent("c1").is_some());
+        received(&mut state, message(Some("m1"), Some("c1"), "saya", "halo", None));
+        assert_eq!(state.messages.len(), 1); // Tidak muncul dua kali
+        let stored = &state.messages.entries()[0].first;
+        assert_eq!(stored.delivery, Delivery::Sent);
+        assert_eq!(stored.message.id.as_deref(), Some("m1"));
+        assert!(state.unsent("c1").is_none());
+    }
+
+    #[test]
+    fn components_update_edits_message_in_any_joined_room() {
+        let mut state = ChatState::default();
+        joined(&mut state, "a");
+        received(&mut state, message(Some("m1"), None, "bot", "pilih", Some("a")));
+        joined(&mut state, "b");
+        let components: Vec<MessageComponent> =
+            serde_json::from_value(serde_json::json!([{ "kind": "button", "custom_id": "ok", "label": "OK" }])).unwrap();
+        assert!(reduce(&mut state, Action::ComponentsUpdated { message_id: "m1".to_string(), components: components.clone() }));
+        assert!(!reduce(&mut state, Action::ComponentsUpdated { message_id: "m9".to_string(), components: Vec::new() }));
+        reduce(&mut state, Action::RoomSwitched("a".to_string()));
+        assert_eq!(state.messages.entries()[0].first.message.components, components);
+    }
+
+    #[test]
+    fn room_switch_swaps_buffers_and_clears_unread() {
+        let mut state = ChatState::default();
+        joined(&mut state, "a");
+        received(&mut state, message(Some("m1"), None, "budi", "di a", Some("a")));
+        joined(&mut state, "b");
+        assert_eq!(state.current_name(), "b");
+        assert!(state.messages.is_empty());
+        // Pesan untuk room latar belakang tidak masuk ke room yang ditampilkan
+        received(&mut state, message(Some("m2"), None, "budi", "lagi di a", Some("a")));
+        received(&mut state, message(Some("m3"), None, "budi", "di b", Some("b")));
+        assert!(!received(&mut state, message(Some("m4"), None, "budi", "room lain", Some("c"))));
+        assert_eq!(texts(&state.messages), ["di b"]);
+        assert_eq!(state.background[0].unread, 1);
+
+        assert!(reduce(&mut state, Action::RoomSwitched("a".to_string())));
+        assert_eq!(state.current_name(), "a");
+        assert_eq!(texts(&state.messages), ["di a", "lagi di a"]);
+        let previous = state.background.last().unwrap();
+        assert_eq!((previous.name(), previous.unread), ("b", 0));
+        assert_eq!(texts(&previous.messages), ["di b"]);
+        assert!(!reduce(&mut state, Action::RoomSwitched("c".to_string()))); // Belum diikuti
+    }
+
+    #[test]
+    fn local_history_restore_skips_known_messages() {
+        let mut state = ChatState::default();
+        joined(&mut state, "a");
+        received(&mut state, message(Some("m2"), None, "budi", "baru", Some("a")));
+        let restored = vec![message(Some("m1"), None, "budi", "lama", Some("a")), message(Some("m2"), None, "budi", "baru", Some("a"))];
+        assert!(reduce(&mut state, Action::LocalHistoryRestored { messages: restored.clone(), now_ms: 0.0 }));
+        assert_eq!(texts(&state.messages), ["lama", "baru"]);
+        assert!(!reduce(&mut state, Action::LocalHistoryRestored { messages: restored, now_ms: 0.0 }));
+        assert_eq!(state.messages.len(), 2);
+    }
+}
@@ -0,0 +1,146 @@
+// src/stats.rs
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::i18n::trf;
+use crate::uploads::format_size;
+
+#[derive(Debug, Default)]
+struct Counters {
+    messages_sent: u64,
+    messages_received: u64,
+    frames_sent: u64, // Semua frame, termasuk perintah/event protokol
+    frames_received: u64,
+    bytes_sent: u64,
+    bytes_received: u64,
+    connects: u32,
+    connected_since: Option<f64>, // Epoch millis koneksi saat ini
+    latency_total_ms: f64,
+    latency_samples: u32,
+    last_latency_ms: Option<f64>,
+}
+
+// Statistik koneksi untuk sesi tab ini. Dibagi lewat Rc ke task baca/kirim di background,
+// sama seperti ActivityMonitor, sehingga mencatat frame tidak memicu re-render.
+#[derive(Clone)]
+pub struct SessionStats {
+    started_at: f64,
+    counters: Rc<RefCell<Counters>>,
+}
+
+// Salinan nilai statistik untuk ditampilkan
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsSnapshot {
+    pub messages_sent: u64,
+    pub messages_received: u64,
+    pub frames_sent: u64,
+    pub frames_received: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub reconnects: u32,
+    pub avg_latency_ms: Option<f64>,
+    pub last_latency_ms: Option<f64>,
+    pub uptime_ms: Option<f64>, // None selama tidak terhubung
+    pub session_ms: f64,
+}
+
+impl SessionStats {
+    pub fn new() -> Self {
+        Self { started_at: js_sys::Date::now(), counters: Rc::default() }
+    }
+
+    pub fn record_sent(&self, bytes: usize, is_chat: bool) {
+        let mut c = self.counters.borrow_mut();
+        c.frames_sent += 1;
+        c.bytes_sent += bytes as u64;
+        if is_chat {
+            c.messages_sent += 1;
+        }
+    }
+
+    pub fn record_received(&self, bytes: usize, is_chat: bool) {
+        let mut c = self.counters.borrow_mut();
+        c.frames_received += 1;
+        c.bytes_received += bytes as u64;
+        if is_chat {
+            c.messages_received += 1;
+        }
+    }
+
+    // Build `worker`: frame yang diterima dan sudah dihitung ChatWorker
+    #[cfg(feature = "worker")]
+    pub fn record_received_batch(&self, frames: u64, bytes: u64, messages: u64) {
+        let mut c = self.counters.borrow_mut();
+        c.frames_received += frames;
+        c.bytes_received += bytes;
+        c.messages_received += messages;
+    }
+
+    pub fn record_connected(&self) {
+        let mut c = self.counters.borrow_mut();
+        c.connects += 1;
+        c.connected_since = Some(js_sys::Date::now());
+    }
+
+    pub fn record_disconnected(&self) {
+        self.counters.borrow_mut().connected_since = None;
+    }
+
+    // Round-trip satu permintaan TimeSync
+    pub fn record_latency(&self, rtt_ms: f64) {
+        let mut c = self.counters.borrow_mut();
+        c.latency_total_ms += rtt_ms;
+        c.latency_samples += 1;
+        c.last_latency_ms = Some(rtt_ms);
+    }
+
+    pub fn snapshot(&self) -> StatsSnapshot {
+        let c = self.counters.borrow();
+        let now = js_sys::Date::now();
+        StatsSnapshot {
+            messages_sent: c.messages_sent,
+            messages_received: c.messages_received,
+            frames_sent: c.frames_sent,
+            frames_received: c.frames_received,
+            bytes_sent: c.bytes_sent,
+            bytes_received: c.bytes_received,
+            reconnects: c.connects.saturating_sub(1),
+            avg_latency_ms: (c.latency_samples > 0).then(|| c.latency_total_ms / f64::from(c.latency_samples)),
+            last_latency_ms: c.last_latency_ms,
+            uptime_ms: c.connected_since.map(|since| now - since),
+            session_ms: now - self.started_at,
+        }
+    }
+}
+
+// "1j 02m 03d", "4m 05d", "12d"
+pub fn format_duration(ms: f64) -> String {
+    let secs = (ms / 1000.0).max(0.0) as u64;
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    match (h, m) {
+        (0, 0) => trf("{}d", &[&s]),
+        (0, _) => trf("{}m {}d", &[&m, &format!("{:02}", s)]),
+        _ => trf("{}j {}m {}d", &[&h, &format!("{:02}", m), &format!("{:02}", s)]),
+    }
+}
+
+pub fn format_latency(ms: Option<f64>) -> String {
+    ms.map_or_else(|| "-".to_string(), |ms| format!("{:.0} ms", ms))
+}
+
+// Laporan teks untuk ditempel ke laporan bug
+pub fn report(stats: &StatsSnapshot, user_agent: &str) -> String {
+    [
+        format!("webchat {}", env!("CARGO_PKG_VERSION")),
+        format!("User agent: {}", user_agent),
+        format!("Durasi sesi: {}", format_duration(stats.session_ms)),
+        format!("Uptime koneksi: {}", stats.uptime_ms.map_or_else(|| "terputus".to_string(), format_duration)),
+        format!("Reconnect: {}", stats.reconnects),
+        format!("Pesan dikirim/diterima: {}/{}", stats.messages_sent, stats.messages_received),
+        format!("Frame dikirim/diterima: {}/{}", stats.frames_sent, stats.frames_received),
+        format!("Data dikirim/diterima: {}/{}", format_size(stats.bytes_sent), format_size(stats.bytes_received)),
+        format!("Latensi rata-rata: {}", format_latency(stats.avg_latency_ms)),
+        format!("Latensi terakhir: {}", format_latency(stats.last_latency_ms)),
+    ]
+    .join("\n")
+}
@@ -0,0 +1,62 @@
+// src/stats_panel.rs
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use yew::prelude::*;
+
+use crate::i18n::use_locale;
+use crate::stats::{self, StatsSnapshot};
+use crate::uploads::format_size;
+
+#[derive(Properties, PartialEq)]
+pub struct StatsPanelProps {
+    pub stats: StatsSnapshot,
+    pub on_refresh: Callback<()>, // Ambil snapshot baru (uptime tidak diperbarui sendiri)
+}
+
+// Panel diagnostik koneksi, dengan laporan yang bisa disalin untuk laporan bug
+#[function_component(StatsPanel)]
+pub fn stats_panel(props: &StatsPanelProps) -> Html {
+    let locale = use_locale();
+    let copied = use_state(|| None::<bool>); // Some(false) = gagal menyalin
+    let stats = &props.stats;
+
+    let on_copy = {
+        let copied = copied.clone();
+        let stats = stats.clone();
+        Callback::from(move |_| {
+            let Some(navigator) = web_sys::window().map(|w| w.navigator()) else { return };
+            let report = stats::report(&stats, &navigator.user_agent().unwrap_or_default());
+            let promise = navigator.clipboard().write_text(&report);
+            let copied = copied.clone();
+            spawn_local(async move {
+                copied.set(Some(JsFuture::from(promise).await.is_ok()));
+            });
+        })
+    };
+
+    html! {
+        <details class="settings-panel stats-panel">
+            <summary>{ locale.t("Diagnostik koneksi") }</summary>
+            <dl class="stats-list">
+                <dt>{ locale.t("Uptime") }</dt>
+                <dd>{ stats.uptime_ms.map_or_else(|| locale.t("terputus").to_string(), stats::format_duration) }</dd>
+                <dt>{ locale.t("Durasi sesi") }</dt>
+                <dd>{ stats::format_duration(stats.session_ms) }</dd>
+                <dt>{ locale.t("Reconnect") }</dt>
+                <dd>{ stats.reconnects }</dd>
+                <dt>{ locale.t("Pesan dikirim / diterima") }</dt>
+                <dd>{ format!("{} / {}", stats.messages_sent, stats.messages_received) }</dd>
+                <dt>{ locale.t("Data dikirim / diterima") }</dt>
+                <dd>{ format!("{} / {}", format_size(stats.bytes_sent), format_size(stats.bytes_received)) }</dd>
+                <dt>{ locale.t("Latensi rata-rata") }</dt>
+                <dd>{ stats::format_latency(stats.avg_latency_ms) }</dd>
+                <dt>{ locale.t("Latensi terakhir") }</dt>
+                <dd>{ stats::format_latency(stats.last_latency_ms) }</dd>
+            </dl>
+            <button onclick={props.on_refresh.reform(|_| ())}>{ locale.t("Perbarui") }</button>
+            <button onclick={on_copy}>{ locale.t("Salin laporan") }</button>
+            if let Some(ok) = *copied {
+                <span class="settings-hint">{ " " }{ locale.t(if ok { "Laporan disalin." } else { "Gagal menyalin ke clipboard." }) }</span>
+            }
+        </details>
+    }
+}
@@ -0,0 +1,91 @@
+// src/status.rs
+// Status kustom user: emoji dan teks singkat dengan waktu kedaluwarsa. Pilihan cepat mengisi
+// editor sekaligus dengan kedaluwarsa yang wajar; waktu kedaluwarsa dihitung dengan jam server
+// (ServerClock) agar user lain melihat status hilang pada saat yang sama.
+use wasm_bindgen::JsValue;
+
+use crate::i18n::{tr, trf, trn};
+use crate::protocol::CustomStatus;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatusExpiry {
+    Never,
+    Minutes(u32),
+    EndOfDay, // Tengah malam waktu lokal user
+}
+
+impl StatusExpiry {
+    pub const ALL: [StatusExpiry; 5] = [
+        StatusExpiry::Never,
+        StatusExpiry::Minutes(30),
+        StatusExpiry::Minutes(60),
+        StatusExpiry::Minutes(240),
+        StatusExpiry::EndOfDay,
+    ];
+
+    pub fn label(self) -> String {
+        match self {
+            StatusExpiry::Never => tr("Jangan hapus").to_string(),
+            StatusExpiry::Minutes(minutes) if minutes % 60 == 0 => trn("{} jam", minutes as usize / 60, &[]),
+            StatusExpiry::Minutes(minutes) => trn("{} menit", minutes as usize, &[]),
+            StatusExpiry::EndOfDay => tr("Hari ini").to_string(),
+        }
+    }
+
+    // `now_ms` adalah jam server; akhir hari dihitung dari jam lokal lalu digeser sebesar selisih keduanya
+    pub fn resolve(self, now_ms: f64) -> Option<f64> {
+        match self {
+            StatusExpiry::Never => None,
+            StatusExpiry::Minutes(minutes) => Some(now_ms + f64::from(minutes) * 60_000.0),
+            StatusExpiry::EndOfDay => {
+                let local_now = js_sys::Date::now();
+                let midnight = js_sys::Date::new(&JsValue::from_f64(local_now));
+                midnight.set_hours(24);
+                midnight.set_minutes(0);
+                midnight.set_seconds(0);
+                midnight.set_milliseconds(0);
+                Some(midnight.get_time() + (now_ms - local_now))
+            }
+        }
+    }
+}
+
+pub struct Preset {
+    pub emoji: &'static str,
+    pub text: &'static str,
+    pub expiry: StatusExpiry,
+}
+
+pub const PRESETS: [Preset; 5] = [
+    Preset { emoji: "🗓️", text: "Sedang rapat", expiry: StatusExpiry::Minutes(60) },
+    Preset { emoji: "🚌", text: "Dalam perjalanan", expiry: StatusExpiry::Minutes(30) },
+    Preset { emoji: "🍽️", text: "Makan siang", expiry: StatusExpiry::Minutes(60) },
+    Preset { emoji: "🎧", text: "Fokus, balas nanti", expiry: StatusExpiry::Minutes(240) },
+    Preset { emoji: "🤒", text: "Sedang sakit", expiry: StatusExpiry::EndOfDay },
+];
+
+// Isi editor sebelum waktu kedaluwarsanya dihitung
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusDraft {
+    pub emoji: String,
+    pub text: String,
+    pub expiry: StatusExpiry,
+}
+
+impl StatusDraft {
+    pub fn validate(&self) -> Result<(), String> {
+        let chars = self.text.trim().chars().count();
+        if chars == 0 {
+            return Err(tr("Teks status tidak boleh kosong.").to_string());
+        }
+        if chars > CustomStatus::MAX_TEXT_CHARS {
+            return Err(trf("Teks status maksimal {} karakter.", &[&CustomStatus::MAX_TEXT_CHARS]));
+        }
+        Ok(())
+    }
+
+    pub fn into_status(self, now_ms: f64) -> CustomStatus {
+        let emoji = Some(self.emoji.trim().to_string()).filter(|emoji| !emoji.is_empty());
+        CustomStatus { emoji, text: self.text.trim().to_string(), expires_at: self.expiry.resolve(now_ms) }
+    }
+}
@@ -0,0 +1,127 @@
+// src/status_panel.rs
+// Editor status kustom di panel online: pilihan cepat, emoji, teks dan waktu kedaluwarsa.
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::i18n::use_locale;
+use crate::protocol::CustomStatus;
+use crate::status::{StatusDraft, StatusExpiry, PRESETS};
+use crate::time;
+
+#[derive(Properties, PartialEq)]
+pub struct StatusEditorProps {
+    pub current: Option<CustomStatus>, // Sudah disaring dari status yang kedaluwarsa
+    pub connected: bool,
+    pub on_change: Callback<Option<StatusDraft>>, // None = hapus status
+}
+
+#[function_component(StatusEditor)]
+pub fn status_editor(props: &StatusEditorProps) -> Html {
+    let locale = use_locale();
+    let editing = use_state(|| false);
+    let draft = use_state(|| StatusDraft { emoji: String::new(), text: String::new(), expiry: StatusExpiry::Never });
+    let error = use_state(|| None::<String>);
+
+    if !*editing {
+        let open = {
+            let (editing, draft, error) = (editing.clone(), draft.clone(), error.clone());
+            let current = props.current.clone();
+            Callback::from(move |_| {
+                // Mulai dari status yang sedang dipakai agar mudah disunting
+                if let Some(current) = &current {
+                    draft.set(StatusDraft { emoji: current.emoji.clone().unwrap_or_default(), text: current.text.clone(), expiry: StatusExpiry::Never });
+                }
+                error.set(None);
+                editing.set(true);
+            })
+        };
+        return html! {
+            <div class="custom-status">
+                if let Some(current) = &props.current {
+                    <span class="custom-status-text" dir="auto" title={current.expires_at.map(|at| locale.f("Sampai {}", &[&time::format_absolute(at)]))}>
+                        { current.label() }
+                    </span>
+                    <button class="link-button" onclick={props.on_change.reform(|_| None)} disabled={!props.connected}>{ locale.t("Hapus") }</button>
+                }
+                <button class="link-button" onclick={open} disabled={!props.connected}>
+                    { locale.t(if props.current.is_some() { "Ubah status" } else { "Atur status…" }) }
+                </button>
+            </div>
+        };
+    }
+
+    let on_emoji = {
+        let draft = draft.clone();
+        Callback::from(move |e: InputEvent| {
+            let emoji = e.target_unchecked_into::<HtmlInputElement>().value();
+            draft.set(StatusDraft { emoji, ..(*draft).clone() });
+        })
+    };
+    let on_text = {
+        let draft = draft.clone();
+        Callback::from(move |e: InputEvent| {
+            let text = e.target_unchecked_into::<HtmlInputElement>().value();
+            draft.set(StatusDraft { text, ..(*draft).clone() });
+        })
+    };
+    let on_expiry = {
+        let draft = draft.clone();
+        Callback::from(move |e: Event| {
+            let index = e.target_unchecked_into::<HtmlSelectElement>().selected_index().max(0) as usize;
+            let expiry = StatusExpiry::ALL.get(index).copied().unwrap_or(StatusExpiry::Never);
+            draft.set(StatusDraft { expiry, ..(*draft).clone() });
+        })
+    };
+    let on_save = {
+        let (editing, draft, error) = (editing.clone(), draft.clone(), error.clone());
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: FocusEvent| {
+            e.prevent_default();
+            match draft.validate() {
+                Ok(()) => {
+                    on_change.emit(Some((*draft).clone()));
+                    editing.set(false);
+                }
+                Err(message) => error.set(Some(message)),
+            }
+        })
+    };
+    let on_cancel = {
+        let editing = editing.clone();
+        Callback::from(move |_| editing.set(false))
+    };
+
+    html! {
+        <form class="custom-status-editor" onsubmit={on_save}>
+            <div class="status-presets">
+                { for PRESETS.iter().map(|preset| {
+                    let draft = draft.clone();
+                    let onclick = Callback::from(move |_| draft.set(StatusDraft { emoji: preset.emoji.to_string(), text: locale.t(preset.text).to_string(), expiry: preset.expiry }));
+                    html! { <button type="button" class="status-preset" {onclick}>{ format!("{} {}", preset.emoji, locale.t(preset.text)) }</button> }
+                }) }
+            </div>
+            <input class="status-emoji" placeholder="🙂" value={draft.emoji.clone()} oninput={on_emoji} aria-label={locale.t("Emoji status")} />
+            <input
+                class="status-text"
+                dir="auto"
+                placeholder={locale.t("Apa yang sedang Anda lakukan?")}
+                maxlength={CustomStatus::MAX_TEXT_CHARS.to_string()}
+                value={draft.text.clone()}
+                oninput={on_text}
+            />
+            <label>
+                { locale.t("Hapus setelah:") }{ " " }
+                <select onchange={on_expiry}>
+                    { for StatusExpiry::ALL.iter().map(|expiry| html! {
+                        <option selected={*expiry == draft.expiry}>{ expiry.label() }</option>
+                    }) }
+                </select>
+            </label>
+            if let Some(message) = &*error {
+                <p class="field-error">{ message }</p>
+            }
+            <button type="submit" disabled={!props.connected}>{ locale.t("Simpan") }</button>
+            <button type="button" onclick={on_cancel}>{ locale.t("Batal") }</button>
+        </form>
+    }
+}
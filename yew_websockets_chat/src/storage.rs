@@ -0,0 +1,50 @@
+// src/storage.rs
+// Pembungkus tipis localStorage. Semua kegagalan (mode privat, kuota penuh) diperlakukan
+// sebagai "tidak ada data" karena penyimpanan lokal hanya pelengkap, bukan sumber kebenaran.
+use serde::{de::DeserializeOwned, Serialize};
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window().and_then(|w| w.local_storage().ok().flatten())
+}
+
+pub fn get(key: &str) -> Option<String> {
+    local_storage().and_then(|s| s.get_item(key).ok().flatten())
+}
+
+pub fn set(key: &str, value: &str) {
+    if let Some(storage) = local_storage() {
+        if storage.set_item(key, value).is_err() {
+            log::warn!("Gagal menyimpan {} ke localStorage", key);
+        }
+    }
+}
+
+pub fn remove(key: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(key);
+    }
+}
+
+// Hapus semua data aplikasi (kunci berawalan "webchat."), mis. setelah akun dihapus
+pub fn clear_app_data() {
+    let Some(storage) = local_storage() else { return };
+    let len = storage.length().unwrap_or(0);
+    let keys: Vec<String> = (0..len)
+        .filter_map(|i| storage.key(i).ok().flatten())
+        .filter(|key| key.starts_with("webchat."))
+        .collect();
+    for key in keys {
+        let _ = storage.remove_item(&key);
+    }
+}
+
+pub fn get_json<T: DeserializeOwned>(key: &str) -> Option<T> {
+    get(key).and_then(|raw| serde_json::from_str(&raw).ok())
+}
+
+pub fn set_json<T: Serialize>(key: &str, value: &T) {
+    match serde_json::to_string(value) {
+        Ok(raw) => set(key, &raw),
+        Err(e) => log::error!("Gagal serialisasi {} untuk localStorage: {}", key, e),
+    }
+}
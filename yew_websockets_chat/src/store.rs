@@ -0,0 +1,276 @@
+// src/store.rs
+use crate::protocol::{MessageComponent, RoomEvent};
+use crate::script::TextProfile;
+use crate::ChatMessage;
+
+pub const ACK_TIMEOUT_MS: f64 = 20_000.0; // Pesan yang belum dikembalikan server selama ini dianggap gagal
+
+// Status kirim pesan. Pesan dari server selalu `Sent`; pesan user ini ditampilkan lebih dulu sebagai
+// `Pending` dan berubah menjadi `Sent` begitu server mengembalikannya dengan `client_id` yang sama.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Delivery {
+    #[default]
+    Sent,
+    Pending { since: f64 }, // Jam server saat dikirim
+    Failed(String), // Alasan yang ditampilkan; pesan bisa dikirim ulang
+}
+
+// Pesan yang sudah diterima beserta payload mentahnya dari server.
+// `raw` hanya diisi pada build dengan fitur `dev` (untuk tampilan "lihat sumber").
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredMessage {
+    pub message: ChatMessage,
+    pub raw: Option<String>,
+    pub delivery: Delivery,
+    pub profile: TextProfile, // Aksara/bahasa teksnya, dihitung sekali di sini bukan di setiap render
+}
+
+impl StoredMessage {
+    fn new(message: ChatMessage, raw: Option<String>, delivery: Delivery) -> Self {
+        let profile = TextProfile::detect(&message.text);
+        Self { message, raw, delivery, profile }
+    }
+
+    fn is_unsent(&self, client_id: &str) -> bool {
+        self.delivery != Delivery::Sent && self.message.client_id.as_deref() == Some(client_id)
+    }
+}
+
+// Satu baris di daftar pesan. Pesan identik yang dikirim berturut-turut oleh user yang sama
+// (spam/flood bot) digabung ke dalam satu entry dengan penghitung "×N".
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageEntry {
+    pub first: StoredMessage,         // Pesan pertama dari rangkaian
+    pub repeats: Vec<StoredMessage>,  // Pesan identik berikutnya, disimpan agar bisa ditampilkan saat di-expand
+    pub expanded: bool,
+    pub mentions_me: bool, // Salah satu pesan di entry ini menyebut user saat ini
+}
+
+impl MessageEntry {
+    fn new(message: StoredMessage) -> Self {
+        Self { first: message, repeats: Vec::new(), expanded: false, mentions_me: false }
+    }
+
+    // Jumlah total pesan yang diwakili entry ini
+    pub fn count(&self) -> usize {
+        1 + self.repeats.len()
+    }
+
+    fn is_repeat_of(&self, msg: &ChatMessage) -> bool {
+        if self.first.delivery != Delivery::Sent {
+            return false; // Pesan yang belum terkirim selalu tampil sendiri agar statusnya terlihat
+        }
+        let first = &self.first.message;
+        let same_sender = match (&first.user_id, &msg.user_id) {
+            (Some(a), Some(b)) => a == b,
+            _ => first.username == msg.username,
+        };
+        // Lampiran ikut dibandingkan: beberapa file berbeda tanpa teks bukan duplikat
+        // Bisikan tidak digabung dengan pesan room biasa yang kebetulan sama
+        same_sender && first.text == msg.text && first.attachment == msg.attachment && first.whisper_to == msg.whisper_to
+    }
+}
+
+// Penyimpanan pesan untuk tampilan. Penggabungan dilakukan saat pesan masuk,
+// bukan di view, supaya daftar tetap pendek walaupun ada ribuan pesan duplikat.
+#[derive(Debug, Clone, Default)]
+pub struct MessageStore {
+    entries: Vec<MessageEntry>,
+    mentions: Vec<usize>, // Index entry yang menyebut user saat ini, urut naik; diperbarui saat pesan masuk
+}
+
+impl MessageStore {
+    pub fn push(&mut self, msg: ChatMessage, raw: Option<String>, mentions_me: bool) {
+        let stored = StoredMessage::new(msg, raw, Delivery::Sent);
+        match self.entries.last_mut() {
+            Some(last) if last.is_repeat_of(&stored.message) => last.repeats.push(stored),
+            _ => self.entries.push(MessageEntry::new(stored)),
+        }
+        let index = self.entries.len() - 1;
+        if mentions_me && self.mentions.last() != Some(&index) {
+            self.entries[index].mentions_me = true;
+            self.mentions.push(index);
+        }
+    }
+
+    // Pesan user ini yang baru dikirim, ditampilkan sebelum dikembalikan server
+    pub fn push_pending(&mut self, msg: ChatMessage, now_ms: f64) {
+        let stored = StoredMessage::new(msg, None, Delivery::Pending { since: now_ms });
+        self.entries.push(MessageEntry::new(stored));
+    }
+
+    // Pesan pending/gagal dengan `client_id` ini. Pesan yang belum terkirim tidak pernah punya duplikat.
+    pub fn unsent(&self, client_id: &str) -> Option<&StoredMessage> {
+        self.entries.iter().map(|entry| &entry.first).find(|stored| stored.is_unsent(client_id))
+    }
+
+    fn unsent_mut(&mut self, client_id: &str) -> Option<&mut StoredMessage> {
+        self.entries.iter_mut().map(|entry| &mut entry.first).find(|stored| stored.is_unsent(client_id))
+    }
+
+    // Ganti pesan pending dengan versi dari server (id dan timestamp terisi) di posisi yang sama.
+    // Mengembalikan pesan itu lagi jika tidak ada pesan pending yang cocok.
+    pub fn acknowledge(&mut self, msg: ChatMessage, raw: Option<String>) -> Option<(ChatMessage, Option<String>)> {
+        match msg.client_id.as_deref().and_then(|client_id| self.unsent_mut(client_id)) {
+            Some(stored) => {
+                *stored = StoredMessage::new(msg, raw, Delivery::Sent);
+                None
+            }
+            None => Some((msg, raw)),
+        }
+    }
+
+    pub fn set_delivery(&mut self, client_id: &str, delivery: Delivery) -> bool {
+        match self.unsent_mut(client_id) {
+            Some(stored) if stored.delivery != delivery => {
+                stored.delivery = delivery;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Pesan pending yang dikirim sebelum `cutoff` ditandai gagal
+    pub fn fail_stale(&mut self, cutoff: f64, reason: &str) -> bool {
+        let mut changed = false;
+        for entry in &mut self.entries {
+            if matches!(entry.first.delivery, Delivery::Pending { since } if since <= cutoff) {
+                entry.first.delivery = Delivery::Failed(reason.to_string());
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    // Buang pesan gagal yang tidak jadi dikirim ulang
+    pub fn remove_unsent(&mut self, client_id: &str) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|entry| !entry.first.is_unsent(client_id));
+        let removed = self.entries.len() != before;
+        if removed {
+            self.rebuild_mentions();
+        }
+        removed
+    }
+
+    // Sisipkan pesan lama (mis. riwayat yang terlewat) di posisi entry tertentu, dengan urutan tetap.
+    // Rangkaian duplikat hanya digabung di dalam batch ini, tidak dengan entry di sekitarnya.
+    pub fn insert_at(&mut self, index: usize, messages: Vec<(ChatMessage, bool)>) {
+        let mut batch = MessageStore::default();
+        for (msg, mentions_me) in messages {
+            batch.push(msg, None, mentions_me);
+        }
+        let index = index.min(self.entries.len());
+        let inserted = batch.entries.len();
+        self.entries.splice(index..index, batch.entries);
+        let split = self.mentions.partition_point(|&i| i < index);
+        let shifted: Vec<usize> = self.mentions.drain(split..).map(|i| i + inserted).collect();
+        self.mentions.extend(batch.mentions.into_iter().map(|i| i + index));
+        self.mentions.extend(shifted);
+    }
+
+    pub fn mentions(&self) -> &[usize] {
+        &self.mentions
+    }
+
+    fn rebuild_mentions(&mut self) {
+        self.mentions = self.entries.iter().enumerate().filter(|(_, entry)| entry.mentions_me).map(|(i, _)| i).collect();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Buka/tutup rangkaian pesan duplikat. Mengembalikan false jika index tidak valid.
+    pub fn toggle_expanded(&mut self, index: usize) -> bool {
+        match self.entries.get_mut(index) {
+            Some(entry) if entry.count() > 1 => {
+                entry.expanded = !entry.expanded;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Hapus pesan sementara yang sudah lewat `now_ms` (jam server). Entry yang pesan pertamanya
+    // kedaluwarsa diwakili oleh duplikat berikutnya. Mengembalikan true jika ada yang dihapus.
+    pub fn remove_expired(&mut self, now_ms: f64) -> bool {
+        let expired = |stored: &StoredMessage| stored.message.expires_at.is_some_and(|at| at <= now_ms);
+        let before = self.entries.len();
+        let mut removed = false;
+        self.entries.retain_mut(|entry| {
+            let repeats = entry.repeats.len();
+            entry.repeats.retain(|stored| !expired(stored));
+            removed |= entry.repeats.len() != repeats;
+            if !expired(&entry.first) {
+                return true;
+            }
+            if entry.repeats.is_empty() {
+                return false;
+            }
+            entry.first = entry.repeats.remove(0);
+            removed = true;
+            true
+        });
+        let removed = removed || self.entries.len() != before;
+        if removed {
+            self.rebuild_mentions();
+        }
+        removed
+    }
+
+    // Waktu kedaluwarsa paling awal di antara pesan yang tersimpan
+    pub fn next_expiry(&self) -> Option<f64> {
+        self.entries
+            .iter()
+            .flat_map(|entry| std::iter::once(&entry.first).chain(&entry.repeats))
+            .filter_map(|stored| stored.message.expires_at)
+            .reduce(f64::min)
+    }
+
+    // Perbarui kartu acara di semua pesan yang membawanya. Mengembalikan true jika ada yang berubah.
+    pub fn update_event(&mut self, event: &RoomEvent) -> bool {
+        let mut changed = false;
+        for entry in &mut self.entries {
+            for stored in std::iter::once(&mut entry.first).chain(entry.repeats.iter_mut()) {
+                match stored.message.event.as_deref_mut() {
+                    Some(current) if current.id == event.id && current != event => {
+                        *current = event.clone();
+                        changed = true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        changed
+    }
+
+    // Ganti tombol/menu pesan bot dengan id ini. Mengembalikan true jika pesannya ada di sini.
+    pub fn update_components(&mut self, message_id: &str, components: &[MessageComponent]) -> bool {
+        let stored = self
+            .entries
+            .iter_mut()
+            .flat_map(|entry| std::iter::once(&mut entry.first).chain(entry.repeats.iter_mut()))
+            .find(|stored| stored.message.id.as_deref() == Some(message_id));
+        match stored {
+            Some(stored) => {
+                stored.message.components = components.to_vec();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn entries(&self) -> &[MessageEntry] {
+        &self.entries
+    }
+
+    // Index entry yang memuat pesan dengan id ini, termasuk sebagai pesan duplikat
+    pub fn position(&self, message_id: &str) -> Option<usize> {
+        self.entries.iter().position(|entry| std::iter::once(&entry.first).chain(&entry.repeats).any(|stored| stored.message.id.as_deref() == Some(message_id)))
+    }
+}
@@ -0,0 +1,68 @@
+// src/support.rs
+// Antrean dukungan untuk mode agen: percakapan tamu yang menunggu diambil dan yang sudah ditangani.
+// Server yang menentukan penugasan; klien hanya mencatat klaim yang sedang menunggu jawaban agar
+// tab DM bisa dibuka otomatis begitu server mengonfirmasi percakapan itu milik user ini.
+use crate::protocol::{SupportAgent, SupportTicket};
+
+#[derive(Debug, Clone, Default)]
+pub struct SupportQueue {
+    tickets: Vec<SupportTicket>, // Urut dari yang paling lama menunggu
+    claiming: Option<String>, // Tamu yang baru diklaim user ini, menunggu SupportTicket dari server
+}
+
+impl SupportQueue {
+    pub fn tickets(&self) -> &[SupportTicket] {
+        &self.tickets
+    }
+
+    pub fn ticket(&self, guest: &str) -> Option<&SupportTicket> {
+        self.tickets.iter().find(|ticket| ticket.guest == guest)
+    }
+
+    // Agen yang menangani percakapan dengan `guest`, jika percakapan itu berasal dari antrean
+    pub fn agent_of(&self, guest: &str) -> Option<&SupportAgent> {
+        self.ticket(guest).and_then(|ticket| ticket.agent.as_ref())
+    }
+
+    pub fn claiming(&self) -> Option<&str> {
+        self.claiming.as_deref()
+    }
+
+    pub fn replace(&mut self, mut tickets: Vec<SupportTicket>) {
+        tickets.sort_by(|a, b| a.opened_at.total_cmp(&b.opened_at));
+        self.tickets = tickets;
+        self.claiming = None; // Snapshot baru setelah (re)connect; klaim lama tidak akan dijawab lagi
+    }
+
+    pub fn start_claim(&mut self, guest: String) {
+        self.claiming = Some(guest);
+    }
+
+    // Tiket baru atau berubah. Mengembalikan true jika ini konfirmasi klaim user `me`.
+    pub fn upsert(&mut self, ticket: SupportTicket, me: &str) -> bool {
+        let claimed = self.claiming.as_deref() == Some(ticket.guest.as_str());
+        let mine = ticket.agent.as_ref().is_some_and(|agent| agent.user_id == me);
+        if claimed {
+            self.claiming = None; // Berhasil atau didahului agen lain, klaim sudah dijawab
+        }
+        match self.tickets.iter_mut().find(|existing| existing.guest == ticket.guest) {
+            Some(existing) => *existing = ticket,
+            None => {
+                let index = self.tickets.partition_point(|existing| existing.opened_at <= ticket.opened_at);
+                self.tickets.insert(index, ticket);
+            }
+        }
+        claimed && mine
+    }
+
+    pub fn remove(&mut self, guest: &str) {
+        self.tickets.retain(|ticket| ticket.guest != guest);
+        if self.claiming.as_deref() == Some(guest) {
+            self.claiming = None;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
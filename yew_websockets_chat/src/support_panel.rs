@@ -0,0 +1,72 @@
+// src/support_panel.rs
+// Antrean dukungan di sidebar untuk agen: tamu yang menunggu bisa diambil, percakapan yang sudah
+// ditangani menampilkan nama agennya agar tidak dibalas dua kali.
+use yew::prelude::*;
+
+use crate::i18n::{tr, trf, use_locale};
+use crate::protocol::SupportTicket;
+use crate::time;
+
+#[derive(Properties, PartialEq)]
+pub struct SupportPanelProps {
+    pub tickets: Vec<SupportTicket>,
+    pub me: String, // User id agen ini
+    pub claiming: Option<String>,
+    pub connected: bool,
+    pub now_ms: f64,
+    pub on_claim: Callback<String>,
+    pub on_release: Callback<String>,
+    pub on_open: Callback<String>,
+}
+
+#[function_component(SupportPanel)]
+pub fn support_panel(props: &SupportPanelProps) -> Html {
+    let locale = use_locale();
+    let waiting = props.tickets.iter().filter(|ticket| ticket.agent.is_none()).count();
+    html! {
+        <aside class="support-queue">
+            <h3>{ locale.f("Antrean dukungan ({})", &[&waiting]) }</h3>
+            <ul>
+                { for props.tickets.iter().map(|ticket| view_ticket(props, ticket)) }
+                if props.tickets.is_empty() {
+                    <li class="member-loading">{ locale.t("Tidak ada tamu yang menunggu") }</li>
+                }
+            </ul>
+        </aside>
+    }
+}
+
+fn view_ticket(props: &SupportPanelProps, ticket: &SupportTicket) -> Html {
+    let guest = ticket.guest.clone();
+    let mine = ticket.agent.as_ref().is_some_and(|agent| agent.user_id == props.me);
+    let action = match &ticket.agent {
+        None => {
+            let claiming = props.claiming.as_deref() == Some(guest.as_str());
+            html! {
+                <button onclick={props.on_claim.reform(move |_| guest.clone())} disabled={claiming || !props.connected}>
+                    { tr(if claiming { "Mengambil…" } else { "Ambil" }) }
+                </button>
+            }
+        }
+        Some(_) if mine => {
+            let release = guest.clone();
+            html! {
+                <>
+                    <button class="link-button" onclick={props.on_open.reform(move |_| guest.clone())}>{ tr("Buka") }</button>
+                    <button class="link-button" onclick={props.on_release.reform(move |_| release.clone())} disabled={!props.connected}>{ tr("Lepas") }</button>
+                </>
+            }
+        }
+        Some(agent) => html! { <span class="support-agent">{ trf("Ditangani {}", &[&agent.name]) }</span> },
+    };
+    html! {
+        <li class={classes!("support-ticket", ticket.agent.is_none().then_some("waiting"), mine.then_some("mine"))} key={ticket.guest.clone()}>
+            <span class="support-guest" dir="auto">{ &ticket.guest_name }</span>
+            <span class="support-waiting" title={time::format_absolute(ticket.opened_at)}>{ time::format_relative(ticket.opened_at, props.now_ms) }</span>
+            if let Some(preview) = &ticket.preview {
+                <span class="support-preview" dir="auto">{ preview }</span>
+            }
+            { action }
+        </li>
+    }
+}
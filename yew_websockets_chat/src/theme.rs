@@ -0,0 +1,92 @@
+// src/theme.rs
+// Tema terang/gelap. Pilihan user disimpan di Settings (localStorage); selama belum memilih, tema
+// mengikuti prefers-color-scheme. Paletnya ada di style.css sebagai var(--chat-...) di bawah
+// [data-theme], yang dipasang appearance::apply. Function component membaca dan mengganti tema
+// lewat ThemeContext tanpa perlu prop dari App.
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+
+use crate::appearance;
+use crate::i18n::use_locale;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+impl Theme {
+    // Nilai atribut data-theme di <html>
+    pub fn attr(self) -> &'static str {
+        match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        }
+    }
+
+    pub fn opposite(self) -> Theme {
+        match self {
+            Theme::Light => Theme::Dark,
+            Theme::Dark => Theme::Light,
+        }
+    }
+
+    // Pilihan user, atau tema sistem jika belum ada
+    pub fn resolve(choice: Option<Theme>) -> Theme {
+        choice.unwrap_or_else(|| if appearance::prefers_dark() { Theme::Dark } else { Theme::Light })
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct ThemeContext {
+    pub theme: Theme,
+    pub set: Callback<Option<Theme>>, // None = kembali mengikuti sistem
+}
+
+impl ThemeContext {
+    pub fn toggle(&self) {
+        self.set.emit(Some(self.theme.opposite()));
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ThemeProviderProps {
+    pub theme: Theme,
+    pub on_change: Callback<Option<Theme>>,
+    #[prop_or_default]
+    pub children: Children,
+}
+
+#[function_component(ThemeProvider)]
+pub fn theme_provider(props: &ThemeProviderProps) -> Html {
+    let context = ThemeContext { theme: props.theme, set: props.on_change.clone() };
+    html! {
+        <ContextProvider<ThemeContext> {context}>
+            { for props.children.iter() }
+        </ContextProvider<ThemeContext>>
+    }
+}
+
+// Di luar ThemeProvider hanya tema sistem yang terbaca, dan set tidak berbuat apa-apa
+#[hook]
+pub fn use_theme() -> ThemeContext {
+    use_context::<ThemeContext>().unwrap_or_else(|| ThemeContext { theme: Theme::resolve(None), set: Callback::noop() })
+}
+
+// Tombol di header: beralih ke tema lawannya dan menyimpannya sebagai pilihan user
+#[function_component(ThemeToggle)]
+pub fn theme_toggle() -> Html {
+    let theme = use_theme();
+    let locale = use_locale();
+    let (icon, label) = match theme.theme {
+        Theme::Light => ("🌙", locale.t("Beralih ke mode gelap")),
+        Theme::Dark => ("☀️", locale.t("Beralih ke mode terang")),
+    };
+    let onclick = Callback::from(move |_| theme.toggle());
+    html! {
+        <button class="theme-toggle" title={label} aria-label={label} {onclick}>
+            { icon }
+        </button>
+    }
+}
@@ -0,0 +1,43 @@
+// src/time.rs
+// Format waktu. Timestamp pesan berupa epoch millis jam server; waktu relatif pesan dihitung terhadap
+// ServerClock::now() agar jam browser yang meleset tidak membuat pesan baru tampil "5 menit lalu".
+// Tanggal (chip dan pemisah hari) mengikuti zona waktu browser.
+use wasm_bindgen::JsValue;
+
+use crate::i18n::{self, tr, trn};
+
+// "baru saja", "5 menit lalu", "3 jam lalu", "2 hari lalu"; lebih dari seminggu tanggalnya saja
+pub fn format_relative(ms: f64, now_ms: f64) -> String {
+    let secs = ((now_ms - ms) / 1000.0).max(0.0) as u64;
+    match secs {
+        0..=59 => tr("baru saja").to_string(),
+        60..=3599 => trn("{} menit lalu", secs as usize / 60, &[]),
+        3600..=86_399 => trn("{} jam lalu", secs as usize / 3600, &[]),
+        86_400..=604_799 => trn("{} hari lalu", secs as usize / 86_400, &[]),
+        _ => format_date(ms),
+    }
+}
+
+// Tanggal dan jam lengkap sesuai bahasa antarmuka
+pub fn format_absolute(ms: f64) -> String {
+    js_sys::Date::new(&JsValue::from_f64(ms))
+        .to_locale_string(i18n::current().tag(), &JsValue::UNDEFINED)
+        .into()
+}
+
+// "12 Mei 2024" / "May 12, 2024" untuk chip tanggal dan pemisah hari
+pub fn format_date(ms: f64) -> String {
+    let options = js_sys::Object::new();
+    for (key, value) in [("day", "numeric"), ("month", "long"), ("year", "numeric")] {
+        let _ = js_sys::Reflect::set(&options, &JsValue::from_str(key), &JsValue::from_str(value));
+    }
+    js_sys::Date::new(&JsValue::from_f64(ms))
+        .to_locale_date_string(i18n::current().tag(), &options)
+        .into()
+}
+
+// Tanggal kalender yang sama menurut zona waktu browser
+pub fn same_day(a: f64, b: f64) -> bool {
+    let (a, b) = (js_sys::Date::new(&JsValue::from_f64(a)), js_sys::Date::new(&JsValue::from_f64(b)));
+    a.get_full_year() == b.get_full_year() && a.get_month() == b.get_month() && a.get_date() == b.get_date()
+}
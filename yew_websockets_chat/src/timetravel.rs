@@ -0,0 +1,81 @@
+// src/timetravel.rs
+// Debugger time-travel untuk build dengan fitur `dev`. Tiap aksi yang melewati reducer dicatat
+// bersama salinan ChatState sesudahnya; tampilan bisa dimundurkan/dimajukan ke salah satu titik
+// itu tanpa mengubah state yang sebenarnya (aksi baru tetap diproses dan dicatat di belakang).
+use std::collections::VecDeque;
+
+use crate::state::ChatState;
+
+const MAX_RECORDS: usize = 200; // Tiap catatan menyalin seluruh state, jadi jumlahnya dibatasi
+
+pub struct Record {
+    pub action: &'static str,
+    pub detail: String, // Debug aksi, dipotong
+    pub at: f64,        // Epoch millis
+    pub state: ChatState,
+}
+
+#[derive(Default)]
+pub struct TimeTravel {
+    records: VecDeque<Record>,
+    cursor: Option<usize>, // None = mengikuti state terbaru
+}
+
+impl TimeTravel {
+    pub fn record(&mut self, action: &'static str, detail: String, state: &ChatState) {
+        let detail = match detail.char_indices().nth(160) {
+            Some((cut, _)) => format!("{}…", &detail[..cut]),
+            None => detail,
+        };
+        self.records.push_back(Record { action, detail, at: js_sys::Date::now(), state: state.clone() });
+        if self.records.len() > MAX_RECORDS {
+            self.records.pop_front();
+            self.cursor = self.cursor.map(|i| i.saturating_sub(1));
+        }
+    }
+
+    // State yang sedang ditinjau, None jika sedang live
+    pub fn snapshot(&self) -> Option<&ChatState> {
+        self.cursor.and_then(|i| self.records.get(i)).map(|record| &record.state)
+    }
+
+    pub fn records(&self) -> impl Iterator<Item = &Record> {
+        self.records.iter()
+    }
+
+    pub fn cursor(&self) -> Option<usize> {
+        self.cursor
+    }
+
+    pub fn is_live(&self) -> bool {
+        self.cursor.is_none()
+    }
+
+    pub fn step_back(&mut self) -> bool {
+        // Saat live, state sama dengan catatan terakhir; mundur berarti ke catatan sebelumnya
+        let current = self.cursor.unwrap_or(self.records.len().saturating_sub(1));
+        if current == 0 {
+            return false;
+        }
+        self.cursor = Some(current - 1);
+        true
+    }
+
+    pub fn step_forward(&mut self) -> bool {
+        let Some(current) = self.cursor else { return false };
+        self.cursor = (current + 2 < self.records.len()).then_some(current + 1);
+        true
+    }
+
+    pub fn jump(&mut self, index: usize) -> bool {
+        if index >= self.records.len() {
+            return false;
+        }
+        self.cursor = (index + 1 < self.records.len()).then_some(index);
+        true
+    }
+
+    pub fn go_live(&mut self) -> bool {
+        self.cursor.take().is_some()
+    }
+}
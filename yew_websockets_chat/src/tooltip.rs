@@ -0,0 +1,53 @@
+// src/tooltip.rs
+use std::cell::Cell;
+
+use yew::prelude::*;
+
+thread_local! {
+    static NEXT_ID: Cell<usize> = const { Cell::new(0) };
+}
+
+#[derive(Properties, PartialEq)]
+pub struct TooltipProps {
+    pub content: Html, // Isi tooltip, boleh berupa markup
+    pub children: Children,
+}
+
+// Tooltip yang muncul saat elemen di-hover atau mendapat fokus keyboard.
+// Isi tooltip baru dirender saat terlihat, jadi aman dipakai untuk setiap pesan.
+#[function_component(Tooltip)]
+pub fn tooltip(props: &TooltipProps) -> Html {
+    let visible = use_state(|| false);
+    let id = use_state(|| {
+        NEXT_ID.with(|next| {
+            let id = next.get();
+            next.set(id + 1);
+            format!("tooltip-{}", id)
+        })
+    });
+    let show = {
+        let visible = visible.clone();
+        Callback::from(move |()| visible.set(true))
+    };
+    let hide = {
+        let visible = visible.clone();
+        Callback::from(move |()| visible.set(false))
+    };
+
+    html! {
+        <span
+            class="tooltip-anchor"
+            tabindex="0"
+            aria-describedby={visible.then(|| (*id).clone())}
+            onmouseenter={show.reform(|_: MouseEvent| ())}
+            onmouseleave={hide.reform(|_: MouseEvent| ())}
+            onfocus={show.reform(|_: FocusEvent| ())}
+            onblur={hide.reform(|_: FocusEvent| ())}
+        >
+            { for props.children.iter() }
+            if *visible {
+                <span class="tooltip" role="tooltip" id={(*id).clone()}>{ props.content.clone() }</span>
+            }
+        </span>
+    }
+}
@@ -0,0 +1,125 @@
+// src/tour.rs
+use web_sys::Element;
+use yew::prelude::*;
+
+use crate::i18n::{tr, Locale};
+use crate::storage;
+
+const TOUR_DONE_KEY: &str = "webchat.tour_done";
+
+// Satu langkah tur: elemen yang disorot beserta penjelasannya
+#[derive(Clone, PartialEq)]
+pub struct TourStep {
+    pub target: NodeRef,
+    pub title: &'static str,
+    pub body: &'static str,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct TourProps {
+    pub steps: Vec<TourStep>,
+    pub on_finish: Callback<()>, // Dipanggil saat tur selesai atau dilewati
+}
+
+pub enum TourMsg {
+    Next,
+    Back,
+    Reflow, // Hitung ulang posisi sorotan setelah elemen target ter-render (juga saat bahasa diganti)
+}
+
+// Overlay tur pengenalan untuk pengguna baru. Posisi sorotan diambil dari NodeRef milik komponen induk.
+pub struct Tour {
+    step: usize,
+    _locale: Option<ContextHandle<Locale>>,
+}
+
+impl Component for Tour {
+    type Message = TourMsg;
+    type Properties = TourProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let locale = ctx.link().context::<Locale>(ctx.link().callback(|_| TourMsg::Reflow));
+        Self { step: 0, _locale: locale.map(|(_, handle)| handle) }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            TourMsg::Next => {
+                if self.step + 1 >= ctx.props().steps.len() {
+                    ctx.props().on_finish.emit(());
+                    return false;
+                }
+                self.step += 1;
+                true
+            }
+            TourMsg::Back => {
+                self.step = self.step.saturating_sub(1);
+                true
+            }
+            TourMsg::Reflow => true,
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let steps = &ctx.props().steps;
+        let Some(step) = steps.get(self.step) else { return html! {} };
+        let link = ctx.link();
+        let on_skip = ctx.props().on_finish.reform(|_| ());
+        let is_last = self.step + 1 == steps.len();
+
+        // Kotak sorotan mengikuti posisi elemen target di viewport
+        let highlight = step.target.cast::<Element>().map(|el| {
+            let rect = el.get_bounding_client_rect();
+            format!(
+                "top:{}px; left:{}px; width:{}px; height:{}px;",
+                rect.top() - 4.0,
+                rect.left() - 4.0,
+                rect.width() + 8.0,
+                rect.height() + 8.0
+            )
+        });
+        let card_style = step.target.cast::<Element>().map(|el| {
+            let rect = el.get_bounding_client_rect();
+            format!("top:{}px; left:{}px;", rect.bottom() + 12.0, rect.left().max(8.0))
+        });
+
+        html! {
+            <div class="tour-overlay">
+                if let Some(style) = highlight {
+                    <div class="tour-highlight" {style}></div>
+                }
+                <div class="tour-card" style={card_style}>
+                    <p class="tour-progress">{ format!("{} / {}", self.step + 1, steps.len()) }</p>
+                    <h3>{ tr(step.title) }</h3>
+                    <p>{ tr(step.body) }</p>
+                    <div class="tour-actions">
+                        <button class="tour-skip" onclick={on_skip}>{ tr("Lewati") }</button>
+                        if self.step > 0 {
+                            <button onclick={link.callback(|_| TourMsg::Back)}>{ tr("Kembali") }</button>
+                        }
+                        <button onclick={link.callback(|_| TourMsg::Next)}>{ tr(if is_last { "Selesai" } else { "Lanjut" }) }</button>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        if let Some(el) = ctx.props().steps.get(self.step).and_then(|step| step.target.cast::<Element>()) {
+            el.scroll_into_view_with_bool(false);
+        }
+        if first_render {
+            // Elemen target milik induk baru terpasang setelah render pertama induk selesai
+            ctx.link().send_message(TourMsg::Reflow);
+        }
+    }
+}
+
+// Tur hanya ditampilkan sekali per browser
+pub fn tour_completed() -> bool {
+    storage::get(TOUR_DONE_KEY).is_some()
+}
+
+pub fn mark_tour_completed() {
+    storage::set(TOUR_DONE_KEY, "1");
+}
@@ -0,0 +1,96 @@
+// src/translate.rs
+// Terjemahan pesan di room yang bahasa utamanya berbeda dari bahasa user. Penerjemahan dilakukan
+// server (ClientCommand::Translate); klien hanya menyimpan hasilnya per id pesan, dan pesan bisa
+// dikembalikan ke teks asli kapan saja. Terjemahan otomatis diaktifkan per room lewat Settings.
+use std::collections::{HashMap, HashSet};
+
+// Pilihan bahasa room dan bahasa user; kode mengikuti subtag utama BCP 47
+pub const LANGUAGES: [(&str, &str); 8] = [
+    ("id", "Bahasa Indonesia"),
+    ("en", "English"),
+    ("ms", "Bahasa Melayu"),
+    ("jv", "Basa Jawa"),
+    ("ar", "العربية"),
+    ("zh", "中文"),
+    ("ja", "日本語"),
+    ("es", "Español"),
+];
+
+const DEFAULT_LANGUAGE: &str = "id"; // Bahasa antarmuka aplikasi ini
+pub const AUTO_TRANSLATE_BACKLOG: usize = 30; // Pesan terakhir yang ikut diterjemahkan saat terjemahan otomatis dinyalakan
+
+pub fn language_name(code: &str) -> &str {
+    LANGUAGES.iter().find(|(known, _)| *known == code).map_or(code, |(_, name)| *name)
+}
+
+// "en-US" -> "en"
+pub fn primary_subtag(tag: &str) -> String {
+    tag.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase()
+}
+
+// Bahasa user jika belum dipilih di pengaturan: bahasa browser
+pub fn browser_language() -> String {
+    web_sys::window()
+        .and_then(|w| w.navigator().language())
+        .map(|tag| primary_subtag(&tag))
+        .filter(|code| !code.is_empty())
+        .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Translation {
+    Pending,
+    Done { text: String, target: String },
+    Failed(String),
+}
+
+#[derive(Debug, Default)]
+pub struct Translations {
+    entries: HashMap<String, Translation>, // Per id pesan
+    originals: HashSet<String>, // Pesan terjemahan yang sedang ditampilkan dalam teks aslinya
+}
+
+impl Translations {
+    pub fn get(&self, message_id: &str) -> Option<&Translation> {
+        self.entries.get(message_id)
+    }
+
+    // Tandai permintaan baru; false jika pesan ini sudah diterjemahkan ke `target` atau sedang menunggu
+    pub fn request(&mut self, message_id: &str, target: &str) -> bool {
+        match self.entries.get(message_id) {
+            Some(Translation::Pending) => false,
+            Some(Translation::Done { target: done, .. }) if done == target => {
+                self.originals.remove(message_id);
+                false
+            }
+            _ => {
+                self.entries.insert(message_id.to_string(), Translation::Pending);
+                true
+            }
+        }
+    }
+
+    pub fn resolve(&mut self, message_id: String, target: String, text: String) {
+        self.originals.remove(&message_id);
+        self.entries.insert(message_id, Translation::Done { text, target });
+    }
+
+    pub fn fail(&mut self, message_id: String, reason: String) {
+        self.entries.insert(message_id, Translation::Failed(reason));
+    }
+
+    // Permintaan yang gagal terkirim tidak dibiarkan menunggu selamanya
+    pub fn cancel(&mut self, message_id: &str) {
+        if self.entries.get(message_id) == Some(&Translation::Pending) {
+            self.entries.remove(message_id);
+        }
+    }
+
+    pub fn showing_original(&self, message_id: &str) -> bool {
+        self.originals.contains(message_id)
+    }
+
+    pub fn show_original(&mut self, message_id: String) {
+        self.originals.insert(message_id);
+    }
+}
@@ -0,0 +1,202 @@
+// src/twofactor.rs
+// Langkah UI untuk autentikasi dua faktor (TOTP). Rahasia dan kode pemulihan dibuat oleh
+// server; klien hanya menampilkan QR, meneruskan kode dari aplikasi authenticator, dan
+// menampilkan hasil verifikasi.
+use base64::Engine as _;
+use qrcode::render::svg;
+use qrcode::QrCode;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::i18n::use_locale;
+
+// Kode yang dimasukkan user saat login
+#[derive(Debug, Clone, PartialEq)]
+pub enum TwoFactorCode {
+    Totp(String),
+    Recovery(String),
+}
+
+impl TwoFactorCode {
+    // Pisahkan ke field `code` / `recovery_code` milik perintah protokol
+    pub fn into_fields(self) -> (Option<String>, Option<String>) {
+        match self {
+            TwoFactorCode::Totp(code) => (Some(code), None),
+            TwoFactorCode::Recovery(code) => (None, Some(code)),
+        }
+    }
+}
+
+// Langkah 2FA yang sedang ditampilkan
+#[derive(Debug, Clone, PartialEq)]
+pub enum TwoFactorStep {
+    Enrolling(Enrollment),
+    Disabling,
+    Challenge, // Server meminta kode sebelum sesi ini boleh dipakai
+}
+
+// Data pendaftaran 2FA dari server
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enrollment {
+    pub secret: String,      // Base32, untuk dimasukkan manual jika QR tidak bisa dipindai
+    pub otpauth_url: String, // otpauth://totp/... untuk QR
+    pub recovery_codes: Vec<String>,
+}
+
+// QR sebagai data URL SVG, supaya tidak perlu mengirim gambar dari server
+fn qr_data_url(content: &str) -> Option<String> {
+    let code = QrCode::new(content.as_bytes()).ok()?;
+    let svg = code.render::<svg::Color>().min_dimensions(180, 180).build();
+    Some(format!("data:image/svg+xml;base64,{}", base64::engine::general_purpose::STANDARD.encode(svg)))
+}
+
+// Kode TOTP selalu 6 digit; spasi dari tampilan authenticator diabaikan
+fn normalize_totp(input: &str) -> Option<String> {
+    let digits: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+    (digits.len() == 6 && digits.chars().all(|c| c.is_ascii_digit())).then_some(digits)
+}
+
+#[derive(Properties, PartialEq)]
+pub struct TwoFactorSetupProps {
+    pub enrollment: Enrollment,
+    pub error: Option<String>,
+    pub on_confirm: Callback<String>, // Kode TOTP pertama untuk membuktikan authenticator sudah tersetel
+    pub on_cancel: Callback<()>,
+}
+
+#[function_component(TwoFactorSetup)]
+pub fn two_factor_setup(props: &TwoFactorSetupProps) -> Html {
+    let locale = use_locale();
+    let code = use_state(String::new);
+    let enrollment = &props.enrollment;
+    let valid = normalize_totp(&code).is_some();
+
+    let on_input = {
+        let code = code.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            code.set(input.value());
+        })
+    };
+    let on_submit = {
+        let code = code.clone();
+        let on_confirm = props.on_confirm.clone();
+        Callback::from(move |e: FocusEvent| {
+            e.prevent_default();
+            if let Some(totp) = normalize_totp(&code) {
+                on_confirm.emit(totp);
+            }
+        })
+    };
+
+    html! {
+        <div class="modal-backdrop">
+            <form class="modal two-factor" onsubmit={on_submit}>
+                <h3>{ locale.t("Aktifkan verifikasi dua langkah") }</h3>
+                <p>{ locale.t("1. Pindai kode QR ini dengan aplikasi authenticator (Google Authenticator, Aegis, 1Password, dll.).") }</p>
+                if let Some(src) = qr_data_url(&enrollment.otpauth_url) {
+                    <img class="two-factor-qr" {src} alt={locale.t("Kode QR 2FA")} />
+                }
+                <p class="settings-hint">{ locale.t("Atau masukkan kunci ini secara manual:") }</p>
+                <code class="two-factor-secret">{ &enrollment.secret }</code>
+                <p>{ locale.t("2. Simpan kode pemulihan berikut di tempat aman. Tiap kode hanya bisa dipakai sekali jika ponsel Anda hilang.") }</p>
+                <ul class="recovery-codes">
+                    { for enrollment.recovery_codes.iter().map(|code| html! { <li><code>{ code }</code></li> }) }
+                </ul>
+                <p>{ locale.t("3. Masukkan kode 6 digit dari aplikasi untuk menyelesaikan.") }</p>
+                <input type="text" inputmode="numeric" autocomplete="one-time-code" placeholder="123456" value={(*code).clone()} oninput={on_input} />
+                if let Some(err) = &props.error {
+                    <p class="field-error">{ err }</p>
+                }
+                <div class="tour-actions">
+                    <button type="button" class="tour-skip" onclick={props.on_cancel.reform(|_| ())}>{ locale.t("Batal") }</button>
+                    <button type="submit" disabled={!valid}>{ locale.t("Aktifkan") }</button>
+                </div>
+            </form>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct TwoFactorPromptProps {
+    pub title: AttrValue,
+    pub error: Option<String>,
+    pub allow_recovery: bool, // Izinkan kode pemulihan sebagai ganti kode TOTP
+    pub on_submit: Callback<TwoFactorCode>,
+    #[prop_or_default]
+    pub on_cancel: Option<Callback<()>>, // None: prompt wajib diisi (mis. saat login)
+}
+
+// Prompt kode 2FA, dipakai saat login dan saat mematikan 2FA
+#[function_component(TwoFactorPrompt)]
+pub fn two_factor_prompt(props: &TwoFactorPromptProps) -> Html {
+    let locale = use_locale();
+    let code = use_state(String::new);
+    let use_recovery = use_state(|| false);
+    let valid = if *use_recovery { !code.trim().is_empty() } else { normalize_totp(&code).is_some() };
+
+    let on_input = {
+        let code = code.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            code.set(input.value());
+        })
+    };
+    let on_submit = {
+        let code = code.clone();
+        let use_recovery = use_recovery.clone();
+        let on_submit = props.on_submit.clone();
+        Callback::from(move |e: FocusEvent| {
+            e.prevent_default();
+            let submitted = if *use_recovery {
+                Some(TwoFactorCode::Recovery(code.trim().to_string()))
+            } else {
+                normalize_totp(&code).map(TwoFactorCode::Totp)
+            };
+            if let Some(submitted) = submitted {
+                on_submit.emit(submitted);
+                code.set(String::new());
+            }
+        })
+    };
+    let on_toggle_recovery = {
+        let code = code.clone();
+        let use_recovery = use_recovery.clone();
+        Callback::from(move |_| {
+            use_recovery.set(!*use_recovery);
+            code.set(String::new());
+        })
+    };
+
+    html! {
+        <div class="modal-backdrop">
+            <form class="modal two-factor" onsubmit={on_submit}>
+                <h3>{ props.title.clone() }</h3>
+                <p>
+                    { locale.t(if *use_recovery { "Masukkan salah satu kode pemulihan Anda." } else { "Masukkan kode 6 digit dari aplikasi authenticator." }) }
+                </p>
+                <input
+                    type="text"
+                    inputmode={if *use_recovery { "text" } else { "numeric" }}
+                    autocomplete="one-time-code"
+                    value={(*code).clone()}
+                    oninput={on_input}
+                />
+                if let Some(err) = &props.error {
+                    <p class="field-error">{ err }</p>
+                }
+                if props.allow_recovery {
+                    <button type="button" class="link-button" onclick={on_toggle_recovery}>
+                        { locale.t(if *use_recovery { "Pakai kode authenticator" } else { "Pakai kode pemulihan" }) }
+                    </button>
+                }
+                <div class="tour-actions">
+                    if let Some(on_cancel) = &props.on_cancel {
+                        <button type="button" class="tour-skip" onclick={on_cancel.reform(|_| ())}>{ locale.t("Batal") }</button>
+                    }
+                    <button type="submit" disabled={!valid}>{ locale.t("Verifikasi") }</button>
+                </div>
+            </form>
+        </div>
+    }
+}
@@ -0,0 +1,201 @@
+// src/typing.rs
+// Indikator "sedang mengetik". Server meneruskan ServerEvent::Typing selama user lain masih mengetik;
+// entry hilang sendiri setelah TYPING_TTL_MS tanpa event baru atau saat pesannya masuk. Di akun bersama
+// (Capability::Operators) tiap operator dicatat terpisah, termasuk operator lain di akun user ini sendiri.
+// Waktu di sini memakai jam lokal (Date::now), karena TTL dihitung dari saat event diterima. Entry juga
+// mencatat sejak kapan user mengetik tanpa putus, untuk hint "menulis pesan panjang" setelah LONG_TYPING_MS,
+// dan dihapus begitu presence melaporkan user itu keluar dari room atau offline.
+use crate::i18n::Locale;
+use crate::protocol::{Operator, TYPING_TTL_MS};
+
+pub const SEND_INTERVAL_MS: f64 = 3_000.0; // Jarak minimal antar ClientCommand::Typing, jauh di bawah TTL
+pub const LONG_TYPING_MS: f64 = 15_000.0; // Mengetik selama ini tanpa putus dianggap menulis pesan panjang
+
+struct Typist {
+    room: String,
+    user_id: String,
+    name: String,
+    operator: Option<Operator>,
+    since: f64, // Typing pertama dari rangkaian yang belum pernah terputus melewati TTL
+    until: f64,
+}
+
+impl Typist {
+    fn label(&self) -> String {
+        match &self.operator {
+            Some(operator) => format!("{} ({})", self.name, operator.name),
+            None => self.name.clone(),
+        }
+    }
+
+    fn is_long(&self, now: f64) -> bool {
+        now - self.since >= LONG_TYPING_MS
+    }
+}
+
+#[derive(Default)]
+pub struct TypingIndicators {
+    typists: Vec<Typist>,
+    last_sent: Option<(String, f64)>, // Room dan waktu Typing terakhir yang dikirim user ini
+}
+
+impl TypingIndicators {
+    pub fn record(&mut self, room: String, user_id: String, name: String, operator: Option<Operator>, now: f64) {
+        let until = now + TYPING_TTL_MS;
+        let same = |typist: &&mut Typist| typist.room == room && typist.user_id == user_id && typist.operator.as_ref().map(|o| &o.name) == operator.as_ref().map(|o| &o.name);
+        match self.typists.iter_mut().find(same) {
+            Some(typist) => {
+                typist.until = until;
+                typist.name = name;
+            }
+            None => self.typists.push(Typist { room, user_id, name, operator, since: now, until }),
+        }
+    }
+
+    // Pesan dari user ini masuk; operator yang mengirimnya berhenti mengetik. Tanpa operator semua entry user dihapus.
+    pub fn stopped(&mut self, room: &str, user_id: &str, operator: Option<&Operator>) -> bool {
+        let before = self.typists.len();
+        self.typists.retain(|typist| {
+            typist.room != room || typist.user_id != user_id || operator.is_some_and(|operator| typist.operator.as_ref().is_some_and(|o| o.name != operator.name))
+        });
+        before != self.typists.len()
+    }
+
+    // true jika ada entry yang hilang
+    pub fn expire(&mut self, now: f64) -> bool {
+        let before = self.typists.len();
+        self.typists.retain(|typist| typist.until > now);
+        before != self.typists.len()
+    }
+
+    // User keluar dari room (None = offline di semua room); indikatornya tidak perlu menunggu TTL
+    pub fn left(&mut self, room: Option<&str>, user_id: &str) -> bool {
+        let before = self.typists.len();
+        self.typists.retain(|typist| typist.user_id != user_id || room.is_some_and(|room| typist.room != room));
+        before != self.typists.len()
+    }
+
+    // Saat berikutnya label bisa berubah sendiri: entry kedaluwarsa atau mulai dianggap pesan panjang
+    pub fn next_change(&self, now: f64) -> Option<f64> {
+        self.typists
+            .iter()
+            .flat_map(|typist| [Some(typist.until), (!typist.is_long(now)).then_some(typist.since + LONG_TYPING_MS)])
+            .flatten()
+            .min_by(f64::total_cmp)
+    }
+
+    pub fn clear(&mut self) {
+        self.typists.clear();
+        self.last_sent = None;
+    }
+
+    // "Budi (Sari) sedang mengetik…", atau "sedang menulis pesan panjang…" setelah LONG_TYPING_MS; lebih dari
+    // dua orang hanya jumlahnya. None jika tidak ada yang mengetik di room ini
+    pub fn label(&self, locale: Locale, room: &str, now: f64) -> Option<String> {
+        let typists: Vec<&Typist> = self.typists.iter().filter(|typist| typist.room == room).collect();
+        match typists.as_slice() {
+            [] => None,
+            [one] if one.is_long(now) => Some(locale.f("{} sedang menulis pesan panjang…", &[&one.label()])),
+            [one] => Some(locale.f("{} sedang mengetik…", &[&one.label()])),
+            [first, second] => Some(locale.f("{} dan {} sedang mengetik…", &[&first.label(), &second.label()])),
+            many => Some(locale.n("{} orang sedang mengetik…", many.len(), &[])),
+        }
+    }
+
+    // Boleh mengirim Typing untuk room ini sekarang? Mencatat waktunya jika ya.
+    pub fn should_send(&mut self, room: &str, now: f64) -> bool {
+        if self.last_sent.as_ref().is_some_and(|(sent_room, at)| sent_room == room && now - at < SEND_INTERVAL_MS) {
+            return false;
+        }
+        self.last_sent = Some((room.to_string(), now));
+        true
+    }
+
+    // Pesan terkirim: ketikan berikutnya langsung diumumkan lagi
+    pub fn sent_message(&mut self) {
+        self.last_sent = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typing(indicators: &mut TypingIndicators, room: &str, user: &str, now: f64) {
+        indicators.record(room.to_string(), user.to_string(), user.to_string(), None, now);
+    }
+
+    fn operator(name: &str) -> Option<Operator> {
+        Some(Operator { name: name.to_string(), initials: name[..1].to_string() })
+    }
+
+    #[test]
+    fn label_names_typists_in_room_and_turns_long() {
+        let mut indicators = TypingIndicators::default();
+        assert_eq!(indicators.label(Locale::Id, "a", 0.0), None);
+        typing(&mut indicators, "a", "budi", 0.0);
+        typing(&mut indicators, "b", "ani", 0.0);
+        assert_eq!(indicators.label(Locale::Id, "a", 0.0).as_deref(), Some("budi sedang mengetik…"));
+        // Mengetik tanpa putus: `since` tetap dari event pertama
+        let mut now = 0.0;
+        while now < LONG_TYPING_MS {
+            now += SEND_INTERVAL_MS;
+            typing(&mut indicators, "a", "budi", now);
+        }
+        assert_eq!(indicators.label(Locale::Id, "a", now).as_deref(), Some("budi sedang menulis pesan panjang…"));
+        indicators.record("a".to_string(), "toko".to_string(), "Toko".to_string(), operator("Sari"), now);
+        assert_eq!(indicators.label(Locale::Id, "a", now).as_deref(), Some("budi dan Toko (Sari) sedang mengetik…"));
+        typing(&mut indicators, "a", "cici", now);
+        assert_eq!(indicators.label(Locale::Id, "a", now).as_deref(), Some("3 orang sedang mengetik…"));
+        assert_eq!(indicators.label(Locale::En, "a", now).as_deref(), Some("3 people are typing…"));
+    }
+
+    #[test]
+    fn left_drops_typist_from_one_room_or_everywhere() {
+        let mut indicators = TypingIndicators::default();
+        typing(&mut indicators, "a", "budi", 0.0);
+        typing(&mut indicators, "b", "budi", 0.0);
+        typing(&mut indicators, "a", "ani", 0.0);
+        assert!(indicators.left(Some("a"), "budi"));
+        assert!(!indicators.left(Some("a"), "budi"));
+        assert_eq!(indicators.typists.len(), 2);
+        assert!(indicators.left(None, "budi")); // Offline: semua room
+        assert_eq!(indicators.typists.iter().map(|typist| typist.user_id.as_str()).collect::<Vec<_>>(), ["ani"]);
+    }
+
+    #[test]
+    fn stopped_only_clears_sending_operator() {
+        let mut indicators = TypingIndicators::default();
+        indicators.record("a".to_string(), "toko".to_string(), "Toko".to_string(), operator("Sari"), 0.0);
+        indicators.record("a".to_string(), "toko".to_string(), "Toko".to_string(), operator("Dedi"), 0.0);
+        assert!(indicators.stopped("a", "toko", operator("Sari").as_ref()));
+        assert_eq!(indicators.typists.len(), 1);
+        assert!(indicators.stopped("a", "toko", None));
+        assert!(indicators.typists.is_empty());
+    }
+
+    #[test]
+    fn next_change_is_earliest_expiry_or_long_threshold() {
+        let mut indicators = TypingIndicators::default();
+        assert_eq!(indicators.next_change(0.0), None);
+        typing(&mut indicators, "a", "budi", 0.0);
+        assert_eq!(indicators.next_change(0.0), Some(TYPING_TTL_MS));
+        let now = LONG_TYPING_MS - 1_000.0;
+        typing(&mut indicators, "a", "budi", now);
+        assert_eq!(indicators.next_change(now), Some(LONG_TYPING_MS)); // Sebelum TTL habis
+        typing(&mut indicators, "a", "budi", LONG_TYPING_MS);
+        assert_eq!(indicators.next_change(LONG_TYPING_MS), Some(LONG_TYPING_MS + TYPING_TTL_MS)); // Sudah panjang: tinggal TTL
+        assert!(indicators.expire(LONG_TYPING_MS + TYPING_TTL_MS));
+        assert_eq!(indicators.next_change(LONG_TYPING_MS + TYPING_TTL_MS), None);
+    }
+
+    #[test]
+    fn typing_is_throttled_per_room_until_message_sent() {
+        let mut indicators = TypingIndicators::default();
+        assert!(indicators.should_send("a", 0.0));
+        assert!(!indicators.should_send("a", SEND_INTERVAL_MS - 1.0));
+        assert!(indicators.should_send("b", 1.0)); // Room lain langsung diumumkan
+        indicators.sent_message();
+        assert!(indicators.should_send("b", 2.0));
+    }
+}
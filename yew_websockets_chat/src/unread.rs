@@ -0,0 +1,92 @@
+// src/unread.rs
+// Pesan yang belum dilihat user. ScrollFollow menjaga daftar pesan tetap di bawah selama user memang
+// berada di bawah, dan menghitung entry baru saat user menggulir ke atas (tombol "N pesan baru").
+// UnreadTitle menambahkan jumlah pesan belum dibaca di judul tab selama tab tidak fokus; judul asli
+// milik halaman embedder dipulihkan begitu tab fokus lagi.
+use web_sys::{Element, ScrollBehavior, ScrollToOptions};
+
+const BOTTOM_SLACK_PX: i32 = 48; // Masih dianggap di bawah dalam jarak ini, mis. saat gambar selesai dimuat
+
+#[derive(Default)]
+pub struct ScrollFollow {
+    detached: bool, // User menggulir ke atas; pesan baru tidak menarik daftar ke bawah
+    seen: usize,    // Jumlah entry saat user terakhir berada di bawah
+}
+
+impl ScrollFollow {
+    // Dari event scroll ul.messages. true jika tombol "pesan baru" perlu dirender ulang.
+    pub fn on_scroll(&mut self, list: &Element, entries: usize) -> bool {
+        let at_bottom = list.scroll_height() - list.scroll_top() - list.client_height() <= BOTTOM_SLACK_PX;
+        let was_showing = self.new_below(entries) > 0;
+        self.detached = !at_bottom;
+        if at_bottom {
+            self.seen = entries;
+        }
+        was_showing != (self.new_below(entries) > 0)
+    }
+
+    // Setelah render: gulir ke entry terbaru jika user mengikuti dan ada entry baru.
+    // Tanpa animasi, karena posisi tengah animasi akan terbaca sebagai user menggulir ke atas.
+    pub fn follow(&mut self, list: &Element, entries: usize) {
+        if self.detached || entries <= self.seen {
+            return;
+        }
+        let options = ScrollToOptions::new();
+        options.set_top(f64::from(list.scroll_height()));
+        options.set_behavior(ScrollBehavior::Instant);
+        list.scroll_to_with_scroll_to_options(&options);
+        self.seen = entries;
+    }
+
+    // Kembali mengikuti entry terbaru, mis. setelah user mengirim pesan atau berpindah room
+    pub fn stick(&mut self) {
+        self.detached = false;
+        self.seen = 0; // Render berikutnya pasti menggulir
+    }
+
+    // User melompat ke entry tertentu; jangan tarik kembali ke bawah
+    pub fn detach(&mut self) {
+        self.detached = true;
+    }
+
+    // Entry lama disisipkan di awal daftar; yang sudah dilihat ikut bergeser
+    pub fn prepended(&mut self, count: usize) {
+        if self.detached {
+            self.seen += count;
+        }
+    }
+
+    pub fn following(&self) -> bool {
+        !self.detached
+    }
+
+    pub fn new_below(&self, entries: usize) -> usize {
+        if self.detached {
+            entries.saturating_sub(self.seen)
+        } else {
+            0
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct UnreadTitle {
+    base: Option<String>, // Judul sebelum hitungan ditambahkan; None = judul tidak sedang diubah
+    count: usize,
+}
+
+impl UnreadTitle {
+    pub fn bump(&mut self) {
+        let Some(document) = web_sys::window().and_then(|w| w.document()) else { return };
+        let base = self.base.get_or_insert_with(|| document.title());
+        self.count += 1;
+        document.set_title(&format!("({}) {}", self.count, base));
+    }
+
+    pub fn clear(&mut self) {
+        self.count = 0;
+        if let (Some(base), Some(document)) = (self.base.take(), web_sys::window().and_then(|w| w.document())) {
+            document.set_title(&base);
+        }
+    }
+}
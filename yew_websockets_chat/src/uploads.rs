@@ -0,0 +1,281 @@
+// src/uploads.rs
+// Upload lampiran secara bertahap (chunk) dengan protokol yang bisa dilanjutkan, mirip tus:
+// server selalu membalas offset byte terakhir yang sudah diterima, sehingga setelah koneksi
+// terputus klien cukup menanyakan offset itu lalu melanjutkan dari sana.
+use gloo_file::Blob;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{DragEvent, Node};
+
+use crate::i18n::{tr, trf};
+use crate::protocol::{ClientCommand, UploadRejection};
+pub use crate::protocol::format_size;
+use crate::settings::Settings;
+use crate::{exif, imaging};
+
+pub const CHUNK_SIZE: u64 = 64 * 1024;
+pub const MAX_VIDEO_SIZE: u64 = 50 * 1024 * 1024;
+pub const VIDEO_TYPES: [&str; 3] = ["video/mp4", "video/webm", "video/ogg"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UploadStatus {
+    Uploading,
+    Paused, // Koneksi terputus; dilanjutkan otomatis saat tersambung lagi
+    Failed(String),            // Gagal di sisi klien (mis. file tidak bisa dibaca)
+    Rejected(UploadRejection), // Ditolak validasi server
+}
+
+// File yang siap di-upload, bisa berupa file asli atau hasil kompresi
+pub struct PreparedFile {
+    pub blob: Blob,
+    pub name: String,
+    pub mime: String,
+    pub original_size: Option<u64>, // Diisi jika file sudah dikompres di browser
+    pub metadata_stripped: bool,     // EXIF/GPS sudah dibuang sebelum file meninggalkan browser
+    pub poster: Option<String>,      // Thumbnail video (data URL JPEG kecil)
+}
+
+// Tolak file yang melanggar batas sebelum apa pun dibaca atau dikirim
+pub fn check_limits(file: &web_sys::File) -> Result<(), String> {
+    let mime = file.type_();
+    if mime.starts_with("video/") {
+        if !VIDEO_TYPES.contains(&mime.as_str()) {
+            return Err(trf("Format video {} tidak didukung (gunakan MP4, WebM atau Ogg).", &[&mime]));
+        }
+        if file.size() as u64 > MAX_VIDEO_SIZE {
+            return Err(trf("Video terlalu besar; maksimal {}.", &[&format_size(MAX_VIDEO_SIZE)]));
+        }
+    }
+    Ok(())
+}
+
+impl From<web_sys::File> for PreparedFile {
+    fn from(file: web_sys::File) -> Self {
+        Self {
+            name: file.name(),
+            mime: file.type_(),
+            blob: Blob::from(file),
+            original_size: None,
+            metadata_stripped: false,
+            poster: None,
+        }
+    }
+}
+
+// Siapkan file untuk di-upload sesuai pengaturan: kompres gambar dan/atau buang metadatanya
+pub async fn prepare(file: web_sys::File, settings: &Settings) -> PreparedFile {
+    let mime = file.type_();
+    if VIDEO_TYPES.contains(&mime.as_str()) {
+        let poster = match imaging::video_poster(&file).await {
+            Ok(poster) => Some(poster),
+            Err(e) => {
+                log::warn!("Gagal membuat thumbnail video: {}", e);
+                None
+            }
+        };
+        return PreparedFile { poster, ..PreparedFile::from(file) };
+    }
+    if settings.compress_images && imaging::is_compressible(&mime) {
+        match imaging::compress_image(&file, settings.max_image_dimension, settings.image_quality).await {
+            // Hasil kompresi hanya dipakai jika memang lebih kecil; encode ulang lewat canvas
+            // otomatis membuang semua metadata
+            Ok(compressed) if (compressed.blob.size() as f64) < file.size() => {
+                return PreparedFile {
+                    original_size: Some(file.size() as u64),
+                    blob: compressed.blob,
+                    name: compressed.name,
+                    mime: compressed.mime,
+                    metadata_stripped: true,
+                    poster: None,
+                };
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Kompresi gambar gagal, mengirim file asli: {}", e),
+        }
+    }
+    if settings.strip_image_metadata && exif::is_supported(&mime) {
+        let blob = Blob::from(file.clone());
+        match gloo_file::futures::read_as_bytes(&blob).await {
+            Ok(bytes) => match exif::strip_metadata(&mime, &bytes) {
+                Some(stripped) => {
+                    return PreparedFile {
+                        blob: Blob::new_with_options(stripped.as_slice(), Some(&mime)),
+                        name: file.name(),
+                        mime,
+                        original_size: None,
+                        metadata_stripped: true,
+                        poster: None,
+                    };
+                }
+                None => log::warn!("Format {} tidak dikenali, metadata tidak dihapus", mime),
+            },
+            Err(e) => log::warn!("Gagal membaca file untuk menghapus metadata: {}", e),
+        }
+    }
+    PreparedFile::from(file)
+}
+
+pub struct Upload {
+    pub id: String,
+    pub blob: Blob,
+    pub name: String,
+    pub mime: String,
+    pub size: u64,
+    pub original_size: Option<u64>,
+    pub metadata_stripped: bool,
+    pub poster: Option<String>,
+    pub acked: u64,      // Jumlah byte yang sudah dikonfirmasi server
+    pub in_flight: bool, // Ada chunk yang sedang dibaca/dikirim dan belum di-ack
+    pub binary: bool,    // Server menerima chunk sebagai UploadChunkFrame biner (lihat UploadOffset)
+    pub status: UploadStatus,
+}
+
+impl Upload {
+    pub fn total_chunks(&self) -> u64 {
+        self.size.div_ceil(CHUNK_SIZE).max(1)
+    }
+
+    pub fn acked_chunks(&self) -> u64 {
+        self.acked.div_ceil(CHUNK_SIZE)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.acked >= self.size
+    }
+
+    // Upload gagal yang masih layak dicoba lagi
+    pub fn can_retry(&self) -> bool {
+        match &self.status {
+            UploadStatus::Failed(_) => true,
+            UploadStatus::Rejected(reason) => reason.is_retryable(),
+            _ => false,
+        }
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(self.status, UploadStatus::Failed(_) | UploadStatus::Rejected(_))
+    }
+
+    fn resume_command(&self) -> ClientCommand {
+        ClientCommand::UploadResume { upload_id: self.id.clone() }
+    }
+}
+
+#[derive(Default)]
+pub struct UploadManager {
+    uploads: Vec<Upload>,
+}
+
+impl UploadManager {
+    // Daftarkan file baru dan kembalikan perintah pembuka upload untuk dikirim ke server
+    pub fn add(&mut self, file: PreparedFile) -> ClientCommand {
+        let id = format!("up-{:08x}-{}", (js_sys::Math::random() * f64::from(u32::MAX)) as u32, js_sys::Date::now() as u64);
+        let upload = Upload {
+            id: id.clone(),
+            size: file.blob.size(),
+            name: file.name,
+            mime: file.mime,
+            original_size: file.original_size,
+            metadata_stripped: file.metadata_stripped,
+            poster: file.poster,
+            blob: file.blob,
+            acked: 0,
+            in_flight: true, // Menunggu offset awal dari server
+            binary: false,
+            status: UploadStatus::Uploading,
+        };
+        let command = ClientCommand::UploadStart {
+            upload_id: id,
+            name: upload.name.clone(),
+            mime: upload.mime.clone(),
+            size: upload.size,
+            chunk_size: CHUNK_SIZE,
+        };
+        self.uploads.push(upload);
+        command
+    }
+
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut Upload> {
+        self.uploads.iter_mut().find(|upload| upload.id == id)
+    }
+
+    pub fn remove(&mut self, id: &str) -> Option<Upload> {
+        let index = self.uploads.iter().position(|upload| upload.id == id)?;
+        Some(self.uploads.remove(index))
+    }
+
+    // Dipanggil saat koneksi putus: chunk yang sedang dikirim dianggap hilang
+    pub fn pause_all(&mut self) {
+        for upload in &mut self.uploads {
+            if upload.status == UploadStatus::Uploading {
+                upload.status = UploadStatus::Paused;
+                upload.in_flight = false;
+            }
+        }
+    }
+
+    // Dipanggil setelah reconnect: tanyakan offset terakhir tiap upload yang tertunda
+    pub fn resume_commands(&mut self) -> Vec<ClientCommand> {
+        self.uploads
+            .iter_mut()
+            .filter(|upload| upload.status == UploadStatus::Paused)
+            .map(|upload| {
+                upload.status = UploadStatus::Uploading;
+                upload.in_flight = true;
+                upload.resume_command()
+            })
+            .collect()
+    }
+
+    // Coba lagi upload yang gagal, mulai dari offset yang sudah dikonfirmasi server
+    pub fn retry(&mut self, id: &str) -> Option<ClientCommand> {
+        let upload = self.get_mut(id)?;
+        upload.status = UploadStatus::Uploading;
+        upload.in_flight = true;
+        Some(upload.resume_command())
+    }
+
+    pub fn uploads(&self) -> &[Upload] {
+        &self.uploads
+    }
+}
+
+// UploadRejection::message() dalam bahasa antarmuka
+pub fn rejection_message(rejection: &UploadRejection) -> String {
+    match rejection {
+        UploadRejection::TypeNotAllowed { detected } => trf("Jenis file {} tidak diizinkan.", &[detected]),
+        UploadRejection::TypeMismatch { declared, detected } => trf("Isi file ({}) tidak sesuai dengan jenisnya ({}).", &[detected, declared]),
+        UploadRejection::TooLarge { max_size } => trf("File terlalu besar; maksimal {}.", &[&format_size(*max_size)]),
+        UploadRejection::Infected { signature: Some(signature) } => trf("File terdeteksi berbahaya ({}).", &[signature]),
+        other => tr(match other {
+            UploadRejection::Infected { .. } => "File terdeteksi berbahaya.",
+            UploadRejection::ScanUnavailable => "Pemindaian file sedang tidak tersedia.",
+            UploadRejection::TooManyPending => "Terlalu banyak upload yang belum selesai; tunggu atau batalkan yang lain dulu.",
+            _ => "Upload ditolak server.",
+        })
+        .to_string(),
+    }
+}
+
+// Drag-and-drop hanya bereaksi jika yang diseret berisi file, bukan teks atau link
+pub fn drags_files(e: &DragEvent) -> bool {
+    e.data_transfer().is_some_and(|transfer| transfer.types().includes(&JsValue::from_str("Files"), 0))
+}
+
+pub fn dropped_files(e: &DragEvent) -> Vec<web_sys::File> {
+    let Some(files) = e.data_transfer().and_then(|transfer| transfer.files()) else { return Vec::new() };
+    (0..files.length()).filter_map(|index| files.get(index)).collect()
+}
+
+// dragleave juga terpicu saat kursor pindah ke elemen anak; hanya keluar dari area drop yang dihitung
+pub fn leaves_drop_area(e: &DragEvent) -> bool {
+    let Some(area) = e.current_target().and_then(|target| target.dyn_into::<Node>().ok()) else { return true };
+    let entered = e.related_target().and_then(|target| target.dyn_into::<Node>().ok());
+    !entered.is_some_and(|node| area.contains(Some(&node)))
+}
+
+// Baca satu chunk file mulai dari `offset`
+pub async fn read_chunk(blob: Blob, offset: u64) -> Result<Vec<u8>, String> {
+    let end = (offset + CHUNK_SIZE).min(blob.size());
+    let chunk = blob.slice(offset, end);
+    gloo_file::futures::read_as_bytes(&chunk).await.map_err(|e| e.to_string())
+}
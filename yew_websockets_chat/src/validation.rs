@@ -0,0 +1,16 @@
+// src/validation.rs
+// Aturan username didefinisikan di chat-protocol dan diterapkan juga oleh chat-server; klien hanya
+// memberi umpan balik lebih cepat sebelum pesan dikirim. Embedder mengganti aturannya lewat prop
+// `username_rules` (App dan ChatRoom), yang sebaiknya sama dengan [usernames] di server.
+use crate::i18n::trf;
+use crate::protocol::UsernameError;
+
+// UsernameError::message() dalam bahasa antarmuka
+pub fn error_message(error: &UsernameError) -> String {
+    match error {
+        UsernameError::TooShort(min) => trf("Username minimal {} karakter.", &[min]),
+        UsernameError::TooLong(max) => trf("Username maksimal {} karakter.", &[max]),
+        UsernameError::InvalidChar(c) => trf("Karakter '{}' tidak diizinkan.", &[c]),
+        UsernameError::Reserved(name) => trf("Nama \"{}\" sudah dicadangkan.", &[name]),
+    }
+}
@@ -0,0 +1,178 @@
+// src/vault.rs
+// Enkripsi data chat yang disimpan di localStorage (antrean outbox dan riwayat lokal). Kunci AES-GCM
+// diturunkan dari passphrase perangkat dengan PBKDF2 lewat WebCrypto, tidak pernah disimpan,
+// dan hanya hidup di memori selama chat tidak dikunci.
+use std::cell::Cell;
+use std::rc::Rc;
+
+use base64::Engine as _;
+use js_sys::{Array, Object, Reflect, Uint8Array};
+use serde::de::DeserializeOwned;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{CryptoKey, SubtleCrypto};
+
+use crate::i18n::tr;
+use crate::storage;
+
+const SALT_KEY: &str = "webchat.vault_salt";
+const CHECK_KEY: &str = "webchat.vault_check"; // Token terenkripsi untuk memeriksa passphrase
+const CHECK_TOKEN: &[u8] = b"webchat-vault-v1";
+const PBKDF2_ITERATIONS: u32 = 310_000;
+const IV_LEN: usize = 12;
+
+// Kunci hasil turunan passphrase; clone hanya menyalin referensi ke objek CryptoKey yang sama
+#[derive(Clone, Debug)]
+pub struct VaultKey(CryptoKey);
+
+// Sudah pernah diaktifkan di browser ini (ada salt tersimpan)
+pub fn is_enabled() -> bool {
+    storage::get(SALT_KEY).is_some()
+}
+
+// Aktifkan enkripsi dengan passphrase baru
+pub async fn setup(passphrase: &str) -> Result<VaultKey, String> {
+    let mut salt = [0u8; 16];
+    crypto()?.get_random_values_with_u8_array(&mut salt).map_err(js_err)?;
+    let key = derive_key(passphrase, &salt).await?;
+    let check = encrypt(&key, CHECK_TOKEN).await?;
+    storage::set(SALT_KEY, &encode(&salt));
+    storage::set(CHECK_KEY, &check);
+    Ok(key)
+}
+
+// Buka kunci dengan passphrase yang sudah ada; gagal jika passphrase salah
+pub async fn unlock(passphrase: &str) -> Result<VaultKey, String> {
+    let salt = storage::get(SALT_KEY).ok_or(tr("Enkripsi belum diaktifkan"))?;
+    let salt = decode(&salt)?;
+    let key = derive_key(passphrase, &salt).await?;
+    let check = storage::get(CHECK_KEY).ok_or(tr("Data verifikasi hilang"))?;
+    match decrypt(&key, &check).await {
+        Ok(token) if token == CHECK_TOKEN => Ok(key),
+        _ => Err(tr("Passphrase salah.").to_string()),
+    }
+}
+
+// Matikan enkripsi; pemanggil bertanggung jawab menulis ulang data dalam bentuk biasa
+pub fn disable() {
+    storage::remove(SALT_KEY);
+    storage::remove(CHECK_KEY);
+}
+
+// Enkripsi lalu kodekan sebagai base64(iv || ciphertext)
+pub async fn encrypt(key: &VaultKey, plaintext: &[u8]) -> Result<String, String> {
+    let mut iv = [0u8; IV_LEN];
+    crypto()?.get_random_values_with_u8_array(&mut iv).map_err(js_err)?;
+    let promise = subtle()?
+        .encrypt_with_object_and_buffer_source(&aes_gcm_params(&iv)?, &key.0, &Uint8Array::from(plaintext))
+        .map_err(js_err)?;
+    let ciphertext = Uint8Array::new(&JsFuture::from(promise).await.map_err(js_err)?).to_vec();
+    let mut out = iv.to_vec();
+    out.extend_from_slice(&ciphertext);
+    Ok(encode(&out))
+}
+
+pub async fn decrypt(key: &VaultKey, data: &str) -> Result<Vec<u8>, String> {
+    let bytes = decode(data)?;
+    if bytes.len() < IV_LEN {
+        return Err(tr("Data terenkripsi rusak").to_string());
+    }
+    let (iv, ciphertext) = bytes.split_at(IV_LEN);
+    let promise = subtle()?
+        .decrypt_with_object_and_buffer_source(&aes_gcm_params(iv)?, &key.0, &Uint8Array::from(ciphertext))
+        .map_err(js_err)?;
+    Ok(Uint8Array::new(&JsFuture::from(promise).await.map_err(js_err)?).to_vec())
+}
+
+// Baca dan dekripsi entri JSON yang ditulis EncryptedSlot; Ok(None) jika belum ada
+pub async fn load_json<T: DeserializeOwned>(key: &VaultKey, storage_key: &str) -> Result<Option<T>, String> {
+    let Some(data) = storage::get(storage_key) else { return Ok(None) };
+    let plaintext = decrypt(key, &data).await?;
+    serde_json::from_slice(&plaintext).map(Some).map_err(|e| e.to_string())
+}
+
+// Satu entri localStorage yang ditulis terenkripsi. Enkripsi berjalan di background, jadi setiap
+// penulisan (termasuk penulisan biasa atau penghapusan oleh pemilik) menaikkan generasi dan hasil
+// enkripsi yang lebih lama tidak menimpa yang lebih baru. Salinan hasil clone berbagi generasi yang sama.
+#[derive(Debug, Default, Clone)]
+pub struct EncryptedSlot {
+    generation: Rc<Cell<u64>>,
+}
+
+impl EncryptedSlot {
+    pub fn store(&self, key: &VaultKey, storage_key: &'static str, plaintext: Vec<u8>) {
+        let generation = self.cancel();
+        let (key, latest) = (key.clone(), self.generation.clone());
+        spawn_local(async move {
+            match encrypt(&key, &plaintext).await {
+                Ok(data) if latest.get() == generation => storage::set(storage_key, &data),
+                Ok(_) => {} // Sudah ada versi yang lebih baru
+                Err(e) => log::error!("Gagal mengenkripsi {}: {}", storage_key, e),
+            }
+        });
+    }
+
+    // Batalkan penulisan terenkripsi yang masih berjalan
+    pub fn cancel(&self) -> u64 {
+        let generation = self.generation.get() + 1;
+        self.generation.set(generation);
+        generation
+    }
+}
+
+async fn derive_key(passphrase: &str, salt: &[u8]) -> Result<VaultKey, String> {
+    let subtle = subtle()?;
+    let base = subtle
+        .import_key_with_str("raw", &Uint8Array::from(passphrase.as_bytes()), "PBKDF2", false, &usages(&["deriveKey"]))
+        .map_err(js_err)?;
+    let base: CryptoKey = JsFuture::from(base).await.map_err(js_err)?.unchecked_into();
+
+    let pbkdf2 = params(&[
+        ("name", "PBKDF2".into()),
+        ("salt", Uint8Array::from(salt).into()),
+        ("iterations", PBKDF2_ITERATIONS.into()),
+        ("hash", "SHA-256".into()),
+    ])?;
+    let aes = params(&[("name", "AES-GCM".into()), ("length", 256.into())])?;
+    let derived = subtle
+        .derive_key_with_object_and_object(&pbkdf2, &base, &aes, false, &usages(&["encrypt", "decrypt"]))
+        .map_err(js_err)?;
+    let key: CryptoKey = JsFuture::from(derived).await.map_err(js_err)?.unchecked_into();
+    Ok(VaultKey(key))
+}
+
+fn aes_gcm_params(iv: &[u8]) -> Result<Object, String> {
+    params(&[("name", "AES-GCM".into()), ("iv", Uint8Array::from(iv).into())])
+}
+
+fn params(fields: &[(&str, JsValue)]) -> Result<Object, String> {
+    let object = Object::new();
+    for (name, value) in fields {
+        Reflect::set(&object, &JsValue::from_str(name), value).map_err(js_err)?;
+    }
+    Ok(object)
+}
+
+fn usages(names: &[&str]) -> JsValue {
+    names.iter().map(|name| JsValue::from_str(name)).collect::<Array>().into()
+}
+
+fn crypto() -> Result<web_sys::Crypto, String> {
+    web_sys::window().ok_or("window tidak tersedia")?.crypto().map_err(js_err)
+}
+
+fn subtle() -> Result<SubtleCrypto, String> {
+    Ok(crypto()?.subtle())
+}
+
+fn encode(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+fn decode(data: &str) -> Result<Vec<u8>, String> {
+    base64::engine::general_purpose::STANDARD.decode(data).map_err(|e| e.to_string())
+}
+
+fn js_err(e: JsValue) -> String {
+    format!("{:?}", e)
+}
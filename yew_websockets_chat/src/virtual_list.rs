@@ -0,0 +1,172 @@
+// src/virtual_list.rs
+// Virtualisasi daftar pesan room: hanya entry di sekitar area yang terlihat yang dirender, entry lain
+// diwakili dua spacer setinggi entry yang dilewati. Tinggi entry diukur setelah dirender dan disimpan
+// per id pesan, karena index bergeser saat riwayat disisipkan atau pesan kedaluwarsa; entry yang belum
+// pernah dirender memakai rata-rata tinggi yang sudah diukur. Perubahan tinggi entry di atas viewport
+// setelah diukur ditangani scroll anchoring browser.
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::ops::Range;
+
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, ScrollBehavior, ScrollToOptions};
+
+use crate::store::MessageEntry;
+
+const DEFAULT_ROW_PX: f64 = 56.0; // Perkiraan tinggi entry sebelum ada yang diukur
+const DEFAULT_VIEWPORT_PX: f64 = 800.0; // Sebelum daftar pesan ada di DOM
+const REMEASURE_SLACK_PX: f64 = 0.5; // Selisih pembulatan sub-pixel bukan perubahan tinggi
+
+// Entry yang dirender dan tinggi kedua spacer di sekitarnya
+#[derive(Debug, Clone, PartialEq)]
+pub struct Window {
+    pub range: Range<usize>,
+    pub before: f64,
+    pub after: f64,
+}
+
+// Diperbarui sambil membaca entry yang sedang ditampilkan (App::shown), jadi state ukurannya memakai Cell
+pub struct VirtualList {
+    heights: RefCell<HashMap<String, f64>>, // Per id pesan pertama entry
+    measured_total: Cell<f64>,
+    scroll_top: Cell<f64>,
+    viewport: Cell<f64>,
+    anchor: Cell<Option<(usize, f64)>>, // Entry teratas yang terlihat dan jaraknya dari atas viewport, dipulihkan setelah render
+    overscan: f64, // Jarak di atas dan di bawah viewport yang ikut dirender
+}
+
+impl VirtualList {
+    pub fn new(overscan: f64) -> Self {
+        Self { heights: RefCell::default(), measured_total: Cell::new(0.0), scroll_top: Cell::new(0.0), viewport: Cell::new(DEFAULT_VIEWPORT_PX), anchor: Cell::new(None), overscan }
+    }
+
+    pub fn set_overscan(&mut self, overscan: f64) {
+        self.overscan = overscan;
+    }
+
+    // `following` = daftar menempel di entry terbaru (ScrollFollow); jendela dihitung dari bawah
+    // sehingga pesan baru langsung ikut dirender tanpa menunggu event scroll.
+    pub fn window(&self, entries: &[MessageEntry], following: bool) -> Window {
+        let heights: Vec<f64> = entries.iter().map(|entry| self.height_of(entry)).collect();
+        let tail = |heights: &[f64]| {
+            let mut start = heights.len();
+            let mut filled = 0.0;
+            while start > 0 && filled < self.viewport.get() + self.overscan {
+                start -= 1;
+                filled += heights[start];
+            }
+            start..heights.len()
+        };
+        let range = if following {
+            tail(&heights)
+        } else {
+            let top = self.scroll_top.get() - self.overscan;
+            let bottom = self.scroll_top.get() + self.viewport.get() + self.overscan;
+            let mut offset = 0.0;
+            let mut start = None;
+            let mut end = heights.len();
+            for (index, height) in heights.iter().enumerate() {
+                if offset >= bottom {
+                    end = index;
+                    break;
+                }
+                if start.is_none() && offset + height > top {
+                    start = Some(index);
+                }
+                offset += height;
+            }
+            match start {
+                Some(start) => start..end,
+                None => tail(&heights), // Posisi scroll lama melewati akhir daftar, mis. setelah pesan kedaluwarsa
+            }
+        };
+        Window { before: heights[..range.start].iter().sum(), after: heights[range.end..].iter().sum(), range }
+    }
+
+    // Dari event scroll ul.messages. true jika entry yang perlu dirender berubah.
+    pub fn on_scroll(&self, list: &Element, entries: &[MessageEntry], following: bool) -> bool {
+        let before = self.window(entries, following).range;
+        self.scroll_top.set(f64::from(list.scroll_top()));
+        self.viewport.set(f64::from(list.client_height()));
+        before != self.window(entries, following).range
+    }
+
+    // Setelah render: ukur entry yang ada di DOM dari jarak antar `li[data-entry]`, sehingga pesan
+    // duplikat yang di-expand dan celah riwayat ikut terhitung di entry sebelumnya.
+    // true jika ada tinggi yang berubah.
+    pub fn measure(&self, list: &Element, entries: &[MessageEntry]) -> bool {
+        self.viewport.set(f64::from(list.client_height()));
+        let Ok(nodes) = list.query_selector_all("li[data-entry], li.virtual-spacer.after") else { return false };
+        let rows: Vec<(Option<usize>, f64)> = (0..nodes.length())
+            .filter_map(|i| nodes.item(i)?.dyn_into::<HtmlElement>().ok())
+            .map(|el| (el.get_attribute("data-entry").and_then(|index| index.parse().ok()), f64::from(el.offset_top())))
+            .collect();
+        let mut heights = self.heights.borrow_mut();
+        let mut changed = false;
+        for pair in rows.windows(2) {
+            let [(Some(index), top), (_, next)] = pair else { continue };
+            let Some(key) = entries.get(*index).and_then(row_key) else { continue };
+            let height = next - top;
+            let previous = heights.insert(key.to_string(), height);
+            self.measured_total.set(self.measured_total.get() + height - previous.unwrap_or(0.0));
+            changed |= previous.is_none_or(|previous| (previous - height).abs() > REMEASURE_SLACK_PX);
+        }
+        changed
+    }
+
+    // Gulir langsung ke perkiraan posisi entry di luar jendela render, agar entry itu ikut dirender
+    pub fn scroll_to(&self, list: &Element, entries: &[MessageEntry], index: usize) {
+        let top = entries.iter().take(index).map(|entry| self.height_of(entry)).sum();
+        self.scroll_top.set(top);
+        let options = ScrollToOptions::new();
+        options.set_top(top);
+        options.set_behavior(ScrollBehavior::Instant);
+        list.scroll_to_with_scroll_to_options(&options);
+    }
+
+    // Sebelum riwayat lama disisipkan di atas: catat entry teratas yang terlihat
+    pub fn hold_position(&self, list: &Element) {
+        let scroll_top = f64::from(list.scroll_top());
+        let Ok(nodes) = list.query_selector_all("li[data-entry]") else { return };
+        let top = (0..nodes.length())
+            .filter_map(|i| nodes.item(i)?.dyn_into::<HtmlElement>().ok())
+            .find(|el| f64::from(el.offset_top() + el.offset_height()) > scroll_top)
+            .and_then(|el| Some((el.get_attribute("data-entry")?.parse().ok()?, f64::from(el.offset_top()) - scroll_top)));
+        self.anchor.set(top);
+    }
+
+    // `count` entry baru ada di awal daftar: geser perkiraan posisi scroll agar render berikutnya sudah
+    // memuat entry yang tadi terlihat, lalu restore_position menepatkannya
+    pub fn prepended(&self, entries: &[MessageEntry], count: usize) {
+        let added: f64 = entries.iter().take(count).map(|entry| self.height_of(entry)).sum();
+        self.scroll_top.set(self.scroll_top.get() + added);
+        self.anchor.set(self.anchor.get().filter(|_| count > 0).map(|(index, offset)| (index + count, offset)));
+    }
+
+    // Setelah render: kembalikan entry yang dicatat hold_position ke posisinya semula di viewport
+    pub fn restore_position(&self, list: &Element) {
+        let Some((index, offset)) = self.anchor.take() else { return };
+        let Ok(Some(el)) = list.query_selector(&format!("li[data-entry=\"{}\"]", index)) else { return };
+        let Ok(el) = el.dyn_into::<HtmlElement>() else { return };
+        let top = f64::from(el.offset_top()) - offset;
+        self.scroll_top.set(top);
+        let options = ScrollToOptions::new();
+        options.set_top(top);
+        options.set_behavior(ScrollBehavior::Instant);
+        list.scroll_to_with_scroll_to_options(&options);
+    }
+
+    fn height_of(&self, entry: &MessageEntry) -> f64 {
+        let heights = self.heights.borrow();
+        row_key(entry).and_then(|key| heights.get(key)).copied().unwrap_or_else(|| match heights.len() {
+            0 => DEFAULT_ROW_PX,
+            count => self.measured_total.get() / count as f64,
+        })
+    }
+}
+
+// Pesan tanpa id (mis. pesan sistem lokal) tidak disimpan tingginya dan selalu memakai perkiraan
+fn row_key(entry: &MessageEntry) -> Option<&str> {
+    let message = &entry.first.message;
+    message.id.as_deref().or(message.client_id.as_deref())
+}
@@ -0,0 +1,225 @@
+// src/voice.rs
+// Pemutar pesan suara dengan waveform. Sampel audio dianalisis lewat Web Audio API
+// (decodeAudioData) lalu diringkas menjadi sejumlah batang puncak amplitudo.
+use gloo_net::http::Request;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{AudioBuffer, AudioContext, Element, HtmlAudioElement};
+use yew::prelude::*;
+
+use crate::i18n::{tr, Locale};
+use crate::protocol::Attachment;
+
+const WAVEFORM_BARS: usize = 48;
+const PLAYBACK_RATES: [f64; 4] = [1.0, 1.25, 1.5, 2.0];
+
+#[derive(Properties, PartialEq)]
+pub struct VoiceMessageProps {
+    pub attachment: Attachment,
+}
+
+pub enum VoiceMsg {
+    Peaks(Vec<f32>),
+    PeaksFailed(String),
+    TogglePlay,
+    TimeUpdate,
+    Ended,
+    ScrubStart(PointerEvent),
+    ScrubMove(PointerEvent),
+    ScrubEnd,
+    CycleRate,
+    LocaleChanged,
+}
+
+pub struct VoiceMessage {
+    audio_ref: NodeRef,
+    wave_ref: NodeRef,
+    peaks: Option<Vec<f32>>, // None selama waveform masih dihitung (atau gagal)
+    playing: bool,
+    progress: f64, // 0.0 - 1.0
+    rate: f64,
+    scrubbing: bool,
+    _locale: Option<ContextHandle<Locale>>,
+}
+
+impl Component for VoiceMessage {
+    type Message = VoiceMsg;
+    type Properties = VoiceMessageProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let url = ctx.props().attachment.url.clone();
+        let link = ctx.link().clone();
+        spawn_local(async move {
+            match load_peaks(&url, WAVEFORM_BARS).await {
+                Ok(peaks) => link.send_message(VoiceMsg::Peaks(peaks)),
+                Err(e) => link.send_message(VoiceMsg::PeaksFailed(e)),
+            }
+        });
+        Self {
+            audio_ref: NodeRef::default(),
+            wave_ref: NodeRef::default(),
+            peaks: None,
+            playing: false,
+            progress: 0.0,
+            rate: 1.0,
+            scrubbing: false,
+            _locale: ctx.link().context::<Locale>(ctx.link().callback(|_| VoiceMsg::LocaleChanged)).map(|(_, handle)| handle),
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let audio = self.audio_ref.cast::<HtmlAudioElement>();
+        match msg {
+            VoiceMsg::Peaks(peaks) => {
+                self.peaks = Some(peaks);
+                true
+            }
+            VoiceMsg::PeaksFailed(e) => {
+                log::warn!("Gagal membuat waveform: {}", e);
+                false // Tetap bisa diputar, hanya tanpa waveform
+            }
+            VoiceMsg::TogglePlay => {
+                let Some(audio) = audio else { return false };
+                if self.playing {
+                    let _ = audio.pause();
+                } else {
+                    audio.set_playback_rate(self.rate);
+                    let _ = audio.play();
+                }
+                self.playing = !self.playing;
+                true
+            }
+            VoiceMsg::TimeUpdate => {
+                let Some(audio) = audio else { return false };
+                if self.scrubbing || !audio.duration().is_finite() || audio.duration() <= 0.0 {
+                    return false;
+                }
+                self.progress = audio.current_time() / audio.duration();
+                true
+            }
+            VoiceMsg::LocaleChanged => true,
+            VoiceMsg::Ended => {
+                self.playing = false;
+                self.progress = 0.0;
+                true
+            }
+            VoiceMsg::ScrubStart(e) => {
+                self.scrubbing = true;
+                self.seek_to_pointer(&e)
+            }
+            VoiceMsg::ScrubMove(e) => self.scrubbing && self.seek_to_pointer(&e),
+            VoiceMsg::ScrubEnd => {
+                self.scrubbing = false;
+                false
+            }
+            VoiceMsg::CycleRate => {
+                let next = PLAYBACK_RATES.iter().position(|&r| r == self.rate).map_or(0, |i| (i + 1) % PLAYBACK_RATES.len());
+                self.rate = PLAYBACK_RATES[next];
+                if let Some(audio) = audio {
+                    audio.set_playback_rate(self.rate);
+                }
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let attachment = &ctx.props().attachment;
+        let played_bars = (self.progress * WAVEFORM_BARS as f64).round() as usize;
+
+        html! {
+            <div class="attachment voice-message">
+                <audio
+                    ref={self.audio_ref.clone()}
+                    src={attachment.url.clone()}
+                    preload="metadata"
+                    ontimeupdate={link.callback(|_| VoiceMsg::TimeUpdate)}
+                    onended={link.callback(|_| VoiceMsg::Ended)}
+                />
+                <button class="voice-play" onclick={link.callback(|_| VoiceMsg::TogglePlay)}>
+                    { if self.playing { "⏸" } else { "▶" } }
+                </button>
+                <div
+                    class="waveform"
+                    ref={self.wave_ref.clone()}
+                    onpointerdown={link.callback(VoiceMsg::ScrubStart)}
+                    onpointermove={link.callback(VoiceMsg::ScrubMove)}
+                    onpointerup={link.callback(|_| VoiceMsg::ScrubEnd)}
+                    onpointerleave={link.callback(|_| VoiceMsg::ScrubEnd)}
+                >
+                    {
+                        match &self.peaks {
+                            Some(peaks) => peaks.iter().enumerate().map(|(i, peak)| html! {
+                                <span
+                                    class={classes!("waveform-bar", (i < played_bars).then_some("played"))}
+                                    style={format!("height: {}%;", (peak * 100.0).max(6.0))}
+                                />
+                            }).collect::<Html>(),
+                            None => html! {
+                                <div class="waveform-fallback">
+                                    <div class="waveform-progress" style={format!("width: {}%;", self.progress * 100.0)} />
+                                </div>
+                            },
+                        }
+                    }
+                </div>
+                <button class="voice-rate" title={tr("Kecepatan putar")} onclick={link.callback(|_| VoiceMsg::CycleRate)}>
+                    { format!("{}×", self.rate) }
+                </button>
+            </div>
+        }
+    }
+}
+
+impl VoiceMessage {
+    // Pindahkan posisi putar ke titik yang ditunjuk pointer di atas waveform
+    fn seek_to_pointer(&mut self, e: &PointerEvent) -> bool {
+        let (Some(wave), Some(audio)) = (self.wave_ref.cast::<Element>(), self.audio_ref.cast::<HtmlAudioElement>()) else {
+            return false;
+        };
+        let rect = wave.get_bounding_client_rect();
+        if rect.width() <= 0.0 {
+            return false;
+        }
+        self.progress = ((f64::from(e.client_x()) - rect.left()) / rect.width()).clamp(0.0, 1.0);
+        if audio.duration().is_finite() {
+            audio.set_current_time(self.progress * audio.duration());
+        }
+        true
+    }
+}
+
+async fn load_peaks(url: &str, bars: usize) -> Result<Vec<f32>, String> {
+    let bytes = Request::get(url)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .binary()
+        .await
+        .map_err(|e| e.to_string())?;
+    let context = AudioContext::new().map_err(|e| format!("{:?}", e))?;
+    let array = js_sys::Uint8Array::from(bytes.as_slice()).buffer();
+    let decoded = context.decode_audio_data(&array).map_err(|e| format!("{:?}", e))?;
+    let buffer: AudioBuffer = JsFuture::from(decoded).await.map_err(|e| format!("{:?}", e))?.unchecked_into();
+    let samples = buffer.get_channel_data(0).map_err(|e| format!("{:?}", e))?;
+    let _ = context.close();
+    Ok(compute_peaks(&samples, bars))
+}
+
+// Ringkas sampel menjadi `bars` nilai puncak amplitudo, dinormalisasi ke 0.0 - 1.0
+fn compute_peaks(samples: &[f32], bars: usize) -> Vec<f32> {
+    if samples.is_empty() || bars == 0 {
+        return vec![0.0; bars];
+    }
+    let chunk = samples.len().div_ceil(bars);
+    let peaks: Vec<f32> = samples
+        .chunks(chunk)
+        .map(|window| window.iter().fold(0.0f32, |max, s| max.max(s.abs())))
+        .collect();
+    let loudest = peaks.iter().cloned().fold(0.0f32, f32::max);
+    if loudest <= 0.0 {
+        return peaks;
+    }
+    peaks.into_iter().map(|peak| peak / loudest).collect()
+}
@@ -0,0 +1,490 @@
+// src/widgets.rs
+// Potongan App yang bisa dipasang terpisah oleh embedder, mis. daftar online di sidebar milik halaman
+// induk sementara transkrip tetap dari crate ini. ChatProvider membuka satu koneksi dan menyimpan
+// ChatState bersama di context; MessageList, MessageInput dan PresenceList membacanya dari sana.
+// ChatRoom merangkai semuanya (dengan komponen dari chat_view) menjadi chat sederhana siap pakai.
+// Aplikasi Yew yang ingin markup sendiri cukup memakai hook use_chat_websocket tanpa ChatProvider.
+// Fitur lengkap (room, lampiran, moderasi, 2FA, ...) tetap hanya ada di App. Jangan pasang App dan
+// ChatProvider bersamaan: masing-masing membuka koneksinya sendiri.
+use std::cell::{Cell, Ref, RefCell};
+use std::rc::Rc;
+
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+use crate::chat_view::{Composer, ConnectionBanner, MessageItem, MessageViewport, UsernameForm};
+use crate::client::{ChatClient, ClientEvent};
+use crate::config;
+use crate::error::ChatError;
+use crate::i18n::{self, use_locale, Locale};
+use crate::media::EmbedPolicy;
+use crate::protocol::{ClientCommand, ServerEvent, UsernameRules};
+use crate::settings::Settings;
+use crate::state::{self, Action, ChatState};
+use crate::stats::SessionStats;
+use crate::validation;
+use crate::{directory, history, outbox, ChatMessage};
+
+// Handle ke state dan koneksi bersama. Clone murah; dua handle sama jika menunjuk store yang sama.
+#[derive(Clone)]
+pub struct ChatStore {
+    inner: Rc<Inner>,
+}
+
+struct Inner {
+    state: RefCell<ChatState>,
+    client: RefCell<ChatClient>,
+    ws_url: String,
+    auth_token: Option<String>,
+    username: RefCell<String>,
+    connected: Cell<bool>,
+    version: Cell<u64>, // Naik setiap kali state berubah; dipakai komponen sebagai pemicu render
+    listeners: RefCell<Vec<(u64, Callback<u64>)>>,
+    next_listener: Cell<u64>,
+}
+
+impl PartialEq for ChatStore {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl ChatStore {
+    // Identitas sama dengan App: id akun lokal dan nama terakhir dari localStorage
+    pub fn new(ws_url: Option<String>, auth_token: Option<String>) -> Self {
+        let user_id = directory::load_or_create_user_id();
+        let username = history::load_username().unwrap_or_else(|| String::from("Anonim"));
+        let mut chat = ChatState { me: user_id.clone(), ..ChatState::default() };
+        chat.directory.set(&user_id, &username);
+        let inner = Rc::new(Inner {
+            state: RefCell::new(chat),
+            client: RefCell::new(crate::default_client(SessionStats::new())),
+            ws_url: ws_url.unwrap_or_else(config::default_ws_url),
+            auth_token,
+            username: RefCell::new(username),
+            connected: Cell::new(false),
+            version: Cell::new(0),
+            listeners: RefCell::default(),
+            next_listener: Cell::new(0),
+        });
+        let weak = Rc::downgrade(&inner);
+        inner.client.borrow().subscribe(Callback::from(move |event| {
+            if let Some(inner) = weak.upgrade() {
+                ChatStore { inner }.apply(event);
+            }
+        }));
+        ChatStore { inner }
+    }
+
+    // Buka (ulang) koneksi. Connected dikirim ChatClient secara sinkron (di build `worker` menyusul dari worker),
+    // jadi perintah awal dikirim di sini, setelah pinjaman client dilepas.
+    pub fn connect(&self) {
+        let result = self.inner.client.borrow_mut().connect(&self.inner.ws_url);
+        if let Err(error) = result {
+            log::error!("Gagal terhubung: {:?}", error);
+            return;
+        }
+        if let Some(token) = self.inner.auth_token.clone() {
+            self.send_command(&ClientCommand::Authenticate { token });
+        }
+        let room = self.state().current_name().to_string();
+        self.send_command(&ClientCommand::SyncRoster { room });
+    }
+
+    // Tutup koneksi atas permintaan pemanggil; tidak tersambung ulang sampai connect dipanggil lagi
+    pub fn disconnect(&self) {
+        self.inner.client.borrow_mut().close();
+        if self.inner.connected.replace(false) {
+            self.notify();
+        }
+    }
+
+    pub fn state(&self) -> Ref<'_, ChatState> {
+        self.inner.state.borrow()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.inner.connected.get()
+    }
+
+    pub fn version(&self) -> u64 {
+        self.inner.version.get()
+    }
+
+    // Ubah state lewat reducer yang sama dengan App, lalu beri tahu semua komponen
+    pub fn dispatch(&self, action: Action) {
+        let changed = state::reduce(&mut self.inner.state.borrow_mut(), action);
+        if changed {
+            self.notify();
+        }
+    }
+
+    pub fn username(&self) -> String {
+        self.inner.username.borrow().clone()
+    }
+
+    // Ganti display name seperti "Set Username" di App; nama baru ikut di pesan berikutnya.
+    // Validasi (UsernameRules) tanggung jawab pemanggil.
+    pub fn rename(&self, name: &str) {
+        *self.inner.username.borrow_mut() = name.to_string();
+        history::save_username(name);
+        let user_id = self.state().me.clone();
+        self.dispatch(Action::UserRenamed { user_id, name: name.to_string() });
+    }
+
+    // Kirim pesan ke room yang ditampilkan. false jika belum tersambung.
+    pub fn send_text(&self, text: &str) -> bool {
+        let client_id = outbox::new_client_id();
+        let message = ChatMessage {
+            id: None,
+            client_id: Some(client_id.clone()),
+            user_id: Some(self.state().me.clone()),
+            username: self.username(),
+            text: text.to_string(),
+            timestamp: None,
+            edited_at: None,
+            attachment: None,
+            expires_at: None,
+            event: None,
+            system: false,
+            components: Vec::new(),
+            room: self.state().current_room.as_ref().map(|room| room.name.clone()),
+            operator: None,
+            whisper_to: None,
+        };
+        let Some(sending) = self.inner.client.borrow().send(&message) else { return false };
+        self.dispatch(Action::MessageSending { message, now_ms: js_sys::Date::now() });
+        let weak = Rc::downgrade(&self.inner);
+        spawn_local(async move {
+            if let (Err(error), Some(inner)) = (sending.await, weak.upgrade()) {
+                ChatStore { inner }.dispatch(Action::DeliveryFailed { client_id, reason: ChatError::from(error).to_string() });
+            }
+        });
+        true
+    }
+
+    // `on_change` menerima nomor versi terbaru; kembalikan id untuk unsubscribe
+    pub fn subscribe(&self, on_change: Callback<u64>) -> u64 {
+        let id = self.inner.next_listener.get() + 1;
+        self.inner.next_listener.set(id);
+        self.inner.listeners.borrow_mut().push((id, on_change));
+        id
+    }
+
+    pub fn unsubscribe(&self, id: u64) {
+        self.inner.listeners.borrow_mut().retain(|(listener, _)| *listener != id);
+    }
+
+    fn send_command(&self, command: &ClientCommand) {
+        if let Some(sending) = self.inner.client.borrow().send(command) {
+            let command = command.clone();
+            spawn_local(async move {
+                if let Err(error) = sending.await {
+                    log::error!("Gagal mengirim perintah {:?}: {:?}", command, error);
+                }
+            });
+        }
+    }
+
+    fn apply(&self, event: ClientEvent) {
+        let now_ms = js_sys::Date::now();
+        match event {
+            ClientEvent::Connected => {
+                self.inner.connected.set(true);
+                self.notify();
+            }
+            ClientEvent::Disconnected(error) => {
+                log::warn!("Koneksi terputus: {:?}", error);
+                self.inner.connected.set(false);
+                self.notify();
+            }
+            ClientEvent::Message { message, raw, .. } => self.dispatch(Action::MessageReceived { message: *message, raw, now_ms }),
+            ClientEvent::Presence(event) => self.dispatch(event.into()),
+            ClientEvent::Server(ServerEvent::HistoryReplay { messages, total_missed }) => {
+                self.dispatch(Action::HistoryReplayed { messages, total_missed, now_ms });
+            }
+            ClientEvent::Server(ServerEvent::MessageRejected { client_id, reason }) => {
+                self.dispatch(Action::DeliveryFailed { client_id, reason });
+            }
+            ClientEvent::Server(ServerEvent::AuthResult { ok: true, user_id: Some(user_id), name, .. }) => {
+                self.dispatch(Action::SignedIn { user_id, name });
+            }
+            ClientEvent::Server(_) => {} // Room, moderasi, dll. hanya ditangani App
+            ClientEvent::Error(error) => log::warn!("{}", error),
+        }
+    }
+
+    fn notify(&self) {
+        let version = self.inner.version.get() + 1;
+        self.inner.version.set(version);
+        // Salin dulu: listener boleh unsubscribe saat dipanggil
+        let listeners: Vec<Callback<u64>> = self.inner.listeners.borrow().iter().map(|(_, listener)| listener.clone()).collect();
+        for listener in listeners {
+            listener.emit(version);
+        }
+    }
+}
+
+// Ambil ChatStore dari ChatProvider terdekat; komponen dirender ulang setiap kali state berubah
+#[hook]
+pub fn use_chat_store() -> ChatStore {
+    let store = use_context::<ChatStore>().expect("use_chat_store dipakai di luar ChatProvider");
+    let version = use_state(|| store.version());
+    {
+        let version = version.clone();
+        use_effect_with_deps(
+            move |store: &ChatStore| {
+                let (store, id) = (store.clone(), store.subscribe(Callback::from(move |latest| version.set(latest))));
+                move || store.unsubscribe(id)
+            },
+            store.clone(),
+        );
+    }
+    store
+}
+
+// Koneksi use_chat_websocket; kosong sama seperti AppProps (host halaman ini, tanpa token)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChatSocketConfig {
+    pub ws_url: Option<String>,
+    pub auth_token: Option<String>,
+}
+
+// Koneksi milik komponen pemanggil: dibuka saat komponen dipasang, ditutup saat dilepas, dan dibuka
+// ulang dengan store baru jika config berubah. Komponen dirender ulang setiap kali state berubah;
+// ChatState yang dikembalikan adalah salinan state saat render; ChatHandle untuk mengirim dan mengatur koneksi.
+#[hook]
+pub fn use_chat_websocket(config: ChatSocketConfig) -> (ChatState, ChatHandle) {
+    let store = use_memo(|config: &ChatSocketConfig| ChatStore::new(config.ws_url.clone(), config.auth_token.clone()), config);
+    let store = (*store).clone();
+    let version = use_state(|| store.version());
+    {
+        let version = version.clone();
+        use_effect_with_deps(
+            move |store: &ChatStore| {
+                let id = store.subscribe(Callback::from(move |latest| version.set(latest)));
+                store.connect();
+                let store = store.clone();
+                move || {
+                    store.unsubscribe(id);
+                    store.disconnect();
+                }
+            },
+            store.clone(),
+        );
+    }
+    let state = store.state().clone();
+    (state, ChatHandle { store })
+}
+
+// Kendali koneksi dari use_chat_websocket. Clone murah; dua handle sama jika menunjuk koneksi yang sama.
+#[derive(Clone, PartialEq)]
+pub struct ChatHandle {
+    store: ChatStore,
+}
+
+impl ChatHandle {
+    // Kirim ke room yang ditampilkan; false jika belum tersambung
+    pub fn send(&self, text: &str) -> bool {
+        self.store.send_text(text)
+    }
+
+    pub fn connect(&self) {
+        self.store.connect();
+    }
+
+    pub fn disconnect(&self) {
+        self.store.disconnect();
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.store.is_connected()
+    }
+
+    pub fn rename(&self, name: &str) {
+        self.store.rename(name);
+    }
+
+    // Store di balik handle ini, mis. untuk dispatch aksi sendiri atau diberikan ke ContextProvider<ChatStore>
+    pub fn store(&self) -> &ChatStore {
+        &self.store
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ChatProviderProps {
+    #[prop_or_default]
+    pub ws_url: Option<String>, // Sama seperti AppProps: kosong = host halaman ini dengan path /ws
+    #[prop_or_default]
+    pub auth_token: Option<String>,
+    #[prop_or_default]
+    pub locale: Option<Locale>, // Bahasa antarmuka; kosong = bahasa browser
+    #[prop_or_default]
+    pub children: Children,
+}
+
+#[function_component(ChatProvider)]
+pub fn chat_provider(props: &ChatProviderProps) -> Html {
+    let store = use_state(|| {
+        let store = ChatStore::new(props.ws_url.clone(), props.auth_token.clone());
+        store.connect();
+        store
+    });
+    let locale = Locale::resolve(props.locale);
+    i18n::set(locale);
+    html! {
+        <ContextProvider<Locale> context={locale}>
+            <ContextProvider<ChatStore> context={(*store).clone()}>
+                { for props.children.iter() }
+            </ContextProvider<ChatStore>>
+        </ContextProvider<Locale>>
+    }
+}
+
+// Transkrip room yang ditampilkan dalam bingkai yang sama dengan App (MessageViewport), tiap entry sebagai MessageItem
+#[function_component(MessageList)]
+pub fn message_list() -> Html {
+    let store = use_chat_store();
+    let chat = store.state();
+    let policy = EmbedPolicy { embeds_disabled: chat.current_room.as_ref().is_some_and(|room| room.embeds_disabled), max_gif_rating: Settings::load().max_gif_rating };
+    html! {
+        <MessageViewport>
+            { for chat.messages.entries().iter().map(|entry| html! {
+                <MessageItem
+                    message={entry.first.clone()}
+                    sender={chat.directory.display_name(&entry.first.message).to_string()}
+                    own={chat.is_own(&entry.first.message)}
+                    repeats={entry.repeats.len()}
+                    {policy}
+                />
+            }) }
+        </MessageViewport>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct MessageInputProps {
+    #[prop_or_default]
+    pub placeholder: Option<AttrValue>, // Kosong = "Ketik pesan..." dalam bahasa antarmuka
+}
+
+#[function_component(MessageInput)]
+pub fn message_input(props: &MessageInputProps) -> Html {
+    let locale = use_locale();
+    let store = use_chat_store();
+    let draft = use_state(String::new);
+    let on_input = {
+        let draft = draft.clone();
+        Callback::from(move |value: String| draft.set(value))
+    };
+    let on_submit = {
+        let (store, draft) = (store.clone(), draft.clone());
+        Callback::from(move |()| {
+            let text = draft.trim();
+            if !text.is_empty() && store.send_text(text) {
+                draft.set(String::new());
+            }
+        })
+    };
+    let connected = store.is_connected();
+    // Composer yang sama dengan App, tanpa lampiran dan balasan cepat (keduanya butuh state App)
+    html! {
+        <div class="input-area">
+            <Composer
+                value={(*draft).clone()}
+                placeholder={props.placeholder.clone().unwrap_or(AttrValue::Static(locale.t("Ketik pesan...")))}
+                read_only={!connected}
+                {connected}
+                {on_input}
+                {on_submit}
+            />
+        </div>
+    }
+}
+
+// User online di room yang ditampilkan, tanpa editor status milik OnlinePanel
+#[function_component(PresenceList)]
+pub fn presence_list() -> Html {
+    let locale = use_locale();
+    let store = use_chat_store();
+    let chat = store.state();
+    let Some(roster) = chat.presence.roster(chat.current_name()) else {
+        return html! { <ul class="online-list"><li class="member-loading">{ locale.t("Memuat...") }</li></ul> };
+    };
+    let mut users: Vec<_> = roster.users.iter().collect();
+    users.sort_by_key(|user| (user.user_id != chat.me, user.name.to_lowercase()));
+    html! {
+        <ul class="online-list">
+            { for users.into_iter().map(|user| html! {
+                <li class={classes!("online-user", user.status.class())} key={user.user_id.clone()} title={locale.t(user.status.label())}>
+                    <span class="status-dot"></span>
+                    <span class="member-name" dir="auto">{ chat.directory.name_of(&user.user_id).unwrap_or(&user.name) }</span>
+                </li>
+            }) }
+        </ul>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ChatRoomProps {
+    #[prop_or_default]
+    pub ws_url: Option<String>,
+    #[prop_or_default]
+    pub auth_token: Option<String>,
+    #[prop_or_default]
+    pub locale: Option<Locale>,
+    // Aturan form ganti nama; samakan dengan [usernames] di chat-server
+    #[prop_or_default]
+    pub username_rules: UsernameRules,
+}
+
+// Chat sederhana siap pakai: status koneksi, ganti nama, transkrip, input dan daftar online di room umum.
+// Untuk tata letak lain, rangkai sendiri ChatProvider dengan komponen-komponen di atas.
+#[function_component(ChatRoom)]
+pub fn chat_room(props: &ChatRoomProps) -> Html {
+    html! {
+        <ChatProvider ws_url={props.ws_url.clone()} auth_token={props.auth_token.clone()} locale={props.locale}>
+            <ChatRoomBody username_rules={props.username_rules.clone()} />
+        </ChatProvider>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct ChatRoomBodyProps {
+    username_rules: UsernameRules,
+}
+
+#[function_component(ChatRoomBody)]
+fn chat_room_body(props: &ChatRoomBodyProps) -> Html {
+    let store = use_chat_store();
+    let name = use_state(String::new);
+    let error = (!name.is_empty()).then(|| props.username_rules.validate(&name).err()).flatten();
+    let on_input = {
+        let name = name.clone();
+        Callback::from(move |value: String| name.set(value))
+    };
+    let on_submit = {
+        let (store, name, rules) = (store.clone(), name.clone(), props.username_rules.clone());
+        Callback::from(move |()| {
+            if rules.validate(&name).is_ok() {
+                store.rename(&name);
+                name.set(String::new());
+            }
+        })
+    };
+    let on_reconnect = {
+        let store = store.clone();
+        Callback::from(move |()| store.connect())
+    };
+    html! {
+        <div class="chat-container" data-connected={store.is_connected().to_string()}>
+            <ConnectionBanner connected={store.is_connected()} {on_reconnect} />
+            <div class="username-area">
+                <UsernameForm value={(*name).clone()} error={error.map(|error| AttrValue::from(validation::error_message(&error)))} {on_input} {on_submit} />
+            </div>
+            <aside class="online-sidebar"><PresenceList /></aside>
+            <MessageList />
+            <MessageInput />
+        </div>
+    }
+}